@@ -0,0 +1,317 @@
+//! Derive macros for Plonky2 circuit types.
+//!
+//! `#[derive(CircuitVariable)]` implements `plonky2::iop::variable::CircuitVariable` for a struct
+//! whose fields are all variables, and defines a companion `<Name>Value<F>` struct holding their
+//! values.
+//!
+//! `#[derive(GateSerialize)]` and `#[derive(GeneratorSerialize)]` implement
+//! `plonky2::util::serialization::CircuitSerialize` for a struct by encoding its fields in
+//! declaration order, each through its own `CircuitSerialize` implementation. The gate or
+//! generator can then implement its `serialize` and `deserialize` methods with
+//! `plonky2::derived_serialization!()`.
+//!
+//! If the struct is generic over the field or the extension degree, the corresponding parameters
+//! must be named `F` and `D`. Otherwise the implementation is made generic over them.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields, GenericParam, Index, Member,
+    Result,
+};
+
+/// Implements `CircuitVariable` for a struct whose fields are all `CircuitVariable`s, and defines
+/// a `<Name>Value<F>` struct with the same fields holding their values.
+#[proc_macro_derive(CircuitVariable)]
+pub fn derive_circuit_variable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_circuit_variable(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Implements `CircuitSerialize` for a gate by serializing its fields in order.
+#[proc_macro_derive(GateSerialize)]
+pub fn derive_gate_serialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_circuit_serialize(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Implements `CircuitSerialize` for a witness generator by serializing its fields in order.
+#[proc_macro_derive(GeneratorSerialize)]
+pub fn derive_generator_serialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_circuit_serialize(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_circuit_serialize(input: DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            name,
+            "serialization can only be derived for structs",
+        ));
+    };
+
+    let mut generics = input.generics.clone();
+    if !generics.type_params().any(|p| p.ident == "F") {
+        generics.params.push(parse_quote!(F));
+    }
+    if !generics.const_params().any(|p| p.ident == "D") {
+        generics.params.push(parse_quote!(const D: usize));
+    }
+    generics.make_where_clause().predicates.push(parse_quote!(
+        F: ::plonky2::hash::hash_types::RichField + ::plonky2::field::extension::Extendable<D>
+    ));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    let trait_path = quote!(::plonky2::util::serialization::CircuitSerialize<F, D>);
+    let (writes, read) = match &data.fields {
+        Fields::Named(fields) => {
+            let idents = fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
+            (
+                quote! {
+                    #(<_ as #trait_path>::write_to(&self.#idents, dst, common_data)?;)*
+                },
+                quote! {
+                    Self {
+                        #(#idents: <_ as #trait_path>::read_from(src, common_data)?,)*
+                    }
+                },
+            )
+        }
+        Fields::Unnamed(fields) => {
+            let indices = (0..fields.unnamed.len()).map(Index::from);
+            let reads = fields
+                .unnamed
+                .iter()
+                .map(|_| quote!(<_ as #trait_path>::read_from(src, common_data)?));
+            (
+                quote! {
+                    #(<_ as #trait_path>::write_to(&self.#indices, dst, common_data)?;)*
+                },
+                quote! {
+                    Self(#(#reads,)*)
+                },
+            )
+        }
+        Fields::Unit => (quote!(), quote!(Self)),
+    };
+
+    Ok(quote! {
+        impl #impl_generics #trait_path for #name #ty_generics #where_clause {
+            fn write_to(
+                &self,
+                dst: &mut ::plonky2::alloc::vec::Vec<u8>,
+                common_data: &::plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
+            ) -> ::plonky2::util::serialization::IoResult<()> {
+                #writes
+                Ok(())
+            }
+
+            fn read_from(
+                src: &mut ::plonky2::util::serialization::Buffer,
+                common_data: &::plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
+            ) -> ::plonky2::util::serialization::IoResult<Self> {
+                Ok(#read)
+            }
+        }
+    })
+}
+
+fn expand_circuit_variable(input: DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            name,
+            "CircuitVariable can only be derived for structs",
+        ));
+    };
+    if data.fields.is_empty() {
+        return Err(Error::new_spanned(
+            name,
+            "CircuitVariable can't be derived for structs without fields",
+        ));
+    }
+    if let Some(param) = input.generics.type_params().find(|p| p.ident == "F") {
+        return Err(Error::new_spanned(
+            param,
+            "the parameter name `F` is reserved for the field of the value struct",
+        ));
+    }
+
+    let variable = quote!(::plonky2::iop::variable::CircuitVariable);
+    let rich_field = quote!(::plonky2::hash::hash_types::RichField);
+    let value_name = format_ident!("{}Value", name);
+    let value_doc = format!("The value of a [`{name}`] in a witness.");
+
+    let members: Vec<Member> = match &data.fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|f| Member::Named(f.ident.clone().unwrap()))
+            .collect(),
+        _ => (0..data.fields.len())
+            .map(|i| Member::Unnamed(Index::from(i)))
+            .collect(),
+    };
+    let tys: Vec<_> = data.fields.iter().map(|f| &f.ty).collect();
+    let field_vis: Vec<_> = data.fields.iter().map(|f| &f.vis).collect();
+    let construct = |values: Vec<TokenStream2>| match &data.fields {
+        Fields::Named(_) => quote!({ #(#members: #values,)* }),
+        _ => quote!((#(#values,)*)),
+    };
+
+    // Both the variable and its value need every field type to be a variable.
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for ty in &tys {
+        where_clause.predicates.push(parse_quote!(#ty: #variable));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut value_generics = generics.clone();
+    let num_lifetimes = value_generics.lifetimes().count();
+    value_generics
+        .params
+        .insert(num_lifetimes, parse_quote!(F: #rich_field));
+    let (value_impl_generics, value_ty_generics, _) = value_generics.split_for_impl();
+    let value_params = &value_generics.params;
+    let value_args = value_generics.params.iter().map(|p| match p {
+        GenericParam::Lifetime(p) => {
+            let lifetime = &p.lifetime;
+            quote!(#lifetime)
+        }
+        GenericParam::Type(p) => {
+            let ident = &p.ident;
+            quote!(#ident)
+        }
+        GenericParam::Const(p) => {
+            let ident = &p.ident;
+            quote!(#ident)
+        }
+    });
+
+    let value_tys = tys.iter().map(|ty| quote!(<#ty as #variable>::Value<F>));
+    let value_fields = match &data.fields {
+        Fields::Named(_) => quote!(#where_clause { #(#field_vis #members: #value_tys,)* }),
+        _ => quote!((#(#field_vis #value_tys,)*) #where_clause;),
+    };
+    let value_debug = match &data.fields {
+        Fields::Named(_) => {
+            let names = members.iter().map(|m| match m {
+                Member::Named(ident) => ident.to_string(),
+                Member::Unnamed(_) => unreachable!(),
+            });
+            quote!(f.debug_struct(stringify!(#value_name))#(.field(#names, &self.#members))*.finish())
+        }
+        _ => quote!(f.debug_tuple(stringify!(#value_name))#(.field(&self.#members))*.finish()),
+    };
+
+    let from_targets = construct(
+        tys.iter()
+            .map(|ty| {
+                quote!(<#ty as #variable>::from_targets(
+                    &targets
+                        .by_ref()
+                        .take(<#ty as #variable>::num_targets())
+                        .collect::<::plonky2::alloc::vec::Vec<_>>(),
+                ))
+            })
+            .collect(),
+    );
+    let add_virtual = construct(
+        tys.iter()
+            .map(|ty| quote!(<#ty as #variable>::add_virtual(builder)))
+            .collect(),
+    );
+    let get_witness = construct(
+        members
+            .iter()
+            .map(|m| quote!(#variable::get_witness(&self.#m, witness)))
+            .collect(),
+    );
+    let value_clone = construct(
+        members
+            .iter()
+            .map(|m| quote!(::core::clone::Clone::clone(&self.#m)))
+            .collect(),
+    );
+
+    Ok(quote! {
+        #[doc = #value_doc]
+        #vis struct #value_name<#value_params> #value_fields
+
+        impl #value_impl_generics ::core::clone::Clone for #value_name #value_ty_generics
+            #where_clause
+        {
+            fn clone(&self) -> Self {
+                Self #value_clone
+            }
+        }
+
+        impl #value_impl_generics ::core::fmt::Debug for #value_name #value_ty_generics
+            #where_clause
+        {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #value_debug
+            }
+        }
+
+        impl #value_impl_generics ::core::cmp::PartialEq for #value_name #value_ty_generics
+            #where_clause
+        {
+            fn eq(&self, other: &Self) -> bool {
+                true #(&& self.#members == other.#members)*
+            }
+        }
+
+        impl #impl_generics #variable for #name #ty_generics #where_clause {
+            type Value<F: #rich_field> = #value_name<#(#value_args),*>;
+
+            fn num_targets() -> usize {
+                0 #(+ <#tys as #variable>::num_targets())*
+            }
+
+            fn targets(&self) -> ::plonky2::alloc::vec::Vec<::plonky2::iop::target::Target> {
+                let mut targets = ::plonky2::alloc::vec::Vec::new();
+                #(targets.extend(#variable::targets(&self.#members));)*
+                targets
+            }
+
+            fn from_targets(targets: &[::plonky2::iop::target::Target]) -> Self {
+                assert_eq!(targets.len(), <Self as #variable>::num_targets());
+                let mut targets = targets.iter().copied();
+                Self #from_targets
+            }
+
+            fn add_virtual<F: #rich_field + ::plonky2::field::extension::Extendable<__D>, const __D: usize>(
+                builder: &mut ::plonky2::plonk::circuit_builder::CircuitBuilder<F, __D>,
+            ) -> Self {
+                Self #add_virtual
+            }
+
+            fn set_witness<F: #rich_field, __W: ::plonky2::iop::witness::WitnessWrite<F>>(
+                &self,
+                witness: &mut __W,
+                value: &<Self as #variable>::Value<F>,
+            ) {
+                #(#variable::set_witness(&self.#members, witness, &value.#members);)*
+            }
+
+            fn get_witness<F: #rich_field, __W: ::plonky2::iop::witness::Witness<F>>(
+                &self,
+                witness: &__W,
+            ) -> <Self as #variable>::Value<F> {
+                #value_name #get_witness
+            }
+        }
+    })
+}
@@ -17,7 +17,8 @@ use plonky2::{
         target::{BoolTarget, Target},
         witness::{PartitionWitness, Witness},
     },
-    plonk::circuit_builder::CircuitBuilder,
+    plonk::{circuit_builder::CircuitBuilder, circuit_data::CommonCircuitData},
+    util::serialization::{Buffer, CircuitSerialize, GeneratorSerialize, IoResult},
 };
 use plonky2_u32::{
     gadgets::{
@@ -27,11 +28,21 @@ use plonky2_u32::{
     witness::{GeneratedValuesU32, WitnessU32},
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct BigUintTarget {
     pub limbs: Vec<U32Target>,
 }
 
+impl<F: RichField + Extendable<D>, const D: usize> CircuitSerialize<F, D> for BigUintTarget {
+    fn write_to(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        self.limbs.write_to(dst, common_data)
+    }
+
+    fn read_from(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        Ok(Self { limbs: Vec::read_from(src, common_data)? })
+    }
+}
+
 impl BigUintTarget {
     pub fn num_limbs(&self) -> usize {
         self.limbs.len()
@@ -314,8 +325,8 @@ impl<F: PrimeField> GeneratedValuesBigUint<F> for GeneratedValues<F> {
     }
 }
 
-#[derive(Debug, Clone)]
-struct BigUintDivRemGenerator<F: RichField + Extendable<D>, const D: usize> {
+#[derive(Debug, Clone, Default, GeneratorSerialize)]
+pub(crate) struct BigUintDivRemGenerator<F: RichField + Extendable<D>, const D: usize> {
     a: BigUintTarget,
     b: BigUintTarget,
     div: BigUintTarget,
@@ -329,22 +340,8 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
     fn id(&self) -> String {
         "BigUintDivRemGenerator".to_string()
     }
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<()> {
-        todo!();
-    }
-    fn deserialize(
-        _src: &mut plonky2::util::serialization::Buffer,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!();
-    }
+    plonky2::derived_serialization!();
+
     fn dependencies(&self) -> Vec<Target> {
         self.a.limbs.iter().chain(&self.b.limbs).map(|&l| l.0).collect()
     }
@@ -402,7 +399,7 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -432,7 +429,7 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -462,7 +459,7 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -488,7 +485,7 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -521,7 +518,7 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -552,6 +549,6 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 }
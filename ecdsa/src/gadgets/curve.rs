@@ -3,7 +3,11 @@ use alloc::{vec, vec::Vec};
 use plonky2::{
     field::{extension::Extendable, types::Sample},
     hash::hash_types::RichField,
-    iop::target::BoolTarget,
+    iop::{
+        target::{BoolTarget, Target},
+        variable::CircuitVariable,
+        witness::{Witness, WitnessWrite},
+    },
     plonk::circuit_builder::CircuitBuilder,
 };
 
@@ -26,6 +30,44 @@ impl<C: Curve> AffinePointTarget<C> {
     }
 }
 
+impl<C: Curve> CircuitVariable for AffinePointTarget<C> {
+    type Value<F: RichField> = AffinePoint<C>;
+
+    fn num_targets() -> usize {
+        2 * NonNativeTarget::<C::BaseField>::num_targets()
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        [self.x.targets(), self.y.targets()].concat()
+    }
+
+    fn from_targets(targets: &[Target]) -> Self {
+        assert_eq!(targets.len(), Self::num_targets());
+        let (x, y) = targets.split_at(targets.len() / 2);
+        Self { x: NonNativeTarget::from_targets(x), y: NonNativeTarget::from_targets(y) }
+    }
+
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        builder.add_virtual_affine_point_target()
+    }
+
+    fn set_witness<F: RichField, W: WitnessWrite<F>>(
+        &self,
+        witness: &mut W,
+        value: &AffinePoint<C>,
+    ) {
+        assert!(!value.zero, "the point at infinity can't be represented by an AffinePointTarget");
+        self.x.set_witness(witness, &value.x);
+        self.y.set_witness(witness, &value.y);
+    }
+
+    fn get_witness<F: RichField, W: Witness<F>>(&self, witness: &W) -> AffinePoint<C> {
+        AffinePoint { x: self.x.get_witness(witness), y: self.y.get_witness(witness), zero: false }
+    }
+}
+
 pub trait CircuitBuilderCurve<F: RichField + Extendable<D>, const D: usize> {
     fn constant_affine_point<C: Curve>(&mut self, point: AffinePoint<C>) -> AffinePointTarget<C>;
 
@@ -262,7 +304,7 @@ mod tests {
             secp256k1_scalar::Secp256K1Scalar,
             types::{Field, Sample},
         },
-        iop::witness::PartialWitness,
+        iop::{target::BoolTarget, variable::CircuitVariable, witness::PartialWitness},
         plonk::{
             circuit_builder::CircuitBuilder,
             circuit_data::CircuitConfig,
@@ -275,7 +317,10 @@ mod tests {
             curve_types::{AffinePoint, Curve, CurveScalar},
             secp256k1::Secp256K1,
         },
-        gadgets::{curve::CircuitBuilderCurve, nonnative::CircuitBuilderNonNative},
+        gadgets::{
+            curve::{AffinePointTarget, CircuitBuilderCurve},
+            nonnative::CircuitBuilderNonNative,
+        },
     };
 
     #[test]
@@ -299,7 +344,37 @@ mod tests {
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
 
-        data.verify(proof)
+        Ok(data.verify(proof)?)
+    }
+
+    #[test]
+    fn test_curve_point_variable() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_ecc_config();
+
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let points: [AffinePointTarget<Secp256K1>; 2] = builder.add_virtual_variable();
+        let b = builder.add_virtual_variable::<BoolTarget>();
+        let selected = builder.select_variable(b, &points[0], &points[1]);
+        builder.curve_assert_valid(&selected);
+        let neg_g_target = builder.constant_affine_point(g.neg());
+        builder.connect_variables(&selected, &neg_g_target);
+
+        let data = builder.build::<C>();
+
+        let values = [g, g.neg()];
+        let mut pw = PartialWitness::new();
+        points.set_witness(&mut pw, &values);
+        assert_eq!(points.get_witness(&pw), values);
+        b.set_witness(&mut pw, &false);
+        let proof = data.prove(pw).unwrap();
+
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -360,7 +435,7 @@ mod tests {
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
 
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -390,7 +465,7 @@ mod tests {
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
 
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -422,7 +497,7 @@ mod tests {
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
 
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -455,7 +530,7 @@ mod tests {
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
 
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -482,6 +557,6 @@ mod tests {
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
 
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 }
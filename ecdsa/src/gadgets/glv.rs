@@ -18,6 +18,7 @@ use plonky2::{
         witness::{PartitionWitness, WitnessWrite},
     },
     plonk::circuit_builder::CircuitBuilder,
+    util::serialization::GeneratorSerialize,
 };
 
 use crate::{
@@ -102,8 +103,8 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderGlv<F, D>
     }
 }
 
-#[derive(Debug, Clone)]
-struct GLVDecompositionGenerator<F: RichField + Extendable<D>, const D: usize> {
+#[derive(Debug, Clone, Default, GeneratorSerialize)]
+pub(crate) struct GLVDecompositionGenerator<F: RichField + Extendable<D>, const D: usize> {
     k: NonNativeTarget<Secp256K1Scalar>,
     k1: NonNativeTarget<Secp256K1Scalar>,
     k2: NonNativeTarget<Secp256K1Scalar>,
@@ -118,22 +119,8 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
     fn id(&self) -> String {
         "GLVDecompositionGenerator".to_string()
     }
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<()> {
-        todo!();
-    }
-    fn deserialize(
-        _src: &mut plonky2::util::serialization::Buffer,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!();
-    }
+    plonky2::derived_serialization!();
+
     fn dependencies(&self) -> Vec<Target> {
         self.k.value.limbs.iter().map(|l| l.0).collect()
     }
@@ -204,6 +191,6 @@ mod tests {
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
 
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 }
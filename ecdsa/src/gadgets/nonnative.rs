@@ -1,9 +1,5 @@
-use alloc::{
-    string::{String, ToString},
-    vec,
-    vec::Vec,
-};
-use core::marker::PhantomData;
+use alloc::{format, string::String, vec, vec::Vec};
+use core::{any::type_name, marker::PhantomData};
 
 use num::{BigUint, Integer, One, Zero};
 use plonky2::{
@@ -15,10 +11,14 @@ use plonky2::{
     iop::{
         generator::{GeneratedValues, SimpleGenerator},
         target::{BoolTarget, Target},
+        variable::CircuitVariable,
         witness::{PartitionWitness, Witness, WitnessWrite},
     },
-    plonk::circuit_builder::CircuitBuilder,
-    util::ceil_div_usize,
+    plonk::{circuit_builder::CircuitBuilder, circuit_data::CommonCircuitData},
+    util::{
+        ceil_div_usize,
+        serialization::{Buffer, CircuitSerialize, GeneratorSerialize, IoResult},
+    },
 };
 use plonky2_u32::{
     gadgets::{
@@ -32,12 +32,63 @@ use crate::gadgets::biguint::{
     BigUintTarget, CircuitBuilderBiguint, GeneratedValuesBigUint, WitnessBigUint,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct NonNativeTarget<FF: Field> {
     pub(crate) value: BigUintTarget,
     pub(crate) _phantom: PhantomData<FF>,
 }
 
+impl<F: RichField + Extendable<D>, const D: usize, FF: Field> CircuitSerialize<F, D>
+    for NonNativeTarget<FF>
+{
+    fn write_to(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        self.value.write_to(dst, common_data)
+    }
+
+    fn read_from(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        Ok(Self { value: BigUintTarget::read_from(src, common_data)?, _phantom: PhantomData })
+    }
+}
+
+/// A non-native field element, made of `ceil(FF::BITS / 32)` limbs, as created by
+/// `add_virtual_nonnative_target`.
+impl<FF: PrimeField> CircuitVariable for NonNativeTarget<FF> {
+    type Value<F: RichField> = FF;
+
+    fn num_targets() -> usize {
+        ceil_div_usize(FF::BITS, 32)
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        debug_assert_eq!(self.value.num_limbs(), Self::num_targets());
+        self.value.limbs.iter().map(|limb| limb.0).collect()
+    }
+
+    fn from_targets(targets: &[Target]) -> Self {
+        assert_eq!(targets.len(), Self::num_targets());
+        let limbs = targets.iter().copied().map(U32Target).collect();
+        Self { value: BigUintTarget { limbs }, _phantom: PhantomData }
+    }
+
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        builder.add_virtual_nonnative_target()
+    }
+
+    fn set_witness<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &FF) {
+        let mut limbs = value.to_canonical_biguint().to_u32_digits();
+        limbs.resize(self.value.num_limbs(), 0);
+        for (limb, value) in self.value.limbs.iter().zip(limbs) {
+            limb.set_witness(witness, &value);
+        }
+    }
+
+    fn get_witness<F: RichField, W: Witness<F>>(&self, witness: &W) -> FF {
+        FF::from_noncanonical_biguint(witness.get_biguint_target(self.value.clone()))
+    }
+}
+
 pub trait CircuitBuilderNonNative<F: RichField + Extendable<D>, const D: usize> {
     fn num_nonnative_limbs<FF: Field>() -> usize {
         ceil_div_usize(FF::BITS, 32)
@@ -451,8 +502,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderNonNative<F, D>
     }
 }
 
-#[derive(Debug, Clone)]
-struct NonNativeAdditionGenerator<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> {
+#[derive(Debug, Clone, Default, GeneratorSerialize)]
+pub(crate) struct NonNativeAdditionGenerator<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FF: PrimeField,
+> {
     a: NonNativeTarget<FF>,
     b: NonNativeTarget<FF>,
     sum: NonNativeTarget<FF>,
@@ -464,24 +519,10 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
     for NonNativeAdditionGenerator<F, D, FF>
 {
     fn id(&self) -> String {
-        "NonNativeAdditionGenerator".to_string()
-    }
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<()> {
-        todo!();
-    }
-    fn deserialize(
-        _src: &mut plonky2::util::serialization::Buffer,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!();
+        format!("NonNativeAdditionGenerator<{}>", type_name::<FF>())
     }
+    plonky2::derived_serialization!();
+
     fn dependencies(&self) -> Vec<Target> {
         self.a.value.limbs.iter().cloned().chain(self.b.value.limbs.clone()).map(|l| l.0).collect()
     }
@@ -504,9 +545,12 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
     }
 }
 
-#[derive(Debug, Clone)]
-struct NonNativeMultipleAddsGenerator<F: RichField + Extendable<D>, const D: usize, FF: PrimeField>
-{
+#[derive(Debug, Clone, Default, GeneratorSerialize)]
+pub(crate) struct NonNativeMultipleAddsGenerator<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FF: PrimeField,
+> {
     summands: Vec<NonNativeTarget<FF>>,
     sum: NonNativeTarget<FF>,
     overflow: U32Target,
@@ -517,24 +561,10 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
     for NonNativeMultipleAddsGenerator<F, D, FF>
 {
     fn id(&self) -> String {
-        "NonNativeMultipleAddsGenerator".to_string()
-    }
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<()> {
-        todo!();
-    }
-    fn deserialize(
-        _src: &mut plonky2::util::serialization::Buffer,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!();
+        format!("NonNativeMultipleAddsGenerator<{}>", type_name::<FF>())
     }
+    plonky2::derived_serialization!();
+
     fn dependencies(&self) -> Vec<Target> {
         self.summands
             .iter()
@@ -564,8 +594,12 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
     }
 }
 
-#[derive(Debug, Clone)]
-struct NonNativeSubtractionGenerator<F: RichField + Extendable<D>, const D: usize, FF: Field> {
+#[derive(Debug, Clone, Default, GeneratorSerialize)]
+pub(crate) struct NonNativeSubtractionGenerator<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FF: Field,
+> {
     a: NonNativeTarget<FF>,
     b: NonNativeTarget<FF>,
     diff: NonNativeTarget<FF>,
@@ -577,24 +611,10 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
     for NonNativeSubtractionGenerator<F, D, FF>
 {
     fn id(&self) -> String {
-        "NonNativeInverseGenerator".to_string()
-    }
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<()> {
-        todo!();
-    }
-    fn deserialize(
-        _src: &mut plonky2::util::serialization::Buffer,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!();
+        format!("NonNativeSubtractionGenerator<{}>", type_name::<FF>())
     }
+    plonky2::derived_serialization!();
+
     fn dependencies(&self) -> Vec<Target> {
         self.a.value.limbs.iter().cloned().chain(self.b.value.limbs.clone()).map(|l| l.0).collect()
     }
@@ -617,8 +637,12 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
     }
 }
 
-#[derive(Debug, Clone)]
-struct NonNativeMultiplicationGenerator<F: RichField + Extendable<D>, const D: usize, FF: Field> {
+#[derive(Debug, Clone, Default, GeneratorSerialize)]
+pub(crate) struct NonNativeMultiplicationGenerator<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FF: Field,
+> {
     a: NonNativeTarget<FF>,
     b: NonNativeTarget<FF>,
     prod: NonNativeTarget<FF>,
@@ -630,24 +654,10 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
     for NonNativeMultiplicationGenerator<F, D, FF>
 {
     fn id(&self) -> String {
-        "NonNativeMultiplicationGenerator".to_string()
-    }
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<()> {
-        todo!();
-    }
-    fn deserialize(
-        _src: &mut plonky2::util::serialization::Buffer,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!();
+        format!("NonNativeMultiplicationGenerator<{}>", type_name::<FF>())
     }
+    plonky2::derived_serialization!();
+
     fn dependencies(&self) -> Vec<Target> {
         self.a.value.limbs.iter().cloned().chain(self.b.value.limbs.clone()).map(|l| l.0).collect()
     }
@@ -668,8 +678,12 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
     }
 }
 
-#[derive(Debug, Clone)]
-struct NonNativeInverseGenerator<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> {
+#[derive(Debug, Clone, Default, GeneratorSerialize)]
+pub(crate) struct NonNativeInverseGenerator<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FF: PrimeField,
+> {
     x: NonNativeTarget<FF>,
     inv: BigUintTarget,
     div: BigUintTarget,
@@ -680,24 +694,10 @@ impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerat
     for NonNativeInverseGenerator<F, D, FF>
 {
     fn id(&self) -> String {
-        "NonNativeInverseGenerator".to_string()
-    }
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<()> {
-        todo!();
-    }
-    fn deserialize(
-        _src: &mut plonky2::util::serialization::Buffer,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!();
+        format!("NonNativeInverseGenerator<{}>", type_name::<FF>())
     }
+    plonky2::derived_serialization!();
+
     fn dependencies(&self) -> Vec<Target> {
         self.x.value.limbs.iter().map(|&l| l.0).collect()
     }
@@ -771,7 +771,7 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -811,7 +811,7 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -841,7 +841,7 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -867,7 +867,7 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -891,7 +891,7 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -915,7 +915,7 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -944,7 +944,7 @@ mod tests {
         pw.set_nonnative_target(y, y_ff);
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     #[test]
@@ -966,6 +966,6 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 }
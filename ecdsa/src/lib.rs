@@ -5,3 +5,4 @@ extern crate alloc;
 
 pub mod curve;
 pub mod gadgets;
+pub mod serialization;
@@ -0,0 +1,130 @@
+use core::marker::PhantomData;
+
+use plonky2::{
+    field::{
+        extension::Extendable, secp256k1_base::Secp256K1Base, secp256k1_scalar::Secp256K1Scalar,
+        types::PrimeField,
+    },
+    get_generator_tag_impl,
+    hash::hash_types::RichField,
+    impl_generator_serializer, read_generator_impl,
+    util::serialization::WitnessGeneratorSerializer,
+};
+
+use crate::gadgets::{
+    biguint::BigUintDivRemGenerator,
+    glv::GLVDecompositionGenerator,
+    nonnative::{
+        NonNativeAdditionGenerator, NonNativeInverseGenerator, NonNativeMultipleAddsGenerator,
+        NonNativeMultiplicationGenerator, NonNativeSubtractionGenerator,
+    },
+};
+
+/// A generator serializer for the generators defined in this crate, with non-native arithmetic
+/// over the secp256k1 base and scalar fields. This crate's gadgets also use the gates and
+/// generators of `plonky2` and `plonky2_u32`, so this is meant to be combined with their
+/// serializers in a `GeneratorSerializerRegistry`.
+#[derive(Debug, Default)]
+pub struct ECDSAGeneratorSerializer;
+
+impl<F: RichField + Extendable<D>, const D: usize> WitnessGeneratorSerializer<F, D>
+    for ECDSAGeneratorSerializer
+{
+    impl_generator_serializer! {
+        ECDSAGeneratorSerializer,
+        BigUintDivRemGenerator<F, D>,
+        GLVDecompositionGenerator<F, D>,
+        NonNativeAdditionGenerator<F, D, Secp256K1Base>,
+        NonNativeAdditionGenerator<F, D, Secp256K1Scalar>,
+        NonNativeMultipleAddsGenerator<F, D, Secp256K1Base>,
+        NonNativeMultipleAddsGenerator<F, D, Secp256K1Scalar>,
+        NonNativeSubtractionGenerator<F, D, Secp256K1Base>,
+        NonNativeSubtractionGenerator<F, D, Secp256K1Scalar>,
+        NonNativeMultiplicationGenerator<F, D, Secp256K1Base>,
+        NonNativeMultiplicationGenerator<F, D, Secp256K1Scalar>,
+        NonNativeInverseGenerator<F, D, Secp256K1Base>,
+        NonNativeInverseGenerator<F, D, Secp256K1Scalar>
+    }
+}
+
+/// A generator serializer for the non-native arithmetic generators over the field `FF`, for
+/// circuits doing non-native arithmetic over fields other than secp256k1's.
+#[derive(Debug, Default)]
+pub struct NonNativeGeneratorSerializer<FF: PrimeField> {
+    _phantom: PhantomData<FF>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> WitnessGeneratorSerializer<F, D>
+    for NonNativeGeneratorSerializer<FF>
+{
+    impl_generator_serializer! {
+        NonNativeGeneratorSerializer,
+        NonNativeAdditionGenerator<F, D, FF>,
+        NonNativeMultipleAddsGenerator<F, D, FF>,
+        NonNativeSubtractionGenerator<F, D, FF>,
+        NonNativeMultiplicationGenerator<F, D, FF>,
+        NonNativeInverseGenerator<F, D, FF>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::{
+        field::types::{Field, Sample},
+        iop::witness::PartialWitness,
+        plonk::{
+            circuit_builder::CircuitBuilder,
+            circuit_data::{CircuitConfig, CircuitData},
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+        util::serialization::{
+            DefaultGateSerializer, DefaultGeneratorSerializer, GateSerializerRegistry,
+            GeneratorSerializerRegistry,
+        },
+    };
+    use plonky2_u32::serialization::{U32GateSerializer, U32GeneratorSerializer};
+
+    use super::*;
+    use crate::gadgets::nonnative::CircuitBuilderNonNative;
+
+    #[test]
+    fn test_ecdsa_generator_serializer() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_ecc_config());
+        let x = builder.constant_nonnative(Secp256K1Base::rand());
+        let x_inv = builder.inv_nonnative(&x);
+        let y = builder.constant_nonnative(Secp256K1Scalar::rand());
+        let y_inv = builder.inv_nonnative(&y);
+        let one = builder.mul_nonnative(&y, &y_inv);
+        let expected_one = builder.constant_nonnative(Secp256K1Scalar::ONE);
+        builder.connect_nonnative(&one, &expected_one);
+        let one = builder.mul_nonnative(&x, &x_inv);
+        let expected_one = builder.constant_nonnative(Secp256K1Base::ONE);
+        builder.connect_nonnative(&one, &expected_one);
+        let data = builder.build::<C>();
+
+        let gate_serializer =
+            GateSerializerRegistry::new().with(DefaultGateSerializer).with(U32GateSerializer);
+        let generator_serializer = GeneratorSerializerRegistry::new()
+            .with(DefaultGeneratorSerializer::<C, D>::default())
+            .with(U32GeneratorSerializer)
+            .with(ECDSAGeneratorSerializer);
+        assert!(data
+            .unsupported_by_serializers(&gate_serializer, &generator_serializer)
+            .is_empty());
+
+        let bytes =
+            data.to_bytes(&gate_serializer, &generator_serializer).map_err(anyhow::Error::msg)?;
+        let data_from_bytes =
+            CircuitData::<F, C, D>::from_bytes(&bytes, &gate_serializer, &generator_serializer)
+                .map_err(anyhow::Error::msg)?;
+        assert_eq!(data_from_bytes, data);
+
+        let proof = data_from_bytes.prove(PartialWitness::new())?;
+        Ok(data.verify(proof)?)
+    }
+}
@@ -7,14 +7,16 @@ use plonky2::{
     iop::{
         generator::{GeneratedValues, SimpleGenerator},
         target::{BoolTarget, Target},
+        variable::CircuitVariable,
         witness::{PartitionWitness, Witness, WitnessWrite},
     },
-    plonk::circuit_builder::CircuitBuilder,
+    plonk::{circuit_builder::CircuitBuilder, circuit_data::CommonCircuitData},
+    util::serialization::{Buffer, CircuitSerialize, GeneratorSerialize, IoResult},
 };
 use plonky2_ecdsa::gadgets::{biguint::BigUintTarget, nonnative::CircuitBuilderNonNative};
 use plonky2_field::{
     extension::{Extendable, FieldExtension},
-    types::Field,
+    types::{Field, PrimeField64},
 };
 use plonky2_u32::gadgets::arithmetic_u32::U32Target;
 
@@ -25,10 +27,22 @@ use crate::{
 
 const THREE: GFp = GoldilocksField(3);
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
 #[repr(transparent)]
 pub struct QuinticExtensionTarget(pub [Target; 5]);
 
+impl<F: RichField + Extendable<D>, const D: usize> CircuitSerialize<F, D>
+    for QuinticExtensionTarget
+{
+    fn write_to(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        self.0.write_to(dst, common_data)
+    }
+
+    fn read_from(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        Ok(Self(<[Target; 5]>::read_from(src, common_data)?))
+    }
+}
+
 impl QuinticExtensionTarget {
     pub fn new(limbs: [Target; 5]) -> Self {
         Self(limbs)
@@ -39,6 +53,37 @@ impl QuinticExtensionTarget {
     }
 }
 
+/// The gadgets of this crate work over the Goldilocks field, so the value of a
+/// `QuinticExtensionTarget` is a `GFp5`, whose limbs are converted to and from the circuit's field
+/// through their canonical `u64` representation.
+impl CircuitVariable for QuinticExtensionTarget {
+    type Value<F: RichField> = GFp5;
+
+    fn num_targets() -> usize {
+        5
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        self.0.to_vec()
+    }
+
+    fn from_targets(targets: &[Target]) -> Self {
+        Self(targets.try_into().expect("a QuinticExtensionTarget is made of 5 targets"))
+    }
+
+    fn set_witness<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &GFp5) {
+        for (&t, x) in self.0.iter().zip(value.0) {
+            witness.set_target(t, F::from_canonical_u64(x.to_canonical_u64()));
+        }
+    }
+
+    fn get_witness<F: RichField, W: Witness<F>>(&self, witness: &W) -> GFp5 {
+        QuinticExtension(
+            self.0.map(|t| GFp::from_noncanonical_u64(witness.get_target(t).to_canonical_u64())),
+        )
+    }
+}
+
 pub trait CircuitBuilderGFp5<F: RichField + Extendable<5>> {
     fn add_virtual_quintic_ext_target(&mut self) -> QuinticExtensionTarget;
     fn connect_quintic_ext(&mut self, a: QuinticExtensionTarget, b: QuinticExtensionTarget);
@@ -712,7 +757,7 @@ impl CircuitBuilderGFp5<GFp> for CircuitBuilder<GFp, 2> {
 // impl_circuit_builder_for_extension_degree!(4);
 // impl_circuit_builder_for_extension_degree!(5);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, GeneratorSerialize)]
 pub struct QuinticQuotientGenerator {
     numerator: QuinticExtensionTarget,
     denominator: QuinticExtensionTarget,
@@ -735,22 +780,8 @@ impl<F: RichField + Extendable<5> + Extendable<2>> SimpleGenerator<F, 2>
     fn id(&self) -> String {
         "QuinticQuotientGenerator".to_string()
     }
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, 2>,
-    ) -> plonky2::util::serialization::IoResult<()> {
-        todo!();
-    }
-    fn deserialize(
-        _src: &mut plonky2::util::serialization::Buffer,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, 2>,
-    ) -> plonky2::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!();
-    }
+    plonky2::derived_serialization!(F, 2);
+
     fn dependencies(&self) -> Vec<Target> {
         let mut deps = self.numerator.to_target_array().to_vec();
         deps.extend(self.denominator.to_target_array());
@@ -777,7 +808,7 @@ impl<F: RichField + Extendable<5> + Extendable<2>> SimpleGenerator<F, 2>
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, GeneratorSerialize)]
 pub struct QuinticSqrtGenerator {
     x: QuinticExtensionTarget,
     root_x: QuinticExtensionTarget,
@@ -798,22 +829,8 @@ impl SimpleGenerator<GFp, 2> for QuinticSqrtGenerator {
     fn id(&self) -> String {
         "QuinticSqrtGenerator".to_string()
     }
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<GFp, 2>,
-    ) -> plonky2::util::serialization::IoResult<()> {
-        todo!();
-    }
-    fn deserialize(
-        _src: &mut plonky2::util::serialization::Buffer,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<GFp, 2>,
-    ) -> plonky2::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!();
-    }
+    plonky2::derived_serialization!(GFp, 2);
+
     fn dependencies(&self) -> Vec<Target> {
         self.x.to_target_array().to_vec()
     }
@@ -894,7 +911,7 @@ mod tests {
         pw.set_quintic_ext_target(z, z_expected);
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 
     #[test]
@@ -923,7 +940,7 @@ mod tests {
         pw.set_quintic_ext_target(z, z_expected);
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 
     #[test]
@@ -952,7 +969,7 @@ mod tests {
         pw.set_quintic_ext_target(z, z_expected);
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 
     #[test]
@@ -981,7 +998,7 @@ mod tests {
         pw.set_quintic_ext_target(z, z_expected);
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 
     #[test]
@@ -1008,7 +1025,7 @@ mod tests {
         pw.set_quintic_ext_target(x_inv, x_inv_expected);
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 
     #[test]
@@ -1031,7 +1048,7 @@ mod tests {
 
         let pw = PartialWitness::new();
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
     #[test]
 
@@ -1056,7 +1073,7 @@ mod tests {
         pw.set_quintic_ext_target(sqrt, x);
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 
     #[test]
@@ -1091,7 +1108,7 @@ mod tests {
 
         let pw = PartialWitness::new();
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
     #[test]
 
@@ -1124,7 +1141,7 @@ mod tests {
 
         let pw = PartialWitness::new();
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 
     #[test]
@@ -1182,7 +1199,7 @@ mod tests {
         pw.set_target(legendre_sym, GFp::ZERO);
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 
     #[test]
@@ -1214,6 +1231,6 @@ mod tests {
         pw.set_scalar_target(&encoded_as_biguint, encoded_expected);
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 }
@@ -11,7 +11,8 @@ use plonky2::{
     hash::hash_types::RichField,
     iop::{
         target::{BoolTarget, Target},
-        witness::Witness,
+        variable::CircuitVariable,
+        witness::{Witness, WitnessWrite},
     },
     plonk::circuit_builder::CircuitBuilder,
 };
@@ -24,6 +25,58 @@ use super::base_field::PartialWitnessQuinticExt;
 #[repr(transparent)]
 pub struct CurveTarget(pub ([QuinticExtensionTarget; 2], BoolTarget));
 
+impl CircuitVariable for CurveTarget {
+    type Value<F: RichField> = WeierstrassPoint;
+
+    fn num_targets() -> usize {
+        2 * QuinticExtensionTarget::num_targets() + 1
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        let CurveTarget((coords, is_inf)) = self;
+        let mut targets = coords.targets();
+        targets.push(is_inf.target);
+        targets
+    }
+
+    fn from_targets(targets: &[Target]) -> Self {
+        assert_eq!(targets.len(), Self::num_targets());
+        let (coords, is_inf) = targets.split_at(targets.len() - 1);
+        CurveTarget((
+            <[QuinticExtensionTarget; 2]>::from_targets(coords),
+            BoolTarget::from_targets(is_inf),
+        ))
+    }
+
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        let coords = <[QuinticExtensionTarget; 2]>::add_virtual(builder);
+        let is_inf = builder.add_virtual_bool_target_safe();
+        CurveTarget((coords, is_inf))
+    }
+
+    fn set_witness<F: RichField, W: WitnessWrite<F>>(
+        &self,
+        witness: &mut W,
+        value: &WeierstrassPoint,
+    ) {
+        let CurveTarget(([x, y], is_inf)) = self;
+        x.set_witness(witness, &value.x);
+        y.set_witness(witness, &value.y);
+        witness.set_bool_target(*is_inf, value.is_inf);
+    }
+
+    fn get_witness<F: RichField, W: Witness<F>>(&self, witness: &W) -> WeierstrassPoint {
+        let CurveTarget(([x, y], is_inf)) = self;
+        WeierstrassPoint {
+            x: x.get_witness(witness),
+            y: y.get_witness(witness),
+            is_inf: witness.get_bool_target(*is_inf),
+        }
+    }
+}
+
 pub trait CircuitBuilderEcGFp5 {
     fn add_virtual_curve_target(&mut self) -> CurveTarget;
     fn register_curve_public_input(&mut self, point: CurveTarget);
@@ -483,7 +536,7 @@ mod tests {
         pw.set_curve_target(p3, p3_expected.to_weierstrass());
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 
     #[test]
@@ -510,7 +563,7 @@ mod tests {
         pw.set_curve_target(p2, p2_expected.to_weierstrass());
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 
     #[test]
@@ -540,7 +593,7 @@ mod tests {
         pw.set_curve_target(prod, prod_expected.to_weierstrass());
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 
     #[test]
@@ -569,7 +622,7 @@ mod tests {
         pw.set_curve_target(prod, prod_expected.to_weierstrass());
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 
     #[test]
@@ -596,7 +649,7 @@ mod tests {
         pw.set_quintic_ext_target(w, w_expected);
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 
     #[test]
@@ -623,7 +676,7 @@ mod tests {
         pw.set_curve_target(p, p_expected.to_weierstrass());
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 
     #[test]
@@ -658,6 +711,6 @@ mod tests {
         pw.set_curve_target(prod, prod_expected.to_weierstrass());
 
         let proof = circuit.prove(pw)?;
-        circuit.verify(proof)
+        Ok(circuit.verify(proof)?)
     }
 }
@@ -12,15 +12,16 @@ use plonky2::{
     },
     plonk::{
         circuit_builder::CircuitBuilder,
-        circuit_data::CircuitConfig,
+        circuit_data::{CircuitConfig, CommonCircuitData},
         vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase},
     },
+    util::serialization::{Buffer, GateSerialize, IoResult, Read, Write},
 };
 use plonky2_field::{extension::Extendable, types::Field};
 
 /// A gate which can perform a weighted multiply, i.e. `result = c0 x y`. If the config
 /// supports enough routed wires, it can support several such operations in one gate.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, GateSerialize)]
 pub struct MulGFp5Gate {
     /// Number of arithmetic operations performed by an arithmetic gate.
     pub num_ops: usize,
@@ -60,27 +61,80 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for MulGFp5Gate {
         format!("{self:?}")
     }
     fn export_circom_verification_code(&self) -> String {
-        todo!();
+        "template MulGFp5Ops$NUM_OPS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  signal tripled[$NUM_OPS][5][2];
+  signal prod[$NUM_OPS][5][2];
+  for (var i = 0; i < $NUM_OPS; i++) {
+    var start = 15 * i;
+    for (var l = 0; l < 5; l++) {
+      tripled[i][l] <== GlExtScalarMul()(wires[start + 5 + l], 3);
+    }
+    // c_k = sum_{j + l = k} a_j b_l + 3 sum_{j + l = k + 5} a_j b_l
+    for (var k = 0; k < 5; k++) {
+      var a[5][2];
+      var b[5][2];
+      for (var j = 0; j < 5; j++) {
+        a[j] = wires[start + j];
+        if (j <= k) {
+          b[j] = wires[start + 5 + k - j];
+        } else {
+          b[j] = tripled[i][k + 5 - j];
+        }
+      }
+      prod[i][k] <== GlExtInnerProduct(5)(a, b);
+      out[5 * i + k] <== ConstraintPush()(constraints[5 * i + k], filter, GlExtSub()(wires[start + 10 + k], GlExtMul()(constants[$NUM_SELECTORS], prod[i][k])));
+    }
+  }
+
+  for (var i = 5 * $NUM_OPS; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .replace("$NUM_OPS", &self.num_ops.to_string())
     }
     fn export_solidity_verification_code(&self) -> String {
-        todo!();
+        "library MulGFp5Ops$NUM_OPSLib {
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
     }
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<()> {
-        todo!();
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint64[2] memory c = ev.constants[$NUM_SELECTORS];
+        for (uint32 i = 0; i < $NUM_OPS; i++) {
+            uint32 start = 15 * i;
+            // c_k = sum_{j + l = k} a_j b_l + 3 sum_{j + l = k + 5} a_j b_l
+            uint64[2][5] memory prod;
+            for (uint32 j = 0; j < 5; j++) {
+                for (uint32 l = 0; l < 5; l++) {
+                    uint64[2] memory term = ev.wires[start + j].mul(ev.wires[start + 5 + l]);
+                    if (j + l < 5) {
+                        prod[j + l] = prod[j + l].add(term);
+                    } else {
+                        prod[j + l - 5] = prod[j + l - 5].add(term.scalar_mul(3));
+                    }
+                }
+            }
+            for (uint32 k = 0; k < 5; k++) {
+                GatesUtilsLib.push(constraints, ev.filter, 5 * i + k, ev.wires[start + 10 + k].sub(c.mul(prod[k])));
+            }
+        }
     }
-    fn deserialize(
-        _src: &mut plonky2::util::serialization::Buffer,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!();
+}"
+        .replace("$NUM_OPS", &self.num_ops.to_string())
     }
+    plonky2::derived_serialization!();
+
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let c = vars.local_constants[0];
 
@@ -194,7 +248,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for MulGFp5Gate {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct MulGFp5Generator<F: RichField + Extendable<D>, const D: usize> {
     row: usize,
     c: F,
@@ -207,22 +261,19 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
     fn id(&self) -> String {
         "MulGFp5Generator".to_string()
     }
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<()> {
-        todo!();
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        dst.write_field(self.c)?;
+        dst.write_usize(self.op_idx)
     }
-    fn deserialize(
-        _src: &mut plonky2::util::serialization::Buffer,
-        _common_data: &plonky2::plonk::circuit_data::CommonCircuitData<F, D>,
-    ) -> plonky2::util::serialization::IoResult<Self>
-    where
-        Self: Sized,
-    {
-        todo!();
+
+    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let row = src.read_usize_at_most(common_data.degree())?;
+        let c = src.read_field()?;
+        let op_idx = src.read_usize_at_most(common_data.config.num_wires)?;
+        Ok(Self { row, c, op_idx })
     }
+
     fn dependencies(&self) -> Vec<Target> {
         MulGFp5Gate::wires_ith_multiplicand_0(self.op_idx)
             .chain(MulGFp5Gate::wires_ith_multiplicand_1(self.op_idx))
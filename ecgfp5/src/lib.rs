@@ -3,6 +3,7 @@ extern crate alloc;
 pub mod curve;
 pub mod gadgets;
 pub(crate) mod gates;
+pub mod serialization;
 
 #[cfg(test)]
 mod macros;
@@ -0,0 +1,129 @@
+use plonky2::{
+    get_gate_tag_impl, get_generator_tag_impl,
+    hash::hash_types::RichField,
+    impl_gate_serializer, impl_generator_serializer, read_gate_impl, read_generator_impl,
+    util::serialization::{GateSerializer, WitnessGeneratorSerializer},
+};
+use plonky2_field::extension::Extendable;
+
+use crate::{
+    curve::GFp,
+    gadgets::base_field::{QuinticQuotientGenerator, QuinticSqrtGenerator},
+    gates::gfp5_mul::{MulGFp5Gate, MulGFp5Generator},
+};
+
+/// A gate serializer for the gates defined in this crate.
+#[derive(Debug, Default)]
+pub struct EcGFp5GateSerializer;
+
+impl<F: RichField + Extendable<D>, const D: usize> GateSerializer<F, D> for EcGFp5GateSerializer {
+    impl_gate_serializer! {
+        EcGFp5GateSerializer,
+        MulGFp5Gate
+    }
+}
+
+/// A generator serializer for the generators defined in this crate. The gadgets of this crate
+/// also use generators from `plonky2`, `plonky2_u32` and `plonky2_ecdsa`, including the
+/// non-native arithmetic generators over the curve's scalar field, so this is meant to be
+/// combined with their serializers in a `GeneratorSerializerRegistry`.
+#[derive(Debug, Default)]
+pub struct EcGFp5GeneratorSerializer;
+
+mod generators {
+    use super::*;
+
+    // The gadgets of this crate are specific to the Goldilocks field, and
+    // `impl_generator_serializer` refers to the field and extension degree as `F` and `D`.
+    type F = GFp;
+    const D: usize = 2;
+
+    impl WitnessGeneratorSerializer<F, D> for EcGFp5GeneratorSerializer {
+        impl_generator_serializer! {
+            EcGFp5GeneratorSerializer,
+            MulGFp5Generator<F, D>,
+            QuinticQuotientGenerator,
+            QuinticSqrtGenerator
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::{
+        iop::witness::PartialWitness,
+        plonk::{
+            circuit_builder::CircuitBuilder,
+            circuit_data::{CircuitConfig, CircuitData},
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+        util::serialization::{
+            DefaultGateSerializer, DefaultGeneratorSerializer, GateSerializerRegistry,
+            GeneratorSerializerRegistry,
+        },
+    };
+    use plonky2_ecdsa::{
+        gadgets::nonnative::CircuitBuilderNonNative,
+        serialization::{ECDSAGeneratorSerializer, NonNativeGeneratorSerializer},
+    };
+    use plonky2_field::types::{Field, Sample};
+    use plonky2_u32::serialization::{U32GateSerializer, U32GeneratorSerializer};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::{
+        curve::{scalar_field::Scalar, GFp5},
+        gadgets::base_field::{CircuitBuilderGFp5, PartialWitnessQuinticExt},
+    };
+
+    #[test]
+    fn test_ecgfp5_serializers() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut rng = thread_rng();
+        // The range checks of the nonnative multiplication need more wires than usual.
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::wide_ecc_config());
+        let x = builder.add_virtual_quintic_ext_target();
+        let x_inv = builder.inverse_quintic_ext(x);
+        let x_sqr = builder.square_quintic_ext(x);
+        let (_, is_sqrt) = builder.try_any_sqrt_quintic_ext(x_sqr);
+        builder.assert_one(is_sqrt.target);
+        builder.register_quintic_ext_public_input(x_inv);
+
+        let s = builder.constant_nonnative(Scalar::sample(&mut rng));
+        let s_inv = builder.inv_nonnative(&s);
+        let one = builder.mul_nonnative(&s, &s_inv);
+        let expected_one = builder.constant_nonnative(Scalar::ONE);
+        builder.connect_nonnative(&one, &expected_one);
+        let data = builder.build::<C>();
+
+        let gate_serializer = GateSerializerRegistry::new()
+            .with(DefaultGateSerializer)
+            .with(U32GateSerializer)
+            .with(EcGFp5GateSerializer);
+        let generator_serializer = GeneratorSerializerRegistry::new()
+            .with(DefaultGeneratorSerializer::<C, D>::default())
+            .with(U32GeneratorSerializer)
+            .with(ECDSAGeneratorSerializer)
+            .with(NonNativeGeneratorSerializer::<Scalar>::default())
+            .with(EcGFp5GeneratorSerializer);
+        assert!(data
+            .unsupported_by_serializers(&gate_serializer, &generator_serializer)
+            .is_empty());
+
+        let bytes =
+            data.to_bytes(&gate_serializer, &generator_serializer).map_err(anyhow::Error::msg)?;
+        let data_from_bytes =
+            CircuitData::<F, C, D>::from_bytes(&bytes, &gate_serializer, &generator_serializer)
+                .map_err(anyhow::Error::msg)?;
+        assert_eq!(data_from_bytes, data);
+
+        let mut pw = PartialWitness::new();
+        pw.set_quintic_ext_target(x, GFp5::sample(&mut rng));
+        let proof = data_from_bytes.prove(pw)?;
+        Ok(data.verify(proof)?)
+    }
+}
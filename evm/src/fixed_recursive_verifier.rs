@@ -956,7 +956,7 @@ where
     }
 
     pub fn verify_root(&self, agg_proof: ProofWithPublicInputs<F, C, D>) -> anyhow::Result<()> {
-        self.root.circuit.verify(agg_proof)
+        Ok(self.root.circuit.verify(agg_proof)?)
     }
 
     pub fn prove_aggregation(
@@ -1274,7 +1274,6 @@ where
             THRESHOLD_DEGREE_BITS,
         );
         let mut shrinking_wrappers = vec![];
-   
 
         // Shrinking recursion loop.
         loop {
@@ -1289,8 +1288,7 @@ where
             }
 
             let mut builder = CircuitBuilder::new(shrinking_config());
-            let proof_with_pis_target =
-                builder.add_virtual_proof_with_pis::<C>(&last.common);
+            let proof_with_pis_target = builder.add_virtual_proof_with_pis::<C>(&last.common);
             let last_vk = builder.constant_verifier_data(&last.verifier_only);
             builder.verify_proof::<C>(&proof_with_pis_target, &last_vk, &last.common);
             builder.register_public_inputs(&proof_with_pis_target.public_inputs); // carry PIs forward
@@ -45,5 +45,5 @@ fn main() -> Result<()> {
         proof.public_inputs[0], proof.public_inputs[1], proof.public_inputs[2]
     );
 
-    data.verify(proof)
+    Ok(data.verify(proof)?)
 }
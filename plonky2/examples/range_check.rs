@@ -34,5 +34,5 @@ fn main() -> Result<()> {
         proof.public_inputs[0], log_max,
     );
 
-    data.verify(proof)
+    Ok(data.verify(proof)?)
 }
@@ -148,5 +148,5 @@ fn main() -> Result<()> {
         assert_eq!(data, data_from_bytes);
     }
 
-    data.verify(proof)
+    Ok(data.verify(proof)?)
 }
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use plonky2::plonk::proof::ProofWithPublicInputs;
+use plonky2_fuzz::circuit_and_proof;
+
+fuzz_target!(|data: &[u8]| {
+    let (circuit, _) = circuit_and_proof();
+    if let Ok(proof) = ProofWithPublicInputs::from_bytes(data.to_vec(), &circuit.common) {
+        let _ = circuit.verify(proof);
+    }
+});
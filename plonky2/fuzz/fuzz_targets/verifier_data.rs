@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use plonky2::plonk::circuit_data::VerifierCircuitData;
+use plonky2::util::serialization::DefaultGateSerializer;
+use plonky2_fuzz::{circuit_and_proof, C, D, F};
+
+fuzz_target!(|data: &[u8]| {
+    let (_, proof) = circuit_and_proof();
+    if let Ok(verifier_data) =
+        VerifierCircuitData::<F, C, D>::from_bytes(data.to_vec(), &DefaultGateSerializer)
+    {
+        let _ = verifier_data.verify(proof.clone());
+    }
+});
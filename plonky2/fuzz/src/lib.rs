@@ -0,0 +1,35 @@
+//! Shared setup for the deserialization fuzz targets.
+
+use std::sync::OnceLock;
+
+use plonky2::field::types::Sample;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::{CircuitConfig, CircuitData};
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use plonky2::plonk::proof::ProofWithPublicInputs;
+
+pub const D: usize = 2;
+pub type C = PoseidonGoldilocksConfig;
+pub type F = <C as GenericConfig<D>>::F;
+
+/// A small circuit proving `x * y = z` for public inputs `x`, `y` and `z`, along with a valid
+/// proof for it. Both are built once and shared by all fuzzing iterations.
+pub fn circuit_and_proof() -> &'static (CircuitData<F, C, D>, ProofWithPublicInputs<F, C, D>) {
+    static CIRCUIT_AND_PROOF: OnceLock<(CircuitData<F, C, D>, ProofWithPublicInputs<F, C, D>)> =
+        OnceLock::new();
+    CIRCUIT_AND_PROOF.get_or_init(|| {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_public_input();
+        let y = builder.add_virtual_public_input();
+        let z = builder.mul(x, y);
+        builder.register_public_input(z);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand());
+        pw.set_target(y, F::rand());
+        let proof = data.prove(pw).expect("failed to prove the fuzzing circuit");
+        (data, proof)
+    })
+}
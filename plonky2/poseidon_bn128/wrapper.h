@@ -1 +0,0 @@
-#include "libposeidon-permute-c.h"
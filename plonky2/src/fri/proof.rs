@@ -237,12 +237,14 @@ impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> FriProof<F, H,
 
 impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriProof<F, H, D> {
     /// Decompress all the Merkle paths in the FRI proof and reinsert duplicate indices.
+    ///
+    /// Returns `None` if the proof is missing data needed to decompress it.
     pub(crate) fn decompress(
         self,
         challenges: &ProofChallenges<F, D>,
         fri_inferred_elements: FriInferredElements<F, D>,
         params: &FriParams,
-    ) -> FriProof<F, H, D> {
+    ) -> Option<FriProof<F, H, D>> {
         let CompressedFriProof {
             commit_phase_merkle_caps,
             query_round_proofs,
@@ -261,8 +263,7 @@ impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriPr
         let num_initial_trees = query_round_proofs
             .initial_trees_proofs
             .values()
-            .next()
-            .unwrap()
+            .next()?
             .evals_proofs
             .len();
 
@@ -286,7 +287,7 @@ impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriPr
         let mut evals_by_depth =
             vec![HashMap::<usize, Vec<_>>::new(); params.reduction_arity_bits.len()];
         for &(mut index) in indices {
-            let initial_trees_proof = query_round_proofs.initial_trees_proofs[&index].clone();
+            let initial_trees_proof = query_round_proofs.initial_trees_proofs.get(&index)?.clone();
             for (i, (leaves_data, proof)) in
                 initial_trees_proof.evals_proofs.into_iter().enumerate()
             {
@@ -300,14 +301,14 @@ impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriPr
                 let FriQueryStep {
                     mut evals,
                     merkle_proof,
-                } = query_round_proofs.steps[i][&index].clone();
+                } = query_round_proofs.steps.get(i)?.get(&index)?.clone();
                 steps_indices[i].push(index);
                 if let Some(v) = evals_by_depth[i].get(&index) {
                     // If this index has already been seen, get `evals` from the `HashMap`.
                     evals = v.to_vec();
                 } else {
                     // Otherwise insert the next inferred element.
-                    evals.insert(index_within_coset, fri_inferred_elements.next().unwrap());
+                    evals.insert(index_within_coset, fri_inferred_elements.next()?);
                     evals_by_depth[i].insert(index, evals.clone());
                 }
                 steps_evals[i].push(flatten(&evals));
@@ -322,10 +323,10 @@ impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriPr
             initial_trees_proofs
         )
         .map(|(ls, is, ps)| decompress_merkle_proofs(ls, is, &ps, height, cap_height))
-        .collect::<Vec<_>>();
+        .collect::<Option<Vec<_>>>()?;
         let steps_proofs = izip!(&steps_evals, &steps_indices, steps_proofs, heights)
             .map(|(ls, is, ps, h)| decompress_merkle_proofs(ls, is, &ps, h, cap_height))
-            .collect::<Vec<_>>();
+            .collect::<Option<Vec<_>>>()?;
 
         let mut decompressed_query_proofs = Vec::with_capacity(num_reductions);
         for i in 0..indices.len() {
@@ -351,12 +352,12 @@ impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriPr
             })
         }
 
-        FriProof {
+        Some(FriProof {
             commit_phase_merkle_caps,
             query_round_proofs: decompressed_query_proofs,
             final_poly,
             pow_witness,
-        }
+        })
     }
 }
 
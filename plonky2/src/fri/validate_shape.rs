@@ -1,18 +1,19 @@
-use anyhow::ensure;
-
 use crate::field::extension::Extendable;
-use crate::fri::proof::{FriProof, FriQueryRound, FriQueryStep};
-use crate::fri::structure::FriInstanceInfo;
+use crate::fri::proof::{
+    CompressedFriProof, FriInitialTreeProof, FriProof, FriQueryRound, FriQueryStep,
+};
+use crate::fri::structure::{FriInstanceInfo, FriOracleInfo};
 use crate::fri::FriParams;
 use crate::hash::hash_types::RichField;
-use crate::plonk::config::GenericConfig;
+use crate::plonk::config::{GenericConfig, Hasher};
 use crate::plonk::plonk_common::salt_size;
+use crate::plonk::verifier::{ensure_shape, VerificationError};
 
 pub(crate) fn validate_fri_proof_shape<F, C, const D: usize>(
     proof: &FriProof<F, C::Hasher, D>,
     instance: &FriInstanceInfo<F, D>,
     params: &FriParams,
-) -> anyhow::Result<()>
+) -> Result<(), VerificationError>
 where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -26,7 +27,7 @@ where
 
     let cap_height = params.config.cap_height;
     for cap in commit_phase_merkle_caps {
-        ensure!(cap.height() == cap_height);
+        ensure_shape(cap.height() == cap_height, "FRI commit phase cap")?;
     }
 
     for query_round in query_round_proofs {
@@ -35,17 +36,12 @@ where
             steps,
         } = query_round;
 
-        ensure!(initial_trees_proof.evals_proofs.len() == instance.oracles.len());
-        for ((leaf, merkle_proof), oracle) in initial_trees_proof
-            .evals_proofs
-            .iter()
-            .zip(&instance.oracles)
-        {
-            ensure!(leaf.len() == oracle.num_polys + salt_size(oracle.blinding && params.hiding));
-            ensure!(merkle_proof.len() + cap_height == params.lde_bits());
-        }
+        validate_initial_trees_proof_shape(initial_trees_proof, &instance.oracles, params, false)?;
 
-        ensure!(steps.len() == params.reduction_arity_bits.len());
+        ensure_shape(
+            steps.len() == params.reduction_arity_bits.len(),
+            "FRI query steps",
+        )?;
         let mut codeword_len_bits = params.lde_bits();
         for (step, arity_bits) in steps.iter().zip(&params.reduction_arity_bits) {
             let FriQueryStep {
@@ -56,12 +52,111 @@ where
             let arity = 1 << arity_bits;
             codeword_len_bits -= arity_bits;
 
-            ensure!(evals.len() == arity);
-            ensure!(merkle_proof.len() + cap_height == codeword_len_bits);
+            ensure_shape(evals.len() == arity, "FRI query step evaluations")?;
+            ensure_shape(
+                merkle_proof.len() + cap_height == codeword_len_bits,
+                "FRI query step Merkle proof",
+            )?;
         }
     }
 
-    ensure!(final_poly.len() == params.final_poly_len());
+    ensure_shape(
+        final_poly.len() == params.final_poly_len(),
+        "FRI final polynomial",
+    )?;
+
+    Ok(())
+}
 
+/// Like `validate_fri_proof_shape`, but for a compressed proof, in which the evaluation inferred
+/// from the previous step is omitted and Merkle proofs may be shortened by path compression.
+///
+/// This does not check that the query rounds contain an entry for every queried index, as the
+/// query indices are only known after the challenges have been derived.
+pub(crate) fn validate_compressed_fri_proof_shape<F, C, const D: usize>(
+    proof: &CompressedFriProof<F, C::Hasher, D>,
+    oracles: &[FriOracleInfo],
+    params: &FriParams,
+) -> Result<(), VerificationError>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let CompressedFriProof {
+        commit_phase_merkle_caps,
+        query_round_proofs,
+        final_poly,
+        pow_witness: _pow_witness,
+    } = proof;
+
+    let cap_height = params.config.cap_height;
+    ensure_shape(
+        commit_phase_merkle_caps.len() == params.reduction_arity_bits.len(),
+        "FRI commit phase caps",
+    )?;
+    for cap in commit_phase_merkle_caps {
+        ensure_shape(cap.height() == cap_height, "FRI commit phase cap")?;
+    }
+
+    for initial_trees_proof in query_round_proofs.initial_trees_proofs.values() {
+        validate_initial_trees_proof_shape(initial_trees_proof, oracles, params, true)?;
+    }
+
+    ensure_shape(
+        query_round_proofs.steps.len() == params.reduction_arity_bits.len(),
+        "FRI query steps",
+    )?;
+    let mut codeword_len_bits = params.lde_bits();
+    for (steps, arity_bits) in query_round_proofs
+        .steps
+        .iter()
+        .zip(&params.reduction_arity_bits)
+    {
+        let arity = 1 << arity_bits;
+        codeword_len_bits -= arity_bits;
+
+        for step in steps.values() {
+            ensure_shape(step.evals.len() == arity - 1, "FRI query step evaluations")?;
+            ensure_shape(
+                step.merkle_proof.len() + cap_height <= codeword_len_bits,
+                "FRI query step Merkle proof",
+            )?;
+        }
+    }
+
+    ensure_shape(
+        final_poly.len() == params.final_poly_len(),
+        "FRI final polynomial",
+    )?;
+
+    Ok(())
+}
+
+fn validate_initial_trees_proof_shape<F: RichField, H: Hasher<F>>(
+    initial_trees_proof: &FriInitialTreeProof<F, H>,
+    oracles: &[FriOracleInfo],
+    params: &FriParams,
+    compressed: bool,
+) -> Result<(), VerificationError> {
+    let cap_height = params.config.cap_height;
+    ensure_shape(
+        initial_trees_proof.evals_proofs.len() == oracles.len(),
+        "FRI initial trees proof",
+    )?;
+    for ((leaf, merkle_proof), oracle) in initial_trees_proof.evals_proofs.iter().zip(oracles) {
+        ensure_shape(
+            leaf.len() == oracle.num_polys + salt_size(oracle.blinding && params.hiding),
+            "FRI initial tree leaf",
+        )?;
+        let merkle_proof_len = merkle_proof.len() + cap_height;
+        ensure_shape(
+            if compressed {
+                merkle_proof_len <= params.lde_bits()
+            } else {
+                merkle_proof_len == params.lde_bits()
+            },
+            "FRI initial tree Merkle proof",
+        )?;
+    }
     Ok(())
 }
@@ -0,0 +1,484 @@
+//! Arrays whose length is only known from the witness.
+//!
+//! An [`ArrayTarget`] holds up to `capacity` elements, with a `len` target giving the number of
+//! elements in use. Elements past `len` are ignored by all gadgets, so they may have any value.
+//! This makes it possible to handle variable-length messages, e.g. to slice them or to pad and
+//! hash them, without padding and masking them by hand.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::iter::repeat;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::{HashOutTarget, RichField, NUM_HASH_OUT_ELTS};
+use crate::hash::hashing::PlonkyPermutation;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::AlgebraicHasher;
+use crate::util::{ceil_div_usize, log2_ceil};
+
+/// An array of at most `elements.len()` field elements, of which the first `len` are in use.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArrayTarget {
+    pub elements: Vec<Target>,
+    pub len: Target,
+}
+
+impl ArrayTarget {
+    /// The maximum length of this array.
+    pub fn capacity(&self) -> usize {
+        self.elements.len()
+    }
+}
+
+/// An `ArrayTarget` whose elements have been constrained to be bytes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(clippy::manual_non_exhaustive)]
+pub struct BytesTarget {
+    pub array: ArrayTarget,
+    /// This private field is here to force all instantiations to go through `new_unsafe`.
+    _private: (),
+}
+
+impl BytesTarget {
+    pub const fn new_unsafe(array: ArrayTarget) -> Self {
+        Self {
+            array,
+            _private: (),
+        }
+    }
+
+    /// The maximum length of this byte string.
+    pub fn capacity(&self) -> usize {
+        self.array.capacity()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Adds a new `ArrayTarget` of the given capacity, whose length is checked to be at most
+    /// `capacity`.
+    pub fn add_virtual_array_target(&mut self, capacity: usize) -> ArrayTarget {
+        let array = ArrayTarget {
+            elements: self.add_virtual_targets(capacity),
+            len: self.add_virtual_target(),
+        };
+        let max_len = self.constant(F::from_canonical_usize(capacity));
+        self.assert_less_or_equal(array.len, max_len, log2_ceil(capacity + 1));
+        array
+    }
+
+    /// Adds a new `BytesTarget` of the given capacity. All its elements, including those past its
+    /// length, are range-checked to be bytes.
+    pub fn add_virtual_bytes_target(&mut self, capacity: usize) -> BytesTarget {
+        let array = self.add_virtual_array_target(capacity);
+        for &byte in &array.elements {
+            self.range_check(byte, 8);
+        }
+        BytesTarget::new_unsafe(array)
+    }
+
+    /// Returns the element of `array` at `index`, which is checked to be less than its length.
+    pub fn array_index(&mut self, array: &ArrayTarget, index: Target) -> Target {
+        self.assert_less_than(index, array.len, log2_ceil(array.capacity() + 1));
+        self.random_access_padded(index, &array.elements)
+    }
+
+    /// Returns the `len` elements of `array` starting at `start`, as an array of the given
+    /// capacity. The slice is checked to be within the length of `array`.
+    pub fn array_slice(
+        &mut self,
+        array: &ArrayTarget,
+        start: Target,
+        len: Target,
+        capacity: usize,
+    ) -> ArrayTarget {
+        let num_bits = log2_ceil(array.capacity() + 1);
+        self.range_check(start, num_bits);
+        let end = self.add(start, len);
+        self.assert_less_or_equal(end, array.len, num_bits + 1);
+
+        let in_slice = self.lt_mask(len, capacity);
+        let elements = in_slice
+            .into_iter()
+            .enumerate()
+            .map(|(j, in_slice)| {
+                // Past the end of the slice, read the first element rather than going out of
+                // bounds.
+                let index = self.add_const(start, F::from_canonical_usize(j));
+                let index = self.mul(in_slice.target, index);
+                self.random_access_padded(index, &array.elements)
+            })
+            .collect();
+        ArrayTarget { elements, len }
+    }
+
+    /// Returns the concatenation of `a` and `b`, whose capacity is the sum of theirs.
+    pub fn array_concat(&mut self, a: &ArrayTarget, b: &ArrayTarget) -> ArrayTarget {
+        let capacity = a.capacity() + b.capacity();
+        let len = self.add(a.len, b.len);
+        let in_a = self.lt_mask(a.len, a.capacity());
+        let in_result = self.lt_mask(len, capacity);
+        let zero = self.zero();
+
+        let elements = (0..capacity)
+            .map(|i| {
+                let (a_element, in_a) = if i < a.capacity() {
+                    (a.elements[i], in_a[i].target)
+                } else {
+                    (zero, zero)
+                };
+                // Within `b`, i.e. past the end of `a` but not past the end of the result, read
+                // `b[i - a.len]`, and otherwise its first element.
+                let in_b = self.sub(in_result[i].target, in_a);
+                let index = self.constant(F::from_canonical_usize(i));
+                let index = self.sub(index, a.len);
+                let index = self.mul(in_b, index);
+                let b_element = self.random_access_padded(index, &b.elements);
+                self.select(BoolTarget::new_unsafe(in_a), a_element, b_element)
+            })
+            .collect();
+        ArrayTarget { elements, len }
+    }
+
+    /// Returns whether `needle` occurs in `haystack` at `offset`. Fails if `offset` doesn't fit
+    /// in as many bits as the capacity of `haystack`.
+    pub fn array_contains_at(
+        &mut self,
+        haystack: &ArrayTarget,
+        needle: &ArrayTarget,
+        offset: Target,
+    ) -> BoolTarget {
+        let haystack_bits = log2_ceil(haystack.capacity() + 1);
+        let needle_bits = log2_ceil(needle.capacity() + 1);
+        self.range_check(offset, haystack_bits);
+        let end = self.add(offset, needle.len);
+        let in_bounds =
+            self.is_less_or_equal(end, haystack.len, haystack_bits.max(needle_bits) + 1);
+
+        let in_needle = self.lt_mask(needle.len, needle.capacity());
+        let mut result = in_bounds;
+        for (j, in_needle) in in_needle.into_iter().enumerate() {
+            let in_range = self.and(in_needle, in_bounds);
+            let index = self.add_const(offset, F::from_canonical_usize(j));
+            let index = self.mul(in_range.target, index);
+            let element = self.random_access_padded(index, &haystack.elements);
+            let equal = self.is_equal(element, needle.elements[j]);
+            let not_in_needle = self.not(in_needle);
+            let matches = self.or(not_in_needle, equal);
+            result = self.and(result, matches);
+        }
+        result
+    }
+
+    /// Applies the SHA-2 padding for 64-byte blocks: appends a `0x80` byte, then zeros, then the
+    /// bit length of `bytes` as a big-endian 64-bit integer, so that the length is a multiple of
+    /// 64.
+    pub fn bytes_pad_sha256(&mut self, bytes: &BytesTarget) -> BytesTarget {
+        let num_bits = log2_ceil(bytes.capacity() + 1);
+        let false_target = self._false();
+        let mut bit_len = vec![false_target; 3];
+        bit_len.extend(self.split_le(bytes.array.len, num_bits));
+        bit_len.resize(64, false_target);
+        let bit_len_bytes: Vec<_> = bit_len
+            .chunks(8)
+            .rev()
+            .map(|bits| self.le_sum(bits.iter()))
+            .collect();
+
+        let (padded, _) = self.pad_dynamic(
+            &bytes.array,
+            64,
+            9,
+            F::from_canonical_u8(0x80),
+            &bit_len_bytes,
+        );
+        BytesTarget::new_unsafe(padded)
+    }
+
+    /// Applies Keccak's `pad10*1` padding for blocks of `rate` bytes: appends a `0x01` byte, then
+    /// zeros, and sets the high bit of the last byte, so that the length is a multiple of `rate`.
+    pub fn bytes_pad101(&mut self, bytes: &BytesTarget, rate: usize) -> BytesTarget {
+        let last = self.constant(F::from_canonical_u8(0x80));
+        let (padded, _) = self.pad_dynamic(&bytes.array, rate, 1, F::ONE, &[last]);
+        BytesTarget::new_unsafe(padded)
+    }
+
+    /// Hashes the first `len` elements of `array`, like `hash_pad` does natively: the elements are
+    /// padded with the `pad10*1` rule, then absorbed by the sponge. All blocks up to the capacity
+    /// of `array` are permuted, and the output is read from the state after the last block in use.
+    pub fn array_hash_pad<H: AlgebraicHasher<F>>(&mut self, array: &ArrayTarget) -> HashOutTarget {
+        let rate = H::AlgebraicPermutation::RATE;
+        let one = self.one();
+        let (padded, is_last_block) = self.pad_dynamic(array, rate, 2, F::ONE, &[one]);
+
+        let zero = self.zero();
+        let mut state = H::AlgebraicPermutation::new(repeat(zero));
+        let mut result = HashOutTarget::from([zero; NUM_HASH_OUT_ELTS]);
+        for (block, is_last) in padded.elements.chunks(rate).zip(is_last_block) {
+            state.set_from_slice(block, 0);
+            state = self.permute::<H>(state);
+            let output = HashOutTarget::from_vec(state.squeeze()[..NUM_HASH_OUT_ELTS].to_vec());
+            result = self.select_variable(is_last, &output, &result);
+        }
+        result
+    }
+
+    /// Pads `array` to a multiple of `block_len`, with at least `min_padding` elements: `marker`
+    /// is written right after the last element, and `suffix` is added to the end of the last
+    /// block. Returns the padded array, and whether each block is the last one in use.
+    fn pad_dynamic(
+        &mut self,
+        array: &ArrayTarget,
+        block_len: usize,
+        min_padding: usize,
+        marker: F,
+        suffix: &[Target],
+    ) -> (ArrayTarget, Vec<BoolTarget>) {
+        assert!(suffix.len() <= min_padding && min_padding <= block_len);
+        let capacity = array.capacity();
+        let num_blocks = |len: usize| ceil_div_usize(len + min_padding, block_len);
+        let max_blocks = num_blocks(capacity);
+
+        let is_len = self.len_indicators(array.len, capacity);
+        let in_array = self.lt_mask_from_indicators(&is_len);
+        let is_last_block: Vec<_> = (1..=max_blocks)
+            .map(|k| {
+                let lens = (0..=capacity).filter(|&len| num_blocks(len) == k);
+                let is_last = self.add_many(lens.map(|len| is_len[len].target));
+                BoolTarget::new_unsafe(is_last)
+            })
+            .collect();
+        let block_counts: Vec<_> = is_last_block
+            .iter()
+            .enumerate()
+            .map(|(k, is_last)| self.mul_const(F::from_canonical_usize(k + 1), is_last.target))
+            .collect();
+        let num_blocks = self.add_many(block_counts);
+        let len = self.mul_const(F::from_canonical_usize(block_len), num_blocks);
+
+        let elements = (0..max_blocks * block_len)
+            .map(|i| {
+                let mut terms = Vec::new();
+                if i < capacity {
+                    terms.push(self.mul(in_array[i].target, array.elements[i]));
+                }
+                if i <= capacity {
+                    terms.push(self.mul_const(marker, is_len[i].target));
+                }
+                let (block, offset) = (i / block_len, i % block_len);
+                if let Some(j) = (offset + suffix.len()).checked_sub(block_len) {
+                    terms.push(self.mul(is_last_block[block].target, suffix[j]));
+                }
+                self.add_many(terms)
+            })
+            .collect();
+        (ArrayTarget { elements, len }, is_last_block)
+    }
+
+    /// Returns `[len == i]` for each `i` in `0..=capacity`, checking that `len <= capacity`.
+    fn len_indicators(&mut self, len: Target, capacity: usize) -> Vec<BoolTarget> {
+        let is_len: Vec<_> = (0..=capacity)
+            .map(|i| {
+                let i = self.constant(F::from_canonical_usize(i));
+                self.is_equal(len, i)
+            })
+            .collect();
+        let sum = self.add_many(is_len.iter().map(|b| b.target));
+        let one = self.one();
+        self.connect(sum, one);
+        is_len
+    }
+
+    /// Returns `[i < len]` for each `i` in `0..capacity`, checking that `len <= capacity`.
+    fn lt_mask(&mut self, len: Target, capacity: usize) -> Vec<BoolTarget> {
+        let is_len = self.len_indicators(len, capacity);
+        self.lt_mask_from_indicators(&is_len)
+    }
+
+    /// Returns `[i < len]` for each `i` in `0..capacity`, given the indicators `[len == i]`.
+    fn lt_mask_from_indicators(&mut self, is_len: &[BoolTarget]) -> Vec<BoolTarget> {
+        // Exactly one indicator is set, so any sum of them is boolean.
+        let capacity = is_len.len() - 1;
+        let mut mask = vec![is_len[capacity]; capacity];
+        for i in (0..capacity.saturating_sub(1)).rev() {
+            mask[i] = BoolTarget::new_unsafe(self.add(mask[i + 1].target, is_len[i + 1].target));
+        }
+        mask
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::field::types::{Field, PrimeField64, Sample};
+    use crate::hash::poseidon::PoseidonHash;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, Hasher, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn register_array(builder: &mut CircuitBuilder<F, D>, array: &ArrayTarget) {
+        builder.register_public_input(array.len);
+        builder.register_public_inputs(&array.elements);
+    }
+
+    /// Reads an array registered with `register_array` from the public inputs.
+    fn read_array<'a>(public_inputs: &mut impl Iterator<Item = &'a F>, capacity: usize) -> Vec<F> {
+        let len = public_inputs.next().unwrap().to_canonical_u64() as usize;
+        let elements: Vec<_> = public_inputs.take(capacity).copied().collect();
+        elements[..len].to_vec()
+    }
+
+    #[test]
+    fn test_array_ops() -> Result<()> {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let a = builder.add_virtual_array_target(70);
+        let b = builder.add_virtual_array_target(10);
+        let needle = builder.add_virtual_array_target(4);
+        let start = builder.add_virtual_target();
+        let slice_len = builder.add_virtual_target();
+        let index = builder.add_virtual_target();
+        let offset = builder.add_virtual_target();
+
+        let concat = builder.array_concat(&a, &b);
+        let slice = builder.array_slice(&concat, start, slice_len, 6);
+        let element = builder.array_index(&b, index);
+        let contains = builder.array_contains_at(&concat, &needle, offset);
+        register_array(&mut builder, &concat);
+        register_array(&mut builder, &slice);
+        builder.register_public_input(element);
+        builder.register_public_input(contains.target);
+        let data = builder.build::<C>();
+
+        let mut rng = OsRng;
+        for (a_len, b_len, start_value, slice_len_value) in
+            [(0, 3, 1, 2), (70, 10, 75, 5), (5, 0, 0, 5), (40, 7, 38, 6)]
+        {
+            let a_values = F::rand_vec(a_len);
+            let b_values = F::rand_vec(b_len);
+            let concat_values = [a_values.clone(), b_values.clone()].concat();
+            let index_value = rng.gen_range(0..b_len.max(1));
+            let offset_value = rng.gen_range(0..concat_values.len().max(1));
+            let needle_values = concat_values[offset_value..]
+                .iter()
+                .take(3)
+                .copied()
+                .collect::<Vec<_>>();
+
+            for (offset_value, expected) in [(offset_value, true), (offset_value + 1, false)] {
+                let mut pw = PartialWitness::new();
+                pw.set_array_target(&a, &a_values);
+                pw.set_array_target(&b, &b_values);
+                pw.set_array_target(&needle, &needle_values);
+                pw.set_target(start, F::from_canonical_usize(start_value));
+                pw.set_target(slice_len, F::from_canonical_usize(slice_len_value));
+                pw.set_target(index, F::from_canonical_usize(index_value));
+                pw.set_target(offset, F::from_canonical_usize(offset_value));
+                if b_len == 0 {
+                    // Indexing into an empty array isn't satisfiable.
+                    assert!(data.try_prove(pw).is_err());
+                    continue;
+                }
+                let proof = data.prove(pw)?;
+
+                let mut public_inputs = proof.public_inputs.iter();
+                assert_eq!(read_array(&mut public_inputs, 80), concat_values);
+                assert_eq!(
+                    read_array(&mut public_inputs, 6),
+                    concat_values[start_value..start_value + slice_len_value]
+                );
+                assert_eq!(*public_inputs.next().unwrap(), b_values[index_value]);
+                // Shifting the needle makes a match overwhelmingly unlikely.
+                assert_eq!(*public_inputs.next().unwrap(), F::from_bool(expected));
+                data.verify(proof)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_out_of_bounds_slice() {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let a = builder.add_virtual_array_target(8);
+        let start = builder.constant(F::from_canonical_usize(3));
+        let len = builder.constant(F::from_canonical_usize(4));
+        builder.array_slice(&a, start, len, 4);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_array_target(&a, &F::rand_vec(6));
+        assert!(data.try_prove(pw).is_err());
+    }
+
+    fn sha256_padding(message: &[u8]) -> Vec<u8> {
+        let mut padded = message.to_vec();
+        padded.push(0x80);
+        while padded.len() % 64 != 56 {
+            padded.push(0);
+        }
+        padded.extend((8 * message.len() as u64).to_be_bytes());
+        padded
+    }
+
+    fn keccak_padding(message: &[u8], rate: usize) -> Vec<u8> {
+        let mut padded = message.to_vec();
+        padded.push(0x01);
+        while padded.len() % rate != 0 {
+            padded.push(0);
+        }
+        *padded.last_mut().unwrap() |= 0x80;
+        padded
+    }
+
+    #[test]
+    fn test_padding() -> Result<()> {
+        const CAPACITY: usize = 140;
+        const RATE: usize = 136;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let bytes = builder.add_virtual_bytes_target(CAPACITY);
+        let sha256_padded = builder.bytes_pad_sha256(&bytes);
+        let keccak_padded = builder.bytes_pad101(&bytes, RATE);
+        let hash = builder.array_hash_pad::<PoseidonHash>(&bytes.array);
+        register_array(&mut builder, &sha256_padded.array);
+        register_array(&mut builder, &keccak_padded.array);
+        builder.register_public_inputs(&hash.elements);
+        let data = builder.build::<C>();
+
+        let mut rng = OsRng;
+        for len in [0, 1, 55, 56, 64, 119, 120, 135, 136, CAPACITY] {
+            let message: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            let mut pw = PartialWitness::new();
+            pw.set_bytes_target(&bytes, &message);
+            let proof = data.prove(pw)?;
+
+            let to_field = |bytes: Vec<u8>| -> Vec<F> {
+                bytes.into_iter().map(F::from_canonical_u8).collect()
+            };
+            let mut public_inputs = proof.public_inputs.iter();
+            let sha256_capacity = sha256_padded.capacity();
+            assert_eq!(
+                read_array(&mut public_inputs, sha256_capacity),
+                to_field(sha256_padding(&message))
+            );
+            assert_eq!(
+                read_array(&mut public_inputs, keccak_padded.capacity()),
+                to_field(keccak_padding(&message, RATE))
+            );
+            let expected_hash = PoseidonHash::hash_pad(&to_field(message));
+            assert_eq!(
+                public_inputs.copied().collect::<Vec<_>>(),
+                expected_hash.elements
+            );
+            data.verify(proof)?;
+        }
+        Ok(())
+    }
+}
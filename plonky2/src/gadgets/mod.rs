@@ -4,6 +4,7 @@
 
 pub mod arithmetic;
 pub mod arithmetic_extension;
+pub mod dynamic_array;
 pub mod hash;
 pub mod interpolation;
 pub mod lookup;
@@ -6,6 +6,7 @@ use crate::gates::random_access::RandomAccessGate;
 use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::Target;
+use crate::iop::variable::CircuitVariable;
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::VerifierCircuitTarget;
 use crate::util::log2_strict;
@@ -39,6 +40,48 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         claimed_element
     }
 
+    /// Like `random_access`, but for a vector of any length, which is padded with zeros to a power
+    /// of two. Vectors too large for a single `RandomAccessGate` are handled by first accessing
+    /// each chunk with the low bits of `access_index`, then the results with its high bits.
+    pub fn random_access_padded(&mut self, access_index: Target, v: &[Target]) -> Target {
+        if v.is_empty() {
+            self.assert_zero(access_index);
+            return self.zero();
+        }
+        let zero = self.zero();
+        let mut v = v.to_vec();
+        v.resize(v.len().next_power_of_two(), zero);
+
+        let bits = log2_strict(v.len());
+        if bits == 0 {
+            // `random_access` doesn't constrain the index of a single element.
+            self.assert_zero(access_index);
+            return v[0];
+        }
+        let max_bits = self.max_random_access_bits();
+        if bits <= max_bits {
+            return self.random_access(access_index, v);
+        }
+        let (low, high) = self.split_low_high(access_index, max_bits, bits);
+        let chunk_results = v
+            .chunks(1 << max_bits)
+            .map(|chunk| self.random_access(low, chunk.to_vec()))
+            .collect::<Vec<_>>();
+        self.random_access_padded(high, &chunk_results)
+    }
+
+    /// The largest `bits` such that a `RandomAccessGate` over `2^bits` elements fits in a row.
+    fn max_random_access_bits(&self) -> usize {
+        let fits = |bits: usize| {
+            RandomAccessGate::<F, D>::new_from_config(&self.config, bits).num_copies > 0
+        };
+        let mut bits = 0;
+        while fits(bits + 1) {
+            bits += 1;
+        }
+        bits
+    }
+
     /// Like `random_access`, but with `ExtensionTarget`s rather than simple `Target`s.
     pub fn random_access_extension(
         &mut self,
@@ -67,6 +110,19 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         selected.into()
     }
 
+    /// Like `random_access`, but with any `CircuitVariable` rather than simple `Target`s.
+    pub fn random_access_variable<V: CircuitVariable>(
+        &mut self,
+        access_index: Target,
+        v: &[V],
+    ) -> V {
+        let targets: Vec<_> = v.iter().map(V::targets).collect();
+        let selected: Vec<_> = (0..V::num_targets())
+            .map(|i| self.random_access(access_index, targets.iter().map(|t| t[i]).collect()))
+            .collect();
+        V::from_targets(&selected)
+    }
+
     /// Like `random_access`, but with `MerkleCapTarget`s rather than simple `Target`s.
     pub fn random_access_merkle_cap(
         &mut self,
@@ -133,7 +189,7 @@ mod tests {
         let data = builder.build::<C>();
         let proof = data.prove(pw)?;
 
-        verify(proof, &data.verifier_only, &data.common)
+        Ok(verify(proof, &data.verifier_only, &data.common)?)
     }
 
     #[test]
@@ -50,6 +50,31 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         (low, high)
     }
 
+    /// Checks that `x <= y`, where `y` is assumed to be less than `2^num_bits`. `x` is
+    /// range-checked to `num_bits` bits as well.
+    pub fn assert_less_or_equal(&mut self, x: Target, y: Target, num_bits: usize) {
+        self.range_check(x, num_bits);
+        let diff = self.sub(y, x);
+        self.range_check(diff, num_bits);
+    }
+
+    /// Checks that `x < y`, where `y` is assumed to be less than `2^num_bits`. `x` is
+    /// range-checked to `num_bits` bits as well.
+    pub fn assert_less_than(&mut self, x: Target, y: Target, num_bits: usize) {
+        self.range_check(x, num_bits);
+        let diff = self.sub(y, x);
+        let diff = self.add_const(diff, F::NEG_ONE);
+        self.range_check(diff, num_bits);
+    }
+
+    /// Returns whether `x <= y`, where both are assumed to be less than `2^num_bits`.
+    pub fn is_less_or_equal(&mut self, x: Target, y: Target, num_bits: usize) -> BoolTarget {
+        // `y - x + 2^num_bits` is in `[1, 2^(num_bits + 1))`, and its top bit is set iff `x <= y`.
+        let diff = self.sub(y, x);
+        let shifted = self.add_const(diff, F::from_canonical_u64(1 << num_bits));
+        self.split_le(shifted, num_bits + 1)[num_bits]
+    }
+
     pub fn assert_bool(&mut self, b: BoolTarget) {
         let z = self.mul_sub(b.target, b.target, b.target);
         let zero = self.zero();
@@ -1,7 +1,13 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use itertools::zip_eq;
+
 use crate::field::extension::Extendable;
 use crate::hash::hash_types::RichField;
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::{BoolTarget, Target};
+use crate::iop::variable::CircuitVariable;
 use crate::plonk::circuit_builder::CircuitBuilder;
 
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
@@ -34,6 +40,14 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let tmp = self.mul_sub(b.target, y, y);
         self.mul_sub(b.target, x, tmp)
     }
+
+    /// Like `select`, but with any `CircuitVariable` rather than a simple `Target`.
+    pub fn select_variable<V: CircuitVariable>(&mut self, b: BoolTarget, x: &V, y: &V) -> V {
+        let selected: Vec<_> = zip_eq(x.targets(), y.targets())
+            .map(|(x, y)| self.select(b, x, y))
+            .collect();
+        V::from_targets(&selected)
+    }
 }
 
 #[cfg(test)]
@@ -75,6 +89,6 @@ mod tests {
         let data = builder.build::<C>();
         let proof = data.prove(pw)?;
 
-        verify(proof, &data.verifier_only, &data.common)
+        Ok(verify(proof, &data.verifier_only, &data.common)?)
     }
 }
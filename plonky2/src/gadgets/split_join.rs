@@ -77,7 +77,8 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Spl
     }
 
     fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
-        let mut integer_value = witness.get_target(self.integer).to_canonical_u64();
+        let value = witness.get_target(self.integer).to_canonical_u64();
+        let mut integer_value = value;
 
         for &b in &self.bits {
             let b_value = integer_value & 1;
@@ -85,10 +86,12 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Spl
             integer_value >>= 1;
         }
 
-        debug_assert_eq!(
-            integer_value, 0,
-            "Integer too large to fit in given number of bits"
-        );
+        // If the integer is too large to fit in the bits, write back the value they represent
+        // instead, so that witness generation fails with a conflict rather than a panic.
+        if integer_value != 0 {
+            let represented = value - (integer_value << self.bits.len());
+            out_buffer.set_target(self.integer, F::from_canonical_u64(represented));
+        }
     }
 
     fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
@@ -120,7 +123,8 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Wir
     }
 
     fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
-        let mut integer_value = witness.get_target(self.integer).to_canonical_u64();
+        let value = witness.get_target(self.integer).to_canonical_u64();
+        let mut integer_value = value;
 
         for &gate in &self.gates {
             let sum = Target::wire(gate, BaseSumGate::<2>::WIRE_SUM);
@@ -138,12 +142,12 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Wir
             out_buffer.set_target(sum, F::from_canonical_u64(truncated_value));
         }
 
-        debug_assert_eq!(
-            integer_value,
-            0,
-            "Integer too large to fit in {} many `BaseSumGate`s",
-            self.gates.len()
-        );
+        // If the integer is too large to fit in the gates, write back the value they represent
+        // instead, so that witness generation fails with a conflict rather than a panic.
+        if integer_value != 0 {
+            let represented = value - (integer_value << (self.num_limbs * self.gates.len()));
+            out_buffer.set_target(self.integer, F::from_canonical_u64(represented));
+        }
     }
 
     fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
@@ -58,8 +58,8 @@ impl<F: RichField + Extendable<D>, const D: usize, const B: usize> Gate<F, D> fo
         dst.write_usize(self.num_limbs)
     }
 
-    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let num_limbs = src.read_usize()?;
+    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_limbs = src.read_usize_at_most(common_data.config.num_wires)?;
         Ok(Self { num_limbs })
     }
 
@@ -259,12 +259,6 @@ impl<F: RichField + Extendable<D>, const B: usize, const D: usize> SimpleGenerat
         let sum_value = witness
             .get_target(Target::wire(self.row, BaseSumGate::<B>::WIRE_SUM))
             .to_canonical_u64() as usize;
-        debug_assert_eq!(
-            (0..self.num_limbs).fold(sum_value, |acc, _| acc / B),
-            0,
-            "Integer too large to fit in given number of limbs"
-        );
-
         let limbs = (BaseSumGate::<B>::START_LIMBS..BaseSumGate::<B>::START_LIMBS + self.num_limbs)
             .map(|i| Target::wire(self.row, i));
         let limbs_value = (0..self.num_limbs)
@@ -275,6 +269,18 @@ impl<F: RichField + Extendable<D>, const B: usize, const D: usize> SimpleGenerat
             })
             .collect::<Vec<_>>();
 
+        // If the integer is too large to fit in the limbs, write back the value they represent
+        // instead, so that witness generation fails with a conflict rather than a panic.
+        if (0..self.num_limbs).fold(sum_value, |acc, _| acc / B) != 0 {
+            let represented = limbs_value.iter().rev().fold(F::ZERO, |acc, &limb| {
+                acc * F::from_canonical_usize(B) + limb
+            });
+            out_buffer.set_target(
+                Target::wire(self.row, BaseSumGate::<B>::WIRE_SUM),
+                represented,
+            );
+        }
+
         for (b, b_value) in limbs.zip(limbs_value) {
             out_buffer.set_target(b, b_value);
         }
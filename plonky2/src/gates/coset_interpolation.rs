@@ -23,7 +23,9 @@ use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CommonCircuitData;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
-use crate::util::serialization::{Buffer, IoResult, Read, Write};
+use crate::plonk::verifier_codegen::solidity_u64_array;
+use crate::util::log2_ceil;
+use crate::util::serialization::{Buffer, IoError, IoResult, Read, Write};
 
 /// One of the instantiations of `InterpolationGate`: allows constraints of variable
 /// degree, up to `1<<subgroup_bits`.
@@ -183,10 +185,13 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for CosetInterpola
         dst.write_field_vec(&self.barycentric_weights)
     }
 
-    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let subgroup_bits = src.read_usize()?;
-        let degree = src.read_usize()?;
+    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let subgroup_bits = src.read_usize_at_most(log2_ceil(common_data.config.num_wires))?;
+        let degree = src.read_usize_at_most(1 << subgroup_bits)?;
         let length = src.read_usize()?;
+        if subgroup_bits == 0 || degree < 2 || length != 1 << subgroup_bits {
+            return Err(IoError);
+        }
         let barycentric_weights: Vec<F> = src.read_field_vec(length)?;
         Ok(Self {
             subgroup_bits,
@@ -196,10 +201,215 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for CosetInterpola
         })
     }
     fn export_circom_verification_code(&self) -> String {
-        todo!()
+        let mut template_str = "template CosetInterpolation$SUBGROUP_BITSDeg$DEGREE() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  var index = 0;
+  var shifted_evaluation_point[$D][2];
+  for (var j = 0; j < $D; j++) {
+    shifted_evaluation_point[j] = wires[$START_SHIFTED_EVALUATION_POINT + j];
+  }
+  for (var j = 0; j < $D; j++) {
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(wires[$START_EVALUATION_POINT + j], GlExtMul()(shifted_evaluation_point[j], wires[0])));
+    index++;
+  }
+
+  // Interpolates point by point. The running evaluation and product are replaced by the
+  // intermediate values of the wires every `$DEGREE - 1` points, starting at point `$DEGREE`.
+  var acc_eval[$D][2];
+  var acc_prod[$D][2];
+  acc_prod[0][0] = 1;
+  signal terms[$NUM_POINTS][2];
+  signal weighted_values[$NUM_POINTS][$D][2];
+  signal evals[$NUM_POINTS][$D][2];
+  signal prods[$NUM_POINTS][$D][2];
+  for (var k = 0; k < $NUM_POINTS; k++) {
+    if (k >= $DEGREE && (k - $DEGREE) % ($DEGREE - 1) == 0) {
+      var i = (k - $DEGREE) \\ ($DEGREE - 1);
+      for (var j = 0; j < $D; j++) {
+        out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(wires[$START_INTERMEDIATES + $D * i + j], acc_eval[j]));
+        index++;
+      }
+      for (var j = 0; j < $D; j++) {
+        out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(wires[$START_INTERMEDIATES + $D * ($NUM_INTERMEDIATES + i) + j], acc_prod[j]));
+        index++;
+      }
+      for (var j = 0; j < $D; j++) {
+        acc_eval[j] = wires[$START_INTERMEDIATES + $D * i + j];
+        acc_prod[j] = wires[$START_INTERMEDIATES + $D * ($NUM_INTERMEDIATES + i) + j];
+      }
+    }
+    var term[$D][2] = shifted_evaluation_point;
+    terms[k] <== GlExtSub()(shifted_evaluation_point[0], GlExt(ci_domain_$SUBGROUP_BITS(k), 0)());
+    term[0] = terms[k];
+    for (var j = 0; j < $D; j++) {
+      weighted_values[k][j] <== GlExtScalarMul()(wires[1 + k * $D + j], ci_barycentric_weight_$SUBGROUP_BITS(k));
+    }
+    evals[k] <== GlExtAlgebraMulAdd()(acc_eval, term, GlExtAlgebraMul()(weighted_values[k], acc_prod));
+    prods[k] <== GlExtAlgebraMul()(acc_prod, term);
+    acc_eval = evals[k];
+    acc_prod = prods[k];
+  }
+
+  for (var j = 0; j < $D; j++) {
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(wires[$START_EVALUATION_VALUE + j], acc_eval[j]));
+    index++;
+  }
+
+  for (var i = index; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}
+function ci_domain_$SUBGROUP_BITS(i) {
+  var domain[$NUM_POINTS] = [$DOMAIN];
+  return domain[i];
+}
+function ci_barycentric_weight_$SUBGROUP_BITS(i) {
+  var weights[$NUM_POINTS] = [$BARYCENTRIC_WEIGHTS];
+  return weights[i];
+}"
+        .to_string();
+
+        let domain = F::two_adic_subgroup(self.subgroup_bits)
+            .iter()
+            .map(|x| x.to_canonical_u64().to_string())
+            .collect::<Vec<_>>();
+        let weights = self
+            .barycentric_weights
+            .iter()
+            .map(|x| x.to_canonical_u64().to_string())
+            .collect::<Vec<_>>();
+        template_str = template_str.replace("$SUBGROUP_BITS", &self.subgroup_bits.to_string());
+        template_str = template_str.replace("$DEGREE", &self.degree.to_string());
+        template_str = template_str.replace("$NUM_POINTS", &self.num_points().to_string());
+        template_str =
+            template_str.replace("$NUM_INTERMEDIATES", &self.num_intermediates().to_string());
+        template_str = template_str.replace(
+            "$START_EVALUATION_POINT",
+            &self.start_evaluation_point().to_string(),
+        );
+        template_str = template_str.replace(
+            "$START_EVALUATION_VALUE",
+            &self.start_evaluation_value().to_string(),
+        );
+        template_str = template_str.replace(
+            "$START_INTERMEDIATES",
+            &self.start_intermediates().to_string(),
+        );
+        template_str = template_str.replace(
+            "$START_SHIFTED_EVALUATION_POINT",
+            &self.wires_shifted_evaluation_point().start.to_string(),
+        );
+        template_str = template_str.replace("$DOMAIN", &domain.join(", "));
+        template_str = template_str.replace("$BARYCENTRIC_WEIGHTS", &weights.join(", "));
+        template_str = template_str.replace("$D", &D.to_string());
+
+        template_str
     }
     fn export_solidity_verification_code(&self) -> String {
-        todo!()
+        let mut template_str = "library CosetInterpolation$SUBGROUP_BITSDeg$DEGREELib {
+    using GoldilocksFieldLib for uint64;
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+
+    // Continues the interpolation from the evaluation `acc[0]` and the product `acc[1]` with the
+    // points `start..end` of the domain.
+    function partial_interpolate(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$D] memory x, uint64[2][$D][2] memory acc, uint32 start, uint32 end) internal pure {
+        uint64[$NUM_POINTS] memory domain = [$DOMAIN];
+        uint64[$NUM_POINTS] memory weights = [$BARYCENTRIC_WEIGHTS];
+        for (uint32 i = start; i < end; i++) {
+            uint64[2][$D] memory term = GatesUtilsLib.algebra_sub(x, GatesUtilsLib.algebra_from_ext(GatesUtilsLib.field_ext_from(domain[i], 0)));
+            uint64[2][$D] memory weighted_value = GatesUtilsLib.algebra_scalar_mul(GatesUtilsLib.wires_algebra(ev.wires, 1 + i * $D), GatesUtilsLib.field_ext_from(weights[i], 0));
+            acc[0] = GatesUtilsLib.algebra_add(GatesUtilsLib.algebra_mul(acc[0], term), GatesUtilsLib.algebra_mul(weighted_value, acc[1]));
+            acc[1] = GatesUtilsLib.algebra_mul(acc[1], term);
+        }
+    }
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint32 index = 0;
+        uint64[2][$D] memory shifted_evaluation_point = GatesUtilsLib.wires_algebra(ev.wires, $START_SHIFTED_EVALUATION_POINT);
+        {
+            uint64[2][$D] memory computed_evaluation_point = GatesUtilsLib.algebra_scalar_mul(shifted_evaluation_point, ev.wires[0]);
+            for (uint32 j = 0; j < $D; j++) {
+                GatesUtilsLib.push(constraints, ev.filter, index++, ev.wires[$START_EVALUATION_POINT + j].sub(computed_evaluation_point[j]));
+            }
+        }
+
+        uint64[2][$D][2] memory acc;
+        acc[1][0] = GoldilocksExtLib.one();
+        partial_interpolate(ev, shifted_evaluation_point, acc, 0, $DEGREE);
+        for (uint32 i = 0; i < $NUM_INTERMEDIATES; i++) {
+            uint64[2][$D] memory intermediate_eval = GatesUtilsLib.wires_algebra(ev.wires, $START_INTERMEDIATES + $D * i);
+            uint64[2][$D] memory intermediate_prod = GatesUtilsLib.wires_algebra(ev.wires, $START_INTERMEDIATES + $D * ($NUM_INTERMEDIATES + i));
+            for (uint32 j = 0; j < $D; j++) {
+                GatesUtilsLib.push(constraints, ev.filter, index++, intermediate_eval[j].sub(acc[0][j]));
+            }
+            for (uint32 j = 0; j < $D; j++) {
+                GatesUtilsLib.push(constraints, ev.filter, index++, intermediate_prod[j].sub(acc[1][j]));
+            }
+            acc[0] = intermediate_eval;
+            acc[1] = intermediate_prod;
+
+            uint32 start = 1 + ($DEGREE - 1) * (i + 1);
+            uint32 end = start + $DEGREE - 1;
+            if (end > $NUM_POINTS) {
+                end = $NUM_POINTS;
+            }
+            partial_interpolate(ev, shifted_evaluation_point, acc, start, end);
+        }
+
+        for (uint32 j = 0; j < $D; j++) {
+            GatesUtilsLib.push(constraints, ev.filter, index++, ev.wires[$START_EVALUATION_VALUE + j].sub(acc[0][j]));
+        }
+    }
+}"
+        .to_string();
+
+        let domain = F::two_adic_subgroup(self.subgroup_bits)
+            .iter()
+            .map(|x| x.to_canonical_u64())
+            .collect::<Vec<_>>();
+        let weights = self
+            .barycentric_weights
+            .iter()
+            .map(|x| x.to_canonical_u64())
+            .collect::<Vec<_>>();
+        template_str = template_str.replace("$SUBGROUP_BITS", &self.subgroup_bits.to_string());
+        template_str = template_str.replace("$DEGREE", &self.degree.to_string());
+        template_str = template_str.replace("$NUM_POINTS", &self.num_points().to_string());
+        template_str =
+            template_str.replace("$NUM_INTERMEDIATES", &self.num_intermediates().to_string());
+        template_str = template_str.replace(
+            "$START_EVALUATION_POINT",
+            &self.start_evaluation_point().to_string(),
+        );
+        template_str = template_str.replace(
+            "$START_EVALUATION_VALUE",
+            &self.start_evaluation_value().to_string(),
+        );
+        template_str = template_str.replace(
+            "$START_INTERMEDIATES",
+            &self.start_intermediates().to_string(),
+        );
+        template_str = template_str.replace(
+            "$START_SHIFTED_EVALUATION_POINT",
+            &self.wires_shifted_evaluation_point().start.to_string(),
+        );
+        template_str = template_str.replace("$DOMAIN", &solidity_u64_array(&domain));
+        template_str = template_str.replace("$BARYCENTRIC_WEIGHTS", &solidity_u64_array(&weights));
+        template_str = template_str.replace("$D", &D.to_string());
+
+        template_str
     }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
@@ -84,8 +84,8 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for Exponentiation
         dst.write_usize(self.num_power_bits)
     }
 
-    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let num_power_bits = src.read_usize()?;
+    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_power_bits = src.read_usize_at_most(common_data.config.num_wires)?;
         Ok(Self::new(num_power_bits))
     }
 
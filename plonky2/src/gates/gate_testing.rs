@@ -159,5 +159,5 @@ pub fn test_eval_fns<
 
     let data = builder.build::<C>();
     let proof = data.prove(pw)?;
-    verify::<F, C, D>(proof, &data.verifier_only, &data.common)
+    Ok(verify::<F, C, D>(proof, &data.verifier_only, &data.common)?)
 }
@@ -19,13 +19,14 @@ use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CommonCircuitData;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
-use crate::util::serialization::{Buffer, IoResult};
+use crate::util::log2_ceil;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
 /// One of the instantiations of `InterpolationGate`: allows constraints of variable
 /// degree, up to `1<<subgroup_bits`.
 /// The higher degree is a tradeoff for less gates (`eval_unfiltered_recursively` for
 /// this version uses less gates than `LowDegreeInterpolationGate`).
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default)]
 pub struct HighDegreeInterpolationGate<F: RichField + Extendable<D>, const D: usize> {
     pub subgroup_bits: usize,
     _phantom: PhantomData<F>,
@@ -91,23 +92,129 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D>
     fn id(&self) -> String {
         format!("{self:?}<D={D}>")
     }
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &CommonCircuitData<F, D>,
-    ) -> IoResult<()> {
-        todo!()
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.subgroup_bits)
     }
 
-    fn deserialize(_src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        todo!()
+    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let subgroup_bits = src.read_usize_at_most(log2_ceil(common_data.config.num_wires))?;
+        Ok(Self::new(subgroup_bits))
     }
 
     fn export_circom_verification_code(&self) -> String {
-        todo!()
+        let mut template_str = "template HighDegreeInterpolation$SUBGROUP_BITS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  var index = 0;
+  var coeffs[$D][$NUM_POINTS][2];
+  for (var i = 0; i < $NUM_POINTS; i++) {
+    for (var j = 0; j < $D; j++) {
+      coeffs[j][i] = wires[1 + ($NUM_POINTS + 2 + i) * $D + j];
+    }
+  }
+  signal points[$NUM_POINTS][2];
+  signal point_powers[$NUM_POINTS][$NUM_POINTS][2];
+  signal computed_values[$NUM_POINTS][$D][2];
+  for (var i = 0; i < $NUM_POINTS; i++) {
+    points[i] <== GlExtScalarMul()(wires[0], gl_exp($GENERATOR, i));
+    point_powers[i] <== GlExtPowers($NUM_POINTS)(points[i]);
+    for (var j = 0; j < $D; j++) {
+      computed_values[i][j] <== GlExtInnerProduct($NUM_POINTS)(coeffs[j], point_powers[i]);
+      out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(wires[1 + i * $D + j], computed_values[i][j]));
+      index++;
+    }
+  }
+
+  var evaluation_point[$D][2];
+  for (var j = 0; j < $D; j++) {
+    evaluation_point[j] = wires[1 + $NUM_POINTS * $D + j];
+  }
+  signal computed_evaluation_value[$NUM_POINTS + 1][$D][2];
+  for (var j = 0; j < $D; j++) {
+    computed_evaluation_value[$NUM_POINTS][j] <== GlExt(0, 0)();
+  }
+  for (var i = $NUM_POINTS; i > 0; i--) {
+    var coeff[$D][2];
+    for (var j = 0; j < $D; j++) {
+      coeff[j] = coeffs[j][i - 1];
+    }
+    computed_evaluation_value[i - 1] <== GlExtAlgebraMulAdd()(computed_evaluation_value[i], evaluation_point, coeff);
+  }
+  for (var j = 0; j < $D; j++) {
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(wires[1 + ($NUM_POINTS + 1) * $D + j], computed_evaluation_value[0][j]));
+    index++;
+  }
+
+  for (var i = index; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+
+        template_str = template_str.replace("$NUM_POINTS", &self.num_points().to_string());
+        template_str = template_str.replace("$D", &D.to_string());
+        template_str = template_str.replace("$SUBGROUP_BITS", &self.subgroup_bits.to_string());
+        template_str = template_str.replace(
+            "$GENERATOR",
+            &F::primitive_root_of_unity(self.subgroup_bits).to_string(),
+        );
+
+        template_str
     }
     fn export_solidity_verification_code(&self) -> String {
-        todo!()
+        let mut template_str = "library HighDegreeInterpolation$SUBGROUP_BITSLib {
+    using GoldilocksFieldLib for uint64;
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+
+    function wires_coeff_start(uint32 i) internal pure returns(uint32) {
+        return 1 + ($NUM_POINTS + 2 + i) * $D;
+    }
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint32 index = 0;
+        uint64[2] memory point = ev.wires[0];
+        for (uint32 i = 0; i < $NUM_POINTS; i++) {
+            uint64[2][$D] memory computed_value;
+            for (uint32 j = $NUM_POINTS; j > 0; j--) {
+                computed_value = GatesUtilsLib.algebra_add(GatesUtilsLib.algebra_scalar_mul(computed_value, point), GatesUtilsLib.wires_algebra(ev.wires, wires_coeff_start(j - 1)));
+            }
+            for (uint32 j = 0; j < $D; j++) {
+                GatesUtilsLib.push(constraints, ev.filter, index++, ev.wires[1 + i * $D + j].sub(computed_value[j]));
+            }
+            point = point.scalar_mul($GENERATOR);
+        }
+        uint64[2][$D] memory evaluation_point = GatesUtilsLib.wires_algebra(ev.wires, 1 + $NUM_POINTS * $D);
+        uint64[2][$D] memory computed_evaluation_value;
+        for (uint32 j = $NUM_POINTS; j > 0; j--) {
+            computed_evaluation_value = GatesUtilsLib.algebra_add(GatesUtilsLib.algebra_mul(computed_evaluation_value, evaluation_point), GatesUtilsLib.wires_algebra(ev.wires, wires_coeff_start(j - 1)));
+        }
+        for (uint32 j = 0; j < $D; j++) {
+            GatesUtilsLib.push(constraints, ev.filter, index++, ev.wires[1 + ($NUM_POINTS + 1) * $D + j].sub(computed_evaluation_value[j]));
+        }
+    }
+}"
+        .to_string();
+
+        template_str = template_str.replace("$NUM_POINTS", &self.num_points().to_string());
+        template_str = template_str.replace("$D", &D.to_string());
+        template_str = template_str.replace("$SUBGROUP_BITS", &self.subgroup_bits.to_string());
+        template_str = template_str.replace(
+            "$GENERATOR",
+            &F::primitive_root_of_unity(self.subgroup_bits).to_string(),
+        );
+
+        template_str
     }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
@@ -192,7 +299,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D>
     }
 
     fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
-        let gen = InterpolationGenerator::<F, D> {
+        let gen = HighDegreeInterpolationGenerator::<F, D> {
             row,
             gate: *self,
             _phantom: PhantomData,
@@ -221,30 +328,34 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D>
     }
 }
 
-#[derive(Debug, Clone)]
-struct InterpolationGenerator<F: RichField + Extendable<D>, const D: usize> {
+#[derive(Debug, Default, Clone)]
+pub struct HighDegreeInterpolationGenerator<F: RichField + Extendable<D>, const D: usize> {
     row: usize,
     gate: HighDegreeInterpolationGate<F, D>,
     _phantom: PhantomData<F>,
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
-    for InterpolationGenerator<F, D>
+    for HighDegreeInterpolationGenerator<F, D>
 {
     fn id(&self) -> String {
-        "InterpolationGenerator".to_string()
+        "HighDegreeInterpolationGenerator".to_string()
     }
 
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &CommonCircuitData<F, D>,
-    ) -> IoResult<()> {
-        todo!()
+    fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        Gate::<F, D>::serialize(&self.gate, dst, common_data)
     }
 
-    fn deserialize(_src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        todo!()
+    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let gate =
+            <HighDegreeInterpolationGate<F, D> as Gate<F, D>>::deserialize(src, common_data)?;
+        Ok(Self {
+            row,
+            gate,
+            _phantom: PhantomData,
+        })
     }
 
     fn dependencies(&self) -> Vec<Target> {
@@ -30,7 +30,7 @@ use crate::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
     EvaluationVarsBasePacked,
 };
-use crate::util::serialization::{Buffer, IoResult, Write};
+use crate::util::serialization::{Buffer, IoError, IoResult, Read, Write};
 
 pub type LookupTable = Arc<Vec<(u16, u16)>>;
 
@@ -92,42 +92,47 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LookupTableGat
         )
     }
 
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &CommonCircuitData<F, D>,
-    ) -> IoResult<()> {
-        todo!()
-        // dst.write_usize(self.num_slots)?;
-        // dst.write_usize(self.last_lut_row)?;
-        // for (i, lut) in common_data.luts.iter().enumerate() {
-        //     if lut == &self.lut {
-        //         dst.write_usize(i)?;
-        //         return dst.write_all(&self.lut_hash);
-        //     }
-        // }
-
-        // panic!("The associated lookup table couldn't be found.")
+    fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.num_slots)?;
+        dst.write_usize(self.last_lut_row)?;
+        for (i, lut) in common_data.luts.iter().enumerate() {
+            if lut == &self.lut {
+                dst.write_usize(i)?;
+                return dst.write_all(&self.lut_hash);
+            }
+        }
+
+        panic!("The associated lookup table couldn't be found.")
     }
 
-    fn deserialize(_src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        todo!()
-        // let num_slots = src.read_usize()?;
-        // let last_lut_row = src.read_usize()?;
-        // let lut_index = src.read_usize()?;
-        // let mut lut_hash = [0u8; 32];
-        // src.read_exact(&mut lut_hash)?;
-
-        // Ok(Self {
-        //     num_slots,
-        //     lut: common_data.luts[lut_index].clone(),
-        //     lut_hash,
-        //     last_lut_row,
-        // })
+    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_slots = src.read_usize_at_most(common_data.config.num_wires)?;
+        let last_lut_row = src.read_usize()?;
+        let lut_index = src.read_usize()?;
+        let mut lut_hash = [0u8; 32];
+        src.read_exact(&mut lut_hash)?;
+
+        Ok(Self {
+            num_slots,
+            lut: common_data.luts.get(lut_index).ok_or(IoError)?.clone(),
+            lut_hash,
+            last_lut_row,
+        })
     }
 
     fn export_circom_verification_code(&self) -> String {
-        unimplemented!()
+        // No main trace constraints for the lookup table. The lookup argument itself is not exported:
+        // `generate_circom_verifier` rejects circuits using lookups.
+        "template LookupTable() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  out <== constraints;
+}"
+        .to_string()
     }
 
     fn export_solidity_verification_code(&self) -> String {
@@ -241,34 +246,33 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Loo
         }
     }
 
-    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+    fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
         dst.write_usize(self.row)?;
         dst.write_usize(self.slot_nb)?;
         dst.write_usize(self.num_slots)?;
-        // dst.write_usize(self.last_lut_row)?;
-        // for (i, lut) in common_data.luts.iter().enumerate() {
-        //     if lut == &self.lut {
-        //         return dst.write_usize(i);
-        //     }
-        // }
+        dst.write_usize(self.last_lut_row)?;
+        for (i, lut) in common_data.luts.iter().enumerate() {
+            if lut == &self.lut {
+                return dst.write_usize(i);
+            }
+        }
 
         panic!("The associated lookup table couldn't be found.")
     }
 
-    fn deserialize(_src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        todo!()
-        // let row = src.read_usize()?;
-        // let slot_nb = src.read_usize()?;
-        // let num_slots = src.read_usize()?;
-        // let last_lut_row = src.read_usize()?;
-        // let lut_index = src.read_usize()?;
-
-        // Ok(Self {
-        //     row,
-        //     lut: common_data.luts[lut_index].clone(),
-        //     slot_nb,
-        //     num_slots,
-        //     last_lut_row,
-        // })
+    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let slot_nb = src.read_usize()?;
+        let num_slots = src.read_usize()?;
+        let last_lut_row = src.read_usize()?;
+        let lut_index = src.read_usize()?;
+
+        Ok(Self {
+            row,
+            lut: common_data.luts.get(lut_index).ok_or(IoError)?.clone(),
+            slot_nb,
+            num_slots,
+            last_lut_row,
+        })
     }
 }
@@ -20,12 +20,13 @@ use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CommonCircuitData;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
-use crate::util::serialization::{Buffer, IoResult};
+use crate::util::log2_ceil;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
 /// One of the instantiations of `InterpolationGate`: all constraints are degree <= 2.
 /// The lower degree is a tradeoff for more gates (`eval_unfiltered_recursively` for
 /// this version uses more gates than `LowDegreeInterpolationGate`).
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default)]
 pub struct LowDegreeInterpolationGate<F: RichField + Extendable<D>, const D: usize> {
     pub subgroup_bits: usize,
     _phantom: PhantomData<F>,
@@ -84,16 +85,13 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LowDegreeInter
     fn id(&self) -> String {
         format!("{self:?}<D={D}>")
     }
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &CommonCircuitData<F, D>,
-    ) -> IoResult<()> {
-        todo!()
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.subgroup_bits)
     }
 
-    fn deserialize(_src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        todo!()
+    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let subgroup_bits = src.read_usize_at_most(log2_ceil(common_data.config.num_wires))?;
+        Ok(Self::new(subgroup_bits))
     }
 
     fn export_circom_verification_code(&self) -> String {
@@ -123,7 +121,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LowDegreeInter
   }}
   for (var i = 0; i < $NUM_POINTS; i++) {{
     for (var j = 0; j < $D; j++) {{
-      altered_coeffs[i][j] <== GlExtMul()(wires[ldi_wires_coeff_start(i) + j], powers_shift[i]);
+      altered_coeffs[i][j] <== GlExtMul()(wires[ldi_wires_coeff_start_$SUBGROUP_BITS(i) + j], powers_shift[i]);
     }}
   }}
   signal value[$SUBGROUP_SIZE][$D][2];
@@ -135,7 +133,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LowDegreeInter
     for (var j = $NUM_POINTS; j > 0; j--) {{
       for (var k = 0; k < $D; k++) {{
         if (j == $NUM_POINTS) acc[i][j - 1][k] <== altered_coeffs[j - 1][k];
-        else acc[i][j - 1][k] <== GlExtAdd()(GlExtMul()(acc[i][j][k], GlExt(two_adic_subgroup(i), 0)()), altered_coeffs[j - 1][k]);
+        else acc[i][j - 1][k] <== GlExtAdd()(GlExtMul()(acc[i][j][k], GlExt(two_adic_subgroup_$SUBGROUP_BITS(i), 0)()), altered_coeffs[j - 1][k]);
       }}
     }}
     for (var j = 0; j < $D; j++) {{
@@ -145,20 +143,20 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LowDegreeInter
   }}
   signal m[$NUM_POINTS - 2][2][2];
   for (var i = 1; i < $NUM_POINTS - 1; i++) {{
-    m[i - 1] <== WiresAlgebraMul(ldi_powers_evaluation_start(i), ldi_powers_evaluation_start(1))(wires);
+    m[i - 1] <== WiresAlgebraMul(ldi_powers_evaluation_start_$SUBGROUP_BITS(i), ldi_powers_evaluation_start_$SUBGROUP_BITS(1))(wires);
     for (var j = 0; j < $D; j++) {{
-      out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(m[i - 1][j], wires[ldi_powers_evaluation_start(i + 1) + j]));
+      out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(m[i - 1][j], wires[ldi_powers_evaluation_start_$SUBGROUP_BITS(i + 1) + j]));
       index++;
     }}
   }}
 
   signal acc2[$D][$NUM_POINTS][2];
   for (var i = 0; i < $D; i++) {{
-    acc2[i][0] <== wires[ldi_wires_coeff_start(0) + i];
+    acc2[i][0] <== wires[ldi_wires_coeff_start_$SUBGROUP_BITS(0) + i];
   }}
   signal m2[$NUM_POINTS - 1][2][2];
   for (var i = 1; i < $NUM_POINTS; i++) {{
-    m2[i - 1] <== WiresAlgebraMul(ldi_powers_evaluation_start(i), ldi_wires_coeff_start(i))(wires);
+    m2[i - 1] <== WiresAlgebraMul(ldi_powers_evaluation_start_$SUBGROUP_BITS(i), ldi_wires_coeff_start_$SUBGROUP_BITS(i))(wires);
     for (var j = 0; j < $D; j++) {{
       acc2[j][i] <== GlExtAdd()(acc2[j][i - 1], m2[i - 1][j]);
     }}
@@ -172,14 +170,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for LowDegreeInter
     out[i] <== constraints[i];
   }}
 }}
-function ldi_powers_evaluation_start(i) {{
+function ldi_powers_evaluation_start_$SUBGROUP_BITS(i) {{
   if (i == 1) return 1 + $NUM_POINTS * $D;
   else return 1 + $D + $D + 2 * $NUM_POINTS * $D + $NUM_POINTS - 2 + (i - 2) * $D;
 }}
-function ldi_wires_coeff_start(i) {{
+function ldi_wires_coeff_start_$SUBGROUP_BITS(i) {{
   return 1 + ($NUM_POINTS + i + 2) * $D;
 }}
-function two_adic_subgroup(i) {{
+function two_adic_subgroup_$SUBGROUP_BITS(i) {{
   var subgroup[$SUBGROUP_SIZE];
   $SET_SUBGROUP;
   return subgroup[i];
@@ -492,7 +490,7 @@ function two_adic_subgroup(i) {{
     }
 
     fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
-        let gen = InterpolationGenerator::<F, D> {
+        let gen = LowDegreeInterpolationGenerator::<F, D> {
             row,
             gate: *self,
             _phantom: PhantomData,
@@ -520,35 +518,33 @@ function two_adic_subgroup(i) {{
     }
 }
 
-#[derive(Debug, Clone)]
-struct InterpolationGenerator<F: RichField + Extendable<D>, const D: usize> {
+#[derive(Debug, Default, Clone)]
+pub struct LowDegreeInterpolationGenerator<F: RichField + Extendable<D>, const D: usize> {
     row: usize,
     gate: LowDegreeInterpolationGate<F, D>,
     _phantom: PhantomData<F>,
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
-    for InterpolationGenerator<F, D>
+    for LowDegreeInterpolationGenerator<F, D>
 {
     fn id(&self) -> String {
-        "InterpolationGenerator".to_string()
+        "LowDegreeInterpolationGenerator".to_string()
     }
 
-    fn serialize(
-        &self,
-        _dst: &mut Vec<u8>,
-        _common_data: &CommonCircuitData<F, D>,
-    ) -> IoResult<()> {
-        todo!()
-        // dst.write_usize(self.row)?;
-        // self.gate.serialize(dst, _common_data)
+    fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        Gate::<F, D>::serialize(&self.gate, dst, common_data)
     }
 
-    fn deserialize(_src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        todo!()
-        // let row = src.read_usize()?;
-        // let gate = CosetInterpolationGate::deserialize(src, _common_data)?;
-        // Ok(Self::new(row, gate))
+    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let gate = <LowDegreeInterpolationGate<F, D> as Gate<F, D>>::deserialize(src, common_data)?;
+        Ok(Self {
+            row,
+            gate,
+            _phantom: PhantomData,
+        })
     }
 
     fn dependencies(&self) -> Vec<Target> {
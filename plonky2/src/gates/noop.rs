@@ -1,5 +1,8 @@
 #[cfg(not(feature = "std"))]
-use alloc::{string::String, vec::Vec};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 
 use crate::field::extension::Extendable;
 use crate::gates::gate::Gate;
@@ -33,11 +36,30 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for NoopGate {
     }
 
     fn export_circom_verification_code(&self) -> String {
-        unimplemented!()
+        "template Noop() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  out <== constraints;
+}"
+        .to_string()
     }
 
     fn export_solidity_verification_code(&self) -> String {
-        unimplemented!()
+        "library NoopLib {
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+
+    function eval(GatesUtilsLib.EvaluationVars memory, uint64[2][$NUM_GATE_CONSTRAINTS] memory) internal pure {
+    }
+}"
+        .to_string()
     }
 
     fn eval_unfiltered(&self, _vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
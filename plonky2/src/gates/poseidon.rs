@@ -23,6 +23,7 @@ use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CommonCircuitData;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use crate::plonk::verifier_codegen::solidity_u64_array;
 use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
 /// Evaluates a full Poseidon permutation with 12 state elements.
@@ -266,7 +267,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for PoseidonGate<F
     index++;
   }}
 
-  for (var i = index + 1; i < NUM_GATE_CONSTRAINTS(); i++) {{
+  for (var i = index; i < NUM_GATE_CONSTRAINTS(); i++) {{
     out[i] <== constraints[i];
   }}
 }}
@@ -294,16 +295,6 @@ function FAST_PARTIAL_FIRST_ROUND_CONSTANT(i) {{
   var value[12];
   $SET_FAST_PARTIAL_FIRST_ROUND_CONSTANT;
   return value[i];
-}}
-function MDS_MATRIX_CIRC(i) {{
-  var mds[12];
-  $SET_MDS_MATRIX_CIRC;
-  return mds[i];
-}}
-function MDS_MATRIX_DIAG(i) {{
-  var mds[12];
-  $SET_MDS_MATRIX_DIAG;
-  return mds[i];
 }}"
         ).to_string();
         template_str = template_str.replace("$WIRE_SWAP", &*Self::WIRE_SWAP.to_string());
@@ -332,26 +323,6 @@ function MDS_MATRIX_DIAG(i) {{
             &*partial_const_str,
         );
 
-        let mut circ_str = "".to_owned();
-        for i in 0..12 {
-            circ_str += &*("  mds[".to_owned()
-                + &*i.to_string()
-                + "] = "
-                + &*<F as Poseidon>::MDS_MATRIX_CIRC[i].to_string()
-                + ";\n");
-        }
-        template_str = template_str.replace("  $SET_MDS_MATRIX_CIRC;\n", &*circ_str);
-
-        let mut diag_str = "".to_owned();
-        for i in 0..12 {
-            diag_str += &*("  mds[".to_owned()
-                + &*i.to_string()
-                + "] = "
-                + &*<F as Poseidon>::MDS_MATRIX_DIAG[i].to_string()
-                + ";\n");
-        }
-        template_str = template_str.replace("  $SET_MDS_MATRIX_DIAG;\n", &*diag_str);
-
         let mut first_round_const_str = "".to_owned();
         for i in 0..12 {
             first_round_const_str += &*("  value[".to_owned()
@@ -412,7 +383,195 @@ function MDS_MATRIX_DIAG(i) {{
         template_str
     }
     fn export_solidity_verification_code(&self) -> String {
-        todo!()
+        assert_eq!(SPONGE_WIDTH, 12);
+        let mut template_str = "library Poseidon12Lib {
+    using GoldilocksFieldLib for uint64;
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+
+    function sbox(uint64[2] memory x) internal pure returns (uint64[2] memory) {
+        uint64[2] memory x2 = x.square();
+        return x.mul(x2).mul(x2.square());
+    }
+
+    function mds_layer(uint64[2][12] memory state) internal pure returns (uint64[2][12] memory result) {
+        uint64[12] memory mds_matrix_circ = [$MDS_MATRIX_CIRC];
+        uint64[12] memory mds_matrix_diag = [$MDS_MATRIX_DIAG];
+        for (uint32 r = 0; r < 12; r++) {
+            result[r] = state[r].scalar_mul(mds_matrix_diag[r]);
+            for (uint32 i = 0; i < 12; i++) {
+                result[r] = result[r].add(state[(i + r) % 12].scalar_mul(mds_matrix_circ[i]));
+            }
+        }
+    }
+
+    function mds_partial_layer_init(uint64[2][12] memory state) internal pure returns (uint64[2][12] memory result) {
+        uint64[121] memory initial_matrix = [$FAST_PARTIAL_ROUND_INITIAL_MATRIX];
+        result[0] = state[0];
+        for (uint32 r = 1; r < 12; r++) {
+            for (uint32 c = 1; c < 12; c++) {
+                result[c] = result[c].add(state[r].scalar_mul(initial_matrix[(r - 1) * 11 + c - 1]));
+            }
+        }
+    }
+
+    function mds_partial_layer_fast(uint64[2][12] memory state, uint64[$NUM_FAST_PARTIAL_ROUND_VS] memory vs, uint64[$NUM_FAST_PARTIAL_ROUND_VS] memory w_hats, uint32 r) internal pure {
+        uint64[2] memory d = state[0].scalar_mul($MDS_MATRIX_0_0);
+        for (uint32 i = 1; i < 12; i++) {
+            d = d.add(state[i].scalar_mul(w_hats[r * 11 + i - 1]));
+        }
+        for (uint32 i = 1; i < 12; i++) {
+            state[i] = state[i].add(state[0].scalar_mul(vs[r * 11 + i - 1]));
+        }
+        state[0] = d;
+    }
+
+    function partial_rounds(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints, uint64[2][12] memory state, uint32 index) internal pure returns (uint64[2][12] memory) {
+        {
+            uint64[12] memory first_round_constant = [$FAST_PARTIAL_FIRST_ROUND_CONSTANT];
+            for (uint32 i = 0; i < 12; i++) {
+                state[i] = state[i].add(GatesUtilsLib.field_ext_from(first_round_constant[i], 0));
+            }
+        }
+        state = mds_partial_layer_init(state);
+        uint64[$N_PARTIAL_ROUNDS] memory round_constants = [$FAST_PARTIAL_ROUND_CONSTANTS];
+        uint64[$NUM_FAST_PARTIAL_ROUND_VS] memory vs = [$FAST_PARTIAL_ROUND_VS];
+        uint64[$NUM_FAST_PARTIAL_ROUND_VS] memory w_hats = [$FAST_PARTIAL_ROUND_W_HATS];
+        for (uint32 r = 0; r < $N_PARTIAL_ROUNDS; r++) {
+            uint64[2] memory sbox_in = ev.wires[$START_PARTIAL + r];
+            GatesUtilsLib.push(constraints, ev.filter, index + r, state[0].sub(sbox_in));
+            state[0] = sbox(sbox_in);
+            if (r < $N_PARTIAL_ROUNDS - 1) {
+                state[0] = state[0].add(GatesUtilsLib.field_ext_from(round_constants[r], 0));
+            }
+            mds_partial_layer_fast(state, vs, w_hats, r);
+        }
+        return state;
+    }
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint32 index = 0;
+        uint64[2] memory swap = ev.wires[$WIRE_SWAP];
+        GatesUtilsLib.push(constraints, ev.filter, index++, swap.mul(swap.sub(GoldilocksExtLib.one())));
+
+        uint64[2][12] memory state;
+        for (uint32 i = 0; i < 4; i++) {
+            uint64[2] memory delta_i = ev.wires[$START_DELTA + i];
+            GatesUtilsLib.push(constraints, ev.filter, index++, swap.mul(ev.wires[i + 4].sub(ev.wires[i])).sub(delta_i));
+            state[i] = ev.wires[i].add(delta_i);
+            state[i + 4] = ev.wires[i + 4].sub(delta_i);
+        }
+        for (uint32 i = 8; i < 12; i++) {
+            state[i] = ev.wires[i];
+        }
+
+        uint64[$NUM_FULL_ROUND_CONSTANTS] memory full_round_constants = [$FULL_ROUND_CONSTANTS];
+        // First set of full rounds.
+        for (uint32 r = 0; r < $HALF_N_FULL_ROUNDS; r++) {
+            for (uint32 i = 0; i < 12; i++) {
+                state[i] = state[i].add(GatesUtilsLib.field_ext_from(full_round_constants[r * 12 + i], 0));
+                if (r != 0) {
+                    uint64[2] memory sbox_in = ev.wires[$START_FULL_0 + 12 * (r - 1) + i];
+                    GatesUtilsLib.push(constraints, ev.filter, index++, state[i].sub(sbox_in));
+                    state[i] = sbox_in;
+                }
+                state[i] = sbox(state[i]);
+            }
+            state = mds_layer(state);
+        }
+
+        // Partial rounds.
+        state = partial_rounds(ev, constraints, state, index);
+        index += $N_PARTIAL_ROUNDS;
+
+        // Second set of full rounds.
+        for (uint32 r = 0; r < $HALF_N_FULL_ROUNDS; r++) {
+            for (uint32 i = 0; i < 12; i++) {
+                state[i] = state[i].add(GatesUtilsLib.field_ext_from(full_round_constants[($HALF_N_FULL_ROUNDS + r) * 12 + i], 0));
+                uint64[2] memory sbox_in = ev.wires[$START_FULL_1 + 12 * r + i];
+                GatesUtilsLib.push(constraints, ev.filter, index++, state[i].sub(sbox_in));
+                state[i] = sbox(sbox_in);
+            }
+            state = mds_layer(state);
+        }
+
+        for (uint32 i = 0; i < 12; i++) {
+            GatesUtilsLib.push(constraints, ev.filter, index++, state[i].sub(ev.wires[12 + i]));
+        }
+    }
+}"
+        .to_string();
+
+        template_str = template_str.replace("$WIRE_SWAP", &Self::WIRE_SWAP.to_string());
+        template_str = template_str.replace("$START_DELTA", &Self::START_DELTA.to_string());
+        template_str = template_str.replace("$START_FULL_0", &Self::START_FULL_0.to_string());
+        template_str = template_str.replace("$START_PARTIAL", &Self::START_PARTIAL.to_string());
+        template_str = template_str.replace("$START_FULL_1", &Self::START_FULL_1.to_string());
+        template_str = template_str.replace(
+            "$HALF_N_FULL_ROUNDS",
+            &poseidon::HALF_N_FULL_ROUNDS.to_string(),
+        );
+        template_str =
+            template_str.replace("$N_PARTIAL_ROUNDS", &poseidon::N_PARTIAL_ROUNDS.to_string());
+
+        // The round constants of the full rounds, the partial rounds using their own constants.
+        let second_full_rounds = (poseidon::HALF_N_FULL_ROUNDS + poseidon::N_PARTIAL_ROUNDS)
+            * SPONGE_WIDTH..poseidon::N_ROUNDS * SPONGE_WIDTH;
+        let full_round_constants = [
+            &poseidon::ALL_ROUND_CONSTANTS[..poseidon::HALF_N_FULL_ROUNDS * SPONGE_WIDTH],
+            &poseidon::ALL_ROUND_CONSTANTS[second_full_rounds],
+        ]
+        .concat();
+        template_str = template_str.replace(
+            "$NUM_FULL_ROUND_CONSTANTS",
+            &full_round_constants.len().to_string(),
+        );
+        template_str = template_str.replace(
+            "$FULL_ROUND_CONSTANTS",
+            &solidity_u64_array(&full_round_constants),
+        );
+        template_str = template_str.replace(
+            "$MDS_MATRIX_CIRC",
+            &solidity_u64_array(&<F as Poseidon>::MDS_MATRIX_CIRC),
+        );
+        template_str = template_str.replace(
+            "$MDS_MATRIX_DIAG",
+            &solidity_u64_array(&<F as Poseidon>::MDS_MATRIX_DIAG),
+        );
+        template_str = template_str.replace(
+            "$MDS_MATRIX_0_0",
+            &(<F as Poseidon>::MDS_MATRIX_CIRC[0] + <F as Poseidon>::MDS_MATRIX_DIAG[0])
+                .to_string(),
+        );
+        template_str = template_str.replace(
+            "$FAST_PARTIAL_FIRST_ROUND_CONSTANT",
+            &solidity_u64_array(&<F as Poseidon>::FAST_PARTIAL_FIRST_ROUND_CONSTANT),
+        );
+        template_str = template_str.replace(
+            "$FAST_PARTIAL_ROUND_CONSTANTS",
+            &solidity_u64_array(&<F as Poseidon>::FAST_PARTIAL_ROUND_CONSTANTS),
+        );
+        template_str = template_str.replace(
+            "$FAST_PARTIAL_ROUND_INITIAL_MATRIX",
+            &solidity_u64_array(&<F as Poseidon>::FAST_PARTIAL_ROUND_INITIAL_MATRIX.concat()),
+        );
+        template_str = template_str.replace(
+            "$NUM_FAST_PARTIAL_ROUND_VS",
+            &(poseidon::N_PARTIAL_ROUNDS * (SPONGE_WIDTH - 1)).to_string(),
+        );
+        template_str = template_str.replace(
+            "$FAST_PARTIAL_ROUND_VS",
+            &solidity_u64_array(&<F as Poseidon>::FAST_PARTIAL_ROUND_VS.concat()),
+        );
+        template_str = template_str.replace(
+            "$FAST_PARTIAL_ROUND_W_HATS",
+            &solidity_u64_array(&<F as Poseidon>::FAST_PARTIAL_ROUND_W_HATS.concat()),
+        );
+
+        template_str
     }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
@@ -0,0 +1,990 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::marker::PhantomData;
+
+use crate::field::extension::Extendable;
+use crate::field::types::Field;
+use crate::gates::gate::Gate;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::hash::poseidon2::{
+    self, constant_layer_circuit, constant_layer_field, external_linear_layer_circuit,
+    external_linear_layer_field, internal_linear_layer_circuit, internal_linear_layer_field,
+    partial_constant_layer_circuit, partial_constant_layer_field, sbox_layer_circuit,
+    sbox_layer_field, sbox_monomial, sbox_monomial_circuit, SPONGE_WIDTH,
+};
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef};
+use crate::iop::target::Target;
+use crate::iop::wire::Wire;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use crate::plonk::verifier_codegen::solidity_u64_array;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// Evaluates a full Poseidon2 permutation with 12 state elements.
+///
+/// This also has some extra features to make it suitable for efficiently verifying Merkle proofs.
+/// It has a flag which can be used to swap the first four inputs with the next four, for ordering
+/// sibling digests.
+#[derive(Debug, Default)]
+pub struct Poseidon2Gate<F: RichField + Extendable<D>, const D: usize>(PhantomData<F>);
+
+impl<F: RichField + Extendable<D>, const D: usize> Poseidon2Gate<F, D> {
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    /// The wire index for the `i`th input to the permutation.
+    pub(crate) const fn wire_input(i: usize) -> usize {
+        i
+    }
+
+    /// The wire index for the `i`th output to the permutation.
+    pub(crate) const fn wire_output(i: usize) -> usize {
+        SPONGE_WIDTH + i
+    }
+
+    /// If this is set to 1, the first four inputs will be swapped with the next four inputs. This
+    /// is useful for ordering hashes in Merkle proofs. Otherwise, this should be set to 0.
+    pub(crate) const WIRE_SWAP: usize = 2 * SPONGE_WIDTH;
+
+    const START_DELTA: usize = 2 * SPONGE_WIDTH + 1;
+
+    /// A wire which stores `swap * (input[i + 4] - input[i])`; used to compute the swapped inputs.
+    const fn wire_delta(i: usize) -> usize {
+        assert!(i < 4);
+        Self::START_DELTA + i
+    }
+
+    const START_FULL_0: usize = Self::START_DELTA + 4;
+
+    /// A wire which stores the input of the `i`-th S-box of the `round`-th round of the first set
+    /// of full rounds.
+    const fn wire_full_sbox_0(round: usize, i: usize) -> usize {
+        debug_assert!(
+            round != 0,
+            "First round S-box inputs are not stored as wires"
+        );
+        debug_assert!(round < poseidon2::HALF_N_FULL_ROUNDS);
+        debug_assert!(i < SPONGE_WIDTH);
+        Self::START_FULL_0 + SPONGE_WIDTH * (round - 1) + i
+    }
+
+    const START_PARTIAL: usize =
+        Self::START_FULL_0 + SPONGE_WIDTH * (poseidon2::HALF_N_FULL_ROUNDS - 1);
+
+    /// A wire which stores the input of the S-box of the `round`-th round of the partial rounds.
+    const fn wire_partial_sbox(round: usize) -> usize {
+        debug_assert!(round < poseidon2::N_PARTIAL_ROUNDS);
+        Self::START_PARTIAL + round
+    }
+
+    const START_FULL_1: usize = Self::START_PARTIAL + poseidon2::N_PARTIAL_ROUNDS;
+
+    /// A wire which stores the input of the `i`-th S-box of the `round`-th round of the second set
+    /// of full rounds.
+    const fn wire_full_sbox_1(round: usize, i: usize) -> usize {
+        debug_assert!(round < poseidon2::HALF_N_FULL_ROUNDS);
+        debug_assert!(i < SPONGE_WIDTH);
+        Self::START_FULL_1 + SPONGE_WIDTH * round + i
+    }
+
+    /// End of wire indices, exclusive.
+    const fn end() -> usize {
+        Self::START_FULL_1 + SPONGE_WIDTH * poseidon2::HALF_N_FULL_ROUNDS
+    }
+
+    /// The round constants of the full rounds, flattened, and the constant of each partial round,
+    /// as used by the exported verifiers.
+    fn round_constants() -> (Vec<u64>, Vec<u64>) {
+        let half = poseidon2::HALF_N_FULL_ROUNDS;
+        let partial_end = half + poseidon2::N_PARTIAL_ROUNDS;
+        let full_round_constants = poseidon2::RC12[..half]
+            .iter()
+            .chain(&poseidon2::RC12[partial_end..partial_end + half])
+            .flatten()
+            .copied()
+            .collect();
+        let partial_round_constants = poseidon2::RC12[half..partial_end]
+            .iter()
+            .map(|round_constants| round_constants[0])
+            .collect();
+        (full_round_constants, partial_round_constants)
+    }
+
+    /// Fills in the wire layout and round counts shared by the exported verifiers.
+    fn replace_wire_placeholders(template_str: String) -> String {
+        template_str
+            .replace("$WIRE_SWAP", &Self::WIRE_SWAP.to_string())
+            .replace("$START_DELTA", &Self::START_DELTA.to_string())
+            .replace("$START_FULL_0", &Self::START_FULL_0.to_string())
+            .replace("$START_PARTIAL", &Self::START_PARTIAL.to_string())
+            .replace("$START_FULL_1", &Self::START_FULL_1.to_string())
+            .replace(
+                "$HALF_N_FULL_ROUNDS",
+                &poseidon2::HALF_N_FULL_ROUNDS.to_string(),
+            )
+            .replace(
+                "$N_PARTIAL_ROUNDS",
+                &poseidon2::N_PARTIAL_ROUNDS.to_string(),
+            )
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for Poseidon2Gate<F, D> {
+    fn id(&self) -> String {
+        format!("{self:?}<WIDTH={SPONGE_WIDTH}>")
+    }
+
+    fn serialize(
+        &self,
+        _dst: &mut Vec<u8>,
+        _common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<()> {
+        Ok(())
+    }
+
+    fn deserialize(_src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        Ok(Poseidon2Gate::new())
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        assert_eq!(SPONGE_WIDTH, 12);
+        let mut template_str = "template Poseidon2_12() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  var index = 0;
+  out[index] <== ConstraintPush()(constraints[index], filter, GlExtMul()(wires[$WIRE_SWAP], GlExtSub()(wires[$WIRE_SWAP], GlExt(1, 0)())));
+  index++;
+
+  for (var i = 0; i < 4; i++) {
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(GlExtMul()(wires[$WIRE_SWAP], GlExtSub()(wires[i + 4], wires[i])), wires[$START_DELTA + i]));
+    index++;
+  }
+
+  // The input layer, each full round and each partial round take respectively 2, 4 and 2 states.
+  signal state[2 + $HALF_N_FULL_ROUNDS * 8 + $N_PARTIAL_ROUNDS * 2][12][2];
+  var state_round = 0;
+  for (var i = 0; i < 4; i++) {
+    state[state_round][i] <== GlExtAdd()(wires[i], wires[$START_DELTA + i]);
+    state[state_round][i + 4] <== GlExtSub()(wires[i + 4], wires[$START_DELTA + i]);
+  }
+  for (var i = 8; i < 12; i++) {
+    state[state_round][i] <== wires[i];
+  }
+  state_round++;
+
+  // The initial linear layer.
+  state[state_round] <== Poseidon2ExternalLayer12()(state[state_round - 1]);
+  state_round++;
+
+  // First set of full rounds.
+  for (var r = 0; r < $HALF_N_FULL_ROUNDS; r++) {
+    for (var i = 0; i < 12; i++) {
+      state[state_round][i] <== GlExtAdd()(state[state_round - 1][i], GlExt(POSEIDON2_FULL_ROUND_CONSTANTS(12 * r + i), 0)());
+    }
+    state_round++;
+    for (var i = 0; i < 12; i++) {
+      if (r == 0) {
+        state[state_round][i] <== state[state_round - 1][i];
+      } else {
+        state[state_round][i] <== wires[$START_FULL_0 + 12 * (r - 1) + i];
+        out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(state[state_round - 1][i], state[state_round][i]));
+        index++;
+      }
+    }
+    state_round++;
+    for (var i = 0; i < 12; i++) {
+      state[state_round][i] <== GlExtExpN(3)(state[state_round - 1][i], 7);
+    }
+    state_round++;
+    state[state_round] <== Poseidon2ExternalLayer12()(state[state_round - 1]);
+    state_round++;
+  }
+
+  // Partial rounds.
+  for (var r = 0; r < $N_PARTIAL_ROUNDS; r++) {
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(GlExtAdd()(state[state_round - 1][0], GlExt(POSEIDON2_PARTIAL_ROUND_CONSTANTS(r), 0)()), wires[$START_PARTIAL + r]));
+    index++;
+    state[state_round][0] <== GlExtExpN(3)(wires[$START_PARTIAL + r], 7);
+    for (var i = 1; i < 12; i++) {
+      state[state_round][i] <== state[state_round - 1][i];
+    }
+    state_round++;
+    state[state_round] <== Poseidon2InternalLayer12()(state[state_round - 1]);
+    state_round++;
+  }
+
+  // Second set of full rounds.
+  for (var r = 0; r < $HALF_N_FULL_ROUNDS; r++) {
+    for (var i = 0; i < 12; i++) {
+      state[state_round][i] <== GlExtAdd()(state[state_round - 1][i], GlExt(POSEIDON2_FULL_ROUND_CONSTANTS(12 * ($HALF_N_FULL_ROUNDS + r) + i), 0)());
+    }
+    state_round++;
+    for (var i = 0; i < 12; i++) {
+      state[state_round][i] <== wires[$START_FULL_1 + 12 * r + i];
+      out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(state[state_round - 1][i], state[state_round][i]));
+      index++;
+    }
+    state_round++;
+    for (var i = 0; i < 12; i++) {
+      state[state_round][i] <== GlExtExpN(3)(state[state_round - 1][i], 7);
+    }
+    state_round++;
+    state[state_round] <== Poseidon2ExternalLayer12()(state[state_round - 1]);
+    state_round++;
+  }
+
+  for (var i = 0; i < 12; i++) {
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(state[state_round - 1][i], wires[12 + i]));
+    index++;
+  }
+
+  for (var i = index; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}
+template Poseidon2ExternalLayer12() {
+  signal input in[12][2];
+  signal output out[12][2];
+
+  // Apply M_4 to each consecutive four elements of the state.
+  signal t0[3][2];
+  signal t1[3][2];
+  signal t2[3][2];
+  signal t3[3][2];
+  signal t4[3][2];
+  signal t5[3][2];
+  signal m4[12][2];
+  for (var k = 0; k < 3; k++) {
+    t0[k] <== GlExtAdd()(in[4 * k], in[4 * k + 1]);
+    t1[k] <== GlExtAdd()(in[4 * k + 2], in[4 * k + 3]);
+    t2[k] <== GlExtAdd()(GlExtScalarMul()(in[4 * k + 1], 2), t1[k]);
+    t3[k] <== GlExtAdd()(GlExtScalarMul()(in[4 * k + 3], 2), t0[k]);
+    t4[k] <== GlExtAdd()(GlExtScalarMul()(t1[k], 4), t3[k]);
+    t5[k] <== GlExtAdd()(GlExtScalarMul()(t0[k], 4), t2[k]);
+    m4[4 * k] <== GlExtAdd()(t3[k], t5[k]);
+    m4[4 * k + 1] <== t5[k];
+    m4[4 * k + 2] <== GlExtAdd()(t2[k], t4[k]);
+    m4[4 * k + 3] <== t4[k];
+  }
+
+  // Apply the outer circulant matrix.
+  signal sums[4][2];
+  for (var k = 0; k < 4; k++) {
+    sums[k] <== GlExtAdd()(GlExtAdd()(m4[k], m4[k + 4]), m4[k + 8]);
+  }
+  for (var i = 0; i < 12; i++) {
+    out[i] <== GlExtAdd()(m4[i], sums[i % 4]);
+  }
+}
+template Poseidon2InternalLayer12() {
+  signal input in[12][2];
+  signal output out[12][2];
+
+  signal sum[12][2];
+  sum[0] <== in[0];
+  for (var i = 1; i < 12; i++) {
+    sum[i] <== GlExtAdd()(sum[i - 1], in[i]);
+  }
+  for (var i = 0; i < 12; i++) {
+    out[i] <== GlExtAdd()(GlExtScalarMul()(in[i], POSEIDON2_INTERNAL_DIAG(i)), sum[11]);
+  }
+}
+function POSEIDON2_FULL_ROUND_CONSTANTS(i) {
+  var value[$NUM_FULL_ROUND_CONSTANTS];
+  $SET_FULL_ROUND_CONSTANTS;
+  return value[i];
+}
+function POSEIDON2_PARTIAL_ROUND_CONSTANTS(i) {
+  var value[$N_PARTIAL_ROUNDS];
+  $SET_PARTIAL_ROUND_CONSTANTS;
+  return value[i];
+}
+function POSEIDON2_INTERNAL_DIAG(i) {
+  var value[12];
+  $SET_INTERNAL_DIAG;
+  return value[i];
+}"
+        .to_string();
+
+        let (full_round_constants, partial_round_constants) = Self::round_constants();
+        let set_values = |values: &[u64]| {
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| format!("  value[{i}] = {v};\n"))
+                .collect::<String>()
+        };
+        template_str = template_str.replace(
+            "  $SET_FULL_ROUND_CONSTANTS;\n",
+            &set_values(&full_round_constants),
+        );
+        template_str = template_str.replace(
+            "  $SET_PARTIAL_ROUND_CONSTANTS;\n",
+            &set_values(&partial_round_constants),
+        );
+        template_str = template_str.replace(
+            "  $SET_INTERNAL_DIAG;\n",
+            &set_values(&poseidon2::MATRIX_DIAG_12_GOLDILOCKS),
+        );
+        template_str = template_str.replace(
+            "$NUM_FULL_ROUND_CONSTANTS",
+            &full_round_constants.len().to_string(),
+        );
+        Self::replace_wire_placeholders(template_str)
+    }
+
+    fn export_solidity_verification_code(&self) -> String {
+        assert_eq!(SPONGE_WIDTH, 12);
+        let mut template_str = "library Poseidon2Lib {
+    using GoldilocksFieldLib for uint64;
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+
+    function sbox(uint64[2] memory x) internal pure returns (uint64[2] memory) {
+        uint64[2] memory x2 = x.square();
+        return x.mul(x2).mul(x2.square());
+    }
+
+    function external_linear_layer(uint64[2][12] memory state) internal pure {
+        // Apply M_4 to each consecutive four elements of the state.
+        for (uint32 k = 0; k < 12; k += 4) {
+            uint64[2] memory t0 = state[k].add(state[k + 1]);
+            uint64[2] memory t1 = state[k + 2].add(state[k + 3]);
+            uint64[2] memory t2 = state[k + 1].add(state[k + 1]).add(t1);
+            uint64[2] memory t3 = state[k + 3].add(state[k + 3]).add(t0);
+            uint64[2] memory t4 = t1.scalar_mul(4).add(t3);
+            uint64[2] memory t5 = t0.scalar_mul(4).add(t2);
+            state[k] = t3.add(t5);
+            state[k + 1] = t5;
+            state[k + 2] = t2.add(t4);
+            state[k + 3] = t4;
+        }
+        // Apply the outer circulant matrix.
+        for (uint32 k = 0; k < 4; k++) {
+            uint64[2] memory sum = state[k].add(state[k + 4]).add(state[k + 8]);
+            state[k] = state[k].add(sum);
+            state[k + 4] = state[k + 4].add(sum);
+            state[k + 8] = state[k + 8].add(sum);
+        }
+    }
+
+    function internal_linear_layer(uint64[2][12] memory state) internal pure {
+        uint64[12] memory internal_diag = [$INTERNAL_DIAG];
+        uint64[2] memory sum = state[0];
+        for (uint32 i = 1; i < 12; i++) {
+            sum = sum.add(state[i]);
+        }
+        for (uint32 i = 0; i < 12; i++) {
+            state[i] = state[i].scalar_mul(internal_diag[i]).add(sum);
+        }
+    }
+
+    function partial_rounds(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints, uint64[2][12] memory state, uint32 index) internal pure {
+        uint64[$N_PARTIAL_ROUNDS] memory round_constants = [$PARTIAL_ROUND_CONSTANTS];
+        for (uint32 r = 0; r < $N_PARTIAL_ROUNDS; r++) {
+            uint64[2] memory sbox_in = ev.wires[$START_PARTIAL + r];
+            GatesUtilsLib.push(constraints, ev.filter, index + r, state[0].add(GatesUtilsLib.field_ext_from(round_constants[r], 0)).sub(sbox_in));
+            state[0] = sbox(sbox_in);
+            internal_linear_layer(state);
+        }
+    }
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint32 index = 0;
+        uint64[2] memory swap = ev.wires[$WIRE_SWAP];
+        GatesUtilsLib.push(constraints, ev.filter, index++, swap.mul(swap.sub(GoldilocksExtLib.one())));
+
+        uint64[2][12] memory state;
+        for (uint32 i = 0; i < 4; i++) {
+            uint64[2] memory delta_i = ev.wires[$START_DELTA + i];
+            GatesUtilsLib.push(constraints, ev.filter, index++, swap.mul(ev.wires[i + 4].sub(ev.wires[i])).sub(delta_i));
+            state[i] = ev.wires[i].add(delta_i);
+            state[i + 4] = ev.wires[i + 4].sub(delta_i);
+        }
+        for (uint32 i = 8; i < 12; i++) {
+            state[i] = ev.wires[i];
+        }
+
+        // The initial linear layer.
+        external_linear_layer(state);
+
+        uint64[$NUM_FULL_ROUND_CONSTANTS] memory full_round_constants = [$FULL_ROUND_CONSTANTS];
+        // First set of full rounds.
+        for (uint32 r = 0; r < $HALF_N_FULL_ROUNDS; r++) {
+            for (uint32 i = 0; i < 12; i++) {
+                state[i] = state[i].add(GatesUtilsLib.field_ext_from(full_round_constants[r * 12 + i], 0));
+                if (r != 0) {
+                    uint64[2] memory sbox_in = ev.wires[$START_FULL_0 + 12 * (r - 1) + i];
+                    GatesUtilsLib.push(constraints, ev.filter, index++, state[i].sub(sbox_in));
+                    state[i] = sbox_in;
+                }
+                state[i] = sbox(state[i]);
+            }
+            external_linear_layer(state);
+        }
+
+        // Partial rounds.
+        partial_rounds(ev, constraints, state, index);
+        index += $N_PARTIAL_ROUNDS;
+
+        // Second set of full rounds.
+        for (uint32 r = 0; r < $HALF_N_FULL_ROUNDS; r++) {
+            for (uint32 i = 0; i < 12; i++) {
+                state[i] = state[i].add(GatesUtilsLib.field_ext_from(full_round_constants[($HALF_N_FULL_ROUNDS + r) * 12 + i], 0));
+                uint64[2] memory sbox_in = ev.wires[$START_FULL_1 + 12 * r + i];
+                GatesUtilsLib.push(constraints, ev.filter, index++, state[i].sub(sbox_in));
+                state[i] = sbox(sbox_in);
+            }
+            external_linear_layer(state);
+        }
+
+        for (uint32 i = 0; i < 12; i++) {
+            GatesUtilsLib.push(constraints, ev.filter, index++, state[i].sub(ev.wires[12 + i]));
+        }
+    }
+}"
+        .to_string();
+
+        let (full_round_constants, partial_round_constants) = Self::round_constants();
+        template_str = template_str.replace(
+            "$NUM_FULL_ROUND_CONSTANTS",
+            &full_round_constants.len().to_string(),
+        );
+        template_str = template_str.replace(
+            "$FULL_ROUND_CONSTANTS",
+            &solidity_u64_array(&full_round_constants),
+        );
+        template_str = template_str.replace(
+            "$PARTIAL_ROUND_CONSTANTS",
+            &solidity_u64_array(&partial_round_constants),
+        );
+        template_str = template_str.replace(
+            "$INTERNAL_DIAG",
+            &solidity_u64_array(&poseidon2::MATRIX_DIAG_12_GOLDILOCKS),
+        );
+        Self::replace_wire_placeholders(template_str)
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+
+        // Assert that `swap` is binary.
+        let swap = vars.local_wires[Self::WIRE_SWAP];
+        constraints.push(swap * (swap - F::Extension::ONE));
+
+        // Assert that each delta wire is set properly: `delta_i = swap * (rhs - lhs)`.
+        for i in 0..4 {
+            let input_lhs = vars.local_wires[Self::wire_input(i)];
+            let input_rhs = vars.local_wires[Self::wire_input(i + 4)];
+            let delta_i = vars.local_wires[Self::wire_delta(i)];
+            constraints.push(swap * (input_rhs - input_lhs) - delta_i);
+        }
+
+        // Compute the possibly-swapped input layer.
+        let mut state = [F::Extension::ZERO; SPONGE_WIDTH];
+        for i in 0..4 {
+            let delta_i = vars.local_wires[Self::wire_delta(i)];
+            let input_lhs = Self::wire_input(i);
+            let input_rhs = Self::wire_input(i + 4);
+            state[i] = vars.local_wires[input_lhs] + delta_i;
+            state[i + 4] = vars.local_wires[input_rhs] - delta_i;
+        }
+        for i in 8..SPONGE_WIDTH {
+            state[i] = vars.local_wires[Self::wire_input(i)];
+        }
+
+        // The initial linear layer.
+        external_linear_layer_field(&mut state);
+
+        let mut round_ctr = 0;
+
+        // First set of full rounds.
+        for r in 0..poseidon2::HALF_N_FULL_ROUNDS {
+            constant_layer_field(&mut state, round_ctr);
+            if r != 0 {
+                for i in 0..SPONGE_WIDTH {
+                    let sbox_in = vars.local_wires[Self::wire_full_sbox_0(r, i)];
+                    constraints.push(state[i] - sbox_in);
+                    state[i] = sbox_in;
+                }
+            }
+            sbox_layer_field(&mut state);
+            external_linear_layer_field(&mut state);
+            round_ctr += 1;
+        }
+
+        // Partial rounds.
+        for r in 0..poseidon2::N_PARTIAL_ROUNDS {
+            partial_constant_layer_field(&mut state, round_ctr);
+            let sbox_in = vars.local_wires[Self::wire_partial_sbox(r)];
+            constraints.push(state[0] - sbox_in);
+            state[0] = sbox_monomial(sbox_in);
+            internal_linear_layer_field(&mut state);
+            round_ctr += 1;
+        }
+
+        // Second set of full rounds.
+        for r in 0..poseidon2::HALF_N_FULL_ROUNDS {
+            constant_layer_field(&mut state, round_ctr);
+            for i in 0..SPONGE_WIDTH {
+                let sbox_in = vars.local_wires[Self::wire_full_sbox_1(r, i)];
+                constraints.push(state[i] - sbox_in);
+                state[i] = sbox_in;
+            }
+            sbox_layer_field(&mut state);
+            external_linear_layer_field(&mut state);
+            round_ctr += 1;
+        }
+
+        for i in 0..SPONGE_WIDTH {
+            constraints.push(state[i] - vars.local_wires[Self::wire_output(i)]);
+        }
+
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        vars: EvaluationVarsBase<F>,
+        mut yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        // Assert that `swap` is binary.
+        let swap = vars.local_wires[Self::WIRE_SWAP];
+        yield_constr.one(swap * swap.sub_one());
+
+        // Assert that each delta wire is set properly: `delta_i = swap * (rhs - lhs)`.
+        for i in 0..4 {
+            let input_lhs = vars.local_wires[Self::wire_input(i)];
+            let input_rhs = vars.local_wires[Self::wire_input(i + 4)];
+            let delta_i = vars.local_wires[Self::wire_delta(i)];
+            yield_constr.one(swap * (input_rhs - input_lhs) - delta_i);
+        }
+
+        // Compute the possibly-swapped input layer.
+        let mut state = [F::ZERO; SPONGE_WIDTH];
+        for i in 0..4 {
+            let delta_i = vars.local_wires[Self::wire_delta(i)];
+            let input_lhs = Self::wire_input(i);
+            let input_rhs = Self::wire_input(i + 4);
+            state[i] = vars.local_wires[input_lhs] + delta_i;
+            state[i + 4] = vars.local_wires[input_rhs] - delta_i;
+        }
+        for i in 8..SPONGE_WIDTH {
+            state[i] = vars.local_wires[Self::wire_input(i)];
+        }
+
+        // The initial linear layer.
+        external_linear_layer_field(&mut state);
+
+        let mut round_ctr = 0;
+
+        // First set of full rounds.
+        for r in 0..poseidon2::HALF_N_FULL_ROUNDS {
+            constant_layer_field(&mut state, round_ctr);
+            if r != 0 {
+                for i in 0..SPONGE_WIDTH {
+                    let sbox_in = vars.local_wires[Self::wire_full_sbox_0(r, i)];
+                    yield_constr.one(state[i] - sbox_in);
+                    state[i] = sbox_in;
+                }
+            }
+            sbox_layer_field(&mut state);
+            external_linear_layer_field(&mut state);
+            round_ctr += 1;
+        }
+
+        // Partial rounds.
+        for r in 0..poseidon2::N_PARTIAL_ROUNDS {
+            partial_constant_layer_field(&mut state, round_ctr);
+            let sbox_in = vars.local_wires[Self::wire_partial_sbox(r)];
+            yield_constr.one(state[0] - sbox_in);
+            state[0] = sbox_monomial(sbox_in);
+            internal_linear_layer_field(&mut state);
+            round_ctr += 1;
+        }
+
+        // Second set of full rounds.
+        for r in 0..poseidon2::HALF_N_FULL_ROUNDS {
+            constant_layer_field(&mut state, round_ctr);
+            for i in 0..SPONGE_WIDTH {
+                let sbox_in = vars.local_wires[Self::wire_full_sbox_1(r, i)];
+                yield_constr.one(state[i] - sbox_in);
+                state[i] = sbox_in;
+            }
+            sbox_layer_field(&mut state);
+            external_linear_layer_field(&mut state);
+            round_ctr += 1;
+        }
+
+        for i in 0..SPONGE_WIDTH {
+            yield_constr.one(state[i] - vars.local_wires[Self::wire_output(i)]);
+        }
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+
+        // Assert that `swap` is binary.
+        let swap = vars.local_wires[Self::WIRE_SWAP];
+        constraints.push(builder.mul_sub_extension(swap, swap, swap));
+
+        // Assert that each delta wire is set properly: `delta_i = swap * (rhs - lhs)`.
+        for i in 0..4 {
+            let input_lhs = vars.local_wires[Self::wire_input(i)];
+            let input_rhs = vars.local_wires[Self::wire_input(i + 4)];
+            let delta_i = vars.local_wires[Self::wire_delta(i)];
+            let diff = builder.sub_extension(input_rhs, input_lhs);
+            constraints.push(builder.mul_sub_extension(swap, diff, delta_i));
+        }
+
+        // Compute the possibly-swapped input layer.
+        let mut state = [builder.zero_extension(); SPONGE_WIDTH];
+        for i in 0..4 {
+            let delta_i = vars.local_wires[Self::wire_delta(i)];
+            let input_lhs = vars.local_wires[Self::wire_input(i)];
+            let input_rhs = vars.local_wires[Self::wire_input(i + 4)];
+            state[i] = builder.add_extension(input_lhs, delta_i);
+            state[i + 4] = builder.sub_extension(input_rhs, delta_i);
+        }
+        for i in 8..SPONGE_WIDTH {
+            state[i] = vars.local_wires[Self::wire_input(i)];
+        }
+
+        // The initial linear layer.
+        external_linear_layer_circuit(builder, &mut state);
+
+        let mut round_ctr = 0;
+
+        // First set of full rounds.
+        for r in 0..poseidon2::HALF_N_FULL_ROUNDS {
+            constant_layer_circuit(builder, &mut state, round_ctr);
+            if r != 0 {
+                for i in 0..SPONGE_WIDTH {
+                    let sbox_in = vars.local_wires[Self::wire_full_sbox_0(r, i)];
+                    constraints.push(builder.sub_extension(state[i], sbox_in));
+                    state[i] = sbox_in;
+                }
+            }
+            sbox_layer_circuit(builder, &mut state);
+            external_linear_layer_circuit(builder, &mut state);
+            round_ctr += 1;
+        }
+
+        // Partial rounds.
+        for r in 0..poseidon2::N_PARTIAL_ROUNDS {
+            partial_constant_layer_circuit(builder, &mut state, round_ctr);
+            let sbox_in = vars.local_wires[Self::wire_partial_sbox(r)];
+            constraints.push(builder.sub_extension(state[0], sbox_in));
+            state[0] = sbox_monomial_circuit(builder, sbox_in);
+            internal_linear_layer_circuit(builder, &mut state);
+            round_ctr += 1;
+        }
+
+        // Second set of full rounds.
+        for r in 0..poseidon2::HALF_N_FULL_ROUNDS {
+            constant_layer_circuit(builder, &mut state, round_ctr);
+            for i in 0..SPONGE_WIDTH {
+                let sbox_in = vars.local_wires[Self::wire_full_sbox_1(r, i)];
+                constraints.push(builder.sub_extension(state[i], sbox_in));
+                state[i] = sbox_in;
+            }
+            sbox_layer_circuit(builder, &mut state);
+            external_linear_layer_circuit(builder, &mut state);
+            round_ctr += 1;
+        }
+
+        for i in 0..SPONGE_WIDTH {
+            constraints
+                .push(builder.sub_extension(state[i], vars.local_wires[Self::wire_output(i)]));
+        }
+
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        let gen = Poseidon2Generator::<F, D> {
+            row,
+            _phantom: PhantomData,
+        };
+        vec![WitnessGeneratorRef::new(gen.adapter())]
+    }
+
+    fn num_wires(&self) -> usize {
+        Self::end()
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        7
+    }
+
+    fn num_constraints(&self) -> usize {
+        SPONGE_WIDTH * (poseidon2::N_FULL_ROUNDS_TOTAL - 1)
+            + poseidon2::N_PARTIAL_ROUNDS
+            + SPONGE_WIDTH
+            + 1
+            + 4
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Poseidon2Generator<F: RichField + Extendable<D>, const D: usize> {
+    row: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for Poseidon2Generator<F, D>
+{
+    fn id(&self) -> String {
+        "Poseidon2Generator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        (0..SPONGE_WIDTH)
+            .map(|i| Poseidon2Gate::<F, D>::wire_input(i))
+            .chain(Some(Poseidon2Gate::<F, D>::WIRE_SWAP))
+            .map(|column| Target::wire(self.row, column))
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let local_wire = |column| Wire {
+            row: self.row,
+            column,
+        };
+
+        let mut state = (0..SPONGE_WIDTH)
+            .map(|i| witness.get_wire(local_wire(Poseidon2Gate::<F, D>::wire_input(i))))
+            .collect::<Vec<_>>();
+
+        let swap_value = witness.get_wire(local_wire(Poseidon2Gate::<F, D>::WIRE_SWAP));
+        debug_assert!(swap_value == F::ZERO || swap_value == F::ONE);
+
+        for i in 0..4 {
+            let delta_i = swap_value * (state[i + 4] - state[i]);
+            out_buffer.set_wire(local_wire(Poseidon2Gate::<F, D>::wire_delta(i)), delta_i);
+        }
+
+        if swap_value == F::ONE {
+            for i in 0..4 {
+                state.swap(i, 4 + i);
+            }
+        }
+
+        let mut state: [F; SPONGE_WIDTH] = state.try_into().unwrap();
+
+        external_linear_layer_field(&mut state);
+
+        let mut round_ctr = 0;
+
+        for r in 0..poseidon2::HALF_N_FULL_ROUNDS {
+            constant_layer_field(&mut state, round_ctr);
+            if r != 0 {
+                for i in 0..SPONGE_WIDTH {
+                    out_buffer.set_wire(
+                        local_wire(Poseidon2Gate::<F, D>::wire_full_sbox_0(r, i)),
+                        state[i],
+                    );
+                }
+            }
+            sbox_layer_field(&mut state);
+            external_linear_layer_field(&mut state);
+            round_ctr += 1;
+        }
+
+        for r in 0..poseidon2::N_PARTIAL_ROUNDS {
+            partial_constant_layer_field(&mut state, round_ctr);
+            out_buffer.set_wire(
+                local_wire(Poseidon2Gate::<F, D>::wire_partial_sbox(r)),
+                state[0],
+            );
+            state[0] = sbox_monomial(state[0]);
+            internal_linear_layer_field(&mut state);
+            round_ctr += 1;
+        }
+
+        for r in 0..poseidon2::HALF_N_FULL_ROUNDS {
+            constant_layer_field(&mut state, round_ctr);
+            for i in 0..SPONGE_WIDTH {
+                out_buffer.set_wire(
+                    local_wire(Poseidon2Gate::<F, D>::wire_full_sbox_1(r, i)),
+                    state[i],
+                );
+            }
+            sbox_layer_field(&mut state);
+            external_linear_layer_field(&mut state);
+            round_ctr += 1;
+        }
+
+        for i in 0..SPONGE_WIDTH {
+            out_buffer.set_wire(local_wire(Poseidon2Gate::<F, D>::wire_output(i)), state[i]);
+        }
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.row)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        Ok(Self {
+            row,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2_field::goldilocks_field::GoldilocksField;
+    use plonky2_field::types::Sample;
+
+    use super::*;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::hash::poseidon2::{Poseidon2, Poseidon2Hash};
+    use crate::iop::generator::generate_partial_witness;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, Hasher, Poseidon2GoldilocksConfig};
+
+    #[test]
+    fn wire_indices() {
+        type F = GoldilocksField;
+        type Gate = Poseidon2Gate<F, 4>;
+
+        assert_eq!(Gate::wire_input(0), 0);
+        assert_eq!(Gate::wire_input(11), 11);
+        assert_eq!(Gate::wire_output(0), 12);
+        assert_eq!(Gate::wire_output(11), 23);
+        assert_eq!(Gate::WIRE_SWAP, 24);
+        assert_eq!(Gate::wire_delta(0), 25);
+        assert_eq!(Gate::wire_delta(3), 28);
+        assert_eq!(Gate::wire_full_sbox_0(1, 0), 29);
+        assert_eq!(Gate::wire_full_sbox_0(3, 0), 53);
+        assert_eq!(Gate::wire_full_sbox_0(3, 11), 64);
+        assert_eq!(Gate::wire_partial_sbox(0), 65);
+        assert_eq!(Gate::wire_partial_sbox(21), 86);
+        assert_eq!(Gate::wire_full_sbox_1(0, 0), 87);
+        assert_eq!(Gate::wire_full_sbox_1(3, 0), 123);
+        assert_eq!(Gate::wire_full_sbox_1(3, 11), 134);
+    }
+
+    #[test]
+    fn generated_output() {
+        const D: usize = 2;
+        type C = Poseidon2GoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig {
+            num_wires: 143,
+            ..CircuitConfig::standard_recursion_config()
+        };
+        let mut builder = CircuitBuilder::new(config);
+        type Gate = Poseidon2Gate<F, D>;
+        let gate = Gate::new();
+        let row = builder.add_gate(gate, vec![]);
+        let circuit = builder.build_prover::<C>();
+
+        let permutation_inputs = (0..SPONGE_WIDTH)
+            .map(F::from_canonical_usize)
+            .collect::<Vec<_>>();
+
+        let mut inputs = PartialWitness::new();
+        inputs.set_wire(
+            Wire {
+                row,
+                column: Gate::WIRE_SWAP,
+            },
+            F::ZERO,
+        );
+        for i in 0..SPONGE_WIDTH {
+            inputs.set_wire(
+                Wire {
+                    row,
+                    column: Gate::wire_input(i),
+                },
+                permutation_inputs[i],
+            );
+        }
+
+        let witness = generate_partial_witness(inputs, &circuit.prover_only, &circuit.common);
+
+        let mut expected_outputs: [F; SPONGE_WIDTH] = permutation_inputs.try_into().unwrap();
+        <F as Poseidon2>::poseidon2(&mut expected_outputs);
+        for i in 0..SPONGE_WIDTH {
+            let out = witness.get_wire(Wire {
+                row: 0,
+                column: Gate::wire_output(i),
+            });
+            assert_eq!(out, expected_outputs[i]);
+        }
+    }
+
+    #[test]
+    fn circuit_hash_matches_native() -> Result<()> {
+        const D: usize = 2;
+        type C = Poseidon2GoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let inputs = builder.add_virtual_targets(20);
+        let hash = builder.hash_n_to_hash_no_pad::<Poseidon2Hash>(inputs.clone());
+        builder.register_public_inputs(&hash.elements);
+        let data = builder.build::<C>();
+
+        let input_values = F::rand_vec(20);
+        let mut pw = PartialWitness::new();
+        pw.set_target_arr(&inputs, &input_values);
+        let proof = data.prove(pw)?;
+
+        let expected = Poseidon2Hash::hash_no_pad(&input_values);
+        assert_eq!(proof.public_inputs, expected.elements);
+        Ok(data.verify(proof)?)
+    }
+
+    #[test]
+    fn low_degree() {
+        type F = GoldilocksField;
+        let gate = Poseidon2Gate::<F, 4>::new();
+        test_low_degree(gate)
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = Poseidon2GoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = Poseidon2Gate::<F, 2>::new();
+        test_eval_fns::<F, C, _, D>(gate)
+    }
+}
@@ -0,0 +1,313 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::marker::PhantomData;
+use core::ops::Range;
+
+use crate::field::extension::{Extendable, FieldExtension};
+use crate::gates::gate::Gate;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::hash::poseidon2::{
+    external_linear_layer_field, external_linear_layer_generic, SPONGE_WIDTH,
+};
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// Poseidon2 MDS Gate. Applies the external matrix `M_E` of Poseidon2 to 12 extension elements.
+#[derive(Debug, Default)]
+pub struct Poseidon2MdsGate<F: RichField + Extendable<D>, const D: usize>(PhantomData<F>);
+
+impl<F: RichField + Extendable<D>, const D: usize> Poseidon2MdsGate<F, D> {
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    pub(crate) const fn wires_input(i: usize) -> Range<usize> {
+        assert!(i < SPONGE_WIDTH);
+        i * D..(i + 1) * D
+    }
+
+    pub(crate) const fn wires_output(i: usize) -> Range<usize> {
+        assert!(i < SPONGE_WIDTH);
+        (SPONGE_WIDTH + i) * D..(SPONGE_WIDTH + i + 1) * D
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for Poseidon2MdsGate<F, D> {
+    fn id(&self) -> String {
+        format!("{self:?}<WIDTH={SPONGE_WIDTH}>")
+    }
+
+    fn serialize(
+        &self,
+        _dst: &mut Vec<u8>,
+        _common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<()> {
+        Ok(())
+    }
+
+    fn deserialize(_src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        Ok(Poseidon2MdsGate::new())
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        assert_eq!(SPONGE_WIDTH, 12);
+        "template Poseidon2MdsGate12() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  // M_E only adds its inputs, so it is applied to each coordinate of the extension algebra.
+  signal t0[$D][3][2];
+  signal t1[$D][3][2];
+  signal t2[$D][3][2];
+  signal t3[$D][3][2];
+  signal t4[$D][3][2];
+  signal t5[$D][3][2];
+  signal m4[$D][12][2];
+  signal sums[$D][4][2];
+  for (var j = 0; j < $D; j++) {
+    // Apply M_4 to each consecutive four elements of the state.
+    for (var k = 0; k < 3; k++) {
+      t0[j][k] <== GlExtAdd()(wires[4 * k * $D + j], wires[(4 * k + 1) * $D + j]);
+      t1[j][k] <== GlExtAdd()(wires[(4 * k + 2) * $D + j], wires[(4 * k + 3) * $D + j]);
+      t2[j][k] <== GlExtAdd()(GlExtScalarMul()(wires[(4 * k + 1) * $D + j], 2), t1[j][k]);
+      t3[j][k] <== GlExtAdd()(GlExtScalarMul()(wires[(4 * k + 3) * $D + j], 2), t0[j][k]);
+      t4[j][k] <== GlExtAdd()(GlExtScalarMul()(t1[j][k], 4), t3[j][k]);
+      t5[j][k] <== GlExtAdd()(GlExtScalarMul()(t0[j][k], 4), t2[j][k]);
+      m4[j][4 * k] <== GlExtAdd()(t3[j][k], t5[j][k]);
+      m4[j][4 * k + 1] <== t5[j][k];
+      m4[j][4 * k + 2] <== GlExtAdd()(t2[j][k], t4[j][k]);
+      m4[j][4 * k + 3] <== t4[j][k];
+    }
+
+    // Apply the outer circulant matrix.
+    for (var k = 0; k < 4; k++) {
+      sums[j][k] <== GlExtAdd()(GlExtAdd()(m4[j][k], m4[j][k + 4]), m4[j][k + 8]);
+    }
+    for (var i = 0; i < 12; i++) {
+      out[i * $D + j] <== ConstraintPush()(constraints[i * $D + j], filter, GlExtSub()(wires[(12 + i) * $D + j], GlExtAdd()(m4[j][i], sums[j][i % 4])));
+    }
+  }
+
+  for (var i = 12 * $D; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string()
+    }
+
+    fn export_solidity_verification_code(&self) -> String {
+        assert_eq!(SPONGE_WIDTH, 12);
+        "library Poseidon2MdsGateLib {
+    using GoldilocksFieldLib for uint64;
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        // M_E only adds its inputs, so it is applied to each coordinate of the extension algebra.
+        for (uint32 j = 0; j < $D; j++) {
+            uint64[2][12] memory state;
+            // Apply M_4 to each consecutive four elements of the state.
+            for (uint32 k = 0; k < 12; k += 4) {
+                uint64[2] memory x1 = ev.wires[(k + 1) * $D + j];
+                uint64[2] memory x3 = ev.wires[(k + 3) * $D + j];
+                uint64[2] memory t0 = ev.wires[k * $D + j].add(x1);
+                uint64[2] memory t1 = ev.wires[(k + 2) * $D + j].add(x3);
+                uint64[2] memory t2 = x1.add(x1).add(t1);
+                uint64[2] memory t3 = x3.add(x3).add(t0);
+                uint64[2] memory t4 = t1.scalar_mul(4).add(t3);
+                uint64[2] memory t5 = t0.scalar_mul(4).add(t2);
+                state[k] = t3.add(t5);
+                state[k + 1] = t5;
+                state[k + 2] = t2.add(t4);
+                state[k + 3] = t4;
+            }
+            // Apply the outer circulant matrix.
+            for (uint32 i = 0; i < 12; i++) {
+                uint64[2] memory computed_out = state[i].add(state[i % 4]).add(state[i % 4 + 4]).add(state[i % 4 + 8]);
+                GatesUtilsLib.push(constraints, ev.filter, i * $D + j, ev.wires[(12 + i) * $D + j].sub(computed_out));
+            }
+        }
+    }
+}"
+        .to_string()
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut computed_outputs: [_; SPONGE_WIDTH] = (0..SPONGE_WIDTH)
+            .map(|i| vars.get_local_ext_algebra(Self::wires_input(i)))
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+
+        external_linear_layer_generic(&mut computed_outputs, |a, b| a + b);
+
+        (0..SPONGE_WIDTH)
+            .map(|i| vars.get_local_ext_algebra(Self::wires_output(i)))
+            .zip(computed_outputs)
+            .flat_map(|(out, computed_out)| (out - computed_out).to_basefield_array())
+            .collect()
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        vars: EvaluationVarsBase<F>,
+        mut yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        let mut computed_outputs: [_; SPONGE_WIDTH] = (0..SPONGE_WIDTH)
+            .map(|i| vars.get_local_ext(Self::wires_input(i)))
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+
+        external_linear_layer_field(&mut computed_outputs);
+
+        yield_constr.many(
+            (0..SPONGE_WIDTH)
+                .map(|i| vars.get_local_ext(Self::wires_output(i)))
+                .zip(computed_outputs)
+                .flat_map(|(out, computed_out)| (out - computed_out).to_basefield_array()),
+        )
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut computed_outputs: [_; SPONGE_WIDTH] = (0..SPONGE_WIDTH)
+            .map(|i| vars.get_local_ext_algebra(Self::wires_input(i)))
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+
+        external_linear_layer_generic(&mut computed_outputs, |a, b| builder.add_ext_algebra(a, b));
+
+        (0..SPONGE_WIDTH)
+            .map(|i| vars.get_local_ext_algebra(Self::wires_output(i)))
+            .zip(computed_outputs)
+            .flat_map(|(out, computed_out)| {
+                builder
+                    .sub_ext_algebra(out, computed_out)
+                    .to_ext_target_array()
+            })
+            .collect()
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        let gen = Poseidon2MdsGenerator::<D> { row };
+        vec![WitnessGeneratorRef::new(gen.adapter())]
+    }
+
+    fn num_wires(&self) -> usize {
+        2 * D * SPONGE_WIDTH
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        1
+    }
+
+    fn num_constraints(&self) -> usize {
+        SPONGE_WIDTH * D
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Poseidon2MdsGenerator<const D: usize> {
+    row: usize,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for Poseidon2MdsGenerator<D>
+{
+    fn id(&self) -> String {
+        "Poseidon2MdsGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        (0..SPONGE_WIDTH)
+            .flat_map(|i| {
+                Target::wires_from_range(self.row, Poseidon2MdsGate::<F, D>::wires_input(i))
+            })
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let get_local_get_target = |wire_range| ExtensionTarget::from_range(self.row, wire_range);
+        let get_local_ext =
+            |wire_range| witness.get_extension_target(get_local_get_target(wire_range));
+
+        let mut outputs: [_; SPONGE_WIDTH] = (0..SPONGE_WIDTH)
+            .map(|i| get_local_ext(Poseidon2MdsGate::<F, D>::wires_input(i)))
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+
+        external_linear_layer_field(&mut outputs);
+
+        for (i, &out) in outputs.iter().enumerate() {
+            out_buffer.set_extension_target(
+                get_local_get_target(Poseidon2MdsGate::<F, D>::wires_output(i)),
+                out,
+            );
+        }
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.row)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        Ok(Self { row })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::gates::poseidon2_mds::Poseidon2MdsGate;
+    use crate::plonk::config::{GenericConfig, Poseidon2GoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        const D: usize = 2;
+        type C = Poseidon2GoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = Poseidon2MdsGate::<F, D>::new();
+        test_low_degree(gate)
+    }
+
+    #[test]
+    fn eval_fns() -> anyhow::Result<()> {
+        const D: usize = 2;
+        type C = Poseidon2GoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = Poseidon2MdsGate::<F, D>::new();
+        test_eval_fns::<F, C, _, D>(gate)
+    }
+}
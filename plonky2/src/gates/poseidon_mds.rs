@@ -22,6 +22,7 @@ use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CommonCircuitData;
 use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use crate::plonk::verifier_codegen::solidity_u64_array;
 use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
 /// Poseidon MDS Gate
@@ -178,7 +179,41 @@ impl<F: RichField + Extendable<D> + Poseidon, const D: usize> Gate<F, D> for Pos
         template_str
     }
     fn export_solidity_verification_code(&self) -> String {
-        todo!()
+        assert_eq!(SPONGE_WIDTH, 12);
+        let mut template_str = "library PoseidonMdsGateLib {
+    using GoldilocksFieldLib for uint64;
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint64[12] memory mds_matrix_circ = [$MDS_MATRIX_CIRC];
+        uint64[12] memory mds_matrix_diag = [$MDS_MATRIX_DIAG];
+        for (uint32 r = 0; r < 12; r++) {
+            for (uint32 j = 0; j < $D; j++) {
+                uint64[2] memory computed_out = ev.wires[r * $D + j].scalar_mul(mds_matrix_diag[r]);
+                for (uint32 i = 0; i < 12; i++) {
+                    computed_out = computed_out.add(ev.wires[((i + r) % 12) * $D + j].scalar_mul(mds_matrix_circ[i]));
+                }
+                GatesUtilsLib.push(constraints, ev.filter, r * $D + j, ev.wires[(12 + r) * $D + j].sub(computed_out));
+            }
+        }
+    }
+}"
+        .to_string();
+
+        template_str = template_str.replace(
+            "$MDS_MATRIX_CIRC",
+            &solidity_u64_array(&<F as Poseidon>::MDS_MATRIX_CIRC),
+        );
+        template_str = template_str.replace(
+            "$MDS_MATRIX_DIAG",
+            &solidity_u64_array(&<F as Poseidon>::MDS_MATRIX_DIAG),
+        );
+
+        template_str
     }
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
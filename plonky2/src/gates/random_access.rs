@@ -27,7 +27,8 @@ use crate::plonk::vars::{
     EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
     EvaluationVarsBasePacked,
 };
-use crate::util::serialization::{Buffer, IoResult, Read, Write};
+use crate::util::log2_ceil;
+use crate::util::serialization::{Buffer, IoError, IoResult, Read, Write};
 
 /// A gate for checking that a particular element of a list matches a given value.
 #[derive(Copy, Clone, Debug, Default)]
@@ -133,10 +134,13 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for RandomAccessGa
         Ok(())
     }
 
-    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let bits = src.read_usize()?;
-        let num_copies = src.read_usize()?;
-        let num_extra_constants = src.read_usize()?;
+    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let bits = src.read_usize_at_most(log2_ceil(common_data.config.num_wires))?;
+        let num_copies = src.read_usize_at_most(common_data.config.num_wires)?;
+        let num_extra_constants = src.read_usize_at_most(common_data.num_constants)?;
+        if bits == 0 || num_copies == 0 {
+            return Err(IoError);
+        }
         Ok(Self::new(num_copies, bits, num_extra_constants))
     }
 
@@ -158,14 +162,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for RandomAccessGa
   for (var copy = 0; copy < $NUM_COPIES; copy++) {{
     for (var i = 0; i < $BITS; i++) {{
       out[index] <== ConstraintPush()(constraints[index], filter,
-        GlExtMul()(wires[ra_wire_bit(i, copy)], GlExtSub()(wires[ra_wire_bit(i, copy)], GlExt(1, 0)())));
+        GlExtMul()(wires[ra_wire_bit_B$BITSC$NUM_COPIESE$NUM_EXTRA_CONSTANTS(i, copy)], GlExtSub()(wires[ra_wire_bit_B$BITSC$NUM_COPIESE$NUM_EXTRA_CONSTANTS(i, copy)], GlExt(1, 0)())));
       index++;
     }}
     for (var i = $BITS; i > 0; i--) {{
       if(i == $BITS) {{
-        acc[copy][i - 1] <== wires[ra_wire_bit(i - 1, copy)];
+        acc[copy][i - 1] <== wires[ra_wire_bit_B$BITSC$NUM_COPIESE$NUM_EXTRA_CONSTANTS(i - 1, copy)];
       }} else {{
-        acc[copy][i - 1] <== GlExtAdd()(GlExtAdd()(acc[copy][i], acc[copy][i]), wires[ra_wire_bit(i - 1, copy)]);
+        acc[copy][i - 1] <== GlExtAdd()(GlExtAdd()(acc[copy][i], acc[copy][i]), wires[ra_wire_bit_B$BITSC$NUM_COPIESE$NUM_EXTRA_CONSTANTS(i - 1, copy)]);
       }}
     }}
     out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(acc[copy][0], wires[(2 + $VEC_SIZE) * copy]));
@@ -175,7 +179,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for RandomAccessGa
     }}
     for (var i = 0; i < $BITS; i++) {{
       for (var j = 0; j < ($VEC_SIZE >> i); j = j + 2) {{
-        list_items[copy][i + 1][j \\ 2] <== GlExtAdd()(list_items[copy][i][j], GlExtMul()(wires[ra_wire_bit(i, copy)], GlExtSub()(list_items[copy][i][j + 1], list_items[copy][i][j])));
+        list_items[copy][i + 1][j \\ 2] <== GlExtAdd()(list_items[copy][i][j], GlExtMul()(wires[ra_wire_bit_B$BITSC$NUM_COPIESE$NUM_EXTRA_CONSTANTS(i, copy)], GlExtSub()(list_items[copy][i][j + 1], list_items[copy][i][j])));
       }}
     }}
     out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(list_items[copy][$BITS][0], wires[(2 + $VEC_SIZE) * copy + 1]));
@@ -190,7 +194,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for RandomAccessGa
     out[i] <== constraints[i];
   }}
 }}
-function ra_wire_bit(i, copy) {{
+function ra_wire_bit_B$BITSC$NUM_COPIESE$NUM_EXTRA_CONSTANTS(i, copy) {{
   return $NUM_ROUTED_WIRES + copy * $BITS + i;
 }}"
         ).to_string();
@@ -504,13 +508,13 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
         let vec_size = self.gate.vec_size();
 
         let access_index_f = get_local_wire(self.gate.wire_access_index(copy));
-        let access_index = access_index_f.to_canonical_u64() as usize;
-        debug_assert!(
-            access_index < vec_size,
-            "Access index {} is larger than the vector size {}",
-            access_index,
-            vec_size
-        );
+        let mut access_index = access_index_f.to_canonical_u64() as usize;
+        // If the index is out of range, write back the first index instead, so that witness
+        // generation fails with a conflict rather than a panic.
+        if access_index >= vec_size {
+            access_index = 0;
+            set_local_wire(self.gate.wire_access_index(copy), F::ZERO);
+        }
 
         set_local_wire(
             self.gate.wire_claimed_element(copy),
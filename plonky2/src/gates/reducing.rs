@@ -70,11 +70,11 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ReducingGate<D
         Ok(())
     }
 
-    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self>
+    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self>
     where
         Self: Sized,
     {
-        let num_coeffs = src.read_usize()?;
+        let num_coeffs = src.read_usize_at_most(common_data.config.num_wires)?;
         Ok(Self::new(num_coeffs))
     }
 
@@ -101,6 +101,20 @@ impl<F: RichField> GenericHashOut<F> for HashOut<F> {
         }
     }
 
+    fn try_from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != NUM_HASH_OUT_ELTS * 8 {
+            return None;
+        }
+        let elements = bytes
+            .chunks(8)
+            .map(|x| u64::from_le_bytes(x.try_into().unwrap()))
+            .map(|x| (x < F::ORDER).then(|| F::from_canonical_u64(x)))
+            .collect::<Option<Vec<_>>>()?;
+        Some(HashOut {
+            elements: elements.try_into().unwrap(),
+        })
+    }
+
     fn to_vec(&self) -> Vec<F> {
         self.elements.to_vec()
     }
@@ -179,6 +193,10 @@ impl<F: RichField, const N: usize> GenericHashOut<F> for BytesHash<N> {
         Self(bytes.try_into().unwrap())
     }
 
+    fn try_from_bytes(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok().map(Self)
+    }
+
     fn to_vec(&self) -> Vec<F> {
         self.0
             // Chunks of 7 bytes since 8 bytes would allow collisions.
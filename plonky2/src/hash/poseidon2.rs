@@ -13,8 +13,13 @@ use super::arch::x86_64::poseidon2_goldilocks_avx2::{
 };
 use super::hash_types::{HashOutTarget, NUM_HASH_OUT_ELTS};
 use crate::field::extension::Extendable;
+use crate::field::types::Field;
+use crate::gates::gate::Gate;
+use crate::gates::poseidon2::Poseidon2Gate;
+use crate::gates::poseidon2_mds::Poseidon2MdsGate;
 use crate::hash::hash_types::{HashOut, RichField};
 use crate::hash::hashing::PlonkyPermutation;
+use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::{BoolTarget, Target};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::config::{AlgebraicHasher, Hasher, HasherType};
@@ -392,7 +397,7 @@ where
 #[derive(Debug, Clone, Default)]
 struct DiffusionMatrixGoldilocks;
 
-pub fn matmul_internal<F: RichField>(
+pub fn matmul_internal<F: Field>(
     state: &mut [F; SPONGE_WIDTH],
     mat_internal_diag_m_1: [u64; SPONGE_WIDTH],
 ) {
@@ -593,6 +598,170 @@ impl<T: Copy + Debug + Default + Eq + Permuter2 + Send + Sync> PlonkyPermutation
 
 impl Poseidon2 for GoldilocksField {}
 
+// The following are the Poseidon2 round functions written for any field and for circuits. Unlike
+// `Poseidon2::poseidon2`, they don't rely on a particular field representation, so they can be used
+// to evaluate `Poseidon2Gate` constraints over extension fields and in recursive circuits.
+
+/// Number of full rounds in each half of the external rounds.
+pub const HALF_N_FULL_ROUNDS: usize = 4;
+/// Number of internal (partial) rounds.
+pub const N_PARTIAL_ROUNDS: usize = 22;
+/// Total number of external (full) rounds.
+pub const N_FULL_ROUNDS_TOTAL: usize = 2 * HALF_N_FULL_ROUNDS;
+
+/// Applies the external matrix `M_E` to `state`, using only the given addition. This lets the same
+/// code be shared by field elements, extension algebras and circuit targets.
+pub(crate) fn external_linear_layer_generic<T: Copy>(
+    state: &mut [T; SPONGE_WIDTH],
+    mut add: impl FnMut(T, T) -> T,
+) {
+    // Apply M_4 to each consecutive four elements of the state, as in `apply_m_4`.
+    for x in state.chunks_exact_mut(4) {
+        let t0 = add(x[0], x[1]);
+        let t1 = add(x[2], x[3]);
+        let double_x1 = add(x[1], x[1]);
+        let t2 = add(double_x1, t1);
+        let double_x3 = add(x[3], x[3]);
+        let t3 = add(double_x3, t0);
+        let double_t1 = add(t1, t1);
+        let quadruple_t1 = add(double_t1, double_t1);
+        let t4 = add(quadruple_t1, t3);
+        let double_t0 = add(t0, t0);
+        let quadruple_t0 = add(double_t0, double_t0);
+        let t5 = add(quadruple_t0, t2);
+        let t6 = add(t3, t5);
+        let t7 = add(t2, t4);
+        x[0] = t6;
+        x[1] = t5;
+        x[2] = t7;
+        x[3] = t4;
+    }
+
+    // Apply the outer circulant matrix.
+    let sums: [T; 4] = core::array::from_fn(|k| {
+        (4..SPONGE_WIDTH)
+            .step_by(4)
+            .fold(state[k], |acc, j| add(acc, state[j + k]))
+    });
+    for i in 0..SPONGE_WIDTH {
+        state[i] = add(state[i], sums[i % 4]);
+    }
+}
+
+/// Same as the external linear layer of `Poseidon2::poseidon2`, for any field.
+pub fn external_linear_layer_field<F: Field>(state: &mut [F; SPONGE_WIDTH]) {
+    external_linear_layer_generic(state, |a, b| a + b);
+}
+
+/// Same as the internal linear layer of `Poseidon2::poseidon2`, for any field.
+pub fn internal_linear_layer_field<F: Field>(state: &mut [F; SPONGE_WIDTH]) {
+    matmul_internal(state, MATRIX_DIAG_12_GOLDILOCKS);
+}
+
+/// Adds the round constants of the `round_ctr`-th external round to `state`.
+pub fn constant_layer_field<F: Field>(state: &mut [F; SPONGE_WIDTH], round_ctr: usize) {
+    for i in 0..SPONGE_WIDTH {
+        state[i] += F::from_canonical_u64(RC12[round_ctr][i]);
+    }
+}
+
+/// Adds the round constant of the `round_ctr`-th internal round to the first state element.
+pub fn partial_constant_layer_field<F: Field>(state: &mut [F; SPONGE_WIDTH], round_ctr: usize) {
+    state[0] += F::from_canonical_u64(RC12[round_ctr][0]);
+}
+
+#[inline(always)]
+pub fn sbox_monomial<F: Field>(x: F) -> F {
+    // x |--> x^7
+    let x2 = x.square();
+    let x4 = x2.square();
+    let x3 = x * x2;
+    x3 * x4
+}
+
+pub fn sbox_layer_field<F: Field>(state: &mut [F; SPONGE_WIDTH]) {
+    for i in 0..SPONGE_WIDTH {
+        state[i] = sbox_monomial(state[i]);
+    }
+}
+
+/// Recursive version of `external_linear_layer_field`.
+pub fn external_linear_layer_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    state: &mut [ExtensionTarget<D>; SPONGE_WIDTH],
+) {
+    // If we have enough routed wires, we will use Poseidon2MdsGate.
+    let mds_gate = Poseidon2MdsGate::<F, D>::new();
+    if builder.config.num_routed_wires >= mds_gate.num_wires() {
+        let index = builder.add_gate(mds_gate, vec![]);
+        for i in 0..SPONGE_WIDTH {
+            let input_wire = Poseidon2MdsGate::<F, D>::wires_input(i);
+            builder.connect_extension(state[i], ExtensionTarget::from_range(index, input_wire));
+        }
+        for i in 0..SPONGE_WIDTH {
+            let output_wire = Poseidon2MdsGate::<F, D>::wires_output(i);
+            state[i] = ExtensionTarget::from_range(index, output_wire);
+        }
+    } else {
+        external_linear_layer_generic(state, |a, b| builder.add_extension(a, b));
+    }
+}
+
+/// Recursive version of `internal_linear_layer_field`.
+pub fn internal_linear_layer_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    state: &mut [ExtensionTarget<D>; SPONGE_WIDTH],
+) {
+    let sum = builder.add_many_extension(state.iter());
+    for i in 0..SPONGE_WIDTH {
+        let c = F::from_canonical_u64(MATRIX_DIAG_12_GOLDILOCKS[i]);
+        state[i] = builder.mul_const_add_extension(c, state[i], sum);
+    }
+}
+
+/// Recursive version of `constant_layer_field`.
+pub fn constant_layer_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    state: &mut [ExtensionTarget<D>; SPONGE_WIDTH],
+    round_ctr: usize,
+) {
+    for i in 0..SPONGE_WIDTH {
+        let c = F::Extension::from_canonical_u64(RC12[round_ctr][i]);
+        let c = builder.constant_extension(c);
+        state[i] = builder.add_extension(state[i], c);
+    }
+}
+
+/// Recursive version of `partial_constant_layer_field`.
+pub fn partial_constant_layer_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    state: &mut [ExtensionTarget<D>; SPONGE_WIDTH],
+    round_ctr: usize,
+) {
+    let c = F::Extension::from_canonical_u64(RC12[round_ctr][0]);
+    let c = builder.constant_extension(c);
+    state[0] = builder.add_extension(state[0], c);
+}
+
+/// Recursive version of `sbox_monomial`.
+pub fn sbox_monomial_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    x: ExtensionTarget<D>,
+) -> ExtensionTarget<D> {
+    // x |--> x^7
+    builder.exp_u64_extension(x, 7)
+}
+
+/// Recursive version of `sbox_layer_field`.
+pub fn sbox_layer_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    state: &mut [ExtensionTarget<D>; SPONGE_WIDTH],
+) {
+    for i in 0..SPONGE_WIDTH {
+        state[i] = sbox_monomial_circuit(builder, state[i]);
+    }
+}
+
 /// Poseidon hash function.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct Poseidon2Hash;
@@ -641,24 +810,42 @@ impl<F: RichField + Poseidon2> AlgebraicHasher<F> for Poseidon2Hash {
     type AlgebraicPermutation = Poseidon2Permutation<Target>;
 
     fn permute_swapped<const D: usize>(
-        _inputs: Self::AlgebraicPermutation,
-        _swap: BoolTarget,
-        _builder: &mut CircuitBuilder<F, D>,
+        inputs: Self::AlgebraicPermutation,
+        swap: BoolTarget,
+        builder: &mut CircuitBuilder<F, D>,
     ) -> Self::AlgebraicPermutation
     where
         F: RichField + Extendable<D>,
     {
-        todo!()
+        let gate_type = Poseidon2Gate::<F, D>::new();
+        let gate = builder.add_gate(gate_type, vec![]);
+
+        let swap_wire = Poseidon2Gate::<F, D>::WIRE_SWAP;
+        let swap_wire = Target::wire(gate, swap_wire);
+        builder.connect(swap.target, swap_wire);
+
+        // Route input wires.
+        let inputs = inputs.as_ref();
+        for i in 0..SPONGE_WIDTH {
+            let in_wire = Poseidon2Gate::<F, D>::wire_input(i);
+            let in_wire = Target::wire(gate, in_wire);
+            builder.connect(inputs[i], in_wire);
+        }
+
+        // Collect output wires.
+        Self::AlgebraicPermutation::new(
+            (0..SPONGE_WIDTH).map(|i| Target::wire(gate, Poseidon2Gate::<F, D>::wire_output(i))),
+        )
     }
 
     fn public_inputs_hash<const D: usize>(
-        _inputs: Vec<Target>,
-        _builder: &mut CircuitBuilder<F, D>,
+        inputs: Vec<Target>,
+        builder: &mut CircuitBuilder<F, D>,
     ) -> HashOutTarget
     where
         F: RichField + Extendable<D>,
     {
-        todo!()
+        HashOutTarget::from_vec(builder.hash_n_to_m_no_pad::<Poseidon2Hash>(inputs, 4))
     }
 }
 
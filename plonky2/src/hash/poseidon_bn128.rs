@@ -1,8 +1,3 @@
-#![allow(non_upper_case_globals)]
-#![allow(non_camel_case_types)]
-#![allow(non_snake_case)]
-include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
-
 use super::hash_types::HashOutTarget;
 use super::poseidon::PoseidonPermutation;
 use crate::field::extension::quadratic::QuadraticExtension;
@@ -11,6 +6,7 @@ use crate::field::goldilocks_field::GoldilocksField;
 use crate::hash::hash_types::{HashOut, RichField};
 use crate::hash::hashing::{compress, hash_n_to_hash_no_pad, PlonkyPermutation};
 use crate::hash::poseidon::{PoseidonHash, SPONGE_RATE, SPONGE_WIDTH};
+use crate::hash::poseidon_bn128_ops::{self, Fr};
 use crate::iop::target::{BoolTarget, Target};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::config::{AlgebraicHasher, GenericConfig, Hasher, HasherType};
@@ -58,86 +54,27 @@ impl<F: RichField> PlonkyPermutation<F> for PoseidonBN128Permutation<F> {
 
     fn permute(&mut self) {
         assert_eq!(SPONGE_WIDTH, 12);
-        // println!("start permute............");
-        unsafe {
-            let h = permute(
-                self.state[0].to_canonical_u64(),
-                self.state[1].to_canonical_u64(),
-                self.state[2].to_canonical_u64(),
-                self.state[3].to_canonical_u64(),
-                self.state[4].to_canonical_u64(),
-                self.state[5].to_canonical_u64(),
-                self.state[6].to_canonical_u64(),
-                self.state[7].to_canonical_u64(),
-                self.state[8].to_canonical_u64(),
-                self.state[9].to_canonical_u64(),
-                self.state[10].to_canonical_u64(),
-                self.state[11].to_canonical_u64(),
-            );
-
-            let permute_output = [
-                F::from_canonical_u64(if h.r0 >= F::ORDER {
-                    h.r0 - F::ORDER
-                } else {
-                    h.r0
-                }),
-                F::from_canonical_u64(if h.r1 >= F::ORDER {
-                    h.r1 - F::ORDER
-                } else {
-                    h.r1
-                }),
-                F::from_canonical_u64(if h.r2 >= F::ORDER {
-                    h.r2 - F::ORDER
-                } else {
-                    h.r2
-                }),
-                F::from_canonical_u64(if h.r3 >= F::ORDER {
-                    h.r3 - F::ORDER
-                } else {
-                    h.r3
-                }),
-                F::from_canonical_u64(if h.r4 >= F::ORDER {
-                    h.r4 - F::ORDER
-                } else {
-                    h.r4
-                }),
-                F::from_canonical_u64(if h.r5 >= F::ORDER {
-                    h.r5 - F::ORDER
-                } else {
-                    h.r5
-                }),
-                F::from_canonical_u64(if h.r6 >= F::ORDER {
-                    h.r6 - F::ORDER
-                } else {
-                    h.r6
-                }),
-                F::from_canonical_u64(if h.r7 >= F::ORDER {
-                    h.r7 - F::ORDER
-                } else {
-                    h.r7
-                }),
-                F::from_canonical_u64(if h.r8 >= F::ORDER {
-                    h.r8 - F::ORDER
-                } else {
-                    h.r8
-                }),
-                F::from_canonical_u64(if h.r9 >= F::ORDER {
-                    h.r9 - F::ORDER
-                } else {
-                    h.r9
-                }),
-                F::from_canonical_u64(if h.r10 >= F::ORDER {
-                    h.r10 - F::ORDER
-                } else {
-                    h.r10
-                }),
-                F::from_canonical_u64(if h.r11 >= F::ORDER {
-                    h.r11 - F::ORDER
-                } else {
-                    h.r11
-                }),
-            ];
-            self.set_from_slice(&permute_output, 0)
+        // Pack the 12 Goldilocks elements into the four rate elements of the BN254 state, three
+        // 64-bit limbs each, with the first element of each chunk in the most significant limb.
+        let mut state = [Fr::ZERO; poseidon_bn128_ops::WIDTH];
+        for (s, chunk) in state[1..].iter_mut().zip(self.state.chunks_exact(3)) {
+            *s = Fr::from_canonical_limbs([
+                chunk[2].to_canonical_u64(),
+                chunk[1].to_canonical_u64(),
+                chunk[0].to_canonical_u64(),
+                0,
+            ]);
+        }
+
+        poseidon_bn128_ops::permute(&mut state);
+
+        // Unpack the low 192 bits of the first four BN254 elements back into Goldilocks elements,
+        // in the same limb order.
+        for (chunk, s) in self.state.chunks_exact_mut(3).zip(state) {
+            let limbs = s.to_canonical_limbs();
+            chunk[0] = F::from_noncanonical_u64(limbs[2]);
+            chunk[1] = F::from_noncanonical_u64(limbs[1]);
+            chunk[2] = F::from_noncanonical_u64(limbs[0]);
         }
     }
 
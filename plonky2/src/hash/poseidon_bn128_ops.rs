@@ -0,0 +1,613 @@
+//! Native implementation of the width-5 Poseidon permutation over the BN254 scalar field, as
+//! used by [`PoseidonBN128Hash`](super::poseidon_bn128::PoseidonBN128Hash).
+//!
+//! The round constants and MDS matrix are those of iden3's circomlib (x^5 S-box, 8 full rounds
+//! and 60 partial rounds), so that the permutation agrees with `go-iden3-crypto` and the
+//! Poseidon templates of circom and gnark.
+
+use core::ops::{Add, Mul};
+
+/// Width of the BN254 Poseidon permutation. The first element is the capacity.
+pub(crate) const WIDTH: usize = 5;
+const N_FULL_ROUNDS: usize = 8;
+const HALF_N_FULL_ROUNDS: usize = N_FULL_ROUNDS / 2;
+const N_PARTIAL_ROUNDS: usize = 60;
+
+/// An element of the BN254 scalar field, stored as four little-endian limbs in Montgomery form.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Fr([u64; 4]);
+
+impl Fr {
+    /// The BN254 scalar field order
+    /// `21888242871839275222246405745257275088548364400416034343698204186575808495617`.
+    const MODULUS: [u64; 4] = [
+        0x43e1f593f0000001,
+        0x2833e84879b97091,
+        0xb85045b68181585d,
+        0x30644e72e131a029,
+    ];
+
+    /// `-MODULUS^{-1} mod 2^64`.
+    const INV: u64 = 0xc2e1f593efffffff;
+
+    /// `2^512 mod MODULUS`, used to convert into Montgomery form.
+    const R2: [u64; 4] = [
+        0x1bb8e645ae216da7,
+        0x53fe3ab1e35c59e3,
+        0x8c49833d53bb8085,
+        0x0216d0b17f4e44a5,
+    ];
+
+    pub(crate) const ZERO: Self = Self([0; 4]);
+
+    /// Builds a field element from its canonical little-endian limbs, which must be smaller than
+    /// the modulus.
+    pub(crate) fn from_canonical_limbs(limbs: [u64; 4]) -> Self {
+        debug_assert!(!geq(&limbs, &Self::MODULUS));
+        Self(mont_mul(&limbs, &Self::R2))
+    }
+
+    /// Returns the canonical little-endian limbs of this field element.
+    pub(crate) fn to_canonical_limbs(self) -> [u64; 4] {
+        mont_mul(&self.0, &[1, 0, 0, 0])
+    }
+
+    fn square(self) -> Self {
+        self * self
+    }
+
+    /// The Poseidon S-box, `x^5`.
+    fn sbox(self) -> Self {
+        let x2 = self.square();
+        let x4 = x2.square();
+        x4 * self
+    }
+}
+
+impl Add for Fr {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let mut sum = [0u64; 4];
+        let mut carry = 0;
+        for i in 0..4 {
+            (sum[i], carry) = adc(self.0[i], rhs.0[i], carry);
+        }
+        // Both operands are below 2^254, so the sum cannot overflow 256 bits.
+        debug_assert_eq!(carry, 0);
+        Self(reduce_once(sum))
+    }
+}
+
+impl Mul for Fr {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(mont_mul(&self.0, &rhs.0))
+    }
+}
+
+/// Computes `a + b + carry`, returning the result and the new carry.
+#[inline(always)]
+const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let ret = (a as u128) + (b as u128) + (carry as u128);
+    (ret as u64, (ret >> 64) as u64)
+}
+
+/// Computes `a - b - borrow`, returning the result and the new borrow.
+#[inline(always)]
+const fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let ret = (a as u128).wrapping_sub((b as u128) + ((borrow >> 63) as u128));
+    (ret as u64, (ret >> 64) as u64)
+}
+
+/// Computes `a + b * c + carry`, returning the result and the new carry.
+#[inline(always)]
+const fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let ret = (a as u128) + ((b as u128) * (c as u128)) + (carry as u128);
+    (ret as u64, (ret >> 64) as u64)
+}
+
+fn geq(a: &[u64; 4], b: &[u64; 4]) -> bool {
+    for i in (0..4).rev() {
+        if a[i] != b[i] {
+            return a[i] > b[i];
+        }
+    }
+    true
+}
+
+/// Subtracts the modulus from `a` if `a >= MODULUS`.
+fn reduce_once(a: [u64; 4]) -> [u64; 4] {
+    if !geq(&a, &Fr::MODULUS) {
+        return a;
+    }
+    let mut res = [0u64; 4];
+    let mut borrow = 0;
+    for i in 0..4 {
+        (res[i], borrow) = sbb(a[i], Fr::MODULUS[i], borrow);
+    }
+    res
+}
+
+/// Montgomery multiplication, computing `a * b * 2^-256 mod MODULUS` (CIOS method).
+fn mont_mul(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut t = [0u64; 6];
+    for i in 0..4 {
+        let mut carry = 0;
+        for j in 0..4 {
+            (t[j], carry) = mac(t[j], a[j], b[i], carry);
+        }
+        (t[4], t[5]) = adc(t[4], carry, 0);
+
+        let m = t[0].wrapping_mul(Fr::INV);
+        let (_, mut carry) = mac(t[0], m, Fr::MODULUS[0], 0);
+        for j in 1..4 {
+            (t[j - 1], carry) = mac(t[j], m, Fr::MODULUS[j], carry);
+        }
+        (t[3], carry) = adc(t[4], carry, 0);
+        t[4] = t[5] + carry;
+    }
+    reduce_once([t[0], t[1], t[2], t[3]])
+}
+
+fn constant_layer(state: &mut [Fr; WIDTH], round_ctr: usize) {
+    for (i, s) in state.iter_mut().enumerate() {
+        *s = *s + ROUND_CONSTANTS[round_ctr * WIDTH + i];
+    }
+}
+
+fn mds_layer(state: &mut [Fr; WIDTH]) {
+    let mut result = [Fr::ZERO; WIDTH];
+    for (r, row) in MDS_MATRIX.iter().enumerate() {
+        for (&m, &s) in row.iter().zip(state.iter()) {
+            result[r] = result[r] + m * s;
+        }
+    }
+    *state = result;
+}
+
+/// Applies the BN254 Poseidon permutation to `state` in place.
+pub(crate) fn permute(state: &mut [Fr; WIDTH]) {
+    let mut round_ctr = 0;
+
+    for _ in 0..HALF_N_FULL_ROUNDS {
+        constant_layer(state, round_ctr);
+        state.iter_mut().for_each(|s| *s = s.sbox());
+        mds_layer(state);
+        round_ctr += 1;
+    }
+
+    for _ in 0..N_PARTIAL_ROUNDS {
+        constant_layer(state, round_ctr);
+        state[0] = state[0].sbox();
+        mds_layer(state);
+        round_ctr += 1;
+    }
+
+    for _ in 0..HALF_N_FULL_ROUNDS {
+        constant_layer(state, round_ctr);
+        state.iter_mut().for_each(|s| *s = s.sbox());
+        mds_layer(state);
+        round_ctr += 1;
+    }
+}
+
+/// Round constants of the width-5 BN254 Poseidon permutation, in Montgomery form.
+#[rustfmt::skip]
+pub(crate) const ROUND_CONSTANTS: [Fr; (N_FULL_ROUNDS + N_PARTIAL_ROUNDS) * WIDTH] = [
+    Fr([0x878a9569334498e4, 0x4641e4a29d08274f, 0xf2713820fea6f0c4, 0x0898c94bd2c76331]),
+    Fr([0xd6dec67b3646bdbc, 0x626a9e071b154f27, 0x71a61cb1f9d90cbe, 0x134dd09bc5dffaa7]),
+    Fr([0xc24d9503f8682c8c, 0x9cf5f5abe19fedff, 0x125f8816cdb2d9f1, 0x05954a7a4436fd78]),
+    Fr([0xc306f8ed4ba6732d, 0x5b187030689573d0, 0xb0a9df5b5120771d, 0x05513e9e64511461]),
+    Fr([0x84b301dccd446ff0, 0x59d0332079fd0d4c, 0xcb69fbff03ebf775, 0x1582477fe7736802]),
+    Fr([0x5362fc5c9a7c42f4, 0xb8b364f0155ab1f0, 0xb30654c42656c7c1, 0x2c5a02372db008fc]),
+    Fr([0x96b72aad17e84cc8, 0x951de8b3af900172, 0x019e8182aa706e63, 0x2698ae703c1abe1a]),
+    Fr([0x00d42e1221436b0e, 0xf2664ef30c6cd002, 0xedf50acc3d8616ae, 0x0f0f24531734899b]),
+    Fr([0x8b729e9643b2f886, 0x0d9d69a737bec5d9, 0x13f8477d2a2dc7df, 0x2f801ef1125e22ee]),
+    Fr([0x48e414c85ea0124d, 0xadf36e25585ad3b6, 0x01fd905aa908ce96, 0x12ae1d5c33dd8585]),
+    Fr([0xd9400c663f46fb1c, 0xc367fbfcb74e14e9, 0x263a31f53539a9a8, 0x2c14ea3e70ece3d6]),
+    Fr([0x8205091fcff4185e, 0x15b16c04f93c6171, 0xe81d72eaa617fc83, 0x1188c21d9ce9974d]),
+    Fr([0x8eac4395aa681654, 0x164ca0ba11b427eb, 0x35554a8d4dd43c78, 0x25884483e90fd08c]),
+    Fr([0xe7263d68cd48cf9a, 0xdfbbd8be5db5ba79, 0xda0fce3e869b03f1, 0x06cddd417a334e35]),
+    Fr([0xa0c5bf320595fc16, 0xf49f2260daad1d81, 0xb75bb40c6c4ad890, 0x0eed000c77ed4842]),
+    Fr([0xaecd15b6a4000350, 0x87fac0171084f2b6, 0x8d5c807f13f5c80c, 0x2af2a0fdc8846217]),
+    Fr([0x2864cfd614d0d590, 0xb408241676c9080a, 0x9080fa2f363b966f, 0x0af9bc0066eeb20a]),
+    Fr([0x0f99ec2d9c7f5c46, 0xc68c8a74c8fa9784, 0x082b8e29d87f91cc, 0x251594e3ce0b5ebd]),
+    Fr([0x406e8b6a120194da, 0x115b890c7f641047, 0x6752259870a05fa0, 0x0a4c706bb8417105]),
+    Fr([0x149a1b98952f0443, 0x7c9198b7d88b08da, 0xb342f127f00655a8, 0x248b7b9b0c55787a]),
+    Fr([0x3af8d053046780e4, 0x3614ef0c253cec2e, 0xce3861bb8d06d02a, 0x0c1f2be6c18f032d]),
+    Fr([0x6fcf69d0a67c195a, 0x39d597ee0bae3c31, 0xd5442c99bb0750e4, 0x2032ec38d1dc6c70]),
+    Fr([0x36c3b5b1458e56f2, 0xa6ab19d26e8c77be, 0x7ea17893f365b20f, 0x10691bce5b521d2f]),
+    Fr([0x9b6b4c808a224350, 0xb1a995f0f8f7dfa1, 0xb11d550562133bf0, 0x2842fdf05ad8f43b]),
+    Fr([0x5d0bb8e16ee958e7, 0x9d0ee403d02451b5, 0xe06f678dabf8d1ca, 0x135df6826cb5631c]),
+    Fr([0xd1287d6e72805c15, 0xf50ffffa5807b974, 0xd1df630fb95ed3b8, 0x026ba33898a91d2f]),
+    Fr([0x0d3d274100f30799, 0x6049cdb06abe61ad, 0x88cf6be341686e54, 0x0865d2b1a2dce2b1]),
+    Fr([0xbc0489a9907d3cb0, 0x1d8e718238e40ae1, 0xb717b8c529e8aec8, 0x25326846f39943c0]),
+    Fr([0x9d8248d4155b0fc5, 0x87b60b0e75d3f8c7, 0xf31fe5dd564a286b, 0x1621a6bb722e8d7b]),
+    Fr([0x68b9aae2a328df44, 0x16c257f026b30af4, 0xf4fb6aeecd769eab, 0x017e173ffd31ceb4]),
+    Fr([0x4acfbb3a0ab1f3af, 0xcea57df3614f599d, 0xa690574499abf0f4, 0x1ac5b7f1fca6daed]),
+    Fr([0x31d90575e2d7eefa, 0xf980315c753fbd71, 0x8973af88e9860147, 0x1ae623170a16aaa6]),
+    Fr([0x4e035b8837167083, 0x7cb27b17484ebcf0, 0x106d9221bd85c992, 0x1311510bd5c3ffba]),
+    Fr([0xd0b913b89c8b591d, 0x283637c85de72e1d, 0x3ab46dca1ed10ecc, 0x0ebbc06fbbfb1a29]),
+    Fr([0x1cd247a161f6d784, 0xe9ec0ef890468906, 0x4503b30d08dfb071, 0x271a41fee8f62a99]),
+    Fr([0xb95ab597bbc45967, 0x383fa8dba9e16989, 0x5295c5835c17d12c, 0x1f61f338ec158b0a]),
+    Fr([0x8b8a25bbccf10b14, 0x1ed6268b11ceb7ea, 0xd380344a54ef979f, 0x1eead5bc0fd5924e]),
+    Fr([0xceabe5b248a077e3, 0xcd6be0ebbec2326b, 0x24b760e4bce6ce62, 0x141a908a6d27f698]),
+    Fr([0x62e7ce178b30c88a, 0x835b2f0c43c4852f, 0xef27746250bd7876, 0x300dcbcb6f2aa657]),
+    Fr([0x2e1ad2dbb8e83c27, 0xc2860d2eabcec4f6, 0x3be3fa4cc0b160a8, 0x2bf92f3fe61f1232]),
+    Fr([0x47719788cbccebe2, 0x4edb4bb6db9f29e2, 0x21f92d8f1fb7eb63, 0x28deb83e98fa211c]),
+    Fr([0xd9a0180c0b1cdbaf, 0x8863c3c471f69245, 0x7f11fe3ad3fee8c2, 0x160fdc2b4558c21c]),
+    Fr([0x1c77bf07af072cab, 0x91991afbbd12154d, 0x0dde94cb7c0d5a92, 0x24eb431b12db1b0e]),
+    Fr([0x4e9f1258182ba056, 0xf953557b7083b6ca, 0xe7940dacb035f381, 0x2f76b4408948eb23]),
+    Fr([0xfcce96724694244f, 0x109488ef26c6bf00, 0xe6d8fd8554c907ed, 0x2a1aa167b3c1d370]),
+    Fr([0xe3db4323e596559d, 0xcb243fa371987f27, 0x61d4edf5855d81b3, 0x15da7b124ced0fe3]),
+    Fr([0x7108addbc90c712a, 0x72abefae6e8506fc, 0x8613f0c133c634cf, 0x053679f8c0431334]),
+    Fr([0x3cf98afc0fcfcf56, 0xad18915e610c8a89, 0xc352625b7b74018f, 0x1338a38250a2eae2]),
+    Fr([0x1dea74549e83599b, 0x7e92f66af4254a88, 0x70c1b47bd2703132, 0x19ed37df27b27592]),
+    Fr([0x228607bcc1a41f16, 0x0abf12885201bc36, 0x1fcf698e7d85860c, 0x2507b3680c3d5c60]),
+    Fr([0x68dff76afcbebb94, 0x115a3fbe38d8798d, 0x2411ecadb9b26692, 0x19f31c5c19cee1ed]),
+    Fr([0xefa41cc0bcf817a9, 0x58244d1d6978224b, 0xde20ee01ef3a3ab6, 0x0c32dac0cf3d1e0e]),
+    Fr([0x1ab51e2b39f5d7b6, 0xfecbb5939b623af7, 0x655bd9dfa4c6cbb1, 0x0a3f8bc7a082f5cf]),
+    Fr([0x49e7c4e977a85aa2, 0x0f334d5ff12aa382, 0x96c8bb2c420e3b98, 0x2c4cbffa8acdbdeb]),
+    Fr([0x825cb2f28a8c1e7c, 0xb19867377b71bd69, 0x7d1106032b26bd25, 0x30263798ec8c94b3]),
+    Fr([0x7e9a787542333054, 0x9ed2026bcce5daef, 0xb94cfda3b19aa18a, 0x2aef388e6fde243e]),
+    Fr([0xdec165892c94bfb4, 0x329cf08d419fd17d, 0xc782558cef565f52, 0x107e2bd0a01a9bd1]),
+    Fr([0x1e3617c341f737dd, 0xeec05d4004fc56ff, 0x9fcb2eec469e1def, 0x17d7ef155407534c]),
+    Fr([0xbc2e3c78ddc790a7, 0x4f2c8974fe39df1e, 0xc1fa27471562cd24, 0x03c3abd8dd76e013]),
+    Fr([0xb6718921c68b5bf3, 0xba4407209c0e858c, 0xaff2f22be243e66d, 0x1597dc6965c1d817]),
+    Fr([0x72e23890fd41a8b1, 0x20ae67fc3e5054c5, 0x0b17c6ddeea6f4bc, 0x0b484f64ff2eb9ee]),
+    Fr([0x1ba1ed783ebc3ccd, 0xf8ec74a93556259a, 0xf6b8df50e3999e23, 0x0a575b9987bce9da]),
+    Fr([0x6eb7507d2f380c81, 0x706bff2d37993e4b, 0x5490dd35553df267, 0x1b4ce24fbfd5173d]),
+    Fr([0xef208235df8e5c80, 0xd3018f9a778b1ba5, 0x0567e27bbb6a94e3, 0x07e1962013fac5ab]),
+    Fr([0x2bef9b8d26453de8, 0x783a4aedb77217d1, 0xd1d0204bbe4363c3, 0x252aed5a7f967a2a]),
+    Fr([0x767e910fd7b1d113, 0x64d3025621dc2bf8, 0x014848e166fb75ce, 0x1e1878fa93dd581b]),
+    Fr([0x74b547568b0b9ca6, 0x820752b8883e6af5, 0x571dc27a11f58de2, 0x0782ebb59653fc84]),
+    Fr([0xe24f568e1423a243, 0xa2c0c8cbb0b437a2, 0x90173ce12fdbf453, 0x1bb605b963e6819e]),
+    Fr([0x424258a0395d5c6a, 0x2e3b982b53d799f3, 0xe1caebe15898eb96, 0x2ecc7aa78cab1cf2]),
+    Fr([0xbe609bfb2d77d073, 0xc2e8b49a0fb971be, 0xfc3f29f72e7c6a02, 0x0a2b4f397bcf9583]),
+    Fr([0xa5b2d83c372a5ee4, 0xce669dfa91c515ba, 0x3d6952ba735dd19b, 0x142cd30377108060]),
+    Fr([0x60c5069959733fe5, 0xea5b12d915a075ac, 0x5894d4b277436a2b, 0x1ae3719bd175bd2e]),
+    Fr([0xeebda1b5058baed7, 0xf4e9c9c5064fa02c, 0x3e2fb0e622e4ab3d, 0x118ebc22997e996f]),
+    Fr([0x81aab5decfde8a1f, 0xfaee7498bf1f1ee2, 0xc80649d2d0ce7c07, 0x229c38125a51f2ea]),
+    Fr([0x985798acf36f6dfa, 0x9e2748a7fd62be49, 0x6778706b49970da2, 0x018c1e02e85993a6]),
+    Fr([0x8efe1a26d380e662, 0xde5aaa7b41a784f4, 0x1556052098327318, 0x17f9f1318cea6698]),
+    Fr([0xa2f259bc5d1fae3d, 0x659ef5c9d71cad42, 0xe923b54fa8d0258b, 0x03671edf5785b875]),
+    Fr([0x522adc4e474f6465, 0xa61be19bb4af4bab, 0x63fbcaccdd0624b3, 0x19d2382d5d50ec5a]),
+    Fr([0xe4b8176417c5b011, 0xe9111b9c9116f47c, 0x31fec42826b9b737, 0x15815cf4d4c29970]),
+    Fr([0x2d3df64d4cc2af8b, 0x8df6d640ad061121, 0xee745dc93e0abc89, 0x1c470d0e1d502236]),
+    Fr([0xc61d1549f24f7aa6, 0xb0b9980daf650c2d, 0xc7e378f87562d8ad, 0x13fc40a1e5880c11]),
+    Fr([0x67d132dbfc543847, 0x2acb1a2d09cd8b3a, 0x2cc8ac43f65c39fc, 0x00e539944f8812f9]),
+    Fr([0x2a650c1e112d61d5, 0xa094d7a998570d6e, 0x3fb61809d34fa3f6, 0x1ccfb9311d38da8c]),
+    Fr([0xedf9c6538dd77409, 0x2ff942bf1d7857ed, 0x65fd59c9d45c0db2, 0x2aa40867a469c80b]),
+    Fr([0xd427338021a545dd, 0x7da6580b3362643c, 0x980a22b76f9889db, 0x123988c2835a9a24]),
+    Fr([0x8b6cc56fc4a76a71, 0xadb2f3e02fd063a2, 0x0bedaba09c4dd557, 0x0b1608caab628954]),
+    Fr([0x456b34ab0915b1b6, 0xcb35fb5dfe479b6d, 0xbe5affaee0f02ffb, 0x04ab07cefb144ee3]),
+    Fr([0x78dd414ae4f7b61a, 0xa501d3572ef1cd14, 0xbc6d4580db92fdf4, 0x0faabe5df613978a]),
+    Fr([0xddb52cf62323987d, 0xb973b8fe44d14732, 0xfa94838f95c33f24, 0x071c6d0b09104a8c]),
+    Fr([0x556cb74f80958c8c, 0x1326cf75aea004da, 0x73ea834fa867569b, 0x1a7a1d35e343d6d6]),
+    Fr([0x3fd1e5a02371b5af, 0x9c7c08f0a0acab1b, 0xcfc13ca10c1a429c, 0x1c7ed01fe7a7cc99]),
+    Fr([0x65077690ae397f68, 0x9a0a194dc1f39b21, 0x0d402bdffec6708b, 0x06dfcdc908172898]),
+    Fr([0x594057b7e3efdd33, 0x1bcff8f9b420c2eb, 0x75d85b723a6772d4, 0x0ab0c47938e78af9]),
+    Fr([0xf12f6fd0f545630b, 0x39271bd6d2eea9a4, 0x879f22b58867f2d2, 0x02f399407f57ee41]),
+    Fr([0xe90c8bc7b3a4ca9b, 0x0e9451f3c6a47db4, 0xc1bfff8303becb17, 0x1c89980423c0e7fd]),
+    Fr([0x850277e063c37b0d, 0x5a1ec8cf872088e9, 0xa9187636ff4e7c1d, 0x11bf93e921c76865]),
+    Fr([0xca727e287aa107f3, 0x8ad0fd863f4a2994, 0xb81f12b15680ff26, 0x2f3b02faa9e2283f]),
+    Fr([0xe315d409289a3102, 0xd2af0f05b44e14d0, 0x37192d8727f960b5, 0x23f59ad717371256]),
+    Fr([0x703f2e5faaa00b28, 0x79a9177e081b6014, 0xbbab285beca97a99, 0x0f60e5597a6e59f0]),
+    Fr([0xebf3648071aa12f6, 0xa4f544819636d793, 0xbb1904ff84234d4a, 0x0d1a7c76aaa9d653]),
+    Fr([0xb4f61ddb5efe775c, 0xbcbab6f0fbbd58e5, 0x2ae426a9a2be2018, 0x24dd801cf2f45716]),
+    Fr([0x91c7251108bb269e, 0xecc3dcaf2a43e282, 0x89e159517c2874db, 0x1b77fa98c8e136cb]),
+    Fr([0xa76625f84556a6d2, 0x0ddf47e35bf922cf, 0x894b9011e1427fe1, 0x0ce0209cf5321212]),
+    Fr([0x0a261aa52b3455f0, 0x1408302a90e94238, 0x70469145b20d69a1, 0x0aabb98394429573]),
+    Fr([0x0a5dd9f92266bbd6, 0x9b4ff705a29d1f79, 0x88bb67e33444fce0, 0x05ccb7329777c838]),
+    Fr([0xc5b0b815fec4067e, 0xc2bf2ecf135b8265, 0xe422a048e71a2a9e, 0x1e86da97264a4809]),
+    Fr([0x77896a472d57c5d7, 0x4c01659d6cb7307d, 0x6d604465d6ae4195, 0x259a279222caf245]),
+    Fr([0xeec7c8d3c9d05669, 0x403cac10f52e5ed3, 0x7d286f43634fc4a8, 0x170ccf1409884506]),
+    Fr([0xbf45ec883a64a1e0, 0xe656c3c04fcdd3ee, 0x4da3e25c02f0cd07, 0x2e92f647578260ce]),
+    Fr([0xf07c35524b1c69b3, 0xdcbea469cffa2bea, 0x3319b3e7626434d2, 0x050473b25d9627f6]),
+    Fr([0x71ae812c01df78cd, 0x8e42ac99ea7b9de8, 0xa2b891b1fe42553b, 0x16df9032ff3b678d]),
+    Fr([0x7be10854d92f366c, 0x88d02eee3ebb1717, 0x1de04ba845924d19, 0x1672eb7cb2f7cea2]),
+    Fr([0xd53686face6d1a94, 0x57a2abe2fb9bdb6f, 0xd4466126890d3ff8, 0x169e5c32fcb12a1a]),
+    Fr([0x2b8ffe0e028fab58, 0x79f2259ad4a7598f, 0xae9e1e6d37ec58a0, 0x0360de92f5d0fcf4]),
+    Fr([0xf9c12f1a674e0eef, 0x925dd455a1ce50d8, 0x0f565f5cda9cc8e0, 0x08b17aece2905e79]),
+    Fr([0x21fb8e0647d40d5d, 0xd8dc830623a71f77, 0x17c0551c8acf8aae, 0x2694844861621ce8]),
+    Fr([0x68748cd83770f6d4, 0x3eed11b90479facc, 0x4e06a8320f30819b, 0x2f5decf20bea441c]),
+    Fr([0xd3087fa57e280102, 0xe99c6d809a79d218, 0xfe0e285307b369a7, 0x1ff7d670edb42335]),
+    Fr([0xe42ea6ade883578a, 0xdb841d736aacdc59, 0xafbc39f661961241, 0x2e286d586bf36f73]),
+    Fr([0xc082fd6317b120b4, 0xe23011069f300b47, 0x0b782e5541f3c64a, 0x0735bfaa4e96d0fb]),
+    Fr([0xa989f12ac7ea4110, 0x1d2ab07c09772de4, 0x93bab326db527582, 0x09102ec7ba7ea44e]),
+    Fr([0x98d3ff67f305789e, 0x3127595225299aee, 0x57254e4d4484c7e0, 0x0c1e884b0fcc2e31]),
+    Fr([0xf134b08e02024ff3, 0x0a4ab6f42842828f, 0x634e64a526bbf3a6, 0x1100e0613865f4ba]),
+    Fr([0xa3a6ccbf0d49f756, 0x345fc8f27a5d2387, 0x2ddea52cd3969df3, 0x2a6b21ff4c95d015]),
+    Fr([0xb98c608ff29f2f56, 0xadbd9a24ce238e22, 0x5ad7533cf5ea087b, 0x04990bdd0180fa57]),
+    Fr([0xd69dd26465503121, 0x1fa76a9b49d89e55, 0xe9688595f7287d0a, 0x13b473bc49f7e9e7]),
+    Fr([0x5fff47d6bf555f70, 0x3a3a538f4403d5d4, 0x4ea8fb4169059e80, 0x301c263212c77179]),
+    Fr([0x9d73e39e1bc8207b, 0xc66f499f87d7361c, 0xeb9b233b4498afda, 0x1202e9aff9a9f48a]),
+    Fr([0x29ad13e72c99554f, 0x37e732f4608bd0ed, 0x450fa35fcad3e8cf, 0x267498bd3dd14816]),
+    Fr([0x78dd745921cdaa06, 0xc2fa4e760b0bd002, 0x730c68208a40352f, 0x03109476f0109626]),
+    Fr([0x41cbcd71f3abb777, 0xe582dd40dda04995, 0x980eeba3de5a793d, 0x081ee281e92f0ce0]),
+    Fr([0x794523182d81b2bf, 0xfec9c95df8c569bf, 0x1a378b90323612ed, 0x22c10df10deab182]),
+    Fr([0x1863e0beab9e8707, 0x3fb9357d4ca3c3e0, 0x8be1de3a9ad6ffb6, 0x24b05d41abf0bd40]),
+    Fr([0x8e9d429d5dd0d532, 0xd95b801bbc973739, 0x868c85e9888db57b, 0x0575ee9d3a474eef]),
+    Fr([0xceeaff93c7c0c95a, 0xbcde7b2f12bc3614, 0xc41db9f28b38bf36, 0x184359a1ae0f4a47]),
+    Fr([0x4fc998ed1ddcec25, 0xb089fdab4f657f4c, 0x30404c2d33ecd21c, 0x11b2163a0a90992d]),
+    Fr([0x508f2637f6bcdeb6, 0x68e03688139a2b75, 0x18343368e4fa64d3, 0x188c9ae0cf76d95a]),
+    Fr([0xcc85ad5d5a797414, 0xa38c86fd60139313, 0x33e81695fd3026f5, 0x0a6ec3a211a3f60c]),
+    Fr([0x15422b856727d2ae, 0x09d77047a31659e2, 0xb0b1e7a613ce7b07, 0x0f38cf2c91abd244]),
+    Fr([0xdc6d4b011bfb6f33, 0x5a9de497fc11123f, 0x7ed4781a5f5baeb8, 0x0f556e267386b10a]),
+    Fr([0x3cb34674384c595f, 0xba0c202373c4cf6c, 0xf71cd2d7f9519111, 0x1880171d34dc6c21]),
+    Fr([0x7bbf92b5a94a78f0, 0x581bf9fca97b1ec0, 0xdf3167248b40cf46, 0x1d15590a903c2c26]),
+    Fr([0x64e8eb865d4b3b4c, 0xbd1894070189c394, 0x292eb0c7dde16552, 0x20d7fba49951d2cd]),
+    Fr([0xf93097bd8c92262f, 0xfac4e71bf44e6758, 0x3c524519a013b3c4, 0x094d37aaaa712f28]),
+    Fr([0x5677cfccbd612156, 0x5e480dc85c78f191, 0x0169787a39b70b0b, 0x2ef06707ee10a21d]),
+    Fr([0x81093a19b3a8f00c, 0x94622405b1d7ad2c, 0x818fa1001f728e48, 0x303e038300021a82]),
+    Fr([0x88aa978c0d927b95, 0x492d8d45d1404ba1, 0x35d4feb55eece5f9, 0x2f0b5c95669aedcc]),
+    Fr([0xea8f5ed5c8930283, 0xeb145895efcf1951, 0xdb9e7b46aa8688d3, 0x225532cc60ad4b46]),
+    Fr([0x4929a7791be7c62c, 0xb1d934da8e2d9f5e, 0x4f36e7b1bdcb6a49, 0x0f7557be447e4104]),
+    Fr([0xd380ae526f3af66f, 0x7a3397f39af4d548, 0xff67c0e77c175bfa, 0x13d7c200c3965533]),
+    Fr([0xbd2537401be3944b, 0x9861eaac6d10c465, 0xfa9dee9430a6a7fb, 0x22358c2f6e6c2aa7]),
+    Fr([0xfaba48cb92359344, 0x266c07e8cf543934, 0xff9976ec73924e29, 0x0d1c95ef146f2623]),
+    Fr([0xf319a92c36026b2e, 0xfd362bc33a7b315c, 0xdc161701b094e3e0, 0x29929e9ec76d5c61]),
+    Fr([0x7b78491dd4372788, 0x4ea3c88de6e59df1, 0x790bffaf3780a075, 0x158d3f8dc8a67644]),
+    Fr([0xcc4150c9c339d440, 0x4c5751913a379ad2, 0x2ee5998671e35e63, 0x09a6049e220e675a]),
+    Fr([0x67a512a6ff1703d0, 0xe82105cdaa2947db, 0x7d9011967988b49f, 0x0e3cc24e617010c4]),
+    Fr([0xfb71128b1194b909, 0x8fa44cc7b267f1e1, 0x309f309498ec27e3, 0x2ec64495af5e87cc]),
+    Fr([0x6db4056eda369d38, 0xdc88877d2b9a369a, 0x73bf6d4fe9463d40, 0x0746c3155c732288]),
+    Fr([0xd4028d93c9297edf, 0x693c98fcea914200, 0x0761c875db80d333, 0x1821ad905515cb71]),
+    Fr([0x1c90d17e3bc84dd0, 0x4fde4a7ff5982aa1, 0x98cd6ff33d171473, 0x2b49d4ced162dd8b]),
+    Fr([0x30ec25e262b067e1, 0x9038b7fbff41e895, 0x4a19965e4be86d7e, 0x29a1cbdfe71b5415]),
+    Fr([0x4e6e766ac76a82f6, 0xcd929231e383d9cb, 0xdd3b4a4a5bd5e446, 0x2982db83c32bdaa3]),
+    Fr([0x9f6304a26a4e91b3, 0xfdfd67fa807018c2, 0x66bb3561af772560, 0x0255932acf2b8d13]),
+    Fr([0x6850ed4937dabe3f, 0x5f87791fcfaf0f3d, 0x31995428bf67976b, 0x2f67ea937511e10e]),
+    Fr([0xf064519170724e46, 0x446d824e7deb3eff, 0xbc1a46d7038cc8b4, 0x0f86068edfb84115]),
+    Fr([0x9d8e409b4578cdbf, 0x537ecdcd756854a0, 0xfcb796dfd81b44fa, 0x010e8d93a7b7c6c8]),
+    Fr([0x7917621743eadb65, 0x1f34d499bb74967c, 0x92dab8898b4eacce, 0x1751b7025b63d0ad]),
+    Fr([0xde1e39a791209d23, 0x7f5492d709e02a13, 0x865c42b83fcfceef, 0x024c52c936cfbcf5]),
+    Fr([0x426677674a93c751, 0x0db3d43f3567928b, 0x4558af4be907ccc6, 0x05a68ca51c6db0ee]),
+    Fr([0xc42816e4180f84e5, 0x440f526cde08e797, 0xb76701ddd74e2901, 0x212c68b31e7bf3ae]),
+    Fr([0x73eab4e25344972a, 0x6f0bb36534de3297, 0x0d6a8049cb1ff7fb, 0x05e7a2e2157fedd2]),
+    Fr([0xfa45c9e2bd330a08, 0x54d20e2de3dbe05a, 0x84dd16705e296afd, 0x0ce2bd1420083dc1]),
+    Fr([0x2af12c96de27345b, 0xf675ccfb24fc5ff4, 0x9d37ed01d66e00cc, 0x1f90842016dffbb7]),
+    Fr([0x58d8746bcd3b1dcb, 0x08e8776132080686, 0x868e950c589220f7, 0x2265d5455c3add5a]),
+    Fr([0xbef008943d053a91, 0x5c23fa0a553b87ed, 0xa1c937f66324c375, 0x280528d263712122]),
+    Fr([0x1497a44949494c9e, 0x7b78eeda10b014fd, 0xa8481162cae2ec7d, 0x1b0aa6b2eaddd1f3]),
+    Fr([0x73c7db1a0144bc5c, 0x53a7d7fd26289015, 0xd03921c476159262, 0x232f83cbaf78e1dc]),
+    Fr([0xa2f148a72097229c, 0x6e81ff6ffcd9af00, 0x71b684b412aad11f, 0x181070f340e3b699]),
+    Fr([0xde64454e65be5f29, 0xd4e20a0599026de3, 0x6c01062e20b2dfa2, 0x170bf9a382b352c9]),
+    Fr([0xc2a6e9665da985b9, 0x8e0efc2dabbee109, 0x42d917c308c90e45, 0x14158062c6ed87bb]),
+    Fr([0x4df3f32864020c6d, 0xe6d76127ee72eeae, 0xdb6ee54466fa2460, 0x288dea8ff700de22]),
+    Fr([0xa95300e9d51de6b1, 0x530325ae77a05c64, 0x966957f059c669ee, 0x0477e3652f25c267]),
+    Fr([0xc202c7afbc4289fe, 0xb5c9f910b59b091f, 0x24cd78014ffb3b50, 0x22f6423e0a80cae8]),
+    Fr([0x8127837ffc28f3cf, 0xb4111183ee027141, 0xd0a1f3620cb1fd42, 0x2414bbb76b8af88d]),
+    Fr([0xb15d0da34874485e, 0xbfa2cf3f5486ace1, 0x5f6ec9c0cfca20f0, 0x2ac55c7ab544afe0]),
+    Fr([0xdc00e3613b460d33, 0x9364c74610ba0aba, 0x748086843f7e8a9a, 0x239e1a731a2ad6e7]),
+    Fr([0xfb378f8a793c155c, 0x6ed80c1bb06d561c, 0x54e80a06b72b0a19, 0x2f3e1771befe5224]),
+    Fr([0x933c5a890a290c2e, 0xbf369ede89e1f71d, 0x6a7fd4cca88f380a, 0x0c41993855ec6f4f]),
+    Fr([0x2cc26b3184259df1, 0xd212a9885fc96f3e, 0x842104ea2bdc5e1b, 0x1a6f60d275d0b8d8]),
+    Fr([0x89bd14b444ed5d3f, 0x1d297dda5aee9993, 0xc6a7789086b5a014, 0x0130fbd0445d73da]),
+    Fr([0xf291e795d978bf00, 0x90276e35d6a04981, 0x99d405aba0ba12fa, 0x055038290f0dbc34]),
+    Fr([0x7469e178c29c20ff, 0x8fd08072ce54d353, 0xe5e2cdbac1ddeb13, 0x0f82298fd67a4b90]),
+    Fr([0x9cf4d58354a8d392, 0x3eb76d20828afc5a, 0xb6d2d65cd9ccb7ba, 0x1c5414dab32784fc]),
+    Fr([0xf0a311f53014cd59, 0xa94f4a8c105e7c26, 0xf30c8f7c5be7fe4b, 0x2285dcaef23d05fd]),
+    Fr([0x16c5469ee7d3c837, 0x4a4d70772dbd3c6f, 0xfd17c91710973055, 0x06e0a97399ba2d58]),
+    Fr([0xcab2c41737fbb6dc, 0xa1e83754d9796129, 0xd2ee8a5f98d7bf9c, 0x17c24db40f1cec8c]),
+    Fr([0xcca2a5657c6e8da9, 0xafd57a506e2bca77, 0x9ca57834be2da4bc, 0x071a0ac54577b165]),
+    Fr([0xb3d9e4680bb4cb73, 0x4ed91d13001412cf, 0x514b6370d17996b3, 0x21eb0792b78ffeb6]),
+    Fr([0x3ba959d3c8732ea1, 0x952a702996e2b796, 0xc45f83663a80953c, 0x0c256cb16a91dce4]),
+    Fr([0x3d5877bce5f26fb9, 0x7cc1ddc68e0aa3ff, 0xbca5bbe57456431e, 0x0ce6b0593eb8151d]),
+    Fr([0xcd3bc337fa008fa1, 0x7601adf611acd524, 0xfd90abfc3d41d204, 0x282aa66a61509994]),
+    Fr([0x6ac40016f0326d83, 0x30a3fcf6c5132f31, 0x9c2a5997ad9ec4b7, 0x01500b51d22068bf]),
+    Fr([0x46c8df62256d60de, 0x482395a9f35ccf91, 0xce4a3f1bf8d781bd, 0x20a456f09a197de2]),
+    Fr([0x4de77f13a0f30c20, 0x239af94fa75e3a2c, 0x0ee9b5be860dacf1, 0x1c1ea86d4091a36b]),
+    Fr([0x5e902a596bb6d9df, 0x136da47e715822b9, 0x32525cef28ddc7bb, 0x150701cc4e7bc83a]),
+    Fr([0x7b7e0be926969bb4, 0xa10a1d8fa1d20b8a, 0xb75a24947f050d8b, 0x1c1ddbd69e2f266f]),
+    Fr([0xadadc9b28e3651de, 0xd6ed5d90d5185d08, 0x0518c9e48c734eee, 0x073ce38550e0418d]),
+    Fr([0xfc4d579035eb7492, 0xa18ba48559397708, 0x2191d2fa413e16ac, 0x0608af7178d9ddd5]),
+    Fr([0x5c77fbcdba4ad6b0, 0xc9930b0366a91213, 0xd9d5f670050425fc, 0x24f7c7ae8deb788a]),
+    Fr([0x869abf12402fc7ae, 0x7411020760a9d758, 0xd01295d913376448, 0x015329e8b4453b0b]),
+    Fr([0x74a1ed2a19e18e0e, 0xe762cf9b0e018a92, 0x4936b3fddc5e6d7a, 0x07607132809b8323]),
+    Fr([0xfa234593dedd1d05, 0x92e86e9b3ea5f7fc, 0x11c8a154aece5744, 0x0c550415d466454b]),
+    Fr([0x5892c1d31caff13a, 0x6a542fed580a85e0, 0xb56668ce6ccd57e2, 0x0e61f8e72756a987]),
+    Fr([0x71844594462c21b5, 0x488af1f2f931daf1, 0xadd4a5dddefe5888, 0x21636076cf956fc1]),
+    Fr([0x23e78fbbc6a32a1b, 0x6f5fa329cd0991d1, 0xbb4abb718dd8f455, 0x2bcfbfa5e6cd40cc]),
+    Fr([0x0808e0ce628624cb, 0xecd0e19a0a73bd8a, 0xbd7d87436139e967, 0x280b5a0cc2bb5816]),
+    Fr([0x3cff1714d68e8d72, 0x534bdf1d429bff8d, 0x5809ab0c50bc2ed1, 0x14d045b98ed5c329]),
+    Fr([0xb93e3c39f6a985ad, 0x9389555a40064049, 0xa8950c8cbb8ddfad, 0x173cd6bacf604287]),
+    Fr([0xaf9ac6edc684750f, 0x0846f4e627d89d37, 0x10e7b1743aa64625, 0x22b807f206ebed35]),
+    Fr([0x9908704fd85cfd9f, 0x59c69d60ac1a2fc7, 0x02fcbb185c51fd9b, 0x2a165b20fe194c1c]),
+    Fr([0xa99a2175e3244e6d, 0x9a6dab1e4d9ed982, 0xc06a275c7e9e49b5, 0x0b797dec795e19c3]),
+    Fr([0xb8d4c54c740aa4e0, 0xd3926f418db1c55d, 0x51b30e1a4a7aa4d0, 0x1b4fc743c0bf5454]),
+    Fr([0xf0b12bc56dd185b8, 0x82415e2c23c0315e, 0xaf6e94b09e59634c, 0x0ebdb449a73d43ef]),
+    Fr([0x35ec8659e800b04f, 0x43b48df13d76b59b, 0x4a88ad3cb7d7d4b7, 0x2c29e8429c50a35e]),
+    Fr([0xb22a0be1ff80c622, 0xad24c279a4b292f0, 0xdbace07b2b405ee5, 0x188dc2b37414f6e0]),
+    Fr([0x854fbedfc6812b82, 0xe114f6cae4f1be19, 0xb302c97771564a5b, 0x114df50adf15cace]),
+    Fr([0xe0790f0d2c2016f4, 0x9cbf563518ca6ca2, 0xb17084e5718902fc, 0x10a14bb19c5aa476]),
+    Fr([0x81366a956da0dedf, 0xa59d99f64cd45ca7, 0xe2b6cf4c4c75ffb4, 0x2019a466867c90b6]),
+    Fr([0x217a061c77d96cea, 0xd5757880bf412318, 0x4afd5c8e76865d25, 0x0e510f9fb5ce1807]),
+    Fr([0x4e80f1691625b1a2, 0x7272645cbd611728, 0x5d8b0ff1c4d732d0, 0x2108cff86c22c765]),
+    Fr([0x0852bbbc2ed04b76, 0x78586fe8ae649077, 0xaa2ef1e436f3bcb5, 0x269683e08d24af59]),
+    Fr([0x1f65272a50a5a9a9, 0x9014d4ef9e15a910, 0x2edfa637e91c7093, 0x0e97879fb3eb72be]),
+    Fr([0x2f90a84b9840f8e2, 0x1044b28500c767be, 0xee5859b5772a557c, 0x09f1c7e818f87272]),
+    Fr([0x5f77a4bcc8d0e3d2, 0xc0dc6053804c6117, 0xe788b42c22246e25, 0x02dc34c216472c4a]),
+    Fr([0x9321a802d463f3d5, 0x83bee316e6217264, 0x911899e8c28b626a, 0x10365af0f6bf6aed]),
+    Fr([0x7fec064766cd1a6e, 0x046138816210957b, 0x6a6f3fef61d89dee, 0x0ab910a8636eb60b]),
+    Fr([0x17a95ea5901d4b4b, 0xbf8e75143d9cbf69, 0x3b9223c11bc9a755, 0x0d7564c6c4ca54e1]),
+    Fr([0xbc5c42f07ef7a405, 0x7daca377d41381e1, 0xab8a7c54bc1afbc2, 0x1e0420480d3601db]),
+    Fr([0xa44af9e83e706a72, 0x30467cc6889c68af, 0x64fadc31a380f886, 0x0c6a44b133ce5468]),
+    Fr([0x419632d8447fbbac, 0x5b160f32939cb250, 0xee8943f1c95f0506, 0x040d52be69f2ba2e]),
+    Fr([0x9fb340c109120444, 0xa3b53a70cee1b541, 0xe178d19117964e79, 0x1c316b3637a2d117]),
+    Fr([0x228e93bfbfe7b701, 0xe600eb51f58b7173, 0x1780bb02c4a7a7a7, 0x17207b2240a0ed38]),
+    Fr([0xa136f0dc41a2c061, 0x5750fef1394380b1, 0xa85d8e513997747f, 0x0a9690842279da9d]),
+    Fr([0xadc82accb4da0421, 0xb2caad1b413968ed, 0xc8a3281e29135dd0, 0x2e4e84eccf8e640d]),
+    Fr([0x0fdec635e98bc3c4, 0x8ef9ac8744b1e318, 0xc79be46ad692d392, 0x07d4e90415338a67]),
+    Fr([0x24b234f059c39fa0, 0xcf2ae865ff76e7d5, 0x13987a15fbacb417, 0x0bcebec43aec0fe4]),
+    Fr([0x22d4ce4d385ef866, 0xd6506c3b58201178, 0x83c51106691dd37f, 0x084bea5184e9bfa4]),
+    Fr([0x9c77d747263244c1, 0x659019a11195b6a9, 0x9511a61ec902de91, 0x184048c60a83e06b]),
+    Fr([0xb68afb7d17ca7f74, 0x17ced1fe273051bd, 0x3351e20e1b303ffc, 0x1bf06a7ea1655033]),
+    Fr([0x33f3e61940f148c5, 0x45a1da3e22bbd5e6, 0x9f1b6cca298e2385, 0x1758fd7f5a703fd6]),
+    Fr([0x1a929fe79e54eabc, 0xeb7a4050f23efb0e, 0xe25079a2ef3a4399, 0x24377e4f8c161340]),
+    Fr([0xd7eaf8cd38c7e4c3, 0x9d688ad8e3b18d4f, 0x3059007f8e528db3, 0x2ba5812b9a603154]),
+    Fr([0x347eefb756ac1cd3, 0xcf154053cabb2dd3, 0x40662cdec5906a43, 0x24f8c0dfb5a57c88]),
+    Fr([0x83a8b78cdf45ae1d, 0xc35a68eddc447d67, 0x1e0631f731313cf5, 0x0695de707e5015b5]),
+    Fr([0x899501116739a0b9, 0xf41302caaabda74e, 0x9daf05fa51e2b577, 0x00a1823ed5cab6c3]),
+    Fr([0xcd573c0b9ddb7d23, 0xe2330b36994c8bc8, 0x3610a9a7975d8c5e, 0x03b957e376d3b540]),
+    Fr([0x1a9e14d51c7ca94d, 0x30bf943e1a14619e, 0x1417b62b1593a092, 0x24f07dcc09f05c17]),
+    Fr([0x3786ae29f089ee8f, 0x14aab5e99ed71801, 0x2af4f353a483b985, 0x29555d4ed1fe4fbd]),
+    Fr([0x8c7e6599c82266f5, 0x8450eefe131bfeeb, 0x2141aa50ae9c1560, 0x1ee3d5f34f25a97d]),
+    Fr([0x10939cbcf5f2ac91, 0x48fae156719c7ed6, 0x9bb952c265573087, 0x272673388c49a89f]),
+    Fr([0x1987c905ef781f7c, 0x8b0b57252a061c91, 0x8daef0652916aecc, 0x0d695d161817c12c]),
+    Fr([0x9b33ef9bd4d5d3a1, 0x06c73061272cbb8a, 0x54aeb39ec55c7d9e, 0x17302c913cef778e]),
+    Fr([0x7743f9d77edc84a9, 0x0837dea3a918265c, 0xe083feca62c33d42, 0x0838d05886b1128e]),
+    Fr([0x20248167321622af, 0x925b50a1c0ac0630, 0x4999b53dc984ba90, 0x11dd2d0e2bc5e00d]),
+    Fr([0x0d246427c28da3ee, 0x949eff9804df6a12, 0xdb5f49b2f04ff98e, 0x07419b83608220ba]),
+    Fr([0x18836051a3dcfd42, 0x59cbcb56f2750e1d, 0x414992bb17367459, 0x0f97423e94ff3f15]),
+    Fr([0xebdcda471a54f684, 0x0d5b255729f4bce8, 0x8da5fb1439c21843, 0x1e4fa6abc631b4d9]),
+    Fr([0xa34c7cabbf77bff3, 0x7ed19d45e0cbd265, 0x552569b3976abf9b, 0x2102a42d31e65219]),
+    Fr([0xa71ea9feb2976fe7, 0x0bcbbc71bdbf3efd, 0x7c1de527a92b6749, 0x10bba45bc3c2b257]),
+    Fr([0xf9d6194270590ec8, 0xe32ae87ab03c6133, 0x0a79ee024465ed7d, 0x13ad9a7d9b5c4e18]),
+    Fr([0x993e031c5692c315, 0x954559258ffdd98e, 0xae079dda309985d8, 0x2f2118e35c224d0b]),
+    Fr([0x6c0c4a3ec0f29525, 0x40d6a04330fb0272, 0x04277ef7f8310f89, 0x1be9af9d3f3cfee8]),
+    Fr([0x2ef36c1ed645075c, 0x75020dd17b209435, 0x46d2d88cf270ddaf, 0x092f58dea8634aab]),
+    Fr([0xd1d946bdebc3168a, 0x50c19dc2ebcd5a66, 0x2c1ff6a7048b8dfd, 0x20f11793efb546bf]),
+    Fr([0xe5fe88403a2eeb08, 0x7e137ccf641d5355, 0x3e07224f16e015b6, 0x051aa72bbb280bd0]),
+    Fr([0x4e9878750691087e, 0xfa3cca7a6703170b, 0xaedcef07fe48b4f7, 0x0ffca06f7ba1d9ad]),
+    Fr([0x046580ad1e787eb7, 0x821d475374e3caa0, 0x570d693273ce7432, 0x098bee97eec22e0c]),
+    Fr([0xee01be092608ca38, 0x9ae8db97ec7213f0, 0xcd94e9911498661f, 0x063cebc9d48c4b16]),
+    Fr([0xc9f0f4202f5302f1, 0x9f095124684e5590, 0x77f7877d1af0b7ae, 0x2102a686942ed886]),
+    Fr([0xa922f27f28abdbef, 0xce9d05a501c4a686, 0x93af2c647cd9f97e, 0x15122514d38426a7]),
+    Fr([0x40096998f6d0958e, 0x6c9067883a006f2c, 0x47c12fa2b3df349c, 0x01e1206fd7a499b7]),
+    Fr([0x09a576cfc3ed0135, 0xc2a8ec597cda2043, 0x1e71fabe8885be3f, 0x12ffc9a6438ca230]),
+    Fr([0x3dfe98919729d14e, 0x2d1a830be6a7d93a, 0xd4b50f79ac794e45, 0x1609b588e4b6255a]),
+    Fr([0xfe0f03062f910a79, 0x12b30f8c764aacb1, 0x071e359c68255f5c, 0x05ad045acb0a6de5]),
+    Fr([0x8cac1102b2cfcafe, 0xf8641260ba9ff7b4, 0x5d408afe6356b7fc, 0x1043e7f9b8c42e63]),
+    Fr([0x8553014b785010b7, 0xf5110e3a0be2fe70, 0x7525dcae2a87117e, 0x08b4b93f80c28df6]),
+    Fr([0x0351052268ae14a8, 0x2143bff152810d7e, 0x3ba34068873880e7, 0x1a27593714889c67]),
+    Fr([0x17a2b5bd2973e07c, 0xc2adb8e2cee7fa22, 0x310f8de36e110362, 0x297d94b76f76534e]),
+    Fr([0x51d81fecad91be7f, 0x91e296b75fed0f14, 0xffb2f2fe9fa9003b, 0x033fa4e84c6e92aa]),
+    Fr([0x2b1d49f32f0b0a2a, 0x684821a554f5a304, 0x7d5412dfa7668473, 0x190fb522bec2c138]),
+    Fr([0xae690bdf16c838a1, 0xe0739bf7c039a838, 0xd6a0b76d0f102e7a, 0x1796dc30c3f70ce5]),
+    Fr([0x1c7e2cddb2a6b28f, 0xb1f864b85b8532d0, 0x6625e36443287b36, 0x056506367354a3b7]),
+    Fr([0x2049bfbcfd6d6a28, 0x7bb4303d8fe81185, 0x127dffc4f0755310, 0x07d45d0757035ca3]),
+    Fr([0x9a97fc22bec1eaaf, 0x99b2914b133d0a3c, 0x0b3303d9730f13dc, 0x0a1a930ed357c473]),
+    Fr([0x20b55d2bcbdb3803, 0x8b6d6827e85599f3, 0x9cd20f39c515325e, 0x2347c266123dc523]),
+    Fr([0x9b51e3ba2ec67900, 0xc28fcb69601ce480, 0xddf62f3c201ec992, 0x1fa93e2f249f1654]),
+    Fr([0xe943d3aeb953c01d, 0x1f213d4759df3a59, 0x30120876a9a18dc5, 0x2e935d6c61eb0b0c]),
+    Fr([0x474a1da3de1f1503, 0x74548e96ca496b0c, 0x09d52791d4720de0, 0x287c6b0f41a01467]),
+    Fr([0xde16e2e8d1926b38, 0x64df4a6554e07f8e, 0xa1fb13776df1fe5d, 0x1df9d6c9bc67b3b5]),
+    Fr([0x290c14f0b6bbbb52, 0xec655f07fa1a6ab2, 0x17209b5800230840, 0x2bcafcaf1e5c23e8]),
+    Fr([0xc9692a287a3a4a41, 0x3f0236b75b1a5fd2, 0xd7a37108023f3112, 0x21dfaa273c3c49a9]),
+    Fr([0x22efb83e46a2c0d5, 0x81d6dda51c8cd637, 0xece59d1ed0b018fe, 0x1a2b0b4e5e72603b]),
+    Fr([0x6dc9066fa1149517, 0x0e99467d5b969478, 0x0932a4ccf44e25c6, 0x0229273fa914e12d]),
+    Fr([0xa8d254dbf311d474, 0xa1c9abd8fccc263f, 0x936223ae609ea10b, 0x25a33a3a18ad9b95]),
+    Fr([0x3ba57c39fe16e1d0, 0xaa9caacc92542499, 0x63b371a57ea9d439, 0x203a0099556072b4]),
+    Fr([0xbfd1e6093770dedc, 0x7c61f5d15aec7adc, 0xab8c467264b182b7, 0x0ac513dce3098f42]),
+    Fr([0x09556764d13dce43, 0x789b6b398ecf3ca2, 0x71e1625ae8cc5d65, 0x1b9b78909989620b]),
+    Fr([0xbaf9b099361f4566, 0xad09d1eb348485ec, 0xb47e86241e7fd8db, 0x07cecc143e7d6575]),
+    Fr([0x42a2d8bf759fa82c, 0xced734cd2133b372, 0xe0024cd88930e2ae, 0x22318af5182b6ca1]),
+    Fr([0xc354d05b1e3564e2, 0xfd7188670b9643cc, 0x14fd18729642b4a0, 0x0d51272bfa735857]),
+    Fr([0xb348063f00c99814, 0xa5412848daaee43c, 0xaf55ed76215373ce, 0x124e428f009d373e]),
+    Fr([0xe57c360aae3f34a8, 0xf42de83ed2de316b, 0x30897a6c5757d3b4, 0x04e995db28ad4d2a]),
+    Fr([0xe13e119bc37d6c8f, 0x02f61b5d76f759b0, 0xc6b47d236891e176, 0x0008b17e2a72a2ae]),
+    Fr([0x2fd8a24755180b46, 0x0a5ebff9fa6f6f10, 0x1dfc8b1c4773facc, 0x00d822599162dfdf]),
+    Fr([0xb0a0b5dcfa3b9c38, 0xc1e38c7e2e80c619, 0x30f65a8c3959e3c5, 0x21debd0d608920ba]),
+    Fr([0x9793629a20e98afa, 0xb0f9a2eaea103653, 0x2120c27bba6653b2, 0x2d12eee484a0036f]),
+    Fr([0xceeb2c202b1fbe1f, 0x2fe541acbf8c0498, 0x2aba3e57e10506a1, 0x08ba122fcb735020]),
+    Fr([0x9828204e8e2b6a9c, 0x5a3a6f15eeb867e0, 0x32454dd332b17a31, 0x0b31858317cb478c]),
+    Fr([0xd8b13dfb140e44ec, 0x96c29b6ffe448479, 0x9b635302d1a0c781, 0x086661ac08af1243]),
+    Fr([0x111a91f7ca2de5fe, 0xed1c30d4bc5f9da7, 0xace7a99a136179c6, 0x1af7af57638f7afc]),
+    Fr([0xf2ebc65a68301f70, 0x098438fc5cfb70fa, 0x1804b70dbc7519fa, 0x1d22f3c500139637]),
+    Fr([0x5448cb4bc2899170, 0xf7fe3031d6326ee3, 0x78948b2f4ee9574d, 0x1005a13172350117]),
+    Fr([0x682b542cad358ff3, 0x5724e9dd9fc8f36b, 0x2ba5e23128ed1f4a, 0x11c31ebae126fd4f]),
+    Fr([0x477f38901e5952b2, 0x9d1a3f4d707c35e0, 0x91302c538bb1afaa, 0x063894ace6abbc7e]),
+    Fr([0x6074b47ef0006e30, 0x5986d0b36ae50101, 0x20d19dafa473c211, 0x2f173440a4883865]),
+    Fr([0xaa20964deafe0b91, 0xe2a7e6ca5edd4a1f, 0x057ba486f576e66d, 0x205bf32d4e1481c5]),
+    Fr([0x8e742f9ec6841d0b, 0x31061ccfad5a5217, 0x7b71500b33057f2a, 0x16daea714103de31]),
+    Fr([0x11abbad157bd1752, 0x086b0bfa4ca1a650, 0x620544b4b2dc4a34, 0x0bc77c690c4727e9]),
+    Fr([0xd592b3bd0452454e, 0xfcb2a667b4f8a458, 0xb0591d7fa9013240, 0x181ad6d3181e4869]),
+    Fr([0x75df9ada1c6e61c7, 0xe7865ac098d96a8d, 0x4e79b52ca624a1bb, 0x2eb9734444d29531]),
+    Fr([0x56252281af9dd325, 0x2b56cd8ca643e6aa, 0x001bdcf70d097969, 0x050fcb4ec2ddeb58]),
+    Fr([0xc520cddd629cf7cf, 0x1dfe1f44cce3fc7f, 0x82e70c14357cc3ca, 0x1e529e7b2aad97ea]),
+    Fr([0xad0e31cb9df06818, 0x981d398bf7cbcb97, 0xb27a6760ea88d928, 0x29acb22cdc4819cc]),
+    Fr([0x018bb277e22b3484, 0x5112b64eddb72d72, 0xf78b3bcffa1ad009, 0x11829e68b5f4754b]),
+    Fr([0x7479e5e3057e0adf, 0xde6f62ade16366ac, 0x5dafb1e94b75a7a3, 0x05439e7c6850556d]),
+    Fr([0x429db1686714e521, 0x4069a1ff88a85207, 0xa771b0afd8bee2df, 0x03fce5510d8086a4]),
+    Fr([0x38b2bf4062f50734, 0xe9ce00492ef1cae8, 0x327429d62062a64a, 0x1cc78575b13144a3]),
+    Fr([0x8fe7bf8806045b99, 0x0eaf5157e1a05477, 0x509622ea5ead2b6e, 0x0348bc748879507c]),
+    Fr([0xa10163a768b038fb, 0x8c2d3eabdce9be3f, 0xb2bbe02028e58741, 0x20d27adef4ab0768]),
+    Fr([0x7233916fa65363e1, 0x67395b53fbf62a40, 0x877b9c7ee0adb055, 0x2260000302501716]),
+];
+
+/// MDS matrix of the width-5 BN254 Poseidon permutation, in Montgomery form.
+#[rustfmt::skip]
+pub(crate) const MDS_MATRIX: [[Fr; WIDTH]; WIDTH] = [
+    [
+        Fr([0x77464b55cd95efca, 0x68ba7a74ae0e5894, 0xbd4dc1c2266c359d, 0x2967c834940e37a0]),
+        Fr([0x9d7560eab0fe4046, 0x35aebb7e1cbabfde, 0x046f4c2b5ffaab98, 0x10c9d5b18c43b9ea]),
+        Fr([0xb866652e4f26da85, 0xb9e2d4c767608cb5, 0x7266982acf0812ff, 0x1075bbdae372b70d]),
+        Fr([0x6190b23770183886, 0x101d044302cb2858, 0xecd03dccfbeaf617, 0x0b084598422035a5]),
+        Fr([0x71d451ca47c3e06f, 0x1a4dc1da0d245f85, 0x4812497a20f7afce, 0x02d1c2ecb1969e4b]),
+    ],
+    [
+        Fr([0x6907e36200995439, 0xb9f80b5666c65169, 0x7ba328f07ebc2640, 0x152d921c334deb59]),
+        Fr([0x9de26ee0faaa6230, 0x8b3cedd3678272c4, 0xbf689106033676ec, 0x0a4f014b431ef663]),
+        Fr([0x0abe2754c2279be8, 0xf34d6acdb0ef8be1, 0x638c985fb12509f5, 0x0ce4a0756717cd0d]),
+        Fr([0x4ff66343628de773, 0x8669e3967283e9d5, 0xdbdb4492fd9478a1, 0x2a172f4971297058]),
+        Fr([0xa96b93484bd7274b, 0xb6ffb6120bbc6f39, 0x4f8cc3b20738a669, 0x26d0dab233956299]),
+    ],
+    [
+        Fr([0x235bc3071b88c57f, 0x1edd9e8b512a928b, 0x4eba9db9a285a5db, 0x208c85cecd6e86b2]),
+        Fr([0x8b7a04145ef1d11a, 0xed5ccb60d2f55df9, 0xc0463074d5d84b7c, 0x0fc883bdcf417770]),
+        Fr([0x16ef19d92023860d, 0x97313a990cdaa693, 0xfa536002a38deb76, 0x157c584bf12b5fc2]),
+        Fr([0x26b36d6f81141445, 0x46db4e5f5c0c0592, 0x1c8ff6641950ef7f, 0x03831bb3c0404ec0]),
+        Fr([0xe693b6e9a4a622a4, 0xd3c7b489ce3e9706, 0x97a65d65e20440eb, 0x01c50a5a391d3e7f]),
+    ],
+    [
+        Fr([0xd7e96fada4cc7131, 0xe05eeb104bdd4f26, 0xd629a31acc8b39c6, 0x292e987009256cb4]),
+        Fr([0xc7a0f540e19091eb, 0xd6b9fc0427f1efb4, 0xd709082fce71505b, 0x2c2f39bf3fb689c1]),
+        Fr([0x32ec79c4fa39b5e0, 0x07e1d8f6dc66882f, 0xdafcf6f32b1b7f1f, 0x0b80626e4af5efe5]),
+        Fr([0x48268958c0294633, 0xe32eaddae7cd0cfb, 0x83f515af535c5f73, 0x0eb68faa42851083]),
+        Fr([0xde28a4428ec83e3a, 0xc302d6eb2a211388, 0x78e5ca7195aeb86e, 0x1f159c9528951410]),
+    ],
+    [
+        Fr([0x9337ce2160d27631, 0xb7603b2e38f0d93e, 0xba04b96b55dfec38, 0x25c45b9bb527b189]),
+        Fr([0x570517f8d7bf3625, 0x06f64bcced634daf, 0x85747cad8e788981, 0x240f49cb93d117d5]),
+        Fr([0x74572ba3822678b6, 0x1178400143204c5f, 0x46e8e28cd12c3a6f, 0x10b1d99213e5666e]),
+        Fr([0x1c641486ade67a7a, 0x4b50719a5e10222c, 0x9f5dd44f4cc1d827, 0x01b5b9eef181679f]),
+        Fr([0xfeb302a5110d9eb0, 0xc251af52f6c4abc6, 0xff454cd9ef575da7, 0x1ab6f8eace913fdb]),
+    ],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{permute, Fr, WIDTH};
+
+    #[test]
+    fn montgomery_round_trip() {
+        let limbs = [
+            0x0123456789abcdef,
+            0xfedcba9876543210,
+            0x0f1e2d3c4b5a6978,
+            0x1020304050607080,
+        ];
+        assert_eq!(Fr::from_canonical_limbs(limbs).to_canonical_limbs(), limbs);
+    }
+
+    #[test]
+    fn test_vector() {
+        // Width-5 Poseidon of circomlib on the state [0, 1, 2, 3, 4]; the first element equals
+        // `poseidon([1, 2, 3, 4])` of circomlibjs.
+        let mut state: [Fr; WIDTH] =
+            core::array::from_fn(|i| Fr::from_canonical_limbs([i as u64, 0, 0, 0]));
+        permute(&mut state);
+        assert_eq!(
+            state[0].to_canonical_limbs(),
+            [
+                0xbaa525df65250465,
+                0x37e60ebb1ce0663d,
+                0x9dcefa40e4510b98,
+                0x299c867db6c1fdd7,
+            ]
+        );
+    }
+}
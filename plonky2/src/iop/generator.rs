@@ -1,14 +1,19 @@
 #[cfg(not(feature = "std"))]
 use alloc::{
     boxed::Box,
+    collections::BTreeMap,
     string::{String, ToString},
     vec,
     vec::Vec,
 };
 use core::fmt::Debug;
 use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
 
 use dyn_clone::DynClone;
+#[cfg(feature = "parallel")]
+use plonky2_maybe_rayon::*;
 
 use crate::field::extension::Extendable;
 use crate::field::types::Field;
@@ -16,7 +21,9 @@ use crate::hash::hash_types::RichField;
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::Target;
 use crate::iop::wire::Wire;
-use crate::iop::witness::{PartialWitness, PartitionWitness, Witness, WitnessWrite};
+use crate::iop::witness::{
+    PartialWitness, PartitionWitness, StuckGenerator, Witness, WitnessError, WitnessWrite,
+};
 use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
 use crate::plonk::config::GenericConfig;
 use crate::util::serialization::{Buffer, IoResult, Read, Write};
@@ -33,6 +40,21 @@ pub fn generate_partial_witness<
     prover_data: &'a ProverOnlyCircuitData<F, C, D>,
     common_data: &'a CommonCircuitData<F, D>,
 ) -> PartitionWitness<'a, F> {
+    try_generate_partial_witness(inputs, prover_data, common_data).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// Like `generate_partial_witness`, but returns an error instead of panicking if a target is set
+/// to conflicting values, or if some generators never run.
+pub fn try_generate_partial_witness<
+    'a,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    inputs: PartialWitness<F>,
+    prover_data: &'a ProverOnlyCircuitData<F, C, D>,
+    common_data: &'a CommonCircuitData<F, D>,
+) -> Result<PartitionWitness<'a, F>, WitnessError<F>> {
     let config = &common_data.config;
     let generators = &prover_data.generators;
     let generator_indices_by_watches = &prover_data.generator_indices_by_watches;
@@ -44,16 +66,45 @@ pub fn generate_partial_witness<
     );
 
     for (t, v) in inputs.target_values.into_iter() {
-        witness.set_target(t, v);
+        witness.try_set_target_returning_rep(t, v)?;
+    }
+
+    let generator_is_expired =
+        run_generators(&mut witness, generators, generator_indices_by_watches)?;
+    let stuck_generators = generators
+        .iter()
+        .zip(generator_is_expired)
+        .filter(|(_, expired)| !expired)
+        .map(|(generator, _)| StuckGenerator {
+            id: generator.0.id(),
+            missing: generator
+                .0
+                .watch_list()
+                .into_iter()
+                .filter(|&target| witness.try_get_target(target).is_none())
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    if !stuck_generators.is_empty() {
+        return Err(WitnessError::GeneratorsNotRun(stuck_generators));
     }
 
+    Ok(witness)
+}
+
+/// Runs generators until none of them can make progress, and returns which generators finished.
+#[cfg(not(feature = "parallel"))]
+fn run_generators<F: RichField + Extendable<D>, const D: usize>(
+    witness: &mut PartitionWitness<F>,
+    generators: &[WitnessGeneratorRef<F, D>],
+    generator_indices_by_watches: &BTreeMap<usize, Vec<usize>>,
+) -> Result<Vec<bool>, WitnessError<F>> {
     // Build a list of "pending" generators which are queued to be run. Initially, all generators
     // are queued.
     let mut pending_generator_indices: Vec<_> = (0..generators.len()).collect();
 
     // We also track a list of "expired" generators which have already returned false.
     let mut generator_is_expired = vec![false; generators.len()];
-    let mut remaining_generators = generators.len();
 
     let mut buffer = GeneratedValues::empty();
 
@@ -66,21 +117,17 @@ pub fn generate_partial_witness<
                 continue;
             }
 
-            let finished = generators[generator_idx].0.run(&witness, &mut buffer);
+            let finished = generators[generator_idx].0.run(witness, &mut buffer);
             if finished {
                 generator_is_expired[generator_idx] = true;
-                remaining_generators -= 1;
             }
 
-            // Merge any generated values into our witness, and get a list of newly-populated
-            // targets' representatives.
-            let new_target_reps = buffer
-                .target_values
-                .drain(..)
-                .flat_map(|(t, v)| witness.set_target_returning_rep(t, v));
-
-            // Enqueue unfinished generators that were watching one of the newly populated targets.
-            for watch in new_target_reps {
+            // Merge any generated values into our witness, and enqueue unfinished generators that
+            // were watching one of the newly populated targets.
+            for (t, v) in buffer.target_values.drain(..) {
+                let Some(watch) = witness.try_set_target_returning_rep(t, v)? else {
+                    continue;
+                };
                 let opt_watchers = generator_indices_by_watches.get(&watch);
                 if let Some(watchers) = opt_watchers {
                     for &watching_generator_idx in watchers {
@@ -95,13 +142,58 @@ pub fn generate_partial_witness<
         pending_generator_indices = next_pending_generator_indices;
     }
 
-    assert_eq!(
-        remaining_generators, 0,
-        "{} generators weren't run",
-        remaining_generators,
-    );
+    Ok(generator_is_expired)
+}
+
+/// Runs generators until none of them can make progress, and returns which generators finished.
+///
+/// Generators are run in rounds. All the generators queued for a round are run concurrently on
+/// the same witness, then their outputs are merged into the witness in the order of their indices,
+/// so that the witness, and the detection of conflicting writes, do not depend on scheduling. A
+/// generator is queued for the next round when one of the targets it watches gets populated.
+#[cfg(feature = "parallel")]
+fn run_generators<F: RichField + Extendable<D>, const D: usize>(
+    witness: &mut PartitionWitness<F>,
+    generators: &[WitnessGeneratorRef<F, D>],
+    generator_indices_by_watches: &BTreeMap<usize, Vec<usize>>,
+) -> Result<Vec<bool>, WitnessError<F>> {
+    let mut pending_generator_indices: Vec<_> = (0..generators.len()).collect();
+    let mut generator_is_expired = vec![false; generators.len()];
+
+    while !pending_generator_indices.is_empty() {
+        let witness_ref = &*witness;
+        let outputs = pending_generator_indices
+            .par_iter()
+            .map(|&generator_idx| {
+                let mut buffer = GeneratedValues::empty();
+                let finished = generators[generator_idx].0.run(witness_ref, &mut buffer);
+                (finished, buffer)
+            })
+            .collect::<Vec<_>>();
+
+        let mut next_pending_generator_indices = Vec::new();
+        for (&generator_idx, (finished, buffer)) in pending_generator_indices.iter().zip(outputs) {
+            if finished {
+                generator_is_expired[generator_idx] = true;
+            }
+
+            for (t, v) in buffer.target_values {
+                if let Some(watch) = witness.try_set_target_returning_rep(t, v)? {
+                    if let Some(watchers) = generator_indices_by_watches.get(&watch) {
+                        next_pending_generator_indices.extend_from_slice(watchers);
+                    }
+                }
+            }
+        }
 
-    witness
+        next_pending_generator_indices.sort_unstable();
+        next_pending_generator_indices.dedup();
+        next_pending_generator_indices
+            .retain(|&generator_idx| !generator_is_expired[generator_idx]);
+        pending_generator_indices = next_pending_generator_indices;
+    }
+
+    Ok(generator_is_expired)
 }
 
 /// A generator participates in the generation of the witness.
@@ -120,6 +212,18 @@ where
     /// run next time a target in its watch list is populated.
     fn run(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) -> bool;
 
+    /// Whether the values written by this generator are random, such as blinding factors, and so
+    /// need no constraint.
+    fn writes_random_values(&self) -> bool {
+        false
+    }
+
+    /// Whether this generator answers a lookup. Its inputs must then be in a lookup table, and the
+    /// values it writes are constrained by the lookup argument.
+    fn is_lookup(&self) -> bool {
+        false
+    }
+
     fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()>;
 
     fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self>
@@ -219,6 +323,16 @@ pub trait SimpleGenerator<F: RichField + Extendable<D>, const D: usize>:
 
     fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>);
 
+    /// See [`WitnessGenerator::writes_random_values`].
+    fn writes_random_values(&self) -> bool {
+        false
+    }
+
+    /// See [`WitnessGenerator::is_lookup`].
+    fn is_lookup(&self) -> bool {
+        false
+    }
+
     fn adapter(self) -> SimpleGeneratorAdapter<F, Self, D>
     where
         Self: Sized,
@@ -266,6 +380,14 @@ impl<F: RichField + Extendable<D>, SG: SimpleGenerator<F, D>, const D: usize> Wi
         }
     }
 
+    fn writes_random_values(&self) -> bool {
+        self.inner.writes_random_values()
+    }
+
+    fn is_lookup(&self) -> bool {
+        self.inner.is_lookup()
+    }
+
     fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
         self.inner.serialize(dst, common_data)
     }
@@ -331,6 +453,10 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Ran
         out_buffer.set_target(self.target, random_value);
     }
 
+    fn writes_random_values(&self) -> bool {
+        true
+    }
+
     fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
         dst.write_target(self.target)
     }
@@ -429,3 +555,141 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Con
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::hash::poseidon::PoseidonHash;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+    use crate::plonk::prover::ProveError;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn build_circuit(builder: &mut CircuitBuilder<F, D>) -> Vec<Target> {
+        let inputs = builder.add_virtual_targets(16);
+        // A long chain of dependent generators...
+        let mut power = inputs[0];
+        for _ in 0..100 {
+            power = builder.mul(power, power);
+        }
+        builder.register_public_input(power);
+        // ...next to many independent ones.
+        for &input in &inputs {
+            let hash = builder.hash_n_to_hash_no_pad::<PoseidonHash>(vec![input, power]);
+            builder.register_public_inputs(&hash.elements);
+        }
+        inputs
+    }
+
+    #[test]
+    fn test_generate_partial_witness() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let inputs = build_circuit(&mut builder);
+        let data = builder.build::<C>();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        build_circuit(&mut builder);
+        let mock_data = builder.mock_build::<C>();
+
+        let values = F::rand_vec(inputs.len());
+        let mut pw = PartialWitness::new();
+        pw.set_target_arr(&inputs, &values);
+        let witness = generate_partial_witness(pw.clone(), &data.prover_only, &data.common);
+        let expected_power = (0..100).fold(values[0], |x, _| x * x);
+        assert_eq!(
+            witness.get_target(data.prover_only.public_inputs[0]),
+            expected_power
+        );
+
+        // The mock prover generates the witness serially. Unused wires of the public input gate
+        // are random.
+        let expected_witness = mock_data.check_witness(pw).unwrap();
+        let pi_gate = (mock_data.layout.gate_instances.iter())
+            .position(|gate| gate.gate_ref.0.id() == "PublicInputGate")
+            .unwrap();
+        for row in (0..data.common.degree()).filter(|&row| row != pi_gate) {
+            for column in 0..config.num_wires {
+                let target = Target::wire(row, column);
+                assert_eq!(
+                    witness.try_get_target(target),
+                    expected_witness.try_get_target(target)
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn test_conflicting_generated_values() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let x_squared = builder.square(x);
+        let y_squared = builder.square(y);
+        builder.connect(x_squared, y_squared);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::ONE);
+        pw.set_target(y, F::TWO);
+        generate_partial_witness(pw, &data.prover_only, &data.common);
+    }
+
+    #[test]
+    fn test_try_generate_partial_witness() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let x_squared = builder.square(x);
+        builder.register_public_input(x_squared);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+
+        // `y` is never set, so the hashing of public inputs is stuck on a wire copied from `y`.
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::TWO);
+        match try_generate_partial_witness(pw, &data.prover_only, &data.common) {
+            Err(WitnessError::GeneratorsNotRun(generators)) => {
+                assert_eq!(generators.len(), 1);
+                assert_eq!(generators[0].id, "PoseidonGenerator");
+                let rep = |t: Target| {
+                    let index = t.index(data.common.config.num_wires, data.common.degree());
+                    data.prover_only.representative_map[index]
+                };
+                assert_eq!(generators[0].missing.len(), 1);
+                assert_eq!(rep(generators[0].missing[0]), rep(y));
+            }
+            _ => panic!("Expected stuck generators"),
+        }
+
+        // `x_squared` is set to a value conflicting with the generated one.
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::TWO);
+        pw.set_target(y, F::ONE);
+        pw.set_target(x_squared, F::from_canonical_u64(5));
+        match data.try_prove(pw) {
+            Err(ProveError::Witness(WitnessError::ConflictingValues {
+                value,
+                other_value,
+                ..
+            })) => {
+                assert_eq!(value, F::from_canonical_u64(4));
+                assert_eq!(other_value, F::from_canonical_u64(5));
+            }
+            _ => panic!("Expected conflicting values"),
+        }
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::TWO);
+        pw.set_target(y, F::ONE);
+        let proof = data.try_prove(pw).unwrap();
+        data.verify(proof).unwrap();
+    }
+}
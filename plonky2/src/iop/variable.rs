@@ -0,0 +1,334 @@
+//! Structured circuit variables.
+//!
+//! A [`CircuitVariable`] is a value made of a fixed number of targets, such as a `HashOutTarget`
+//! or a tuple of targets. Gadgets which only move targets around, like
+//! [`CircuitBuilder::connect_variables`], [`CircuitBuilder::select_variable`] or
+//! [`CircuitBuilder::random_access_variable`], work on any variable, so a composite value can be
+//! handled as a whole rather than limb by limb. Types whose number of targets depends on the
+//! circuit, such as `MerkleCapTarget`, aren't variables.
+//!
+//! Structs whose fields are all variables can derive the trait:
+//!
+//! ```ignore
+//! #[derive(Clone, Debug, CircuitVariable)]
+//! struct Account {
+//!     balance: U32Target,
+//!     key: HashOutTarget,
+//! }
+//! ```
+//!
+//! This also defines an `AccountValue<F>` struct, with the same fields holding the values of
+//! these variables, which is the type used to set and read an `Account` in a witness.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::fmt::Debug;
+
+use itertools::zip_eq;
+pub use plonky2_derive::CircuitVariable;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::{HashOut, HashOutTarget, RichField, NUM_HASH_OUT_ELTS};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// A value represented in a circuit by a fixed number of targets.
+pub trait CircuitVariable: Clone + Debug {
+    /// The value of this variable in a witness of a circuit over `F`.
+    type Value<F: RichField>: Clone + Debug + PartialEq;
+
+    /// The number of targets making up this variable.
+    fn num_targets() -> usize;
+
+    /// The targets making up this variable, in the order expected by `from_targets`.
+    fn targets(&self) -> Vec<Target>;
+
+    /// Builds a variable from its targets, without adding any constraint on them.
+    ///
+    /// Panics if `targets` doesn't have exactly `num_targets()` elements.
+    fn from_targets(targets: &[Target]) -> Self;
+
+    /// Adds a new virtual variable to the circuit. This adds the same constraints as the
+    /// corresponding `add_virtual_*` method of the builder, e.g. `BoolTarget`s are constrained to
+    /// be boolean, while `Target`s are unconstrained.
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        Self::from_targets(&builder.add_virtual_targets(Self::num_targets()))
+    }
+
+    fn set_witness<F: RichField, W: WitnessWrite<F>>(
+        &self,
+        witness: &mut W,
+        value: &Self::Value<F>,
+    );
+
+    fn get_witness<F: RichField, W: Witness<F>>(&self, witness: &W) -> Self::Value<F>;
+
+    /// Registers the targets of this variable as public inputs.
+    fn register_public_input<F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+    ) {
+        builder.register_public_inputs(&self.targets());
+    }
+}
+
+impl CircuitVariable for Target {
+    type Value<F: RichField> = F;
+
+    fn num_targets() -> usize {
+        1
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        vec![*self]
+    }
+
+    fn from_targets(targets: &[Target]) -> Self {
+        assert_eq!(targets.len(), 1);
+        targets[0]
+    }
+
+    fn set_witness<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &F) {
+        witness.set_target(*self, *value);
+    }
+
+    fn get_witness<F: RichField, W: Witness<F>>(&self, witness: &W) -> F {
+        witness.get_target(*self)
+    }
+}
+
+impl CircuitVariable for BoolTarget {
+    type Value<F: RichField> = bool;
+
+    fn num_targets() -> usize {
+        1
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        vec![self.target]
+    }
+
+    fn from_targets(targets: &[Target]) -> Self {
+        assert_eq!(targets.len(), 1);
+        BoolTarget::new_unsafe(targets[0])
+    }
+
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        builder.add_virtual_bool_target_safe()
+    }
+
+    fn set_witness<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &bool) {
+        witness.set_bool_target(*self, *value);
+    }
+
+    fn get_witness<F: RichField, W: Witness<F>>(&self, witness: &W) -> bool {
+        witness.get_bool_target(*self)
+    }
+}
+
+impl CircuitVariable for HashOutTarget {
+    type Value<F: RichField> = HashOut<F>;
+
+    fn num_targets() -> usize {
+        NUM_HASH_OUT_ELTS
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        self.elements.to_vec()
+    }
+
+    fn from_targets(targets: &[Target]) -> Self {
+        HashOutTarget::from_vec(targets.to_vec())
+    }
+
+    fn set_witness<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &HashOut<F>) {
+        witness.set_hash_target(*self, *value);
+    }
+
+    fn get_witness<F: RichField, W: Witness<F>>(&self, witness: &W) -> HashOut<F> {
+        witness.get_hash_target(*self)
+    }
+}
+
+impl<V: CircuitVariable, const N: usize> CircuitVariable for [V; N] {
+    type Value<F: RichField> = [V::Value<F>; N];
+
+    fn num_targets() -> usize {
+        N * V::num_targets()
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        self.iter().flat_map(V::targets).collect()
+    }
+
+    fn from_targets(targets: &[Target]) -> Self {
+        assert_eq!(targets.len(), Self::num_targets());
+        let n = V::num_targets();
+        core::array::from_fn(|i| V::from_targets(&targets[i * n..(i + 1) * n]))
+    }
+
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        core::array::from_fn(|_| V::add_virtual(builder))
+    }
+
+    fn set_witness<F: RichField, W: WitnessWrite<F>>(
+        &self,
+        witness: &mut W,
+        value: &Self::Value<F>,
+    ) {
+        for (x, v) in zip_eq(self, value) {
+            x.set_witness(witness, v);
+        }
+    }
+
+    fn get_witness<F: RichField, W: Witness<F>>(&self, witness: &W) -> Self::Value<F> {
+        core::array::from_fn(|i| self[i].get_witness(witness))
+    }
+}
+
+macro_rules! impl_circuit_variable_for_tuple {
+    ($($var:ident: $idx:tt),+) => {
+        impl<$($var: CircuitVariable),+> CircuitVariable for ($($var,)+) {
+            type Value<F: RichField> = ($($var::Value<F>,)+);
+
+            fn num_targets() -> usize {
+                0 $(+ $var::num_targets())+
+            }
+
+            fn targets(&self) -> Vec<Target> {
+                let mut targets = Vec::with_capacity(Self::num_targets());
+                $(targets.extend(self.$idx.targets());)+
+                targets
+            }
+
+            fn from_targets(targets: &[Target]) -> Self {
+                assert_eq!(targets.len(), Self::num_targets());
+                let mut targets = targets.iter().copied();
+                ($($var::from_targets(
+                    &targets.by_ref().take($var::num_targets()).collect::<Vec<_>>(),
+                ),)+)
+            }
+
+            fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+                builder: &mut CircuitBuilder<F, D>,
+            ) -> Self {
+                ($($var::add_virtual(builder),)+)
+            }
+
+            fn set_witness<F: RichField, W: WitnessWrite<F>>(
+                &self,
+                witness: &mut W,
+                value: &Self::Value<F>,
+            ) {
+                $(self.$idx.set_witness(witness, &value.$idx);)+
+            }
+
+            fn get_witness<F: RichField, W: Witness<F>>(&self, witness: &W) -> Self::Value<F> {
+                ($(self.$idx.get_witness(witness),)+)
+            }
+        }
+    };
+}
+
+impl_circuit_variable_for_tuple!(V0: 0);
+impl_circuit_variable_for_tuple!(V0: 0, V1: 1);
+impl_circuit_variable_for_tuple!(V0: 0, V1: 1, V2: 2);
+impl_circuit_variable_for_tuple!(V0: 0, V1: 1, V2: 2, V3: 3);
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Adds a new virtual variable. See [`CircuitVariable::add_virtual`].
+    pub fn add_virtual_variable<V: CircuitVariable>(&mut self) -> V {
+        V::add_virtual(self)
+    }
+
+    /// Constrains the variables `x` and `y` to be equal.
+    pub fn connect_variables<V: CircuitVariable>(&mut self, x: &V, y: &V) {
+        for (a, b) in zip_eq(x.targets(), y.targets()) {
+            self.connect(a, b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::{Field, Sample};
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[derive(Clone, Debug, CircuitVariable)]
+    struct Leaf {
+        hash: HashOutTarget,
+        flags: [BoolTarget; 2],
+        pair: (Target, BoolTarget),
+    }
+
+    #[derive(Clone, Debug, CircuitVariable)]
+    struct Wrapper(Leaf, Target);
+
+    fn random_leaf(i: u64) -> LeafValue<F> {
+        LeafValue {
+            hash: HashOut::rand(),
+            flags: [i % 2 == 0, i % 3 == 0],
+            pair: (F::from_canonical_u64(i), i > 1),
+        }
+    }
+
+    #[test]
+    fn test_derived_variable() -> Result<()> {
+        assert_eq!(Leaf::num_targets(), NUM_HASH_OUT_ELTS + 4);
+        assert_eq!(Wrapper::num_targets(), Leaf::num_targets() + 1);
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let leaves: [Leaf; 4] = builder.add_virtual_variable();
+        let index = builder.add_virtual_target();
+        let b = builder.add_virtual_variable::<BoolTarget>();
+
+        let selected = builder.random_access_variable(index, &leaves);
+        let chosen = builder.select_variable(b, &leaves[0], &leaves[1]);
+        let wrapper = Wrapper(chosen, index);
+        wrapper.register_public_input(&mut builder);
+        let expected = builder.add_virtual_variable::<Leaf>();
+        builder.connect_variables(&selected, &expected);
+
+        let round_trip = Wrapper::from_targets(&wrapper.targets());
+        assert_eq!(round_trip.targets(), wrapper.targets());
+        let data = builder.build::<C>();
+
+        let values = [0, 1, 2, 3].map(random_leaf);
+        let mut pw = PartialWitness::new();
+        leaves.set_witness(&mut pw, &values);
+        assert_eq!(leaves.get_witness(&pw), values);
+        index.set_witness(&mut pw, &F::TWO);
+        b.set_witness(&mut pw, &false);
+        expected.set_witness(&mut pw, &values[2]);
+        let proof = data.prove(pw)?;
+
+        let chosen_value = &values[1];
+        let expected_public_inputs = [
+            chosen_value.hash.elements.to_vec(),
+            chosen_value.flags.map(F::from_bool).to_vec(),
+            vec![
+                chosen_value.pair.0,
+                F::from_bool(chosen_value.pair.1),
+                F::TWO,
+            ],
+        ];
+        assert_eq!(proof.public_inputs, expected_public_inputs.concat());
+        Ok(data.verify(proof)?)
+    }
+}
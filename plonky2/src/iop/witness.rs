@@ -1,5 +1,6 @@
 #[cfg(not(feature = "std"))]
-use alloc::{vec, vec::Vec};
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::{self, Display, Formatter};
 
 use hashbrown::HashMap;
 use itertools::{zip_eq, Itertools};
@@ -8,6 +9,7 @@ use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::types::Field;
 use crate::fri::structure::{FriOpenings, FriOpeningsTarget};
 use crate::fri::witness_util::set_fri_proof_target;
+use crate::gadgets::dynamic_array::{ArrayTarget, BytesTarget};
 use crate::hash::hash_types::{HashOut, HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::ext_target::ExtensionTarget;
@@ -17,6 +19,61 @@ use crate::plonk::circuit_data::{VerifierCircuitTarget, VerifierOnlyCircuitData}
 use crate::plonk::config::{AlgebraicHasher, GenericConfig};
 use crate::plonk::proof::{Proof, ProofTarget, ProofWithPublicInputs, ProofWithPublicInputsTarget};
 
+/// An error while generating a witness.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WitnessError<F: Field> {
+    /// A target was set to a different value than its partition, i.e. than the targets it is
+    /// copy-constrained to.
+    ConflictingValues {
+        target: Target,
+        value: F,
+        /// A target of the same partition, which already had `other_value`.
+        other_target: Target,
+        other_value: F,
+    },
+    /// Some generators never finished, as targets they watch were never set.
+    GeneratorsNotRun(Vec<StuckGenerator>),
+}
+
+/// A generator which never finished.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StuckGenerator {
+    /// The ID of the generator.
+    pub id: String,
+    /// The targets watched by the generator which were never set.
+    pub missing: Vec<Target>,
+}
+
+impl<F: Field> Display for WitnessError<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConflictingValues {
+                target,
+                value,
+                other_target,
+                other_value,
+            } => write!(
+                f,
+                "Partition containing {target:?} was set twice with different values: {other_value} != {value} ({other_target:?} = {other_value})"
+            ),
+            Self::GeneratorsNotRun(generators) => {
+                write!(f, "{} generators weren't run:", generators.len())?;
+                for generator in generators {
+                    write!(
+                        f,
+                        " {} is waiting on {:?};",
+                        generator.id, generator.missing
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F: Field> std::error::Error for WitnessError<F> {}
+
 pub trait WitnessWrite<F: Field> {
     fn set_target(&mut self, target: Target, value: F);
 
@@ -67,6 +124,21 @@ pub trait WitnessWrite<F: Field> {
         self.set_target(target.target, F::from_bool(value))
     }
 
+    /// Sets the length of `array` to the number of `values`, and its elements to `values`
+    /// followed by zeros.
+    fn set_array_target(&mut self, array: &ArrayTarget, values: &[F]) {
+        assert!(values.len() <= array.capacity());
+        self.set_target(array.len, F::from_canonical_usize(values.len()));
+        for (i, &target) in array.elements.iter().enumerate() {
+            self.set_target(target, values.get(i).copied().unwrap_or(F::ZERO));
+        }
+    }
+
+    fn set_bytes_target(&mut self, bytes: &BytesTarget, values: &[u8]) {
+        let values: Vec<_> = values.iter().map(|&b| F::from_canonical_u8(b)).collect();
+        self.set_array_target(&bytes.array, &values);
+    }
+
     /// Set the targets in a `ProofWithPublicInputsTarget` to their corresponding values in a
     /// `ProofWithPublicInputs`.
     fn set_proof_with_pis_target<C: GenericConfig<D, F = F>, const D: usize>(
@@ -216,6 +288,25 @@ pub trait Witness<F: Field>: WitnessWrite<F> {
         panic!("not a bool")
     }
 
+    /// Returns the elements of `array` in use.
+    fn get_array_target(&self, array: &ArrayTarget) -> Vec<F>
+    where
+        F: RichField,
+    {
+        let len = self.get_target(array.len).to_canonical_u64() as usize;
+        self.get_targets(&array.elements[..len])
+    }
+
+    fn get_bytes_target(&self, bytes: &BytesTarget) -> Vec<u8>
+    where
+        F: RichField,
+    {
+        self.get_array_target(&bytes.array)
+            .into_iter()
+            .map(|b| u8::try_from(b.to_canonical_u64()).expect("not a byte"))
+            .collect()
+    }
+
     fn get_hash_target(&self, ht: HashOutTarget) -> HashOut<F> {
         HashOut {
             elements: self.get_targets(&ht.elements).try_into().unwrap(),
@@ -318,18 +409,44 @@ impl<'a, F: Field> PartitionWitness<'a, F> {
     /// Set a `Target`. On success, returns the representative index of the newly-set target. If the
     /// target was already set, returns `None`.
     pub fn set_target_returning_rep(&mut self, target: Target, value: F) -> Option<usize> {
+        self.try_set_target_returning_rep(target, value)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Like `set_target_returning_rep`, but returns an error instead of panicking if the partition
+    /// of the target was already set to a different value.
+    pub fn try_set_target_returning_rep(
+        &mut self,
+        target: Target,
+        value: F,
+    ) -> Result<Option<usize>, WitnessError<F>> {
         let rep_index = self.representative_map[self.target_index(target)];
         let rep_value = &mut self.values[rep_index];
         if let Some(old_value) = *rep_value {
-            assert_eq!(
-                value, old_value,
-                "Partition containing {:?} was set twice with different values: {} != {}",
-                target, old_value, value
-            );
-            None
+            if value != old_value {
+                return Err(WitnessError::ConflictingValues {
+                    target,
+                    value,
+                    other_target: self.target_at(rep_index),
+                    other_value: old_value,
+                });
+            }
+            Ok(None)
         } else {
             *rep_value = Some(value);
-            Some(rep_index)
+            Ok(Some(rep_index))
+        }
+    }
+
+    /// The inverse of `target_index`.
+    fn target_at(&self, index: usize) -> Target {
+        let num_wire_targets = self.num_wires * self.degree;
+        if index < num_wire_targets {
+            Target::wire(index / self.num_wires, index % self.num_wires)
+        } else {
+            Target::VirtualTarget {
+                index: index - num_wire_targets,
+            }
         }
     }
 
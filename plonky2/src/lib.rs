@@ -7,6 +7,9 @@
 // #[cfg(not(feature = "std"))]
 pub extern crate alloc;
 
+// Lets code generated by `plonky2_derive` refer to `::plonky2` from within this crate.
+extern crate self as plonky2;
+
 /// Re-export of `plonky2_field`.
 #[doc(inline)]
 pub use plonky2_field as field;
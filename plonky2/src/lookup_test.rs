@@ -46,7 +46,6 @@ fn test_no_lookup() -> anyhow::Result<()> {
 
 #[should_panic]
 #[test]
-#[ignore]
 fn test_lookup_table_not_used() {
     init_logger();
 
@@ -76,7 +75,6 @@ fn test_lookup_without_table() {
 
 // Tests two lookups in one lookup table.
 #[test]
-#[ignore]
 fn test_one_lookup() -> anyhow::Result<()> {
     init_logger();
 
@@ -130,7 +128,6 @@ fn test_one_lookup() -> anyhow::Result<()> {
 
 // Tests one lookup in two different lookup tables.
 #[test]
-#[ignore]
 fn test_two_luts() -> anyhow::Result<()> {
     init_logger();
 
@@ -206,7 +203,6 @@ fn test_two_luts() -> anyhow::Result<()> {
 }
 
 #[test]
-#[ignore]
 fn test_different_inputs() -> anyhow::Result<()> {
     init_logger();
 
@@ -284,7 +280,6 @@ fn test_different_inputs() -> anyhow::Result<()> {
 
 // This test looks up over 514 values for one LookupTableGate, which means that several LookupGates are created.
 #[test]
-#[ignore]
 fn test_many_lookups() -> anyhow::Result<()> {
     init_logger();
 
@@ -367,7 +362,6 @@ fn test_many_lookups() -> anyhow::Result<()> {
 
 // Tests whether, when adding the same LUT to the circuit, the circuit only adds one copy, with the same index.
 #[test]
-#[ignore]
 fn test_same_luts() -> anyhow::Result<()> {
     init_logger();
 
@@ -423,7 +417,6 @@ fn test_same_luts() -> anyhow::Result<()> {
 }
 
 #[test]
-#[ignore]
 fn test_big_lut() -> anyhow::Result<()> {
     init_logger();
 
@@ -463,11 +456,10 @@ fn test_big_lut() -> anyhow::Result<()> {
         F::from_canonical_u16(lut_fn(look_val_b))
     );
 
-    data.verify(proof)
+    Ok(data.verify(proof)?)
 }
 
 #[test]
-#[ignore]
 fn test_many_lookups_on_big_lut() -> anyhow::Result<()> {
     init_logger();
 
@@ -515,7 +507,7 @@ fn test_many_lookups_on_big_lut() -> anyhow::Result<()> {
         F::from_canonical_u16(lut_fn(look_val_a) + lut_fn(look_val_b))
     );
 
-    data.verify(proof)
+    Ok(data.verify(proof)?)
 }
 
 fn init_logger() {
@@ -0,0 +1,450 @@
+// Goldilocks field arithmetic emulated in the BN254 scalar field. Every signal standing for a
+// Goldilocks element holds its canonical value, which is enforced by range checks whenever a value
+// is computed or provided by the prover.
+
+function GL_P() {
+  return 18446744069414584321;
+}
+
+function gl_mul(a, b) {
+  return (a * b) % GL_P();
+}
+
+function gl_exp(x, e) {
+  var res = 1;
+  var base = x;
+  while (e > 0) {
+    if ((e & 1) == 1) {
+      res = gl_mul(res, base);
+    }
+    base = gl_mul(base, base);
+    e = e >> 1;
+  }
+  return res;
+}
+
+function gl_inverse(x) {
+  return gl_exp(x, GL_P() - 2);
+}
+
+// Returns the number of bits needed to represent `n - 1`.
+function log2_ceil(n) {
+  var bits = 0;
+  while ((1 << bits) < n) {
+    bits++;
+  }
+  return bits;
+}
+
+// Decomposes `in`, which must be smaller than 2^N, into little-endian bits.
+template ToBits(N) {
+  signal input in;
+  signal output out[N];
+
+  var sum = 0;
+  for (var i = 0; i < N; i++) {
+    out[i] <-- (in >> i) & 1;
+    out[i] * (out[i] - 1) === 0;
+    sum += out[i] * (1 << i);
+  }
+  sum === in;
+}
+
+// Constrains `in` to be smaller than 2^N.
+template RangeCheck(N) {
+  signal input in;
+
+  component bits = ToBits(N);
+  bits.in <== in;
+}
+
+// Returns `in[i]`, where `sel` are the `BITS > 0` little-endian bits of `i`.
+template BitsMux(BITS, WIDTH) {
+  signal input in[1 << BITS][WIDTH];
+  signal input sel[BITS];
+  signal output out[WIDTH];
+
+  // The inner nodes of a binary tree whose node `k` has children `2k + 1` and `2k + 2`, and whose
+  // leaves are the inputs.
+  var num_leaves = 1 << BITS;
+  signal nodes[num_leaves - 1][WIDTH];
+  for (var k = num_leaves - 2; k >= 0; k--) {
+    var depth = 0;
+    while ((1 << (depth + 1)) <= k + 1) {
+      depth++;
+    }
+    var children[2][WIDTH];
+    for (var c = 0; c < 2; c++) {
+      var child = 2 * k + 1 + c;
+      for (var w = 0; w < WIDTH; w++) {
+        if (child >= num_leaves - 1) {
+          children[c][w] = in[child - num_leaves + 1][w];
+        } else {
+          children[c][w] = nodes[child][w];
+        }
+      }
+    }
+    for (var w = 0; w < WIDTH; w++) {
+      nodes[k][w] <== children[0][w] + sel[BITS - 1 - depth] * (children[1][w] - children[0][w]);
+    }
+  }
+  out <== nodes[0];
+}
+
+template IsZero() {
+  signal input in;
+  signal output out;
+
+  signal inv <-- in != 0 ? 1 / in : 0;
+  out <== 1 - in * inv;
+  in * out === 0;
+}
+
+// Constrains `in` to be a canonical Goldilocks element, i.e. smaller than 2^64 - 2^32 + 1.
+template GlRangeCheck() {
+  signal input in;
+
+  signal lo <-- in & 0xffffffff;
+  signal hi <-- in >> 32;
+  component lo_bits = RangeCheck(32);
+  lo_bits.in <== lo;
+  component hi_bits = RangeCheck(32);
+  hi_bits.in <== hi;
+  in === hi * 4294967296 + lo;
+  // If the high half is 2^32 - 1, the low half must be zero.
+  signal hi_max <== IsZero()(hi - 4294967295);
+  hi_max * lo === 0;
+}
+
+// Reduces `in`, which must be smaller than 2^N, modulo the Goldilocks prime.
+template GlReduce(N) {
+  signal input in;
+  signal output out;
+
+  signal quotient <-- in \ GL_P();
+  out <-- in % GL_P();
+  in === quotient * GL_P() + out;
+  // As GL_P() > 2^63, the quotient is smaller than 2^(N - 63).
+  component quotient_bits = RangeCheck(N - 63);
+  quotient_bits.in <== quotient;
+  component range_check = GlRangeCheck();
+  range_check.in <== out;
+}
+
+template GlAdd() {
+  signal input a;
+  signal input b;
+  signal output out;
+
+  out <== GlReduce(65)(a + b);
+}
+
+template GlSub() {
+  signal input a;
+  signal input b;
+  signal output out;
+
+  out <== GlReduce(65)(a + GL_P() - b);
+}
+
+template GlMul() {
+  signal input a;
+  signal input b;
+  signal output out;
+
+  out <== GlReduce(128)(a * b);
+}
+
+template GlInverse() {
+  signal input in;
+  signal output out;
+
+  out <-- gl_inverse(in);
+  component range_check = GlRangeCheck();
+  range_check.in <== out;
+  signal product <== GlMul()(in, out);
+  product === 1;
+}
+
+// Computes `x^(2^N)`.
+template GlExpPowerOf2(N) {
+  signal input x;
+  signal output out;
+
+  signal powers[N + 1];
+  powers[0] <== x;
+  for (var i = 0; i < N; i++) {
+    powers[i + 1] <== GlMul()(powers[i], powers[i]);
+  }
+  out <== powers[N];
+}
+
+// Returns `[1, x, ..., x^(N - 1)]`.
+template GlPowers(N) {
+  signal input x;
+  signal output out[N];
+
+  out[0] <== 1;
+  for (var i = 1; i < N; i++) {
+    out[i] <== GlMul()(out[i - 1], x);
+  }
+}
+
+// Elements of the quadratic extension `F[X] / (X^2 - 7)` are pairs of Goldilocks elements.
+template GlExt(x, y) {
+  signal output out[2];
+
+  out[0] <== x;
+  out[1] <== y;
+}
+
+template GlExtAdd() {
+  signal input a[2];
+  signal input b[2];
+  signal output out[2];
+
+  for (var i = 0; i < 2; i++) {
+    out[i] <== GlReduce(65)(a[i] + b[i]);
+  }
+}
+
+template GlExtSub() {
+  signal input a[2];
+  signal input b[2];
+  signal output out[2];
+
+  for (var i = 0; i < 2; i++) {
+    out[i] <== GlReduce(65)(a[i] + GL_P() - b[i]);
+  }
+}
+
+template GlExtMul() {
+  signal input a[2];
+  signal input b[2];
+  signal output out[2];
+
+  signal a1b1 <== a[1] * b[1];
+  signal a1b0 <== a[1] * b[0];
+  out[0] <== GlReduce(131)(a[0] * b[0] + 7 * a1b1);
+  out[1] <== GlReduce(129)(a[0] * b[1] + a1b0);
+}
+
+template GlExtSquare() {
+  signal input a[2];
+  signal output out[2];
+
+  out <== GlExtMul()(a, a);
+}
+
+// Computes `a * c + b` for a base field element `c`.
+template GlExtScalarMulAdd() {
+  signal input a[2];
+  signal input c;
+  signal input b[2];
+  signal output out[2];
+
+  for (var i = 0; i < 2; i++) {
+    out[i] <== GlReduce(129)(a[i] * c + b[i]);
+  }
+}
+
+template GlExtScalarMul() {
+  signal input a[2];
+  signal input c;
+  signal output out[2];
+
+  for (var i = 0; i < 2; i++) {
+    out[i] <== GlReduce(128)(a[i] * c);
+  }
+}
+
+// Computes `a * b + c`.
+template GlExtMulAdd() {
+  signal input a[2];
+  signal input b[2];
+  signal input c[2];
+  signal output out[2];
+
+  signal a1b1 <== a[1] * b[1];
+  signal a1b0 <== a[1] * b[0];
+  out[0] <== GlReduce(132)(a[0] * b[0] + 7 * a1b1 + c[0]);
+  out[1] <== GlReduce(130)(a[0] * b[1] + a1b0 + c[1]);
+}
+
+// Computes `a / b`. `b` must not be zero.
+template GlExtDiv() {
+  signal input a[2];
+  signal input b[2];
+  signal output out[2];
+
+  var p = GL_P();
+  var denominator = (b[0] * b[0] + 7 * b[1] * (p - b[1])) % p;
+  var inverse = gl_inverse(denominator);
+  out[0] <-- gl_mul((a[0] * b[0] + 7 * a[1] * (p - b[1])) % p, inverse);
+  out[1] <-- gl_mul((a[1] * b[0] + a[0] * (p - b[1])) % p, inverse);
+  component range_check[2];
+  for (var i = 0; i < 2; i++) {
+    range_check[i] = GlRangeCheck();
+    range_check[i].in <== out[i];
+  }
+  signal product[2] <== GlExtMul()(out, b);
+  for (var i = 0; i < 2; i++) {
+    product[i] === a[i];
+  }
+}
+
+// Computes `x^n` for an `N`-bit exponent `n`.
+template GlExtExpN(N) {
+  signal input x[2];
+  signal input n;
+  signal output out[2];
+
+  signal bits[N] <== ToBits(N)(n);
+  signal powers[N][2];
+  signal products[N][2];
+  signal acc[N + 1][2];
+  acc[0] <== GlExt(1, 0)();
+  for (var i = 0; i < N; i++) {
+    if (i == 0) {
+      powers[i] <== x;
+    } else {
+      powers[i] <== GlExtSquare()(powers[i - 1]);
+    }
+    products[i] <== GlExtMul()(acc[i], powers[i]);
+    for (var j = 0; j < 2; j++) {
+      acc[i + 1][j] <== acc[i][j] + bits[i] * (products[i][j] - acc[i][j]);
+    }
+  }
+  out <== acc[N];
+}
+
+// Computes `x^(2^N)`.
+template GlExtExpPowerOf2(N) {
+  signal input x[2];
+  signal output out[2];
+
+  signal powers[N + 1][2];
+  powers[0] <== x;
+  for (var i = 0; i < N; i++) {
+    powers[i + 1] <== GlExtSquare()(powers[i]);
+  }
+  out <== powers[N];
+}
+
+// Returns `[1, x, ..., x^(N - 1)]`.
+template GlExtPowers(N) {
+  signal input x[2];
+  signal output out[N][2];
+
+  out[0] <== GlExt(1, 0)();
+  for (var i = 1; i < N; i++) {
+    out[i] <== GlExtMul()(out[i - 1], x);
+  }
+}
+
+// Returns `sum_i a[i] * b[i]` for base field elements `b[i]`, reducing only the final sums.
+template GlExtScalarInnerProduct(N) {
+  signal input a[N][2];
+  signal input b[N];
+  signal output out[2];
+
+  signal products[N][2];
+  for (var j = 0; j < 2; j++) {
+    var sum = 0;
+    for (var i = 0; i < N; i++) {
+      products[i][j] <== a[i][j] * b[i];
+      sum += products[i][j];
+    }
+    out[j] <== GlReduce(128 + log2_ceil(N))(sum);
+  }
+}
+
+// Returns `sum_i a[i] * b[i]`, reducing only the final sums.
+template GlExtInnerProduct(N) {
+  signal input a[N][2];
+  signal input b[N][2];
+  signal output out[2];
+
+  signal products[N][4];
+  var sum[2] = [0, 0];
+  for (var i = 0; i < N; i++) {
+    products[i][0] <== a[i][0] * b[i][0];
+    products[i][1] <== a[i][1] * b[i][1];
+    products[i][2] <== a[i][0] * b[i][1];
+    products[i][3] <== a[i][1] * b[i][0];
+    sum[0] += products[i][0] + 7 * products[i][1];
+    sum[1] += products[i][2] + products[i][3];
+  }
+  out[0] <== GlReduce(131 + log2_ceil(N))(sum[0]);
+  out[1] <== GlReduce(129 + log2_ceil(N))(sum[1]);
+}
+
+// Returns `constraint + filter * value`.
+template ConstraintPush() {
+  signal input constraint[2];
+  signal input filter[2];
+  signal input value[2];
+  signal output out[2];
+
+  out <== GlExtMulAdd()(filter, value, constraint);
+}
+
+// Multiplies the `ExtensionAlgebra` elements starting at wires `l` and `r`.
+template WiresAlgebraMul(l, r) {
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal output out[2][2];
+
+  signal w_a1b1[2] <== GlExtMul()(wires[l + 1], wires[r + 1]);
+  out[0] <== GlExtMulAdd()(wires[l], wires[r], GlExtScalarMul()(w_a1b1, 7));
+  out[1] <== GlExtMulAdd()(wires[l], wires[r + 1], GlExtMul()(wires[l + 1], wires[r]));
+}
+
+// Computes `a * b + c` for elements of the `ExtensionAlgebra` `F[X, Y] / (X^2 - 7, Y^2 - 7)`, given
+// as pairs of extension field elements.
+template GlExtAlgebraMulAdd() {
+  signal input a[2][2];
+  signal input b[2][2];
+  signal input c[2][2];
+  signal output out[2][2];
+
+  signal a1b1[2] <== GlExtMul()(a[1], b[1]);
+  out[0] <== GlExtMulAdd()(a[0], b[0], GlExtAdd()(GlExtScalarMul()(a1b1, 7), c[0]));
+  out[1] <== GlExtMulAdd()(a[0], b[1], GlExtMulAdd()(a[1], b[0], c[1]));
+}
+
+template GlExtAlgebraMul() {
+  signal input a[2][2];
+  signal input b[2][2];
+  signal output out[2][2];
+
+  var zero[2][2] = [[0, 0], [0, 0]];
+  out <== GlExtAlgebraMulAdd()(a, b, zero);
+}
+
+// Returns `prod_{i < N} (x - i)`, which vanishes exactly when `x` is one of `0, ..., N - 1`.
+template RangeProduct(N) {
+  signal input x[2];
+  signal output out[2];
+
+  signal acc[N][2];
+  acc[0] <== x;
+  for (var i = 1; i < N; i++) {
+    acc[i] <== GlExtMul()(acc[i - 1], GlExtSub()(x, GlExt(i, 0)()));
+  }
+  out <== acc[N - 1];
+}
+
+// Computes `old_eval * alpha^N + sum_i in[i] * alpha^i`.
+template Reduce(N) {
+  signal input in[N][2];
+  signal input alpha[2];
+  signal input old_eval[2];
+  signal output out[2];
+
+  signal acc[N + 1][2];
+  acc[N] <== old_eval;
+  for (var i = N; i > 0; i--) {
+    acc[i - 1] <== GlExtMulAdd()(acc[i], alpha, in[i - 1]);
+  }
+  out <== acc[0];
+}
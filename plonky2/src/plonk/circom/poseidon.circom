@@ -0,0 +1,302 @@
+// Poseidon over the Goldilocks field, used to hash the public inputs and by the Poseidon gates.
+
+function GL_CONST(i) {
+  var value[$GL_NUM_ROUND_CONSTANTS] = [$GL_ROUND_CONSTANTS];
+  return value[i];
+}
+
+function MDS_MATRIX_CIRC(i) {
+  var mds[12] = [$GL_MDS_MATRIX_CIRC];
+  return mds[i];
+}
+
+function MDS_MATRIX_DIAG(i) {
+  var mds[12] = [$GL_MDS_MATRIX_DIAG];
+  return mds[i];
+}
+
+// The S-box `x^7` of an element smaller than 2^65.
+template GlSbox() {
+  signal input in;
+  signal output out;
+
+  signal x2 <== GlReduce(130)(in * in);
+  signal x3 <== GlReduce(129)(x2 * in);
+  signal x4 <== GlReduce(128)(x2 * x2);
+  out <== GlReduce(128)(x3 * x4);
+}
+
+template PoseidonGoldilocks() {
+  signal input in[12];
+  signal output out[12];
+
+  var half_n_full_rounds = 4;
+  var n_partial_rounds = 22;
+  var n_rounds = 2 * half_n_full_rounds + n_partial_rounds;
+  signal state[n_rounds + 1][12];
+  signal full_sbox[2 * half_n_full_rounds][12];
+  signal partial_sbox[n_partial_rounds];
+  state[0] <== in;
+  var full_round = 0;
+  for (var r = 0; r < n_rounds; r++) {
+    var t[12];
+    for (var i = 0; i < 12; i++) {
+      t[i] = state[r][i] + GL_CONST(12 * r + i);
+    }
+    if (r < half_n_full_rounds || r >= half_n_full_rounds + n_partial_rounds) {
+      for (var i = 0; i < 12; i++) {
+        full_sbox[full_round][i] <== GlSbox()(t[i]);
+        t[i] = full_sbox[full_round][i];
+      }
+      full_round++;
+    } else {
+      partial_sbox[r - half_n_full_rounds] <== GlSbox()(t[0]);
+      t[0] = partial_sbox[r - half_n_full_rounds];
+    }
+    // The elements of `t` are smaller than 2^65 and the MDS coefficients than 2^6.
+    for (var i = 0; i < 12; i++) {
+      var sum = t[i] * MDS_MATRIX_DIAG(i);
+      for (var j = 0; j < 12; j++) {
+        sum += t[(i + j) % 12] * MDS_MATRIX_CIRC(j);
+      }
+      state[r + 1][i] <== GlReduce(75)(sum);
+    }
+  }
+  out <== state[n_rounds];
+}
+
+// Hashes `N > 0` Goldilocks elements without padding.
+template HashNoPadGoldilocks(N) {
+  signal input in[N];
+  signal output out[4];
+
+  var num_chunks = (N + 7) \ 8;
+  signal state[num_chunks][12];
+  for (var c = 0; c < num_chunks; c++) {
+    var chunk[12];
+    for (var i = 0; i < 12; i++) {
+      if (i < 8 && 8 * c + i < N) {
+        chunk[i] = in[8 * c + i];
+      } else if (c == 0) {
+        chunk[i] = 0;
+      } else {
+        chunk[i] = state[c - 1][i];
+      }
+    }
+    state[c] <== PoseidonGoldilocks()(chunk);
+  }
+  for (var i = 0; i < 4; i++) {
+    out[i] <== state[num_chunks - 1][i];
+  }
+}
+
+// The width-5 Poseidon permutation over the BN254 scalar field, the native field of circom.
+
+function BN128_ROUND_CONSTANT(i) {
+  var value[$BN128_NUM_ROUND_CONSTANTS] = [$BN128_ROUND_CONSTANTS];
+  return value[i];
+}
+
+function BN128_MDS_MATRIX(i, j) {
+  var mds[25] = [$BN128_MDS_MATRIX];
+  return mds[5 * i + j];
+}
+
+template PoseidonBN128() {
+  signal input in[5];
+  signal output out[5];
+
+  var half_n_full_rounds = 4;
+  var n_partial_rounds = 60;
+  var n_rounds = 2 * half_n_full_rounds + n_partial_rounds;
+  signal full_x2[2 * half_n_full_rounds][5];
+  signal full_x4[2 * half_n_full_rounds][5];
+  signal full_x5[2 * half_n_full_rounds][5];
+  signal partial_x2[n_partial_rounds];
+  signal partial_x4[n_partial_rounds];
+  signal partial_x5[n_partial_rounds];
+  var state[5];
+  for (var i = 0; i < 5; i++) {
+    state[i] = in[i];
+  }
+  var full_round = 0;
+  for (var r = 0; r < n_rounds; r++) {
+    for (var i = 0; i < 5; i++) {
+      state[i] += BN128_ROUND_CONSTANT(5 * r + i);
+    }
+    if (r < half_n_full_rounds || r >= half_n_full_rounds + n_partial_rounds) {
+      for (var i = 0; i < 5; i++) {
+        full_x2[full_round][i] <== state[i] * state[i];
+        full_x4[full_round][i] <== full_x2[full_round][i] * full_x2[full_round][i];
+        full_x5[full_round][i] <== full_x4[full_round][i] * state[i];
+        state[i] = full_x5[full_round][i];
+      }
+      full_round++;
+    } else {
+      var p = r - half_n_full_rounds;
+      partial_x2[p] <== state[0] * state[0];
+      partial_x4[p] <== partial_x2[p] * partial_x2[p];
+      partial_x5[p] <== partial_x4[p] * state[0];
+      state[0] = partial_x5[p];
+    }
+    var mixed[5];
+    for (var i = 0; i < 5; i++) {
+      mixed[i] = 0;
+      for (var j = 0; j < 5; j++) {
+        mixed[i] += BN128_MDS_MATRIX(i, j) * state[j];
+      }
+    }
+    state = mixed;
+  }
+  out <== state;
+}
+
+// Decomposes a BN254 scalar field element into the little-endian bits of its canonical
+// representative.
+template ToBitsStrict() {
+  signal input in;
+  signal output out[254];
+
+  out <== ToBits(254)(in);
+  // Compares the bits with those of `r - 1` from the most significant one: `eq[i]` is one if the
+  // bits from `i` upwards are equal, and `lt` is one if `in < r - 1`.
+  var r_minus_one = 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000;
+  signal eq[254];
+  var eq_above = 1;
+  var lt = 0;
+  for (var i = 253; i >= 0; i--) {
+    if (((r_minus_one >> i) & 1) == 1) {
+      eq[i] <== eq_above * out[i];
+      lt += eq_above - eq[i];
+    } else {
+      eq[i] <== eq_above * (1 - out[i]);
+    }
+    eq_above = eq[i];
+  }
+  lt + eq[0] === 1;
+}
+
+// Reduces the 64-bit integer with little-endian bits `in` modulo the Goldilocks prime.
+template GlFromBits64() {
+  signal input in[64];
+  signal output out;
+
+  var lo = 0;
+  var hi = 0;
+  var num_hi_ones = 0;
+  for (var i = 0; i < 32; i++) {
+    lo += in[i] * (1 << i);
+    hi += in[32 + i] * (1 << i);
+    num_hi_ones += in[32 + i];
+  }
+  // The integer is at least the prime if its high half is 2^32 - 1 and its low half is nonzero.
+  signal hi_max <== IsZero()(num_hi_ones - 32);
+  signal lo_zero <== IsZero()(lo);
+  signal overflow <== hi_max * (1 - lo_zero);
+  out <== hi * (1 << 32) + lo - overflow * GL_P();
+}
+
+// Permutes a Goldilocks sponge state by packing it into four BN254 scalar field elements, as
+// `PoseidonBN128Hash` does, and returns the first `NUM_OUTPUTS` elements of the result.
+template PoseidonBN128Permutation(NUM_OUTPUTS) {
+  signal input in[12];
+  signal output out[NUM_OUTPUTS];
+
+  var packed[5];
+  packed[0] = 0;
+  for (var i = 0; i < 4; i++) {
+    packed[i + 1] = in[3 * i] * (1 << 128) + in[3 * i + 1] * (1 << 64) + in[3 * i + 2];
+  }
+  signal permuted[5] <== PoseidonBN128()(packed);
+  var num_packed_outputs = (NUM_OUTPUTS + 2) \ 3;
+  signal bits[num_packed_outputs][254];
+  for (var i = 0; i < num_packed_outputs; i++) {
+    bits[i] <== ToBitsStrict()(permuted[i]);
+  }
+  for (var i = 0; i < NUM_OUTPUTS; i++) {
+    // The first element of each triple is in the most significant limb.
+    var limb_start = 64 * (2 - i % 3);
+    var limb[64];
+    for (var j = 0; j < 64; j++) {
+      limb[j] = bits[i \ 3][limb_start + j];
+    }
+    out[i] <== GlFromBits64()(limb);
+  }
+}
+
+// Hashes `N > 0` Goldilocks elements without padding.
+template HashNoPadBN128(N) {
+  signal input in[N];
+  signal output out[4];
+
+  var num_chunks = (N + 7) \ 8;
+  signal state[num_chunks][12];
+  for (var c = 0; c < num_chunks; c++) {
+    var chunk[12];
+    for (var i = 0; i < 12; i++) {
+      if (i < 8 && 8 * c + i < N) {
+        chunk[i] = in[8 * c + i];
+      } else if (c == 0) {
+        chunk[i] = 0;
+      } else {
+        chunk[i] = state[c - 1][i];
+      }
+    }
+    state[c] <== PoseidonBN128Permutation(12)(chunk);
+  }
+  for (var i = 0; i < 4; i++) {
+    out[i] <== state[num_chunks - 1][i];
+  }
+}
+
+// Hashes `N > 0` elements into a Merkle tree leaf digest. Inputs of at most four elements are
+// padded with zeros instead of being hashed.
+template HashOrNoopBN128(N) {
+  signal input in[N];
+  signal output out[4];
+
+  if (N <= 4) {
+    for (var i = 0; i < 4; i++) {
+      if (i < N) {
+        out[i] <== in[i];
+      } else {
+        out[i] <== 0;
+      }
+    }
+  } else {
+    out <== HashNoPadBN128(N)(in);
+  }
+}
+
+// Verifies the Merkle proof of `leaf` against `cap`. `index_bits` are the little-endian bits of a
+// query index among the `2^LDE_BITS()` points of the LDE, and the index of the leaf is the query index
+// shifted right by `BITS_OFFSET`.
+template VerifyMerkleProof(LEAF_LEN, BITS_OFFSET) {
+  var depth = LDE_BITS() - CAP_HEIGHT() - BITS_OFFSET;
+  signal input leaf[LEAF_LEN];
+  signal input siblings[depth][4];
+  signal input index_bits[LDE_BITS()];
+  signal input cap[CAP_LEN()][4];
+
+  signal digests[depth + 1][4];
+  signal left[depth][4];
+  digests[0] <== HashOrNoopBN128(LEAF_LEN)(leaf);
+  for (var i = 0; i < depth; i++) {
+    var state[12];
+    for (var j = 0; j < 4; j++) {
+      left[i][j] <== digests[i][j] + index_bits[BITS_OFFSET + i] * (siblings[i][j] - digests[i][j]);
+      state[j] = left[i][j];
+      state[j + 4] = digests[i][j] + siblings[i][j] - left[i][j];
+      state[j + 8] = 0;
+    }
+    digests[i + 1] <== PoseidonBN128Permutation(4)(state);
+  }
+  var cap_index_bits[CAP_HEIGHT()];
+  for (var i = 0; i < CAP_HEIGHT(); i++) {
+    cap_index_bits[i] = index_bits[BITS_OFFSET + depth + i];
+  }
+  signal root[4] <== BitsMux(CAP_HEIGHT(), 4)(cap, cap_index_bits);
+  for (var i = 0; i < 4; i++) {
+    digests[depth][i] === root[i];
+  }
+}
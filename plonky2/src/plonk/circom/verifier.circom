@@ -0,0 +1,723 @@
+// The verifier of a plonky2 proof. The circuit-specific constants and the constraints of the gates
+// are generated along with this template.
+
+function MULTIPLICATIVE_GROUP_GENERATOR() {
+  return 7;
+}
+
+// Returns a generator of the subgroup of order `2^n_log`.
+function primitive_root_of_unity(n_log) {
+  var power_of_two_generator = 1753635133440165772;
+  var two_adicity = 32;
+  var res = power_of_two_generator;
+  for (var i = n_log; i < two_adicity; i++) {
+    res = gl_mul(res, res);
+  }
+  return res;
+}
+
+function reverse_bits(n, num_bits) {
+  var res = 0;
+  for (var i = 0; i < num_bits; i++) {
+    res = (res << 1) | ((n >> i) & 1);
+  }
+  return res;
+}
+
+function NUM_OPENINGS_AT_ZETA() {
+  return NUM_PREPROCESSED_POLYS() + NUM_OPENINGS_WIRES() + NUM_ZS_PARTIAL_PRODUCTS_POLYS() + NUM_QUOTIENT_POLYS();
+}
+
+// The number of bits the query indices are shifted by before the `i`-th FRI reduction.
+function fri_reduction_bits_offset(i) {
+  var offset = 0;
+  for (var j = 0; j < i; j++) {
+    offset += REDUCTION_ARITY_BITS(j);
+  }
+  return offset;
+}
+
+// The position of the evaluations of the `i`-th FRI step among those of all steps.
+function fri_step_evals_offset(i) {
+  var offset = 0;
+  for (var j = 0; j < i; j++) {
+    offset += 1 << REDUCTION_ARITY_BITS(j);
+  }
+  return offset;
+}
+
+// The position of the Merkle proof siblings of the `i`-th FRI step among those of all steps.
+function fri_step_siblings_offset(i) {
+  var offset = 0;
+  for (var j = 0; j < i; j++) {
+    offset += LDE_BITS() - CAP_HEIGHT() - fri_reduction_bits_offset(j + 1);
+  }
+  return offset;
+}
+
+// ---------------------------------------------------------------------------------------------
+// Fiat-Shamir challenges.
+
+// The transcript is made of segments of observed elements, each followed by some challenges.
+function NUM_TRANSCRIPT_SEGMENTS() {
+  return 5 + NUM_REDUCTIONS();
+}
+
+function transcript_segment_len(i) {
+  if (i == 0) {
+    // The circuit digest, the public inputs hash and the wires cap.
+    return 8 + 4 * CAP_LEN();
+  } else if (i == 3) {
+    // The openings at zeta, then those at g * zeta.
+    return 2 * (NUM_OPENINGS_AT_ZETA() + NUM_CHALLENGES());
+  } else if (i == NUM_TRANSCRIPT_SEGMENTS() - 1) {
+    // The final polynomial and the proof of work witness.
+    return 2 * FINAL_POLY_LEN() + 1;
+  } else {
+    return 4 * CAP_LEN();
+  }
+}
+
+function transcript_segment_challenges(i) {
+  if (i == 0) {
+    // Betas and gammas.
+    return 2 * NUM_CHALLENGES();
+  } else if (i == 1) {
+    // Alphas.
+    return NUM_CHALLENGES();
+  } else if (i == NUM_TRANSCRIPT_SEGMENTS() - 1) {
+    // The proof of work response and the query indices.
+    return 1 + NUM_QUERY_ROUNDS();
+  } else {
+    // Zeta, the FRI alpha and the FRI betas.
+    return 2;
+  }
+}
+
+function TRANSCRIPT_LEN() {
+  var len = 0;
+  for (var i = 0; i < NUM_TRANSCRIPT_SEGMENTS(); i++) {
+    len += transcript_segment_len(i);
+  }
+  return len;
+}
+
+function NUM_TRANSCRIPT_CHALLENGES() {
+  var num = 0;
+  for (var i = 0; i < NUM_TRANSCRIPT_SEGMENTS(); i++) {
+    num += transcript_segment_challenges(i);
+  }
+  return num;
+}
+
+// The number of permutations done by `Challenger`.
+function challenger_num_permutations() {
+  var num = 0;
+  var input_len = 0;
+  var output_len = 0;
+  for (var s = 0; s < NUM_TRANSCRIPT_SEGMENTS(); s++) {
+    for (var i = 0; i < transcript_segment_len(s); i++) {
+      output_len = 0;
+      input_len++;
+      if (input_len == 8) {
+        num++;
+        input_len = 0;
+        output_len = 8;
+      }
+    }
+    for (var i = 0; i < transcript_segment_challenges(s); i++) {
+      if (input_len > 0 || output_len == 0) {
+        num++;
+        input_len = 0;
+        output_len = 8;
+      }
+      output_len--;
+    }
+  }
+  return num;
+}
+
+// A duplex sponge challenger over `PoseidonBN128Hash`, squeezing the challenges of each segment of
+// the transcript after observing it.
+template Challenger() {
+  signal input transcript[TRANSCRIPT_LEN()];
+  signal output challenges[NUM_TRANSCRIPT_CHALLENGES()];
+
+  component perms[challenger_num_permutations()];
+  var state[12];
+  for (var i = 0; i < 12; i++) {
+    state[i] = 0;
+  }
+  var num_perms = 0;
+  var input_len = 0;
+  var output_len = 0;
+  var pos = 0;
+  var num_challenges = 0;
+  for (var s = 0; s < NUM_TRANSCRIPT_SEGMENTS(); s++) {
+    for (var i = 0; i < transcript_segment_len(s); i++) {
+      output_len = 0;
+      state[input_len] = transcript[pos];
+      pos++;
+      input_len++;
+      if (input_len == 8) {
+        perms[num_perms] = PoseidonBN128Permutation(12);
+        perms[num_perms].in <== state;
+        state = perms[num_perms].out;
+        num_perms++;
+        input_len = 0;
+        output_len = 8;
+      }
+    }
+    for (var i = 0; i < transcript_segment_challenges(s); i++) {
+      if (input_len > 0 || output_len == 0) {
+        perms[num_perms] = PoseidonBN128Permutation(12);
+        perms[num_perms].in <== state;
+        state = perms[num_perms].out;
+        num_perms++;
+        input_len = 0;
+        output_len = 8;
+      }
+      output_len--;
+      challenges[num_challenges] <== state[output_len];
+      num_challenges++;
+    }
+  }
+}
+
+// ---------------------------------------------------------------------------------------------
+// Vanishing polynomial.
+
+// Sums the filtered constraints of all gates.
+template EvalGateConstraints() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal constraints[$NUM_GATES + 1][NUM_GATE_CONSTRAINTS()][2];
+  for (var i = 0; i < NUM_GATE_CONSTRAINTS(); i++) {
+    constraints[0][i] <== GlExt(0, 0)();
+  }
+$GATE_EVALUATION
+  out <== constraints[$NUM_GATES];
+}
+
+// Checks `vanishing(zeta) = Z_H(zeta) * quotient(zeta)` for each challenge.
+template VerifyQuotient() {
+  signal input openings_constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input openings_plonk_sigmas[NUM_ROUTED_WIRES()][2];
+  signal input openings_wires[NUM_OPENINGS_WIRES()][2];
+  signal input openings_plonk_zs[NUM_CHALLENGES()][2];
+  signal input openings_plonk_zs_next[NUM_CHALLENGES()][2];
+  signal input openings_partial_products[NUM_CHALLENGES() * NUM_PARTIAL_PRODUCTS()][2];
+  signal input openings_quotient_polys[NUM_QUOTIENT_POLYS()][2];
+  signal input public_inputs_hash[4];
+  signal input plonk_betas[NUM_CHALLENGES()];
+  signal input plonk_gammas[NUM_CHALLENGES()];
+  signal input plonk_alphas[NUM_CHALLENGES()];
+  signal input plonk_zeta[2];
+
+  var num_chunks = NUM_PARTIAL_PRODUCTS() + 1;
+  var num_terms = NUM_CHALLENGES() * (num_chunks + 1) + NUM_GATE_CONSTRAINTS();
+  signal terms[num_terms][2];
+
+  // L_0(zeta) = (zeta^n - 1) / (n * (zeta - 1)).
+  signal zeta_pow_deg[2] <== GlExtExpPowerOf2(DEGREE_BITS())(plonk_zeta);
+  signal z_h_zeta[2] <== GlExtSub()(zeta_pow_deg, GlExt(1, 0)());
+  signal l_0[2] <== GlExtDiv()(z_h_zeta, GlExtScalarMul()(GlExtSub()(plonk_zeta, GlExt(1, 0)()), 1 << DEGREE_BITS()));
+
+  signal beta_zeta[NUM_CHALLENGES()][2];
+  signal numerator_factors[NUM_CHALLENGES()][NUM_ROUTED_WIRES()][2];
+  signal denominator_factors[NUM_CHALLENGES()][NUM_ROUTED_WIRES()][2];
+  signal numerators[NUM_CHALLENGES()][NUM_ROUTED_WIRES()][2];
+  signal denominators[NUM_CHALLENGES()][NUM_ROUTED_WIRES()][2];
+  for (var i = 0; i < NUM_CHALLENGES(); i++) {
+    terms[i] <== GlExtMul()(l_0, GlExtSub()(openings_plonk_zs[i], GlExt(1, 0)()));
+
+    beta_zeta[i] <== GlExtScalarMul()(plonk_zeta, plonk_betas[i]);
+    for (var w = 0; w < NUM_ROUTED_WIRES(); w++) {
+      // wire + beta * k_i * zeta + gamma and wire + beta * sigma + gamma.
+      numerator_factors[i][w][0] <== GlReduce(129)(openings_wires[w][0] + K_IS(w) * beta_zeta[i][0] + plonk_gammas[i]);
+      numerator_factors[i][w][1] <== GlReduce(129)(openings_wires[w][1] + K_IS(w) * beta_zeta[i][1]);
+      denominator_factors[i][w][0] <== GlReduce(129)(openings_wires[w][0] + plonk_betas[i] * openings_plonk_sigmas[w][0] + plonk_gammas[i]);
+      denominator_factors[i][w][1] <== GlReduce(129)(openings_wires[w][1] + plonk_betas[i] * openings_plonk_sigmas[w][1]);
+      // Running products over each chunk of QUOTIENT_DEGREE_FACTOR() wires.
+      if (w % QUOTIENT_DEGREE_FACTOR() == 0) {
+        numerators[i][w] <== numerator_factors[i][w];
+        denominators[i][w] <== denominator_factors[i][w];
+      } else {
+        numerators[i][w] <== GlExtMul()(numerators[i][w - 1], numerator_factors[i][w]);
+        denominators[i][w] <== GlExtMul()(denominators[i][w - 1], denominator_factors[i][w]);
+      }
+    }
+
+    var prev_acc[2] = openings_plonk_zs[i];
+    for (var j = 0; j < num_chunks; j++) {
+      var last_wire = (j + 1) * QUOTIENT_DEGREE_FACTOR() - 1;
+      if (last_wire >= NUM_ROUTED_WIRES()) {
+        last_wire = NUM_ROUTED_WIRES() - 1;
+      }
+      var next_acc[2];
+      if (j == num_chunks - 1) {
+        next_acc = openings_plonk_zs_next[i];
+      } else {
+        next_acc = openings_partial_products[i * NUM_PARTIAL_PRODUCTS() + j];
+      }
+      terms[NUM_CHALLENGES() + i * num_chunks + j] <== GlExtSub()(GlExtMul()(prev_acc, numerators[i][last_wire]), GlExtMul()(next_acc, denominators[i][last_wire]));
+      prev_acc = next_acc;
+    }
+  }
+
+  signal constraints[NUM_GATE_CONSTRAINTS()][2] <== EvalGateConstraints()(openings_constants, openings_wires, public_inputs_hash);
+  for (var i = 0; i < NUM_GATE_CONSTRAINTS(); i++) {
+    terms[NUM_CHALLENGES() * (num_chunks + 1) + i] <== constraints[i];
+  }
+
+  signal zeta_pow_deg_powers[QUOTIENT_DEGREE_FACTOR()][2] <== GlExtPowers(QUOTIENT_DEGREE_FACTOR())(zeta_pow_deg);
+  signal alpha_powers[NUM_CHALLENGES()][num_terms];
+  signal vanishing[NUM_CHALLENGES()][2];
+  signal quotient[NUM_CHALLENGES()][2];
+  signal expected[NUM_CHALLENGES()][2];
+  for (var i = 0; i < NUM_CHALLENGES(); i++) {
+    alpha_powers[i] <== GlPowers(num_terms)(plonk_alphas[i]);
+    vanishing[i] <== GlExtScalarInnerProduct(num_terms)(terms, alpha_powers[i]);
+    var quotient_polys[QUOTIENT_DEGREE_FACTOR()][2];
+    for (var j = 0; j < QUOTIENT_DEGREE_FACTOR(); j++) {
+      quotient_polys[j] = openings_quotient_polys[i * QUOTIENT_DEGREE_FACTOR() + j];
+    }
+    quotient[i] <== GlExtInnerProduct(QUOTIENT_DEGREE_FACTOR())(quotient_polys, zeta_pow_deg_powers);
+    expected[i] <== GlExtMul()(z_h_zeta, quotient[i]);
+    vanishing[i][0] === expected[i][0];
+    vanishing[i][1] === expected[i][1];
+  }
+}
+
+// ---------------------------------------------------------------------------------------------
+// FRI.
+
+// Checks the evaluations of the `I`-th FRI step and computes the next evaluation.
+template FriStep(I) {
+  var arity_bits = REDUCTION_ARITY_BITS(I);
+  var arity = 1 << arity_bits;
+  var bits_offset = fri_reduction_bits_offset(I);
+  var evals_offset = fri_step_evals_offset(I);
+  signal input step_v[fri_step_evals_offset(NUM_REDUCTIONS())][2];
+  signal input step_p[fri_step_siblings_offset(NUM_REDUCTIONS())][4];
+  signal input index_bits[LDE_BITS()];
+  signal input cap[CAP_LEN()][4];
+  // The current point, and its `arity`-th power.
+  signal input x;
+  signal input next_x;
+  signal input old_eval[2];
+  signal input beta[2];
+  signal output out[2];
+
+  var evals[arity][2];
+  var index_within_coset_bits[arity_bits];
+  for (var i = 0; i < arity; i++) {
+    evals[i] = step_v[evals_offset + i];
+  }
+  for (var i = 0; i < arity_bits; i++) {
+    index_within_coset_bits[i] = index_bits[bits_offset + i];
+  }
+  signal eval[2] <== BitsMux(arity_bits, 2)(evals, index_within_coset_bits);
+  eval[0] === old_eval[0];
+  eval[1] === old_eval[1];
+
+  // The evaluations are those at the points `coset_start * g^rev(i)`, where `g` generates the
+  // subgroup of order `arity` and `coset_start = x * g^-rev(index_within_coset)`.
+  var g = primitive_root_of_unity(arity_bits);
+  signal coset_start[arity_bits + 1];
+  coset_start[0] <== x;
+  for (var i = 0; i < arity_bits; i++) {
+    var inverse_power = gl_exp(g, arity - (1 << (arity_bits - 1 - i)));
+    coset_start[i + 1] <== GlReduce(128)(coset_start[i] * (1 + index_within_coset_bits[i] * (inverse_power - 1)));
+  }
+
+  // Evaluates at `beta` the interpolant of the evaluations. The points are the roots of
+  // `X^arity - next_x`, so their barycentric weights are `x_i / (arity * next_x)`.
+  signal points[arity];
+  signal quotients[arity][2];
+  for (var i = 0; i < arity; i++) {
+    points[i] <== GlReduce(128)(coset_start[arity_bits] * gl_exp(g, i));
+    var point[2] = [points[i], 0];
+    quotients[i] <== GlExtDiv()(evals[reverse_bits(i, arity_bits)], GlExtSub()(beta, point));
+  }
+  signal sum[2] <== GlExtScalarInnerProduct(arity)(quotients, points);
+  signal beta_pow_arity[2] <== GlExtExpPowerOf2(arity_bits)(beta);
+  var next_x_ext[2] = [next_x, 0];
+  signal weight <== GlInverse()(GlReduce(64 + arity_bits)(arity * next_x));
+  out <== GlExtScalarMul()(GlExtMul()(sum, GlExtSub()(beta_pow_arity, next_x_ext)), weight);
+
+  var leaf[2 * arity];
+  for (var i = 0; i < arity; i++) {
+    leaf[2 * i] = evals[i][0];
+    leaf[2 * i + 1] = evals[i][1];
+  }
+  var depth = LDE_BITS() - CAP_HEIGHT() - bits_offset - arity_bits;
+  var siblings[depth][4];
+  for (var i = 0; i < depth; i++) {
+    siblings[i] = step_p[fri_step_siblings_offset(I) + i];
+  }
+  component merkle_proof = VerifyMerkleProof(2 * arity, bits_offset + arity_bits);
+  merkle_proof.leaf <== leaf;
+  merkle_proof.siblings <== siblings;
+  merkle_proof.index_bits <== index_bits;
+  merkle_proof.cap <== cap;
+}
+
+template VerifyQueryRound() {
+  var merkle_depth = LDE_BITS() - CAP_HEIGHT();
+  signal input index;
+  signal input init_constants_sigmas_v[NUM_PREPROCESSED_POLYS()];
+  signal input init_constants_sigmas_p[merkle_depth][4];
+  signal input init_wires_v[NUM_OPENINGS_WIRES()];
+  signal input init_wires_p[merkle_depth][4];
+  signal input init_zs_partial_products_v[NUM_ZS_PARTIAL_PRODUCTS_POLYS()];
+  signal input init_zs_partial_products_p[merkle_depth][4];
+  signal input init_quotient_v[NUM_QUOTIENT_POLYS()];
+  signal input init_quotient_p[merkle_depth][4];
+  signal input step_v[fri_step_evals_offset(NUM_REDUCTIONS())][2];
+  signal input step_p[fri_step_siblings_offset(NUM_REDUCTIONS())][4];
+  signal input wires_cap[CAP_LEN()][4];
+  signal input plonk_zs_partial_products_cap[CAP_LEN()][4];
+  signal input quotient_polys_cap[CAP_LEN()][4];
+  signal input commit_phase_merkle_caps[NUM_REDUCTIONS()][CAP_LEN()][4];
+  signal input final_poly[FINAL_POLY_LEN()][2];
+  signal input plonk_zeta[2];
+  signal input fri_alpha_powers[NUM_OPENINGS_AT_ZETA()][2];
+  signal input fri_betas[NUM_REDUCTIONS()][2];
+  // The openings at zeta and at `g * zeta` reduced with the powers of the FRI alpha.
+  signal input reduced_openings[2][2];
+
+  signal bits[64] <== ToBits(64)(index);
+  var index_bits[LDE_BITS()];
+  for (var i = 0; i < LDE_BITS(); i++) {
+    index_bits[i] = bits[i];
+  }
+
+  var constants_sigmas_cap[CAP_LEN()][4];
+  for (var i = 0; i < CAP_LEN(); i++) {
+    for (var j = 0; j < 4; j++) {
+      constants_sigmas_cap[i][j] = CONSTANTS_SIGMAS_CAP(i, j);
+    }
+  }
+  component merkle_proofs[4];
+  merkle_proofs[0] = VerifyMerkleProof(NUM_PREPROCESSED_POLYS(), 0);
+  merkle_proofs[0].leaf <== init_constants_sigmas_v;
+  merkle_proofs[0].siblings <== init_constants_sigmas_p;
+  merkle_proofs[0].cap <== constants_sigmas_cap;
+  merkle_proofs[1] = VerifyMerkleProof(NUM_OPENINGS_WIRES(), 0);
+  merkle_proofs[1].leaf <== init_wires_v;
+  merkle_proofs[1].siblings <== init_wires_p;
+  merkle_proofs[1].cap <== wires_cap;
+  merkle_proofs[2] = VerifyMerkleProof(NUM_ZS_PARTIAL_PRODUCTS_POLYS(), 0);
+  merkle_proofs[2].leaf <== init_zs_partial_products_v;
+  merkle_proofs[2].siblings <== init_zs_partial_products_p;
+  merkle_proofs[2].cap <== plonk_zs_partial_products_cap;
+  merkle_proofs[3] = VerifyMerkleProof(NUM_QUOTIENT_POLYS(), 0);
+  merkle_proofs[3].leaf <== init_quotient_v;
+  merkle_proofs[3].siblings <== init_quotient_p;
+  merkle_proofs[3].cap <== quotient_polys_cap;
+  for (var i = 0; i < 4; i++) {
+    merkle_proofs[i].index_bits <== index_bits;
+  }
+
+  // subgroup_x = g * w^rev(index), where `w` generates the subgroup of order 2^LDE_BITS().
+  signal subgroup_x[LDE_BITS() + 1];
+  subgroup_x[0] <== MULTIPLICATIVE_GROUP_GENERATOR();
+  for (var i = 0; i < LDE_BITS(); i++) {
+    var power = gl_exp(primitive_root_of_unity(LDE_BITS()), 1 << (LDE_BITS() - 1 - i));
+    subgroup_x[i + 1] <== GlReduce(128)(subgroup_x[i] * (1 + index_bits[i] * (power - 1)));
+  }
+  signal x <== subgroup_x[LDE_BITS()];
+  var x_ext[2] = [x, 0];
+
+  // Combines the initial openings into the first FRI evaluation.
+  var evals[NUM_OPENINGS_AT_ZETA()];
+  var num_evals = 0;
+  for (var i = 0; i < NUM_PREPROCESSED_POLYS(); i++) {
+    evals[num_evals] = init_constants_sigmas_v[i];
+    num_evals++;
+  }
+  for (var i = 0; i < NUM_OPENINGS_WIRES(); i++) {
+    evals[num_evals] = init_wires_v[i];
+    num_evals++;
+  }
+  for (var i = 0; i < NUM_ZS_PARTIAL_PRODUCTS_POLYS(); i++) {
+    evals[num_evals] = init_zs_partial_products_v[i];
+    num_evals++;
+  }
+  for (var i = 0; i < NUM_QUOTIENT_POLYS(); i++) {
+    evals[num_evals] = init_quotient_v[i];
+    num_evals++;
+  }
+  signal reduced_evals[2] <== GlExtScalarInnerProduct(NUM_OPENINGS_AT_ZETA())(fri_alpha_powers, evals);
+  signal sum[2] <== GlExtDiv()(GlExtSub()(reduced_evals, reduced_openings[0]), GlExtSub()(x_ext, plonk_zeta));
+  var zs_alpha_powers[NUM_CHALLENGES()][2];
+  var zs[NUM_CHALLENGES()];
+  for (var i = 0; i < NUM_CHALLENGES(); i++) {
+    zs_alpha_powers[i] = fri_alpha_powers[i];
+    zs[i] = init_zs_partial_products_v[i];
+  }
+  signal reduced_zs[2] <== GlExtScalarInnerProduct(NUM_CHALLENGES())(zs_alpha_powers, zs);
+  signal g_zeta[2] <== GlExtScalarMul()(plonk_zeta, DEGREE_GENERATOR());
+  signal zs_sum[2] <== GlExtDiv()(GlExtSub()(reduced_zs, reduced_openings[1]), GlExtSub()(x_ext, g_zeta));
+  signal initial_eval[2] <== GlExtScalarMul()(GlExtMulAdd()(sum, fri_alpha_powers[NUM_CHALLENGES()], zs_sum), x);
+
+  signal xs[NUM_REDUCTIONS() + 1];
+  signal old_evals[NUM_REDUCTIONS() + 1][2];
+  xs[0] <== x;
+  old_evals[0] <== initial_eval;
+  for (var i = 0; i < NUM_REDUCTIONS(); i++) {
+    xs[i + 1] <== GlExpPowerOf2(REDUCTION_ARITY_BITS(i))(xs[i]);
+    old_evals[i + 1] <== FriStep(i)(step_v, step_p, index_bits, commit_phase_merkle_caps[i], xs[i], xs[i + 1], old_evals[i], fri_betas[i]);
+  }
+
+  signal x_powers[FINAL_POLY_LEN()] <== GlPowers(FINAL_POLY_LEN())(xs[NUM_REDUCTIONS()]);
+  signal final_eval[2] <== GlExtScalarInnerProduct(FINAL_POLY_LEN())(final_poly, x_powers);
+  final_eval[0] === old_evals[NUM_REDUCTIONS()][0];
+  final_eval[1] === old_evals[NUM_REDUCTIONS()][1];
+}
+
+// ---------------------------------------------------------------------------------------------
+// Verifier.
+
+template Plonky2Verifier() {
+  var merkle_depth = LDE_BITS() - CAP_HEIGHT();
+  var num_step_evals = fri_step_evals_offset(NUM_REDUCTIONS());
+  var num_step_siblings = fri_step_siblings_offset(NUM_REDUCTIONS());
+  signal input public_inputs[NUM_PUBLIC_INPUTS()];
+  signal input wires_cap[CAP_LEN()][4];
+  signal input plonk_zs_partial_products_cap[CAP_LEN()][4];
+  signal input quotient_polys_cap[CAP_LEN()][4];
+  signal input openings_constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input openings_plonk_sigmas[NUM_ROUTED_WIRES()][2];
+  signal input openings_wires[NUM_OPENINGS_WIRES()][2];
+  signal input openings_plonk_zs[NUM_CHALLENGES()][2];
+  signal input openings_plonk_zs_next[NUM_CHALLENGES()][2];
+  signal input openings_partial_products[NUM_CHALLENGES() * NUM_PARTIAL_PRODUCTS()][2];
+  signal input openings_quotient_polys[NUM_QUOTIENT_POLYS()][2];
+  signal input fri_commit_phase_merkle_caps[NUM_REDUCTIONS()][CAP_LEN()][4];
+  signal input fri_query_init_constants_sigmas_v[NUM_QUERY_ROUNDS()][NUM_PREPROCESSED_POLYS()];
+  signal input fri_query_init_constants_sigmas_p[NUM_QUERY_ROUNDS()][merkle_depth][4];
+  signal input fri_query_init_wires_v[NUM_QUERY_ROUNDS()][NUM_OPENINGS_WIRES()];
+  signal input fri_query_init_wires_p[NUM_QUERY_ROUNDS()][merkle_depth][4];
+  signal input fri_query_init_zs_partial_v[NUM_QUERY_ROUNDS()][NUM_ZS_PARTIAL_PRODUCTS_POLYS()];
+  signal input fri_query_init_zs_partial_p[NUM_QUERY_ROUNDS()][merkle_depth][4];
+  signal input fri_query_init_quotient_v[NUM_QUERY_ROUNDS()][NUM_QUOTIENT_POLYS()];
+  signal input fri_query_init_quotient_p[NUM_QUERY_ROUNDS()][merkle_depth][4];
+  // The evaluations and Merkle proof siblings of all FRI steps of each query round.
+  signal input fri_query_step_v[NUM_QUERY_ROUNDS()][num_step_evals][2];
+  signal input fri_query_step_p[NUM_QUERY_ROUNDS()][num_step_siblings][4];
+  signal input fri_final_poly_ext_v[FINAL_POLY_LEN()][2];
+  signal input fri_pow_witness;
+
+  // All elements of the proof must be canonical. The transcript contains all of them except the
+  // public inputs and the query round proofs.
+  var transcript[TRANSCRIPT_LEN()];
+  var transcript_len = 8;
+  var query_round_len = NUM_OPENINGS_AT_ZETA() + 16 * merkle_depth + 2 * num_step_evals + 4 * num_step_siblings;
+  component range_checks[TRANSCRIPT_LEN() - 8 + NUM_PUBLIC_INPUTS() + NUM_QUERY_ROUNDS() * query_round_len];
+  var num_range_checks = 0;
+  for (var i = 0; i < NUM_PUBLIC_INPUTS(); i++) {
+    range_checks[num_range_checks] = GlRangeCheck();
+    range_checks[num_range_checks].in <== public_inputs[i];
+    num_range_checks++;
+  }
+  for (var i = 0; i < CAP_LEN(); i++) {
+    for (var j = 0; j < 4; j++) {
+      transcript[transcript_len] = wires_cap[i][j];
+      transcript[transcript_len + 4 * CAP_LEN()] = plonk_zs_partial_products_cap[i][j];
+      transcript[transcript_len + 8 * CAP_LEN()] = quotient_polys_cap[i][j];
+      transcript_len++;
+    }
+  }
+  transcript_len += 8 * CAP_LEN();
+  var openings[NUM_OPENINGS_AT_ZETA() + NUM_CHALLENGES()][2];
+  var num_openings = 0;
+  for (var i = 0; i < NUM_OPENINGS_CONSTANTS(); i++) {
+    openings[num_openings] = openings_constants[i];
+    num_openings++;
+  }
+  for (var i = 0; i < NUM_ROUTED_WIRES(); i++) {
+    openings[num_openings] = openings_plonk_sigmas[i];
+    num_openings++;
+  }
+  for (var i = 0; i < NUM_OPENINGS_WIRES(); i++) {
+    openings[num_openings] = openings_wires[i];
+    num_openings++;
+  }
+  for (var i = 0; i < NUM_CHALLENGES(); i++) {
+    openings[num_openings] = openings_plonk_zs[i];
+    num_openings++;
+  }
+  for (var i = 0; i < NUM_CHALLENGES() * NUM_PARTIAL_PRODUCTS(); i++) {
+    openings[num_openings] = openings_partial_products[i];
+    num_openings++;
+  }
+  for (var i = 0; i < NUM_QUOTIENT_POLYS(); i++) {
+    openings[num_openings] = openings_quotient_polys[i];
+    num_openings++;
+  }
+  for (var i = 0; i < NUM_CHALLENGES(); i++) {
+    openings[num_openings] = openings_plonk_zs_next[i];
+    num_openings++;
+  }
+  for (var i = 0; i < num_openings; i++) {
+    transcript[transcript_len] = openings[i][0];
+    transcript[transcript_len + 1] = openings[i][1];
+    transcript_len += 2;
+  }
+  for (var r = 0; r < NUM_REDUCTIONS(); r++) {
+    for (var i = 0; i < CAP_LEN(); i++) {
+      for (var j = 0; j < 4; j++) {
+        transcript[transcript_len] = fri_commit_phase_merkle_caps[r][i][j];
+        transcript_len++;
+      }
+    }
+  }
+  for (var i = 0; i < FINAL_POLY_LEN(); i++) {
+    transcript[transcript_len] = fri_final_poly_ext_v[i][0];
+    transcript[transcript_len + 1] = fri_final_poly_ext_v[i][1];
+    transcript_len += 2;
+  }
+  transcript[transcript_len] = fri_pow_witness;
+  transcript_len++;
+  for (var i = 8; i < transcript_len; i++) {
+    range_checks[num_range_checks] = GlRangeCheck();
+    range_checks[num_range_checks].in <== transcript[i];
+    num_range_checks++;
+  }
+  for (var r = 0; r < NUM_QUERY_ROUNDS(); r++) {
+    var round[query_round_len];
+    var round_len = 0;
+    for (var i = 0; i < NUM_PREPROCESSED_POLYS(); i++) {
+      round[round_len] = fri_query_init_constants_sigmas_v[r][i];
+      round_len++;
+    }
+    for (var i = 0; i < NUM_OPENINGS_WIRES(); i++) {
+      round[round_len] = fri_query_init_wires_v[r][i];
+      round_len++;
+    }
+    for (var i = 0; i < NUM_ZS_PARTIAL_PRODUCTS_POLYS(); i++) {
+      round[round_len] = fri_query_init_zs_partial_v[r][i];
+      round_len++;
+    }
+    for (var i = 0; i < NUM_QUOTIENT_POLYS(); i++) {
+      round[round_len] = fri_query_init_quotient_v[r][i];
+      round_len++;
+    }
+    for (var i = 0; i < merkle_depth; i++) {
+      for (var j = 0; j < 4; j++) {
+        round[round_len] = fri_query_init_constants_sigmas_p[r][i][j];
+        round[round_len + 1] = fri_query_init_wires_p[r][i][j];
+        round[round_len + 2] = fri_query_init_zs_partial_p[r][i][j];
+        round[round_len + 3] = fri_query_init_quotient_p[r][i][j];
+        round_len += 4;
+      }
+    }
+    for (var i = 0; i < num_step_evals; i++) {
+      round[round_len] = fri_query_step_v[r][i][0];
+      round[round_len + 1] = fri_query_step_v[r][i][1];
+      round_len += 2;
+    }
+    for (var i = 0; i < num_step_siblings; i++) {
+      for (var j = 0; j < 4; j++) {
+        round[round_len] = fri_query_step_p[r][i][j];
+        round_len++;
+      }
+    }
+    for (var i = 0; i < query_round_len; i++) {
+      range_checks[num_range_checks] = GlRangeCheck();
+      range_checks[num_range_checks].in <== round[i];
+      num_range_checks++;
+    }
+  }
+
+  signal public_inputs_hash[4] <== HashNoPadGoldilocks(NUM_PUBLIC_INPUTS())(public_inputs);
+  for (var i = 0; i < 4; i++) {
+    transcript[i] = CIRCUIT_DIGEST(i);
+    transcript[4 + i] = public_inputs_hash[i];
+  }
+  signal challenges[NUM_TRANSCRIPT_CHALLENGES()] <== Challenger()(transcript);
+  var num_challenges = 0;
+  var plonk_betas[NUM_CHALLENGES()];
+  var plonk_gammas[NUM_CHALLENGES()];
+  var plonk_alphas[NUM_CHALLENGES()];
+  for (var i = 0; i < NUM_CHALLENGES(); i++) {
+    plonk_betas[i] = challenges[i];
+    plonk_gammas[i] = challenges[NUM_CHALLENGES() + i];
+    plonk_alphas[i] = challenges[2 * NUM_CHALLENGES() + i];
+  }
+  num_challenges += 3 * NUM_CHALLENGES();
+  var plonk_zeta[2] = [challenges[num_challenges], challenges[num_challenges + 1]];
+  var fri_alpha[2] = [challenges[num_challenges + 2], challenges[num_challenges + 3]];
+  num_challenges += 4;
+  var fri_betas[NUM_REDUCTIONS()][2];
+  for (var i = 0; i < NUM_REDUCTIONS(); i++) {
+    fri_betas[i][0] = challenges[num_challenges];
+    fri_betas[i][1] = challenges[num_challenges + 1];
+    num_challenges += 2;
+  }
+  var fri_pow_response = challenges[num_challenges];
+  num_challenges++;
+
+  component verify_quotient = VerifyQuotient();
+  verify_quotient.openings_constants <== openings_constants;
+  verify_quotient.openings_plonk_sigmas <== openings_plonk_sigmas;
+  verify_quotient.openings_wires <== openings_wires;
+  verify_quotient.openings_plonk_zs <== openings_plonk_zs;
+  verify_quotient.openings_plonk_zs_next <== openings_plonk_zs_next;
+  verify_quotient.openings_partial_products <== openings_partial_products;
+  verify_quotient.openings_quotient_polys <== openings_quotient_polys;
+  verify_quotient.public_inputs_hash <== public_inputs_hash;
+  verify_quotient.plonk_betas <== plonk_betas;
+  verify_quotient.plonk_gammas <== plonk_gammas;
+  verify_quotient.plonk_alphas <== plonk_alphas;
+  verify_quotient.plonk_zeta <== plonk_zeta;
+
+  // The proof of work response must have POW_BITS() leading zeros.
+  component pow_check = RangeCheck(64 - POW_BITS());
+  pow_check.in <== fri_pow_response;
+
+  signal fri_alpha_powers[NUM_OPENINGS_AT_ZETA()][2] <== GlExtPowers(NUM_OPENINGS_AT_ZETA())(fri_alpha);
+  var openings_at_zeta[NUM_OPENINGS_AT_ZETA()][2];
+  for (var i = 0; i < NUM_OPENINGS_AT_ZETA(); i++) {
+    openings_at_zeta[i] = openings[i];
+  }
+  var zs_alpha_powers[NUM_CHALLENGES()][2];
+  for (var i = 0; i < NUM_CHALLENGES(); i++) {
+    zs_alpha_powers[i] = fri_alpha_powers[i];
+  }
+  signal reduced_openings[2][2];
+  reduced_openings[0] <== GlExtInnerProduct(NUM_OPENINGS_AT_ZETA())(openings_at_zeta, fri_alpha_powers);
+  reduced_openings[1] <== GlExtInnerProduct(NUM_CHALLENGES())(openings_plonk_zs_next, zs_alpha_powers);
+
+  component rounds[NUM_QUERY_ROUNDS()];
+  for (var r = 0; r < NUM_QUERY_ROUNDS(); r++) {
+    rounds[r] = VerifyQueryRound();
+    rounds[r].index <== challenges[num_challenges + r];
+    rounds[r].init_constants_sigmas_v <== fri_query_init_constants_sigmas_v[r];
+    rounds[r].init_constants_sigmas_p <== fri_query_init_constants_sigmas_p[r];
+    rounds[r].init_wires_v <== fri_query_init_wires_v[r];
+    rounds[r].init_wires_p <== fri_query_init_wires_p[r];
+    rounds[r].init_zs_partial_products_v <== fri_query_init_zs_partial_v[r];
+    rounds[r].init_zs_partial_products_p <== fri_query_init_zs_partial_p[r];
+    rounds[r].init_quotient_v <== fri_query_init_quotient_v[r];
+    rounds[r].init_quotient_p <== fri_query_init_quotient_p[r];
+    rounds[r].step_v <== fri_query_step_v[r];
+    rounds[r].step_p <== fri_query_step_p[r];
+    rounds[r].wires_cap <== wires_cap;
+    rounds[r].plonk_zs_partial_products_cap <== plonk_zs_partial_products_cap;
+    rounds[r].quotient_polys_cap <== quotient_polys_cap;
+    rounds[r].commit_phase_merkle_caps <== fri_commit_phase_merkle_caps;
+    rounds[r].final_poly <== fri_final_poly_ext_v;
+    rounds[r].plonk_zeta <== plonk_zeta;
+    rounds[r].fri_alpha_powers <== fri_alpha_powers;
+    rounds[r].fri_betas <== fri_betas;
+    rounds[r].reduced_openings <== reduced_openings;
+  }
+}
+
+component main {public [public_inputs]} = Plonky2Verifier();
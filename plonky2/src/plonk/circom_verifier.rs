@@ -0,0 +1,563 @@
+//! Generation of circom verifier circuits for proofs using [`PoseidonBN128GoldilocksConfig`].
+//!
+//! The generated circuit works over the BN254 scalar field, so that it can be proven with Groth16,
+//! and emulates the Goldilocks field. It bundles the Goldilocks arithmetic, the Poseidon
+//! permutations, the constraint evaluation code of every gate used by the circuit and the FRI
+//! verifier. Its main component takes the proof as input signals, as produced by
+//! [`generate_circom_input`], and exposes the public inputs of the proof as its public signals.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::Write;
+
+use anyhow::{bail, ensure, Result};
+use hashbrown::HashMap;
+
+use crate::field::extension::{Extendable, FieldExtension};
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::field::types::{Field, PrimeField64};
+use crate::gates::selectors::UNUSED_SELECTOR;
+use crate::hash::merkle_tree::MerkleCap;
+use crate::hash::poseidon::{Poseidon, ALL_ROUND_CONSTANTS};
+use crate::hash::poseidon_bn128::PoseidonBN128GoldilocksConfig;
+use crate::hash::poseidon_bn128_ops;
+use crate::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
+use crate::plonk::config::GenericConfig;
+use crate::plonk::proof::ProofWithPublicInputs;
+use crate::plonk::verifier_codegen::{circom_fr_array, circom_u64_array, hash_elements};
+
+type F = GoldilocksField;
+type C = PoseidonBN128GoldilocksConfig;
+const D: usize = 2;
+
+const GOLDILOCKS_TEMPLATE: &str = include_str!("circom/goldilocks.circom");
+const POSEIDON_TEMPLATE: &str = include_str!("circom/poseidon.circom");
+const VERIFIER_TEMPLATE: &str = include_str!("circom/verifier.circom");
+
+/// Returns the source of a circom circuit whose main component `Plonky2Verifier` is satisfiable
+/// exactly for the proofs of the given circuit accepted by the native verifier, up to a negligible
+/// completeness loss.
+///
+/// Circuits using lookups or zero-knowledge are not supported.
+pub fn generate_circom_verifier(
+    common: &CommonCircuitData<F, D>,
+    verifier_only: &VerifierOnlyCircuitData<C, D>,
+) -> Result<String> {
+    ensure!(
+        common.num_lookup_polys == 0,
+        "lookups are not supported by the circom verifier"
+    );
+    ensure!(
+        !common.fri_params.hiding && !common.config.zero_knowledge,
+        "zero-knowledge proofs are not supported by the circom verifier"
+    );
+    // The native verifier opens the `Z`s at `g * zeta` for `g` in the extension field.
+    let [degree_generator, degree_generator_high] = FieldExtension::<D>::to_basefield_array(
+        &<F as Extendable<D>>::Extension::primitive_root_of_unity(common.degree_bits()),
+    );
+    ensure!(
+        degree_generator_high.is_zero(),
+        "the subgroup generator is not in the base field"
+    );
+    // circom does not allow arrays of size zero.
+    ensure!(
+        common.num_public_inputs > 0,
+        "circuits without public inputs are not supported by the circom verifier"
+    );
+    ensure!(
+        common.num_partial_products > 0,
+        "circuits without partial products are not supported by the circom verifier"
+    );
+    let cap_height = common.config.fri_config.cap_height;
+    let reduction_arity_bits = &common.fri_params.reduction_arity_bits;
+    ensure!(
+        cap_height > 0,
+        "a cap height of zero is not supported by the circom verifier"
+    );
+    ensure!(
+        !reduction_arity_bits.is_empty(),
+        "FRI without reductions is not supported by the circom verifier"
+    );
+    ensure!(
+        common.fri_params.lde_bits() > cap_height + reduction_arity_bits.iter().sum::<usize>(),
+        "the Merkle proofs of the last FRI step must not be empty"
+    );
+
+    let mut source = String::from("pragma circom 2.1.0;\n\n");
+    let mut push_constant = |name: &str, value: usize| {
+        writeln!(source, "function {name}() {{\n  return {value};\n}}").unwrap();
+    };
+    push_constant("NUM_PUBLIC_INPUTS", common.num_public_inputs);
+    push_constant("CAP_HEIGHT", cap_height);
+    push_constant("CAP_LEN", common.config.fri_config.num_cap_elements());
+    push_constant("NUM_OPENINGS_CONSTANTS", common.num_constants);
+    push_constant("NUM_OPENINGS_WIRES", common.config.num_wires);
+    push_constant("NUM_ROUTED_WIRES", common.config.num_routed_wires);
+    push_constant("NUM_CHALLENGES", common.config.num_challenges);
+    push_constant("NUM_PARTIAL_PRODUCTS", common.num_partial_products);
+    push_constant("QUOTIENT_DEGREE_FACTOR", common.quotient_degree_factor);
+    push_constant("NUM_GATE_CONSTRAINTS", common.num_gate_constraints);
+    push_constant("DEGREE_BITS", common.degree_bits());
+    push_constant("LDE_BITS", common.fri_params.lde_bits());
+    push_constant(
+        "NUM_QUERY_ROUNDS",
+        common.config.fri_config.num_query_rounds,
+    );
+    push_constant(
+        "POW_BITS",
+        common.config.fri_config.proof_of_work_bits as usize,
+    );
+    push_constant("FINAL_POLY_LEN", common.fri_params.final_poly_len());
+    push_constant("NUM_REDUCTIONS", reduction_arity_bits.len());
+    push_constant("NUM_PREPROCESSED_POLYS", common.num_preprocessed_polys());
+    push_constant(
+        "NUM_ZS_PARTIAL_PRODUCTS_POLYS",
+        common.num_zs_partial_products_polys(),
+    );
+    push_constant("NUM_QUOTIENT_POLYS", common.num_quotient_polys());
+    writeln!(
+        source,
+        "// The generator of the subgroup of order `2^DEGREE_BITS()`.\nfunction DEGREE_GENERATOR() {{\n  return {};\n}}",
+        degree_generator.to_canonical_u64()
+    )
+    .unwrap();
+
+    let mut push_array = |name: &str, values: &[u64]| {
+        writeln!(
+            source,
+            "function {name}(i) {{\n  var value[{}] = [{}];\n  return value[i];\n}}",
+            values.len(),
+            circom_u64_array(values)
+        )
+        .unwrap();
+    };
+    push_array(
+        "REDUCTION_ARITY_BITS",
+        &reduction_arity_bits
+            .iter()
+            .map(|&bits| bits as u64)
+            .collect::<Vec<_>>(),
+    );
+    push_array(
+        "CIRCUIT_DIGEST",
+        &hash_elements(&[verifier_only.circuit_digest]),
+    );
+    push_array(
+        "K_IS",
+        &common
+            .k_is
+            .iter()
+            .map(|k| k.to_canonical_u64())
+            .collect::<Vec<_>>(),
+    );
+    let constants_sigmas_cap = hash_elements(&verifier_only.constants_sigmas_cap.0);
+    writeln!(
+        source,
+        "function CONSTANTS_SIGMAS_CAP(i, j) {{\n  var value[{}] = [{}];\n  return value[4 * i + j];\n}}",
+        constants_sigmas_cap.len(),
+        circom_u64_array(&constants_sigmas_cap)
+    )
+    .unwrap();
+    source += "\n";
+
+    source += GOLDILOCKS_TEMPLATE;
+    source += "\n";
+    source += &poseidon_template();
+    source += "\n";
+
+    let (gate_templates, gate_evaluation) = gate_templates(common)?;
+    source += &gate_templates;
+    source += &VERIFIER_TEMPLATE
+        .replace("$NUM_GATES", &common.gates.len().to_string())
+        .replace("$GATE_EVALUATION\n", &gate_evaluation);
+    ensure!(
+        !source.contains('$'),
+        "unsubstituted placeholder in the generated verifier"
+    );
+    Ok(source)
+}
+
+fn poseidon_template() -> String {
+    let bn128_mds = poseidon_bn128_ops::MDS_MATRIX
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+    POSEIDON_TEMPLATE
+        .replace(
+            "$GL_NUM_ROUND_CONSTANTS",
+            &ALL_ROUND_CONSTANTS.len().to_string(),
+        )
+        .replace(
+            "$GL_ROUND_CONSTANTS",
+            &circom_u64_array(&ALL_ROUND_CONSTANTS),
+        )
+        .replace(
+            "$GL_MDS_MATRIX_CIRC",
+            &circom_u64_array(&<F as Poseidon>::MDS_MATRIX_CIRC),
+        )
+        .replace(
+            "$GL_MDS_MATRIX_DIAG",
+            &circom_u64_array(&<F as Poseidon>::MDS_MATRIX_DIAG),
+        )
+        .replace(
+            "$BN128_NUM_ROUND_CONSTANTS",
+            &poseidon_bn128_ops::ROUND_CONSTANTS.len().to_string(),
+        )
+        .replace(
+            "$BN128_ROUND_CONSTANTS",
+            &circom_fr_array(&poseidon_bn128_ops::ROUND_CONSTANTS),
+        )
+        .replace("$BN128_MDS_MATRIX", &circom_fr_array(&bn128_mds))
+}
+
+/// Splits the code exported by a gate into its template and its top-level functions, keyed by
+/// name.
+fn split_functions(code: &str) -> (String, Vec<(String, String)>) {
+    let mut template = String::new();
+    let mut functions: Vec<(String, String)> = Vec::new();
+    let mut in_function = false;
+    for line in code.lines() {
+        if let Some(rest) = line.strip_prefix("function ") {
+            let name = rest.split('(').next().unwrap_or_default().trim();
+            functions.push((name.to_string(), String::new()));
+            in_function = true;
+        }
+        if in_function {
+            let function = &mut functions.last_mut().unwrap().1;
+            *function += line;
+            *function += "\n";
+            in_function = line != "}";
+        } else {
+            template += line;
+            template += "\n";
+        }
+    }
+    (template, functions)
+}
+
+/// Returns the templates evaluating the constraints of each gate of the circuit, along with the
+/// functions they use, and the code accumulating their filtered constraints.
+fn gate_templates(common: &CommonCircuitData<F, D>) -> Result<(String, String)> {
+    let num_selectors = common.selectors_info.num_selectors();
+    let mut templates = String::new();
+    let mut evaluation = String::new();
+    let mut templates_by_name: HashMap<String, String> = HashMap::new();
+    let mut functions: HashMap<String, String> = HashMap::new();
+    let mut function_names = Vec::new();
+    for (i, gate) in common.gates.iter().enumerate() {
+        let selector_index = common.selectors_info.selector_indices[i];
+        let mut factors = Vec::new();
+        for j in common.selectors_info.groups[selector_index].clone() {
+            if j != i {
+                factors.push(j as u64);
+            }
+        }
+        if num_selectors > 1 {
+            factors.push(UNUSED_SELECTOR as u64);
+        }
+        let filter = factors
+            .into_iter()
+            .map(|j| format!("GlExtSub()(GlExt({j}, 0)(), constants[{selector_index}])"))
+            .reduce(|acc, factor| format!("GlExtMul()({acc}, {factor})"))
+            .unwrap_or_else(|| "GlExt(1, 0)()".to_string());
+
+        let code = gate
+            .0
+            .export_circom_verification_code()
+            .replace("$SET_FILTER", &format!("filter <== {filter}"))
+            .replace(
+                "$NUM_SELECTORS",
+                &(num_selectors + common.num_lookup_selectors).to_string(),
+            )
+            .replace("$D", &D.to_string());
+        let (template, gate_functions) = split_functions(&code);
+        let name = template
+            .split_once("template ")
+            .and_then(|(_, rest)| rest.split('(').next())
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        ensure!(
+            !name.is_empty(),
+            "no template in the code of gate {}",
+            gate.0.id()
+        );
+        // Gates without constraints in the main trace, such as the lookup gates, share a template.
+        match templates_by_name.get(&name) {
+            Some(existing) if *existing != template => {
+                bail!("several gates define different templates named {name}")
+            }
+            Some(_) => {}
+            None => {
+                templates += &template;
+                templates += "\n";
+                templates_by_name.insert(name.clone(), template);
+            }
+        }
+        for (function_name, function) in gate_functions {
+            match functions.get(&function_name) {
+                Some(existing) if *existing != function => {
+                    bail!("several gates define different functions named {function_name}")
+                }
+                Some(_) => {}
+                None => {
+                    function_names.push(function_name.clone());
+                    functions.insert(function_name, function);
+                }
+            }
+        }
+        writeln!(
+            evaluation,
+            "  constraints[{}] <== {name}()(constants, wires, public_input_hash, constraints[{i}]);",
+            i + 1
+        )
+        .unwrap();
+    }
+    for name in function_names {
+        templates += &functions[&name];
+        templates += "\n";
+    }
+    Ok((templates, evaluation))
+}
+
+/// Writes `values` as a JSON array of decimal strings with the given dimensions but the first one.
+fn write_json_array(json: &mut String, values: &[u64], dims: &[usize]) {
+    json.push('[');
+    if dims.is_empty() {
+        let elements = values
+            .iter()
+            .map(|v| format!("\"{v}\""))
+            .collect::<Vec<_>>();
+        *json += &elements.join(", ");
+    } else {
+        for (i, chunk) in values.chunks(dims.iter().product()).enumerate() {
+            if i > 0 {
+                *json += ", ";
+            }
+            write_json_array(json, chunk, &dims[1..]);
+        }
+    }
+    json.push(']');
+}
+
+/// Returns the input signals of the main component of a circuit generated by
+/// [`generate_circom_verifier`] for the given proof, as the contents of an `input.json` file.
+pub fn generate_circom_input(proof_with_pis: &ProofWithPublicInputs<F, C, D>) -> String {
+    fn ext(values: &[<F as Extendable<D>>::Extension]) -> Vec<u64> {
+        values
+            .iter()
+            .flat_map(|x| FieldExtension::<D>::to_basefield_array(x).map(|y| y.to_canonical_u64()))
+            .collect()
+    }
+    fn base(values: &[F]) -> Vec<u64> {
+        values.iter().map(|x| x.to_canonical_u64()).collect()
+    }
+    fn cap(cap: &MerkleCap<F, <C as GenericConfig<D>>::Hasher>) -> Vec<u64> {
+        hash_elements(&cap.0)
+    }
+
+    let proof = &proof_with_pis.proof;
+    let openings = &proof.openings;
+    let fri_proof = &proof.opening_proof;
+    let rounds = &fri_proof.query_round_proofs;
+
+    // Each entry is a signal name, its flattened values and its dimensions but the first one.
+    let mut inputs: Vec<(&str, Vec<u64>, Vec<usize>)> = vec![
+        ("public_inputs", base(&proof_with_pis.public_inputs), vec![]),
+        ("wires_cap", cap(&proof.wires_cap), vec![4]),
+        (
+            "plonk_zs_partial_products_cap",
+            cap(&proof.plonk_zs_partial_products_cap),
+            vec![4],
+        ),
+        (
+            "quotient_polys_cap",
+            cap(&proof.quotient_polys_cap),
+            vec![4],
+        ),
+        ("openings_constants", ext(&openings.constants), vec![D]),
+        (
+            "openings_plonk_sigmas",
+            ext(&openings.plonk_sigmas),
+            vec![D],
+        ),
+        ("openings_wires", ext(&openings.wires), vec![D]),
+        ("openings_plonk_zs", ext(&openings.plonk_zs), vec![D]),
+        (
+            "openings_plonk_zs_next",
+            ext(&openings.plonk_zs_next),
+            vec![D],
+        ),
+        (
+            "openings_partial_products",
+            ext(&openings.partial_products),
+            vec![D],
+        ),
+        (
+            "openings_quotient_polys",
+            ext(&openings.quotient_polys),
+            vec![D],
+        ),
+        (
+            "fri_commit_phase_merkle_caps",
+            fri_proof
+                .commit_phase_merkle_caps
+                .iter()
+                .flat_map(cap)
+                .collect(),
+            vec![fri_proof.commit_phase_merkle_caps[0].0.len(), 4],
+        ),
+    ];
+    let initial_trees = [
+        (
+            "fri_query_init_constants_sigmas_v",
+            "fri_query_init_constants_sigmas_p",
+        ),
+        ("fri_query_init_wires_v", "fri_query_init_wires_p"),
+        ("fri_query_init_zs_partial_v", "fri_query_init_zs_partial_p"),
+        ("fri_query_init_quotient_v", "fri_query_init_quotient_p"),
+    ];
+    for (tree, (evals_name, siblings_name)) in initial_trees.into_iter().enumerate() {
+        let mut evals = Vec::new();
+        let mut siblings = Vec::new();
+        for round in rounds {
+            let (tree_evals, merkle_proof) = &round.initial_trees_proof.evals_proofs[tree];
+            evals.extend(base(tree_evals));
+            siblings.extend(hash_elements(&merkle_proof.siblings));
+        }
+        let (tree_evals, merkle_proof) = &rounds[0].initial_trees_proof.evals_proofs[tree];
+        inputs.push((evals_name, evals, vec![tree_evals.len()]));
+        inputs.push((
+            siblings_name,
+            siblings,
+            vec![merkle_proof.siblings.len(), 4],
+        ));
+    }
+    // The evaluations and Merkle proof siblings of all steps of a round are concatenated.
+    let mut step_evals = Vec::new();
+    let mut step_siblings = Vec::new();
+    for round in rounds {
+        for step in &round.steps {
+            step_evals.extend(ext(&step.evals));
+            step_siblings.extend(hash_elements(&step.merkle_proof.siblings));
+        }
+    }
+    let steps = &rounds[0].steps;
+    let num_step_evals = steps.iter().map(|step| step.evals.len()).sum();
+    let num_step_siblings = steps
+        .iter()
+        .map(|step| step.merkle_proof.siblings.len())
+        .sum();
+    inputs.push(("fri_query_step_v", step_evals, vec![num_step_evals, D]));
+    inputs.push((
+        "fri_query_step_p",
+        step_siblings,
+        vec![num_step_siblings, 4],
+    ));
+    inputs.push((
+        "fri_final_poly_ext_v",
+        ext(&fri_proof.final_poly.coeffs),
+        vec![D],
+    ));
+
+    let mut json = String::from("{\n");
+    for (name, values, dims) in &inputs {
+        write!(json, "  \"{name}\": ").unwrap();
+        write_json_array(&mut json, values, dims);
+        json += ",\n";
+    }
+    writeln!(
+        json,
+        "  \"fri_pow_witness\": \"{}\"\n}}",
+        fri_proof.pow_witness.to_canonical_u64()
+    )
+    .unwrap();
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::Field;
+    use crate::gates::noop::NoopGate;
+    use crate::gates::poseidon2_mds::Poseidon2MdsGate;
+    use crate::hash::poseidon2::Poseidon2Hash;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+
+    #[test]
+    fn test_generate_circom_verifier() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.exp_u64(x, 123);
+        builder.register_public_input(x);
+        builder.register_public_input(y);
+        // Enough rows for FRI to reduce the degree at least once.
+        for _ in 0..1000 {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(5));
+        let proof = data.prove(pw)?;
+        data.verify(proof.clone())?;
+
+        let source = generate_circom_verifier(&data.common, &data.verifier_only)?;
+        assert!(!source.contains('$'));
+        assert!(source.contains("component main {public [public_inputs]}"));
+
+        let input = generate_circom_input(&proof);
+        assert!(input.starts_with("{\n  \"public_inputs\": [\"5\", "));
+        assert!(input.contains("\"fri_query_init_quotient_p\": [[["));
+        assert!(input.contains("\"fri_query_step_v\": [[["));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_circom_verifier_poseidon2_gates() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let inputs = builder.add_virtual_targets(4);
+        let hash = builder.hash_n_to_hash_no_pad::<Poseidon2Hash>(inputs);
+        builder.register_public_inputs(&hash.elements);
+        builder.add_gate(Poseidon2MdsGate::<F, D>::new(), vec![]);
+        // Enough rows for FRI to reduce the degree at least once.
+        for _ in 0..1000 {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        let data = builder.build::<C>();
+
+        let source = generate_circom_verifier(&data.common, &data.verifier_only)?;
+        assert!(source.contains("template Poseidon2_12()"));
+        assert!(source.contains("template Poseidon2MdsGate12()"));
+        assert!(source.contains("function POSEIDON2_INTERNAL_DIAG(i)"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_circom_verifier_rejects_lookups() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let table = (0..16u16).map(|i| (i, i * i)).collect::<Vec<_>>();
+        let table_index = builder.add_lookup_table_from_pairs(table.into());
+        let square = builder.add_lookup_from_index(x, table_index);
+        builder.register_public_input(x);
+        builder.register_public_input(square);
+        let data = builder.build::<C>();
+
+        let err = generate_circom_verifier(&data.common, &data.verifier_only).unwrap_err();
+        assert!(err.to_string().contains("lookups are not supported"));
+    }
+}
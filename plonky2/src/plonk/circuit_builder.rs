@@ -24,11 +24,11 @@ use crate::gates::arithmetic_base::ArithmeticGate;
 use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
 use crate::gates::constant::ConstantGate;
 use crate::gates::gate::{CurrentSlot, Gate, GateInstance, GateRef};
-use crate::gates::lookup::Lookup;
+use crate::gates::lookup::{Lookup, LookupGate};
 use crate::gates::lookup_table::LookupTable;
 use crate::gates::noop::NoopGate;
 use crate::gates::public_input::PublicInputGate;
-use crate::gates::selectors::selector_polynomials;
+use crate::gates::selectors::{selector_ends_lookups, selector_polynomials, selectors_lookup};
 use crate::hash::hash_types::{HashOut, HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_proofs::MerkleProofTarget;
 use crate::hash::merkle_tree::MerkleCap;
@@ -44,13 +44,15 @@ use crate::plonk::circuit_data::{
 };
 use crate::plonk::config::{AlgebraicHasher, GenericConfig, GenericHashOut, Hasher};
 use crate::plonk::copy_constraint::CopyConstraint;
+use crate::plonk::mock_prover::CircuitLayout;
 use crate::plonk::permutation_argument::Forest;
 use crate::plonk::plonk_common::PlonkOracle;
+use crate::plonk::unconstrained::find_unconstrained_targets;
 use crate::timed;
 use crate::util::context_tree::ContextTree;
 use crate::util::partial_products::num_partial_products;
 use crate::util::timing::TimingTree;
-use crate::util::{log2_ceil, log2_strict, transpose, transpose_poly_values};
+use crate::util::{ceil_div_usize, log2_ceil, log2_strict, transpose, transpose_poly_values};
 
 /// Number of random coins needed for lookups (for each challenge).
 /// A coin is a randomly sampled extension field element from the verifier,
@@ -146,7 +148,7 @@ pub struct CircuitBuilder<F: RichField + Extendable<D>, const D: usize> {
     domain_separator: Option<Vec<F>>,
 
     /// The types of gates used in this circuit.
-    gates: HashSet<GateRef<F, D>>,
+    pub(crate) gates: HashSet<GateRef<F, D>>,
 
     /// The concrete placement of each gate.
     pub(crate) gate_instances: Vec<GateInstance<F, D>>,
@@ -198,6 +200,9 @@ pub struct CircuitBuilder<F: RichField + Extendable<D>, const D: usize> {
     /// Optional verifier data that is registered as public inputs.
     /// This is used in cyclic recursion to hold the circuit's own verifier key.
     pub(crate) verifier_data_public_input: Option<VerifierCircuitTarget>,
+
+    /// Whether building fails if some target is written by a generator but not constrained.
+    fail_on_unconstrained_targets: bool,
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
@@ -227,6 +232,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             luts: Vec::new(),
             goal_common_data: None,
             verifier_data_public_input: None,
+            fail_on_unconstrained_targets: false,
         };
         builder.check_config();
         builder
@@ -262,6 +268,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.domain_separator = Some(separator);
     }
 
+    /// Makes building fail if some target is written by a witness generator, but not constrained
+    /// by any gate. The offending targets are logged along with the scope that created them.
+    pub fn set_fail_on_unconstrained_targets(&mut self, fail: bool) {
+        self.fail_on_unconstrained_targets = fail;
+    }
+
     /// Outputs the number of gates in this circuit.
     pub fn num_gates(&self) -> usize {
         self.gate_instances.len()
@@ -679,11 +691,13 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     }
 
     pub fn push_context(&mut self, level: log::Level, ctx: &str) {
-        self.context_log.push(ctx, level, self.num_gates());
+        self.context_log
+            .push(ctx, level, self.num_gates(), self.virtual_target_index);
     }
 
     pub fn pop_context(&mut self) {
-        self.context_log.pop(self.num_gates());
+        self.context_log
+            .pop(self.num_gates(), self.virtual_target_index);
     }
 
     /// Returns the total number of LUTs.
@@ -1043,9 +1057,19 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     }
 
     pub fn try_build_with_options<C: GenericConfig<D, F = F>>(
+        self,
+        commit_to_sigma: bool,
+    ) -> (CircuitData<F, C, D>, bool) {
+        let (circuit_data, success, _) = self.try_build_with_layout(commit_to_sigma);
+        (circuit_data, success)
+    }
+
+    /// Like `try_build_with_options`, but also returns the layout of the circuit, which is needed
+    /// to check witnesses against it.
+    fn try_build_with_layout<C: GenericConfig<D, F = F>>(
         mut self,
         _commit_to_sigma: bool,
-    ) -> (CircuitData<F, C, D>, bool) {
+    ) -> (CircuitData<F, C, D>, bool, CircuitLayout<F, D>) {
         let mut timing = TimingTree::new("preprocess", Level::Trace);
 
         #[cfg(feature = "std")]
@@ -1054,7 +1078,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let rate_bits = self.config.fri_config.rate_bits;
         let cap_height = self.config.fri_config.cap_height;
         // Total number of LUTs.
-        // let num_luts = self.get_luts_length();
+        let num_luts = self.get_luts_length();
         // Hash the public inputs, and route them to a `PublicInputGate` which will enforce that
         // those hash wires match the claimed public inputs.
         let num_public_inputs = self.public_inputs.len();
@@ -1073,7 +1097,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.randomize_unused_pi_wires(pi_gate);
 
         // Place LUT-related gates.
-        // self.add_all_lookups();
+        self.add_all_lookups();
 
         // Make sure we have enough constant generators. If not, add a `ConstantGate`.
         while self.constants_to_targets.len() > self.constant_generators.len() {
@@ -1123,6 +1147,20 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         gates.sort_unstable_by_key(|g| (g.0.degree(), g.0.id()));
         let (mut constant_vecs, selectors_info) =
             selector_polynomials(&gates, &self.gate_instances, quotient_degree_factor + 1);
+
+        // Get the lookup selectors.
+        let num_lookup_selectors = if num_luts != 0 {
+            let selector_lookups =
+                selectors_lookup(&gates, &self.gate_instances, &self.lookup_rows);
+            let selector_ends = selector_ends_lookups(&self.lookup_rows, &self.gate_instances);
+            let all_lookup_selectors = [selector_lookups, selector_ends].concat();
+            let num_lookup_selectors = all_lookup_selectors.len();
+            constant_vecs.extend(all_lookup_selectors);
+            num_lookup_selectors
+        } else {
+            0
+        };
+
         constant_vecs.extend(self.constant_polys());
         let num_constants = constant_vecs.len();
 
@@ -1197,6 +1235,14 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let num_partial_products =
             num_partial_products(self.config.num_routed_wires, quotient_degree_factor);
 
+        let lookup_degree = self.config.max_quotient_degree_factor - 1;
+        let num_lookup_polys = if num_luts == 0 {
+            0
+        } else {
+            // There is 1 RE polynomial and multiple Sum/LDC polynomials.
+            ceil_div_usize(LookupGate::num_slots(&self.config), lookup_degree) + 1
+        };
+
         let constants_sigmas_cap = constants_sigmas_commitment.merkle_tree.cap.clone();
         let domain_separator = self.domain_separator.unwrap_or_default();
         let domain_separator_digest = C::Hasher::hash_pad(&domain_separator);
@@ -1222,6 +1268,9 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             num_public_inputs,
             k_is,
             num_partial_products,
+            num_lookup_polys,
+            num_lookup_selectors,
+            luts: self.luts,
         };
 
         let mut success = true;
@@ -1243,6 +1292,8 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             representative_map: forest.parents,
             fft_root_table: Some(fft_root_table),
             circuit_digest,
+            lookup_rows: self.lookup_rows,
+            lut_to_lookups: self.lut_to_lookups,
         };
 
         let verifier_only = VerifierOnlyCircuitData {
@@ -1251,6 +1302,21 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         };
 
         timing.print();
+        let layout = CircuitLayout {
+            gate_instances: self.gate_instances,
+            copy_constraints: self.copy_constraints,
+            context_log: self.context_log,
+        };
+        if self.fail_on_unconstrained_targets {
+            let unconstrained = find_unconstrained_targets(&prover_only, &common, &layout);
+            for target in &unconstrained {
+                warn!("{target}");
+            }
+            if !unconstrained.is_empty() {
+                success = false;
+            }
+        }
+
         #[cfg(feature = "std")]
         debug!("Building circuit took {}s", start.elapsed().as_secs_f32());
         (
@@ -1260,6 +1326,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
                 common,
             },
             success,
+            layout,
         )
     }
 
@@ -1268,13 +1335,19 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         self.build_with_options(true)
     }
 
+    /// Builds a circuit for witness generation and checking only.
     pub fn mock_build<C: GenericConfig<D, F = F>>(self) -> MockCircuitData<F, C, D> {
-        let circuit_data = self.build_with_options(false);
+        let (circuit_data, success, layout) = self.try_build_with_layout(false);
+        if !success {
+            panic!("Failed to build circuit");
+        }
         MockCircuitData {
             prover_only: circuit_data.prover_only,
             common: circuit_data.common,
+            layout,
         }
     }
+
     /// Builds a "prover circuit", with data needed to generate proofs but not verify them.
     pub fn build_prover<C: GenericConfig<D, F = F>>(self) -> ProverCircuitData<F, C, D> {
         // TODO: Can skip parts of this.
@@ -13,13 +13,13 @@
 //! This is useful to allow even small devices to verify plonky2 proofs.
 
 #[cfg(not(feature = "std"))]
-use alloc::{collections::BTreeMap, vec, vec::Vec};
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
 use core::ops::{Range, RangeFrom};
 #[cfg(feature = "std")]
 use std::collections::BTreeMap;
 
 use anyhow::Result;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::field::extension::Extendable;
 use crate::field::fft::FftRootTable;
@@ -32,6 +32,8 @@ use crate::fri::structure::{
 };
 use crate::fri::{FriConfig, FriParams};
 use crate::gates::gate::GateRef;
+use crate::gates::lookup::Lookup;
+use crate::gates::lookup_table::LookupTable;
 use crate::gates::selectors::SelectorsInfo;
 use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_tree::MerkleCap;
@@ -39,12 +41,14 @@ use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::generator::{generate_partial_witness, WitnessGeneratorRef};
 use crate::iop::target::Target;
 use crate::iop::witness::{PartialWitness, PartitionWitness};
-use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_builder::{CircuitBuilder, LookupWire};
 use crate::plonk::config::{GenericConfig, Hasher};
+use crate::plonk::mock_prover::CircuitLayout;
 use crate::plonk::plonk_common::PlonkOracle;
 use crate::plonk::proof::{CompressedProofWithPublicInputs, ProofWithPublicInputs};
-use crate::plonk::prover::prove;
-use crate::plonk::verifier::verify;
+use crate::plonk::prover::{prove, try_prove, ProveError};
+use crate::plonk::verifier::{verify, VerificationError};
+use crate::util::serialization::envelope::{self, EnvelopeError, PayloadKind};
 use crate::util::serialization::{
     Buffer, GateSerializer, IoResult, Read, WitnessGeneratorSerializer, Write,
 };
@@ -55,7 +59,7 @@ use crate::util::timing::TimingTree;
 ///
 /// It supports a [`Default`] implementation tailored for recursion with Poseidon hash (of width 12)
 /// as internal hash function and FRI rate of 1/8.
-#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct CircuitConfig {
     /// The number of wires available at each row. This corresponds to the "width" of the circuit,
     /// and consists in the sum of routed wires and advice wires.
@@ -143,6 +147,7 @@ pub struct MockCircuitData<F: RichField + Extendable<D>, C: GenericConfig<D, F =
 {
     pub prover_only: ProverOnlyCircuitData<F, C, D>,
     pub common: CommonCircuitData<F, D>,
+    pub(crate) layout: CircuitLayout<F, D>,
 }
 
 impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
@@ -183,6 +188,61 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         buffer.read_circuit_data(gate_serializer, generator_serializer)
     }
 
+    /// Returns the ids of the gates and generators of this circuit which can't be written by the
+    /// given serializers, so that a missing serializer entry can be caught before the circuit
+    /// data is actually serialized.
+    pub fn unsupported_by_serializers(
+        &self,
+        gate_serializer: &dyn GateSerializer<F, D>,
+        generator_serializer: &dyn WitnessGeneratorSerializer<F, D>,
+    ) -> Vec<String> {
+        let gates = self
+            .common
+            .gates
+            .iter()
+            .filter(|gate| !gate_serializer.supports_gate(gate, &self.common))
+            .map(|gate| gate.0.id());
+        let generators = self
+            .prover_only
+            .generators
+            .iter()
+            .filter(|generator| !generator_serializer.supports_generator(generator, &self.common))
+            .map(|generator| generator.0.id());
+        let mut ids = gates.chain(generators).collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Serializes the circuit data in a versioned envelope identifying the config and the
+    /// circuit. See [`envelope`] for the format.
+    pub fn to_versioned_bytes(
+        &self,
+        gate_serializer: &dyn GateSerializer<F, D>,
+        generator_serializer: &dyn WitnessGeneratorSerializer<F, D>,
+    ) -> IoResult<Vec<u8>> {
+        Ok(envelope::seal::<C, D>(
+            PayloadKind::CircuitData,
+            &self.verifier_only.circuit_digest,
+            &self.to_bytes(gate_serializer, generator_serializer)?,
+        ))
+    }
+
+    /// Deserializes circuit data written by `to_versioned_bytes`, checking that it was made with
+    /// this config.
+    pub fn from_versioned_bytes(
+        bytes: &[u8],
+        gate_serializer: &dyn GateSerializer<F, D>,
+        generator_serializer: &dyn WitnessGeneratorSerializer<F, D>,
+    ) -> Result<Self, EnvelopeError> {
+        let (header, payload) = envelope::open::<C, D>(bytes, PayloadKind::CircuitData, None)?;
+        let data = envelope::read_payload(&payload, |buffer| {
+            buffer.read_circuit_data(gate_serializer, generator_serializer)
+        })?;
+        envelope::check_circuit_digest::<C, D>(&header, &data.verifier_only.circuit_digest)?;
+        Ok(data)
+    }
+
     pub fn prove(&self, inputs: PartialWitness<F>) -> Result<ProofWithPublicInputs<F, C, D>> {
         prove::<F, C, D>(
             &self.prover_only,
@@ -192,14 +252,30 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         )
     }
 
-    pub fn verify(&self, proof_with_pis: ProofWithPublicInputs<F, C, D>) -> Result<()> {
+    /// Like `prove`, but returns an error instead of panicking if the witness cannot be generated.
+    pub fn try_prove(
+        &self,
+        inputs: PartialWitness<F>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>, ProveError<F>> {
+        try_prove::<F, C, D>(
+            &self.prover_only,
+            &self.common,
+            inputs,
+            &mut TimingTree::default(),
+        )
+    }
+
+    pub fn verify(
+        &self,
+        proof_with_pis: ProofWithPublicInputs<F, C, D>,
+    ) -> Result<(), VerificationError> {
         verify::<F, C, D>(proof_with_pis, &self.verifier_only, &self.common)
     }
 
     pub fn verify_compressed(
         &self,
         compressed_proof_with_pis: CompressedProofWithPublicInputs<F, C, D>,
-    ) -> Result<()> {
+    ) -> Result<(), VerificationError> {
         compressed_proof_with_pis.verify(&self.verifier_only, &self.common)
     }
 
@@ -289,6 +365,19 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
             &mut TimingTree::default(),
         )
     }
+
+    /// Like `prove`, but returns an error instead of panicking if the witness cannot be generated.
+    pub fn try_prove(
+        &self,
+        inputs: PartialWitness<F>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>, ProveError<F>> {
+        try_prove::<F, C, D>(
+            &self.prover_only,
+            &self.common,
+            inputs,
+            &mut TimingTree::default(),
+        )
+    }
 }
 
 /// Circuit data required by the prover.
@@ -319,14 +408,45 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         buffer.read_verifier_circuit_data(gate_serializer)
     }
 
-    pub fn verify(&self, proof_with_pis: ProofWithPublicInputs<F, C, D>) -> Result<()> {
+    /// Serializes the verifier data in a versioned envelope identifying the config and the
+    /// circuit. See [`envelope`] for the format.
+    pub fn to_versioned_bytes(
+        &self,
+        gate_serializer: &dyn GateSerializer<F, D>,
+    ) -> IoResult<Vec<u8>> {
+        Ok(envelope::seal::<C, D>(
+            PayloadKind::VerifierCircuitData,
+            &self.verifier_only.circuit_digest,
+            &self.to_bytes(gate_serializer)?,
+        ))
+    }
+
+    /// Deserializes verifier data written by `to_versioned_bytes`, checking that it was made with
+    /// this config.
+    pub fn from_versioned_bytes(
+        bytes: &[u8],
+        gate_serializer: &dyn GateSerializer<F, D>,
+    ) -> Result<Self, EnvelopeError> {
+        let (header, payload) =
+            envelope::open::<C, D>(bytes, PayloadKind::VerifierCircuitData, None)?;
+        let data = envelope::read_payload(&payload, |buffer| {
+            buffer.read_verifier_circuit_data(gate_serializer)
+        })?;
+        envelope::check_circuit_digest::<C, D>(&header, &data.verifier_only.circuit_digest)?;
+        Ok(data)
+    }
+
+    pub fn verify(
+        &self,
+        proof_with_pis: ProofWithPublicInputs<F, C, D>,
+    ) -> Result<(), VerificationError> {
         verify::<F, C, D>(proof_with_pis, &self.verifier_only, &self.common)
     }
 
     pub fn verify_compressed(
         &self,
         compressed_proof_with_pis: CompressedProofWithPublicInputs<F, C, D>,
-    ) -> Result<()> {
+    ) -> Result<(), VerificationError> {
         compressed_proof_with_pis.verify(&self.verifier_only, &self.common)
     }
 }
@@ -358,10 +478,10 @@ pub struct ProverOnlyCircuitData<
     /// A digest of the "circuit" (i.e. the instance, minus public inputs), which can be used to
     /// seed Fiat-Shamir.
     pub circuit_digest: <<C as GenericConfig<D>>::Hasher as Hasher<F>>::Hash,
-    // ///The concrete placement of the lookup gates for each lookup table index.
-    // pub lookup_rows: Vec<LookupWire>,
-    // /// A vector of (looking_in, looking_out) pairs for for each lookup table index.
-    // pub lut_to_lookups: Vec<Lookup>,
+    ///The concrete placement of the lookup gates for each lookup table index.
+    pub lookup_rows: Vec<LookupWire>,
+    /// A vector of (looking_in, looking_out) pairs for for each lookup table index.
+    pub lut_to_lookups: Vec<Lookup>,
 }
 
 impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
@@ -388,7 +508,8 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
 }
 
 /// Circuit data required by the verifier, but not the prover.
-#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct VerifierOnlyCircuitData<C: GenericConfig<D>, const D: usize> {
     /// A commitment to each constant polynomial and each permutation polynomial.
     pub constants_sigmas_cap: MerkleCap<C::F, C::Hasher>,
@@ -408,6 +529,27 @@ impl<C: GenericConfig<D>, const D: usize> VerifierOnlyCircuitData<C, D> {
         let mut buffer = Buffer::new(&bytes);
         buffer.read_verifier_only_circuit_data()
     }
+
+    /// Serializes the verifier data in a versioned envelope identifying the config and the
+    /// circuit. See [`envelope`] for the format.
+    pub fn to_versioned_bytes(&self) -> IoResult<Vec<u8>> {
+        Ok(envelope::seal::<C, D>(
+            PayloadKind::VerifierOnlyCircuitData,
+            &self.circuit_digest,
+            &self.to_bytes()?,
+        ))
+    }
+
+    /// Deserializes verifier data written by `to_versioned_bytes`, checking that it was made with
+    /// this config.
+    pub fn from_versioned_bytes(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        let (header, payload) =
+            envelope::open::<C, D>(bytes, PayloadKind::VerifierOnlyCircuitData, None)?;
+        let data: Self =
+            envelope::read_payload(&payload, |buffer| buffer.read_verifier_only_circuit_data())?;
+        envelope::check_circuit_digest::<C, D>(&header, &data.circuit_digest)?;
+        Ok(data)
+    }
 }
 
 /// Circuit data required by both the prover and the verifier.
@@ -439,14 +581,15 @@ pub struct CommonCircuitData<F: RichField + Extendable<D>, const D: usize> {
 
     /// The number of partial products needed to compute the `Z` polynomials.
     pub num_partial_products: usize,
-    // /// The number of lookup polynomials.
-    // pub num_lookup_polys: usize,
 
-    // /// The number of lookup selectors.
-    // pub num_lookup_selectors: usize,
+    /// The number of lookup polynomials.
+    pub num_lookup_polys: usize,
+
+    /// The number of lookup selectors.
+    pub num_lookup_selectors: usize,
 
-    // /// The stored lookup tables.
-    // pub luts: Vec<LookupTable>,
+    /// The stored lookup tables.
+    pub luts: Vec<LookupTable>,
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
@@ -518,10 +661,10 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
     }
 
     /// Range of lookup polynomials needed for evaluation at `g * zeta`.
-    // pub fn next_lookup_range(&self, i: usize) -> Range<usize> {
-    //     self.num_zs_partial_products_polys() + i * self.num_lookup_polys
-    //         ..self.num_zs_partial_products_polys() + i * self.num_lookup_polys + 2
-    // }
+    pub const fn next_lookup_range(&self, i: usize) -> Range<usize> {
+        self.num_zs_partial_products_polys() + i * self.num_lookup_polys
+            ..self.num_zs_partial_products_polys() + i * self.num_lookup_polys + 2
+    }
 
     pub(crate) fn get_fri_instance(&self, zeta: F::Extension) -> FriInstanceInfo<F, D> {
         // All polynomials are opened at zeta.
@@ -535,7 +678,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
         let zeta_next = g * zeta;
         let zeta_next_batch = FriBatchInfo {
             point: zeta_next,
-            polynomials: self.fri_zs_polys(),
+            polynomials: self.fri_next_batch_polys(),
         };
 
         let openings = vec![zeta_batch, zeta_next_batch];
@@ -561,7 +704,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
         let zeta_next = builder.mul_const_extension(g, zeta);
         let zeta_next_batch = FriBatchInfoTarget {
             point: zeta_next,
-            polynomials: self.fri_zs_polys(),
+            polynomials: self.fri_next_batch_polys(),
         };
 
         let openings = vec![zeta_batch, zeta_next_batch];
@@ -571,7 +714,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
         }
     }
 
-    fn fri_oracles(&self) -> Vec<FriOracleInfo> {
+    pub(crate) fn fri_oracles(&self) -> Vec<FriOracleInfo> {
         vec![
             FriOracleInfo {
                 num_polys: self.num_preprocessed_polys(),
@@ -582,7 +725,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
                 blinding: PlonkOracle::WIRES.blinding,
             },
             FriOracleInfo {
-                num_polys: self.num_zs_partial_products_polys(),
+                num_polys: self.num_zs_partial_products_polys() + self.num_all_lookup_polys(),
                 blinding: PlonkOracle::ZS_PARTIAL_PRODUCTS.blinding,
             },
             FriOracleInfo {
@@ -620,25 +763,30 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
     }
 
     /// Returns the total number of lookup polynomials.
-    // pub(crate) const fn num_all_lookup_polys(&self) -> usize {
-    //     self.config.num_challenges * self.num_lookup_polys
-    // }
+    pub(crate) const fn num_all_lookup_polys(&self) -> usize {
+        self.config.num_challenges * self.num_lookup_polys
+    }
     fn fri_zs_polys(&self) -> Vec<FriPolynomialInfo> {
         FriPolynomialInfo::from_range(PlonkOracle::ZS_PARTIAL_PRODUCTS.index, self.zs_range())
     }
 
+    /// Returns polynomials that require evaluation at `zeta` and `g * zeta`.
+    fn fri_next_batch_polys(&self) -> Vec<FriPolynomialInfo> {
+        [self.fri_zs_polys(), self.fri_lookup_polys()].concat()
+    }
+
     fn fri_quotient_polys(&self) -> Vec<FriPolynomialInfo> {
         FriPolynomialInfo::from_range(PlonkOracle::QUOTIENT.index, 0..self.num_quotient_polys())
     }
 
     /// Returns the information for lookup polynomials, i.e. the index within the oracle and the indices of the polynomials within the commitment.
-    // fn fri_lookup_polys(&self) -> Vec<FriPolynomialInfo> {
-    //     FriPolynomialInfo::from_range(
-    //         PlonkOracle::ZS_PARTIAL_PRODUCTS.index,
-    //         self.num_zs_partial_products_polys()
-    //             ..self.num_zs_partial_products_polys() + self.num_all_lookup_polys(),
-    //     )
-    // }
+    fn fri_lookup_polys(&self) -> Vec<FriPolynomialInfo> {
+        FriPolynomialInfo::from_range(
+            PlonkOracle::ZS_PARTIAL_PRODUCTS.index,
+            self.num_zs_partial_products_polys()
+                ..self.num_zs_partial_products_polys() + self.num_all_lookup_polys(),
+        )
+    }
     pub(crate) const fn num_quotient_polys(&self) -> usize {
         self.config.num_challenges * self.quotient_degree_factor
     }
@@ -649,6 +797,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
             self.fri_wire_polys(),
             self.fri_zs_partial_products_polys(),
             self.fri_quotient_polys(),
+            self.fri_lookup_polys(),
         ]
         .concat()
     }
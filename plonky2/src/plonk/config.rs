@@ -38,6 +38,11 @@ pub trait GenericHashOut<F: RichField>:
     fn to_bytes(&self) -> Vec<u8>;
     fn from_bytes(bytes: &[u8]) -> Self;
 
+    /// Like `from_bytes`, but returns `None` if `bytes` is not the canonical encoding of a hash.
+    fn try_from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(Self::from_bytes(bytes))
+    }
+
     fn to_vec(&self) -> Vec<F>;
 }
 
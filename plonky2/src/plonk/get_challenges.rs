@@ -12,7 +12,7 @@ use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::challenger::{Challenger, RecursiveChallenger};
 use crate::iop::target::Target;
-use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_builder::{CircuitBuilder, NUM_COINS_LOOKUP};
 use crate::plonk::circuit_data::CommonCircuitData;
 use crate::plonk::config::{AlgebraicHasher, GenericConfig, Hasher};
 use crate::plonk::proof::{
@@ -33,12 +33,12 @@ fn get_challenges<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, cons
     pow_witness: F,
     circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
     common_data: &CommonCircuitData<F, D>,
-) -> anyhow::Result<ProofChallenges<F, D>> {
+) -> ProofChallenges<F, D> {
     let config = &common_data.config;
     let num_challenges = config.num_challenges;
 
     let mut challenger = Challenger::<F, C::Hasher>::new();
-    // let has_lookup = common_data.num_lookup_polys != 0;
+    let has_lookup = common_data.num_lookup_polys != 0;
 
     // Observe the instance.
     challenger.observe_hash::<C::Hasher>(*circuit_digest);
@@ -50,18 +50,18 @@ fn get_challenges<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, cons
 
     // If there are lookups in the circuit, we should get delta challenges as well.
     // But we can use the already generated `plonk_betas` and `plonk_gammas` as the first `plonk_deltas` challenges.
-    // let plonk_deltas = if has_lookup {
-    //     let num_lookup_challenges = NUM_COINS_LOOKUP * num_challenges;
-    //     let mut deltas = Vec::with_capacity(num_lookup_challenges);
-    //     let num_additional_challenges = num_lookup_challenges - 2 * num_challenges;
-    //     let additional = challenger.get_n_challenges(num_additional_challenges);
-    //     deltas.extend(&plonk_betas);
-    //     deltas.extend(&plonk_gammas);
-    //     deltas.extend(additional);
-    //     deltas
-    // } else {
-    //     vec![]
-    // };
+    let plonk_deltas = if has_lookup {
+        let num_lookup_challenges = NUM_COINS_LOOKUP * num_challenges;
+        let mut deltas = Vec::with_capacity(num_lookup_challenges);
+        let num_additional_challenges = num_lookup_challenges - 2 * num_challenges;
+        let additional = challenger.get_n_challenges(num_additional_challenges);
+        deltas.extend(&plonk_betas);
+        deltas.extend(&plonk_gammas);
+        deltas.extend(additional);
+        deltas
+    } else {
+        vec![]
+    };
 
     // `plonk_zs_partial_products_cap` also contains the commitment to lookup polynomials.
     challenger.observe_cap::<C::Hasher>(plonk_zs_partial_products_cap);
@@ -72,11 +72,11 @@ fn get_challenges<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, cons
 
     challenger.observe_openings(&openings.to_fri_openings());
 
-    Ok(ProofChallenges {
+    ProofChallenges {
         plonk_betas,
         plonk_gammas,
         plonk_alphas,
-        // plonk_deltas,
+        plonk_deltas,
         plonk_zeta,
         fri_challenges: challenger.fri_challenges::<C, D>(
             commit_phase_merkle_caps,
@@ -85,7 +85,7 @@ fn get_challenges<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, cons
             common_data.degree_bits(),
             &config.fri_config,
         ),
-    })
+    }
 }
 
 impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
@@ -95,11 +95,10 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         &self,
         circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
         common_data: &CommonCircuitData<F, D>,
-    ) -> anyhow::Result<Vec<usize>> {
-        Ok(self
-            .get_challenges(self.get_public_inputs_hash(), circuit_digest, common_data)?
+    ) -> Vec<usize> {
+        self.get_challenges(self.get_public_inputs_hash(), circuit_digest, common_data)
             .fri_challenges
-            .fri_query_indices)
+            .fri_query_indices
     }
 
     /// Computes all Fiat-Shamir challenges used in the Plonk proof.
@@ -108,7 +107,7 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         public_inputs_hash: <<C as GenericConfig<D>>::InnerHasher as Hasher<F>>::Hash,
         circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
         common_data: &CommonCircuitData<F, D>,
-    ) -> anyhow::Result<ProofChallenges<F, D>> {
+    ) -> ProofChallenges<F, D> {
         let Proof {
             wires_cap,
             plonk_zs_partial_products_cap,
@@ -147,7 +146,7 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         public_inputs_hash: <<C as GenericConfig<D>>::InnerHasher as Hasher<F>>::Hash,
         circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
         common_data: &CommonCircuitData<F, D>,
-    ) -> anyhow::Result<ProofChallenges<F, D>> {
+    ) -> ProofChallenges<F, D> {
         let CompressedProof {
             wires_cap,
             plonk_zs_partial_products_cap,
@@ -273,6 +272,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let num_challenges = config.num_challenges;
 
         let mut challenger = RecursiveChallenger::<F, C::Hasher, D>::new(self);
+        let has_lookup = inner_common_data.num_lookup_polys != 0;
 
         // Observe the instance.
         challenger.observe_hash(&inner_circuit_digest);
@@ -282,6 +282,21 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let plonk_betas = challenger.get_n_challenges(self, num_challenges);
         let plonk_gammas = challenger.get_n_challenges(self, num_challenges);
 
+        // If there are lookups in the circuit, we should get delta challenges as well.
+        // But we can use the already generated `plonk_betas` and `plonk_gammas` as the first `plonk_deltas` challenges.
+        let plonk_deltas = if has_lookup {
+            let num_lookup_challenges = NUM_COINS_LOOKUP * num_challenges;
+            let mut deltas = Vec::with_capacity(num_lookup_challenges);
+            let num_additional_challenges = num_lookup_challenges - 2 * num_challenges;
+            let additional = challenger.get_n_challenges(self, num_additional_challenges);
+            deltas.extend(&plonk_betas);
+            deltas.extend(&plonk_gammas);
+            deltas.extend(additional);
+            deltas
+        } else {
+            vec![]
+        };
+
         challenger.observe_cap(plonk_zs_partial_products_cap);
         let plonk_alphas = challenger.get_n_challenges(self, num_challenges);
 
@@ -294,6 +309,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             plonk_betas,
             plonk_gammas,
             plonk_alphas,
+            plonk_deltas,
             plonk_zeta,
             fri_challenges: challenger.fri_challenges::<C>(
                 self,
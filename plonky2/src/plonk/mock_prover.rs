@@ -0,0 +1,554 @@
+//! Constraint checking of witnesses without generating proofs.
+//!
+//! [`MockCircuitData::check_witness`] runs the witness generators of a circuit, then checks every
+//! gate constraint on every row, every copy constraint, every lookup and the binding of the public
+//! inputs. Each failure is reported with the row, constraint and `push_context` scope it comes
+//! from, which is much easier to act on than a failing proof.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+use hashbrown::HashMap;
+
+use crate::field::extension::Extendable;
+use crate::field::types::Field;
+use crate::gates::gate::GateInstance;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::GeneratedValues;
+use crate::iop::target::Target;
+use crate::iop::witness::{PartialWitness, PartitionWitness, Witness};
+use crate::plonk::circuit_data::MockCircuitData;
+use crate::plonk::config::{GenericConfig, Hasher};
+use crate::plonk::copy_constraint::CopyConstraint;
+use crate::plonk::prover::set_lookup_wires;
+use crate::plonk::vars::EvaluationVarsBaseBatch;
+use crate::util::context_tree::ContextTree;
+
+/// The parts of a circuit which are dropped when building it, but are needed to check witnesses
+/// against it.
+#[derive(Eq, PartialEq, Debug)]
+pub(crate) struct CircuitLayout<F: RichField + Extendable<D>, const D: usize> {
+    /// The gate used in each row, after padding.
+    pub(crate) gate_instances: Vec<GateInstance<F, D>>,
+    pub(crate) copy_constraints: Vec<CopyConstraint>,
+    /// The scopes opened with `push_context`, with the rows they cover.
+    pub(crate) context_log: ContextTree,
+}
+
+/// A way in which a witness fails to satisfy a circuit.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WitnessCheckFailure<F: Field> {
+    /// A constraint of the gate in `row` does not vanish.
+    Gate {
+        row: usize,
+        /// The ID of the gate.
+        gate: String,
+        /// The index of the constraint among those of the gate.
+        constraint: usize,
+        /// The value of the constraint.
+        value: F,
+        /// The scope that was open when the row was added.
+        context: String,
+    },
+    /// Two targets which are copy-constrained to each other were assigned different values.
+    Copy {
+        target: Target,
+        value: F,
+        /// The target which was assigned first in the same partition.
+        other_target: Target,
+        other_value: F,
+        /// The scope of a copy constraint involving `target` or `other_target`, or else of the
+        /// row of `target`.
+        context: String,
+    },
+    /// A generator assigned `target` a different value than the one it was already given by the
+    /// inputs or by another generator.
+    Conflict {
+        target: Target,
+        /// The value written by the generator.
+        value: F,
+        other_value: F,
+        /// The ID of the generator.
+        generator: String,
+        /// The scope of a copy constraint involving `target`, or else of its row.
+        context: String,
+    },
+    /// The pair looked up by `input` and `output` is not in the lookup table at index `table`.
+    Lookup {
+        table: usize,
+        input: Target,
+        input_value: F,
+        output: Target,
+        output_value: F,
+    },
+    /// The public input at `index` was never assigned.
+    PublicInput { index: usize, target: Target },
+    /// A generator never ran because some of the targets it watches were never assigned.
+    Generator {
+        /// The ID of the generator.
+        generator: String,
+        missing: Vec<Target>,
+    },
+}
+
+impl<F: Field> Display for WitnessCheckFailure<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Gate {
+                row,
+                gate,
+                constraint,
+                value,
+                context,
+            } => write!(
+                f,
+                "constraint {constraint} of {gate} in row {row} evaluates to {value} (in {context})"
+            ),
+            Self::Copy {
+                target,
+                value,
+                other_target,
+                other_value,
+                context,
+            } => write!(
+                f,
+                "{target:?} = {value} but the copy-constrained {other_target:?} = {other_value} (in {context})"
+            ),
+            Self::Conflict {
+                target,
+                value,
+                other_value,
+                generator,
+                context,
+            } => write!(
+                f,
+                "{generator} set {target:?} = {value} but it was already {other_value} (in {context})"
+            ),
+            Self::Lookup {
+                table,
+                input,
+                input_value,
+                output,
+                output_value,
+            } => write!(
+                f,
+                "({input_value}, {output_value}) looked up by ({input:?}, {output:?}) is not in table {table}"
+            ),
+            Self::PublicInput { index, target } => {
+                write!(f, "public input {index} ({target:?}) is not set")
+            }
+            Self::Generator { generator, missing } => {
+                write!(f, "{generator} never ran, as {missing:?} are not set")
+            }
+        }
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    MockCircuitData<F, C, D>
+{
+    /// Generates the witness for the given inputs like [`Self::generate_witness`], and checks that
+    /// it satisfies every constraint of the circuit. Unlike the prover, which only reports that
+    /// some constraint failed, this returns every failure along with where it comes from.
+    ///
+    /// Wires which are never assigned are taken to be zero, as in the prover.
+    pub fn check_witness(
+        &self,
+        inputs: PartialWitness<F>,
+    ) -> Result<PartitionWitness<'_, F>, Vec<WitnessCheckFailure<F>>> {
+        let mut failures = Vec::new();
+        let mut witness = self.generate_witness_checked(inputs, &mut failures);
+        self.check_lookups(&witness, &mut failures);
+        // The multiplicities of the lookup tables can only be counted once every lookup is valid.
+        if failures.is_empty() {
+            set_lookup_wires(&self.prover_only, &self.common, &mut witness);
+        }
+
+        let public_inputs = self
+            .prover_only
+            .public_inputs
+            .iter()
+            .enumerate()
+            .map(|(index, &target)| {
+                witness.try_get_target(target).unwrap_or_else(|| {
+                    failures.push(WitnessCheckFailure::PublicInput { index, target });
+                    F::ZERO
+                })
+            })
+            .collect::<Vec<_>>();
+        let public_inputs_hash = C::InnerHasher::hash_public_inputs(&public_inputs);
+
+        let num_wires = self.common.config.num_wires;
+        let mut local_wires = vec![F::ZERO; num_wires];
+        for (row, gate) in self.layout.gate_instances.iter().enumerate() {
+            for (column, wire) in local_wires.iter_mut().enumerate() {
+                *wire = witness
+                    .try_get_target(Target::wire(row, column))
+                    .unwrap_or(F::ZERO);
+            }
+            let vars =
+                EvaluationVarsBaseBatch::new(1, &gate.constants, &local_wires, &public_inputs_hash);
+            let constraints = gate.gate_ref.0.eval_unfiltered_base_batch(vars);
+            for (constraint, &value) in constraints.iter().enumerate() {
+                if value != F::ZERO {
+                    failures.push(WitnessCheckFailure::Gate {
+                        row,
+                        gate: gate.gate_ref.0.id(),
+                        constraint,
+                        value,
+                        context: self.layout.context_log.stack_at(row),
+                    });
+                }
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(witness)
+        } else {
+            Err(failures)
+        }
+    }
+
+    /// Runs the generators like `generate_partial_witness`, but records conflicting assignments
+    /// and generators which never ran as failures instead of panicking. Lookup generators whose
+    /// input is not in their table are skipped, and the lookup is reported by `check_lookups`.
+    fn generate_witness_checked(
+        &self,
+        inputs: PartialWitness<F>,
+        failures: &mut Vec<WitnessCheckFailure<F>>,
+    ) -> PartitionWitness<'_, F> {
+        let generators = &self.prover_only.generators;
+        let generator_indices_by_watches = &self.prover_only.generator_indices_by_watches;
+        let mut witness = PartitionWitness::new(
+            self.common.config.num_wires,
+            self.common.degree(),
+            &self.prover_only.representative_map,
+        );
+        // The first target assigned in each partition, indexed by representative.
+        let mut assigned_by = vec![None; witness.values.len()];
+        // The table looked up by the input of a lookup in each partition, indexed by representative.
+        let mut tables_by_rep = HashMap::new();
+        for (table, lookups) in self.prover_only.lut_to_lookups.iter().enumerate() {
+            for &(input, _) in lookups {
+                tables_by_rep.insert(
+                    witness.representative_map[witness.target_index(input)],
+                    table,
+                );
+            }
+        }
+
+        // `generator` is the index of the generator writing the value, if any.
+        let mut set_target = |witness: &mut PartitionWitness<F>,
+                              target: Target,
+                              value: F,
+                              generator: Option<usize>| {
+            let rep_index = witness.representative_map[witness.target_index(target)];
+            match witness.values[rep_index] {
+                Some(old_value) if old_value != value => {
+                    let other_target = assigned_by[rep_index].unwrap_or(target);
+                    let context = self.copy_constraint_context(target, other_target);
+                    failures.push(match generator {
+                        Some(generator) if other_target == target => {
+                            WitnessCheckFailure::Conflict {
+                                target,
+                                value,
+                                other_value: old_value,
+                                generator: generators[generator].0.id(),
+                                context,
+                            }
+                        }
+                        _ => WitnessCheckFailure::Copy {
+                            target,
+                            value,
+                            other_target,
+                            other_value: old_value,
+                            context,
+                        },
+                    });
+                    None
+                }
+                Some(_) => None,
+                None => {
+                    witness.values[rep_index] = Some(value);
+                    assigned_by[rep_index] = Some(target);
+                    Some(rep_index)
+                }
+            }
+        };
+
+        for (target, value) in inputs.target_values {
+            set_target(&mut witness, target, value, None);
+        }
+
+        let mut pending_generator_indices: Vec<_> = (0..generators.len()).collect();
+        let mut generator_is_expired = vec![false; generators.len()];
+        let mut buffer = GeneratedValues::empty();
+        while !pending_generator_indices.is_empty() {
+            let mut next_pending_generator_indices = Vec::new();
+            for &generator_idx in &pending_generator_indices {
+                if generator_is_expired[generator_idx] {
+                    continue;
+                }
+                let generator = &generators[generator_idx].0;
+                if generator.is_lookup()
+                    && !self.lookup_inputs_in_tables(
+                        &witness,
+                        &tables_by_rep,
+                        generator.watch_list(),
+                    )
+                {
+                    generator_is_expired[generator_idx] = true;
+                    continue;
+                }
+                if generator.run(&witness, &mut buffer) {
+                    generator_is_expired[generator_idx] = true;
+                }
+                for (target, value) in buffer.target_values.drain(..) {
+                    if let Some(rep_index) =
+                        set_target(&mut witness, target, value, Some(generator_idx))
+                    {
+                        if let Some(watchers) = generator_indices_by_watches.get(&rep_index) {
+                            next_pending_generator_indices.extend(
+                                watchers
+                                    .iter()
+                                    .filter(|&&watcher| !generator_is_expired[watcher]),
+                            );
+                        }
+                    }
+                }
+            }
+            pending_generator_indices = next_pending_generator_indices;
+        }
+
+        for (generator, _) in generators
+            .iter()
+            .zip(generator_is_expired)
+            .filter(|(_, expired)| !expired)
+        {
+            failures.push(WitnessCheckFailure::Generator {
+                generator: generator.0.id(),
+                missing: generator
+                    .0
+                    .watch_list()
+                    .into_iter()
+                    .filter(|&target| witness.try_get_target(target).is_none())
+                    .collect(),
+            });
+        }
+
+        witness
+    }
+
+    /// Whether the values which are set among `targets`, the targets watched by a lookup
+    /// generator, are inputs of the tables they are looked up in.
+    fn lookup_inputs_in_tables(
+        &self,
+        witness: &PartitionWitness<F>,
+        tables_by_rep: &HashMap<usize, usize>,
+        targets: Vec<Target>,
+    ) -> bool {
+        targets.into_iter().all(|target| {
+            let rep_index = witness.representative_map[witness.target_index(target)];
+            match (tables_by_rep.get(&rep_index), witness.values[rep_index]) {
+                (Some(&table), Some(value)) => self.common.luts[table]
+                    .iter()
+                    .any(|&(input, _)| F::from_canonical_u16(input) == value),
+                _ => true,
+            }
+        })
+    }
+
+    /// Checks that every pair looked up is in its table, which the prover enforces with the
+    /// lookup argument. Lookups whose input is not set are left to the generator failures.
+    fn check_lookups(
+        &self,
+        witness: &PartitionWitness<F>,
+        failures: &mut Vec<WitnessCheckFailure<F>>,
+    ) {
+        for (table, lookups) in self.prover_only.lut_to_lookups.iter().enumerate() {
+            for &(input, output) in lookups {
+                let Some(input_value) = witness.try_get_target(input) else {
+                    continue;
+                };
+                let output_value = witness.try_get_target(output).unwrap_or(F::ZERO);
+                let in_table = self.common.luts[table].iter().any(|&(i, o)| {
+                    F::from_canonical_u16(i) == input_value
+                        && F::from_canonical_u16(o) == output_value
+                });
+                if !in_table {
+                    failures.push(WitnessCheckFailure::Lookup {
+                        table,
+                        input,
+                        input_value,
+                        output,
+                        output_value,
+                    });
+                }
+            }
+        }
+    }
+
+    /// The scope of a copy constraint involving `target`, or else `other_target`. Targets which
+    /// are not copy-constrained get the scope of their row.
+    fn copy_constraint_context(&self, target: Target, other_target: Target) -> String {
+        let involving = |t: Target| {
+            self.layout
+                .copy_constraints
+                .iter()
+                .find(|c| c.pair.0 == t || c.pair.1 == t)
+        };
+        involving(target)
+            .or_else(|| involving(other_target))
+            .map(|c| c.name.clone())
+            .unwrap_or_else(|| match target {
+                Target::Wire(wire) => self.layout.context_log.stack_at(wire.row),
+                Target::VirtualTarget { .. } => self.layout.context_log.stack_at(usize::MAX),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gates::arithmetic_base::ArithmeticGate;
+    use crate::iop::witness::WitnessWrite;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_check_witness() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        builder.push_context(log::Level::Debug, "square");
+        let x2 = builder.mul(x, x);
+        builder.pop_context();
+        builder.connect(x2, y);
+        builder.register_public_input(y);
+        let data = builder.mock_build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        let witness = data.check_witness(pw).unwrap();
+        assert_eq!(witness.get_target(y), F::from_canonical_u64(9));
+
+        // Forcing `y` to the wrong value breaks its copy constraint with `x * x`.
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        pw.set_target(y, F::from_canonical_u64(10));
+        let failures = data.check_witness(pw).unwrap_err();
+        assert!(failures.iter().any(|failure| matches!(
+            failure,
+            WitnessCheckFailure::Copy { value, other_value, .. }
+                if *value == F::from_canonical_u64(9) && *other_value == F::from_canonical_u64(10)
+        )));
+
+        // Without inputs, nothing can be generated.
+        let failures = data.check_witness(PartialWitness::new()).unwrap_err();
+        assert!(failures.contains(&WitnessCheckFailure::PublicInput {
+            index: 0,
+            target: y,
+        }));
+        assert!(failures
+            .iter()
+            .any(|failure| matches!(failure, WitnessCheckFailure::Generator { .. })));
+    }
+
+    #[test]
+    fn test_check_witness_reports_gate_row() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        builder.push_context(log::Level::Debug, "outer");
+        builder.push_context(log::Level::Debug, "mul");
+        let row = builder.add_gate(ArithmeticGate { num_ops: 1 }, vec![F::ONE, F::ONE]);
+        builder.pop_context();
+        builder.pop_context();
+        let data = builder.mock_build::<C>();
+
+        // Setting the output of the gate directly conflicts with its generator, and the wrong value
+        // is kept, so the constraint of the gate fails in its row.
+        let mut pw = PartialWitness::new();
+        let wire = |column| Target::wire(row, column);
+        pw.set_target(wire(ArithmeticGate::wire_ith_multiplicand_0(0)), F::TWO);
+        pw.set_target(
+            wire(ArithmeticGate::wire_ith_multiplicand_1(0)),
+            F::from_canonical_u64(3),
+        );
+        pw.set_target(wire(ArithmeticGate::wire_ith_addend(0)), F::ONE);
+        pw.set_target(
+            wire(ArithmeticGate::wire_ith_output(0)),
+            F::from_canonical_u64(8),
+        );
+        let failures = data.check_witness(pw).unwrap_err();
+        assert_eq!(
+            failures,
+            vec![
+                WitnessCheckFailure::Conflict {
+                    target: wire(ArithmeticGate::wire_ith_output(0)),
+                    value: F::from_canonical_u64(7),
+                    other_value: F::from_canonical_u64(8),
+                    generator: "ArithmeticBaseGenerator".to_string(),
+                    context: "root > outer > mul".to_string(),
+                },
+                WitnessCheckFailure::Gate {
+                    row,
+                    gate: data.layout.gate_instances[row].gate_ref.0.id(),
+                    constraint: 0,
+                    value: F::ONE,
+                    context: "root > outer > mul".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_witness_lookups() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let table = (0..16u16).map(|i| (i, i * i)).collect::<Vec<_>>();
+        let table_index = builder.add_lookup_table_from_pairs(table.into());
+        let square = builder.add_lookup_from_index(x, table_index);
+        builder.register_public_input(x);
+        let data = builder.mock_build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        let witness = data.check_witness(pw).unwrap();
+        assert_eq!(witness.get_target(square), F::from_canonical_u64(9));
+
+        // 20 is not an input of the table, so nothing can be looked up for it.
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(20));
+        assert_eq!(
+            data.check_witness(pw).unwrap_err(),
+            vec![WitnessCheckFailure::Lookup {
+                table: 0,
+                input: x,
+                input_value: F::from_canonical_u64(20),
+                output: square,
+                output_value: F::ZERO,
+            }]
+        );
+
+        // Forcing the output to a value of the table which is not the square of the input.
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        pw.set_target(square, F::from_canonical_u64(16));
+        let failures = data.check_witness(pw).unwrap_err();
+        assert!(failures.contains(&WitnessCheckFailure::Lookup {
+            table: 0,
+            input: x,
+            input_value: F::from_canonical_u64(3),
+            output: square,
+            output_value: F::from_canonical_u64(16),
+        }));
+    }
+}
@@ -3,16 +3,21 @@
 //! This module also defines the [CircuitBuilder](circuit_builder::CircuitBuilder)
 //! structure, used to build custom plonky2 circuits satisfying arbitrary statements.
 
+pub mod circom_verifier;
 pub mod circuit_builder;
 pub mod circuit_data;
 pub mod config;
 pub(crate) mod copy_constraint;
 mod get_challenges;
+pub mod mock_prover;
 pub(crate) mod permutation_argument;
 pub mod plonk_common;
 pub mod proof;
 pub mod prover;
-mod validate_shape;
+pub mod solidity_verifier;
+pub mod unconstrained;
+pub(crate) mod validate_shape;
 pub(crate) mod vanishing_poly;
 pub mod vars;
 pub mod verifier;
+pub(crate) mod verifier_codegen;
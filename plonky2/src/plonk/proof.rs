@@ -7,7 +7,6 @@
 #[cfg(not(feature = "std"))]
 use alloc::{vec, vec::Vec};
 
-use anyhow::ensure;
 use plonky2_maybe_rayon::*;
 use serde::{Deserialize, Serialize};
 
@@ -26,7 +25,11 @@ use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::Target;
 use crate::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
 use crate::plonk::config::{GenericConfig, Hasher};
-use crate::plonk::verifier::verify_with_challenges;
+use crate::plonk::validate_shape::validate_compressed_proof_with_pis_shape;
+use crate::plonk::verifier::{
+    ensure_shape, verify_opening_proof, verify_quotient, VerificationError,
+};
+use crate::util::serialization::envelope::{self, EnvelopeError, PayloadKind};
 use crate::util::serialization::{Buffer, Read, Write};
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
@@ -93,7 +96,7 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
         common_data: &CommonCircuitData<F, D>,
     ) -> anyhow::Result<CompressedProofWithPublicInputs<F, C, D>> {
-        let indices = self.fri_query_indices(circuit_digest, common_data)?;
+        let indices = self.fri_query_indices(circuit_digest, common_data);
         let compressed_proof = self.proof.compress(&indices, &common_data.fri_params);
         Ok(CompressedProofWithPublicInputs {
             public_inputs: self.public_inputs,
@@ -118,12 +121,39 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
     pub fn from_bytes(
         bytes: Vec<u8>,
         common_data: &CommonCircuitData<F, D>,
-    ) -> anyhow::Result<Self> {
+    ) -> Result<Self, VerificationError> {
         let mut buffer = Buffer::new(&bytes);
-        let proof = buffer
-            .read_proof_with_public_inputs(common_data)
-            .map_err(anyhow::Error::msg)?;
-        Ok(proof)
+        Ok(buffer.read_proof_with_public_inputs(common_data)?)
+    }
+
+    /// Serializes the proof in a versioned envelope identifying the config and the circuit with
+    /// digest `circuit_digest`. See [`envelope`] for the format.
+    pub fn to_versioned_bytes(
+        &self,
+        circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
+    ) -> Vec<u8> {
+        envelope::seal::<C, D>(
+            PayloadKind::ProofWithPublicInputs,
+            circuit_digest,
+            &self.to_bytes(),
+        )
+    }
+
+    /// Deserializes a proof written by `to_versioned_bytes`, checking that it was made with this
+    /// config for the circuit with digest `circuit_digest`.
+    pub fn from_versioned_bytes(
+        bytes: &[u8],
+        circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<Self, EnvelopeError> {
+        let (_, payload) = envelope::open::<C, D>(
+            bytes,
+            PayloadKind::ProofWithPublicInputs,
+            Some(circuit_digest),
+        )?;
+        envelope::read_payload(&payload, |buffer| {
+            buffer.read_proof_with_public_inputs(common_data)
+        })
     }
 }
 
@@ -146,13 +176,13 @@ pub struct CompressedProof<F: RichField + Extendable<D>, C: GenericConfig<D, F =
 impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
     CompressedProof<F, C, D>
 {
-    /// Decompress the proof.
+    /// Decompress the proof, returning `None` if it is missing data needed to decompress it.
     pub(crate) fn decompress(
         self,
         challenges: &ProofChallenges<F, D>,
         fri_inferred_elements: FriInferredElements<F, D>,
         params: &FriParams,
-    ) -> Proof<F, C, D> {
+    ) -> Option<Proof<F, C, D>> {
         let CompressedProof {
             wires_cap,
             plonk_zs_partial_products_cap,
@@ -161,13 +191,13 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
             opening_proof,
         } = self;
 
-        Proof {
+        Some(Proof {
             wires_cap,
             plonk_zs_partial_products_cap,
             quotient_polys_cap,
             openings,
-            opening_proof: opening_proof.decompress(challenges, fri_inferred_elements, params),
-        }
+            opening_proof: opening_proof.decompress(challenges, fri_inferred_elements, params)?,
+        })
     }
 }
 
@@ -190,41 +220,38 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
         common_data: &CommonCircuitData<F, D>,
     ) -> anyhow::Result<ProofWithPublicInputs<F, C, D>> {
+        validate_compressed_proof_with_pis_shape(&self, common_data).map_err(anyhow::Error::msg)?;
         let challenges =
-            self.get_challenges(self.get_public_inputs_hash(), circuit_digest, common_data)?;
-        let fri_inferred_elements = self.get_inferred_elements(&challenges, common_data);
-        let decompressed_proof =
-            self.proof
-                .decompress(&challenges, fri_inferred_elements, &common_data.fri_params);
-        Ok(ProofWithPublicInputs {
-            public_inputs: self.public_inputs,
-            proof: decompressed_proof,
-        })
+            self.get_challenges(self.get_public_inputs_hash(), circuit_digest, common_data);
+        self.decompress_with_challenges(&challenges, common_data)
+            .map_err(anyhow::Error::msg)
     }
 
     pub(crate) fn verify(
         self,
         verifier_data: &VerifierOnlyCircuitData<C, D>,
         common_data: &CommonCircuitData<F, D>,
-    ) -> anyhow::Result<()> {
-        ensure!(
-            self.public_inputs.len() == common_data.num_public_inputs,
-            "Number of public inputs doesn't match circuit data."
-        );
+    ) -> Result<(), VerificationError> {
+        validate_compressed_proof_with_pis_shape(&self, common_data)?;
         let public_inputs_hash = self.get_public_inputs_hash();
         let challenges = self.get_challenges(
             public_inputs_hash,
             &verifier_data.circuit_digest,
             common_data,
-        )?;
-        let fri_inferred_elements = self.get_inferred_elements(&challenges, common_data);
-        let decompressed_proof =
-            self.proof
-                .decompress(&challenges, fri_inferred_elements, &common_data.fri_params);
-        verify_with_challenges::<F, C, D>(
-            decompressed_proof,
+        );
+        // The query rounds were compressed for the challenges of the original proof. Check the
+        // openings first, so that a proof with altered public inputs or openings is reported as
+        // failing the constraint identities rather than as malformed.
+        verify_quotient::<F, C, D>(
+            &self.proof.openings,
             public_inputs_hash,
-            challenges,
+            &challenges,
+            common_data,
+        )?;
+        let decompressed_proof = self.decompress_with_challenges(&challenges, common_data)?;
+        verify_opening_proof::<F, C, D>(
+            decompressed_proof.proof,
+            &challenges,
             verifier_data,
             common_data,
         )
@@ -237,6 +264,54 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         C::InnerHasher::hash_public_inputs(&self.public_inputs)
     }
 
+    /// Decompresses a proof whose shape has already been validated, given its challenges.
+    fn decompress_with_challenges(
+        self,
+        challenges: &ProofChallenges<F, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>, VerificationError> {
+        ensure_shape(
+            self.has_query_round_proofs(
+                &challenges.fri_challenges.fri_query_indices,
+                &common_data.fri_params,
+            ),
+            "compressed FRI query round proofs",
+        )?;
+        let fri_inferred_elements = self.get_inferred_elements(challenges, common_data);
+        let proof = self
+            .proof
+            .decompress(challenges, fri_inferred_elements, &common_data.fri_params)
+            .ok_or(VerificationError::MalformedProof(
+                "compressed Merkle proofs",
+            ))?;
+        Ok(ProofWithPublicInputs {
+            proof,
+            public_inputs: self.public_inputs,
+        })
+    }
+
+    /// Checks that the compressed query rounds were made for the given queries and contain an
+    /// entry for every index they visit, so that decompressing them can't fail.
+    fn has_query_round_proofs(&self, fri_query_indices: &[usize], params: &FriParams) -> bool {
+        let query_round_proofs = &self.proof.opening_proof.query_round_proofs;
+        query_round_proofs.indices == fri_query_indices
+            && query_round_proofs.steps.len() == params.reduction_arity_bits.len()
+            && fri_query_indices.iter().all(|&x_index| {
+                query_round_proofs
+                    .initial_trees_proofs
+                    .contains_key(&x_index)
+                    && params
+                        .reduction_arity_bits
+                        .iter()
+                        .scan(x_index, |index, &arity_bits| {
+                            *index >>= arity_bits;
+                            Some(*index)
+                        })
+                        .zip(&query_round_proofs.steps)
+                        .all(|(index, step)| step.contains_key(&index))
+            })
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut buffer = Vec::new();
         buffer
@@ -248,12 +323,39 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
     pub fn from_bytes(
         bytes: Vec<u8>,
         common_data: &CommonCircuitData<F, D>,
-    ) -> anyhow::Result<Self> {
+    ) -> Result<Self, VerificationError> {
         let mut buffer = Buffer::new(&bytes);
-        let proof = buffer
-            .read_compressed_proof_with_public_inputs(common_data)
-            .map_err(anyhow::Error::msg)?;
-        Ok(proof)
+        Ok(buffer.read_compressed_proof_with_public_inputs(common_data)?)
+    }
+
+    /// Serializes the proof in a versioned envelope identifying the config and the circuit with
+    /// digest `circuit_digest`. See [`envelope`] for the format.
+    pub fn to_versioned_bytes(
+        &self,
+        circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
+    ) -> Vec<u8> {
+        envelope::seal::<C, D>(
+            PayloadKind::CompressedProofWithPublicInputs,
+            circuit_digest,
+            &self.to_bytes(),
+        )
+    }
+
+    /// Deserializes a proof written by `to_versioned_bytes`, checking that it was made with this
+    /// config for the circuit with digest `circuit_digest`.
+    pub fn from_versioned_bytes(
+        bytes: &[u8],
+        circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<Self, EnvelopeError> {
+        let (_, payload) = envelope::open::<C, D>(
+            bytes,
+            PayloadKind::CompressedProofWithPublicInputs,
+            Some(circuit_digest),
+        )?;
+        envelope::read_payload(&payload, |buffer| {
+            buffer.read_compressed_proof_with_public_inputs(common_data)
+        })
     }
 }
 
@@ -268,8 +370,9 @@ pub struct ProofChallenges<F: RichField + Extendable<D>, const D: usize> {
     /// Random values used to combine PLONK constraints.
     pub plonk_alphas: Vec<F>,
 
-    // /// Lookup challenges.
-    // pub plonk_deltas: Vec<F>,
+    /// Lookup challenges.
+    pub plonk_deltas: Vec<F>,
+
     /// Point at which the PLONK polynomials are opened.
     pub plonk_zeta: F::Extension,
 
@@ -280,7 +383,7 @@ pub(crate) struct ProofChallengesTarget<const D: usize> {
     pub plonk_betas: Vec<Target>,
     pub plonk_gammas: Vec<Target>,
     pub plonk_alphas: Vec<Target>,
-    // pub plonk_deltas: Vec<Target>,
+    pub plonk_deltas: Vec<Target>,
     pub plonk_zeta: ExtensionTarget<D>,
     pub fri_challenges: FriChallengesTarget<D>,
 }
@@ -306,8 +409,8 @@ pub struct OpeningSet<F: RichField + Extendable<D>, const D: usize> {
     pub plonk_zs_next: Vec<F::Extension>,
     pub partial_products: Vec<F::Extension>,
     pub quotient_polys: Vec<F::Extension>,
-    // pub lookup_zs: Vec<F::Extension>,
-    // pub lookup_zs_next: Vec<F::Extension>,
+    pub lookup_zs: Vec<F::Extension>,
+    pub lookup_zs_next: Vec<F::Extension>,
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> OpeningSet<F, D> {
@@ -316,7 +419,7 @@ impl<F: RichField + Extendable<D>, const D: usize> OpeningSet<F, D> {
         g: F::Extension,
         constants_sigmas_commitment: &PolynomialBatch<F, C, D>,
         wires_commitment: &PolynomialBatch<F, C, D>,
-        zs_partial_products_commitment: &PolynomialBatch<F, C, D>,
+        zs_partial_products_lookup_commitment: &PolynomialBatch<F, C, D>,
         quotient_polys_commitment: &PolynomialBatch<F, C, D>,
         common_data: &CommonCircuitData<F, D>,
     ) -> Self {
@@ -327,18 +430,25 @@ impl<F: RichField + Extendable<D>, const D: usize> OpeningSet<F, D> {
                 .collect::<Vec<_>>()
         };
         let constants_sigmas_eval = eval_commitment(zeta, constants_sigmas_commitment);
-        let zs_partial_products_eval = eval_commitment(zeta, zs_partial_products_commitment);
+
+        // `zs_partial_products_lookup_eval` contains the permutation argument polynomials as well as lookup polynomials.
+        let zs_partial_products_lookup_eval =
+            eval_commitment(zeta, zs_partial_products_lookup_commitment);
+        let zs_partial_products_lookup_next_eval =
+            eval_commitment(g * zeta, zs_partial_products_lookup_commitment);
+
         Self {
             constants: constants_sigmas_eval[common_data.constants_range()].to_vec(),
             plonk_sigmas: constants_sigmas_eval[common_data.sigmas_range()].to_vec(),
             wires: eval_commitment(zeta, wires_commitment),
-            plonk_zs: zs_partial_products_eval[common_data.zs_range()].to_vec(),
-            plonk_zs_next: eval_commitment(g * zeta, zs_partial_products_commitment)
-                [common_data.zs_range()]
-            .to_vec(),
-            partial_products: zs_partial_products_eval[common_data.partial_products_range()]
+            plonk_zs: zs_partial_products_lookup_eval[common_data.zs_range()].to_vec(),
+            plonk_zs_next: zs_partial_products_lookup_next_eval[common_data.zs_range()].to_vec(),
+            partial_products: zs_partial_products_lookup_eval[common_data.partial_products_range()]
                 .to_vec(),
             quotient_polys: eval_commitment(zeta, quotient_polys_commitment),
+            lookup_zs: zs_partial_products_lookup_eval[common_data.lookup_range()].to_vec(),
+            lookup_zs_next: zs_partial_products_lookup_next_eval[common_data.lookup_range()]
+                .to_vec(),
         }
     }
     pub(crate) fn to_fri_openings(&self) -> FriOpenings<F, D> {
@@ -350,11 +460,16 @@ impl<F: RichField + Extendable<D>, const D: usize> OpeningSet<F, D> {
                 self.plonk_zs.as_slice(),
                 self.partial_products.as_slice(),
                 self.quotient_polys.as_slice(),
+                self.lookup_zs.as_slice(),
             ]
             .concat(),
         };
         let zeta_next_batch = FriOpeningBatch {
-            values: self.plonk_zs_next.clone(),
+            values: [
+                self.plonk_zs_next.as_slice(),
+                self.lookup_zs_next.as_slice(),
+            ]
+            .concat(),
         };
         FriOpenings {
             batches: vec![zeta_batch, zeta_next_batch],
@@ -372,6 +487,8 @@ pub struct OpeningSetTarget<const D: usize> {
     pub plonk_zs_next: Vec<ExtensionTarget<D>>,
     pub partial_products: Vec<ExtensionTarget<D>>,
     pub quotient_polys: Vec<ExtensionTarget<D>>,
+    pub lookup_zs: Vec<ExtensionTarget<D>>,
+    pub next_lookup_zs: Vec<ExtensionTarget<D>>,
 }
 
 impl<const D: usize> OpeningSetTarget<D> {
@@ -384,11 +501,16 @@ impl<const D: usize> OpeningSetTarget<D> {
                 self.plonk_zs.as_slice(),
                 self.partial_products.as_slice(),
                 self.quotient_polys.as_slice(),
+                self.lookup_zs.as_slice(),
             ]
             .concat(),
         };
         let zeta_next_batch = FriOpeningBatchTarget {
-            values: self.plonk_zs_next.clone(),
+            values: [
+                self.plonk_zs_next.as_slice(),
+                self.next_lookup_zs.as_slice(),
+            ]
+            .concat(),
         };
         FriOpeningsTarget {
             batches: vec![zeta_batch, zeta_next_batch],
@@ -451,7 +573,7 @@ mod tests {
         assert_eq!(proof, decompressed_compressed_proof);
 
         verify(proof, &data.verifier_only, &data.common)?;
-        data.verify_compressed(compressed_proof)
+        Ok(data.verify_compressed(compressed_proof)?)
     }
 
     #[test]
@@ -508,6 +630,6 @@ mod tests {
         assert_eq!(proof, decompressed_compressed_proof);
 
         verify(proof, &data.verifier_only, &data.common)?;
-        data.verify_compressed(compressed_proof)
+        Ok(data.verify_compressed(compressed_proof)?)
     }
 }
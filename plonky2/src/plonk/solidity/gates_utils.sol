@@ -0,0 +1,81 @@
+library GatesUtilsLib {
+    using GoldilocksExtLib for uint64[2];
+
+    struct EvaluationVars {
+        uint64[2][$NUM_OPENINGS_CONSTANTS] constants;
+        uint64[2][$NUM_OPENINGS_WIRES] wires;
+        uint64[2][4] public_input_hash;
+        uint64[2] filter;
+    }
+
+    function push(uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints, uint64[2] memory filter, uint32 index, uint64[2] memory value) internal pure {
+        constraints[index] = constraints[index].add(value.mul(filter));
+    }
+
+    function field_ext_from(uint64 a, uint64 b) internal pure returns (uint64[2] memory res) {
+        res[0] = a;
+        res[1] = b;
+    }
+
+    // Computes `value * (value - 1) * ... * (value - (n - 1))`, which vanishes iff `value` is in `0..n`.
+    function range_product(uint64[2] memory value, uint32 n) internal pure returns (uint64[2] memory res) {
+        res = GoldilocksExtLib.one();
+        for (uint32 i = 0; i < n; i++) {
+            res = res.mul(value.sub(field_ext_from(i, 0)));
+        }
+    }
+
+    // Multiplies the extension algebra elements `wires[l..l + D]` and `wires[r..r + D]`.
+    function wires_algebra_mul(uint64[2][$NUM_OPENINGS_WIRES] memory wires, uint32 l, uint32 r) internal pure returns (uint64[2][$D] memory res) {
+        for (uint32 i = 0; i < $D; i++) {
+            for (uint32 j = 0; j < $D; j++) {
+                if (i + j < $D) {
+                    res[i + j] = res[i + j].add(wires[l + i].mul(wires[r + j]));
+                } else {
+                    res[i + j - $D] = res[i + j - $D].add(wires[l + i].mul(wires[r + j]).scalar_mul(7));
+                }
+            }
+        }
+    }
+
+    function wires_algebra(uint64[2][$NUM_OPENINGS_WIRES] memory wires, uint32 start) internal pure returns (uint64[2][$D] memory res) {
+        for (uint32 i = 0; i < $D; i++) {
+            res[i] = wires[start + i];
+        }
+    }
+
+    function algebra_add(uint64[2][$D] memory a, uint64[2][$D] memory b) internal pure returns (uint64[2][$D] memory res) {
+        for (uint32 i = 0; i < $D; i++) {
+            res[i] = a[i].add(b[i]);
+        }
+    }
+
+    function algebra_sub(uint64[2][$D] memory a, uint64[2][$D] memory b) internal pure returns (uint64[2][$D] memory res) {
+        for (uint32 i = 0; i < $D; i++) {
+            res[i] = a[i].sub(b[i]);
+        }
+    }
+
+    function algebra_mul(uint64[2][$D] memory a, uint64[2][$D] memory b) internal pure returns (uint64[2][$D] memory res) {
+        for (uint32 i = 0; i < $D; i++) {
+            for (uint32 j = 0; j < $D; j++) {
+                if (i + j < $D) {
+                    res[i + j] = res[i + j].add(a[i].mul(b[j]));
+                } else {
+                    res[i + j - $D] = res[i + j - $D].add(a[i].mul(b[j]).scalar_mul(7));
+                }
+            }
+        }
+    }
+
+    function algebra_scalar_mul(uint64[2][$D] memory a, uint64[2] memory s) internal pure returns (uint64[2][$D] memory res) {
+        for (uint32 i = 0; i < $D; i++) {
+            res[i] = a[i].mul(s);
+        }
+    }
+
+    // Embeds the extension field element `a` as `a + 0 * X + ...` in the extension algebra.
+    function algebra_from_ext(uint64[2] memory a) internal pure returns (uint64[2][$D] memory res) {
+        res[0] = a;
+    }
+}
@@ -0,0 +1,135 @@
+library GoldilocksFieldLib {
+    uint64 constant MOD = 0xFFFFFFFF00000001;
+
+    function add(uint64 a, uint64 b) internal pure returns (uint64) {
+        return uint64(addmod(a, b, MOD));
+    }
+
+    function sub(uint64 a, uint64 b) internal pure returns (uint64) {
+        return uint64(addmod(a, MOD - b, MOD));
+    }
+
+    function mul(uint64 a, uint64 b) internal pure returns (uint64) {
+        return uint64(mulmod(a, b, MOD));
+    }
+
+    function neg(uint64 a) internal pure returns (uint64) {
+        return a == 0 ? 0 : MOD - a;
+    }
+
+    function square(uint64 a) internal pure returns (uint64) {
+        return uint64(mulmod(a, a, MOD));
+    }
+
+    function exp(uint64 a, uint256 e) internal pure returns (uint64) {
+        uint256 result = 1;
+        uint256 base = a;
+        while (e > 0) {
+            if (e & 1 == 1) {
+                result = mulmod(result, base, MOD);
+            }
+            base = mulmod(base, base, MOD);
+            e >>= 1;
+        }
+        return uint64(result);
+    }
+
+    function exp_power_of_2(uint64 a, uint256 power_log) internal pure returns (uint64) {
+        uint256 result = a;
+        for (uint256 i = 0; i < power_log; i++) {
+            result = mulmod(result, result, MOD);
+        }
+        return uint64(result);
+    }
+
+    function inverse(uint64 a) internal pure returns (uint64) {
+        require(a != 0, "inverse of zero");
+        return exp(a, MOD - 2);
+    }
+}
+
+library GoldilocksExtLib {
+    using GoldilocksFieldLib for uint64;
+
+    uint64 constant MOD = 0xFFFFFFFF00000001;
+    // The quadratic extension is F[X] / (X^2 - W).
+    uint64 constant W = 7;
+
+    function zero() internal pure returns (uint64[2] memory res) {}
+
+    function one() internal pure returns (uint64[2] memory res) {
+        res[0] = 1;
+    }
+
+    function from_base(uint64 a) internal pure returns (uint64[2] memory res) {
+        res[0] = a;
+    }
+
+    function add(uint64[2] memory a, uint64[2] memory b) internal pure returns (uint64[2] memory res) {
+        res[0] = a[0].add(b[0]);
+        res[1] = a[1].add(b[1]);
+    }
+
+    function sub(uint64[2] memory a, uint64[2] memory b) internal pure returns (uint64[2] memory res) {
+        res[0] = a[0].sub(b[0]);
+        res[1] = a[1].sub(b[1]);
+    }
+
+    function neg(uint64[2] memory a) internal pure returns (uint64[2] memory res) {
+        res[0] = a[0].neg();
+        res[1] = a[1].neg();
+    }
+
+    function mul(uint64[2] memory a, uint64[2] memory b) internal pure returns (uint64[2] memory res) {
+        res[0] = uint64(addmod(mulmod(a[0], b[0], MOD), mulmod(mulmod(a[1], b[1], MOD), W, MOD), MOD));
+        res[1] = uint64(addmod(mulmod(a[0], b[1], MOD), mulmod(a[1], b[0], MOD), MOD));
+    }
+
+    function square(uint64[2] memory a) internal pure returns (uint64[2] memory) {
+        return mul(a, a);
+    }
+
+    function scalar_mul(uint64[2] memory a, uint64 s) internal pure returns (uint64[2] memory res) {
+        res[0] = a[0].mul(s);
+        res[1] = a[1].mul(s);
+    }
+
+    function exp_power_of_2(uint64[2] memory a, uint256 power_log) internal pure returns (uint64[2] memory res) {
+        res = a;
+        for (uint256 i = 0; i < power_log; i++) {
+            res = mul(res, res);
+        }
+    }
+
+    function exp(uint64[2] memory a, uint256 e) internal pure returns (uint64[2] memory res) {
+        res = one();
+        uint64[2] memory base = a;
+        while (e > 0) {
+            if (e & 1 == 1) {
+                res = mul(res, base);
+            }
+            base = mul(base, base);
+            e >>= 1;
+        }
+    }
+
+    // (a0 + a1 X)^-1 = (a0 - a1 X) / (a0^2 - W a1^2).
+    function inverse(uint64[2] memory a) internal pure returns (uint64[2] memory res) {
+        uint64 norm = a[0].square().sub(a[1].square().mul(W));
+        uint64 norm_inv = norm.inverse();
+        res[0] = a[0].mul(norm_inv);
+        res[1] = a[1].neg().mul(norm_inv);
+    }
+
+    function div(uint64[2] memory a, uint64[2] memory b) internal pure returns (uint64[2] memory) {
+        return mul(a, inverse(b));
+    }
+
+    function is_zero(uint64[2] memory a) internal pure returns (bool) {
+        return a[0] == 0 && a[1] == 0;
+    }
+
+    function equal(uint64[2] memory a, uint64[2] memory b) internal pure returns (bool) {
+        return a[0] == b[0] && a[1] == b[1];
+    }
+}
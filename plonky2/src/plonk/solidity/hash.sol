@@ -0,0 +1,163 @@
+// Poseidon over the Goldilocks field, only used to hash the public inputs. Its public functions
+// are deployed with the library, which keeps the round constants out of the verifier's code.
+library PoseidonGoldilocksLib {
+    using GoldilocksFieldLib for uint64;
+
+    uint64 constant MOD = 0xFFFFFFFF00000001;
+    uint256 constant HALF_N_FULL_ROUNDS = 4;
+    uint256 constant N_PARTIAL_ROUNDS = 22;
+
+    struct Params {
+        uint64[$GL_NUM_ROUND_CONSTANTS] round_constants;
+        uint64[12] mds_circ;
+        uint64[12] mds_diag;
+    }
+
+    function params() internal pure returns (Params memory p) {
+        p.round_constants = [$GL_ROUND_CONSTANTS];
+        p.mds_circ = [$GL_MDS_MATRIX_CIRC];
+        p.mds_diag = [$GL_MDS_MATRIX_DIAG];
+    }
+
+    function sbox(uint64 x) internal pure returns (uint64) {
+        uint64 x2 = x.square();
+        uint64 x3 = x.mul(x2);
+        return x3.mul(x2.square());
+    }
+
+    function round(uint64[12] memory state, Params memory p, uint256 round_ctr, bool full) internal pure {
+        for (uint256 i = 0; i < 12; i++) {
+            state[i] = state[i].add(p.round_constants[round_ctr * 12 + i]);
+        }
+        if (full) {
+            for (uint256 i = 0; i < 12; i++) {
+                state[i] = sbox(state[i]);
+            }
+        } else {
+            state[0] = sbox(state[0]);
+        }
+        uint64[12] memory result;
+        for (uint256 r = 0; r < 12; r++) {
+            uint256 sum = mulmod(state[r], p.mds_diag[r], MOD);
+            for (uint256 i = 0; i < 12; i++) {
+                sum = addmod(sum, mulmod(state[(i + r) % 12], p.mds_circ[i], MOD), MOD);
+            }
+            result[r] = uint64(sum);
+        }
+        for (uint256 i = 0; i < 12; i++) {
+            state[i] = result[i];
+        }
+    }
+
+    function permute(uint64[12] memory state, Params memory p) internal pure {
+        uint256 round_ctr = 0;
+        for (uint256 r = 0; r < HALF_N_FULL_ROUNDS; r++) {
+            round(state, p, round_ctr++, true);
+        }
+        for (uint256 r = 0; r < N_PARTIAL_ROUNDS; r++) {
+            round(state, p, round_ctr++, false);
+        }
+        for (uint256 r = 0; r < HALF_N_FULL_ROUNDS; r++) {
+            round(state, p, round_ctr++, true);
+        }
+    }
+
+    function hash_no_pad(uint64[] memory input) public pure returns (uint64[4] memory res) {
+        Params memory p = params();
+        uint64[12] memory state;
+        for (uint256 i = 0; i < input.length; i += 8) {
+            for (uint256 j = 0; j < 8 && i + j < input.length; j++) {
+                state[j] = input[i + j];
+            }
+            permute(state, p);
+        }
+        for (uint256 i = 0; i < 4; i++) {
+            res[i] = state[i];
+        }
+    }
+}
+
+// The width-5 Poseidon permutation over the BN254 scalar field, applied to 12 Goldilocks elements
+// packed three per field element. Its public functions are deployed with the library, which keeps
+// the round constants out of the verifier's code.
+library PoseidonBN128Lib {
+    uint256 constant R = 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001;
+    uint64 constant MOD = 0xFFFFFFFF00000001;
+    uint256 constant HALF_N_FULL_ROUNDS = 4;
+    uint256 constant N_PARTIAL_ROUNDS = 60;
+
+    // The round constants together with scratch space, so that a permutation does not allocate
+    // memory.
+    struct Context {
+        uint256[$BN128_NUM_ROUND_CONSTANTS] round_constants;
+        uint256[25] mds;
+        uint256[5] s;
+        uint256[5] t;
+    }
+
+    function context() internal pure returns (Context memory ctx) {
+        ctx.round_constants = [$BN128_ROUND_CONSTANTS];
+        ctx.mds = [$BN128_MDS_MATRIX];
+    }
+
+    function sbox(uint256 x) internal pure returns (uint256) {
+        uint256 x2 = mulmod(x, x, R);
+        return mulmod(mulmod(x2, x2, R), x, R);
+    }
+
+    function round(Context memory ctx, uint256 round_ctr, bool full) internal pure {
+        uint256[5] memory s = ctx.s;
+        uint256[5] memory t = ctx.t;
+        for (uint256 i = 0; i < 5; i++) {
+            s[i] = addmod(s[i], ctx.round_constants[round_ctr * 5 + i], R);
+        }
+        if (full) {
+            for (uint256 i = 0; i < 5; i++) {
+                s[i] = sbox(s[i]);
+            }
+        } else {
+            s[0] = sbox(s[0]);
+        }
+        for (uint256 r = 0; r < 5; r++) {
+            uint256 sum = 0;
+            for (uint256 i = 0; i < 5; i++) {
+                sum = addmod(sum, mulmod(ctx.mds[r * 5 + i], s[i], R), R);
+            }
+            t[r] = sum;
+        }
+        for (uint256 i = 0; i < 5; i++) {
+            s[i] = t[i];
+        }
+    }
+
+    // Returns the permutation of a Goldilocks sponge state. A library call has its own memory, so
+    // the context is rebuilt on every call.
+    function permutation(uint64[12] memory state) public pure returns (uint64[12] memory) {
+        permute(state, context());
+        return state;
+    }
+
+    // Permutes a Goldilocks sponge state by packing it into four BN128 field elements.
+    function permute(uint64[12] memory state, Context memory ctx) internal pure {
+        uint256[5] memory s = ctx.s;
+        s[0] = 0;
+        for (uint256 i = 0; i < 4; i++) {
+            s[i + 1] = (uint256(state[3 * i]) << 128) | (uint256(state[3 * i + 1]) << 64) | uint256(state[3 * i + 2]);
+        }
+        uint256 round_ctr = 0;
+        for (uint256 r = 0; r < HALF_N_FULL_ROUNDS; r++) {
+            round(ctx, round_ctr++, true);
+        }
+        for (uint256 r = 0; r < N_PARTIAL_ROUNDS; r++) {
+            round(ctx, round_ctr++, false);
+        }
+        for (uint256 r = 0; r < HALF_N_FULL_ROUNDS; r++) {
+            round(ctx, round_ctr++, true);
+        }
+        for (uint256 i = 0; i < 4; i++) {
+            state[3 * i] = uint64(((s[i] >> 128) & 0xFFFFFFFFFFFFFFFF) % MOD);
+            state[3 * i + 1] = uint64(((s[i] >> 64) & 0xFFFFFFFFFFFFFFFF) % MOD);
+            state[3 * i + 2] = uint64((s[i] & 0xFFFFFFFFFFFFFFFF) % MOD);
+        }
+    }
+}
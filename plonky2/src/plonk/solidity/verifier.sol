@@ -0,0 +1,481 @@
+contract Plonky2Verifier {
+    using GoldilocksFieldLib for uint64;
+    using GoldilocksExtLib for uint64[2];
+
+    uint64 constant MOD = 0xFFFFFFFF00000001;
+    uint64 constant MULTIPLICATIVE_GROUP_GENERATOR = 7;
+    // A generator of the subgroup of order 2^TWO_ADICITY.
+    uint64 constant POWER_OF_TWO_GENERATOR = 1753635133440165772;
+    uint256 constant TWO_ADICITY = 32;
+
+$CIRCUIT_CONSTANTS
+
+    struct ProofChallenges {
+        uint64[4] public_inputs_hash;
+        uint64[] plonk_betas;
+        uint64[] plonk_gammas;
+        uint64[] plonk_alphas;
+        uint64[2] plonk_zeta;
+        uint64[2] fri_alpha;
+        uint64[2][] fri_betas;
+        uint64 fri_pow_response;
+        uint256[] fri_query_indices;
+    }
+
+    struct Challenger {
+        uint64[12] state;
+        uint64[8] input_buffer;
+        uint256 input_len;
+        uint64[8] output_buffer;
+        uint256 output_len;
+    }
+
+    struct QueryRound {
+        uint256 x_index;
+        uint64 subgroup_x;
+        uint64[2] old_eval;
+        // Position of the next unread element of the query round proof.
+        uint256 offset;
+        // Depth of the next Merkle proof.
+        uint256 depth;
+    }
+
+    // Verifies a proof encoded as a sequence of big-endian 64-bit field elements, public inputs
+    // first. Reverts if the proof is invalid.
+    function verify(bytes calldata proof) external pure returns (bool) {
+        require(proof.length == PROOF_SIZE * 8, "invalid proof length");
+        for (uint256 i = 0; i < PROOF_SIZE; i++) {
+            require(read(proof, i) < MOD, "non-canonical field element");
+        }
+        ProofChallenges memory challenges = get_challenges(proof);
+        verify_quotient(proof, challenges);
+        verify_fri_proof(proof, challenges);
+        return true;
+    }
+
+    function read(bytes calldata proof, uint256 index) internal pure returns (uint64) {
+        return uint64(bytes8(proof[index * 8:index * 8 + 8]));
+    }
+
+    function read_ext(bytes calldata proof, uint256 index) internal pure returns (uint64[2] memory res) {
+        res[0] = read(proof, index);
+        res[1] = read(proof, index + 1);
+    }
+
+    function primitive_root_of_unity(uint256 n_log) internal pure returns (uint64) {
+        return POWER_OF_TWO_GENERATOR.exp_power_of_2(TWO_ADICITY - n_log);
+    }
+
+    function reverse_bits(uint256 n, uint256 num_bits) internal pure returns (uint256 res) {
+        for (uint256 i = 0; i < num_bits; i++) {
+            res = (res << 1) | (n & 1);
+            n >>= 1;
+        }
+    }
+
+    function circuit_digest() internal pure returns (uint64[4] memory) {
+        return [$CIRCUIT_DIGEST];
+    }
+
+    function constants_sigmas_cap() internal pure returns (uint64[] memory cap) {
+        uint64[$CAP_WORDS] memory words = [$CONSTANTS_SIGMAS_CAP];
+        cap = new uint64[]($CAP_WORDS);
+        for (uint256 i = 0; i < $CAP_WORDS; i++) {
+            cap[i] = words[i];
+        }
+    }
+
+    function k_is() internal pure returns (uint64[$NUM_ROUTED_WIRES] memory) {
+        return [$K_IS];
+    }
+
+    function fri_reduction_arity_bits() internal pure returns (uint256[] memory bits) {
+        bits = new uint256[](NUM_REDUCTIONS);
+$SET_FRI_REDUCTION_ARITY_BITS
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // Fiat-Shamir challenges.
+
+    function duplex(Challenger memory challenger) internal pure {
+        for (uint256 i = 0; i < challenger.input_len; i++) {
+            challenger.state[i] = challenger.input_buffer[i];
+        }
+        challenger.input_len = 0;
+        challenger.state = PoseidonBN128Lib.permutation(challenger.state);
+        for (uint256 i = 0; i < 8; i++) {
+            challenger.output_buffer[i] = challenger.state[i];
+        }
+        challenger.output_len = 8;
+    }
+
+    function observe(Challenger memory challenger, uint64 element) internal pure {
+        challenger.output_len = 0;
+        challenger.input_buffer[challenger.input_len++] = element;
+        if (challenger.input_len == 8) {
+            duplex(challenger);
+        }
+    }
+
+    function observe_proof(Challenger memory challenger, bytes calldata proof, uint256 start, uint256 len) internal pure {
+        for (uint256 i = start; i < start + len; i++) {
+            observe(challenger, read(proof, i));
+        }
+    }
+
+    function get_challenge(Challenger memory challenger) internal pure returns (uint64) {
+        if (challenger.input_len > 0 || challenger.output_len == 0) {
+            duplex(challenger);
+        }
+        return challenger.output_buffer[--challenger.output_len];
+    }
+
+    function get_n_challenges(Challenger memory challenger, uint256 n) internal pure returns (uint64[] memory res) {
+        res = new uint64[](n);
+        for (uint256 i = 0; i < n; i++) {
+            res[i] = get_challenge(challenger);
+        }
+    }
+
+    function get_extension_challenge(Challenger memory challenger) internal pure returns (uint64[2] memory res) {
+        res[0] = get_challenge(challenger);
+        res[1] = get_challenge(challenger);
+    }
+
+    function get_challenges(bytes calldata proof) internal pure returns (ProofChallenges memory challenges) {
+        Challenger memory challenger;
+        {
+            uint64[] memory public_inputs = new uint64[](NUM_PUBLIC_INPUTS);
+            for (uint256 i = 0; i < NUM_PUBLIC_INPUTS; i++) {
+                public_inputs[i] = read(proof, i);
+            }
+            challenges.public_inputs_hash = PoseidonGoldilocksLib.hash_no_pad(public_inputs);
+            uint64[4] memory digest = circuit_digest();
+            for (uint256 i = 0; i < 4; i++) {
+                observe(challenger, digest[i]);
+            }
+            for (uint256 i = 0; i < 4; i++) {
+                observe(challenger, challenges.public_inputs_hash[i]);
+            }
+        }
+
+        observe_proof(challenger, proof, WIRES_CAP_OFFSET, 4 * CAP_LEN);
+        challenges.plonk_betas = get_n_challenges(challenger, NUM_CHALLENGES);
+        challenges.plonk_gammas = get_n_challenges(challenger, NUM_CHALLENGES);
+        observe_proof(challenger, proof, PLONK_ZS_PARTIAL_PRODUCTS_CAP_OFFSET, 4 * CAP_LEN);
+        challenges.plonk_alphas = get_n_challenges(challenger, NUM_CHALLENGES);
+        observe_proof(challenger, proof, QUOTIENT_POLYS_CAP_OFFSET, 4 * CAP_LEN);
+        challenges.plonk_zeta = get_extension_challenge(challenger);
+
+        // The openings at zeta, then the openings at g * zeta.
+        observe_proof(challenger, proof, CONSTANTS_OFFSET, PLONK_ZS_NEXT_OFFSET - CONSTANTS_OFFSET);
+        observe_proof(challenger, proof, PARTIAL_PRODUCTS_OFFSET, OPENINGS_END - PARTIAL_PRODUCTS_OFFSET);
+        observe_proof(challenger, proof, PLONK_ZS_NEXT_OFFSET, PARTIAL_PRODUCTS_OFFSET - PLONK_ZS_NEXT_OFFSET);
+
+        challenges.fri_alpha = get_extension_challenge(challenger);
+        challenges.fri_betas = new uint64[2][](NUM_REDUCTIONS);
+        for (uint256 i = 0; i < NUM_REDUCTIONS; i++) {
+            observe_proof(challenger, proof, COMMIT_PHASE_CAPS_OFFSET + 4 * CAP_LEN * i, 4 * CAP_LEN);
+            challenges.fri_betas[i] = get_extension_challenge(challenger);
+        }
+        observe_proof(challenger, proof, FINAL_POLY_OFFSET, 2 * FINAL_POLY_LEN);
+        observe(challenger, read(proof, POW_WITNESS_OFFSET));
+        challenges.fri_pow_response = get_challenge(challenger);
+        challenges.fri_query_indices = new uint256[](NUM_QUERY_ROUNDS);
+        for (uint256 i = 0; i < NUM_QUERY_ROUNDS; i++) {
+            challenges.fri_query_indices[i] = uint256(get_challenge(challenger)) % LDE_SIZE;
+        }
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // Vanishing polynomial.
+
+    function evaluate_gate_constraints(bytes calldata proof, uint64[4] memory public_inputs_hash) internal pure returns (uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) {
+        GatesUtilsLib.EvaluationVars memory ev;
+        for (uint256 i = 0; i < NUM_CONSTANTS; i++) {
+            ev.constants[i] = read_ext(proof, CONSTANTS_OFFSET + 2 * i);
+        }
+        for (uint256 i = 0; i < NUM_WIRES; i++) {
+            ev.wires[i] = read_ext(proof, WIRES_OFFSET + 2 * i);
+        }
+        for (uint256 i = 0; i < 4; i++) {
+            ev.public_input_hash[i] = GoldilocksExtLib.from_base(public_inputs_hash[i]);
+        }
+$GATE_EVALUATION
+    }
+
+    function eval_l_0(uint64[2] memory x) internal pure returns (uint64[2] memory) {
+        if (x.equal(GoldilocksExtLib.one())) {
+            return GoldilocksExtLib.one();
+        }
+        uint64[2] memory z_x = x.exp_power_of_2(DEGREE_BITS).sub(GoldilocksExtLib.one());
+        return z_x.div(x.sub(GoldilocksExtLib.one()).scalar_mul(uint64(DEGREE)));
+    }
+
+    // Returns the products of the numerators and of the denominators of the `j`-th chunk of the
+    // permutation argument for challenge `i`.
+    function partial_product_chunk(bytes calldata proof, ProofChallenges memory challenges, uint64[$NUM_ROUTED_WIRES] memory k_is_, uint256 i, uint256 j) internal pure returns (uint64[2][2] memory res) {
+        res[0] = GoldilocksExtLib.one();
+        res[1] = GoldilocksExtLib.one();
+        uint64[2] memory gamma = GoldilocksExtLib.from_base(challenges.plonk_gammas[i]);
+        for (uint256 w = j * QUOTIENT_DEGREE_FACTOR; w < (j + 1) * QUOTIENT_DEGREE_FACTOR && w < NUM_ROUTED_WIRES; w++) {
+            uint64[2] memory wire = read_ext(proof, WIRES_OFFSET + 2 * w);
+            res[0] = res[0].mul(wire.add(challenges.plonk_zeta.scalar_mul(k_is_[w]).scalar_mul(challenges.plonk_betas[i])).add(gamma));
+            res[1] = res[1].mul(wire.add(read_ext(proof, PLONK_SIGMAS_OFFSET + 2 * w).scalar_mul(challenges.plonk_betas[i])).add(gamma));
+        }
+    }
+
+    function check_partial_products(bytes calldata proof, ProofChallenges memory challenges, uint64[2][] memory terms, uint256 i) internal pure {
+        uint64[$NUM_ROUTED_WIRES] memory k_is_ = k_is();
+        uint64[2] memory prev_acc = read_ext(proof, PLONK_ZS_OFFSET + 2 * i);
+        for (uint256 j = 0; j <= NUM_PARTIAL_PRODUCTS; j++) {
+            uint64[2][2] memory chunk = partial_product_chunk(proof, challenges, k_is_, i, j);
+            uint64[2] memory next_acc;
+            if (j == NUM_PARTIAL_PRODUCTS) {
+                next_acc = read_ext(proof, PLONK_ZS_NEXT_OFFSET + 2 * i);
+            } else {
+                next_acc = read_ext(proof, PARTIAL_PRODUCTS_OFFSET + 2 * (i * NUM_PARTIAL_PRODUCTS + j));
+            }
+            terms[NUM_CHALLENGES + i * (NUM_PARTIAL_PRODUCTS + 1) + j] = prev_acc.mul(chunk[0]).sub(next_acc.mul(chunk[1]));
+            prev_acc = next_acc;
+        }
+    }
+
+    function vanishing_terms(bytes calldata proof, ProofChallenges memory challenges) internal pure returns (uint64[2][] memory terms) {
+        uint256 num_permutation_terms = NUM_CHALLENGES * (NUM_PARTIAL_PRODUCTS + 2);
+        terms = new uint64[2][](num_permutation_terms + NUM_GATE_CONSTRAINTS);
+        uint64[2] memory l_0 = eval_l_0(challenges.plonk_zeta);
+        for (uint256 i = 0; i < NUM_CHALLENGES; i++) {
+            terms[i] = l_0.mul(read_ext(proof, PLONK_ZS_OFFSET + 2 * i).sub(GoldilocksExtLib.one()));
+            check_partial_products(proof, challenges, terms, i);
+        }
+        uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints = evaluate_gate_constraints(proof, challenges.public_inputs_hash);
+        for (uint256 i = 0; i < NUM_GATE_CONSTRAINTS; i++) {
+            terms[num_permutation_terms + i] = constraints[i];
+        }
+    }
+
+    // Checks `vanishing(zeta) = Z_H(zeta) * quotient(zeta)` for each challenge.
+    function verify_quotient(bytes calldata proof, ProofChallenges memory challenges) internal pure {
+        uint64[2][] memory terms = vanishing_terms(proof, challenges);
+        uint64[2] memory zeta_pow_deg = challenges.plonk_zeta.exp_power_of_2(DEGREE_BITS);
+        uint64[2] memory z_h_zeta = zeta_pow_deg.sub(GoldilocksExtLib.one());
+        for (uint256 i = 0; i < NUM_CHALLENGES; i++) {
+            uint64[2] memory vanishing;
+            for (uint256 j = terms.length; j > 0; j--) {
+                vanishing = vanishing.scalar_mul(challenges.plonk_alphas[i]).add(terms[j - 1]);
+            }
+            uint64[2] memory quotient;
+            for (uint256 j = QUOTIENT_DEGREE_FACTOR; j > 0; j--) {
+                quotient = quotient.mul(zeta_pow_deg).add(read_ext(proof, QUOTIENT_POLYS_OFFSET + 2 * (i * QUOTIENT_DEGREE_FACTOR + j - 1)));
+            }
+            require(vanishing.equal(z_h_zeta.mul(quotient)), "vanishing polynomial does not match quotient");
+        }
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // FRI.
+
+    function load_caps(bytes calldata proof) internal pure returns (uint64[][] memory caps) {
+        caps = new uint64[][](4 + NUM_REDUCTIONS);
+        caps[0] = constants_sigmas_cap();
+        for (uint256 i = 1; i < caps.length; i++) {
+            uint256 offset;
+            if (i == 1) {
+                offset = WIRES_CAP_OFFSET;
+            } else if (i == 2) {
+                offset = PLONK_ZS_PARTIAL_PRODUCTS_CAP_OFFSET;
+            } else if (i == 3) {
+                offset = QUOTIENT_POLYS_CAP_OFFSET;
+            } else {
+                offset = COMMIT_PHASE_CAPS_OFFSET + 4 * CAP_LEN * (i - 4);
+            }
+            caps[i] = new uint64[](4 * CAP_LEN);
+            for (uint256 j = 0; j < 4 * CAP_LEN; j++) {
+                caps[i][j] = read(proof, offset + j);
+            }
+        }
+    }
+
+    // Hashes `len` elements of the proof starting at `offset` into `state[0..4]`.
+    function hash_or_noop(bytes calldata proof, uint256 offset, uint256 len) internal pure returns (uint64[12] memory state) {
+        if (len <= 4) {
+            for (uint256 i = 0; i < len; i++) {
+                state[i] = read(proof, offset + i);
+            }
+            return state;
+        }
+        for (uint256 i = 0; i < len; i += 8) {
+            for (uint256 j = 0; j < 8 && i + j < len; j++) {
+                state[j] = read(proof, offset + i + j);
+            }
+            state = PoseidonBN128Lib.permutation(state);
+        }
+    }
+
+    // Verifies the Merkle proof of the leaf made of `leaf_len` elements at `leaf_offset`, followed by
+    // `depth` sibling hashes.
+    function verify_merkle_proof(bytes calldata proof, uint256 leaf_offset, uint256 leaf_len, uint256 index, uint256 depth, uint64[] memory cap) internal pure {
+        uint64[12] memory state = hash_or_noop(proof, leaf_offset, leaf_len);
+        for (uint256 i = 0; i < depth; i++) {
+            for (uint256 j = 0; j < 4; j++) {
+                if (index & 1 == 1) {
+                    state[j + 4] = state[j];
+                    state[j] = read(proof, leaf_offset + leaf_len + 4 * i + j);
+                } else {
+                    state[j + 4] = read(proof, leaf_offset + leaf_len + 4 * i + j);
+                }
+                state[j + 8] = 0;
+            }
+            state = PoseidonBN128Lib.permutation(state);
+            index >>= 1;
+        }
+        for (uint256 j = 0; j < 4; j++) {
+            require(state[j] == cap[4 * index + j], "invalid Merkle proof");
+        }
+    }
+
+    function reduce_openings(bytes calldata proof, uint64[2] memory alpha) internal pure returns (uint64[2][2] memory res) {
+        // Batch 0: constants, sigmas, wires, zs, partial products and quotient polynomials at zeta.
+        for (uint256 i = OPENINGS_END; i > PARTIAL_PRODUCTS_OFFSET; i -= 2) {
+            res[0] = res[0].mul(alpha).add(read_ext(proof, i - 2));
+        }
+        for (uint256 i = PLONK_ZS_NEXT_OFFSET; i > CONSTANTS_OFFSET; i -= 2) {
+            res[0] = res[0].mul(alpha).add(read_ext(proof, i - 2));
+        }
+        // Batch 1: zs at g * zeta.
+        for (uint256 i = PARTIAL_PRODUCTS_OFFSET; i > PLONK_ZS_NEXT_OFFSET; i -= 2) {
+            res[1] = res[1].mul(alpha).add(read_ext(proof, i - 2));
+        }
+    }
+
+    // Sets `acc = acc * alpha^len + sum_i proof[offset + i] * alpha^i`.
+    function reduce_evals(bytes calldata proof, uint256 offset, uint256 len, uint64[2] memory alpha, uint64[2] memory acc) internal pure {
+        uint256 a0 = acc[0];
+        uint256 a1 = acc[1];
+        for (uint256 i = offset + len; i > offset; i--) {
+            uint256 t = addmod(mulmod(a0, alpha[0], MOD), mulmod(mulmod(a1, alpha[1], MOD), 7, MOD), MOD);
+            a1 = addmod(mulmod(a0, alpha[1], MOD), mulmod(a1, alpha[0], MOD), MOD);
+            a0 = addmod(t, read(proof, i - 1), MOD);
+        }
+        acc[0] = uint64(a0);
+        acc[1] = uint64(a1);
+    }
+
+    function verify_initial_trees(bytes calldata proof, uint256 x_index, uint256 offset, uint64[][] memory caps) internal pure {
+        uint256[4] memory sizes = [uint256(NUM_PREPROCESSED_POLYS), NUM_WIRES, NUM_ZS_PARTIAL_PRODUCTS_POLYS, NUM_QUOTIENT_POLYS];
+        for (uint256 i = 0; i < 4; i++) {
+            verify_merkle_proof(proof, offset, sizes[i], x_index, LDE_BITS - CAP_HEIGHT, caps[i]);
+            offset += sizes[i] + 4 * (LDE_BITS - CAP_HEIGHT);
+        }
+    }
+
+    function combine_initial(bytes calldata proof, ProofChallenges memory challenges, uint64[2][2] memory reduced_openings, QueryRound memory round) internal pure returns (uint64[2] memory) {
+        uint256 merkle_proof_len = 4 * (LDE_BITS - CAP_HEIGHT);
+        uint256 zs_offset = round.offset + NUM_PREPROCESSED_POLYS + NUM_WIRES + 2 * merkle_proof_len;
+        uint64[2] memory sum;
+        reduce_evals(proof, zs_offset + NUM_ZS_PARTIAL_PRODUCTS_POLYS + merkle_proof_len, NUM_QUOTIENT_POLYS, challenges.fri_alpha, sum);
+        reduce_evals(proof, zs_offset, NUM_ZS_PARTIAL_PRODUCTS_POLYS, challenges.fri_alpha, sum);
+        reduce_evals(proof, round.offset + NUM_PREPROCESSED_POLYS + merkle_proof_len, NUM_WIRES, challenges.fri_alpha, sum);
+        reduce_evals(proof, round.offset, NUM_PREPROCESSED_POLYS, challenges.fri_alpha, sum);
+        uint64[2] memory x = GoldilocksExtLib.from_base(round.subgroup_x);
+        sum = sum.sub(reduced_openings[0]).div(x.sub(challenges.plonk_zeta));
+
+        uint64[2] memory zs;
+        reduce_evals(proof, zs_offset, NUM_CHALLENGES, challenges.fri_alpha, zs);
+        zs = zs.sub(reduced_openings[1]).div(x.sub(challenges.plonk_zeta.scalar_mul(DEGREE_GENERATOR)));
+        sum = sum.mul(challenges.fri_alpha.exp(NUM_CHALLENGES)).add(zs);
+        return sum.mul(x);
+    }
+
+    function batch_inverse(uint64[2][] memory values) internal pure returns (uint64[2][] memory res) {
+        res = new uint64[2][](values.length);
+        uint64[2] memory acc = GoldilocksExtLib.one();
+        for (uint256 i = 0; i < values.length; i++) {
+            res[i] = acc;
+            acc = acc.mul(values[i]);
+        }
+        acc = acc.inverse();
+        for (uint256 i = values.length; i > 0; i--) {
+            res[i - 1] = res[i - 1].mul(acc);
+            acc = acc.mul(values[i - 1]);
+        }
+    }
+
+    // Evaluates at `beta` the interpolant of `values` on the coset `coset_start * <g>`. The points
+    // are the roots of `X^n - s` with `s = coset_start^n`, so their barycentric weights are
+    // `x_i / (n * s)`.
+    function interpolate_coset(uint64[2][] memory values, uint64 coset_start, uint64 g, uint64[2] memory beta) internal pure returns (uint64[2] memory res) {
+        uint256 n = values.length;
+        uint64[] memory points = new uint64[](n);
+        uint64[2][] memory denominators = new uint64[2][](n);
+        for (uint256 i = 0; i < n; i++) {
+            points[i] = i == 0 ? coset_start : points[i - 1].mul(g);
+            denominators[i] = beta.sub(GoldilocksExtLib.from_base(points[i]));
+            if (denominators[i].is_zero()) {
+                return values[i];
+            }
+        }
+        uint64[2][] memory inverses = batch_inverse(denominators);
+        for (uint256 i = 0; i < n; i++) {
+            res = res.add(values[i].mul(inverses[i]).scalar_mul(points[i]));
+        }
+        uint64 s = coset_start.exp(n);
+        res = res.mul(beta.exp(n).sub(GoldilocksExtLib.from_base(s))).scalar_mul(uint64(n).mul(s).inverse());
+    }
+
+    // Computes P'(x^arity) from the evaluations {P(x * g^i)} of the previous FRI polynomial.
+    function compute_evaluation(bytes calldata proof, uint256 evals_offset, uint64 x, uint256 x_index_within_coset, uint256 arity_bits, uint64[2] memory beta) internal pure returns (uint64[2] memory) {
+        // The evaluations are stored in bit-reversed order.
+        uint64[2][] memory values = new uint64[2][](uint256(1) << arity_bits);
+        for (uint256 i = 0; i < values.length; i++) {
+            values[i] = read_ext(proof, evals_offset + 2 * reverse_bits(i, arity_bits));
+        }
+        uint64 g = primitive_root_of_unity(arity_bits);
+        uint64 coset_start = x.mul(g.exp(values.length - reverse_bits(x_index_within_coset, arity_bits)));
+        return interpolate_coset(values, coset_start, g, beta);
+    }
+
+    function verify_fri_step(bytes calldata proof, ProofChallenges memory challenges, uint64[][] memory caps, QueryRound memory round, uint256 i, uint256 arity_bits) internal pure {
+        uint256 arity = uint256(1) << arity_bits;
+        uint256 x_index_within_coset = round.x_index & (arity - 1);
+        require(read_ext(proof, round.offset + 2 * x_index_within_coset).equal(round.old_eval), "inconsistent FRI evaluations");
+        round.old_eval = compute_evaluation(proof, round.offset, round.subgroup_x, x_index_within_coset, arity_bits, challenges.fri_betas[i]);
+        round.x_index >>= arity_bits;
+        round.depth -= arity_bits;
+        verify_merkle_proof(proof, round.offset, 2 * arity, round.x_index, round.depth, caps[4 + i]);
+        round.offset += 2 * arity + 4 * round.depth;
+        round.subgroup_x = round.subgroup_x.exp_power_of_2(arity_bits);
+    }
+
+    function verify_query_round(bytes calldata proof, ProofChallenges memory challenges, uint64[2][2] memory reduced_openings, uint64[][] memory caps, uint256 r) internal pure {
+        QueryRound memory round;
+        round.x_index = challenges.fri_query_indices[r];
+        round.offset = QUERY_ROUND_PROOFS_OFFSET + r * QUERY_ROUND_SIZE;
+        verify_initial_trees(proof, round.x_index, round.offset, caps);
+        round.subgroup_x = MULTIPLICATIVE_GROUP_GENERATOR.mul(primitive_root_of_unity(LDE_BITS).exp(reverse_bits(round.x_index, LDE_BITS)));
+        round.old_eval = combine_initial(proof, challenges, reduced_openings, round);
+        round.offset += INITIAL_TREES_PROOF_SIZE;
+        round.depth = LDE_BITS - CAP_HEIGHT;
+
+        uint256[] memory arity_bits = fri_reduction_arity_bits();
+        for (uint256 i = 0; i < NUM_REDUCTIONS; i++) {
+            verify_fri_step(proof, challenges, caps, round, i, arity_bits[i]);
+        }
+
+        uint64[2] memory final_eval;
+        for (uint256 i = FINAL_POLY_LEN; i > 0; i--) {
+            final_eval = final_eval.scalar_mul(round.subgroup_x).add(read_ext(proof, FINAL_POLY_OFFSET + 2 * (i - 1)));
+        }
+        require(final_eval.equal(round.old_eval), "final polynomial evaluation is invalid");
+    }
+
+    function verify_fri_proof(bytes calldata proof, ProofChallenges memory challenges) internal pure {
+        require(uint256(challenges.fri_pow_response) >> (64 - POW_BITS) == 0, "invalid proof of work witness");
+        uint64[2][2] memory reduced_openings = reduce_openings(proof, challenges.fri_alpha);
+        uint64[][] memory caps = load_caps(proof);
+        for (uint256 r = 0; r < NUM_QUERY_ROUNDS; r++) {
+            verify_query_round(proof, challenges, reduced_openings, caps, r);
+        }
+    }
+}
@@ -0,0 +1,462 @@
+//! Generation of Solidity verifier contracts for proofs using [`PoseidonBN128GoldilocksConfig`].
+//!
+//! The generated source is self-contained: it bundles Goldilocks field arithmetic, the Poseidon
+//! permutations, the constraint evaluation code of every gate used by the circuit and the FRI
+//! verifier. Proofs are passed to it as produced by [`encode_proof_calldata`].
+//!
+//! The Poseidon permutations and the evaluation of each gate are public functions of their own
+//! libraries, so that the verifier contract does not embed the whole circuit and every unit stays
+//! under the EIP-170 contract size limit of 24576 bytes. The libraries with public functions are
+//! deployed first and linked into `Plonky2Verifier`.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+
+use anyhow::{bail, ensure, Result};
+use hashbrown::HashSet;
+
+use crate::field::extension::{flatten, Extendable, FieldExtension};
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::field::types::{Field, PrimeField64};
+use crate::gates::selectors::UNUSED_SELECTOR;
+use crate::hash::poseidon::{Poseidon, ALL_ROUND_CONSTANTS};
+use crate::hash::poseidon_bn128::PoseidonBN128GoldilocksConfig;
+use crate::hash::poseidon_bn128_ops;
+use crate::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
+use crate::plonk::proof::ProofWithPublicInputs;
+use crate::plonk::verifier_codegen::{hash_elements, solidity_fr_array, solidity_u64_array};
+
+type F = GoldilocksField;
+type C = PoseidonBN128GoldilocksConfig;
+const D: usize = 2;
+
+const GOLDILOCKS_TEMPLATE: &str = include_str!("solidity/goldilocks.sol");
+const HASH_TEMPLATE: &str = include_str!("solidity/hash.sol");
+const GATES_UTILS_TEMPLATE: &str = include_str!("solidity/gates_utils.sol");
+const VERIFIER_TEMPLATE: &str = include_str!("solidity/verifier.sol");
+
+/// Returns the offsets, in field elements, of the sections of a proof encoded by
+/// [`encode_proof_calldata`], along with the sizes of query round proofs and of the whole proof.
+fn proof_layout(common: &CommonCircuitData<F, D>) -> Vec<(&'static str, usize)> {
+    let cap_words = 4 * common.config.fri_config.num_cap_elements();
+    let num_challenges = common.config.num_challenges;
+    let merkle_proof_len = common.fri_params.lde_bits() - common.config.fri_config.cap_height;
+
+    let initial_trees_proof_size = common.num_preprocessed_polys()
+        + common.config.num_wires
+        + common.num_zs_partial_products_polys()
+        + common.num_quotient_polys()
+        + 4 * 4 * merkle_proof_len;
+    let mut query_round_size = initial_trees_proof_size;
+    let mut depth = merkle_proof_len;
+    for &arity_bits in &common.fri_params.reduction_arity_bits {
+        depth -= arity_bits;
+        query_round_size += D * (1 << arity_bits) + 4 * depth;
+    }
+
+    let mut layout = Vec::new();
+    let mut offset = common.num_public_inputs;
+    let mut section = |name: &'static str, len: usize| {
+        layout.push((name, offset));
+        offset += len;
+    };
+    section("WIRES_CAP_OFFSET", cap_words);
+    section("PLONK_ZS_PARTIAL_PRODUCTS_CAP_OFFSET", cap_words);
+    section("QUOTIENT_POLYS_CAP_OFFSET", cap_words);
+    section("CONSTANTS_OFFSET", D * common.num_constants);
+    section("PLONK_SIGMAS_OFFSET", D * common.config.num_routed_wires);
+    section("WIRES_OFFSET", D * common.config.num_wires);
+    section("PLONK_ZS_OFFSET", D * num_challenges);
+    section("PLONK_ZS_NEXT_OFFSET", D * num_challenges);
+    section(
+        "PARTIAL_PRODUCTS_OFFSET",
+        D * num_challenges * common.num_partial_products,
+    );
+    section("QUOTIENT_POLYS_OFFSET", D * common.num_quotient_polys());
+    section("OPENINGS_END", 0);
+    section(
+        "COMMIT_PHASE_CAPS_OFFSET",
+        cap_words * common.fri_params.reduction_arity_bits.len(),
+    );
+    section(
+        "QUERY_ROUND_PROOFS_OFFSET",
+        query_round_size * common.config.fri_config.num_query_rounds,
+    );
+    section("FINAL_POLY_OFFSET", D * common.fri_params.final_poly_len());
+    section("POW_WITNESS_OFFSET", 1);
+    section("PROOF_SIZE", 0);
+    layout.push(("INITIAL_TREES_PROOF_SIZE", initial_trees_proof_size));
+    layout.push(("QUERY_ROUND_SIZE", query_round_size));
+    layout
+}
+
+/// Returns the source of a Solidity contract `Plonky2Verifier` whose `verify(bytes)` function
+/// accepts exactly the proofs of the given circuit accepted by the native verifier.
+///
+/// Circuits using lookups or zero-knowledge are not supported.
+pub fn generate_solidity_verifier(
+    common: &CommonCircuitData<F, D>,
+    verifier_only: &VerifierOnlyCircuitData<C, D>,
+) -> Result<String> {
+    ensure!(
+        common.num_lookup_polys == 0,
+        "lookups are not supported by the Solidity verifier"
+    );
+    ensure!(
+        !common.fri_params.hiding && !common.config.zero_knowledge,
+        "zero-knowledge proofs are not supported by the Solidity verifier"
+    );
+    // The native verifier opens the `Z`s at `g * zeta` for `g` in the extension field.
+    let [degree_generator, degree_generator_high] = FieldExtension::<D>::to_basefield_array(
+        &<F as Extendable<D>>::Extension::primitive_root_of_unity(common.degree_bits()),
+    );
+    ensure!(
+        degree_generator_high.is_zero(),
+        "the subgroup generator is not in the base field"
+    );
+
+    let mut circuit_constants = String::new();
+    let mut push_constant = |name: &str, value: usize| {
+        writeln!(circuit_constants, "    uint256 constant {name} = {value};").unwrap();
+    };
+    push_constant("NUM_PUBLIC_INPUTS", common.num_public_inputs);
+    push_constant("CAP_HEIGHT", common.config.fri_config.cap_height);
+    push_constant("CAP_LEN", common.config.fri_config.num_cap_elements());
+    push_constant("NUM_CONSTANTS", common.num_constants);
+    push_constant("NUM_ROUTED_WIRES", common.config.num_routed_wires);
+    push_constant("NUM_WIRES", common.config.num_wires);
+    push_constant("NUM_CHALLENGES", common.config.num_challenges);
+    push_constant("NUM_PARTIAL_PRODUCTS", common.num_partial_products);
+    push_constant("QUOTIENT_DEGREE_FACTOR", common.quotient_degree_factor);
+    push_constant("NUM_GATE_CONSTRAINTS", common.num_gate_constraints);
+    push_constant("DEGREE_BITS", common.degree_bits());
+    push_constant("DEGREE", common.degree());
+    push_constant("LDE_BITS", common.fri_params.lde_bits());
+    push_constant("LDE_SIZE", common.lde_size());
+    push_constant(
+        "NUM_QUERY_ROUNDS",
+        common.config.fri_config.num_query_rounds,
+    );
+    push_constant(
+        "POW_BITS",
+        common.config.fri_config.proof_of_work_bits as usize,
+    );
+    push_constant("FINAL_POLY_LEN", common.fri_params.final_poly_len());
+    push_constant(
+        "NUM_REDUCTIONS",
+        common.fri_params.reduction_arity_bits.len(),
+    );
+    push_constant("NUM_PREPROCESSED_POLYS", common.num_preprocessed_polys());
+    push_constant(
+        "NUM_ZS_PARTIAL_PRODUCTS_POLYS",
+        common.num_zs_partial_products_polys(),
+    );
+    push_constant("NUM_QUOTIENT_POLYS", common.num_quotient_polys());
+    for (name, value) in proof_layout(common) {
+        push_constant(name, value);
+    }
+    writeln!(
+        circuit_constants,
+        "    // The generator of the subgroup of order `DEGREE`.\n    uint64 constant DEGREE_GENERATOR = {};",
+        degree_generator.to_canonical_u64()
+    )
+    .unwrap();
+
+    let mut set_arity_bits = String::new();
+    for (i, arity_bits) in common.fri_params.reduction_arity_bits.iter().enumerate() {
+        writeln!(set_arity_bits, "        bits[{i}] = {arity_bits};").unwrap();
+    }
+
+    let constants_sigmas_cap = hash_elements(&verifier_only.constants_sigmas_cap.0);
+    let k_is = common
+        .k_is
+        .iter()
+        .map(|k| k.to_canonical_u64())
+        .collect::<Vec<_>>();
+
+    let mut contract = String::from("// SPDX-License-Identifier: MIT OR Apache-2.0\n");
+    contract += "pragma solidity ^0.8.5;\n\n";
+    contract += GOLDILOCKS_TEMPLATE;
+    contract += "\n";
+    contract += &hash_template();
+    contract += "\n";
+    contract += &GATES_UTILS_TEMPLATE
+        .replace("$NUM_OPENINGS_CONSTANTS", &common.num_constants.to_string())
+        .replace("$NUM_OPENINGS_WIRES", &common.config.num_wires.to_string())
+        .replace(
+            "$NUM_GATE_CONSTRAINTS",
+            &common.num_gate_constraints.to_string(),
+        )
+        .replace("$D", &D.to_string());
+    contract += "\n";
+
+    let (gate_libraries, gate_evaluation) = gate_libraries(common)?;
+    contract += &gate_libraries;
+    contract += &VERIFIER_TEMPLATE
+        .replace("$CIRCUIT_CONSTANTS", &circuit_constants)
+        .replace(
+            "$CIRCUIT_DIGEST",
+            &solidity_u64_array(&hash_elements(&[verifier_only.circuit_digest])),
+        )
+        .replace("$CAP_WORDS", &constants_sigmas_cap.len().to_string())
+        .replace(
+            "$CONSTANTS_SIGMAS_CAP",
+            &solidity_u64_array(&constants_sigmas_cap),
+        )
+        .replace("$K_IS", &solidity_u64_array(&k_is))
+        .replace(
+            "$NUM_ROUTED_WIRES",
+            &common.config.num_routed_wires.to_string(),
+        )
+        .replace("$SET_FRI_REDUCTION_ARITY_BITS\n", &set_arity_bits)
+        .replace(
+            "$NUM_GATE_CONSTRAINTS",
+            &common.num_gate_constraints.to_string(),
+        )
+        .replace("$GATE_EVALUATION\n", &gate_evaluation);
+    ensure!(
+        !contract.contains('$'),
+        "unsubstituted placeholder in the generated verifier"
+    );
+    Ok(contract)
+}
+
+fn hash_template() -> String {
+    let gl_round_constants = ALL_ROUND_CONSTANTS.to_vec();
+    let bn128_mds = poseidon_bn128_ops::MDS_MATRIX
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+    HASH_TEMPLATE
+        .replace(
+            "$GL_NUM_ROUND_CONSTANTS",
+            &gl_round_constants.len().to_string(),
+        )
+        .replace(
+            "$GL_ROUND_CONSTANTS",
+            &solidity_u64_array(&gl_round_constants),
+        )
+        .replace(
+            "$GL_MDS_MATRIX_CIRC",
+            &solidity_u64_array(&<F as Poseidon>::MDS_MATRIX_CIRC),
+        )
+        .replace(
+            "$GL_MDS_MATRIX_DIAG",
+            &solidity_u64_array(&<F as Poseidon>::MDS_MATRIX_DIAG),
+        )
+        .replace(
+            "$BN128_NUM_ROUND_CONSTANTS",
+            &poseidon_bn128_ops::ROUND_CONSTANTS.len().to_string(),
+        )
+        .replace(
+            "$BN128_ROUND_CONSTANTS",
+            &solidity_fr_array(&poseidon_bn128_ops::ROUND_CONSTANTS),
+        )
+        .replace("$BN128_MDS_MATRIX", &solidity_fr_array(&bn128_mds))
+}
+
+/// Returns the libraries evaluating the constraints of each gate of the circuit, and the code
+/// accumulating their filtered constraints through calls to the libraries' `evaluate` functions.
+fn gate_libraries(common: &CommonCircuitData<F, D>) -> Result<(String, String)> {
+    let num_selectors = common.selectors_info.num_selectors();
+    let mut libraries = String::new();
+    let mut evaluation = String::new();
+    let mut names = HashSet::new();
+    for (i, gate) in common.gates.iter().enumerate() {
+        let selector_index = common.selectors_info.selector_indices[i];
+        let mut filter = "ev.filter = GoldilocksExtLib.one()".to_string();
+        let selector = format!(".sub(ev.constants[{selector_index}]))");
+        for j in common.selectors_info.groups[selector_index].clone() {
+            if j != i {
+                filter += &format!(".mul(GatesUtilsLib.field_ext_from({j}, 0){selector}");
+            }
+        }
+        if num_selectors > 1 {
+            filter += &format!(".mul(GatesUtilsLib.field_ext_from({UNUSED_SELECTOR}, 0){selector}");
+        }
+
+        let code = gate
+            .0
+            .export_solidity_verification_code()
+            .replace("$SET_FILTER", &filter)
+            .replace(
+                "$NUM_GATE_CONSTRAINTS",
+                &common.num_gate_constraints.to_string(),
+            )
+            .replace(
+                "$NUM_SELECTORS",
+                &(num_selectors + common.num_lookup_selectors).to_string(),
+            )
+            .replace("$D", &D.to_string());
+        let name = code
+            .split_once("library ")
+            .and_then(|(_, rest)| {
+                rest.split(|c: char| !c.is_alphanumeric() && c != '_')
+                    .next()
+            })
+            .unwrap_or_default()
+            .to_string();
+        ensure!(
+            !name.is_empty(),
+            "no library in the code of gate {}",
+            gate.0.id()
+        );
+        ensure!(
+            names.insert(name.clone()),
+            "several gates use the library name {name}"
+        );
+        // The public entry point called by the verifier. Memory is not shared with a library call,
+        // so the accumulated constraints are passed in and returned.
+        let Some(end) = code.rfind('}') else {
+            bail!("no library in the code of gate {}", gate.0.id());
+        };
+        libraries += &code[..end];
+        writeln!(
+            libraries,
+            "
+    function evaluate(GatesUtilsLib.EvaluationVars memory ev, uint64[2][{n}] memory constraints) public pure returns (uint64[2][{n}] memory) {{
+        set_filter(ev);
+        eval(ev, constraints);
+        return constraints;
+    }}",
+            n = common.num_gate_constraints
+        )
+        .unwrap();
+        libraries += &code[end..];
+        libraries += "\n\n";
+        writeln!(
+            evaluation,
+            "        constraints = {name}.evaluate(ev, constraints);"
+        )
+        .unwrap();
+    }
+    Ok((libraries, evaluation))
+}
+
+/// Encodes a proof as the argument of the `verify` function of a contract generated by
+/// [`generate_solidity_verifier`]: its field elements as 8-byte big-endian words, public inputs
+/// first.
+pub fn encode_proof_calldata(proof_with_pis: &ProofWithPublicInputs<F, C, D>) -> Vec<u8> {
+    let mut elements: Vec<F> = proof_with_pis.public_inputs.clone();
+    let proof = &proof_with_pis.proof;
+    for cap in [
+        &proof.wires_cap,
+        &proof.plonk_zs_partial_products_cap,
+        &proof.quotient_polys_cap,
+    ] {
+        elements.extend(cap.0.iter().flat_map(|h| h.elements));
+    }
+    let openings = &proof.openings;
+    for values in [
+        &openings.constants,
+        &openings.plonk_sigmas,
+        &openings.wires,
+        &openings.plonk_zs,
+        &openings.plonk_zs_next,
+        &openings.partial_products,
+        &openings.quotient_polys,
+        &openings.lookup_zs,
+        &openings.lookup_zs_next,
+    ] {
+        elements.extend(flatten::<F, D>(values));
+    }
+    let fri_proof = &proof.opening_proof;
+    for cap in &fri_proof.commit_phase_merkle_caps {
+        elements.extend(cap.0.iter().flat_map(|h| h.elements));
+    }
+    for round in &fri_proof.query_round_proofs {
+        for (evals, merkle_proof) in &round.initial_trees_proof.evals_proofs {
+            elements.extend(evals);
+            elements.extend(merkle_proof.siblings.iter().flat_map(|h| h.elements));
+        }
+        for step in &round.steps {
+            elements.extend(flatten::<F, D>(&step.evals));
+            elements.extend(step.merkle_proof.siblings.iter().flat_map(|h| h.elements));
+        }
+    }
+    elements.extend(flatten::<F, D>(&fri_proof.final_poly.coeffs));
+    elements.push(fri_proof.pow_witness);
+
+    elements
+        .iter()
+        .flat_map(|x| x.to_canonical_u64().to_be_bytes())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::Field;
+    use crate::gates::poseidon2_mds::Poseidon2MdsGate;
+    use crate::hash::poseidon2::Poseidon2Hash;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+
+    #[test]
+    fn test_generate_solidity_verifier() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.exp_u64(x, 123);
+        builder.register_public_input(x);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(5));
+        let proof = data.prove(pw)?;
+        data.verify(proof.clone())?;
+
+        let source = generate_solidity_verifier(&data.common, &data.verifier_only)?;
+        assert!(!source.contains('$'));
+
+        let proof_size = proof_layout(&data.common)
+            .into_iter()
+            .find(|(name, _)| *name == "PROOF_SIZE")
+            .unwrap()
+            .1;
+        assert_eq!(encode_proof_calldata(&proof).len(), 8 * proof_size);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_solidity_verifier_poseidon2_gates() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let inputs = builder.add_virtual_targets(4);
+        let hash = builder.hash_n_to_hash_no_pad::<Poseidon2Hash>(inputs);
+        builder.register_public_inputs(&hash.elements);
+        builder.add_gate(Poseidon2MdsGate::<F, D>::new(), vec![]);
+        let data = builder.build::<C>();
+
+        let source = generate_solidity_verifier(&data.common, &data.verifier_only)?;
+        assert!(source.contains("library Poseidon2Lib"));
+        assert!(source.contains("library Poseidon2MdsGateLib"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_solidity_verifier_rejects_lookups() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let table = (0..16u16).map(|i| (i, i * i)).collect::<Vec<_>>();
+        let table_index = builder.add_lookup_table_from_pairs(table.into());
+        let square = builder.add_lookup_from_index(x, table_index);
+        builder.register_public_input(x);
+        builder.register_public_input(square);
+        let data = builder.build::<C>();
+
+        let err = generate_solidity_verifier(&data.common, &data.verifier_only).unwrap_err();
+        assert!(err.to_string().contains("lookups are not supported"));
+    }
+}
@@ -0,0 +1,255 @@
+//! Detection of targets which are assigned by witness generators, but not constrained.
+//!
+//! A value written by a generator only matters to the verifier if it ends up in a wire which some
+//! gate constrains, either directly or through copy constraints. Anything else can be set to any
+//! value by a malicious prover, which is almost always a bug, e.g. a result of
+//! `add_virtual_bool_target_unsafe` which never gets an `assert_bool`.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+use hashbrown::{HashMap, HashSet};
+
+use crate::field::extension::Extendable;
+use crate::field::types::Sample;
+use crate::hash::hash_types::{HashOut, RichField};
+use crate::iop::generator::GeneratedValues;
+use crate::iop::target::Target;
+use crate::iop::wire::Wire;
+use crate::iop::witness::PartitionWitness;
+use crate::plonk::circuit_data::{CommonCircuitData, MockCircuitData, ProverOnlyCircuitData};
+use crate::plonk::config::GenericConfig;
+use crate::plonk::mock_prover::CircuitLayout;
+use crate::plonk::vars::EvaluationVarsBaseBatch;
+
+/// A target which is written by a witness generator, but whose value no constraint depends on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnconstrainedTarget {
+    pub target: Target,
+    /// The ID of the generator which writes the target.
+    pub generator: String,
+    /// The scope in which the target was created, i.e. the scope of its row for wires.
+    pub context: String,
+}
+
+impl Display for UnconstrainedTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} is written by {} but not constrained (in {})",
+            self.target, self.generator, self.context
+        )
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    MockCircuitData<F, C, D>
+{
+    /// Lists the targets which are written by a witness generator, but not constrained by any
+    /// gate, either directly or through copy constraints.
+    pub fn unconstrained_targets(&self) -> Vec<UnconstrainedTarget> {
+        find_unconstrained_targets(&self.prover_only, &self.common, &self.layout)
+    }
+}
+
+/// Lists the targets which are written by a witness generator, but not constrained by any gate.
+///
+/// Generators do not declare the targets they write, so each one is run once on a witness where
+/// every target is one, except for lookup generators which would need real table inputs. A wire is constrained by its gate if changing its value changes one of the
+/// constraints of the gate, which is tested at a random point. Targets holding random values, or
+/// values derived only from random values, are blinding factors and are not reported.
+pub(crate) fn find_unconstrained_targets<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_only: &ProverOnlyCircuitData<F, C, D>,
+    common: &CommonCircuitData<F, D>,
+    layout: &CircuitLayout<F, D>,
+) -> Vec<UnconstrainedTarget> {
+    let num_wires = common.config.num_wires;
+    let mut witness =
+        PartitionWitness::new(num_wires, common.degree(), &prover_only.representative_map);
+    let rep_index = |witness: &PartitionWitness<F>, target: Target| {
+        witness.representative_map[witness.target_index(target)]
+    };
+
+    // Mark the partitions containing a wire which its gate constrains.
+    let mut is_constrained = vec![false; witness.values.len()];
+    let mut constrained_wires_by_gate = HashMap::new();
+    for (row, gate) in layout.gate_instances.iter().enumerate() {
+        let constrained_wires = constrained_wires_by_gate
+            .entry((gate.gate_ref.0.id(), gate.constants.clone()))
+            .or_insert_with(|| {
+                let eval = |wires: &[F], public_inputs_hash: &HashOut<F>| {
+                    gate.gate_ref
+                        .0
+                        .eval_unfiltered_base_batch(EvaluationVarsBaseBatch::new(
+                            1,
+                            &gate.constants,
+                            wires,
+                            public_inputs_hash,
+                        ))
+                };
+                let public_inputs_hash = HashOut::rand();
+                let mut wires = F::rand_vec(num_wires);
+                let constraints = eval(&wires, &public_inputs_hash);
+                (0..num_wires)
+                    .map(|column| {
+                        let value = wires[column];
+                        wires[column] = F::rand();
+                        let changed = eval(&wires, &public_inputs_hash) != constraints;
+                        wires[column] = value;
+                        changed
+                    })
+                    .collect::<Vec<_>>()
+            });
+        for (column, _) in constrained_wires.iter().enumerate().filter(|(_, &c)| c) {
+            is_constrained[rep_index(&witness, Target::wire(row, column))] = true;
+        }
+    }
+    // Lookups and lookup tables are constrained by the lookup argument rather than by their gates.
+    for lookup_rows in &prover_only.lookup_rows {
+        for row in lookup_rows.last_lu_gate..=lookup_rows.first_lut_gate {
+            for column in 0..num_wires {
+                is_constrained[rep_index(&witness, Target::wire(row, column))] = true;
+            }
+        }
+    }
+
+    for value in witness.values.iter_mut() {
+        *value = Some(F::ONE);
+    }
+    let mut is_random = vec![false; witness.values.len()];
+    let mut unconstrained = Vec::new();
+    let mut reported = HashSet::new();
+    let mut buffer = GeneratedValues::empty();
+    for generator in &prover_only.generators {
+        // Lookup generators would fail on inputs which aren't in their table, and the rows they
+        // write are already marked as constrained.
+        if generator.0.is_lookup() {
+            continue;
+        }
+        let watch_list = generator.0.watch_list();
+        let random = generator.0.writes_random_values()
+            || (!watch_list.is_empty()
+                && watch_list
+                    .iter()
+                    .all(|&t| is_random[rep_index(&witness, t)]));
+        generator.0.run(&witness, &mut buffer);
+        for (target, _) in buffer.target_values.drain(..) {
+            let rep = rep_index(&witness, target);
+            if random {
+                is_random[rep] = true;
+            } else if !is_constrained[rep] && reported.insert(target) {
+                unconstrained.push((target, generator.0.id()));
+            }
+        }
+    }
+
+    unconstrained
+        .into_iter()
+        .filter(|&(target, _)| !is_random[rep_index(&witness, target)])
+        .map(|(target, generator)| UnconstrainedTarget {
+            target,
+            generator,
+            context: match target {
+                Target::Wire(Wire { row, .. }) => layout.context_log.stack_at(row),
+                Target::VirtualTarget { index } => {
+                    layout.context_log.stack_at_virtual_target(index)
+                }
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_unconstrained_targets() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let bits = builder.split_le(x, 8);
+        let sum = builder.add(x, y);
+        builder.register_public_input(sum);
+
+        // An unsafe boolean which is never range checked nor used.
+        builder.push_context(log::Level::Debug, "flag");
+        let flag = builder.add_virtual_bool_target_unsafe();
+        builder.pop_context();
+        builder.generate_copy(bits[0].target, flag.target);
+
+        let data = builder.mock_build::<C>();
+        assert_eq!(
+            data.unconstrained_targets(),
+            vec![UnconstrainedTarget {
+                target: flag.target,
+                generator: "CopyGenerator".into(),
+                context: "root > flag".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_no_unconstrained_targets() {
+        let config = CircuitConfig::standard_recursion_zk_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let quotient = builder.div(x, y);
+        let is_equal = builder.is_equal(x, y);
+        builder.range_check(quotient, 32);
+        builder.register_public_input(is_equal.target);
+        let table = (0..16u16).map(|i| (i, i * i)).collect::<Vec<_>>();
+        let table_index = builder.add_lookup_table_from_pairs(table.into());
+        let square = builder.add_lookup_from_index(x, table_index);
+        builder.register_public_input(square);
+
+        let data = builder.mock_build::<C>();
+        assert_eq!(data.unconstrained_targets(), vec![]);
+    }
+
+    #[test]
+    fn test_unconstrained_targets_with_lookups() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        // Neither table has an entry for one, the value every target takes during the analysis.
+        let table = (2..18u16).map(|i| (i, i * i)).collect::<Vec<_>>();
+        let table_index = builder.add_lookup_table_from_pairs(table.into());
+        let square = builder.add_lookup_from_index(x, table_index);
+        let single_table_index = builder.add_lookup_table_from_pairs(vec![(7, 49)].into());
+        let single = builder.add_lookup_from_index(y, single_table_index);
+        builder.register_public_input(square);
+        builder.register_public_input(single);
+
+        let data = builder.mock_build::<C>();
+        assert_eq!(data.unconstrained_targets(), vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to build circuit")]
+    fn test_fail_on_unconstrained_targets() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        builder.set_fail_on_unconstrained_targets(true);
+        let x = builder.add_virtual_target();
+        builder.register_public_input(x);
+        let flag = builder.add_virtual_bool_target_unsafe();
+        builder.generate_copy(x, flag.target);
+        builder.build::<C>();
+    }
+}
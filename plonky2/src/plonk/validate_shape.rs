@@ -1,15 +1,18 @@
-use anyhow::ensure;
-
 use crate::field::extension::Extendable;
+use crate::fri::validate_shape::validate_compressed_fri_proof_shape;
 use crate::hash::hash_types::RichField;
+use crate::hash::merkle_tree::MerkleCap;
 use crate::plonk::circuit_data::CommonCircuitData;
 use crate::plonk::config::GenericConfig;
-use crate::plonk::proof::{OpeningSet, Proof, ProofWithPublicInputs};
+use crate::plonk::proof::{
+    CompressedProof, CompressedProofWithPublicInputs, OpeningSet, Proof, ProofWithPublicInputs,
+};
+use crate::plonk::verifier::{ensure_shape, VerificationError};
 
 pub(crate) fn validate_proof_with_pis_shape<F, C, const D: usize>(
     proof_with_pis: &ProofWithPublicInputs<F, C, D>,
     common_data: &CommonCircuitData<F, D>,
-) -> anyhow::Result<()>
+) -> Result<(), VerificationError>
 where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -19,22 +22,64 @@ where
         public_inputs,
     } = proof_with_pis;
     validate_proof_shape(proof, common_data)?;
-    ensure!(
-        public_inputs.len() == common_data.num_public_inputs,
-        "Number of public inputs doesn't match circuit data."
-    );
+    validate_num_public_inputs(public_inputs.len(), common_data)
+}
+
+pub(crate) fn validate_compressed_proof_with_pis_shape<F, C, const D: usize>(
+    proof_with_pis: &CompressedProofWithPublicInputs<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> Result<(), VerificationError>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let CompressedProofWithPublicInputs {
+        proof,
+        public_inputs,
+    } = proof_with_pis;
+    validate_num_public_inputs(public_inputs.len(), common_data)?;
+    let CompressedProof {
+        wires_cap,
+        plonk_zs_partial_products_cap,
+        quotient_polys_cap,
+        openings,
+        opening_proof,
+    } = proof;
+    validate_caps_and_openings_shape::<F, C, D>(
+        wires_cap,
+        plonk_zs_partial_products_cap,
+        quotient_polys_cap,
+        openings,
+        common_data,
+    )?;
+    validate_compressed_fri_proof_shape::<F, C, D>(
+        opening_proof,
+        &common_data.fri_oracles(),
+        &common_data.fri_params,
+    )
+}
+
+fn validate_num_public_inputs<F: RichField + Extendable<D>, const D: usize>(
+    num_public_inputs: usize,
+    common_data: &CommonCircuitData<F, D>,
+) -> Result<(), VerificationError> {
+    if num_public_inputs != common_data.num_public_inputs {
+        return Err(VerificationError::NumPublicInputs {
+            expected: common_data.num_public_inputs,
+            actual: num_public_inputs,
+        });
+    }
     Ok(())
 }
 
 fn validate_proof_shape<F, C, const D: usize>(
     proof: &Proof<F, C, D>,
     common_data: &CommonCircuitData<F, D>,
-) -> anyhow::Result<()>
+) -> Result<(), VerificationError>
 where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
 {
-    let config = &common_data.config;
     let Proof {
         wires_cap,
         plonk_zs_partial_products_cap,
@@ -44,6 +89,27 @@ where
         // validate_fri_proof_shape), so we ignore it here.
         opening_proof: _,
     } = proof;
+    validate_caps_and_openings_shape::<F, C, D>(
+        wires_cap,
+        plonk_zs_partial_products_cap,
+        quotient_polys_cap,
+        openings,
+        common_data,
+    )
+}
+
+fn validate_caps_and_openings_shape<F, C, const D: usize>(
+    wires_cap: &MerkleCap<F, C::Hasher>,
+    plonk_zs_partial_products_cap: &MerkleCap<F, C::Hasher>,
+    quotient_polys_cap: &MerkleCap<F, C::Hasher>,
+    openings: &OpeningSet<F, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> Result<(), VerificationError>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let config = &common_data.config;
     let OpeningSet {
         constants,
         plonk_sigmas,
@@ -52,17 +118,45 @@ where
         plonk_zs_next,
         partial_products,
         quotient_polys,
+        lookup_zs,
+        lookup_zs_next,
     } = openings;
     let cap_height = common_data.fri_params.config.cap_height;
-    ensure!(wires_cap.height() == cap_height);
-    ensure!(plonk_zs_partial_products_cap.height() == cap_height);
-    ensure!(quotient_polys_cap.height() == cap_height);
-    ensure!(constants.len() == common_data.num_constants);
-    ensure!(plonk_sigmas.len() == config.num_routed_wires);
-    ensure!(wires.len() == config.num_wires);
-    ensure!(plonk_zs.len() == config.num_challenges);
-    ensure!(plonk_zs_next.len() == config.num_challenges);
-    ensure!(partial_products.len() == config.num_challenges * common_data.num_partial_products);
-    ensure!(quotient_polys.len() == common_data.num_quotient_polys());
+    ensure_shape(wires_cap.height() == cap_height, "wires cap")?;
+    ensure_shape(
+        plonk_zs_partial_products_cap.height() == cap_height,
+        "plonk zs partial products cap",
+    )?;
+    ensure_shape(
+        quotient_polys_cap.height() == cap_height,
+        "quotient polys cap",
+    )?;
+    ensure_shape(constants.len() == common_data.num_constants, "constants")?;
+    ensure_shape(
+        plonk_sigmas.len() == config.num_routed_wires,
+        "plonk sigmas",
+    )?;
+    ensure_shape(wires.len() == config.num_wires, "wires")?;
+    ensure_shape(plonk_zs.len() == config.num_challenges, "plonk zs")?;
+    ensure_shape(
+        plonk_zs_next.len() == config.num_challenges,
+        "plonk zs next",
+    )?;
+    ensure_shape(
+        partial_products.len() == config.num_challenges * common_data.num_partial_products,
+        "partial products",
+    )?;
+    ensure_shape(
+        quotient_polys.len() == common_data.num_quotient_polys(),
+        "quotient polys",
+    )?;
+    ensure_shape(
+        lookup_zs.len() == common_data.num_all_lookup_polys(),
+        "lookup zs",
+    )?;
+    ensure_shape(
+        lookup_zs_next.len() == common_data.num_all_lookup_polys(),
+        "lookup zs next",
+    )?;
     Ok(())
 }
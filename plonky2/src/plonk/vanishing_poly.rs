@@ -1,41 +1,49 @@
 #[cfg(not(feature = "std"))]
 use alloc::{format, vec, vec::Vec};
+use core::cmp::min;
 
 use crate::field::batch_util::batch_add_inplace;
 use crate::field::extension::{Extendable, FieldExtension};
+use crate::field::polynomial::PolynomialCoeffs;
 use crate::field::types::Field;
 use crate::field::zero_poly_coset::ZeroPolyOnCoset;
+use crate::gates::lookup::LookupGate;
+use crate::gates::lookup_table::LookupTableGate;
+use crate::gates::selectors::LookupSelectors;
 use crate::hash::hash_types::RichField;
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::Target;
-use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_builder::{CircuitBuilder, LookupChallenges, NUM_COINS_LOOKUP};
 use crate::plonk::circuit_data::CommonCircuitData;
 use crate::plonk::config::GenericConfig;
 use crate::plonk::plonk_common;
 use crate::plonk::plonk_common::eval_l_0_circuit;
-use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBaseBatch};
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+};
+use crate::util::ceil_div_usize;
 use crate::util::partial_products::{check_partial_products, check_partial_products_circuit};
 use crate::util::reducing::ReducingFactorTarget;
 use crate::util::strided_view::PackedStridedView;
 use crate::with_context;
 
-// /// Get the polynomial associated to a lookup table with current challenges.
-// pub(crate) fn get_lut_poly<F: RichField + Extendable<D>, const D: usize>(
-//     common_data: &CommonCircuitData<F, D>,
-//     lut_index: usize,
-//     deltas: &[F],
-//     degree: usize,
-// ) -> PolynomialCoeffs<F> {
-//     let b = deltas[LookupChallenges::ChallengeB as usize];
-//     let mut coeffs = Vec::with_capacity(common_data.luts[lut_index].len());
-//     let n = common_data.luts[lut_index].len();
-//     for (input, output) in common_data.luts[lut_index].iter() {
-//         coeffs.push(F::from_canonical_u16(*input) + b * F::from_canonical_u16(*output));
-//     }
-//     coeffs.append(&mut vec![F::ZERO; degree - n]);
-//     coeffs.reverse();
-//     PolynomialCoeffs::new(coeffs)
-// }
+/// Get the polynomial associated to a lookup table with current challenges.
+pub(crate) fn get_lut_poly<F: RichField + Extendable<D>, const D: usize>(
+    common_data: &CommonCircuitData<F, D>,
+    lut_index: usize,
+    deltas: &[F],
+    degree: usize,
+) -> PolynomialCoeffs<F> {
+    let b = deltas[LookupChallenges::ChallengeB as usize];
+    let mut coeffs = Vec::with_capacity(common_data.luts[lut_index].len());
+    let n = common_data.luts[lut_index].len();
+    for (input, output) in common_data.luts[lut_index].iter() {
+        coeffs.push(F::from_canonical_u16(*input) + b * F::from_canonical_u16(*output));
+    }
+    coeffs.append(&mut vec![F::ZERO; degree - n]);
+    coeffs.reverse();
+    PolynomialCoeffs::new(coeffs)
+}
 
 /// Evaluate the vanishing polynomial at `x`. In this context, the vanishing polynomial is a random
 /// linear combination of gate constraints, plus some other terms relating to the permutation
@@ -50,19 +58,37 @@ pub(crate) fn eval_vanishing_poly<
     vars: EvaluationVars<F, D>,
     local_zs: &[F::Extension],
     next_zs: &[F::Extension],
+    local_lookup_zs: &[F::Extension],
+    next_lookup_zs: &[F::Extension],
     partial_products: &[F::Extension],
     s_sigmas: &[F::Extension],
     betas: &[F],
     gammas: &[F],
     alphas: &[F],
+    deltas: &[F],
 ) -> Vec<F::Extension> {
+    let has_lookup = common_data.num_lookup_polys != 0;
     let max_degree = common_data.quotient_degree_factor;
     let num_prods = common_data.num_partial_products;
 
     let constraint_terms = evaluate_gate_constraints::<F, C, D>(common_data, vars);
 
+    let lookup_selectors = &vars.local_constants[common_data.selectors_info.num_selectors()
+        ..common_data.selectors_info.num_selectors() + common_data.num_lookup_selectors];
+
     // The L_0(x) (Z(x) - 1) vanishing terms.
     let mut vanishing_z_1_terms = Vec::new();
+
+    // The terms checking the lookup constraints, if any.
+    let mut vanishing_all_lookup_terms = if has_lookup {
+        let num_sldc_polys = common_data.num_lookup_polys - 1;
+        Vec::with_capacity(
+            common_data.config.num_challenges * (4 + common_data.luts.len() + 2 * num_sldc_polys),
+        )
+    } else {
+        Vec::new()
+    };
+
     // The terms checking the partial products.
     let mut vanishing_partial_products_terms = Vec::new();
 
@@ -73,6 +99,26 @@ pub(crate) fn eval_vanishing_poly<
         let z_gx = next_zs[i];
         vanishing_z_1_terms.push(l_0_x * (z_x - F::Extension::ONE));
 
+        if has_lookup {
+            let cur_local_lookup_zs = &local_lookup_zs
+                [common_data.num_lookup_polys * i..common_data.num_lookup_polys * (i + 1)];
+            let cur_next_lookup_zs = &next_lookup_zs
+                [common_data.num_lookup_polys * i..common_data.num_lookup_polys * (i + 1)];
+
+            let cur_deltas = &deltas[NUM_COINS_LOOKUP * i..NUM_COINS_LOOKUP * (i + 1)];
+
+            let lookup_constraints = check_lookup_constraints(
+                common_data,
+                vars,
+                cur_local_lookup_zs,
+                cur_next_lookup_zs,
+                lookup_selectors,
+                cur_deltas.try_into().unwrap(),
+            );
+
+            vanishing_all_lookup_terms.extend(lookup_constraints);
+        }
+
         let numerator_values = (0..common_data.config.num_routed_wires)
             .map(|j| {
                 let wire_value = vars.local_wires[j];
@@ -106,6 +152,7 @@ pub(crate) fn eval_vanishing_poly<
     let vanishing_terms = [
         vanishing_z_1_terms,
         vanishing_partial_products_terms,
+        vanishing_all_lookup_terms,
         constraint_terms,
     ]
     .concat();
@@ -126,18 +173,31 @@ pub(crate) fn eval_vanishing_poly_base_batch<
     vars_batch: EvaluationVarsBaseBatch<F>,
     local_zs_batch: &[&[F]],
     next_zs_batch: &[&[F]],
+    local_lookup_zs_batch: &[&[F]],
+    next_lookup_zs_batch: &[&[F]],
     partial_products_batch: &[&[F]],
     s_sigmas_batch: &[&[F]],
     betas: &[F],
     gammas: &[F],
+    deltas: &[F],
     alphas: &[F],
     z_h_on_coset: &ZeroPolyOnCoset<F>,
+    lut_re_poly_evals: &[&[F]],
 ) -> Vec<Vec<F>> {
+    let has_lookup = common_data.num_lookup_polys != 0;
+
     let n = indices_batch.len();
     assert_eq!(xs_batch.len(), n);
     assert_eq!(vars_batch.len(), n);
     assert_eq!(local_zs_batch.len(), n);
     assert_eq!(next_zs_batch.len(), n);
+    if has_lookup {
+        assert_eq!(local_lookup_zs_batch.len(), n);
+        assert_eq!(next_lookup_zs_batch.len(), n);
+    } else {
+        assert_eq!(local_lookup_zs_batch.len(), 0);
+        assert_eq!(next_lookup_zs_batch.len(), 0);
+    }
     assert_eq!(partial_products_batch.len(), n);
     assert_eq!(s_sigmas_batch.len(), n);
 
@@ -161,13 +221,39 @@ pub(crate) fn eval_vanishing_poly_base_batch<
     // The terms checking the partial products.
     let mut vanishing_partial_products_terms = Vec::new();
 
+    // The terms checking the lookup constraints.
+    let mut vanishing_all_lookup_terms = if has_lookup {
+        let num_sldc_polys = common_data.num_lookup_polys - 1;
+        Vec::with_capacity(
+            common_data.config.num_challenges * (4 + common_data.luts.len() + 2 * num_sldc_polys),
+        )
+    } else {
+        Vec::new()
+    };
+
     let mut res_batch: Vec<Vec<F>> = Vec::with_capacity(n);
     for k in 0..n {
         let index = indices_batch[k];
         let x = xs_batch[k];
         let vars = vars_batch.view(k);
+
+        let lookup_selectors: Vec<F> = (0..common_data.num_lookup_selectors)
+            .map(|i| vars.local_constants[common_data.selectors_info.num_selectors() + i])
+            .collect();
+
         let local_zs = local_zs_batch[k];
         let next_zs = next_zs_batch[k];
+        let local_lookup_zs = if has_lookup {
+            local_lookup_zs_batch[k]
+        } else {
+            &[]
+        };
+        let next_lookup_zs = if has_lookup {
+            next_lookup_zs_batch[k]
+        } else {
+            &[]
+        };
+
         let partial_products = partial_products_batch[k];
         let s_sigmas = s_sigmas_batch[k];
 
@@ -179,6 +265,27 @@ pub(crate) fn eval_vanishing_poly_base_batch<
             let z_gx = next_zs[i];
             vanishing_z_1_terms.push(l_0_x * z_x.sub_one());
 
+            // If there are lookups in the circuit, then we add the lookup constraints.
+            if has_lookup {
+                let cur_deltas = &deltas[NUM_COINS_LOOKUP * i..NUM_COINS_LOOKUP * (i + 1)];
+
+                let cur_local_lookup_zs = &local_lookup_zs
+                    [common_data.num_lookup_polys * i..common_data.num_lookup_polys * (i + 1)];
+                let cur_next_lookup_zs = &next_lookup_zs
+                    [common_data.num_lookup_polys * i..common_data.num_lookup_polys * (i + 1)];
+
+                let lookup_constraints = check_lookup_constraints_batch(
+                    common_data,
+                    vars,
+                    cur_local_lookup_zs,
+                    cur_next_lookup_zs,
+                    &lookup_selectors,
+                    cur_deltas.try_into().unwrap(),
+                    lut_re_poly_evals[i],
+                );
+                vanishing_all_lookup_terms.extend(lookup_constraints);
+            }
+
             numerator_values.extend((0..num_routed_wires).map(|j| {
                 let wire_value = vars.local_wires[j];
                 let k_i = common_data.k_is[j];
@@ -211,12 +318,14 @@ pub(crate) fn eval_vanishing_poly_base_batch<
         let vanishing_terms = vanishing_z_1_terms
             .iter()
             .chain(vanishing_partial_products_terms.iter())
+            .chain(vanishing_all_lookup_terms.iter())
             .chain(constraint_terms);
         let res = plonk_common::reduce_with_powers_multi(vanishing_terms, alphas);
         res_batch.push(res);
 
         vanishing_z_1_terms.clear();
         vanishing_partial_products_terms.clear();
+        vanishing_all_lookup_terms.clear();
     }
     res_batch
 }
@@ -231,329 +340,329 @@ pub(crate) fn eval_vanishing_poly_base_batch<
 /// Sum and LDC are broken down in partial polynomials to lower the constraint degree, similarly to the permutation argument.
 /// They also share the same partial SLDC polynomials, so that the last SLDC value is Sum(end) - LDC(end). The final constraint
 /// Sum(end) = LDC(end) becomes simply SLDC(end) = 0, and we can remove the LDC initial constraint.
-// pub fn check_lookup_constraints<F: RichField + Extendable<D>, const D: usize>(
-//     common_data: &CommonCircuitData<F, D>,
-//     vars: EvaluationVars<F, D>,
-//     local_lookup_zs: &[F::Extension],
-//     next_lookup_zs: &[F::Extension],
-//     lookup_selectors: &[F::Extension],
-//     deltas: &[F; 4],
-// ) -> Vec<F::Extension> {
-//     let num_lu_slots = LookupGate::num_slots(&common_data.config);
-//     let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
-//     let lu_degree = common_data.quotient_degree_factor - 1;
-//     let num_sldc_polys = local_lookup_zs.len() - 1;
-//     let lut_degree = ceil_div_usize(num_lut_slots, num_sldc_polys);
-
-//     let mut constraints = Vec::with_capacity(4 + common_data.luts.len() + 2 * num_sldc_polys);
-
-//     // RE is the first polynomial stored.
-//     let z_re = local_lookup_zs[0];
-//     let next_z_re = next_lookup_zs[0];
-
-//     // Partial Sums and LDCs are both stored in the remaining SLDC polynomials.
-//     let z_x_lookup_sldcs = &local_lookup_zs[1..num_sldc_polys + 1];
-//     let z_gx_lookup_sldcs = &next_lookup_zs[1..num_sldc_polys + 1];
-
-//     let delta_challenge_a = F::Extension::from(deltas[LookupChallenges::ChallengeA as usize]);
-//     let delta_challenge_b = F::Extension::from(deltas[LookupChallenges::ChallengeB as usize]);
-
-//     // Compute all current looked and looking combos, i.e. the combos we need for the SLDC polynomials.
-//     let current_looked_combos: Vec<F::Extension> = (0..num_lut_slots)
-//         .map(|s| {
-//             let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-//             let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-//             input_wire + delta_challenge_a * output_wire
-//         })
-//         .collect();
-
-//     let current_looking_combos: Vec<F::Extension> = (0..num_lu_slots)
-//         .map(|s| {
-//             let input_wire = vars.local_wires[LookupGate::wire_ith_looking_inp(s)];
-//             let output_wire = vars.local_wires[LookupGate::wire_ith_looking_out(s)];
-//             input_wire + delta_challenge_a * output_wire
-//         })
-//         .collect();
-
-//     // Compute all current lookup combos, i.e. the combos used to check that the LUT is correct.
-//     let current_lookup_combos: Vec<F::Extension> = (0..num_lut_slots)
-//         .map(|s| {
-//             let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-//             let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-//             input_wire + delta_challenge_b * output_wire
-//         })
-//         .collect();
-
-//     // Check last LDC constraint.
-//     constraints.push(
-//         lookup_selectors[LookupSelectors::LastLdc as usize] * z_x_lookup_sldcs[num_sldc_polys - 1],
-//     );
-
-//     // Check initial Sum constraint.
-//     constraints.push(lookup_selectors[LookupSelectors::InitSre as usize] * z_x_lookup_sldcs[0]);
-
-//     // Check initial RE constraint.
-//     constraints.push(lookup_selectors[LookupSelectors::InitSre as usize] * z_re);
-
-//     let current_delta = deltas[LookupChallenges::ChallengeDelta as usize];
-
-//     // Check final RE constraints for each different LUT.
-//     for r in LookupSelectors::StartEnd as usize..common_data.num_lookup_selectors {
-//         let cur_ends_selector = lookup_selectors[r];
-//         let lut_row_number = ceil_div_usize(
-//             common_data.luts[r - LookupSelectors::StartEnd as usize].len(),
-//             num_lut_slots,
-//         );
-//         let cur_function_eval = get_lut_poly(
-//             common_data,
-//             r - LookupSelectors::StartEnd as usize,
-//             deltas,
-//             num_lut_slots * lut_row_number,
-//         )
-//         .eval(current_delta);
-
-//         constraints.push(cur_ends_selector * (z_re - cur_function_eval.into()))
-//     }
-
-//     // Check RE row transition constraint.
-//     let mut cur_sum = next_z_re;
-//     for elt in &current_lookup_combos {
-//         cur_sum =
-//             cur_sum * F::Extension::from(deltas[LookupChallenges::ChallengeDelta as usize]) + *elt;
-//     }
-//     let unfiltered_re_line = z_re - cur_sum;
-
-//     constraints.push(lookup_selectors[LookupSelectors::TransSre as usize] * unfiltered_re_line);
-
-//     for poly in 0..num_sldc_polys {
-//         // Compute prod(alpha - combo) for the current slot for Sum.
-//         let lut_prod: F::Extension = (poly * lut_degree
-//             ..min((poly + 1) * lut_degree, num_lut_slots))
-//             .map(|i| {
-//                 F::Extension::from(deltas[LookupChallenges::ChallengeAlpha as usize])
-//                     - current_looked_combos[i]
-//             })
-//             .product();
-
-//         // Compute prod(alpha - combo) for the current slot for LDC.
-//         let lu_prod: F::Extension = (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
-//             .map(|i| {
-//                 F::Extension::from(deltas[LookupChallenges::ChallengeAlpha as usize])
-//                     - current_looking_combos[i]
-//             })
-//             .product();
-
-//         // Function which computes, given index i: prod_{j!=i}(alpha - combo_j) for Sum.
-//         let lut_prod_i = |i| {
-//             (poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots))
-//                 .map(|j| {
-//                     if j != i {
-//                         F::Extension::from(deltas[LookupChallenges::ChallengeAlpha as usize])
-//                             - current_looked_combos[j]
-//                     } else {
-//                         F::Extension::ONE
-//                     }
-//                 })
-//                 .product()
-//         };
-
-//         // Function which computes, given index i: prod_{j!=i}(alpha - combo_j) for LDC.
-//         let lu_prod_i = |i| {
-//             (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
-//                 .map(|j| {
-//                     if j != i {
-//                         F::Extension::from(deltas[LookupChallenges::ChallengeAlpha as usize])
-//                             - current_looking_combos[j]
-//                     } else {
-//                         F::Extension::ONE
-//                     }
-//                 })
-//                 .product()
-//         };
-//         // Compute sum_i(prod_{j!=i}(alpha - combo_j)) for LDC.
-//         let lu_sum_prods = (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
-//             .fold(F::Extension::ZERO, |acc, i| acc + lu_prod_i(i));
-
-//         // Compute sum_i(mul_i.prod_{j!=i}(alpha - combo_j)) for Sum.
-//         let lut_sum_prods_with_mul = (poly * lut_degree
-//             ..min((poly + 1) * lut_degree, num_lut_slots))
-//             .fold(F::Extension::ZERO, |acc, i| {
-//                 acc + vars.local_wires[LookupTableGate::wire_ith_multiplicity(i)] * lut_prod_i(i)
-//             });
-
-//         // The previous element is the previous poly of the current row or the last poly of the next row.
-//         let prev = if poly == 0 {
-//             z_gx_lookup_sldcs[num_sldc_polys - 1]
-//         } else {
-//             z_x_lookup_sldcs[poly - 1]
-//         };
-
-//         // Check Sum row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
-//         let unfiltered_sum_transition =
-//             lut_prod * (z_x_lookup_sldcs[poly] - prev) - lut_sum_prods_with_mul;
-//         constraints
-//             .push(lookup_selectors[LookupSelectors::TransSre as usize] * unfiltered_sum_transition);
-
-//         // Check LDC row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
-//         let unfiltered_ldc_transition = lu_prod * (z_x_lookup_sldcs[poly] - prev) + lu_sum_prods;
-//         constraints
-//             .push(lookup_selectors[LookupSelectors::TransLdc as usize] * unfiltered_ldc_transition);
-//     }
-
-//     constraints
-// }
+pub fn check_lookup_constraints<F: RichField + Extendable<D>, const D: usize>(
+    common_data: &CommonCircuitData<F, D>,
+    vars: EvaluationVars<F, D>,
+    local_lookup_zs: &[F::Extension],
+    next_lookup_zs: &[F::Extension],
+    lookup_selectors: &[F::Extension],
+    deltas: &[F; 4],
+) -> Vec<F::Extension> {
+    let num_lu_slots = LookupGate::num_slots(&common_data.config);
+    let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
+    let lu_degree = common_data.quotient_degree_factor - 1;
+    let num_sldc_polys = local_lookup_zs.len() - 1;
+    let lut_degree = ceil_div_usize(num_lut_slots, num_sldc_polys);
+
+    let mut constraints = Vec::with_capacity(4 + common_data.luts.len() + 2 * num_sldc_polys);
+
+    // RE is the first polynomial stored.
+    let z_re = local_lookup_zs[0];
+    let next_z_re = next_lookup_zs[0];
+
+    // Partial Sums and LDCs are both stored in the remaining SLDC polynomials.
+    let z_x_lookup_sldcs = &local_lookup_zs[1..num_sldc_polys + 1];
+    let z_gx_lookup_sldcs = &next_lookup_zs[1..num_sldc_polys + 1];
+
+    let delta_challenge_a = F::Extension::from(deltas[LookupChallenges::ChallengeA as usize]);
+    let delta_challenge_b = F::Extension::from(deltas[LookupChallenges::ChallengeB as usize]);
+
+    // Compute all current looked and looking combos, i.e. the combos we need for the SLDC polynomials.
+    let current_looked_combos: Vec<F::Extension> = (0..num_lut_slots)
+        .map(|s| {
+            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
+            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
+            input_wire + delta_challenge_a * output_wire
+        })
+        .collect();
+
+    let current_looking_combos: Vec<F::Extension> = (0..num_lu_slots)
+        .map(|s| {
+            let input_wire = vars.local_wires[LookupGate::wire_ith_looking_inp(s)];
+            let output_wire = vars.local_wires[LookupGate::wire_ith_looking_out(s)];
+            input_wire + delta_challenge_a * output_wire
+        })
+        .collect();
+
+    // Compute all current lookup combos, i.e. the combos used to check that the LUT is correct.
+    let current_lookup_combos: Vec<F::Extension> = (0..num_lut_slots)
+        .map(|s| {
+            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
+            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
+            input_wire + delta_challenge_b * output_wire
+        })
+        .collect();
+
+    // Check last LDC constraint.
+    constraints.push(
+        lookup_selectors[LookupSelectors::LastLdc as usize] * z_x_lookup_sldcs[num_sldc_polys - 1],
+    );
+
+    // Check initial Sum constraint.
+    constraints.push(lookup_selectors[LookupSelectors::InitSre as usize] * z_x_lookup_sldcs[0]);
+
+    // Check initial RE constraint.
+    constraints.push(lookup_selectors[LookupSelectors::InitSre as usize] * z_re);
+
+    let current_delta = deltas[LookupChallenges::ChallengeDelta as usize];
+
+    // Check final RE constraints for each different LUT.
+    for r in LookupSelectors::StartEnd as usize..common_data.num_lookup_selectors {
+        let cur_ends_selector = lookup_selectors[r];
+        let lut_row_number = ceil_div_usize(
+            common_data.luts[r - LookupSelectors::StartEnd as usize].len(),
+            num_lut_slots,
+        );
+        let cur_function_eval = get_lut_poly(
+            common_data,
+            r - LookupSelectors::StartEnd as usize,
+            deltas,
+            num_lut_slots * lut_row_number,
+        )
+        .eval(current_delta);
+
+        constraints.push(cur_ends_selector * (z_re - cur_function_eval.into()))
+    }
+
+    // Check RE row transition constraint.
+    let mut cur_sum = next_z_re;
+    for elt in &current_lookup_combos {
+        cur_sum =
+            cur_sum * F::Extension::from(deltas[LookupChallenges::ChallengeDelta as usize]) + *elt;
+    }
+    let unfiltered_re_line = z_re - cur_sum;
+
+    constraints.push(lookup_selectors[LookupSelectors::TransSre as usize] * unfiltered_re_line);
+
+    for poly in 0..num_sldc_polys {
+        // Compute prod(alpha - combo) for the current slot for Sum.
+        let lut_prod: F::Extension = (poly * lut_degree
+            ..min((poly + 1) * lut_degree, num_lut_slots))
+            .map(|i| {
+                F::Extension::from(deltas[LookupChallenges::ChallengeAlpha as usize])
+                    - current_looked_combos[i]
+            })
+            .product();
+
+        // Compute prod(alpha - combo) for the current slot for LDC.
+        let lu_prod: F::Extension = (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
+            .map(|i| {
+                F::Extension::from(deltas[LookupChallenges::ChallengeAlpha as usize])
+                    - current_looking_combos[i]
+            })
+            .product();
+
+        // Function which computes, given index i: prod_{j!=i}(alpha - combo_j) for Sum.
+        let lut_prod_i = |i| {
+            (poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots))
+                .map(|j| {
+                    if j != i {
+                        F::Extension::from(deltas[LookupChallenges::ChallengeAlpha as usize])
+                            - current_looked_combos[j]
+                    } else {
+                        F::Extension::ONE
+                    }
+                })
+                .product()
+        };
+
+        // Function which computes, given index i: prod_{j!=i}(alpha - combo_j) for LDC.
+        let lu_prod_i = |i| {
+            (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
+                .map(|j| {
+                    if j != i {
+                        F::Extension::from(deltas[LookupChallenges::ChallengeAlpha as usize])
+                            - current_looking_combos[j]
+                    } else {
+                        F::Extension::ONE
+                    }
+                })
+                .product()
+        };
+        // Compute sum_i(prod_{j!=i}(alpha - combo_j)) for LDC.
+        let lu_sum_prods = (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
+            .fold(F::Extension::ZERO, |acc, i| acc + lu_prod_i(i));
+
+        // Compute sum_i(mul_i.prod_{j!=i}(alpha - combo_j)) for Sum.
+        let lut_sum_prods_with_mul = (poly * lut_degree
+            ..min((poly + 1) * lut_degree, num_lut_slots))
+            .fold(F::Extension::ZERO, |acc, i| {
+                acc + vars.local_wires[LookupTableGate::wire_ith_multiplicity(i)] * lut_prod_i(i)
+            });
+
+        // The previous element is the previous poly of the current row or the last poly of the next row.
+        let prev = if poly == 0 {
+            z_gx_lookup_sldcs[num_sldc_polys - 1]
+        } else {
+            z_x_lookup_sldcs[poly - 1]
+        };
+
+        // Check Sum row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
+        let unfiltered_sum_transition =
+            lut_prod * (z_x_lookup_sldcs[poly] - prev) - lut_sum_prods_with_mul;
+        constraints
+            .push(lookup_selectors[LookupSelectors::TransSre as usize] * unfiltered_sum_transition);
+
+        // Check LDC row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
+        let unfiltered_ldc_transition = lu_prod * (z_x_lookup_sldcs[poly] - prev) + lu_sum_prods;
+        constraints
+            .push(lookup_selectors[LookupSelectors::TransLdc as usize] * unfiltered_ldc_transition);
+    }
+
+    constraints
+}
 
 /// Same as `check_lookup_constraints`, but for the base field case.
-// pub fn check_lookup_constraints_batch<F: RichField + Extendable<D>, const D: usize>(
-//     common_data: &CommonCircuitData<F, D>,
-//     vars: EvaluationVarsBase<F>,
-//     local_lookup_zs: &[F],
-//     next_lookup_zs: &[F],
-//     lookup_selectors: &[F],
-//     deltas: &[F; 4],
-//     lut_re_poly_evals: &[F],
-// ) -> Vec<F> {
-//     let num_lu_slots = LookupGate::num_slots(&common_data.config);
-//     let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
-//     let lu_degree = common_data.quotient_degree_factor - 1;
-//     let num_sldc_polys = local_lookup_zs.len() - 1;
-//     let lut_degree = ceil_div_usize(num_lut_slots, num_sldc_polys);
-
-//     let mut constraints = Vec::with_capacity(4 + common_data.luts.len() + 2 * num_sldc_polys);
-
-//     // RE is the first polynomial stored.
-//     let z_re = local_lookup_zs[0];
-//     let next_z_re = next_lookup_zs[0];
-
-//     // Partial Sums and LDCs are both stored in the remaining polynomials.
-//     let z_x_lookup_sldcs = &local_lookup_zs[1..num_sldc_polys + 1];
-//     let z_gx_lookup_sldcs = &next_lookup_zs[1..num_sldc_polys + 1];
-
-//     // Compute all current looked and looking combos, i.e. the combos we need for the SLDC polynomials.
-//     let current_looked_combos: Vec<F> = (0..num_lut_slots)
-//         .map(|s| {
-//             let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-//             let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-//             input_wire + deltas[LookupChallenges::ChallengeA as usize] * output_wire
-//         })
-//         .collect();
-
-//     let current_looking_combos: Vec<F> = (0..num_lu_slots)
-//         .map(|s| {
-//             let input_wire = vars.local_wires[LookupGate::wire_ith_looking_inp(s)];
-//             let output_wire = vars.local_wires[LookupGate::wire_ith_looking_out(s)];
-//             input_wire + deltas[LookupChallenges::ChallengeA as usize] * output_wire
-//         })
-//         .collect();
-
-//     // Compute all current lookup combos, i.e. the combos used to check that the LUT is correct.
-//     let current_lookup_combos: Vec<F> = (0..num_lut_slots)
-//         .map(|s| {
-//             let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-//             let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-//             input_wire + deltas[LookupChallenges::ChallengeB as usize] * output_wire
-//         })
-//         .collect();
-
-//     // Check last LDC constraint.
-//     constraints.push(
-//         lookup_selectors[LookupSelectors::LastLdc as usize] * z_x_lookup_sldcs[num_sldc_polys - 1],
-//     );
-
-//     // Check initial Sum constraint.
-//     constraints.push(lookup_selectors[LookupSelectors::InitSre as usize] * z_x_lookup_sldcs[0]);
-
-//     // Check initial RE constraint.
-//     constraints.push(lookup_selectors[LookupSelectors::InitSre as usize] * z_re);
-
-//     // Check final RE constraints for each different LUT.
-//     for r in LookupSelectors::StartEnd as usize..common_data.num_lookup_selectors {
-//         let cur_ends_selector = lookup_selectors[r];
-
-//         // Use the precomputed value for the lut poly evaluation
-//         let re_poly_eval = lut_re_poly_evals[r - LookupSelectors::StartEnd as usize];
-
-//         constraints.push(cur_ends_selector * (z_re - re_poly_eval))
-//     }
-
-//     // Check RE row transition constraint.
-//     let mut cur_sum = next_z_re;
-//     for elt in &current_lookup_combos {
-//         cur_sum = cur_sum * deltas[LookupChallenges::ChallengeDelta as usize] + *elt;
-//     }
-//     let unfiltered_re_line = z_re - cur_sum;
-
-//     constraints.push(lookup_selectors[LookupSelectors::TransSre as usize] * unfiltered_re_line);
-
-//     for poly in 0..num_sldc_polys {
-//         // Compute prod(alpha - combo) for the current slot for Sum.
-//         let lut_prod: F = (poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots))
-//             .map(|i| deltas[LookupChallenges::ChallengeAlpha as usize] - current_looked_combos[i])
-//             .product();
-
-//         // Compute prod(alpha - combo) for the current slot for LDC.
-//         let lu_prod: F = (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
-//             .map(|i| deltas[LookupChallenges::ChallengeAlpha as usize] - current_looking_combos[i])
-//             .product();
-
-//         // Function which computes, given index i: prod_{j!=i}(alpha - combo_j) for Sum.
-//         let lut_prod_i = |i| {
-//             (poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots))
-//                 .map(|j| {
-//                     if j != i {
-//                         deltas[LookupChallenges::ChallengeAlpha as usize] - current_looked_combos[j]
-//                     } else {
-//                         F::ONE
-//                     }
-//                 })
-//                 .product()
-//         };
-
-//         // Function which computes, given index i: prod_{j!=i}(alpha - combo_j) for LDC.
-//         let lu_prod_i = |i| {
-//             (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
-//                 .map(|j| {
-//                     if j != i {
-//                         deltas[LookupChallenges::ChallengeAlpha as usize]
-//                             - current_looking_combos[j]
-//                     } else {
-//                         F::ONE
-//                     }
-//                 })
-//                 .product()
-//         };
-
-//         // Compute sum_i(prod_{j!=i}(alpha - combo_j)) for LDC.
-//         let lu_sum_prods = (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
-//             .fold(F::ZERO, |acc, i| acc + lu_prod_i(i));
-
-//         // Compute sum_i(mul_i.prod_{j!=i}(alpha - combo_j)) for Sum.
-//         let lut_sum_prods_with_mul = (poly * lut_degree
-//             ..min((poly + 1) * lut_degree, num_lut_slots))
-//             .fold(F::ZERO, |acc, i| {
-//                 acc + vars.local_wires[LookupTableGate::wire_ith_multiplicity(i)] * lut_prod_i(i)
-//             });
-
-//         // The previous element is the previous poly of the current row or the last poly of the next row.
-//         let prev = if poly == 0 {
-//             z_gx_lookup_sldcs[num_sldc_polys - 1]
-//         } else {
-//             z_x_lookup_sldcs[poly - 1]
-//         };
-
-//         // Check Sum row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
-//         let unfiltered_sum_transition =
-//             lut_prod * (z_x_lookup_sldcs[poly] - prev) - lut_sum_prods_with_mul;
-//         constraints
-//             .push(lookup_selectors[LookupSelectors::TransSre as usize] * unfiltered_sum_transition);
-
-//         // Check LDC row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
-//         let unfiltered_ldc_transition = lu_prod * (z_x_lookup_sldcs[poly] - prev) + lu_sum_prods;
-//         constraints
-//             .push(lookup_selectors[LookupSelectors::TransLdc as usize] * unfiltered_ldc_transition);
-//     }
-//     constraints
-// }
+pub fn check_lookup_constraints_batch<F: RichField + Extendable<D>, const D: usize>(
+    common_data: &CommonCircuitData<F, D>,
+    vars: EvaluationVarsBase<F>,
+    local_lookup_zs: &[F],
+    next_lookup_zs: &[F],
+    lookup_selectors: &[F],
+    deltas: &[F; 4],
+    lut_re_poly_evals: &[F],
+) -> Vec<F> {
+    let num_lu_slots = LookupGate::num_slots(&common_data.config);
+    let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
+    let lu_degree = common_data.quotient_degree_factor - 1;
+    let num_sldc_polys = local_lookup_zs.len() - 1;
+    let lut_degree = ceil_div_usize(num_lut_slots, num_sldc_polys);
+
+    let mut constraints = Vec::with_capacity(4 + common_data.luts.len() + 2 * num_sldc_polys);
+
+    // RE is the first polynomial stored.
+    let z_re = local_lookup_zs[0];
+    let next_z_re = next_lookup_zs[0];
+
+    // Partial Sums and LDCs are both stored in the remaining polynomials.
+    let z_x_lookup_sldcs = &local_lookup_zs[1..num_sldc_polys + 1];
+    let z_gx_lookup_sldcs = &next_lookup_zs[1..num_sldc_polys + 1];
+
+    // Compute all current looked and looking combos, i.e. the combos we need for the SLDC polynomials.
+    let current_looked_combos: Vec<F> = (0..num_lut_slots)
+        .map(|s| {
+            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
+            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
+            input_wire + deltas[LookupChallenges::ChallengeA as usize] * output_wire
+        })
+        .collect();
+
+    let current_looking_combos: Vec<F> = (0..num_lu_slots)
+        .map(|s| {
+            let input_wire = vars.local_wires[LookupGate::wire_ith_looking_inp(s)];
+            let output_wire = vars.local_wires[LookupGate::wire_ith_looking_out(s)];
+            input_wire + deltas[LookupChallenges::ChallengeA as usize] * output_wire
+        })
+        .collect();
+
+    // Compute all current lookup combos, i.e. the combos used to check that the LUT is correct.
+    let current_lookup_combos: Vec<F> = (0..num_lut_slots)
+        .map(|s| {
+            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
+            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
+            input_wire + deltas[LookupChallenges::ChallengeB as usize] * output_wire
+        })
+        .collect();
+
+    // Check last LDC constraint.
+    constraints.push(
+        lookup_selectors[LookupSelectors::LastLdc as usize] * z_x_lookup_sldcs[num_sldc_polys - 1],
+    );
+
+    // Check initial Sum constraint.
+    constraints.push(lookup_selectors[LookupSelectors::InitSre as usize] * z_x_lookup_sldcs[0]);
+
+    // Check initial RE constraint.
+    constraints.push(lookup_selectors[LookupSelectors::InitSre as usize] * z_re);
+
+    // Check final RE constraints for each different LUT.
+    for r in LookupSelectors::StartEnd as usize..common_data.num_lookup_selectors {
+        let cur_ends_selector = lookup_selectors[r];
+
+        // Use the precomputed value for the lut poly evaluation
+        let re_poly_eval = lut_re_poly_evals[r - LookupSelectors::StartEnd as usize];
+
+        constraints.push(cur_ends_selector * (z_re - re_poly_eval))
+    }
+
+    // Check RE row transition constraint.
+    let mut cur_sum = next_z_re;
+    for elt in &current_lookup_combos {
+        cur_sum = cur_sum * deltas[LookupChallenges::ChallengeDelta as usize] + *elt;
+    }
+    let unfiltered_re_line = z_re - cur_sum;
+
+    constraints.push(lookup_selectors[LookupSelectors::TransSre as usize] * unfiltered_re_line);
+
+    for poly in 0..num_sldc_polys {
+        // Compute prod(alpha - combo) for the current slot for Sum.
+        let lut_prod: F = (poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots))
+            .map(|i| deltas[LookupChallenges::ChallengeAlpha as usize] - current_looked_combos[i])
+            .product();
+
+        // Compute prod(alpha - combo) for the current slot for LDC.
+        let lu_prod: F = (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
+            .map(|i| deltas[LookupChallenges::ChallengeAlpha as usize] - current_looking_combos[i])
+            .product();
+
+        // Function which computes, given index i: prod_{j!=i}(alpha - combo_j) for Sum.
+        let lut_prod_i = |i| {
+            (poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots))
+                .map(|j| {
+                    if j != i {
+                        deltas[LookupChallenges::ChallengeAlpha as usize] - current_looked_combos[j]
+                    } else {
+                        F::ONE
+                    }
+                })
+                .product()
+        };
+
+        // Function which computes, given index i: prod_{j!=i}(alpha - combo_j) for LDC.
+        let lu_prod_i = |i| {
+            (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
+                .map(|j| {
+                    if j != i {
+                        deltas[LookupChallenges::ChallengeAlpha as usize]
+                            - current_looking_combos[j]
+                    } else {
+                        F::ONE
+                    }
+                })
+                .product()
+        };
+
+        // Compute sum_i(prod_{j!=i}(alpha - combo_j)) for LDC.
+        let lu_sum_prods = (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots))
+            .fold(F::ZERO, |acc, i| acc + lu_prod_i(i));
+
+        // Compute sum_i(mul_i.prod_{j!=i}(alpha - combo_j)) for Sum.
+        let lut_sum_prods_with_mul = (poly * lut_degree
+            ..min((poly + 1) * lut_degree, num_lut_slots))
+            .fold(F::ZERO, |acc, i| {
+                acc + vars.local_wires[LookupTableGate::wire_ith_multiplicity(i)] * lut_prod_i(i)
+            });
+
+        // The previous element is the previous poly of the current row or the last poly of the next row.
+        let prev = if poly == 0 {
+            z_gx_lookup_sldcs[num_sldc_polys - 1]
+        } else {
+            z_x_lookup_sldcs[poly - 1]
+        };
+
+        // Check Sum row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
+        let unfiltered_sum_transition =
+            lut_prod * (z_x_lookup_sldcs[poly] - prev) - lut_sum_prods_with_mul;
+        constraints
+            .push(lookup_selectors[LookupSelectors::TransSre as usize] * unfiltered_sum_transition);
+
+        // Check LDC row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
+        let unfiltered_ldc_transition = lu_prod * (z_x_lookup_sldcs[poly] - prev) + lu_sum_prods;
+        constraints
+            .push(lookup_selectors[LookupSelectors::TransLdc as usize] * unfiltered_ldc_transition);
+    }
+    constraints
+}
 
 /// Evaluates all gate constraints.
 ///
@@ -577,6 +686,7 @@ pub fn evaluate_gate_constraints<
             selector_index,
             common_data.selectors_info.groups[selector_index].clone(),
             common_data.selectors_info.num_selectors(),
+            common_data.num_lookup_selectors,
         );
         for (i, c) in gate_constraints.into_iter().enumerate() {
             debug_assert!(
@@ -611,6 +721,7 @@ pub fn evaluate_gate_constraints_base_batch<
             selector_index,
             common_data.selectors_info.groups[selector_index].clone(),
             common_data.selectors_info.num_selectors(),
+            common_data.num_lookup_selectors,
         );
         debug_assert!(
             gate_constraints_batch.len() <= constraints_batch.len(),
@@ -647,6 +758,7 @@ pub fn evaluate_gate_constraints_circuit<
                 selector_index,
                 common_data.selectors_info.groups[selector_index].clone(),
                 common_data.selectors_info.num_selectors(),
+                common_data.num_lookup_selectors,
                 &mut all_gate_constraints,
             )
         );
@@ -654,35 +766,35 @@ pub fn evaluate_gate_constraints_circuit<
     all_gate_constraints
 }
 
-// pub(crate) fn get_lut_poly_circuit<F: RichField + Extendable<D>, const D: usize>(
-//     builder: &mut CircuitBuilder<F, D>,
-//     common_data: &CommonCircuitData<F, D>,
-//     lut_index: usize,
-//     deltas: &[Target],
-//     degree: usize,
-// ) -> Target {
-//     let b = deltas[LookupChallenges::ChallengeB as usize];
-//     let delta = deltas[LookupChallenges::ChallengeDelta as usize];
-//     let n = common_data.luts[lut_index].len();
-//     let mut coeffs: Vec<Target> = common_data.luts[lut_index]
-//         .iter()
-//         .map(|(input, output)| {
-//             let temp = builder.mul_const(F::from_canonical_u16(*output), b);
-//             builder.add_const(temp, F::from_canonical_u16(*input))
-//         })
-//         .collect();
-//     for _ in n..degree {
-//         coeffs.push(builder.zero());
-//     }
-//     coeffs.reverse();
-//     coeffs
-//         .iter()
-//         .rev()
-//         .fold(builder.constant(F::ZERO), |acc, &c| {
-//             let temp = builder.mul(acc, delta);
-//             builder.add(temp, c)
-//         })
-// }
+pub(crate) fn get_lut_poly_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    common_data: &CommonCircuitData<F, D>,
+    lut_index: usize,
+    deltas: &[Target],
+    degree: usize,
+) -> Target {
+    let b = deltas[LookupChallenges::ChallengeB as usize];
+    let delta = deltas[LookupChallenges::ChallengeDelta as usize];
+    let n = common_data.luts[lut_index].len();
+    let mut coeffs: Vec<Target> = common_data.luts[lut_index]
+        .iter()
+        .map(|(input, output)| {
+            let temp = builder.mul_const(F::from_canonical_u16(*output), b);
+            builder.add_const(temp, F::from_canonical_u16(*input))
+        })
+        .collect();
+    for _ in n..degree {
+        coeffs.push(builder.zero());
+    }
+    coeffs.reverse();
+    coeffs
+        .iter()
+        .rev()
+        .fold(builder.constant(F::ZERO), |acc, &c| {
+            let temp = builder.mul(acc, delta);
+            builder.add(temp, c)
+        })
+}
 
 /// Evaluate the vanishing polynomial at `x`. In this context, the vanishing polynomial is a random
 /// linear combination of gate constraints, plus some other terms relating to the permutation
@@ -702,12 +814,16 @@ pub(crate) fn eval_vanishing_poly_circuit<
     vars: EvaluationTargets<D>,
     local_zs: &[ExtensionTarget<D>],
     next_zs: &[ExtensionTarget<D>],
+    local_lookup_zs: &[ExtensionTarget<D>],
+    next_lookup_zs: &[ExtensionTarget<D>],
     partial_products: &[ExtensionTarget<D>],
     s_sigmas: &[ExtensionTarget<D>],
     betas: &[Target],
     gammas: &[Target],
     alphas: &[Target],
+    deltas: &[Target],
 ) -> Vec<ExtensionTarget<D>> {
+    let has_lookup = common_data.num_lookup_polys != 0;
     let max_degree = common_data.quotient_degree_factor;
     let num_prods = common_data.num_partial_products;
 
@@ -717,8 +833,22 @@ pub(crate) fn eval_vanishing_poly_circuit<
         evaluate_gate_constraints_circuit::<F, C, D>(builder, common_data, vars,)
     );
 
+    let lookup_selectors = &vars.local_constants[common_data.selectors_info.num_selectors()
+        ..common_data.selectors_info.num_selectors() + common_data.num_lookup_selectors];
+
     // The L_0(x) (Z(x) - 1) vanishing terms.
     let mut vanishing_z_1_terms = Vec::new();
+
+    // The terms checking lookup constraints.
+    let mut vanishing_all_lookup_terms = if has_lookup {
+        let num_sldc_polys = common_data.num_lookup_polys - 1;
+        Vec::with_capacity(
+            common_data.config.num_challenges * (4 + common_data.luts.len() + 2 * num_sldc_polys),
+        )
+    } else {
+        Vec::new()
+    };
+
     // The terms checking the partial products.
     let mut vanishing_partial_products_terms = Vec::new();
 
@@ -738,6 +868,27 @@ pub(crate) fn eval_vanishing_poly_circuit<
         // L_0(x) (Z(x) - 1) = 0.
         vanishing_z_1_terms.push(builder.mul_sub_extension(l_0_x, z_x, l_0_x));
 
+        // If there are lookups in the circuit, then we add the lookup constraints.
+        if has_lookup {
+            let cur_local_lookup_zs = &local_lookup_zs
+                [common_data.num_lookup_polys * i..common_data.num_lookup_polys * (i + 1)];
+            let cur_next_lookup_zs = &next_lookup_zs
+                [common_data.num_lookup_polys * i..common_data.num_lookup_polys * (i + 1)];
+
+            let cur_deltas = &deltas[NUM_COINS_LOOKUP * i..NUM_COINS_LOOKUP * (i + 1)];
+
+            let lookup_constraints = check_lookup_constraints_circuit(
+                builder,
+                common_data,
+                vars,
+                cur_local_lookup_zs,
+                cur_next_lookup_zs,
+                lookup_selectors,
+                cur_deltas,
+            );
+            vanishing_all_lookup_terms.extend(lookup_constraints);
+        }
+
         let mut numerator_values = Vec::new();
         let mut denominator_values = Vec::new();
 
@@ -774,6 +925,7 @@ pub(crate) fn eval_vanishing_poly_circuit<
     let vanishing_terms = [
         vanishing_z_1_terms,
         vanishing_partial_products_terms,
+        vanishing_all_lookup_terms,
         constraint_terms,
     ]
     .concat();
@@ -788,214 +940,214 @@ pub(crate) fn eval_vanishing_poly_circuit<
         .collect()
 }
 
-// Same as `check_lookup_constraints`, but for the recursive case.
-// pub fn check_lookup_constraints_circuit<F: RichField + Extendable<D>, const D: usize>(
-//     builder: &mut CircuitBuilder<F, D>,
-//     common_data: &CommonCircuitData<F, D>,
-//     vars: EvaluationTargets<D>,
-//     local_lookup_zs: &[ExtensionTarget<D>],
-//     next_lookup_zs: &[ExtensionTarget<D>],
-//     lookup_selectors: &[ExtensionTarget<D>],
-//     deltas: &[Target],
-// ) -> Vec<ExtensionTarget<D>> {
-//     let num_lu_slots = LookupGate::num_slots(&common_data.config);
-//     let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
-//     let lu_degree = common_data.quotient_degree_factor - 1;
-//     let num_sldc_polys = local_lookup_zs.len() - 1;
-//     let lut_degree = ceil_div_usize(num_lut_slots, num_sldc_polys);
-
-//     let mut constraints = Vec::with_capacity(4 + common_data.luts.len() + 2 * num_sldc_polys);
-
-//     // RE is the first polynomial stored.
-//     let z_re = local_lookup_zs[0];
-//     let next_z_re = next_lookup_zs[0];
-
-//     // Partial Sums and LDCs (i.e. the SLDC polynomials) are stored in the remaining polynomials.
-//     let z_x_lookup_sldcs = &local_lookup_zs[1..num_sldc_polys + 1];
-//     let z_gx_lookup_sldcs = &next_lookup_zs[1..num_sldc_polys + 1];
-
-//     // Convert deltas to ExtensionTargets.
-//     let ext_deltas = deltas
-//         .iter()
-//         .map(|d| builder.convert_to_ext(*d))
-//         .collect::<Vec<_>>();
-
-//     // Computing all current looked and looking combos, i.e. the combos we need for the SLDC polynomials.
-//     let current_looked_combos = (0..num_lut_slots)
-//         .map(|s| {
-//             let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-//             let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-//             builder.mul_add_extension(
-//                 ext_deltas[LookupChallenges::ChallengeA as usize],
-//                 output_wire,
-//                 input_wire,
-//             )
-//         })
-//         .collect::<Vec<_>>();
-//     let current_looking_combos = (0..num_lu_slots)
-//         .map(|s| {
-//             let input_wire = vars.local_wires[LookupGate::wire_ith_looking_inp(s)];
-//             let output_wire = vars.local_wires[LookupGate::wire_ith_looking_out(s)];
-//             builder.mul_add_extension(
-//                 ext_deltas[LookupChallenges::ChallengeA as usize],
-//                 output_wire,
-//                 input_wire,
-//             )
-//         })
-//         .collect::<Vec<_>>();
-
-//     let current_lut_subs = (0..num_lut_slots)
-//         .map(|s| {
-//             builder.sub_extension(
-//                 ext_deltas[LookupChallenges::ChallengeAlpha as usize],
-//                 current_looked_combos[s],
-//             )
-//         })
-//         .collect::<Vec<_>>();
-
-//     let current_lu_subs = (0..num_lu_slots)
-//         .map(|s| {
-//             builder.sub_extension(
-//                 ext_deltas[LookupChallenges::ChallengeAlpha as usize],
-//                 current_looking_combos[s],
-//             )
-//         })
-//         .collect::<Vec<_>>();
-
-//     // Computing all current lookup combos, i.e. the combos used to check that the LUT is correct.
-//     let current_lookup_combos = (0..num_lut_slots)
-//         .map(|s| {
-//             let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
-//             let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
-//             builder.mul_add_extension(
-//                 ext_deltas[LookupChallenges::ChallengeB as usize],
-//                 output_wire,
-//                 input_wire,
-//             )
-//         })
-//         .collect::<Vec<_>>();
-
-//     // Check last LDC constraint.
-//     constraints.push(builder.mul_extension(
-//         lookup_selectors[LookupSelectors::LastLdc as usize],
-//         z_x_lookup_sldcs[num_sldc_polys - 1],
-//     ));
-
-//     // Check initial Sum constraint.
-//     constraints.push(builder.mul_extension(
-//         lookup_selectors[LookupSelectors::InitSre as usize],
-//         z_x_lookup_sldcs[0],
-//     ));
-
-//     // Check initial RE constraint.
-//     constraints
-//         .push(builder.mul_extension(lookup_selectors[LookupSelectors::InitSre as usize], z_re));
-
-//     // Check final RE constraints for each different LUT.
-//     for r in LookupSelectors::StartEnd as usize..common_data.num_lookup_selectors {
-//         let cur_ends_selectors = lookup_selectors[r];
-//         let lut_row_number = ceil_div_usize(
-//             common_data.luts[r - LookupSelectors::StartEnd as usize].len(),
-//             num_lut_slots,
-//         );
-//         let cur_function_eval = get_lut_poly_circuit(
-//             builder,
-//             common_data,
-//             r - LookupSelectors::StartEnd as usize,
-//             deltas,
-//             num_lut_slots * lut_row_number,
-//         );
-//         let cur_function_eval_ext = builder.convert_to_ext(cur_function_eval);
-
-//         let cur_re = builder.sub_extension(z_re, cur_function_eval_ext);
-//         constraints.push(builder.mul_extension(cur_ends_selectors, cur_re));
-//     }
-
-//     // Check RE row transition constraint.
-//     let mut cur_sum = next_z_re;
-//     for elt in &current_lookup_combos {
-//         cur_sum = builder.mul_add_extension(
-//             cur_sum,
-//             ext_deltas[LookupChallenges::ChallengeDelta as usize],
-//             *elt,
-//         );
-//     }
-//     let unfiltered_re_line = builder.sub_extension(z_re, cur_sum);
-
-//     constraints.push(builder.mul_extension(
-//         lookup_selectors[LookupSelectors::TransSre as usize],
-//         unfiltered_re_line,
-//     ));
-
-//     for poly in 0..num_sldc_polys {
-//         // Compute prod(alpha - combo) for the current slot for Sum.
-//         let mut lut_prod = builder.one_extension();
-//         for i in poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots) {
-//             lut_prod = builder.mul_extension(lut_prod, current_lut_subs[i]);
-//         }
-
-//         // Compute prod(alpha - combo) for the current slot for LDC.
-//         let mut lu_prod = builder.one_extension();
-//         for i in poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots) {
-//             lu_prod = builder.mul_extension(lu_prod, current_lu_subs[i]);
-//         }
-
-//         let one = builder.one_extension();
-//         let zero = builder.zero_extension();
-
-//         // Compute sum_i(prod_{j!=i}(alpha - combo_j)) for LDC.
-//         let lu_sum_prods =
-//             (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots)).fold(zero, |acc, i| {
-//                 let mut prod_i = one;
-
-//                 for j in poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots) {
-//                     if j != i {
-//                         prod_i = builder.mul_extension(prod_i, current_lu_subs[j]);
-//                     }
-//                 }
-//                 builder.add_extension(acc, prod_i)
-//             });
-
-//         // Compute sum_i(mul_i.prod_{j!=i}(alpha - combo_j)) for Sum.
-//         let lut_sum_prods_mul = (poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots))
-//             .fold(zero, |acc, i| {
-//                 let mut prod_i = one;
-
-//                 for j in poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots) {
-//                     if j != i {
-//                         prod_i = builder.mul_extension(prod_i, current_lut_subs[j]);
-//                     }
-//                 }
-//                 builder.mul_add_extension(
-//                     prod_i,
-//                     vars.local_wires[LookupTableGate::wire_ith_multiplicity(i)],
-//                     acc,
-//                 )
-//             });
-
-//         // The previous element is the previous poly of the current row or the last poly of the next row.
-//         let prev = if poly == 0 {
-//             z_gx_lookup_sldcs[num_sldc_polys - 1]
-//         } else {
-//             z_x_lookup_sldcs[poly - 1]
-//         };
-
-//         let cur_sub = builder.sub_extension(z_x_lookup_sldcs[poly], prev);
-
-//         // Check sum row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
-//         let unfiltered_sum_transition =
-//             builder.mul_sub_extension(lut_prod, cur_sub, lut_sum_prods_mul);
-//         constraints.push(builder.mul_extension(
-//             lookup_selectors[LookupSelectors::TransSre as usize],
-//             unfiltered_sum_transition,
-//         ));
-
-//         // Check ldc row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
-//         let unfiltered_ldc_transition = builder.mul_add_extension(lu_prod, cur_sub, lu_sum_prods);
-//         constraints.push(builder.mul_extension(
-//             lookup_selectors[LookupSelectors::TransLdc as usize],
-//             unfiltered_ldc_transition,
-//         ));
-//     }
-//     constraints
-// }
+/// Same as `check_lookup_constraints`, but for the recursive case.
+pub fn check_lookup_constraints_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    common_data: &CommonCircuitData<F, D>,
+    vars: EvaluationTargets<D>,
+    local_lookup_zs: &[ExtensionTarget<D>],
+    next_lookup_zs: &[ExtensionTarget<D>],
+    lookup_selectors: &[ExtensionTarget<D>],
+    deltas: &[Target],
+) -> Vec<ExtensionTarget<D>> {
+    let num_lu_slots = LookupGate::num_slots(&common_data.config);
+    let num_lut_slots = LookupTableGate::num_slots(&common_data.config);
+    let lu_degree = common_data.quotient_degree_factor - 1;
+    let num_sldc_polys = local_lookup_zs.len() - 1;
+    let lut_degree = ceil_div_usize(num_lut_slots, num_sldc_polys);
+
+    let mut constraints = Vec::with_capacity(4 + common_data.luts.len() + 2 * num_sldc_polys);
+
+    // RE is the first polynomial stored.
+    let z_re = local_lookup_zs[0];
+    let next_z_re = next_lookup_zs[0];
+
+    // Partial Sums and LDCs (i.e. the SLDC polynomials) are stored in the remaining polynomials.
+    let z_x_lookup_sldcs = &local_lookup_zs[1..num_sldc_polys + 1];
+    let z_gx_lookup_sldcs = &next_lookup_zs[1..num_sldc_polys + 1];
+
+    // Convert deltas to ExtensionTargets.
+    let ext_deltas = deltas
+        .iter()
+        .map(|d| builder.convert_to_ext(*d))
+        .collect::<Vec<_>>();
+
+    // Computing all current looked and looking combos, i.e. the combos we need for the SLDC polynomials.
+    let current_looked_combos = (0..num_lut_slots)
+        .map(|s| {
+            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
+            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
+            builder.mul_add_extension(
+                ext_deltas[LookupChallenges::ChallengeA as usize],
+                output_wire,
+                input_wire,
+            )
+        })
+        .collect::<Vec<_>>();
+    let current_looking_combos = (0..num_lu_slots)
+        .map(|s| {
+            let input_wire = vars.local_wires[LookupGate::wire_ith_looking_inp(s)];
+            let output_wire = vars.local_wires[LookupGate::wire_ith_looking_out(s)];
+            builder.mul_add_extension(
+                ext_deltas[LookupChallenges::ChallengeA as usize],
+                output_wire,
+                input_wire,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let current_lut_subs = (0..num_lut_slots)
+        .map(|s| {
+            builder.sub_extension(
+                ext_deltas[LookupChallenges::ChallengeAlpha as usize],
+                current_looked_combos[s],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let current_lu_subs = (0..num_lu_slots)
+        .map(|s| {
+            builder.sub_extension(
+                ext_deltas[LookupChallenges::ChallengeAlpha as usize],
+                current_looking_combos[s],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Computing all current lookup combos, i.e. the combos used to check that the LUT is correct.
+    let current_lookup_combos = (0..num_lut_slots)
+        .map(|s| {
+            let input_wire = vars.local_wires[LookupTableGate::wire_ith_looked_inp(s)];
+            let output_wire = vars.local_wires[LookupTableGate::wire_ith_looked_out(s)];
+            builder.mul_add_extension(
+                ext_deltas[LookupChallenges::ChallengeB as usize],
+                output_wire,
+                input_wire,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Check last LDC constraint.
+    constraints.push(builder.mul_extension(
+        lookup_selectors[LookupSelectors::LastLdc as usize],
+        z_x_lookup_sldcs[num_sldc_polys - 1],
+    ));
+
+    // Check initial Sum constraint.
+    constraints.push(builder.mul_extension(
+        lookup_selectors[LookupSelectors::InitSre as usize],
+        z_x_lookup_sldcs[0],
+    ));
+
+    // Check initial RE constraint.
+    constraints
+        .push(builder.mul_extension(lookup_selectors[LookupSelectors::InitSre as usize], z_re));
+
+    // Check final RE constraints for each different LUT.
+    for r in LookupSelectors::StartEnd as usize..common_data.num_lookup_selectors {
+        let cur_ends_selectors = lookup_selectors[r];
+        let lut_row_number = ceil_div_usize(
+            common_data.luts[r - LookupSelectors::StartEnd as usize].len(),
+            num_lut_slots,
+        );
+        let cur_function_eval = get_lut_poly_circuit(
+            builder,
+            common_data,
+            r - LookupSelectors::StartEnd as usize,
+            deltas,
+            num_lut_slots * lut_row_number,
+        );
+        let cur_function_eval_ext = builder.convert_to_ext(cur_function_eval);
+
+        let cur_re = builder.sub_extension(z_re, cur_function_eval_ext);
+        constraints.push(builder.mul_extension(cur_ends_selectors, cur_re));
+    }
+
+    // Check RE row transition constraint.
+    let mut cur_sum = next_z_re;
+    for elt in &current_lookup_combos {
+        cur_sum = builder.mul_add_extension(
+            cur_sum,
+            ext_deltas[LookupChallenges::ChallengeDelta as usize],
+            *elt,
+        );
+    }
+    let unfiltered_re_line = builder.sub_extension(z_re, cur_sum);
+
+    constraints.push(builder.mul_extension(
+        lookup_selectors[LookupSelectors::TransSre as usize],
+        unfiltered_re_line,
+    ));
+
+    for poly in 0..num_sldc_polys {
+        // Compute prod(alpha - combo) for the current slot for Sum.
+        let mut lut_prod = builder.one_extension();
+        for i in poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots) {
+            lut_prod = builder.mul_extension(lut_prod, current_lut_subs[i]);
+        }
+
+        // Compute prod(alpha - combo) for the current slot for LDC.
+        let mut lu_prod = builder.one_extension();
+        for i in poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots) {
+            lu_prod = builder.mul_extension(lu_prod, current_lu_subs[i]);
+        }
+
+        let one = builder.one_extension();
+        let zero = builder.zero_extension();
+
+        // Compute sum_i(prod_{j!=i}(alpha - combo_j)) for LDC.
+        let lu_sum_prods =
+            (poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots)).fold(zero, |acc, i| {
+                let mut prod_i = one;
+
+                for j in poly * lu_degree..min((poly + 1) * lu_degree, num_lu_slots) {
+                    if j != i {
+                        prod_i = builder.mul_extension(prod_i, current_lu_subs[j]);
+                    }
+                }
+                builder.add_extension(acc, prod_i)
+            });
+
+        // Compute sum_i(mul_i.prod_{j!=i}(alpha - combo_j)) for Sum.
+        let lut_sum_prods_mul = (poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots))
+            .fold(zero, |acc, i| {
+                let mut prod_i = one;
+
+                for j in poly * lut_degree..min((poly + 1) * lut_degree, num_lut_slots) {
+                    if j != i {
+                        prod_i = builder.mul_extension(prod_i, current_lut_subs[j]);
+                    }
+                }
+                builder.mul_add_extension(
+                    prod_i,
+                    vars.local_wires[LookupTableGate::wire_ith_multiplicity(i)],
+                    acc,
+                )
+            });
+
+        // The previous element is the previous poly of the current row or the last poly of the next row.
+        let prev = if poly == 0 {
+            z_gx_lookup_sldcs[num_sldc_polys - 1]
+        } else {
+            z_x_lookup_sldcs[poly - 1]
+        };
+
+        let cur_sub = builder.sub_extension(z_x_lookup_sldcs[poly], prev);
+
+        // Check sum row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
+        let unfiltered_sum_transition =
+            builder.mul_sub_extension(lut_prod, cur_sub, lut_sum_prods_mul);
+        constraints.push(builder.mul_extension(
+            lookup_selectors[LookupSelectors::TransSre as usize],
+            unfiltered_sum_transition,
+        ));
+
+        // Check ldc row and col transitions. It's the same constraint, with a row transition happening for slot == 0.
+        let unfiltered_ldc_transition = builder.mul_add_extension(lu_prod, cur_sub, lu_sum_prods);
+        constraints.push(builder.mul_extension(
+            lookup_selectors[LookupSelectors::TransLdc as usize],
+            unfiltered_ldc_transition,
+        ));
+    }
+    constraints
+}
@@ -1,6 +1,6 @@
 //! plonky2 verifier implementation.
 
-use anyhow::{ensure, Result};
+use core::fmt;
 
 use crate::field::extension::Extendable;
 use crate::field::types::Field;
@@ -9,16 +9,108 @@ use crate::hash::hash_types::RichField;
 use crate::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
 use crate::plonk::config::{GenericConfig, Hasher};
 use crate::plonk::plonk_common::reduce_with_powers;
-use crate::plonk::proof::{Proof, ProofChallenges, ProofWithPublicInputs};
+use crate::plonk::proof::{OpeningSet, Proof, ProofChallenges, ProofWithPublicInputs};
 use crate::plonk::validate_shape::validate_proof_with_pis_shape;
 use crate::plonk::vanishing_poly::eval_vanishing_poly;
 use crate::plonk::vars::EvaluationVars;
+use crate::util::serialization::IoError;
+
+/// The reason a proof was rejected by the verifier.
+///
+/// A proof whose public inputs (and hence public inputs hash) differ from the ones it was
+/// generated with fails with [`VerificationError::QuotientMismatch`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VerificationError {
+    /// Some part of the proof doesn't have the shape expected by the circuit; the string names it.
+    MalformedProof(&'static str),
+    /// The proof has a different number of public inputs than the circuit.
+    NumPublicInputs { expected: usize, actual: usize },
+    /// The constraint identity `vanishing(zeta) = Z_H(zeta) quotient(zeta)` doesn't hold for the
+    /// given challenge.
+    QuotientMismatch { challenge: usize },
+    /// The FRI proof-of-work witness is invalid.
+    ProofOfWork,
+    /// A Merkle path opening one of the initial oracles is invalid.
+    InitialMerklePath { query_round: usize, oracle: usize },
+    /// A Merkle path opening one of the FRI commit-phase trees is invalid.
+    CommitPhaseMerklePath {
+        query_round: usize,
+        reduction_round: usize,
+    },
+    /// An opened coset evaluation doesn't match the value folded from the previous round.
+    InconsistentEvaluation {
+        query_round: usize,
+        reduction_round: usize,
+    },
+    /// The folded evaluation doesn't match the final FRI polynomial.
+    FinalPolynomial { query_round: usize },
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MalformedProof(part) => write!(f, "Malformed proof: invalid {part}."),
+            Self::NumPublicInputs { expected, actual } => write!(
+                f,
+                "Number of public inputs doesn't match circuit data: expected {expected}, got {actual}."
+            ),
+            Self::QuotientMismatch { challenge } => write!(
+                f,
+                "Mismatch between evaluation and opening of quotient polynomial for challenge {challenge}."
+            ),
+            Self::ProofOfWork => write!(f, "Invalid proof of work witness."),
+            Self::InitialMerklePath {
+                query_round,
+                oracle,
+            } => write!(
+                f,
+                "Invalid Merkle proof for oracle {oracle} in query round {query_round}."
+            ),
+            Self::CommitPhaseMerklePath {
+                query_round,
+                reduction_round,
+            } => write!(
+                f,
+                "Invalid Merkle proof for reduction round {reduction_round} in query round {query_round}."
+            ),
+            Self::InconsistentEvaluation {
+                query_round,
+                reduction_round,
+            } => write!(
+                f,
+                "Inconsistent evaluation at reduction round {reduction_round} in query round {query_round}."
+            ),
+            Self::FinalPolynomial { query_round } => write!(
+                f,
+                "Final polynomial evaluation is invalid in query round {query_round}."
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerificationError {}
+
+impl From<IoError> for VerificationError {
+    fn from(_: IoError) -> Self {
+        Self::MalformedProof("serialized proof")
+    }
+}
+
+/// Returns `Err(VerificationError::MalformedProof(part))` unless `condition` holds.
+pub fn ensure_shape(condition: bool, part: &'static str) -> Result<(), VerificationError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(VerificationError::MalformedProof(part))
+    }
+}
 
 pub(crate) fn verify<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
     proof_with_pis: ProofWithPublicInputs<F, C, D>,
     verifier_data: &VerifierOnlyCircuitData<C, D>,
     common_data: &CommonCircuitData<F, D>,
-) -> Result<()> {
+) -> Result<(), VerificationError> {
     validate_proof_with_pis_shape(&proof_with_pis, common_data)?;
 
     let public_inputs_hash = proof_with_pis.get_public_inputs_hash();
@@ -26,7 +118,7 @@ pub(crate) fn verify<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, c
         public_inputs_hash,
         &verifier_data.circuit_digest,
         common_data,
-    )?;
+    );
 
     verify_with_challenges::<F, C, D>(
         proof_with_pis.proof,
@@ -47,18 +139,41 @@ pub(crate) fn verify_with_challenges<
     challenges: ProofChallenges<F, D>,
     verifier_data: &VerifierOnlyCircuitData<C, D>,
     common_data: &CommonCircuitData<F, D>,
-) -> Result<()> {
-    let local_constants = &proof.openings.constants;
-    let local_wires = &proof.openings.wires;
+) -> Result<(), VerificationError> {
+    verify_quotient::<F, C, D>(
+        &proof.openings,
+        public_inputs_hash,
+        &challenges,
+        common_data,
+    )?;
+    verify_opening_proof::<F, C, D>(proof, &challenges, verifier_data, common_data)
+}
+
+/// Checks the constraint identities at `zeta` against the opened values. This only needs the
+/// openings, so that a compressed proof can be checked before its query rounds are decompressed.
+pub(crate) fn verify_quotient<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    openings: &OpeningSet<F, D>,
+    public_inputs_hash: <<C as GenericConfig<D>>::InnerHasher as Hasher<F>>::Hash,
+    challenges: &ProofChallenges<F, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> Result<(), VerificationError> {
+    let local_constants = &openings.constants;
+    let local_wires = &openings.wires;
     let vars = EvaluationVars {
         local_constants,
         local_wires,
         public_inputs_hash: &public_inputs_hash,
     };
-    let local_zs = &proof.openings.plonk_zs;
-    let next_zs = &proof.openings.plonk_zs_next;
-    let s_sigmas = &proof.openings.plonk_sigmas;
-    let partial_products = &proof.openings.partial_products;
+    let local_zs = &openings.plonk_zs;
+    let next_zs = &openings.plonk_zs_next;
+    let local_lookup_zs = &openings.lookup_zs;
+    let next_lookup_zs = &openings.lookup_zs_next;
+    let s_sigmas = &openings.plonk_sigmas;
+    let partial_products = &openings.partial_products;
 
     // Evaluate the vanishing polynomial at our challenge point, zeta.
     let vanishing_polys_zeta = eval_vanishing_poly::<F, C, D>(
@@ -67,15 +182,18 @@ pub(crate) fn verify_with_challenges<
         vars,
         local_zs,
         next_zs,
+        local_lookup_zs,
+        next_lookup_zs,
         partial_products,
         s_sigmas,
         &challenges.plonk_betas,
         &challenges.plonk_gammas,
         &challenges.plonk_alphas,
+        &challenges.plonk_deltas,
     );
 
     // Check each polynomial identity, of the form `vanishing(x) = Z_H(x) quotient(x)`, at zeta.
-    let quotient_polys_zeta = &proof.openings.quotient_polys;
+    let quotient_polys_zeta = &openings.quotient_polys;
     let zeta_pow_deg = challenges
         .plonk_zeta
         .exp_power_of_2(common_data.degree_bits());
@@ -89,9 +207,25 @@ pub(crate) fn verify_with_challenges<
         .chunks(common_data.quotient_degree_factor)
         .enumerate()
     {
-        ensure!(vanishing_polys_zeta[i] == z_h_zeta * reduce_with_powers(chunk, zeta_pow_deg));
+        if vanishing_polys_zeta[i] != z_h_zeta * reduce_with_powers(chunk, zeta_pow_deg) {
+            return Err(VerificationError::QuotientMismatch { challenge: i });
+        }
     }
 
+    Ok(())
+}
+
+/// Checks the FRI proof of the openings of a proof.
+pub(crate) fn verify_opening_proof<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    proof: Proof<F, C, D>,
+    challenges: &ProofChallenges<F, D>,
+    verifier_data: &VerifierOnlyCircuitData<C, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> Result<(), VerificationError> {
     let merkle_caps = &[
         verifier_data.constants_sigmas_cap.clone(),
         proof.wires_cap,
@@ -110,3 +244,104 @@ pub(crate) fn verify_with_challenges<
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn multiplication_circuit() -> Result<(CircuitData<F, C, D>, ProofWithPublicInputs<F, C, D>)> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.mul(x, y);
+        builder.register_public_input(x);
+        builder.register_public_input(z);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand());
+        pw.set_target(y, F::rand());
+        let proof = data.prove(pw)?;
+        Ok((data, proof))
+    }
+
+    #[test]
+    fn test_verification_errors() -> Result<()> {
+        let (data, proof) = multiplication_circuit()?;
+        data.verify(proof.clone())?;
+
+        let mut wrong_public_inputs = proof.clone();
+        wrong_public_inputs.public_inputs[1] += F::ONE;
+        assert!(matches!(
+            data.verify(wrong_public_inputs),
+            Err(VerificationError::QuotientMismatch { .. })
+        ));
+
+        let mut extra_public_input = proof.clone();
+        extra_public_input.public_inputs.push(F::ZERO);
+        assert_eq!(
+            data.verify(extra_public_input),
+            Err(VerificationError::NumPublicInputs {
+                expected: 2,
+                actual: 3
+            })
+        );
+
+        let mut truncated_wires = proof.clone();
+        truncated_wires.proof.openings.wires.pop();
+        assert_eq!(
+            data.verify(truncated_wires),
+            Err(VerificationError::MalformedProof("wires"))
+        );
+
+        let mut wrong_pow_witness = proof.clone();
+        wrong_pow_witness.proof.opening_proof.pow_witness += F::ONE;
+        assert_eq!(
+            data.verify(wrong_pow_witness),
+            Err(VerificationError::ProofOfWork)
+        );
+
+        let mut wrong_leaf = proof.clone();
+        wrong_leaf.proof.opening_proof.query_round_proofs[0]
+            .initial_trees_proof
+            .evals_proofs[1]
+            .0[0] += F::ONE;
+        assert_eq!(
+            data.verify(wrong_leaf),
+            Err(VerificationError::InitialMerklePath {
+                query_round: 0,
+                oracle: 1
+            })
+        );
+
+        let mut truncated = proof.to_bytes();
+        truncated.pop();
+        assert_eq!(
+            ProofWithPublicInputs::<F, C, D>::from_bytes(truncated, &data.common).unwrap_err(),
+            VerificationError::MalformedProof("serialized proof")
+        );
+
+        let compressed = data.compress(proof)?;
+        data.verify_compressed(compressed.clone())?;
+        let mut wrong_compressed = compressed;
+        wrong_compressed.public_inputs[0] += F::ONE;
+        assert_eq!(
+            data.verify_compressed(wrong_compressed),
+            Err(VerificationError::QuotientMismatch { challenge: 0 })
+        );
+
+        Ok(())
+    }
+}
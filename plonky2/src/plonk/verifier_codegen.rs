@@ -0,0 +1,76 @@
+//! Helpers shared by the circom and Solidity verifier generators.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::field::types::PrimeField64;
+use crate::hash::hash_types::HashOut;
+use crate::hash::poseidon_bn128_ops::Fr;
+
+/// Returns the elements of `hashes`, in order.
+pub(crate) fn hash_elements(hashes: &[HashOut<GoldilocksField>]) -> Vec<u64> {
+    hashes
+        .iter()
+        .flat_map(|h| h.elements.iter().map(|x| x.to_canonical_u64()))
+        .collect()
+}
+
+/// Formats `value` as a 256-bit hexadecimal literal.
+fn fr_hex(value: &Fr) -> String {
+    let limbs = value.to_canonical_limbs();
+    format!(
+        "0x{:016x}{:016x}{:016x}{:016x}",
+        limbs[3], limbs[2], limbs[1], limbs[0]
+    )
+}
+
+/// Formats `values` as the elements of a circom array literal.
+pub(crate) fn circom_u64_array(values: &[u64]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Formats `values` as the elements of a circom array literal.
+pub(crate) fn circom_fr_array(values: &[Fr]) -> String {
+    values.iter().map(fr_hex).collect::<Vec<_>>().join(", ")
+}
+
+/// Formats `values` as the elements of a Solidity `uint64` array literal.
+pub(crate) fn solidity_u64_array(values: &[u64]) -> String {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            if i == 0 {
+                format!("uint64({v})")
+            } else {
+                v.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Formats `values` as the elements of a Solidity `uint256` array literal.
+pub(crate) fn solidity_fr_array(values: &[Fr]) -> String {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            if i == 0 {
+                format!("uint256({})", fr_hex(v))
+            } else {
+                fr_hex(v)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,332 @@
+//! Aggregation of many proofs of the same circuit into one, using a binary tree of recursive
+//! proofs.
+//!
+//! Each node of the tree is a proof of a merge circuit, which verifies the proofs of its two
+//! children. The merge circuits only depend on the level of the node, so an [`AggregationTree`]
+//! builds them once and reuses them for all the nodes of a level, and across trees.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use anyhow::{ensure, Result};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::witness::{PartialWitness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{CircuitConfig, CircuitData, VerifierCircuitData};
+use crate::plonk::config::{AlgebraicHasher, GenericConfig, Hasher};
+use crate::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+use crate::util::serialization::{
+    Buffer, GateSerializer, IoResult, Read, WitnessGeneratorSerializer, Write,
+};
+
+/// How the nodes of an [`AggregationTree`] commit to the public inputs of their children.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PublicInputPolicy {
+    /// A node exposes the public inputs of its left child followed by those of its right child,
+    /// so the root exposes the public inputs of all the leaves, in order.
+    Concatenate,
+    /// A node exposes the hash of the public inputs of its left child followed by those of its
+    /// right child, so the root exposes the root of a Merkle tree over the leaves.
+    Hash,
+}
+
+/// The merge circuit of one level of an [`AggregationTree`], which verifies two proofs of the
+/// level below.
+#[derive(Debug)]
+pub struct AggregationLevel<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    pub circuit: CircuitData<F, C, D>,
+    left: ProofWithPublicInputsTarget<D>,
+    right: ProofWithPublicInputsTarget<D>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    AggregationLevel<F, C, D>
+where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    pub fn to_bytes(
+        &self,
+        gate_serializer: &dyn GateSerializer<F, D>,
+        generator_serializer: &dyn WitnessGeneratorSerializer<F, D>,
+    ) -> IoResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        buffer.write_circuit_data(&self.circuit, gate_serializer, generator_serializer)?;
+        buffer.write_target_proof_with_public_inputs(&self.left)?;
+        buffer.write_target_proof_with_public_inputs(&self.right)?;
+        Ok(buffer)
+    }
+
+    pub fn from_bytes(
+        bytes: &[u8],
+        gate_serializer: &dyn GateSerializer<F, D>,
+        generator_serializer: &dyn WitnessGeneratorSerializer<F, D>,
+    ) -> IoResult<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let circuit = buffer.read_circuit_data(gate_serializer, generator_serializer)?;
+        let left = buffer.read_target_proof_with_public_inputs()?;
+        let right = buffer.read_target_proof_with_public_inputs()?;
+        Ok(Self {
+            circuit,
+            left,
+            right,
+        })
+    }
+
+    /// Proves that `left` and `right` are valid proofs of the level below.
+    pub fn prove(
+        &self,
+        left: &ProofWithPublicInputs<F, C, D>,
+        right: &ProofWithPublicInputs<F, C, D>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        let mut pw = PartialWitness::new();
+        pw.set_proof_with_pis_target(&self.left, left);
+        pw.set_proof_with_pis_target(&self.right, right);
+        self.circuit.prove(pw)
+    }
+}
+
+/// Aggregates proofs of one circuit into a single proof, by verifying them pairwise in a binary
+/// tree of merge circuits.
+///
+/// A level with an odd number of proofs merges its last proof with itself, so that every merge
+/// circuit verifies two proofs of the same circuit.
+#[derive(Debug)]
+pub struct AggregationTree<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+{
+    /// The circuit whose proofs are aggregated.
+    leaf: VerifierCircuitData<F, C, D>,
+    /// The configuration of the merge circuits.
+    config: CircuitConfig,
+    policy: PublicInputPolicy,
+    /// The merge circuits built so far, starting with the one verifying leaf proofs.
+    levels: Vec<AggregationLevel<F, C, D>>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    AggregationTree<F, C, D>
+where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    pub fn new(
+        leaf: VerifierCircuitData<F, C, D>,
+        config: CircuitConfig,
+        policy: PublicInputPolicy,
+    ) -> Self {
+        Self {
+            leaf,
+            config,
+            policy,
+            levels: Vec::new(),
+        }
+    }
+
+    /// Creates a tree reusing merge circuits built by another tree with the same leaf circuit,
+    /// configuration and policy, e.g. deserialized with [`AggregationLevel::from_bytes`].
+    pub fn from_levels(
+        leaf: VerifierCircuitData<F, C, D>,
+        config: CircuitConfig,
+        policy: PublicInputPolicy,
+        levels: Vec<AggregationLevel<F, C, D>>,
+    ) -> Self {
+        Self {
+            leaf,
+            config,
+            policy,
+            levels,
+        }
+    }
+
+    /// The merge circuits built so far, starting with the one verifying leaf proofs.
+    pub fn levels(&self) -> &[AggregationLevel<F, C, D>] {
+        &self.levels
+    }
+
+    /// The number of levels of merge circuits above `num_leaves` leaves.
+    pub fn num_levels(num_leaves: usize) -> usize {
+        num_leaves.next_power_of_two().trailing_zeros().max(1) as usize
+    }
+
+    /// Returns the merge circuit of the given level, building it and the levels below if needed.
+    pub fn level(&mut self, level: usize) -> &AggregationLevel<F, C, D> {
+        while self.levels.len() <= level {
+            let inner = match self.levels.last() {
+                Some(below) => below.circuit.verifier_data(),
+                None => self.leaf.clone(),
+            };
+            let merge = self.build_level(&inner);
+            self.levels.push(merge);
+        }
+        &self.levels[level]
+    }
+
+    fn build_level(&self, inner: &VerifierCircuitData<F, C, D>) -> AggregationLevel<F, C, D> {
+        let mut builder = CircuitBuilder::<F, D>::new(self.config.clone());
+        let verifier_data = builder.constant_verifier_data(&inner.verifier_only);
+        let left = builder.add_virtual_proof_with_pis::<C>(&inner.common);
+        let right = builder.add_virtual_proof_with_pis::<C>(&inner.common);
+        builder.verify_proof::<C>(&left, &verifier_data, &inner.common);
+        builder.verify_proof::<C>(&right, &verifier_data, &inner.common);
+
+        let public_inputs = [left.public_inputs.clone(), right.public_inputs.clone()].concat();
+        match self.policy {
+            PublicInputPolicy::Concatenate => builder.register_public_inputs(&public_inputs),
+            PublicInputPolicy::Hash => {
+                let hash = builder.hash_n_to_hash_no_pad::<C::InnerHasher>(public_inputs);
+                builder.register_public_inputs(&hash.elements);
+            }
+        }
+
+        AggregationLevel {
+            circuit: builder.build::<C>(),
+            left,
+            right,
+        }
+    }
+
+    /// Aggregates proofs of the leaf circuit into a proof of the merge circuit of level
+    /// `Self::num_levels(proofs.len()) - 1`.
+    pub fn prove(
+        &mut self,
+        proofs: Vec<ProofWithPublicInputs<F, C, D>>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        ensure!(!proofs.is_empty(), "No proofs to aggregate");
+        for proof in &proofs {
+            self.leaf.verify(proof.clone())?;
+        }
+
+        let mut nodes = proofs;
+        for level in 0..Self::num_levels(nodes.len()) {
+            let merge = self.level(level);
+            nodes = nodes
+                .chunks(2)
+                .map(|pair| merge.prove(&pair[0], pair.last().unwrap()))
+                .collect::<Result<_>>()?;
+        }
+        Ok(nodes.pop().unwrap())
+    }
+
+    /// The public inputs of the root proof aggregating leaf proofs with the given public inputs.
+    pub fn root_public_inputs(&self, leaf_public_inputs: &[Vec<F>]) -> Vec<F> {
+        assert!(!leaf_public_inputs.is_empty(), "No proofs to aggregate");
+        let mut nodes = leaf_public_inputs.to_vec();
+        for _ in 0..Self::num_levels(nodes.len()) {
+            nodes = nodes
+                .chunks(2)
+                .map(|pair| {
+                    let public_inputs = [pair[0].clone(), pair.last().unwrap().clone()].concat();
+                    match self.policy {
+                        PublicInputPolicy::Concatenate => public_inputs,
+                        PublicInputPolicy::Hash => C::InnerHasher::hash_no_pad(&public_inputs)
+                            .elements
+                            .to_vec(),
+                    }
+                })
+                .collect();
+        }
+        nodes.pop().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::Field;
+    use crate::iop::target::Target;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+    use crate::util::serialization::{DefaultGateSerializer, DefaultGeneratorSerializer};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type LeafProofs = (CircuitData<F, C, D>, Vec<ProofWithPublicInputs<F, C, D>>);
+
+    /// A circuit proving knowledge of the square root of its public input.
+    fn leaf_circuit() -> (CircuitData<F, C, D>, Target) {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_target();
+        let square = builder.square(x);
+        builder.register_public_input(square);
+        (builder.build::<C>(), x)
+    }
+
+    fn leaf_proofs(n: u64) -> Result<LeafProofs> {
+        let (leaf, x) = leaf_circuit();
+        let proofs = (1..=n)
+            .map(|i| {
+                let mut pw = PartialWitness::new();
+                pw.set_target(x, F::from_canonical_u64(i));
+                leaf.prove(pw)
+            })
+            .collect::<Result<_>>()?;
+        Ok((leaf, proofs))
+    }
+
+    #[test]
+    fn test_aggregation_tree_hash() -> Result<()> {
+        let (leaf, proofs) = leaf_proofs(3)?;
+        let leaf_public_inputs = proofs
+            .iter()
+            .map(|proof| proof.public_inputs.clone())
+            .collect::<Vec<_>>();
+        let config = CircuitConfig::standard_recursion_config();
+        let mut tree = AggregationTree::new(
+            leaf.verifier_data(),
+            config.clone(),
+            PublicInputPolicy::Hash,
+        );
+        let root = tree.prove(proofs.clone())?;
+        assert_eq!(AggregationTree::<F, C, D>::num_levels(3), 2);
+        assert_eq!(
+            root.public_inputs,
+            tree.root_public_inputs(&leaf_public_inputs)
+        );
+        tree.level(1).circuit.verify(root)?;
+
+        // Aggregate again with merge circuits loaded from bytes.
+        let gate_serializer = DefaultGateSerializer;
+        let generator_serializer = DefaultGeneratorSerializer::<C, D>::default();
+        let levels = tree
+            .levels()
+            .iter()
+            .map(|level| {
+                let bytes = level.to_bytes(&gate_serializer, &generator_serializer)?;
+                AggregationLevel::from_bytes(&bytes, &gate_serializer, &generator_serializer)
+            })
+            .collect::<IoResult<Vec<_>>>()
+            .unwrap();
+        let mut tree = AggregationTree::from_levels(
+            leaf.verifier_data(),
+            config,
+            PublicInputPolicy::Hash,
+            levels,
+        );
+        let root = tree.prove(proofs[..2].to_vec())?;
+        assert_eq!(
+            root.public_inputs,
+            tree.root_public_inputs(&leaf_public_inputs[..2])
+        );
+        Ok(tree.level(0).circuit.verify(root)?)
+    }
+
+    #[test]
+    fn test_aggregation_tree_concatenate() -> Result<()> {
+        let (leaf, proofs) = leaf_proofs(2)?;
+        let mut tree = AggregationTree::new(
+            leaf.verifier_data(),
+            CircuitConfig::standard_recursion_config(),
+            PublicInputPolicy::Concatenate,
+        );
+        let root = tree.prove(proofs)?;
+        assert_eq!(
+            root.public_inputs,
+            [1, 4].map(F::from_canonical_u64).to_vec()
+        );
+        Ok(tree.level(0).circuit.verify(root)?)
+    }
+}
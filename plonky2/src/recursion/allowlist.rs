@@ -0,0 +1,378 @@
+//! Recursive verification of a proof of any circuit in a committed set of circuits.
+//!
+//! The set is a Merkle tree over the verifier data of the allowed circuits, so that a circuit only
+//! depends on its root. All the allowed circuits must share the same [`CommonCircuitData`], which
+//! [`allowlist_common_data`] and [`CircuitBuilder::pad_to_common_data`] take care of for circuits
+//! of different sizes, gates and numbers of public inputs.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use anyhow::{anyhow, bail, ensure, Result};
+
+use crate::field::extension::Extendable;
+use crate::gates::noop::NoopGate;
+use crate::hash::hash_types::{HashOut, HashOutTarget, RichField};
+use crate::hash::merkle_proofs::MerkleProofTarget;
+use crate::hash::merkle_tree::MerkleTree;
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::WitnessWrite;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{
+    CommonCircuitData, VerifierCircuitTarget, VerifierOnlyCircuitData,
+};
+use crate::plonk::config::{AlgebraicHasher, GenericConfig, GenericHashOut};
+use crate::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+
+/// The leaf committing to the verifier data of a circuit, i.e. its circuit digest followed by its
+/// constants and sigmas cap, in the order of `CircuitBuilder::add_verifier_data_public_inputs`.
+fn verifier_data_leaf<C: GenericConfig<D>, const D: usize>(
+    verifier_data: &VerifierOnlyCircuitData<C, D>,
+) -> Vec<C::F> {
+    let mut leaf = verifier_data.circuit_digest.to_vec();
+    leaf.extend(verifier_data.constants_sigmas_cap.flatten());
+    leaf
+}
+
+/// A set of circuits sharing the same [`CommonCircuitData`], committed to with a Merkle tree over
+/// their verifier data.
+#[derive(Debug)]
+pub struct VerifierAllowlist<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    verifier_data: Vec<VerifierOnlyCircuitData<C, D>>,
+    tree: MerkleTree<F, C::Hasher>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    VerifierAllowlist<F, C, D>
+where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    pub fn new(verifier_data: Vec<VerifierOnlyCircuitData<C, D>>) -> Self {
+        assert!(!verifier_data.is_empty(), "The allowlist is empty");
+        let mut leaves = verifier_data
+            .iter()
+            .map(verifier_data_leaf)
+            .collect::<Vec<_>>();
+        // Pad with zero leaves, which no circuit has.
+        let leaf_len = leaves[0].len();
+        leaves.resize(leaves.len().next_power_of_two(), vec![F::ZERO; leaf_len]);
+        Self {
+            verifier_data,
+            tree: MerkleTree::new_from_2d(leaves, 0),
+        }
+    }
+
+    /// The root of the Merkle tree of allowed circuits.
+    pub fn root(&self) -> HashOut<F> {
+        self.tree.cap.0[0]
+    }
+
+    /// The height of the Merkle tree of allowed circuits.
+    pub fn height(&self) -> usize {
+        self.verifier_data
+            .len()
+            .next_power_of_two()
+            .trailing_zeros() as usize
+    }
+
+    pub fn verifier_data(&self) -> &[VerifierOnlyCircuitData<C, D>] {
+        &self.verifier_data
+    }
+
+    /// The index of a circuit in the allowlist, if it is allowed.
+    pub fn index_of(&self, verifier_data: &VerifierOnlyCircuitData<C, D>) -> Option<usize> {
+        self.verifier_data.iter().position(|vd| vd == verifier_data)
+    }
+
+    /// Sets the targets of an [`AllowlistProofTarget`] to a proof of the allowed circuit with the
+    /// given verifier data.
+    pub fn set_proof_target<W: WitnessWrite<F>>(
+        &self,
+        witness: &mut W,
+        target: &AllowlistProofTarget<D>,
+        proof_with_pis: &ProofWithPublicInputs<F, C, D>,
+        verifier_data: &VerifierOnlyCircuitData<C, D>,
+    ) -> Result<()> {
+        let index = self
+            .index_of(verifier_data)
+            .ok_or_else(|| anyhow!("The circuit is not in the allowlist"))?;
+        witness.set_proof_with_pis_target(&target.proof_with_pis, proof_with_pis);
+        witness.set_verifier_data_target(&target.verifier_data, verifier_data);
+        witness.set_target(target.index, F::from_canonical_usize(index));
+        let merkle_proof = self.tree.prove(index);
+        for (&sibling_target, &sibling) in target
+            .merkle_proof
+            .siblings
+            .iter()
+            .zip(&merkle_proof.siblings)
+        {
+            witness.set_hash_target(sibling_target, sibling);
+        }
+        Ok(())
+    }
+}
+
+/// A proof of a circuit in an allowlist, together with the verifier data of the circuit and a
+/// proof of its membership in the allowlist.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowlistProofTarget<const D: usize> {
+    pub proof_with_pis: ProofWithPublicInputsTarget<D>,
+    pub verifier_data: VerifierCircuitTarget,
+    /// The index of the circuit in the allowlist.
+    pub index: Target,
+    pub merkle_proof: MerkleProofTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Verifies a proof of any circuit in the allowlist with the given root and height. The
+    /// allowed circuits must all have `common_data` as their common data.
+    pub fn verify_proof_in_allowlist<C: GenericConfig<D, F = F>>(
+        &mut self,
+        allowlist_root: HashOutTarget,
+        allowlist_height: usize,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> AllowlistProofTarget<D>
+    where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let proof_with_pis = self.add_virtual_proof_with_pis::<C>(common_data);
+        let verifier_data =
+            self.add_virtual_verifier_data(common_data.config.fri_config.cap_height);
+        self.verify_proof::<C>(&proof_with_pis, &verifier_data, common_data);
+
+        let index = self.add_virtual_target();
+        let index_bits: Vec<BoolTarget> = self.split_le(index, allowlist_height);
+        let merkle_proof = MerkleProofTarget {
+            siblings: self.add_virtual_hashes(allowlist_height),
+        };
+        let mut leaf = verifier_data.circuit_digest.elements.to_vec();
+        leaf.extend(
+            verifier_data
+                .constants_sigmas_cap
+                .0
+                .iter()
+                .flat_map(|h| h.elements),
+        );
+        self.verify_merkle_proof::<C::Hasher>(leaf, &index_bits, allowlist_root, &merkle_proof);
+
+        AllowlistProofTarget {
+            proof_with_pis,
+            verifier_data,
+            index,
+            merkle_proof,
+        }
+    }
+
+    /// Pads the circuit being built so that its common data is `common_data`, provided that it
+    /// uses a subset of its gates, no more public inputs and no more rows, and the same lookups.
+    /// Missing public inputs are registered as zeros.
+    ///
+    /// `common_data` becomes the goal common data of the circuit, so building it fails if its
+    /// common data still differs, e.g. because it has too many rows or gates were added after
+    /// padding.
+    pub fn pad_to_common_data(&mut self, common_data: &CommonCircuitData<F, D>) -> Result<()> {
+        ensure!(
+            self.config == common_data.config,
+            "The circuit configuration does not match"
+        );
+        ensure!(
+            self.num_public_inputs() <= common_data.num_public_inputs,
+            "The circuit has {} public inputs, more than {}",
+            self.num_public_inputs(),
+            common_data.num_public_inputs
+        );
+        if let Some(gate) = self
+            .gates
+            .iter()
+            .find(|gate| !common_data.gates.contains(gate))
+        {
+            bail!(
+                "The circuit uses {}, which the common data lacks",
+                gate.0.id()
+            );
+        }
+        ensure!(
+            self.goal_common_data
+                .as_ref()
+                .map_or(true, |goal| goal == common_data),
+            "The circuit already has different goal common data"
+        );
+        self.goal_common_data = Some(common_data.clone());
+        for gate in &common_data.gates {
+            self.add_gate_to_gate_set(gate.clone());
+        }
+        let zero = self.zero();
+        while self.num_public_inputs() < common_data.num_public_inputs {
+            self.register_public_input(zero);
+        }
+        self.pad_to_degree_bits(common_data.degree_bits());
+        Ok(())
+    }
+
+    /// Adds `NoopGate`s so that the circuit is padded to at least `1 << degree_bits` rows.
+    fn pad_to_degree_bits(&mut self, degree_bits: usize) {
+        while self.num_gates() <= (1 << degree_bits) / 2 {
+            self.add_gate(NoopGate, vec![]);
+        }
+    }
+}
+
+/// Returns the smallest common data which all the circuits with the given common data can be
+/// padded to with [`CircuitBuilder::pad_to_common_data`].
+pub fn allowlist_common_data<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    common_data: &[&CommonCircuitData<F, D>],
+) -> Result<CommonCircuitData<F, D>> {
+    let first = common_data
+        .first()
+        .ok_or_else(|| anyhow!("No circuits to allow"))?;
+    ensure!(
+        common_data.iter().all(|c| c.config == first.config),
+        "The circuits have different configurations"
+    );
+    let mut builder = CircuitBuilder::<F, D>::new(first.config.clone());
+    for gate in common_data.iter().flat_map(|c| &c.gates) {
+        builder.add_gate_to_gate_set(gate.clone());
+    }
+    let num_public_inputs = common_data.iter().map(|c| c.num_public_inputs).max();
+    for _ in 0..num_public_inputs.unwrap_or_default() {
+        builder.add_virtual_public_input();
+    }
+    let degree_bits = common_data.iter().map(|c| c.degree_bits()).max();
+    builder.pad_to_degree_bits(degree_bits.unwrap_or_default());
+    Ok(builder.build::<C>().common)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::Field;
+    use crate::hash::poseidon::PoseidonHash;
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// Proves knowledge of a factorization of its public input.
+    fn factors_circuit(builder: &mut CircuitBuilder<F, D>) -> Vec<Target> {
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let product = builder.mul(x, y);
+        builder.register_public_input(product);
+        vec![x, y]
+    }
+
+    /// Proves knowledge of a 16-bit preimage of its public hash.
+    fn preimage_circuit(builder: &mut CircuitBuilder<F, D>) -> Vec<Target> {
+        let x = builder.add_virtual_target();
+        builder.range_check(x, 16);
+        let hash = builder.hash_n_to_hash_no_pad::<PoseidonHash>(vec![x]);
+        builder.register_public_inputs(&hash.elements);
+        vec![x]
+    }
+
+    fn build_padded(
+        circuit: fn(&mut CircuitBuilder<F, D>) -> Vec<Target>,
+        common_data: Option<&CommonCircuitData<F, D>>,
+    ) -> Result<(CircuitData<F, C, D>, Vec<Target>)> {
+        let mut builder = CircuitBuilder::new(CircuitConfig::standard_recursion_config());
+        let inputs = circuit(&mut builder);
+        if let Some(common_data) = common_data {
+            builder.pad_to_common_data(common_data)?;
+        }
+        Ok((builder.build::<C>(), inputs))
+    }
+
+    #[test]
+    fn test_verify_proof_in_allowlist() -> Result<()> {
+        let circuits = [factors_circuit, preimage_circuit];
+        let natural_common_data = circuits
+            .iter()
+            .map(|&circuit| Ok(build_padded(circuit, None)?.0.common))
+            .collect::<Result<Vec<_>>>()?;
+        assert_ne!(natural_common_data[0], natural_common_data[1]);
+        let common_data =
+            allowlist_common_data::<F, C, D>(&natural_common_data.iter().collect::<Vec<_>>())?;
+
+        let mut apps = Vec::new();
+        for circuit in circuits {
+            let (data, inputs) = build_padded(circuit, Some(&common_data))?;
+            assert_eq!(data.common, common_data);
+            apps.push((data, inputs));
+        }
+        let allowlist = VerifierAllowlist::<F, C, D>::new(
+            apps.iter()
+                .map(|(data, _)| data.verifier_only.clone())
+                .collect(),
+        );
+
+        let mut builder = CircuitBuilder::new(CircuitConfig::standard_recursion_config());
+        let root = builder.constant_hash(allowlist.root());
+        let target = builder.verify_proof_in_allowlist::<C>(root, allowlist.height(), &common_data);
+        builder.register_public_inputs(&target.proof_with_pis.public_inputs);
+        let data = builder.build::<C>();
+
+        let witnesses = [
+            [3, 5].map(F::from_canonical_u64).to_vec(),
+            vec![F::from_canonical_u64(1234)],
+        ];
+        for ((app, inputs), values) in apps.iter().zip(witnesses) {
+            let mut pw = PartialWitness::new();
+            pw.set_target_arr(inputs, &values);
+            let app_proof = app.prove(pw)?;
+
+            let mut pw = PartialWitness::new();
+            allowlist.set_proof_target(&mut pw, &target, &app_proof, &app.verifier_only)?;
+            let proof = data.prove(pw)?;
+            assert_eq!(proof.public_inputs, app_proof.public_inputs);
+            data.verify(proof)?;
+        }
+
+        // A circuit with the same common data, but not in the allowlist.
+        let (other, inputs) = build_padded(
+            |builder| {
+                let x = builder.add_virtual_target();
+                let square = builder.square(x);
+                builder.register_public_input(square);
+                vec![x]
+            },
+            Some(&common_data),
+        )?;
+        let mut pw = PartialWitness::new();
+        pw.set_target(inputs[0], F::TWO);
+        let other_proof = other.prove(pw)?;
+        let mut pw = PartialWitness::new();
+        assert!(allowlist
+            .set_proof_target(&mut pw, &target, &other_proof, &other.verifier_only)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_pad_to_common_data_mismatch() -> Result<()> {
+        let (factors, _) = build_padded(factors_circuit, None)?;
+
+        // The preimage circuit uses Poseidon gates, which the factors circuit lacks.
+        assert!(build_padded(preimage_circuit, Some(&factors.common)).is_err());
+
+        // Gates added after padding are only caught when building.
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        factors_circuit(&mut builder);
+        builder.pad_to_common_data(&factors.common)?;
+        let x = builder.add_virtual_target();
+        builder.hash_n_to_hash_no_pad::<PoseidonHash>(vec![x]);
+        let (_, success) = builder.try_build_with_options::<C>(true);
+        assert!(!success);
+        Ok(())
+    }
+}
@@ -192,8 +192,8 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             wires: self.select_vec_ext(b, &os0.wires, &os1.wires),
             plonk_zs: self.select_vec_ext(b, &os0.plonk_zs, &os1.plonk_zs),
             plonk_zs_next: self.select_vec_ext(b, &os0.plonk_zs_next, &os1.plonk_zs_next),
-            // lookup_zs: self.select_vec_ext(b, &os0.lookup_zs, &os1.lookup_zs),
-            // next_lookup_zs: self.select_vec_ext(b, &os0.next_lookup_zs, &os1.next_lookup_zs),
+            lookup_zs: self.select_vec_ext(b, &os0.lookup_zs, &os1.lookup_zs),
+            next_lookup_zs: self.select_vec_ext(b, &os0.next_lookup_zs, &os1.next_lookup_zs),
             partial_products: self.select_vec_ext(b, &os0.partial_products, &os1.partial_products),
             quotient_polys: self.select_vec_ext(b, &os0.quotient_polys, &os1.quotient_polys),
         }
@@ -403,7 +403,7 @@ mod tests {
         builder.print_gate_counts(100);
         let data = builder.build::<C>();
         let proof = data.prove(pw)?;
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     fn init_logger() {
@@ -0,0 +1,369 @@
+//! Incrementally verifiable computation (IVC) on top of cyclic recursion.
+//!
+//! A computation is described by a [`StepCircuit`], which maps a state to the next one. An [`Ivc`]
+//! builds a cyclic circuit which applies the step to the state proven by a previous proof of the
+//! same circuit, so that after `n` steps a single proof attests to the whole computation.
+//!
+//! The public inputs of the cyclic circuit are
+//! - the initial state (`state_len`),
+//! - the current state, i.e. the state after the last step (`state_len`),
+//! - the number of steps applied so far (1),
+//! - the verifier data of the cyclic circuit.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use anyhow::{bail, ensure, Result};
+
+use crate::field::extension::Extendable;
+use crate::gates::noop::NoopGate;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartialWitness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{
+    CircuitConfig, CircuitData, CommonCircuitData, VerifierCircuitTarget,
+};
+use crate::plonk::config::{AlgebraicHasher, GenericConfig};
+use crate::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+use crate::recursion::cyclic_recursion::check_cyclic_proof_verifier_data;
+use crate::recursion::dummy_circuit::cyclic_base_proof;
+
+/// The number of times [`Ivc::new`] rebuilds the cyclic circuit while searching for common data
+/// matching the circuit it describes.
+const MAX_BUILD_ATTEMPTS: usize = 4;
+
+/// One step of an incrementally verifiable computation.
+pub trait StepCircuit<F: RichField + Extendable<D>, const D: usize> {
+    /// The targets of the private inputs of a step, which are set by
+    /// [`set_step_witness`](StepCircuit::set_step_witness).
+    type Targets;
+    /// The private inputs of a step.
+    type Input;
+
+    /// The number of field elements of the state.
+    fn state_len(&self) -> usize;
+
+    /// Adds the logic of a step, returning the next state, of length `state_len`, and the targets
+    /// of the private inputs of the step.
+    fn build_step(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        state_in: &[Target],
+    ) -> (Vec<Target>, Self::Targets);
+
+    /// Sets the private inputs of a step.
+    fn set_step_witness(
+        &self,
+        pw: &mut PartialWitness<F>,
+        targets: &Self::Targets,
+        input: &Self::Input,
+    );
+}
+
+/// The statement proven by an [`Ivc`] proof.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IvcOutput<F> {
+    /// The number of steps applied to the initial state.
+    pub num_steps: u64,
+    pub initial_state: Vec<F>,
+    /// The state after the last step.
+    pub state: Vec<F>,
+}
+
+/// Proves an incrementally verifiable computation, by applying a [`StepCircuit`] repeatedly with
+/// cyclic recursion.
+#[derive(Debug)]
+pub struct Ivc<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: StepCircuit<F, D>,
+    const D: usize,
+> {
+    step: S,
+    pub circuit: CircuitData<F, C, D>,
+    step_targets: S::Targets,
+    condition: BoolTarget,
+    inner_proof: ProofWithPublicInputsTarget<D>,
+    verifier_data: VerifierCircuitTarget,
+}
+
+impl<
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F> + 'static,
+        S: StepCircuit<F, D>,
+        const D: usize,
+    > Ivc<F, C, S, D>
+where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    /// Builds the cyclic circuit of `step`.
+    ///
+    /// The circuit verifies proofs of itself, so it is built against the common data it is
+    /// expected to have, starting from that of a small recursive circuit. If the step makes the
+    /// circuit larger, or uses other gates, the circuit is rebuilt against the common data it
+    /// actually had, until both match.
+    pub fn new(mut step: S, config: CircuitConfig) -> Result<Self> {
+        ensure!(
+            !config.zero_knowledge,
+            "Cyclic recursion does not support zero-knowledge configurations"
+        );
+        let mut common_data = common_data_for_recursion::<F, C, D>(&config);
+        for _ in 0..MAX_BUILD_ATTEMPTS {
+            let (ivc, success) = Self::build(step, &config, &mut common_data)?;
+            if success {
+                return Ok(ivc);
+            }
+            common_data = ivc.circuit.common;
+            step = ivc.step;
+        }
+        bail!("The cyclic circuit does not match the common data it verifies")
+    }
+
+    /// Builds the cyclic circuit against `common_data`, returning whether the circuit matches it.
+    fn build(
+        step: S,
+        config: &CircuitConfig,
+        common_data: &mut CommonCircuitData<F, D>,
+    ) -> Result<(Self, bool)> {
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let one = builder.one();
+        let state_len = step.state_len();
+
+        let initial_state = builder.add_virtual_targets(state_len);
+        builder.register_public_inputs(&initial_state);
+        let state_in = builder.add_virtual_targets(state_len);
+        let (state_out, step_targets) = step.build_step(&mut builder, &state_in);
+        ensure!(
+            state_out.len() == state_len,
+            "The step returned a state of length {}, expected {}",
+            state_out.len(),
+            state_len
+        );
+        builder.register_public_inputs(&state_out);
+        let counter = builder.add_virtual_public_input();
+
+        let verifier_data = builder.add_verifier_data_public_inputs();
+        common_data.num_public_inputs = builder.num_public_inputs();
+
+        let condition = builder.add_virtual_bool_target_safe();
+        let inner_proof = builder.add_virtual_proof_with_pis::<C>(common_data);
+        let inner_pis = &inner_proof.public_inputs;
+
+        // The initial state is carried over from the inner proof. In the base case, it is taken
+        // from the public inputs of the dummy proof.
+        for (&initial, &inner_initial) in initial_state.iter().zip(&inner_pis[..state_len]) {
+            builder.connect(initial, inner_initial);
+        }
+        // The step is applied to the state of the inner proof, or to the initial state in the base
+        // case.
+        for ((&input, &inner_state), &initial) in state_in
+            .iter()
+            .zip(&inner_pis[state_len..2 * state_len])
+            .zip(&initial_state)
+        {
+            let actual_input = builder.select(condition, inner_state, initial);
+            builder.connect(input, actual_input);
+        }
+        let new_counter = builder.mul_add(condition.target, inner_pis[2 * state_len], one);
+        builder.connect(counter, new_counter);
+
+        builder.conditionally_verify_cyclic_proof_or_dummy::<C>(
+            condition,
+            &inner_proof,
+            common_data,
+        )?;
+
+        let (circuit, success) = builder.try_build_with_options::<C>(true);
+        Ok((
+            Self {
+                step,
+                circuit,
+                step_targets,
+                condition,
+                inner_proof,
+                verifier_data,
+            },
+            success,
+        ))
+    }
+
+    pub fn step_circuit(&self) -> &S {
+        &self.step
+    }
+
+    /// Proves the first step of the computation, starting from `initial_state`.
+    pub fn prove_first_step(
+        &self,
+        initial_state: &[F],
+        input: &S::Input,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        ensure!(
+            initial_state.len() == self.step.state_len(),
+            "The initial state has length {}, expected {}",
+            initial_state.len(),
+            self.step.state_len()
+        );
+        let base_proof = cyclic_base_proof(
+            &self.circuit.common,
+            &self.circuit.verifier_only,
+            initial_state.iter().copied().enumerate().collect(),
+        );
+        self.prove(false, &base_proof, input)
+    }
+
+    /// Proves the next step of the computation proven by `previous`.
+    pub fn prove_step(
+        &self,
+        previous: &ProofWithPublicInputs<F, C, D>,
+        input: &S::Input,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        self.prove(true, previous, input)
+    }
+
+    fn prove(
+        &self,
+        condition: bool,
+        inner_proof: &ProofWithPublicInputs<F, C, D>,
+        input: &S::Input,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        let mut pw = PartialWitness::new();
+        pw.set_bool_target(self.condition, condition);
+        pw.set_proof_with_pis_target(&self.inner_proof, inner_proof);
+        pw.set_verifier_data_target(&self.verifier_data, &self.circuit.verifier_only);
+        self.step
+            .set_step_witness(&mut pw, &self.step_targets, input);
+        self.circuit.prove(pw)
+    }
+
+    /// Verifies a proof of the computation, including that it was recursively proven with this
+    /// circuit, and returns the statement it proves.
+    pub fn verify(&self, proof: ProofWithPublicInputs<F, C, D>) -> Result<IvcOutput<F>> {
+        check_cyclic_proof_verifier_data(
+            &proof,
+            &self.circuit.verifier_only,
+            &self.circuit.common,
+        )?;
+        let state_len = self.step.state_len();
+        let output = IvcOutput {
+            num_steps: proof.public_inputs[2 * state_len].to_canonical_u64(),
+            initial_state: proof.public_inputs[..state_len].to_vec(),
+            state: proof.public_inputs[state_len..2 * state_len].to_vec(),
+        };
+        self.circuit.verify(proof)?;
+        Ok(output)
+    }
+}
+
+/// Returns the common data of a small circuit verifying a recursive proof, which the cyclic
+/// circuit of simple steps matches.
+fn common_data_for_recursion<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    config: &CircuitConfig,
+) -> CommonCircuitData<F, D>
+where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    let builder = CircuitBuilder::<F, D>::new(config.clone());
+    let data = builder.build::<C>();
+    let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+    let proof = builder.add_virtual_proof_with_pis::<C>(&data.common);
+    let verifier_data = builder.add_virtual_verifier_data(data.common.config.fri_config.cap_height);
+    builder.verify_proof::<C>(&proof, &verifier_data, &data.common);
+    let data = builder.build::<C>();
+
+    let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+    let proof = builder.add_virtual_proof_with_pis::<C>(&data.common);
+    let verifier_data = builder.add_virtual_verifier_data(data.common.config.fri_config.cap_height);
+    builder.verify_proof::<C>(&proof, &verifier_data, &data.common);
+    while builder.num_gates() < 1 << 12 {
+        builder.add_gate(NoopGate, vec![]);
+    }
+    builder.build::<C>().common
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::Field;
+    use crate::hash::hashing::hash_n_to_hash_no_pad;
+    use crate::hash::poseidon::{PoseidonHash, PoseidonPermutation};
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// Hashes the state together with a private input.
+    #[derive(Debug)]
+    struct HashStep;
+
+    impl StepCircuit<F, D> for HashStep {
+        type Targets = Target;
+        type Input = F;
+
+        fn state_len(&self) -> usize {
+            4
+        }
+
+        fn build_step(
+            &self,
+            builder: &mut CircuitBuilder<F, D>,
+            state_in: &[Target],
+        ) -> (Vec<Target>, Target) {
+            let input = builder.add_virtual_target();
+            let mut inputs = state_in.to_vec();
+            inputs.push(input);
+            let hash = builder.hash_n_to_hash_no_pad::<PoseidonHash>(inputs);
+            (hash.elements.to_vec(), input)
+        }
+
+        fn set_step_witness(&self, pw: &mut PartialWitness<F>, targets: &Target, input: &F) {
+            pw.set_target(*targets, *input);
+        }
+    }
+
+    fn hash_step(state: &[F], input: F) -> Vec<F> {
+        let mut inputs = state.to_vec();
+        inputs.push(input);
+        hash_n_to_hash_no_pad::<F, PoseidonPermutation<F>>(&inputs)
+            .elements
+            .to_vec()
+    }
+
+    #[test]
+    fn test_ivc() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let ivc = Ivc::<F, C, _, D>::new(HashStep, config)?;
+
+        let initial_state = [F::ZERO, F::ONE, F::TWO, F::from_canonical_u64(3)];
+        let inputs = [5, 6, 7].map(F::from_canonical_u64);
+        let mut proof = ivc.prove_first_step(&initial_state, &inputs[0])?;
+        let mut state = hash_step(&initial_state, inputs[0]);
+        assert_eq!(
+            ivc.verify(proof.clone())?,
+            IvcOutput {
+                num_steps: 1,
+                initial_state: initial_state.to_vec(),
+                state: state.clone(),
+            }
+        );
+
+        for input in &inputs[1..] {
+            proof = ivc.prove_step(&proof, input)?;
+            state = hash_step(&state, *input);
+        }
+        assert_eq!(
+            ivc.verify(proof)?,
+            IvcOutput {
+                num_steps: 3,
+                initial_state: initial_state.to_vec(),
+                state,
+            }
+        );
+        Ok(())
+    }
+}
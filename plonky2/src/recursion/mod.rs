@@ -2,9 +2,15 @@
 //!
 //! This module also provides ways to perform conditional recursive verification
 //! (between two different circuits, depending on a condition), and cyclic
-//! recursion where a circuit implements its own verification logic.
+//! recursion where a circuit implements its own verification logic, on top of which
+//! incrementally verifiable computations can be built, verification of a proof of any circuit
+//! in a committed allowlist, and aggregation of many proofs of one circuit with a binary tree of
+//! recursive proofs.
 
+pub mod aggregation;
+pub mod allowlist;
 pub mod conditional_recursive_verifier;
 pub mod cyclic_recursion;
 pub mod dummy_circuit;
+pub mod ivc;
 pub mod recursive_verifier;
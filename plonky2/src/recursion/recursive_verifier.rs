@@ -17,6 +17,9 @@ use crate::with_context;
 
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     /// Recursively verifies an inner proof.
+    ///
+    /// The inner config's hasher must be algebraic, so proofs made with `KeccakGoldilocksConfig`
+    /// can't be verified here.
     pub fn verify_proof<C: GenericConfig<D, F = F>>(
         &mut self,
         proof_with_pis: &ProofWithPublicInputsTarget<D>,
@@ -72,8 +75,8 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         };
         let local_zs = &proof.openings.plonk_zs;
         let next_zs = &proof.openings.plonk_zs_next;
-        // let local_lookup_zs = &proof.openings.lookup_zs;
-        // let next_lookup_zs = &proof.openings.next_lookup_zs;
+        let local_lookup_zs = &proof.openings.lookup_zs;
+        let next_lookup_zs = &proof.openings.next_lookup_zs;
         let s_sigmas = &proof.openings.plonk_sigmas;
         let partial_products = &proof.openings.partial_products;
 
@@ -90,14 +93,14 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
                 vars,
                 local_zs,
                 next_zs,
-                // local_lookup_zs,
-                // next_lookup_zs,
+                local_lookup_zs,
+                next_lookup_zs,
                 partial_products,
                 s_sigmas,
                 &challenges.plonk_betas,
                 &challenges.plonk_gammas,
                 &challenges.plonk_alphas,
-                // &challenges.plonk_deltas,
+                &challenges.plonk_deltas,
             )
         );
 
@@ -161,8 +164,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let num_leaves_per_oracle = &mut vec![
             common_data.num_preprocessed_polys(),
             config.num_wires + salt,
-            // NOTE: v2 change
-            common_data.num_zs_partial_products_polys() + salt, // + common_data.num_all_lookup_polys() ,
+            common_data.num_zs_partial_products_polys() + common_data.num_all_lookup_polys() + salt,
         ];
 
         if common_data.num_quotient_polys() > 0 {
@@ -193,6 +195,8 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             plonk_zs_next: self.add_virtual_extension_targets(num_challenges),
             partial_products: self.add_virtual_extension_targets(total_partial_products),
             quotient_polys: self.add_virtual_extension_targets(common_data.num_quotient_polys()),
+            lookup_zs: self.add_virtual_extension_targets(common_data.num_all_lookup_polys()),
+            next_lookup_zs: self.add_virtual_extension_targets(common_data.num_all_lookup_polys()),
         }
     }
 }
@@ -216,7 +220,9 @@ mod tests {
     use crate::gates::noop::NoopGate;
     use crate::iop::witness::{PartialWitness, WitnessWrite};
     use crate::plonk::circuit_data::{CircuitConfig, VerifierOnlyCircuitData};
-    use crate::plonk::config::{KeccakGoldilocksConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::config::{
+        KeccakGoldilocksConfig, Poseidon2GoldilocksConfig, PoseidonGoldilocksConfig,
+    };
     use crate::plonk::proof::{CompressedProofWithPublicInputs, ProofWithPublicInputs};
     use crate::plonk::prover::prove;
     use crate::util::timing::TimingTree;
@@ -239,7 +245,25 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
+    fn test_recursive_verifier_poseidon2() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type C = Poseidon2GoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+
+        let (proof, vd, common_data) = dummy_proof::<F, C, D>(&config, 4_000)?;
+        let (proof, vd, common_data) =
+            recursive_proof::<F, C, C, D>(proof, vd, common_data, &config, None, true, true)?;
+        let (proof, _vd, common_data) =
+            recursive_proof::<F, C, C, D>(proof, vd, common_data, &config, None, true, true)?;
+        assert_eq!(common_data.degree_bits(), 12);
+        assert!(proof.public_inputs.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
     fn test_recursive_verifier_one_lookup() -> Result<()> {
         init_logger();
         const D: usize = 2;
@@ -256,7 +280,6 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
     fn test_recursive_verifier_two_luts() -> Result<()> {
         init_logger();
         const D: usize = 2;
@@ -273,7 +296,6 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
     fn test_recursive_verifier_too_many_rows() -> Result<()> {
         init_logger();
         const D: usize = 2;
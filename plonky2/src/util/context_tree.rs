@@ -8,7 +8,7 @@ use alloc::{
 use log::{log, Level};
 
 /// The hierarchy of contexts, and the gate count contributed by each one. Useful for debugging.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) struct ContextTree {
     /// The name of this scope.
     name: String,
@@ -18,6 +18,11 @@ pub(crate) struct ContextTree {
     enter_gate_count: usize,
     /// The gate count when this scope was destroyed, or None if it has not yet been destroyed.
     exit_gate_count: Option<usize>,
+    /// The virtual target count when this scope was created.
+    enter_virtual_target_count: usize,
+    /// The virtual target count when this scope was destroyed, or None if it has not yet been
+    /// destroyed.
+    exit_virtual_target_count: Option<usize>,
     /// Any child contexts.
     children: Vec<ContextTree>,
 }
@@ -29,6 +34,8 @@ impl ContextTree {
             level: Level::Debug,
             enter_gate_count: 0,
             exit_gate_count: None,
+            enter_virtual_target_count: 0,
+            exit_virtual_target_count: None,
             children: vec![],
         }
     }
@@ -45,6 +52,34 @@ impl ContextTree {
         stack.join(" > ")
     }
 
+    /// A description of the stack of scopes which were open when the gate at `gate_index` was
+    /// added.
+    pub fn stack_at(&self, gate_index: usize) -> String {
+        self.stack_where(|scope| {
+            scope.enter_gate_count <= gate_index
+                && gate_index < scope.exit_gate_count.unwrap_or(usize::MAX)
+        })
+    }
+
+    /// A description of the stack of scopes which were open when the virtual target at `index`
+    /// was added.
+    pub fn stack_at_virtual_target(&self, index: usize) -> String {
+        self.stack_where(|scope| {
+            scope.enter_virtual_target_count <= index
+                && index < scope.exit_virtual_target_count.unwrap_or(usize::MAX)
+        })
+    }
+
+    fn stack_where(&self, contains: impl Fn(&Self) -> bool) -> String {
+        let mut stack = vec![self.name.clone()];
+        let mut scope = self;
+        while let Some(child) = scope.children.iter().find(|&c| contains(c)) {
+            stack.push(child.name.clone());
+            scope = child;
+        }
+        stack.join(" > ")
+    }
+
     fn open_stack_helper(&self, stack: &mut Vec<String>) {
         if self.is_open() {
             stack.push(self.name.clone());
@@ -54,7 +89,13 @@ impl ContextTree {
         }
     }
 
-    pub fn push(&mut self, ctx: &str, mut level: log::Level, current_gate_count: usize) {
+    pub fn push(
+        &mut self,
+        ctx: &str,
+        mut level: log::Level,
+        current_gate_count: usize,
+        current_virtual_target_count: usize,
+    ) {
         assert!(self.is_open());
 
         // We don't want a scope's log level to be stronger than that of its parent.
@@ -62,7 +103,7 @@ impl ContextTree {
 
         if let Some(last_child) = self.children.last_mut() {
             if last_child.is_open() {
-                last_child.push(ctx, level, current_gate_count);
+                last_child.push(ctx, level, current_gate_count, current_virtual_target_count);
                 return;
             }
         }
@@ -72,22 +113,25 @@ impl ContextTree {
             level,
             enter_gate_count: current_gate_count,
             exit_gate_count: None,
+            enter_virtual_target_count: current_virtual_target_count,
+            exit_virtual_target_count: None,
             children: vec![],
         })
     }
 
     /// Close the deepest open context from this tree.
-    pub fn pop(&mut self, current_gate_count: usize) {
+    pub fn pop(&mut self, current_gate_count: usize, current_virtual_target_count: usize) {
         assert!(self.is_open());
 
         if let Some(last_child) = self.children.last_mut() {
             if last_child.is_open() {
-                last_child.pop(current_gate_count);
+                last_child.pop(current_gate_count, current_virtual_target_count);
                 return;
             }
         }
 
         self.exit_gate_count = Some(current_gate_count);
+        self.exit_virtual_target_count = Some(current_virtual_target_count);
     }
 
     fn gate_count_delta(&self, current_gate_count: usize) -> usize {
@@ -101,6 +145,8 @@ impl ContextTree {
             level: self.level,
             enter_gate_count: self.enter_gate_count,
             exit_gate_count: self.exit_gate_count,
+            enter_virtual_target_count: self.enter_virtual_target_count,
+            exit_virtual_target_count: self.exit_virtual_target_count,
             children: self
                 .children
                 .iter()
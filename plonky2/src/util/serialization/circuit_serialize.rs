@@ -0,0 +1,261 @@
+//! Field-by-field serialization of gates and witness generators.
+//!
+//! A gate or generator whose fields all implement [`CircuitSerialize`] can derive it with
+//! `#[derive(GateSerialize)]` or `#[derive(GeneratorSerialize)]`, and then implement its
+//! `serialize` and `deserialize` methods with [`derived_serialization!`](crate::derived_serialization).
+//! Fields are encoded in declaration order, using the same encoding as the corresponding
+//! [`Write`] methods, so a derived implementation is compatible with a hand-written one that
+//! writes the same fields in the same order.
+//!
+//! ```ignore
+//! #[derive(Clone, Debug, Default, GeneratorSerialize)]
+//! struct MyGenerator {
+//!     x: Target,
+//!     bits: Vec<BoolTarget>,
+//! }
+//!
+//! impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for MyGenerator {
+//!     plonky2::derived_serialization!();
+//!     // ...
+//! }
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use super::{capacity_for, Buffer, IoError, IoResult, Read, Write};
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_data::CommonCircuitData;
+
+/// A value which can be written to and read from a buffer as part of a gate or generator.
+pub trait CircuitSerialize<F: RichField + Extendable<D>, const D: usize>: Sized {
+    fn write_to(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()>;
+
+    fn read_from(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self>;
+}
+
+/// The `usize` fields of gates and generators are sizes or indices within the circuit, so they are
+/// bounded by its number of wires or its degree, whichever is larger.
+impl<F: RichField + Extendable<D>, const D: usize> CircuitSerialize<F, D> for usize {
+    fn write_to(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(*self)
+    }
+
+    fn read_from(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        src.read_usize_at_most(common_data.config.num_wires.max(common_data.degree()))
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitSerialize<F, D> for u32 {
+    fn write_to(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_u32(*self)
+    }
+
+    fn read_from(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        src.read_u32()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitSerialize<F, D> for bool {
+    fn write_to(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_bool(*self)
+    }
+
+    fn read_from(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        src.read_bool()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitSerialize<F, D> for Target {
+    fn write_to(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target(*self)
+    }
+
+    fn read_from(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        src.read_target()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitSerialize<F, D> for BoolTarget {
+    fn write_to(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_bool(*self)
+    }
+
+    fn read_from(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        src.read_target_bool()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, const E: usize> CircuitSerialize<F, D>
+    for ExtensionTarget<E>
+{
+    fn write_to(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_ext(*self)
+    }
+
+    fn read_from(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        src.read_target_ext()
+    }
+}
+
+/// Vectors are prefixed by their length, as in [`Write::write_target_vec`].
+impl<F: RichField + Extendable<D>, const D: usize, T: CircuitSerialize<F, D>> CircuitSerialize<F, D>
+    for Vec<T>
+{
+    fn write_to(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.len())?;
+        for x in self {
+            x.write_to(dst, common_data)?;
+        }
+        Ok(())
+    }
+
+    fn read_from(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let len = src.read_usize()?;
+        let mut res = Vec::with_capacity(capacity_for(len));
+        for _ in 0..len {
+            res.push(T::read_from(src, common_data)?);
+        }
+        Ok(res)
+    }
+}
+
+/// Arrays have a fixed length, so it isn't written.
+impl<F: RichField + Extendable<D>, const D: usize, T: CircuitSerialize<F, D>, const N: usize>
+    CircuitSerialize<F, D> for [T; N]
+{
+    fn write_to(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        for x in self {
+            x.write_to(dst, common_data)?;
+        }
+        Ok(())
+    }
+
+    fn read_from(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        (0..N)
+            .map(|_| T::read_from(src, common_data))
+            .collect::<IoResult<Vec<_>>>()?
+            .try_into()
+            .map_err(|_| IoError)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, T> CircuitSerialize<F, D> for PhantomData<T> {
+    fn write_to(&self, _dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        Ok(())
+    }
+
+    fn read_from(_src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        Ok(PhantomData)
+    }
+}
+
+/// Implements the `serialize` and `deserialize` methods of a `Gate` or `SimpleGenerator` using
+/// the type's [`CircuitSerialize`] implementation, as derived by `#[derive(GateSerialize)]` or
+/// `#[derive(GeneratorSerialize)]`.
+///
+/// The field and extension degree default to `F` and `D`, and can be given explicitly for an
+/// implementation which isn't generic over them, as in `derived_serialization!(GoldilocksField, 2)`.
+#[macro_export]
+macro_rules! derived_serialization {
+    () => {
+        $crate::derived_serialization!(F, D);
+    };
+    ($field:ty, $degree:expr) => {
+        fn serialize(
+            &self,
+            dst: &mut $crate::alloc::vec::Vec<u8>,
+            common_data: &$crate::plonk::circuit_data::CommonCircuitData<$field, $degree>,
+        ) -> $crate::util::serialization::IoResult<()> {
+            $crate::util::serialization::CircuitSerialize::<$field, $degree>::write_to(
+                self,
+                dst,
+                common_data,
+            )
+        }
+
+        fn deserialize(
+            src: &mut $crate::util::serialization::Buffer,
+            common_data: &$crate::plonk::circuit_data::CommonCircuitData<$field, $degree>,
+        ) -> $crate::util::serialization::IoResult<Self> {
+            <Self as $crate::util::serialization::CircuitSerialize<$field, $degree>>::read_from(
+                src,
+                common_data,
+            )
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::util::serialization::{GeneratorSerialize, Remaining};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[derive(Debug, PartialEq, GeneratorSerialize)]
+    struct Named {
+        x: Target,
+        bits: Vec<BoolTarget>,
+        ext: [ExtensionTarget<D>; 2],
+        len: usize,
+        _phantom: PhantomData<F>,
+    }
+
+    #[derive(Debug, PartialEq, GeneratorSerialize)]
+    struct Tuple(Named, bool);
+
+    #[test]
+    fn test_derived_round_trip() -> IoResult<()> {
+        let common_data = crate::plonk::circuit_builder::CircuitBuilder::<F, D>::new(
+            CircuitConfig::standard_recursion_config(),
+        )
+        .build::<C>()
+        .common;
+
+        let named = Named {
+            x: Target::wire(3, 4),
+            bits: vec![BoolTarget::new_unsafe(Target::VirtualTarget { index: 7 })],
+            ext: [ExtensionTarget([Target::wire(0, 1), Target::wire(0, 2)]); 2],
+            len: 12,
+            _phantom: PhantomData,
+        };
+
+        // The derived encoding matches the hand-written one.
+        let mut expected = Vec::new();
+        expected.write_target(named.x)?;
+        expected.write_target_bool_vec(&named.bits)?;
+        expected.write_target_ext(named.ext[0])?;
+        expected.write_target_ext(named.ext[1])?;
+        expected.write_usize(named.len)?;
+        let mut bytes = Vec::new();
+        named.write_to(&mut bytes, &common_data)?;
+        assert_eq!(bytes, expected);
+
+        let tuple = Tuple(named, true);
+        let mut bytes = Vec::new();
+        tuple.write_to(&mut bytes, &common_data)?;
+        let mut buffer = Buffer::new(&bytes);
+        assert_eq!(Tuple::read_from(&mut buffer, &common_data)?, tuple);
+        assert_eq!(buffer.remaining(), 0);
+
+        // Truncated input is rejected.
+        let mut buffer = Buffer::new(&bytes[..bytes.len() - 1]);
+        assert!(Tuple::read_from(&mut buffer, &common_data).is_err());
+
+        // So is a size larger than the circuit.
+        let mut bytes = Vec::new();
+        bytes.write_usize(1 << 40)?;
+        let mut buffer = Buffer::new(&bytes);
+        assert!(<usize as CircuitSerialize<F, D>>::read_from(&mut buffer, &common_data).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,524 @@
+//! A versioned, self-describing envelope around the raw serialization format.
+//!
+//! The raw `to_bytes` encodings carry no information about what they contain, so a blob produced
+//! with a different [`GenericConfig`] or for another circuit only fails deep inside the reader, if
+//! at all. An envelope prefixes the raw payload with a header identifying it, and appends a
+//! checksum:
+//!
+//! | field                    | size               |
+//! |--------------------------|--------------------|
+//! | magic bytes [`MAGIC`]    | 4                  |
+//! | format version           | 2                  |
+//! | [`PayloadKind`]          | 1                  |
+//! | `C::Hasher` type         | 1                  |
+//! | `C::InnerHasher` type    | 1                  |
+//! | extension degree `D`     | 1                  |
+//! | circuit digest length    | 2                  |
+//! | circuit digest           | `C::Hasher::HASH_SIZE` |
+//! | payload length           | 8                  |
+//! | payload                  | payload length     |
+//! | checksum                 | 8                  |
+//!
+//! All integers are little-endian, and the checksum is a prefix of the Keccak-256 hash of all the
+//! bytes before it.
+//!
+//! Blobs written by older versions of the format stay readable: when the payload layout changes,
+//! [`FORMAT_VERSION`] is bumped and `migrate_payload` gains an arm converting older payloads to
+//! the current layout. Raw blobs written before envelopes existed can be migrated by reading them
+//! with the corresponding `from_bytes` and writing them back with `to_versioned_bytes`.
+
+#[cfg(not(feature = "std"))]
+use alloc::{borrow::Cow, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+use keccak_hash::keccak;
+
+use crate::plonk::config::{GenericConfig, GenericHashOut, Hasher, HasherType};
+use crate::util::serialization::{Buffer, IoResult, Remaining};
+
+/// Magic bytes at the start of every envelope.
+pub const MAGIC: [u8; 4] = *b"PLK2";
+
+/// The current version of the envelope format, written by `to_versioned_bytes`.
+pub const FORMAT_VERSION: u16 = 1;
+
+/// The oldest version of the envelope format that can still be read.
+pub const MIN_SUPPORTED_FORMAT_VERSION: u16 = 1;
+
+const CHECKSUM_LEN: usize = 8;
+
+/// The type of the value serialized in an envelope.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PayloadKind {
+    ProofWithPublicInputs = 0,
+    CompressedProofWithPublicInputs = 1,
+    VerifierOnlyCircuitData = 2,
+    VerifierCircuitData = 3,
+    CircuitData = 4,
+}
+
+impl PayloadKind {
+    const fn from_u8(x: u8) -> Option<Self> {
+        match x {
+            0 => Some(Self::ProofWithPublicInputs),
+            1 => Some(Self::CompressedProofWithPublicInputs),
+            2 => Some(Self::VerifierOnlyCircuitData),
+            3 => Some(Self::VerifierCircuitData),
+            4 => Some(Self::CircuitData),
+            _ => None,
+        }
+    }
+}
+
+const fn hasher_type_from_u8(x: u8) -> Option<HasherType> {
+    match x {
+        0 => Some(HasherType::Poseidon),
+        1 => Some(HasherType::Keccak),
+        2 => Some(HasherType::PoseidonBN128),
+        3 => Some(HasherType::Poseidon2),
+        _ => None,
+    }
+}
+
+/// The reason an envelope was rejected.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EnvelopeError {
+    /// The bytes don't start with [`MAGIC`], e.g. because they are a raw `to_bytes` blob.
+    BadMagic,
+    /// The bytes end before the header, payload or checksum is complete, or continue after it.
+    BadLength,
+    /// The checksum doesn't match the contents of the envelope.
+    BadChecksum,
+    /// The envelope was written by a format version this crate can't read.
+    UnsupportedVersion(u16),
+    /// The header contains a payload kind or hasher type this crate doesn't know.
+    UnknownTag(u8),
+    /// The envelope contains a different kind of value than requested.
+    KindMismatch {
+        expected: PayloadKind,
+        actual: PayloadKind,
+    },
+    /// The envelope was written with a config using a different hasher.
+    HasherMismatch {
+        expected: HasherType,
+        actual: HasherType,
+    },
+    /// The envelope was written with a config using a different inner hasher.
+    InnerHasherMismatch {
+        expected: HasherType,
+        actual: HasherType,
+    },
+    /// The envelope was written with a different extension degree.
+    DegreeMismatch { expected: usize, actual: usize },
+    /// The envelope belongs to a different circuit.
+    CircuitDigestMismatch,
+    /// The payload itself failed to deserialize.
+    MalformedPayload,
+}
+
+impl Display for EnvelopeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadMagic => write!(f, "Not a versioned plonky2 blob: bad magic bytes."),
+            Self::BadLength => write!(f, "Envelope length doesn't match its header."),
+            Self::BadChecksum => write!(f, "Envelope checksum mismatch."),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported format version {version}, expected {MIN_SUPPORTED_FORMAT_VERSION} to {FORMAT_VERSION}."
+            ),
+            Self::UnknownTag(tag) => write!(f, "Unknown tag {tag} in envelope header."),
+            Self::KindMismatch { expected, actual } => {
+                write!(f, "Expected a {expected:?}, got a {actual:?}.")
+            }
+            Self::HasherMismatch { expected, actual } => {
+                write!(f, "Hasher mismatch: expected {expected:?}, got {actual:?}.")
+            }
+            Self::InnerHasherMismatch { expected, actual } => write!(
+                f,
+                "Inner hasher mismatch: expected {expected:?}, got {actual:?}."
+            ),
+            Self::DegreeMismatch { expected, actual } => write!(
+                f,
+                "Extension degree mismatch: expected {expected}, got {actual}."
+            ),
+            Self::CircuitDigestMismatch => write!(f, "Circuit digest mismatch."),
+            Self::MalformedPayload => write!(f, "Malformed payload."),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EnvelopeError {}
+
+/// The header of an envelope, describing its payload.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnvelopeHeader {
+    pub version: u16,
+    pub kind: PayloadKind,
+    pub hasher: HasherType,
+    pub inner_hasher: HasherType,
+    pub degree: usize,
+    /// The bytes of the digest of the circuit the payload belongs to.
+    pub circuit_digest: Vec<u8>,
+}
+
+impl EnvelopeHeader {
+    /// Parses the header of `bytes` and checks the envelope's length and checksum, returning the
+    /// header along with the payload, migrated to the current format version.
+    pub fn parse(bytes: &[u8]) -> Result<(Self, Cow<[u8]>), EnvelopeError> {
+        let body_len = bytes
+            .len()
+            .checked_sub(CHECKSUM_LEN)
+            .ok_or(EnvelopeError::BadLength)?;
+        let (body, checksum) = bytes.split_at(body_len);
+        let mut reader = SliceReader(body);
+
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(EnvelopeError::BadMagic);
+        }
+        if checksum != &keccak(body).0[..CHECKSUM_LEN] {
+            return Err(EnvelopeError::BadChecksum);
+        }
+
+        let version = u16::from_le_bytes(reader.take_array()?);
+        if !(MIN_SUPPORTED_FORMAT_VERSION..=FORMAT_VERSION).contains(&version) {
+            return Err(EnvelopeError::UnsupportedVersion(version));
+        }
+        let kind = reader.take_tag(PayloadKind::from_u8)?;
+        let hasher = reader.take_tag(hasher_type_from_u8)?;
+        let inner_hasher = reader.take_tag(hasher_type_from_u8)?;
+        let degree = reader.take(1)?[0] as usize;
+        let digest_len = u16::from_le_bytes(reader.take_array()?) as usize;
+        let circuit_digest = reader.take(digest_len)?.to_vec();
+        let payload_len = u64::from_le_bytes(reader.take_array()?);
+        if payload_len != reader.0.len() as u64 {
+            return Err(EnvelopeError::BadLength);
+        }
+
+        let header = Self {
+            version,
+            kind,
+            hasher,
+            inner_hasher,
+            degree,
+            circuit_digest,
+        };
+        let payload = migrate_payload(&header, reader.0)?;
+        Ok((header, payload))
+    }
+}
+
+/// Converts a payload written by format version `header.version` to the current format version.
+fn migrate_payload<'a>(
+    header: &EnvelopeHeader,
+    payload: &'a [u8],
+) -> Result<Cow<'a, [u8]>, EnvelopeError> {
+    match header.version {
+        FORMAT_VERSION => Ok(Cow::Borrowed(payload)),
+        version => Err(EnvelopeError::UnsupportedVersion(version)),
+    }
+}
+
+/// Wraps the raw serialization `payload` of a value of the given kind in an envelope.
+pub(crate) fn seal<C: GenericConfig<D>, const D: usize>(
+    kind: PayloadKind,
+    circuit_digest: &<C::Hasher as Hasher<C::F>>::Hash,
+    payload: &[u8],
+) -> Vec<u8> {
+    let circuit_digest = circuit_digest.to_bytes();
+    let mut bytes = Vec::with_capacity(
+        MAGIC.len() + 8 + circuit_digest.len() + 8 + payload.len() + CHECKSUM_LEN,
+    );
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    bytes.push(kind as u8);
+    bytes.push(C::Hasher::HASHER_TYPE as u8);
+    bytes.push(C::InnerHasher::HASHER_TYPE as u8);
+    bytes.push(D as u8);
+    bytes.extend_from_slice(&(circuit_digest.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(&circuit_digest);
+    bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(payload);
+    let checksum = keccak(&bytes).0;
+    bytes.extend_from_slice(&checksum[..CHECKSUM_LEN]);
+    bytes
+}
+
+/// Opens an envelope holding a value of the given kind, checking that it was written with the
+/// config `C` and, if given, for the circuit with digest `circuit_digest`.
+///
+/// Returns the header along with the payload, migrated to the current format version.
+pub(crate) fn open<'a, C: GenericConfig<D>, const D: usize>(
+    bytes: &'a [u8],
+    kind: PayloadKind,
+    circuit_digest: Option<&<C::Hasher as Hasher<C::F>>::Hash>,
+) -> Result<(EnvelopeHeader, Cow<'a, [u8]>), EnvelopeError> {
+    let (header, payload) = EnvelopeHeader::parse(bytes)?;
+    if header.kind != kind {
+        return Err(EnvelopeError::KindMismatch {
+            expected: kind,
+            actual: header.kind,
+        });
+    }
+    if header.hasher != C::Hasher::HASHER_TYPE {
+        return Err(EnvelopeError::HasherMismatch {
+            expected: C::Hasher::HASHER_TYPE,
+            actual: header.hasher,
+        });
+    }
+    if header.inner_hasher != C::InnerHasher::HASHER_TYPE {
+        return Err(EnvelopeError::InnerHasherMismatch {
+            expected: C::InnerHasher::HASHER_TYPE,
+            actual: header.inner_hasher,
+        });
+    }
+    if header.degree != D {
+        return Err(EnvelopeError::DegreeMismatch {
+            expected: D,
+            actual: header.degree,
+        });
+    }
+    if let Some(circuit_digest) = circuit_digest {
+        check_circuit_digest::<C, D>(&header, circuit_digest)?;
+    }
+    Ok((header, payload))
+}
+
+/// Checks that the envelope with the given header belongs to the circuit with digest
+/// `circuit_digest`.
+pub(crate) fn check_circuit_digest<C: GenericConfig<D>, const D: usize>(
+    header: &EnvelopeHeader,
+    circuit_digest: &<C::Hasher as Hasher<C::F>>::Hash,
+) -> Result<(), EnvelopeError> {
+    if header.circuit_digest != circuit_digest.to_bytes() {
+        return Err(EnvelopeError::CircuitDigestMismatch);
+    }
+    Ok(())
+}
+
+/// A cursor over a byte slice, failing with `EnvelopeError::BadLength` when it runs out.
+struct SliceReader<'a>(&'a [u8]);
+
+impl<'a> SliceReader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], EnvelopeError> {
+        if self.0.len() < n {
+            return Err(EnvelopeError::BadLength);
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], EnvelopeError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn take_tag<T>(&mut self, from_u8: fn(u8) -> Option<T>) -> Result<T, EnvelopeError> {
+        let tag = self.take(1)?[0];
+        from_u8(tag).ok_or(EnvelopeError::UnknownTag(tag))
+    }
+}
+
+/// Deserializes a value from an envelope's payload with `read`, failing unless it consumes the
+/// whole payload.
+pub(crate) fn read_payload<T>(
+    payload: &[u8],
+    read: impl FnOnce(&mut Buffer) -> IoResult<T>,
+) -> Result<T, EnvelopeError> {
+    let mut buffer = Buffer::new(payload);
+    let value = read(&mut buffer).map_err(|_| EnvelopeError::MalformedPayload)?;
+    if buffer.remaining() != 0 {
+        return Err(EnvelopeError::MalformedPayload);
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{
+        CircuitConfig, CircuitData, VerifierCircuitData, VerifierOnlyCircuitData,
+    };
+    use crate::plonk::config::{
+        KeccakGoldilocksConfig, Poseidon2GoldilocksConfig, PoseidonGoldilocksConfig,
+    };
+    use crate::plonk::proof::ProofWithPublicInputs;
+    use crate::util::serialization::{DefaultGateSerializer, DefaultGeneratorSerializer};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_versioned_proof() -> Result<()> {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_public_input();
+        let y = builder.square(x);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand());
+        let proof = data.prove(pw)?;
+        let digest = &data.verifier_only.circuit_digest;
+
+        let bytes = proof.to_versioned_bytes(digest);
+        let (header, _) = EnvelopeHeader::parse(&bytes)?;
+        assert_eq!(header.version, FORMAT_VERSION);
+        assert_eq!(header.kind, PayloadKind::ProofWithPublicInputs);
+        assert_eq!(header.hasher, HasherType::Poseidon);
+        assert_eq!(header.degree, D);
+        let parsed =
+            ProofWithPublicInputs::<F, C, D>::from_versioned_bytes(&bytes, digest, &data.common)?;
+        assert_eq!(parsed, proof);
+
+        // Raw blobs, corrupted blobs and blobs for other circuits or configs are rejected.
+        assert_eq!(
+            ProofWithPublicInputs::<F, C, D>::from_versioned_bytes(
+                &proof.to_bytes(),
+                digest,
+                &data.common
+            ),
+            Err(EnvelopeError::BadMagic)
+        );
+        let mut corrupted = bytes.clone();
+        corrupted[MAGIC.len() + 8] ^= 1;
+        assert_eq!(
+            ProofWithPublicInputs::<F, C, D>::from_versioned_bytes(
+                &corrupted,
+                digest,
+                &data.common
+            ),
+            Err(EnvelopeError::BadChecksum)
+        );
+        assert_eq!(
+            ProofWithPublicInputs::<F, C, D>::from_versioned_bytes(
+                &bytes,
+                &Default::default(),
+                &data.common
+            ),
+            Err(EnvelopeError::CircuitDigestMismatch)
+        );
+        assert_eq!(
+            open::<KeccakGoldilocksConfig, D>(&bytes, PayloadKind::ProofWithPublicInputs, None)
+                .map(|_| ()),
+            Err(EnvelopeError::HasherMismatch {
+                expected: HasherType::Keccak,
+                actual: HasherType::Poseidon,
+            })
+        );
+        assert_eq!(
+            VerifierOnlyCircuitData::<C, D>::from_versioned_bytes(&bytes),
+            Err(EnvelopeError::KindMismatch {
+                expected: PayloadKind::VerifierOnlyCircuitData,
+                actual: PayloadKind::ProofWithPublicInputs,
+            })
+        );
+
+        let bytes = data
+            .verifier_only
+            .to_versioned_bytes()
+            .map_err(anyhow::Error::msg)?;
+        assert_eq!(
+            VerifierOnlyCircuitData::<C, D>::from_versioned_bytes(&bytes)?,
+            data.verifier_only
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_versioned_circuit_data() -> Result<()> {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_public_input();
+        let y = builder.square(x);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+        let gate_serializer = DefaultGateSerializer;
+        let generator_serializer = DefaultGeneratorSerializer::<C, D>::default();
+
+        let bytes = data
+            .to_versioned_bytes(&gate_serializer, &generator_serializer)
+            .map_err(anyhow::Error::msg)?;
+        let (header, _) = EnvelopeHeader::parse(&bytes)?;
+        assert_eq!(header.kind, PayloadKind::CircuitData);
+        assert_eq!(
+            CircuitData::<F, C, D>::from_versioned_bytes(
+                &bytes,
+                &gate_serializer,
+                &generator_serializer
+            )?,
+            data
+        );
+
+        // Raw blobs, truncated blobs, other payloads and other configs are rejected.
+        let raw = data
+            .to_bytes(&gate_serializer, &generator_serializer)
+            .map_err(anyhow::Error::msg)?;
+        assert_eq!(
+            CircuitData::<F, C, D>::from_versioned_bytes(
+                &raw,
+                &gate_serializer,
+                &generator_serializer
+            ),
+            Err(EnvelopeError::BadMagic)
+        );
+        assert_eq!(
+            CircuitData::<F, C, D>::from_versioned_bytes(
+                &bytes[..bytes.len() - 1],
+                &gate_serializer,
+                &generator_serializer
+            ),
+            Err(EnvelopeError::BadChecksum)
+        );
+        assert_eq!(
+            VerifierCircuitData::<F, C, D>::from_versioned_bytes(&bytes, &gate_serializer),
+            Err(EnvelopeError::KindMismatch {
+                expected: PayloadKind::VerifierCircuitData,
+                actual: PayloadKind::CircuitData,
+            })
+        );
+        assert_eq!(
+            CircuitData::<F, Poseidon2GoldilocksConfig, D>::from_versioned_bytes(
+                &bytes,
+                &gate_serializer,
+                &DefaultGeneratorSerializer::<Poseidon2GoldilocksConfig, D>::default()
+            ),
+            Err(EnvelopeError::HasherMismatch {
+                expected: HasherType::Poseidon2,
+                actual: HasherType::Poseidon,
+            })
+        );
+
+        let verifier_data = data.verifier_data();
+        let bytes = verifier_data
+            .to_versioned_bytes(&gate_serializer)
+            .map_err(anyhow::Error::msg)?;
+        assert_eq!(
+            VerifierCircuitData::<F, C, D>::from_versioned_bytes(&bytes, &gate_serializer)?,
+            verifier_data
+        );
+        let mut corrupted = bytes.clone();
+        corrupted[bytes.len() / 2] ^= 1;
+        assert_eq!(
+            VerifierCircuitData::<F, C, D>::from_versioned_bytes(&corrupted, &gate_serializer),
+            Err(EnvelopeError::BadChecksum)
+        );
+
+        // The digest in the header must match the one of the deserialized circuit.
+        let raw = verifier_data
+            .to_bytes(&gate_serializer)
+            .map_err(anyhow::Error::msg)?;
+        let mislabeled = seal::<C, D>(PayloadKind::VerifierCircuitData, &Default::default(), &raw);
+        assert_eq!(
+            VerifierCircuitData::<F, C, D>::from_versioned_bytes(&mislabeled, &gate_serializer),
+            Err(EnvelopeError::CircuitDigestMismatch)
+        );
+        Ok(())
+    }
+}
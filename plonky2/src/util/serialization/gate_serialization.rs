@@ -24,6 +24,13 @@ pub trait GateSerializer<F: RichField + Extendable<D>, const D: usize> {
         gate: &GateRef<F, D>,
         common_data: &CommonCircuitData<F, D>,
     ) -> IoResult<()>;
+
+    /// Returns whether `gate` can be written by this serializer. The default implementation
+    /// attempts to write it to a scratch buffer; serializers defined with `impl_gate_serializer`
+    /// only check the gate's type.
+    fn supports_gate(&self, gate: &GateRef<F, D>, common_data: &CommonCircuitData<F, D>) -> bool {
+        self.write_gate(&mut Vec::new(), gate, common_data).is_ok()
+    }
 }
 
 #[macro_export]
@@ -43,14 +50,25 @@ macro_rules! read_gate_impl {
 }
 
 #[macro_export]
-macro_rules! get_gate_tag_impl {
+macro_rules! find_gate_tag_impl {
     ($gate:expr, $($gate_types:ty),+) => {{
         let gate_any = $gate.0.as_any();
         let mut i = 0..;
         $(if let (tag, true) = (i.next().unwrap(), gate_any.is::<$gate_types>()) {
-            Ok(tag)
+            Some(tag)
         } else)*
         {
+            None
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! get_gate_tag_impl {
+    ($gate:expr, $($gate_types:ty),+) => {{
+        if let Some(tag) = $crate::find_gate_tag_impl!($gate, $($gate_types),+) {
+            Ok(tag)
+        } else {
             log::log!(
                 log::Level::Error,
                 "attempted to serialize gate with id `{}` which is unsupported by this gate serializer",
@@ -89,6 +107,14 @@ macro_rules! impl_gate_serializer {
             gate.0.serialize(buf, common)?;
             Ok(())
         }
+
+        fn supports_gate(
+            &self,
+            gate: &$crate::gates::gate::GateRef<F, D>,
+            _common: &$crate::plonk::circuit_data::CommonCircuitData<F, D>,
+        ) -> bool {
+            $crate::find_gate_tag_impl!(gate, $($gate_types),+).is_some()
+        }
     };
 }
 
@@ -101,11 +127,15 @@ pub mod default {
     use crate::gates::constant::ConstantGate;
     use crate::gates::coset_interpolation::CosetInterpolationGate;
     use crate::gates::exponentiation::ExponentiationGate;
+    use crate::gates::high_degree_interpolation::HighDegreeInterpolationGate;
     use crate::gates::lookup::LookupGate;
     use crate::gates::lookup_table::LookupTableGate;
+    use crate::gates::low_degree_interpolation::LowDegreeInterpolationGate;
     use crate::gates::multiplication_extension::MulExtensionGate;
     use crate::gates::noop::NoopGate;
     use crate::gates::poseidon::PoseidonGate;
+    use crate::gates::poseidon2::Poseidon2Gate;
+    use crate::gates::poseidon2_mds::Poseidon2MdsGate;
     use crate::gates::poseidon_mds::PoseidonMdsGate;
     use crate::gates::public_input::PublicInputGate;
     use crate::gates::random_access::RandomAccessGate;
@@ -125,6 +155,7 @@ pub mod default {
     #[derive(Debug)]
     pub struct DefaultGateSerializer;
     impl<F: RichField + Extendable<D>, const D: usize> GateSerializer<F, D> for DefaultGateSerializer {
+        // The tag of a gate is its position in this list, so new gates go at the end.
         impl_gate_serializer! {
             DefaultGateSerializer,
             ArithmeticGate,
@@ -142,7 +173,11 @@ pub mod default {
             PublicInputGate,
             RandomAccessGate<F, D>,
             ReducingExtensionGate<D>,
-            ReducingGate<D>
+            ReducingGate<D>,
+            Poseidon2MdsGate<F, D>,
+            Poseidon2Gate<F, D>,
+            HighDegreeInterpolationGate<F, D>,
+            LowDegreeInterpolationGate<F, D>
         }
     }
 }
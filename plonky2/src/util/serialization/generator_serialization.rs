@@ -25,6 +25,18 @@ pub trait WitnessGeneratorSerializer<F: RichField + Extendable<D>, const D: usiz
         generator: &WitnessGeneratorRef<F, D>,
         common_data: &CommonCircuitData<F, D>,
     ) -> IoResult<()>;
+
+    /// Returns whether `generator` can be written by this serializer. The default implementation
+    /// attempts to write it to a scratch buffer; serializers defined with
+    /// `impl_generator_serializer` only check the generator's id.
+    fn supports_generator(
+        &self,
+        generator: &WitnessGeneratorRef<F, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> bool {
+        self.write_generator(&mut Vec::new(), generator, common_data)
+            .is_ok()
+    }
 }
 
 #[macro_export]
@@ -48,13 +60,24 @@ macro_rules! read_generator_impl {
 }
 
 #[macro_export]
-macro_rules! get_generator_tag_impl {
+macro_rules! find_generator_tag_impl {
     ($generator:expr, $($generator_types:ty),+) => {{
         let mut i = 0..;
         $(if let (tag, true) = (i.next().unwrap(), $generator.0.id() == $crate::iop::generator::SimpleGenerator::<F, D>::id(&<$generator_types>::default())) {
-            Ok(tag)
+            Some(tag)
         } else)*
         {
+            None
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! get_generator_tag_impl {
+    ($generator:expr, $($generator_types:ty),+) => {{
+        if let Some(tag) = $crate::find_generator_tag_impl!($generator, $($generator_types),+) {
+            Ok(tag)
+        } else {
             log::log!(
                 log::Level::Error,
                 "attempted to serialize generator with id {} which is unsupported by this generator serializer",
@@ -93,6 +116,14 @@ macro_rules! impl_generator_serializer {
             generator.0.serialize(buf, common)?;
             Ok(())
         }
+
+        fn supports_generator(
+            &self,
+            generator: &$crate::iop::generator::WitnessGeneratorRef<F, D>,
+            _common: &$crate::plonk::circuit_data::CommonCircuitData<F, D>,
+        ) -> bool {
+            $crate::find_generator_tag_impl!(generator, $($generator_types),+).is_some()
+        }
     };
 }
 
@@ -111,10 +142,14 @@ pub mod default {
     use crate::gates::base_sum::BaseSplitGenerator;
     use crate::gates::coset_interpolation::InterpolationGenerator;
     use crate::gates::exponentiation::ExponentiationGenerator;
+    use crate::gates::high_degree_interpolation::HighDegreeInterpolationGenerator;
     use crate::gates::lookup::LookupGenerator;
     use crate::gates::lookup_table::LookupTableGenerator;
+    use crate::gates::low_degree_interpolation::LowDegreeInterpolationGenerator;
     use crate::gates::multiplication_extension::MulExtensionGenerator;
     use crate::gates::poseidon::PoseidonGenerator;
+    use crate::gates::poseidon2::Poseidon2Generator;
+    use crate::gates::poseidon2_mds::Poseidon2MdsGenerator;
     use crate::gates::poseidon_mds::PoseidonMdsGenerator;
     use crate::gates::random_access::RandomAccessGenerator;
     use crate::gates::reducing::ReducingGenerator;
@@ -151,6 +186,7 @@ pub mod default {
         C: GenericConfig<D, F = F> + 'static,
         C::Hasher: AlgebraicHasher<F>,
     {
+        // The tag of a generator is its position in this list, so new generators go at the end.
         impl_generator_serializer! {
             DefaultGeneratorSerializer,
             ArithmeticBaseGenerator<F, D>,
@@ -176,7 +212,11 @@ pub mod default {
             ReducingGenerator<D>,
             ReducingExtensionGenerator<D>,
             SplitGenerator,
-            WireSplitGenerator
+            WireSplitGenerator,
+            Poseidon2Generator<F, D>,
+            Poseidon2MdsGenerator<D>,
+            HighDegreeInterpolationGenerator<F, D>,
+            LowDegreeInterpolationGenerator<F, D>
         }
     }
 }
@@ -4,6 +4,11 @@ pub mod generator_serialization;
 #[macro_use]
 pub mod gate_serialization;
 
+mod circuit_serialize;
+pub mod envelope;
+pub mod portable;
+pub mod registry;
+
 #[cfg(not(feature = "std"))]
 use alloc::{collections::BTreeMap, sync::Arc, vec, vec::Vec};
 use core::convert::Infallible;
@@ -13,11 +18,14 @@ use core::ops::Range;
 #[cfg(feature = "std")]
 use std::{collections::BTreeMap, sync::Arc};
 
+pub use circuit_serialize::CircuitSerialize;
 pub use gate_serialization::default::DefaultGateSerializer;
 pub use gate_serialization::GateSerializer;
 pub use generator_serialization::default::DefaultGeneratorSerializer;
 pub use generator_serialization::WitnessGeneratorSerializer;
 use hashbrown::HashMap;
+pub use plonky2_derive::{GateSerialize, GeneratorSerialize};
+pub use registry::{GateSerializerRegistry, GeneratorSerializerRegistry};
 
 use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::polynomial::PolynomialCoeffs;
@@ -31,8 +39,9 @@ use crate::fri::reduction_strategies::FriReductionStrategy;
 use crate::fri::{FriConfig, FriParams};
 use crate::gadgets::polynomial::PolynomialCoeffsExtTarget;
 use crate::gates::gate::GateRef;
-use crate::gates::lookup::Lookup;
-use crate::gates::selectors::SelectorsInfo;
+use crate::gates::lookup::{Lookup, LookupGate};
+use crate::gates::lookup_table::LookupTableGate;
+use crate::gates::selectors::{LookupSelectors, SelectorsInfo};
 use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_proofs::{MerkleProof, MerkleProofTarget};
 use crate::hash::merkle_tree::{MerkleCap, MerkleTree};
@@ -51,6 +60,7 @@ use crate::plonk::proof::{
     CompressedProof, CompressedProofWithPublicInputs, OpeningSet, OpeningSetTarget, Proof,
     ProofTarget, ProofWithPublicInputs, ProofWithPublicInputsTarget,
 };
+use crate::util::{ceil_div_usize, partial_products};
 
 /// A no_std compatible variant of `std::io::Error`
 #[derive(Debug)]
@@ -65,6 +75,20 @@ impl Display for IoError {
 /// A no_std compatible variant of `std::io::Result`
 pub type IoResult<T> = Result<T, IoError>;
 
+/// Upper bound on the capacity reserved up front for a vector whose length is read from the
+/// buffer. Longer vectors still deserialize, but grow as their elements are actually read, so a
+/// forged length can't trigger a huge allocation.
+const MAX_PREALLOCATED_LEN: usize = 1 << 16;
+
+/// Returns the capacity to reserve for a vector of `len` elements read from untrusted input.
+fn capacity_for(len: usize) -> usize {
+    len.min(MAX_PREALLOCATED_LEN)
+}
+
+/// Upper bound on the dimensions (number of wires, constants, public inputs, ...) of a
+/// deserialized [`CommonCircuitData`], which keeps the derived sizes free of overflows.
+const MAX_CIRCUIT_DIMENSION: usize = 1 << 24;
+
 /// A `Read` which is able to report how many bytes are remaining.
 pub trait Remaining: Read {
     /// Returns the number of bytes remaining in the buffer.
@@ -139,11 +163,21 @@ pub trait Read {
         Ok(u64::from_le_bytes(buf) as usize)
     }
 
+    /// Reads a `usize` value from `self`, failing if it is greater than `max`.
+    #[inline]
+    fn read_usize_at_most(&mut self, max: usize) -> IoResult<usize> {
+        let x = self.read_usize()?;
+        if x > max {
+            return Err(IoError);
+        }
+        Ok(x)
+    }
+
     /// Reads a vector of `usize` value from `self`.
     #[inline]
     fn read_usize_vec(&mut self) -> IoResult<Vec<usize>> {
         let len = self.read_usize()?;
-        let mut res = Vec::with_capacity(len);
+        let mut res = Vec::with_capacity(capacity_for(len));
         for _ in 0..len {
             res.push(self.read_usize()?);
         }
@@ -152,6 +186,8 @@ pub trait Read {
     }
 
     /// Reads a element from the field `F` with size less than `2^64` from `self.`
+    ///
+    /// Fails if the value read isn't the canonical representative of a field element.
     #[inline]
     fn read_field<F>(&mut self) -> IoResult<F>
     where
@@ -159,7 +195,11 @@ pub trait Read {
     {
         let mut buf = [0; size_of::<u64>()];
         self.read_exact(&mut buf)?;
-        Ok(F::from_canonical_u64(u64::from_le_bytes(buf)))
+        let x = u64::from_le_bytes(buf);
+        if x >= F::ORDER {
+            return Err(IoError);
+        }
+        Ok(F::from_canonical_u64(x))
     }
 
     /// Reads a vector of elements from the field `F` from `self`.
@@ -261,7 +301,7 @@ pub trait Read {
     {
         let mut buf = vec![0; H::HASH_SIZE];
         self.read_exact(&mut buf)?;
-        Ok(H::Hash::from_bytes(&buf))
+        H::Hash::try_from_bytes(&buf).ok_or(IoError)
     }
 
     /// Reads a HashOutTarget value from `self`.
@@ -294,7 +334,10 @@ pub trait Read {
         F: RichField,
         H: Hasher<F>,
     {
-        let cap_length = 1 << cap_height;
+        let cap_length = u32::try_from(cap_height)
+            .ok()
+            .and_then(|cap_height| 1usize.checked_shl(cap_height))
+            .ok_or(IoError)?;
         Ok(MerkleCap(
             (0..cap_length)
                 .map(|_| self.read_hash::<F, H>())
@@ -322,7 +365,7 @@ pub trait Read {
     {
         let leaves_len = self.read_usize()?;
         let leaf_len = self.read_usize()?;
-        let mut leaves_2d = Vec::with_capacity(leaves_len * leaf_len);
+        let mut leaves_2d = Vec::with_capacity(capacity_for(leaves_len));
         for _ in 0..leaves_len {
             // let leaf_len = self.read_usize()?;
             leaves_2d.push(self.read_field_vec(leaf_len)?);
@@ -355,6 +398,8 @@ pub trait Read {
         let wires = self.read_field_ext_vec::<F, D>(config.num_wires)?;
         let plonk_zs = self.read_field_ext_vec::<F, D>(config.num_challenges)?;
         let plonk_zs_next = self.read_field_ext_vec::<F, D>(config.num_challenges)?;
+        let lookup_zs = self.read_field_ext_vec::<F, D>(common_data.num_all_lookup_polys())?;
+        let lookup_zs_next = self.read_field_ext_vec::<F, D>(common_data.num_all_lookup_polys())?;
         let partial_products = self
             .read_field_ext_vec::<F, D>(common_data.num_partial_products * config.num_challenges)?;
         let quotient_polys = self.read_field_ext_vec::<F, D>(
@@ -368,6 +413,8 @@ pub trait Read {
             plonk_zs_next,
             partial_products,
             quotient_polys,
+            lookup_zs,
+            lookup_zs_next,
         })
     }
 
@@ -379,8 +426,8 @@ pub trait Read {
         let wires = self.read_target_ext_vec::<D>()?;
         let plonk_zs = self.read_target_ext_vec::<D>()?;
         let plonk_zs_next = self.read_target_ext_vec::<D>()?;
-        let _lookup_zs = self.read_target_ext_vec::<D>()?;
-        let _next_lookup_zs = self.read_target_ext_vec::<D>()?;
+        let lookup_zs = self.read_target_ext_vec::<D>()?;
+        let next_lookup_zs = self.read_target_ext_vec::<D>()?;
         let partial_products = self.read_target_ext_vec::<D>()?;
         let quotient_polys = self.read_target_ext_vec::<D>()?;
 
@@ -390,8 +437,8 @@ pub trait Read {
             wires,
             plonk_zs,
             plonk_zs_next,
-            // lookup_zs,
-            // next_lookup_zs,
+            lookup_zs,
+            next_lookup_zs,
             partial_products,
             quotient_polys,
         })
@@ -464,7 +511,7 @@ pub trait Read {
     #[inline]
     fn read_target_fri_initial_proof(&mut self) -> IoResult<FriInitialTreeProofTarget> {
         let len = self.read_usize()?;
-        let mut evals_proofs = Vec::with_capacity(len);
+        let mut evals_proofs = Vec::with_capacity(capacity_for(len));
 
         for _ in 0..len {
             evals_proofs.push((self.read_target_vec()?, self.read_target_merkle_proof()?));
@@ -516,7 +563,7 @@ pub trait Read {
         C: GenericConfig<D, F = F>,
     {
         let config = &common_data.config;
-        let mut fqrs = Vec::with_capacity(config.fri_config.num_query_rounds);
+        let mut fqrs = Vec::with_capacity(capacity_for(config.fri_config.num_query_rounds));
         for _ in 0..config.fri_config.num_query_rounds {
             let initial_trees_proof = self.read_fri_initial_proof::<F, C, D>(common_data)?;
             let steps = common_data
@@ -539,7 +586,7 @@ pub trait Read {
         &mut self,
     ) -> IoResult<Vec<FriQueryRoundTarget<D>>> {
         let num_query_rounds = self.read_usize()?;
-        let mut fqrs = Vec::with_capacity(num_query_rounds);
+        let mut fqrs = Vec::with_capacity(capacity_for(num_query_rounds));
         for _ in 0..num_query_rounds {
             let initial_trees_proof = self.read_target_fri_initial_proof()?;
             let num_steps = self.read_usize()?;
@@ -700,7 +747,7 @@ pub trait Read {
     fn read_selectors_info(&mut self) -> IoResult<SelectorsInfo> {
         let selector_indices = self.read_usize_vec()?;
         let groups_len = self.read_usize()?;
-        let mut groups = Vec::with_capacity(groups_len);
+        let mut groups = Vec::with_capacity(capacity_for(groups_len));
         for _ in 0..groups_len {
             let start = self.read_usize()?;
             let end = self.read_usize()?;
@@ -721,7 +768,7 @@ pub trait Read {
         &mut self,
     ) -> IoResult<PolynomialBatch<F, C, D>> {
         let poly_len = self.read_usize()?;
-        let mut polynomials = Vec::with_capacity(poly_len);
+        let mut polynomials = Vec::with_capacity(capacity_for(poly_len));
         for _ in 0..poly_len {
             let plen = self.read_usize()?;
             polynomials.push(PolynomialCoeffs::new(self.read_field_vec(plen)?));
@@ -759,17 +806,17 @@ pub trait Read {
 
         let num_partial_products = self.read_usize()?;
 
-        let _num_lookup_polys = self.read_usize()?;
-        let _num_lookup_selectors = self.read_usize()?;
+        let num_lookup_polys = self.read_usize()?;
+        let num_lookup_selectors = self.read_usize()?;
         let length = self.read_usize()?;
-        let mut luts = Vec::with_capacity(length);
+        let mut luts = Vec::with_capacity(capacity_for(length));
 
         for _ in 0..length {
             luts.push(Arc::new(self.read_lut()?));
         }
 
         let gates_len = self.read_usize()?;
-        let mut gates = Vec::with_capacity(gates_len);
+        let mut gates = Vec::with_capacity(capacity_for(gates_len));
 
         // We construct the common data without gates first,
         // to pass it as argument when reading the gates.
@@ -784,17 +831,21 @@ pub trait Read {
             num_public_inputs,
             k_is,
             num_partial_products,
-            // num_lookup_polys,
-            // num_lookup_selectors,
-            // luts,
+            num_lookup_polys,
+            num_lookup_selectors,
+            luts,
         };
 
+        // Check the dimensions before reading the gates, which are bounded by them.
+        check_common_circuit_data_dimensions(&common_data)?;
+
         for _ in 0..gates_len {
             let gate = self.read_gate::<F, D>(gate_serializer, &common_data)?;
             gates.push(gate);
         }
 
         common_data.gates = gates;
+        check_common_circuit_data_gates(&common_data)?;
 
         Ok(common_data)
     }
@@ -828,7 +879,7 @@ pub trait Read {
         common_data: &CommonCircuitData<F, D>,
     ) -> IoResult<ProverOnlyCircuitData<F, C, D>> {
         let gen_len = self.read_usize()?;
-        let mut generators = Vec::with_capacity(gen_len);
+        let mut generators = Vec::with_capacity(capacity_for(gen_len));
         for _ in 0..gen_len {
             generators.push(self.read_generator(generator_serializer, common_data)?);
         }
@@ -841,7 +892,7 @@ pub trait Read {
 
         let constants_sigmas_commitment = self.read_polynomial_batch()?;
         let sigmas_len = self.read_usize()?;
-        let mut sigmas = Vec::with_capacity(sigmas_len);
+        let mut sigmas = Vec::with_capacity(capacity_for(sigmas_len));
         for _ in 0..sigmas_len {
             let sigma_len = self.read_usize()?;
             sigmas.push(self.read_field_vec(sigma_len)?);
@@ -858,7 +909,7 @@ pub trait Read {
         let fft_root_table = match is_some {
             true => {
                 let table_len = self.read_usize()?;
-                let mut table = Vec::with_capacity(table_len);
+                let mut table = Vec::with_capacity(capacity_for(table_len));
                 for _ in 0..table_len {
                     let len = self.read_usize()?;
                     table.push(self.read_field_vec(len)?);
@@ -871,7 +922,7 @@ pub trait Read {
         let circuit_digest = self.read_hash::<F, <C as GenericConfig<D>>::Hasher>()?;
 
         let length = self.read_usize()?;
-        let mut lookup_rows = Vec::with_capacity(length);
+        let mut lookup_rows = Vec::with_capacity(capacity_for(length));
         for _ in 0..length {
             lookup_rows.push(LookupWire {
                 last_lu_gate: self.read_usize()?,
@@ -881,7 +932,7 @@ pub trait Read {
         }
 
         let length = self.read_usize()?;
-        let mut lut_to_lookups = Vec::with_capacity(length);
+        let mut lut_to_lookups = Vec::with_capacity(capacity_for(length));
         for _ in 0..length {
             lut_to_lookups.push(self.read_target_lut()?);
         }
@@ -896,8 +947,8 @@ pub trait Read {
             representative_map,
             fft_root_table,
             circuit_digest,
-            // lookup_rows,
-            // lut_to_lookups,
+            lookup_rows,
+            lut_to_lookups,
         })
     }
 
@@ -942,8 +993,12 @@ pub trait Read {
         &mut self,
         gate_serializer: &dyn GateSerializer<F, D>,
     ) -> IoResult<VerifierCircuitData<F, C, D>> {
-        let verifier_only = self.read_verifier_only_circuit_data()?;
+        let verifier_only: VerifierOnlyCircuitData<C, D> =
+            self.read_verifier_only_circuit_data()?;
         let common = self.read_common_circuit_data(gate_serializer)?;
+        if verifier_only.constants_sigmas_cap.height() != common.fri_params.config.cap_height {
+            return Err(IoError);
+        }
         Ok(VerifierCircuitData {
             verifier_only,
             common,
@@ -1013,8 +1068,9 @@ pub trait Read {
         C: GenericConfig<D, F = F>,
     {
         let proof = self.read_proof(common_data)?;
-        let pi_len = self.read_usize()?;
-        let public_inputs = self.read_field_vec(pi_len)?;
+        // NOTE: circom_compatability
+        // The public inputs are written without a length prefix.
+        let public_inputs = self.read_field_vec(common_data.num_public_inputs)?;
         Ok(ProofWithPublicInputs {
             proof,
             public_inputs,
@@ -1146,7 +1202,10 @@ pub trait Read {
         C: GenericConfig<D, F = F>,
     {
         let proof = self.read_compressed_proof(common_data)?;
-        let public_inputs = self.read_field_vec(self.remaining() / size_of::<u64>())?;
+        if self.remaining() != common_data.num_public_inputs * size_of::<u64>() {
+            return Err(IoError);
+        }
+        let public_inputs = self.read_field_vec(common_data.num_public_inputs)?;
         Ok(CompressedProofWithPublicInputs {
             proof,
             public_inputs,
@@ -1157,7 +1216,7 @@ pub trait Read {
     #[inline]
     fn read_lut(&mut self) -> IoResult<Vec<(u16, u16)>> {
         let length = self.read_usize()?;
-        let mut lut = Vec::with_capacity(length);
+        let mut lut = Vec::with_capacity(capacity_for(length));
         for _ in 0..length {
             lut.push((self.read_u16()?, self.read_u16()?));
         }
@@ -1169,7 +1228,7 @@ pub trait Read {
     #[inline]
     fn read_target_lut(&mut self) -> IoResult<Lookup> {
         let length = self.read_usize()?;
-        let mut lut = Vec::with_capacity(length);
+        let mut lut = Vec::with_capacity(capacity_for(length));
         for _ in 0..length {
             lut.push((self.read_target()?, self.read_target()?));
         }
@@ -1178,6 +1237,103 @@ pub trait Read {
     }
 }
 
+/// Checks that the dimensions of a deserialized [`CommonCircuitData`] are bounded and consistent
+/// with each other, so that the verifier can rely on them.
+fn check_common_circuit_data_dimensions<F: RichField + Extendable<D>, const D: usize>(
+    common_data: &CommonCircuitData<F, D>,
+) -> IoResult<()> {
+    let CommonCircuitData {
+        config,
+        fri_params,
+        quotient_degree_factor,
+        num_gate_constraints,
+        num_constants,
+        num_public_inputs,
+        k_is,
+        num_partial_products,
+        num_lookup_polys,
+        num_lookup_selectors,
+        luts,
+        ..
+    } = common_data;
+
+    let dimensions = [
+        config.num_wires,
+        config.num_challenges,
+        config.fri_config.num_query_rounds,
+        *quotient_degree_factor,
+        *num_gate_constraints,
+        *num_constants,
+        *num_public_inputs,
+        *num_lookup_polys,
+        *num_lookup_selectors,
+        luts.len(),
+    ];
+    let lde_bits = fri_params
+        .degree_bits
+        .checked_add(fri_params.config.rate_bits)
+        .ok_or(IoError)?;
+    let total_arities = fri_params
+        .reduction_arity_bits
+        .iter()
+        .try_fold(0usize, |acc, &bits| acc.checked_add(bits))
+        .ok_or(IoError)?;
+    let is_valid = dimensions.iter().all(|&d| d <= MAX_CIRCUIT_DIMENSION)
+        && config.num_routed_wires <= config.num_wires
+        && *quotient_degree_factor > 1
+        && k_is.len() == config.num_routed_wires
+        && *num_partial_products
+            == partial_products::num_partial_products(
+                config.num_routed_wires,
+                *quotient_degree_factor,
+            )
+        && fri_params.config == config.fri_config
+        && lde_bits <= F::TWO_ADICITY
+        && total_arities <= fri_params.degree_bits
+        && fri_params.config.cap_height <= lde_bits - total_arities
+        && if luts.is_empty() {
+            *num_lookup_polys == 0 && *num_lookup_selectors == 0
+        } else {
+            LookupTableGate::num_slots(config) > 0
+                && *num_lookup_polys
+                    == ceil_div_usize(LookupGate::num_slots(config), quotient_degree_factor - 1) + 1
+                && *num_lookup_selectors == LookupSelectors::StartEnd as usize + luts.len()
+        };
+    if !is_valid {
+        return Err(IoError);
+    }
+    Ok(())
+}
+
+/// Checks that the gates and selectors of a deserialized [`CommonCircuitData`] fit within its
+/// wires, constants and constraints.
+fn check_common_circuit_data_gates<F: RichField + Extendable<D>, const D: usize>(
+    common_data: &CommonCircuitData<F, D>,
+) -> IoResult<()> {
+    let SelectorsInfo {
+        selector_indices,
+        groups,
+    } = &common_data.selectors_info;
+    let num_gates = common_data.gates.len();
+    let num_selectors = common_data.selectors_info.num_selectors();
+    let selectors_are_valid = selector_indices.len() == num_gates
+        && selector_indices.iter().all(|&i| i < groups.len())
+        && groups
+            .iter()
+            .all(|group| group.start <= group.end && group.end <= num_gates)
+        && num_selectors + common_data.num_lookup_selectors <= common_data.num_constants;
+    let gates_are_valid = common_data.gates.iter().all(|gate| {
+        gate.0.num_wires() <= common_data.config.num_wires
+            && num_selectors + common_data.num_lookup_selectors + gate.0.num_constants()
+                <= common_data.num_constants
+            && gate.0.num_constraints() <= common_data.num_gate_constraints
+    });
+    if !(selectors_are_valid && gates_are_valid) {
+        return Err(IoError);
+    }
+    Ok(())
+}
+
 /// Writing
 pub trait Write {
     /// Error Type
@@ -1439,8 +1595,8 @@ pub trait Write {
         self.write_field_ext_vec::<F, D>(&os.wires)?;
         self.write_field_ext_vec::<F, D>(&os.plonk_zs)?;
         self.write_field_ext_vec::<F, D>(&os.plonk_zs_next)?;
-        // self.write_field_ext_vec::<F, D>(&os.lookup_zs)?;
-        // self.write_field_ext_vec::<F, D>(&os.lookup_zs_next)?;
+        self.write_field_ext_vec::<F, D>(&os.lookup_zs)?;
+        self.write_field_ext_vec::<F, D>(&os.lookup_zs_next)?;
         self.write_field_ext_vec::<F, D>(&os.partial_products)?;
         self.write_field_ext_vec::<F, D>(&os.quotient_polys)
     }
@@ -1456,8 +1612,8 @@ pub trait Write {
         self.write_target_ext_vec::<D>(&os.wires)?;
         self.write_target_ext_vec::<D>(&os.plonk_zs)?;
         self.write_target_ext_vec::<D>(&os.plonk_zs_next)?;
-        // self.write_target_ext_vec::<D>(&os.lookup_zs)?;
-        // self.write_target_ext_vec::<D>(&os.next_lookup_zs)?;
+        self.write_target_ext_vec::<D>(&os.lookup_zs)?;
+        self.write_target_ext_vec::<D>(&os.next_lookup_zs)?;
         self.write_target_ext_vec::<D>(&os.partial_products)?;
         self.write_target_ext_vec::<D>(&os.quotient_polys)
     }
@@ -1775,9 +1931,9 @@ pub trait Write {
             num_public_inputs,
             k_is,
             num_partial_products,
-            // num_lookup_polys,
-            // num_lookup_selectors,
-            // luts,
+            num_lookup_polys,
+            num_lookup_selectors,
+            luts,
         } = common_data;
 
         self.write_circuit_config(config)?;
@@ -1794,12 +1950,12 @@ pub trait Write {
 
         self.write_usize(*num_partial_products)?;
 
-        // self.write_usize(*num_lookup_polys)?;
-        // self.write_usize(*num_lookup_selectors)?;
-        // self.write_usize(luts.len())?;
-        // for lut in luts.iter() {
-        //     self.write_lut(lut)?;
-        // }
+        self.write_usize(*num_lookup_polys)?;
+        self.write_usize(*num_lookup_selectors)?;
+        self.write_usize(luts.len())?;
+        for lut in luts.iter() {
+            self.write_lut(lut)?;
+        }
 
         self.write_usize(gates.len())?;
         for gate in gates.iter() {
@@ -1848,8 +2004,8 @@ pub trait Write {
             representative_map,
             fft_root_table,
             circuit_digest,
-            // lookup_rows,
-            // lut_to_lookups,
+            lookup_rows,
+            lut_to_lookups,
         } = prover_only_circuit_data;
 
         self.write_usize(generators.len())?;
@@ -1888,17 +2044,17 @@ pub trait Write {
 
         self.write_hash::<F, <C as GenericConfig<D>>::Hasher>(*circuit_digest)?;
 
-        // self.write_usize(lookup_rows.len())?;
-        // for wire in lookup_rows.iter() {
-        //     self.write_usize(wire.last_lu_gate)?;
-        //     self.write_usize(wire.last_lut_gate)?;
-        //     self.write_usize(wire.first_lut_gate)?;
-        // }
+        self.write_usize(lookup_rows.len())?;
+        for wire in lookup_rows.iter() {
+            self.write_usize(wire.last_lu_gate)?;
+            self.write_usize(wire.last_lut_gate)?;
+            self.write_usize(wire.first_lut_gate)?;
+        }
 
-        // self.write_usize(lut_to_lookups.len())?;
-        // for tlut in lut_to_lookups.iter() {
-        //     self.write_target_lut(tlut)?;
-        // }
+        self.write_usize(lut_to_lookups.len())?;
+        for tlut in lut_to_lookups.iter() {
+            self.write_target_lut(tlut)?;
+        }
 
         Ok(())
     }
@@ -2229,3 +2385,116 @@ impl<'a> Read for Buffer<'a> {
         generator_serializer.read_generator(self, common_data)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::{Field, Sample};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn test_circuit_and_proof() -> Result<(CircuitData<F, C, D>, ProofWithPublicInputs<F, C, D>)> {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_public_input();
+        let y = builder.add_virtual_public_input();
+        let z = builder.mul(x, y);
+        builder.register_public_input(z);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand());
+        pw.set_target(y, F::rand());
+        let proof = data.prove(pw)?;
+        Ok((data, proof))
+    }
+
+    /// Returns copies of `bytes` truncated or with a bit flipped at a sample of positions.
+    fn corruptions(bytes: &[u8]) -> impl Iterator<Item = Vec<u8>> + '_ {
+        let step = (bytes.len() / 256).max(1);
+        (0..bytes.len()).step_by(step).flat_map(move |i| {
+            let mut flipped = bytes.to_vec();
+            flipped[i] ^= 1 << (i % 8);
+            [bytes[..i].to_vec(), flipped]
+        })
+    }
+
+    #[test]
+    fn test_oversized_length_prefix() {
+        let bytes = u64::MAX.to_le_bytes();
+        assert!(Buffer::new(&bytes).read_usize_vec().is_err());
+    }
+
+    #[test]
+    fn test_non_canonical_field_element() {
+        let bytes = F::ORDER.to_le_bytes();
+        assert!(Buffer::new(&bytes).read_field::<F>().is_err());
+
+        let bytes = (F::ORDER - 1).to_le_bytes();
+        assert_eq!(Buffer::new(&bytes).read_field::<F>().unwrap(), F::NEG_ONE);
+    }
+
+    #[test]
+    fn test_corrupted_proof_bytes() -> Result<()> {
+        let (data, proof) = test_circuit_and_proof()?;
+
+        let bytes = proof.to_bytes();
+        let parsed = ProofWithPublicInputs::<F, C, D>::from_bytes(bytes.clone(), &data.common)?;
+        assert_eq!(parsed, proof);
+        for corrupted in corruptions(&bytes) {
+            if let Ok(proof) = ProofWithPublicInputs::from_bytes(corrupted, &data.common) {
+                assert!(data.verify(proof).is_err());
+            }
+        }
+
+        let compressed_proof = data.compress(proof)?;
+        let bytes = compressed_proof.to_bytes();
+        let parsed =
+            CompressedProofWithPublicInputs::<F, C, D>::from_bytes(bytes.clone(), &data.common)?;
+        assert_eq!(parsed, compressed_proof);
+        for corrupted in corruptions(&bytes) {
+            if let Ok(proof) = CompressedProofWithPublicInputs::from_bytes(corrupted, &data.common)
+            {
+                assert!(data.verify_compressed(proof).is_err());
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupted_verifier_data_bytes() -> Result<()> {
+        let (data, proof) = test_circuit_and_proof()?;
+        let gate_serializer = DefaultGateSerializer;
+        let verifier_data = data.verifier_data();
+
+        let bytes = verifier_data
+            .to_bytes(&gate_serializer)
+            .map_err(anyhow::Error::msg)?;
+        let parsed = VerifierCircuitData::<F, C, D>::from_bytes(bytes.clone(), &gate_serializer)
+            .map_err(anyhow::Error::msg)?;
+        assert_eq!(parsed, verifier_data);
+        for corrupted in corruptions(&bytes) {
+            if let Ok(verifier_data) =
+                VerifierCircuitData::<F, C, D>::from_bytes(corrupted, &gate_serializer)
+            {
+                let _ = verifier_data.verify(proof.clone());
+            }
+        }
+
+        // A constants-sigmas cap whose height disagrees with the FRI config is rejected.
+        let mut bad_verifier_data = verifier_data;
+        let cap = &mut bad_verifier_data.verifier_only.constants_sigmas_cap.0;
+        cap.truncate(cap.len() / 2);
+        let bytes = bad_verifier_data
+            .to_bytes(&gate_serializer)
+            .map_err(anyhow::Error::msg)?;
+        assert!(VerifierCircuitData::<F, C, D>::from_bytes(bytes, &gate_serializer).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+//! A serde-compatible representation of [`CommonCircuitData`].
+//!
+//! Gates are trait objects, so `CommonCircuitData` can only be deserialized with the help of a
+//! [`GateSerializer`]. [`PortableCommonCircuitData`] instead represents each gate by its
+//! serializer tag and the bytes of its parameters, which makes it usable with any serde format
+//! such as JSON or CBOR.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use serde::{Deserialize, Serialize};
+
+use super::{check_common_circuit_data_dimensions, check_common_circuit_data_gates};
+use crate::field::extension::Extendable;
+use crate::fri::FriParams;
+use crate::gates::gate::GateRef;
+use crate::gates::lookup_table::LookupTable;
+use crate::gates::selectors::SelectorsInfo;
+use crate::hash::hash_types::RichField;
+use crate::plonk::circuit_data::{CircuitConfig, CommonCircuitData};
+use crate::util::serialization::{Buffer, GateSerializer, IoError, IoResult, Read, Remaining};
+
+/// A gate, as identified by a [`GateSerializer`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PortableGate {
+    /// The index of the gate's type in the list of gates supported by the serializer.
+    pub tag: u32,
+    /// The gate's `id`, for readability. It must match the gate rebuilt from `tag` and `params`.
+    pub id: String,
+    /// The gate's parameters, as written by `Gate::serialize`.
+    pub params: Vec<u8>,
+}
+
+/// A serde-compatible representation of [`CommonCircuitData`], with the same fields except that
+/// gates are given as [`PortableGate`]s.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PortableCommonCircuitData<F: RichField + Extendable<D>, const D: usize> {
+    pub config: CircuitConfig,
+    pub fri_params: FriParams,
+    pub gates: Vec<PortableGate>,
+    pub selectors_info: SelectorsInfo,
+    pub quotient_degree_factor: usize,
+    pub num_gate_constraints: usize,
+    pub num_constants: usize,
+    pub num_public_inputs: usize,
+    pub k_is: Vec<F>,
+    pub num_partial_products: usize,
+    pub num_lookup_polys: usize,
+    pub num_lookup_selectors: usize,
+    pub luts: Vec<LookupTable>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
+    /// Converts this circuit data to its serde-compatible representation, encoding gates with
+    /// `gate_serializer`.
+    pub fn to_portable(
+        &self,
+        gate_serializer: &dyn GateSerializer<F, D>,
+    ) -> IoResult<PortableCommonCircuitData<F, D>> {
+        let gates = self
+            .gates
+            .iter()
+            .map(|gate| {
+                let mut bytes = Vec::new();
+                gate_serializer.write_gate(&mut bytes, gate, self)?;
+                let (tag, params) = bytes.split_at(4);
+                Ok(PortableGate {
+                    tag: u32::from_le_bytes(tag.try_into().unwrap()),
+                    id: gate.0.id(),
+                    params: params.to_vec(),
+                })
+            })
+            .collect::<IoResult<Vec<_>>>()?;
+        Ok(PortableCommonCircuitData {
+            config: self.config.clone(),
+            fri_params: self.fri_params.clone(),
+            gates,
+            selectors_info: self.selectors_info.clone(),
+            quotient_degree_factor: self.quotient_degree_factor,
+            num_gate_constraints: self.num_gate_constraints,
+            num_constants: self.num_constants,
+            num_public_inputs: self.num_public_inputs,
+            k_is: self.k_is.clone(),
+            num_partial_products: self.num_partial_products,
+            num_lookup_polys: self.num_lookup_polys,
+            num_lookup_selectors: self.num_lookup_selectors,
+            luts: self.luts.clone(),
+        })
+    }
+
+    /// Rebuilds circuit data from its serde-compatible representation, decoding gates with
+    /// `gate_serializer`. This performs the same consistency checks as
+    /// [`CommonCircuitData::from_bytes`].
+    pub fn from_portable(
+        portable: PortableCommonCircuitData<F, D>,
+        gate_serializer: &dyn GateSerializer<F, D>,
+    ) -> IoResult<Self> {
+        let PortableCommonCircuitData {
+            config,
+            fri_params,
+            gates: portable_gates,
+            selectors_info,
+            quotient_degree_factor,
+            num_gate_constraints,
+            num_constants,
+            num_public_inputs,
+            k_is,
+            num_partial_products,
+            num_lookup_polys,
+            num_lookup_selectors,
+            luts,
+        } = portable;
+
+        // As when reading from bytes, gates are decoded against the common data without gates.
+        let mut common_data = CommonCircuitData {
+            config,
+            fri_params,
+            gates: Vec::new(),
+            selectors_info,
+            quotient_degree_factor,
+            num_gate_constraints,
+            num_constants,
+            num_public_inputs,
+            k_is,
+            num_partial_products,
+            num_lookup_polys,
+            num_lookup_selectors,
+            luts,
+        };
+        check_common_circuit_data_dimensions(&common_data)?;
+
+        let gates = portable_gates
+            .iter()
+            .map(|portable_gate| read_portable_gate(portable_gate, gate_serializer, &common_data))
+            .collect::<IoResult<Vec<_>>>()?;
+        common_data.gates = gates;
+        check_common_circuit_data_gates(&common_data)?;
+
+        Ok(common_data)
+    }
+}
+
+fn read_portable_gate<F: RichField + Extendable<D>, const D: usize>(
+    portable_gate: &PortableGate,
+    gate_serializer: &dyn GateSerializer<F, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> IoResult<GateRef<F, D>> {
+    let mut bytes = portable_gate.tag.to_le_bytes().to_vec();
+    bytes.extend_from_slice(&portable_gate.params);
+    let mut buffer = Buffer::new(&bytes);
+    let gate = buffer.read_gate(gate_serializer, common_data)?;
+    if buffer.remaining() != 0 || gate.0.id() != portable_gate.id {
+        return Err(IoError);
+    }
+    Ok(gate)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::VerifierOnlyCircuitData;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::proof::CompressedProofWithPublicInputs;
+    use crate::util::serialization::DefaultGateSerializer;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_serde_round_trip() -> Result<()> {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_public_input();
+        let y = builder.exp_u64(x, 12345);
+        let zero = builder.zero();
+        let z = builder.random_access(zero, vec![x, y]);
+        builder.register_public_input(z);
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand());
+        let proof = data.prove(pw)?;
+        let compressed_proof = data.compress(proof)?;
+
+        let gate_serializer = DefaultGateSerializer;
+        let portable = data
+            .common
+            .to_portable(&gate_serializer)
+            .map_err(anyhow::Error::msg)?;
+
+        let json = serde_json::to_string(&portable)?;
+        let common =
+            CommonCircuitData::from_portable(serde_json::from_str(&json)?, &gate_serializer)
+                .map_err(anyhow::Error::msg)?;
+        assert_eq!(common, data.common);
+
+        let cbor = serde_cbor::to_vec(&portable)?;
+        let common =
+            CommonCircuitData::from_portable(serde_cbor::from_slice(&cbor)?, &gate_serializer)
+                .map_err(anyhow::Error::msg)?;
+        assert_eq!(common, data.common);
+
+        let json = serde_json::to_string(&data.verifier_only)?;
+        let verifier_only: VerifierOnlyCircuitData<C, D> = serde_json::from_str(&json)?;
+        assert_eq!(verifier_only, data.verifier_only);
+
+        let json = serde_json::to_string(&compressed_proof)?;
+        let parsed: CompressedProofWithPublicInputs<F, C, D> = serde_json::from_str(&json)?;
+        assert_eq!(parsed, compressed_proof);
+        let cbor = serde_cbor::to_vec(&compressed_proof)?;
+        let parsed: CompressedProofWithPublicInputs<F, C, D> = serde_cbor::from_slice(&cbor)?;
+        assert_eq!(parsed, compressed_proof);
+        data.verify_compressed(parsed)?;
+
+        // A gate whose id doesn't match its tag and parameters is rejected.
+        let mut portable = portable;
+        portable.gates[0].id.push('!');
+        assert!(CommonCircuitData::from_portable(portable, &gate_serializer).is_err());
+        Ok(())
+    }
+}
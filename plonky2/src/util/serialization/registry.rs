@@ -0,0 +1,270 @@
+//! Composable gate and generator serializers.
+//!
+//! A serializer defined with `impl_gate_serializer` or `impl_generator_serializer` covers a fixed
+//! list of types. Libraries of gadgets can each export such a serializer for the gates and
+//! generators they define, and an application can merge them with
+//! [`GateSerializerRegistry`] and [`GeneratorSerializerRegistry`]:
+//!
+//! ```ignore
+//! let gate_serializer = GateSerializerRegistry::new()
+//!     .with(DefaultGateSerializer)
+//!     .with(U32GateSerializer);
+//! ```
+//!
+//! Each gate or generator is written by the first serializer of the registry supporting it,
+//! prefixed with that serializer's index. The encoding therefore depends on the order in which
+//! serializers are added, and differs from the encoding of any single serializer. Registries
+//! implement the serializer traits themselves, so they can be nested.
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt::{Debug, Formatter};
+
+use super::{Buffer, GateSerializer, IoError, IoResult, Read, WitnessGeneratorSerializer, Write};
+use crate::field::extension::Extendable;
+use crate::gates::gate::GateRef;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::WitnessGeneratorRef;
+use crate::plonk::circuit_data::CommonCircuitData;
+
+/// A [`GateSerializer`] combining several gate serializers.
+pub struct GateSerializerRegistry<F: RichField + Extendable<D>, const D: usize> {
+    serializers: Vec<Box<dyn GateSerializer<F, D>>>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> GateSerializerRegistry<F, D> {
+    pub fn new() -> Self {
+        Self {
+            serializers: Vec::new(),
+        }
+    }
+
+    /// Adds `serializer` to the registry. It is used for the gates not supported by the
+    /// serializers added before it.
+    pub fn with<S: GateSerializer<F, D> + 'static>(mut self, serializer: S) -> Self {
+        self.serializers.push(Box::new(serializer));
+        self
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Default for GateSerializerRegistry<F, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Debug for GateSerializerRegistry<F, D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GateSerializerRegistry")
+            .field("num_serializers", &self.serializers.len())
+            .finish()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> GateSerializer<F, D>
+    for GateSerializerRegistry<F, D>
+{
+    fn read_gate(
+        &self,
+        buf: &mut Buffer,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<GateRef<F, D>> {
+        let index = buf.read_u32()? as usize;
+        let serializer = self.serializers.get(index).ok_or(IoError)?;
+        serializer.read_gate(buf, common_data)
+    }
+
+    fn write_gate(
+        &self,
+        buf: &mut Vec<u8>,
+        gate: &GateRef<F, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<()> {
+        let Some(index) = self
+            .serializers
+            .iter()
+            .position(|s| s.supports_gate(gate, common_data))
+        else {
+            log::error!(
+                "attempted to serialize gate with id `{}` which is unsupported by this gate serializer registry",
+                gate.0.id()
+            );
+            return Err(IoError);
+        };
+        buf.write_u32(index as u32)?;
+        self.serializers[index].write_gate(buf, gate, common_data)
+    }
+
+    fn supports_gate(&self, gate: &GateRef<F, D>, common_data: &CommonCircuitData<F, D>) -> bool {
+        self.serializers
+            .iter()
+            .any(|s| s.supports_gate(gate, common_data))
+    }
+}
+
+/// A [`WitnessGeneratorSerializer`] combining several generator serializers.
+pub struct GeneratorSerializerRegistry<F: RichField + Extendable<D>, const D: usize> {
+    serializers: Vec<Box<dyn WitnessGeneratorSerializer<F, D>>>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> GeneratorSerializerRegistry<F, D> {
+    pub fn new() -> Self {
+        Self {
+            serializers: Vec::new(),
+        }
+    }
+
+    /// Adds `serializer` to the registry. It is used for the generators not supported by the
+    /// serializers added before it.
+    pub fn with<S: WitnessGeneratorSerializer<F, D> + 'static>(mut self, serializer: S) -> Self {
+        self.serializers.push(Box::new(serializer));
+        self
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Default for GeneratorSerializerRegistry<F, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Debug for GeneratorSerializerRegistry<F, D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GeneratorSerializerRegistry")
+            .field("num_serializers", &self.serializers.len())
+            .finish()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> WitnessGeneratorSerializer<F, D>
+    for GeneratorSerializerRegistry<F, D>
+{
+    fn read_generator(
+        &self,
+        buf: &mut Buffer,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<WitnessGeneratorRef<F, D>> {
+        let index = buf.read_u32()? as usize;
+        let serializer = self.serializers.get(index).ok_or(IoError)?;
+        serializer.read_generator(buf, common_data)
+    }
+
+    fn write_generator(
+        &self,
+        buf: &mut Vec<u8>,
+        generator: &WitnessGeneratorRef<F, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<()> {
+        let Some(index) = self
+            .serializers
+            .iter()
+            .position(|s| s.supports_generator(generator, common_data))
+        else {
+            log::error!(
+                "attempted to serialize generator with id {} which is unsupported by this generator serializer registry",
+                generator.0.id()
+            );
+            return Err(IoError);
+        };
+        buf.write_u32(index as u32)?;
+        self.serializers[index].write_generator(buf, generator, common_data)
+    }
+
+    fn supports_generator(
+        &self,
+        generator: &WitnessGeneratorRef<F, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> bool {
+        self.serializers
+            .iter()
+            .any(|s| s.supports_generator(generator, common_data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::Field;
+    use crate::gates::gate::Gate;
+    use crate::gates::noop::NoopGate;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::util::serialization::{DefaultGateSerializer, DefaultGeneratorSerializer};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// A gate serializer supporting only `NoopGate`.
+    struct NoopGateSerializer;
+
+    impl GateSerializer<F, D> for NoopGateSerializer {
+        fn read_gate(
+            &self,
+            buf: &mut Buffer,
+            common_data: &CommonCircuitData<F, D>,
+        ) -> IoResult<GateRef<F, D>> {
+            Ok(GateRef::new(<NoopGate as Gate<F, D>>::deserialize(
+                buf,
+                common_data,
+            )?))
+        }
+
+        fn write_gate(
+            &self,
+            buf: &mut Vec<u8>,
+            gate: &GateRef<F, D>,
+            common_data: &CommonCircuitData<F, D>,
+        ) -> IoResult<()> {
+            if !gate.0.as_any().is::<NoopGate>() {
+                return Err(IoError);
+            }
+            gate.0.serialize(buf, common_data)
+        }
+    }
+
+    #[test]
+    fn test_registry_round_trip() -> Result<()> {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_target();
+        let y = builder.mul(x, x);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+
+        let gate_serializer = GateSerializerRegistry::new()
+            .with(NoopGateSerializer)
+            .with(DefaultGateSerializer);
+        let generator_serializer =
+            GeneratorSerializerRegistry::new().with(DefaultGeneratorSerializer::<C, D>::default());
+        assert!(data
+            .unsupported_by_serializers(&gate_serializer, &generator_serializer)
+            .is_empty());
+
+        let bytes = data
+            .to_bytes(&gate_serializer, &generator_serializer)
+            .map_err(anyhow::Error::msg)?;
+        let data_from_bytes =
+            CircuitData::<F, C, D>::from_bytes(&bytes, &gate_serializer, &generator_serializer)
+                .map_err(anyhow::Error::msg)?;
+        assert_eq!(data_from_bytes, data);
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::TWO);
+        data_from_bytes.verify(data_from_bytes.prove(pw)?)?;
+
+        // Without the default serializers, only `NoopGate` is supported.
+        let gate_serializer = GateSerializerRegistry::new().with(NoopGateSerializer);
+        let generator_serializer = GeneratorSerializerRegistry::new();
+        let unsupported = data.unsupported_by_serializers(&gate_serializer, &generator_serializer);
+        assert!(!unsupported.is_empty());
+        assert!(unsupported.iter().all(|id| !id.starts_with("NoopGate")));
+        assert!(data
+            .to_bytes(&gate_serializer, &generator_serializer)
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use std::path::Path;
+use std::process::Command;
+
+use plonky2::plonk::circom_verifier::{generate_circom_input, generate_circom_verifier};
+
+#[cfg(feature = "cuda")]
+use crate::test_utils::init_cuda;
+use crate::verifier_fixture::wrapper_proof;
+#[cfg(feature = "cuda")]
+pub mod test_utils;
+pub mod verifier_fixture;
+
+/// Compiles `verifier.circom` in `dir` to WebAssembly with the `circom` found on `PATH`.
+fn compile(dir: &Path) {
+    let output = Command::new("circom")
+        .arg("verifier.circom")
+        .args(["--wasm", "--O1"])
+        .current_dir(dir)
+        .output()
+        .expect("circom must be installed to run this test");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Computes the witness of the compiled verifier for the input at `input`, and returns whether
+/// all the constraints are satisfied.
+fn generate_witness(dir: &Path, input: &Path) -> bool {
+    let js_dir = dir.join("verifier_js");
+    Command::new("node")
+        .arg(js_dir.join("generate_witness.js"))
+        .arg(js_dir.join("verifier.wasm"))
+        .arg(input)
+        .arg(dir.join("witness.wtns"))
+        .status()
+        .expect("node must be installed to run this test")
+        .success()
+}
+
+/// Compiles the generated verifier and checks that it accepts a real proof and rejects tampered
+/// ones. Needs `circom` and `node`, which CI doesn't install, so this is run by hand.
+#[test]
+#[ignore]
+fn test_circom_verifier() {
+    #[cfg(feature = "cuda")]
+    init_cuda();
+
+    let (data, proof) = wrapper_proof();
+
+    let dir = std::env::temp_dir().join(format!("plonky2_circom_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let source = generate_circom_verifier(&data.common, &data.verifier_only).unwrap();
+    std::fs::write(dir.join("verifier.circom"), source).unwrap();
+    compile(&dir);
+
+    let input = generate_circom_input(&proof);
+    let input_path = dir.join("input.json");
+    std::fs::write(&input_path, &input).unwrap();
+    assert!(generate_witness(&dir, &input_path), "valid proof rejected");
+
+    // Changing a public input or the proof of work witness must be rejected.
+    let tampered_path = dir.join("tampered.json");
+    for key in ["\"public_inputs\": [\"", "\"fri_pow_witness\": \""] {
+        let start = input.find(key).unwrap() + key.len();
+        let last = start + input[start..].find('"').unwrap() - 1;
+        let mut tampered = input.clone();
+        let digit = if &input[last..last + 1] == "1" {
+            "2"
+        } else {
+            "1"
+        };
+        tampered.replace_range(last..last + 1, digit);
+        std::fs::write(&tampered_path, tampered).unwrap();
+        assert!(
+            !generate_witness(&dir, &tampered_path),
+            "tampered proof accepted at {key}"
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::process::Command;
+
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::CircuitConfig;
+use plonky2::plonk::solidity_verifier::{encode_proof_calldata, generate_solidity_verifier};
+use revm::db::{CacheDB, EmptyDB};
+use revm::primitives::{
+    keccak256, Address, Bytes, ExecutionResult, Output, TransactTo, MAX_CODE_SIZE, U256,
+};
+use revm::Evm;
+
+#[cfg(feature = "cuda")]
+use crate::test_utils::init_cuda;
+use crate::verifier_fixture::{inner_circuit, wrapper_circuit, wrapper_proof, C, D, F};
+#[cfg(feature = "cuda")]
+pub mod test_utils;
+pub mod verifier_fixture;
+
+/// Compiles `source` with the `solc` found on `PATH` and returns the creation bytecode of every
+/// contract and library, keyed by fully qualified name, as hex strings with unlinked library
+/// placeholders.
+fn compile(source: &str) -> HashMap<String, String> {
+    let dir = std::env::temp_dir().join(format!("plonky2_solidity_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Verifier.sol");
+    std::fs::write(&path, source).unwrap();
+
+    let output = Command::new("solc")
+        .args(["--optimize", "--combined-json", "bin"])
+        .arg(&path)
+        .output()
+        .expect("solc must be installed to run this test");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    json["contracts"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .map(|(name, contract)| (name.clone(), contract["bin"].as_str().unwrap().to_string()))
+        .collect()
+}
+
+/// Deploys the contract `name` after deploying and linking the libraries it calls, and returns
+/// its address. Fails if any deployed code exceeds the EIP-170 size limit.
+fn deploy(
+    evm: &mut Evm<'_, (), CacheDB<EmptyDB>>,
+    bins: &HashMap<String, String>,
+    deployed: &mut HashMap<String, Address>,
+    name: &str,
+) -> Address {
+    if let Some(address) = deployed.get(name) {
+        return *address;
+    }
+    let mut bin = bins[name].clone();
+    for library in bins.keys() {
+        let hash = keccak256(library.as_bytes());
+        let placeholder = format!("__${}$__", &hex_encode(&hash[..])[..34]);
+        if bin.contains(&placeholder) {
+            let address = deploy(evm, bins, deployed, library);
+            bin = bin.replace(&placeholder, &hex_encode(&address[..]));
+        }
+    }
+    let address = match transact(evm, TransactTo::create(), hex_decode(&bin)) {
+        ExecutionResult::Success {
+            output: Output::Create(code, Some(address)),
+            ..
+        } => {
+            assert!(
+                code.len() <= MAX_CODE_SIZE,
+                "{name} has {} bytes of code",
+                code.len()
+            );
+            address
+        }
+        other => panic!("deployment of {name} failed: {other:?}"),
+    };
+    deployed.insert(name.to_string(), address);
+    address
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn hex_decode(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// ABI-encodes a call to `verify(bytes)`.
+fn verify_call(proof: &[u8]) -> Vec<u8> {
+    let mut data = keccak256(b"verify(bytes)")[..4].to_vec();
+    data.extend(U256::from(32).to_be_bytes::<32>());
+    data.extend(U256::from(proof.len()).to_be_bytes::<32>());
+    data.extend(proof);
+    data.resize(data.len() + (32 - proof.len() % 32) % 32, 0);
+    data
+}
+
+fn transact(
+    evm: &mut Evm<'_, (), CacheDB<EmptyDB>>,
+    to: TransactTo,
+    data: Vec<u8>,
+) -> ExecutionResult {
+    evm.tx_mut().transact_to = to;
+    evm.tx_mut().data = Bytes::from(data);
+    evm.transact_commit().unwrap()
+}
+
+/// Splits a generated source into its libraries and contracts, keyed by name.
+fn units(source: &str) -> HashMap<&str, &str> {
+    let starts = source
+        .match_indices("\nlibrary ")
+        .chain(source.match_indices("\ncontract "))
+        .map(|(i, _)| i + 1)
+        .collect::<Vec<_>>();
+    starts
+        .iter()
+        .map(|&start| {
+            let end = starts
+                .iter()
+                .copied()
+                .filter(|&i| i > start)
+                .min()
+                .unwrap_or(source.len());
+            let unit = &source[start..end];
+            let name = unit.split_whitespace().nth(1).unwrap();
+            (name, unit)
+        })
+        .collect()
+}
+
+/// Checks without `solc` that the verifier contract delegates gate evaluation and hashing to
+/// libraries, so that its size does not grow with the gates of the circuit.
+#[test]
+fn test_solidity_verifier_structure() {
+    let (inner, _) = inner_circuit();
+    let (data, _, _) = wrapper_circuit(&inner);
+    let source = generate_solidity_verifier(&data.common, &data.verifier_only).unwrap();
+    let contracts = units(&source);
+
+    let verifier = contracts["Plonky2Verifier"];
+    assert!(verifier.contains("PoseidonBN128Lib.permutation("));
+    assert!(!verifier.contains("PoseidonBN128Lib.context()"));
+    let gate_libraries = contracts
+        .iter()
+        .filter(|(_, unit)| unit.contains("function eval("))
+        .collect::<Vec<_>>();
+    assert_eq!(gate_libraries.len(), data.common.gates.len());
+    for (name, unit) in gate_libraries {
+        assert!(unit.contains("function evaluate(GatesUtilsLib.EvaluationVars memory ev"));
+        assert!(unit.contains(") public pure returns"));
+        assert!(verifier.contains(&format!("constraints = {name}.evaluate(ev, constraints);")));
+    }
+    assert!(!verifier.contains(".eval(ev, constraints)"));
+    assert!(!verifier.contains(".set_filter(ev)"));
+
+    // Compared to a circuit with a handful of gates, the verifier only grows by the calls to the
+    // additional gate libraries and by a few constants.
+    let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+    let x = builder.add_virtual_target();
+    let y = builder.exp_u64(x, 123);
+    builder.register_public_input(x);
+    builder.register_public_input(y);
+    let small = builder.build::<C>();
+    let small_source = generate_solidity_verifier(&small.common, &small.verifier_only).unwrap();
+    let small_verifier = units(&small_source)["Plonky2Verifier"];
+    assert!(verifier.len() <= small_verifier.len() + 128 * data.common.gates.len());
+}
+
+/// Compiles the generated verifier, deploys it with its libraries and checks that it accepts a
+/// real proof and rejects tampered ones. Needs `solc` on `PATH`, which CI installs.
+#[test]
+fn test_solidity_verifier() {
+    #[cfg(feature = "cuda")]
+    init_cuda();
+
+    let (data, proof) = wrapper_proof();
+
+    let source = generate_solidity_verifier(&data.common, &data.verifier_only).unwrap();
+    let bins = compile(&source);
+
+    // The default configuration enforces the EIP-170 contract size limit.
+    let mut evm = Evm::builder()
+        .with_db(CacheDB::new(EmptyDB::default()))
+        .modify_block_env(|block| block.gas_limit = U256::MAX)
+        .modify_tx_env(|tx| {
+            tx.caller = Address::with_last_byte(1);
+            tx.gas_limit = u64::MAX;
+        })
+        .build();
+
+    let verifier = bins
+        .keys()
+        .find(|name| name.ends_with(":Plonky2Verifier"))
+        .unwrap();
+    let address = deploy(&mut evm, &bins, &mut HashMap::new(), verifier);
+
+    let calldata = encode_proof_calldata(&proof);
+    match transact(&mut evm, TransactTo::Call(address), verify_call(&calldata)) {
+        ExecutionResult::Success {
+            output: Output::Call(out),
+            ..
+        } => assert_eq!(U256::from_be_slice(&out), U256::from(1)),
+        other => panic!("verification failed: {other:?}"),
+    }
+
+    // Flipping a bit in the public inputs, a cap, an opening or the proof of work must be
+    // rejected.
+    let num_elements = calldata.len() / 8;
+    for i in [0, 5, num_elements / 3, num_elements / 2, num_elements - 1] {
+        let mut tampered = calldata.clone();
+        tampered[8 * i + 7] ^= 1;
+        let result = transact(&mut evm, TransactTo::Call(address), verify_call(&tampered));
+        assert!(
+            !result.is_success(),
+            "tampered proof accepted at element {i}"
+        );
+    }
+}
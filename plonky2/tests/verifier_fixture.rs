@@ -0,0 +1,60 @@
+//! The circuit and proof shared by the tests of the generated circom and Solidity verifiers.
+
+use plonky2::field::types::Field;
+use plonky2::hash::poseidon_bn128::PoseidonBN128GoldilocksConfig;
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::{CircuitConfig, CircuitData, VerifierCircuitTarget};
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use plonky2::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+
+pub const D: usize = 2;
+pub type InnerC = PoseidonGoldilocksConfig;
+pub type C = PoseidonBN128GoldilocksConfig;
+pub type F = <C as GenericConfig<D>>::F;
+
+/// An inner circuit proven with the Goldilocks Poseidon config, and its input.
+pub fn inner_circuit() -> (CircuitData<F, InnerC, D>, Target) {
+    let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+    let x = builder.add_virtual_target();
+    let mut acc = x;
+    for _ in 0..100 {
+        acc = builder.mul_add(acc, x, x);
+    }
+    builder.register_public_input(x);
+    builder.register_public_input(acc);
+    (builder.build::<InnerC>(), x)
+}
+
+/// A circuit verifying a proof of `inner`, whose Merkle caps use the BN128 Poseidon hash.
+pub fn wrapper_circuit(
+    inner: &CircuitData<F, InnerC, D>,
+) -> (
+    CircuitData<F, C, D>,
+    ProofWithPublicInputsTarget<D>,
+    VerifierCircuitTarget,
+) {
+    let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+    let proof_target = builder.add_virtual_proof_with_pis::<InnerC>(&inner.common);
+    let vd_target = builder.add_virtual_verifier_data(inner.common.config.fri_config.cap_height);
+    builder.verify_proof::<InnerC>(&proof_target, &vd_target, &inner.common);
+    builder.register_public_inputs(&proof_target.public_inputs);
+    (builder.build::<C>(), proof_target, vd_target)
+}
+
+/// Returns the wrapper circuit and a proof of it, checked by the native verifier.
+pub fn wrapper_proof() -> (CircuitData<F, C, D>, ProofWithPublicInputs<F, C, D>) {
+    let (inner, x) = inner_circuit();
+    let mut pw = PartialWitness::new();
+    pw.set_target(x, F::from_canonical_u64(3));
+    let inner_proof = inner.prove(pw).unwrap();
+
+    let (data, proof_target, vd_target) = wrapper_circuit(&inner);
+    let mut pw = PartialWitness::new();
+    pw.set_proof_with_pis_target(&proof_target, &inner_proof);
+    pw.set_verifier_data_target(&vd_target, &inner.verifier_only);
+    let proof = data.prove(pw).unwrap();
+    data.verify(proof.clone()).unwrap();
+    (data, proof)
+}
@@ -61,7 +61,8 @@ const FIBONACCI_COLUMNS: usize = 2;
 const FIBONACCI_PUBLIC_INPUTS: usize = 3;
 
 impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FibonacciStark<F, D> {
-    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, FIBONACCI_COLUMNS, FIBONACCI_PUBLIC_INPUTS>
+    type EvaluationFrame<FE, P, const D2: usize>
+        = StarkFrame<P, P::Scalar, FIBONACCI_COLUMNS, FIBONACCI_PUBLIC_INPUTS>
     where
         FE: FieldExtension<D2, BaseField = F>,
         P: PackedField<Scalar = FE>;
@@ -141,6 +142,7 @@ mod tests {
     use plonky2::plonk::circuit_builder::CircuitBuilder;
     use plonky2::plonk::circuit_data::CircuitConfig;
     use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::plonk::verifier::VerificationError;
     use plonky2::util::timing::TimingTree;
 
     use crate::config::StarkConfig;
@@ -180,7 +182,75 @@ mod tests {
             &mut TimingTree::default(),
         )?;
 
-        verify_stark_proof(stark, proof, &config)
+        Ok(verify_stark_proof(stark, proof, &config)?)
+    }
+
+    #[test]
+    fn test_fibonacci_stark_serde() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+
+        let json = serde_json::to_string(&proof)?;
+        let proof: StarkProofWithPublicInputs<F, C, D> = serde_json::from_str(&json)?;
+        assert_eq!(serde_json::to_string(&proof)?, json);
+        Ok(verify_stark_proof(stark, proof, &config)?)
+    }
+
+    #[test]
+    fn test_fibonacci_stark_wrong_public_inputs() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+
+        let mut wrong_output = proof.clone();
+        wrong_output.public_inputs[2] += F::ONE;
+        assert!(matches!(
+            verify_stark_proof(stark, wrong_output, &config),
+            Err(VerificationError::QuotientMismatch { .. })
+        ));
+
+        let mut missing_output = proof;
+        missing_output.public_inputs.pop();
+        assert_eq!(
+            verify_stark_proof(stark, missing_output, &config),
+            Err(VerificationError::NumPublicInputs {
+                expected: 3,
+                actual: 2
+            })
+        );
+
+        Ok(())
     }
 
     #[test]
@@ -265,7 +335,7 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw)?;
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     fn init_logger() {
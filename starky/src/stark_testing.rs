@@ -138,7 +138,7 @@ pub fn test_stark_circuit_constraints<
 
     let data = builder.build::<C>();
     let proof = data.prove(pw)?;
-    data.verify(proof)
+    Ok(data.verify(proof)?)
 }
 
 fn random_low_degree_matrix<F: Field>(num_polys: usize, rate_bits: usize) -> Vec<Vec<F>> {
@@ -45,7 +45,8 @@ const COLUMNS: usize = 2;
 const PUBLIC_INPUTS: usize = 0;
 
 impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for UnconstrainedStark<F, D> {
-    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    type EvaluationFrame<FE, P, const D2: usize>
+        = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
     where
         FE: FieldExtension<D2, BaseField = F>,
         P: PackedField<Scalar = FE>;
@@ -115,7 +116,7 @@ mod tests {
         let trace = stark.generate_trace();
         let proof = prove::<F, C, S, D>(stark, &config, trace, &[], &mut TimingTree::default())?;
 
-        verify_stark_proof(stark, proof, &config)
+        Ok(verify_stark_proof(stark, proof, &config)?)
     }
 
     #[test]
@@ -192,7 +193,7 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw)?;
-        data.verify(proof)
+        Ok(data.verify(proof)?)
     }
 
     fn init_logger() {
@@ -5,7 +5,6 @@ use alloc::vec::Vec;
 use core::any::type_name;
 use core::iter::once;
 
-use anyhow::{anyhow, ensure, Result};
 use itertools::Itertools;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::types::Field;
@@ -15,6 +14,7 @@ use plonky2::hash::merkle_tree::MerkleCap;
 use plonky2::iop::challenger::Challenger;
 use plonky2::plonk::config::GenericConfig;
 use plonky2::plonk::plonk_common::reduce_with_powers;
+use plonky2::plonk::verifier::{ensure_shape, VerificationError};
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::ConstraintConsumer;
@@ -35,8 +35,8 @@ pub fn verify_stark_proof<
     stark: S,
     proof_with_pis: StarkProofWithPublicInputs<F, C, D>,
     config: &StarkConfig,
-) -> Result<()> {
-    ensure!(proof_with_pis.public_inputs.len() == S::PUBLIC_INPUTS);
+) -> Result<(), VerificationError> {
+    ensure_num_public_inputs::<F, S, D>(&proof_with_pis.public_inputs)?;
     let mut challenger = Challenger::<F, C::Hasher>::new();
 
     let challenges = proof_with_pis.get_challenges(&mut challenger, None, false, config);
@@ -62,7 +62,7 @@ pub fn verify_stark_proof_with_challenges<F, C, S, const D: usize>(
     ctl_vars: Option<&[CtlCheckVars<F, F::Extension, F::Extension, D>]>,
     public_inputs: &[F],
     config: &StarkConfig,
-) -> Result<()>
+) -> Result<(), VerificationError>
 where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -170,10 +170,9 @@ where
         .flat_map(|x| x.chunks(stark.quotient_degree_factor()))
         .enumerate()
     {
-        ensure!(
-            vanishing_polys_zeta[i] == z_h_zeta * reduce_with_powers(chunk, zeta_pow_deg),
-            "Mismatch between evaluation and opening of quotient polynomial"
-        );
+        if vanishing_polys_zeta[i] != z_h_zeta * reduce_with_powers(chunk, zeta_pow_deg) {
+            return Err(VerificationError::QuotientMismatch { challenge: i });
+        }
     }
 
     let merkle_caps = once(proof.trace_cap.clone())
@@ -214,7 +213,7 @@ fn validate_proof_shape<F, C, S, const D: usize>(
     config: &StarkConfig,
     num_ctl_helpers: usize,
     num_ctl_zs: usize,
-) -> anyhow::Result<()>
+) -> Result<(), VerificationError>
 where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -241,24 +240,28 @@ where
         quotient_polys,
     } = openings;
 
-    ensure!(public_inputs.len() == S::PUBLIC_INPUTS);
+    ensure_num_public_inputs::<F, S, D>(public_inputs)?;
 
     let fri_params = config.fri_params(degree_bits);
     let cap_height = fri_params.config.cap_height;
 
-    ensure!(trace_cap.height() == cap_height);
-    ensure!(
+    ensure_shape(trace_cap.height() == cap_height, "trace cap")?;
+    ensure_shape(
         quotient_polys_cap.is_none()
-            || quotient_polys_cap.as_ref().map(|q| q.height()) == Some(cap_height)
-    );
+            || quotient_polys_cap.as_ref().map(|q| q.height()) == Some(cap_height),
+        "quotient polys cap",
+    )?;
 
-    ensure!(local_values.len() == S::COLUMNS);
-    ensure!(next_values.len() == S::COLUMNS);
-    ensure!(if let Some(quotient_polys) = quotient_polys {
-        quotient_polys.len() == stark.num_quotient_polys(config)
-    } else {
-        stark.num_quotient_polys(config) == 0
-    });
+    ensure_shape(local_values.len() == S::COLUMNS, "local values")?;
+    ensure_shape(next_values.len() == S::COLUMNS, "next values")?;
+    ensure_shape(
+        if let Some(quotient_polys) = quotient_polys {
+            quotient_polys.len() == stark.num_quotient_polys(config)
+        } else {
+            stark.num_quotient_polys(config) == 0
+        },
+        "quotient polys",
+    )?;
 
     check_lookup_options::<F, C, S, D>(
         stark,
@@ -274,6 +277,22 @@ where
     Ok(())
 }
 
+fn ensure_num_public_inputs<F, S, const D: usize>(
+    public_inputs: &[F],
+) -> Result<(), VerificationError>
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+{
+    if public_inputs.len() != S::PUBLIC_INPUTS {
+        return Err(VerificationError::NumPublicInputs {
+            expected: S::PUBLIC_INPUTS,
+            actual: public_inputs.len(),
+        });
+    }
+    Ok(())
+}
+
 /// Evaluate the Lagrange polynomials `L_0` and `L_(n-1)` at a point `x`.
 /// `L_0(x) = (x^n - 1)/(n * (x - 1))`
 /// `L_(n-1)(x) = (x^n - 1)/(n * (g * x - 1))`, with `g` the first element of the subgroup.
@@ -297,7 +316,7 @@ fn check_lookup_options<F, C, S, const D: usize>(
     num_ctl_zs: usize,
     ctl_zs_first: &Option<Vec<F>>,
     config: &StarkConfig,
-) -> Result<()>
+) -> Result<(), VerificationError>
 where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -309,25 +328,31 @@ where
 
         let auxiliary_polys_cap = auxiliary_polys_cap
             .as_ref()
-            .ok_or_else(|| anyhow!("Missing auxiliary_polys_cap"))?;
+            .ok_or(VerificationError::MalformedProof("auxiliary polys cap"))?;
         let auxiliary_polys = auxiliary_polys
             .as_ref()
-            .ok_or_else(|| anyhow!("Missing auxiliary_polys"))?;
+            .ok_or(VerificationError::MalformedProof("auxiliary polys"))?;
         let auxiliary_polys_next = auxiliary_polys_next
             .as_ref()
-            .ok_or_else(|| anyhow!("Missing auxiliary_polys_next"))?;
+            .ok_or(VerificationError::MalformedProof("auxiliary polys next"))?;
 
         if let Some(ctl_zs_first) = ctl_zs_first {
-            ensure!(ctl_zs_first.len() == num_ctl_zs);
+            ensure_shape(ctl_zs_first.len() == num_ctl_zs, "ctl zs first")?;
         }
 
-        ensure!(auxiliary_polys_cap.height() == cap_height);
-        ensure!(auxiliary_polys.len() == num_auxiliary);
-        ensure!(auxiliary_polys_next.len() == num_auxiliary);
+        ensure_shape(
+            auxiliary_polys_cap.height() == cap_height,
+            "auxiliary polys cap",
+        )?;
+        ensure_shape(auxiliary_polys.len() == num_auxiliary, "auxiliary polys")?;
+        ensure_shape(
+            auxiliary_polys_next.len() == num_auxiliary,
+            "auxiliary polys next",
+        )?;
     } else {
-        ensure!(auxiliary_polys_cap.is_none());
-        ensure!(auxiliary_polys.is_none());
-        ensure!(auxiliary_polys_next.is_none());
+        ensure_shape(auxiliary_polys_cap.is_none(), "auxiliary polys cap")?;
+        ensure_shape(auxiliary_polys.is_none(), "auxiliary polys")?;
+        ensure_shape(auxiliary_polys_next.is_none(), "auxiliary polys next")?;
     }
 
     Ok(())
@@ -4,10 +4,7 @@ use alloc::{
     vec::Vec,
 };
 use core::marker::PhantomData;
-use plonky2::{
-    plonk::circuit_data::CommonCircuitData,
-    util::serialization::{Buffer, IoResult, Read, Write},
-};
+use plonky2::util::serialization::GeneratorSerialize;
 
 use plonky2::{
     field::extension::Extendable,
@@ -15,23 +12,52 @@ use plonky2::{
     iop::{
         generator::{GeneratedValues, SimpleGenerator},
         target::Target,
-        witness::{PartitionWitness, Witness},
+        variable::CircuitVariable,
+        witness::{PartitionWitness, Witness, WitnessWrite},
     },
     plonk::circuit_builder::CircuitBuilder,
 };
 
 use crate::{
+    gadgets::range_check::range_check_u32_circuit,
     gates::{
-        add_many_u32::U32AddManyGate, arithmetic_u32::U32ArithmeticGate,
+        add_many_u32::U32AddManyGate,
+        arithmetic_u32::U32ArithmeticGate,
+        bitwise_u32::{BitwiseOp, U32BitwiseGate},
+        comparison::ComparisonGate,
         subtraction_u32::U32SubtractionGate,
     },
-    serialization::{ReadU32, WriteU32},
     witness::GeneratedValuesU32,
 };
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct U32Target(pub Target);
 
+impl CircuitVariable for U32Target {
+    type Value<F: RichField> = u32;
+
+    fn num_targets() -> usize {
+        1
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        vec![self.0]
+    }
+
+    fn from_targets(targets: &[Target]) -> Self {
+        U32Target(Target::from_targets(targets))
+    }
+
+    fn set_witness<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &u32) {
+        witness.set_target(self.0, F::from_canonical_u32(*value));
+    }
+
+    fn get_witness<F: RichField, W: Witness<F>>(&self, witness: &W) -> u32 {
+        let value = witness.get_target(self.0).to_canonical_u64();
+        u32::try_from(value).expect("not a u32")
+    }
+}
+
 pub trait CircuitBuilderU32<F: RichField + Extendable<D>, const D: usize> {
     fn add_virtual_u32_target(&mut self) -> U32Target;
 
@@ -75,6 +101,29 @@ pub trait CircuitBuilderU32<F: RichField + Extendable<D>, const D: usize> {
 
     // Returns x - y - borrow, as a pair (result, borrow), where borrow is 0 or 1 depending on whether borrowing from the next digit is required (iff y + borrow > x).
     fn sub_u32(&mut self, x: U32Target, y: U32Target, borrow: U32Target) -> (U32Target, U32Target);
+
+    /// Returns `(x / y, x % y)`. If `y` is zero, the circuit is unsatisfiable.
+    fn div_rem_u32(&mut self, x: U32Target, y: U32Target) -> (U32Target, U32Target);
+
+    fn and_u32(&mut self, x: U32Target, y: U32Target) -> U32Target;
+
+    fn or_u32(&mut self, x: U32Target, y: U32Target) -> U32Target;
+
+    fn xor_u32(&mut self, x: U32Target, y: U32Target) -> U32Target;
+
+    fn not_u32(&mut self, x: U32Target) -> U32Target;
+
+    /// Returns `x << n`, discarding the bits shifted out. Panics if `n >= 32`.
+    fn shl_u32(&mut self, x: U32Target, n: usize) -> U32Target;
+
+    /// Returns `x >> n`. Panics if `n >= 32`.
+    fn shr_u32(&mut self, x: U32Target, n: usize) -> U32Target;
+
+    /// Returns `x` rotated left by `n` bits.
+    fn rotl_u32(&mut self, x: U32Target, n: usize) -> U32Target;
+
+    /// Returns `x` rotated right by `n` bits.
+    fn rotr_u32(&mut self, x: U32Target, n: usize) -> U32Target;
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderU32<F, D>
@@ -231,14 +280,137 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderU32<F, D>
 
         (output_result, output_borrow)
     }
+
+    /// Returns `(x / y, x % y)`. If `y` is zero, the circuit is unsatisfiable.
+    fn div_rem_u32(&mut self, x: U32Target, y: U32Target) -> (U32Target, U32Target) {
+        if let (Some(x_const), Some(y_const)) =
+            (self.target_as_constant(x.0), self.target_as_constant(y.0))
+        {
+            let (x_const, y_const) = (x_const.to_canonical_u64(), y_const.to_canonical_u64());
+            if y_const != 0 {
+                let quotient = self.constant_u32((x_const / y_const) as u32);
+                let remainder = self.constant_u32((x_const % y_const) as u32);
+                return (quotient, remainder);
+            }
+        }
+
+        let quotient = self.add_virtual_u32_target();
+        let remainder = self.add_virtual_u32_target();
+        self.add_simple_generator(U32DivRemGenerator::<F, D> {
+            x,
+            y,
+            quotient,
+            remainder,
+            _phantom: PhantomData,
+        });
+
+        // The comparison below range-checks the remainder, but the quotient needs its own check
+        // so that `quotient * y + remainder` can't wrap around the field.
+        range_check_u32_circuit(self, vec![quotient]);
+        let (low, high) = self.mul_add_u32(quotient, y, remainder);
+        self.connect_u32(low, x);
+        self.assert_zero_u32(high);
+
+        // `remainder < y` iff `y <= remainder` is false. This can't hold when `y` is zero.
+        let gate = ComparisonGate::new(32, 16);
+        let row = self.add_gate(gate.clone(), vec![]);
+        self.connect(Target::wire(row, gate.wire_first_input()), y.0);
+        self.connect(Target::wire(row, gate.wire_second_input()), remainder.0);
+        self.assert_zero(Target::wire(row, gate.wire_result_bool()));
+
+        (quotient, remainder)
+    }
+
+    fn and_u32(&mut self, x: U32Target, y: U32Target) -> U32Target {
+        bitwise_u32(self, BitwiseOp::And, x, y)
+    }
+
+    fn or_u32(&mut self, x: U32Target, y: U32Target) -> U32Target {
+        bitwise_u32(self, BitwiseOp::Or, x, y)
+    }
+
+    fn xor_u32(&mut self, x: U32Target, y: U32Target) -> U32Target {
+        bitwise_u32(self, BitwiseOp::Xor, x, y)
+    }
+
+    fn not_u32(&mut self, x: U32Target) -> U32Target {
+        // Since `x` is a u32, `u32::MAX - x` never underflows.
+        let max = self.constant(F::from_canonical_u32(u32::MAX));
+        U32Target(self.sub(max, x.0))
+    }
+
+    /// Returns `x << n`, discarding the bits shifted out. Panics if `n >= 32`.
+    fn shl_u32(&mut self, x: U32Target, n: usize) -> U32Target {
+        assert!(n < 32, "shift amount must be less than 32");
+        if n == 0 {
+            return x;
+        }
+
+        let power = self.constant_u32(1 << n);
+        let (low, _) = self.mul_u32(x, power);
+        low
+    }
+
+    /// Returns `x >> n`. Panics if `n >= 32`.
+    fn shr_u32(&mut self, x: U32Target, n: usize) -> U32Target {
+        assert!(n < 32, "shift amount must be less than 32");
+        if n == 0 {
+            return x;
+        }
+
+        let power = self.constant_u32(1 << (32 - n));
+        let (_, high) = self.mul_u32(x, power);
+        high
+    }
+
+    /// Returns `x` rotated left by `n` bits.
+    fn rotl_u32(&mut self, x: U32Target, n: usize) -> U32Target {
+        let n = n % 32;
+        if n == 0 {
+            return x;
+        }
+
+        // The low half has its bottom `n` bits cleared and the high half fits in `n` bits, so
+        // their sum is the rotation.
+        let power = self.constant_u32(1 << n);
+        let (low, high) = self.mul_u32(x, power);
+        U32Target(self.add(low.0, high.0))
+    }
+
+    /// Returns `x` rotated right by `n` bits.
+    fn rotr_u32(&mut self, x: U32Target, n: usize) -> U32Target {
+        self.rotl_u32(x, (32 - n % 32) % 32)
+    }
 }
 
-#[derive(Debug, Clone)]
-struct SplitToU32Generator<F: RichField + Extendable<D>, const D: usize> {
-    x: Target,
-    low: U32Target,
-    high: U32Target,
-    _phantom: PhantomData<F>,
+fn bitwise_u32<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    op: BitwiseOp,
+    x: U32Target,
+    y: U32Target,
+) -> U32Target {
+    if let (Some(x_const), Some(y_const)) =
+        (builder.target_as_constant(x.0), builder.target_as_constant(y.0))
+    {
+        let result = op.apply(x_const.to_canonical_u64() as u32, y_const.to_canonical_u64() as u32);
+        return builder.constant_u32(result);
+    }
+
+    let gate = U32BitwiseGate::<F, D>::new_from_config(&builder.config, op);
+    let (row, copy) = builder.find_slot(gate, &[], &[]);
+
+    builder.connect(Target::wire(row, gate.wire_ith_input_x(copy)), x.0);
+    builder.connect(Target::wire(row, gate.wire_ith_input_y(copy)), y.0);
+
+    U32Target(Target::wire(row, gate.wire_ith_output(copy)))
+}
+
+#[derive(Debug, Clone, Default, GeneratorSerialize)]
+pub(crate) struct SplitToU32Generator<F: RichField + Extendable<D>, const D: usize> {
+    pub(crate) x: Target,
+    pub(crate) low: U32Target,
+    pub(crate) high: U32Target,
+    pub(crate) _phantom: PhantomData<F>,
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
@@ -248,18 +420,7 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
         "SplitToU32Generator".to_string()
     }
 
-    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
-        dst.write_target(self.x)?;
-        dst.write_target_u32(self.low)?;
-        dst.write_target_u32(self.high)
-    }
-
-    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let x = src.read_target()?;
-        let low = src.read_target_u32()?;
-        let high = src.read_target_u32()?;
-        Ok(Self { x, low, high, _phantom: PhantomData })
-    }
+    plonky2::derived_serialization!();
 
     fn dependencies(&self) -> Vec<Target> {
         vec![self.x]
@@ -276,10 +437,46 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
     }
 }
 
+#[derive(Debug, Clone, Default, GeneratorSerialize)]
+pub(crate) struct U32DivRemGenerator<F: RichField + Extendable<D>, const D: usize> {
+    x: U32Target,
+    y: U32Target,
+    quotient: U32Target,
+    remainder: U32Target,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for U32DivRemGenerator<F, D>
+{
+    fn id(&self) -> String {
+        "U32DivRemGenerator".to_string()
+    }
+
+    plonky2::derived_serialization!();
+
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.x.0, self.y.0]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let x = witness.get_target(self.x.0).to_canonical_u64() as u32;
+        let y = witness.get_target(self.y.0).to_canonical_u64() as u32;
+        // Division by zero leaves the circuit unsatisfiable, since no remainder is less than zero;
+        // filling in `(0, x)` lets that surface as a failed constraint.
+        let quotient = x.checked_div(y).unwrap_or(0);
+        let remainder = x.checked_rem(y).unwrap_or(x);
+
+        out_buffer.set_u32_target(self.quotient, quotient);
+        out_buffer.set_u32_target(self.remainder, remainder);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
     use plonky2::{
+        field::types::Field,
         iop::witness::PartialWitness,
         plonk::{
             circuit_data::CircuitConfig,
@@ -289,6 +486,7 @@ mod tests {
     use rand::{rngs::OsRng, Rng};
 
     use super::*;
+    use crate::witness::WitnessU32;
 
     #[test]
     pub fn test_add_many_u32s() -> Result<()> {
@@ -321,6 +519,118 @@ mod tests {
 
         let data = builder.build::<C>();
         let proof = data.prove(pw).unwrap();
-        data.verify(proof)
+        Ok(data.verify(proof)?)
+    }
+
+    #[test]
+    pub fn test_div_rem_u32() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut rng = OsRng;
+        let x_value: u32 = rng.gen();
+        let cases = [(x_value, rng.gen_range(1..=u16::MAX as u32)), (x_value, 1), (5, u32::MAX)];
+        for (x_value, y_value) in cases {
+            let x = builder.add_virtual_u32_target();
+            let y = builder.add_virtual_u32_target();
+            pw.set_u32_target(x, x_value);
+            pw.set_u32_target(y, y_value);
+
+            let (quotient, remainder) = builder.div_rem_u32(x, y);
+            let expected_quotient = builder.constant_u32(x_value / y_value);
+            let expected_remainder = builder.constant_u32(x_value % y_value);
+            builder.connect_u32(quotient, expected_quotient);
+            builder.connect_u32(remainder, expected_remainder);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+        Ok(data.verify(proof)?)
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_div_rem_u32_by_zero() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_u32_target();
+        let y = builder.add_virtual_u32_target();
+        pw.set_u32_target(x, 12345);
+        pw.set_u32_target(y, 0);
+        builder.div_rem_u32(x, y);
+
+        let data = builder.build::<C>();
+        data.prove(pw).unwrap();
+    }
+
+    #[test]
+    pub fn test_bitwise_u32() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut rng = OsRng;
+        let x_value: u32 = rng.gen();
+        let y_value: u32 = rng.gen();
+
+        let x = builder.add_virtual_u32_target();
+        let y = builder.add_virtual_u32_target();
+        pw.set_u32_target(x, x_value);
+        pw.set_u32_target(y, y_value);
+
+        let mut results = vec![
+            (builder.and_u32(x, y), x_value & y_value),
+            (builder.or_u32(x, y), x_value | y_value),
+            (builder.xor_u32(x, y), x_value ^ y_value),
+            (builder.not_u32(x), !x_value),
+        ];
+        for n in 0..32 {
+            results.push((builder.shl_u32(x, n), x_value << n));
+            results.push((builder.shr_u32(x, n), x_value >> n));
+        }
+        for n in 0..64 {
+            results.push((builder.rotl_u32(x, n), x_value.rotate_left(n as u32)));
+            results.push((builder.rotr_u32(x, n), x_value.rotate_right(n as u32)));
+        }
+
+        // A constant operand still goes through the gate, while two constant operands are folded.
+        let y_const = builder.constant_u32(y_value);
+        results.push((builder.xor_u32(x, y_const), x_value ^ y_value));
+        let x_const = builder.constant_u32(x_value);
+        for (op, expected) in [
+            (BitwiseOp::And, x_value & y_value),
+            (BitwiseOp::Or, x_value | y_value),
+            (BitwiseOp::Xor, x_value ^ y_value),
+        ] {
+            let result = bitwise_u32(&mut builder, op, x_const, y_const);
+            assert_eq!(builder.target_as_constant(result.0), Some(F::from_canonical_u32(expected)));
+        }
+
+        for (result, expected) in results {
+            let expected = builder.constant_u32(expected);
+            builder.connect_u32(result, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+        Ok(data.verify(proof)?)
     }
 }
@@ -0,0 +1,428 @@
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::marker::PhantomData;
+use plonky2::util::serialization::GeneratorSerialize;
+
+use plonky2::{
+    field::extension::Extendable,
+    hash::hash_types::RichField,
+    iop::{
+        generator::{GeneratedValues, SimpleGenerator},
+        target::{BoolTarget, Target},
+        variable::CircuitVariable,
+        witness::{PartitionWitness, Witness, WitnessWrite},
+    },
+    plonk::circuit_builder::CircuitBuilder,
+};
+
+use crate::{
+    gadgets::{
+        arithmetic_u32::{CircuitBuilderU32, SplitToU32Generator, U32Target},
+        multiple_comparison::list_le_u32_circuit,
+        range_check::range_check_u32_circuit,
+    },
+    witness::{GeneratedValuesU64, WitnessU64},
+};
+
+/// A 64-bit unsigned integer, made of two little-endian 32-bit limbs.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct U64Target {
+    pub limbs: [U32Target; 2],
+}
+
+impl CircuitVariable for U64Target {
+    type Value<F: RichField> = u64;
+
+    fn num_targets() -> usize {
+        2
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        vec![self.limbs[0].0, self.limbs[1].0]
+    }
+
+    fn from_targets(targets: &[Target]) -> Self {
+        assert_eq!(targets.len(), 2);
+        U64Target { limbs: [U32Target(targets[0]), U32Target(targets[1])] }
+    }
+
+    fn set_witness<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &u64) {
+        self.limbs[0].set_witness(witness, &(*value as u32));
+        self.limbs[1].set_witness(witness, &((*value >> 32) as u32));
+    }
+
+    fn get_witness<F: RichField, W: Witness<F>>(&self, witness: &W) -> u64 {
+        let low = self.limbs[0].get_witness(witness);
+        let high = self.limbs[1].get_witness(witness);
+        ((high as u64) << 32) | low as u64
+    }
+}
+
+pub trait CircuitBuilderU64<F: RichField + Extendable<D>, const D: usize> {
+    fn add_virtual_u64_target(&mut self) -> U64Target;
+
+    fn add_virtual_u64_targets(&mut self, n: usize) -> Vec<U64Target>;
+
+    fn constant_u64(&mut self, c: u64) -> U64Target;
+
+    fn zero_u64(&mut self) -> U64Target;
+
+    fn one_u64(&mut self) -> U64Target;
+
+    fn connect_u64(&mut self, x: U64Target, y: U64Target);
+
+    /// Decomposes the canonical representative of `x` into a `U64Target`, range-checking both
+    /// limbs.
+    fn target_to_u64(&mut self, x: Target) -> U64Target;
+
+    /// Returns `x` as a field element. As not every u64 fits in the field, this asserts that `x`
+    /// is less than the field order.
+    fn u64_to_target(&mut self, x: U64Target) -> Target;
+
+    /// Returns `x + y + carry`, as a pair (result, carry). The output carry is 0 or 1 if the input
+    /// carry is.
+    fn add_u64(&mut self, x: U64Target, y: U64Target, carry: U32Target) -> (U64Target, U32Target);
+
+    /// Returns `x - y - borrow`, as a pair (result, borrow), where the output borrow is 1 iff
+    /// `y + borrow > x`.
+    fn sub_u64(&mut self, x: U64Target, y: U64Target, borrow: U32Target) -> (U64Target, U32Target);
+
+    /// Returns the 128-bit product `x * y`, as a pair (low, high).
+    fn mul_u64(&mut self, x: U64Target, y: U64Target) -> (U64Target, U64Target);
+
+    /// Returns true if `x <= y`.
+    fn le_u64(&mut self, x: U64Target, y: U64Target) -> BoolTarget;
+
+    /// Returns true if `x < y`.
+    fn lt_u64(&mut self, x: U64Target, y: U64Target) -> BoolTarget;
+
+    /// Returns `(x / y, x % y)`. The circuit is unsatisfiable if `y` is zero.
+    fn div_rem_u64(&mut self, x: U64Target, y: U64Target) -> (U64Target, U64Target);
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderU64<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn add_virtual_u64_target(&mut self) -> U64Target {
+        U64Target { limbs: [self.add_virtual_u32_target(), self.add_virtual_u32_target()] }
+    }
+
+    fn add_virtual_u64_targets(&mut self, n: usize) -> Vec<U64Target> {
+        (0..n).map(|_| self.add_virtual_u64_target()).collect()
+    }
+
+    fn constant_u64(&mut self, c: u64) -> U64Target {
+        U64Target { limbs: [self.constant_u32(c as u32), self.constant_u32((c >> 32) as u32)] }
+    }
+
+    fn zero_u64(&mut self) -> U64Target {
+        self.constant_u64(0)
+    }
+
+    fn one_u64(&mut self) -> U64Target {
+        self.constant_u64(1)
+    }
+
+    fn connect_u64(&mut self, x: U64Target, y: U64Target) {
+        self.connect_u32(x.limbs[0], y.limbs[0]);
+        self.connect_u32(x.limbs[1], y.limbs[1]);
+    }
+
+    /// Decomposes the canonical representative of `x` into a `U64Target`, range-checking both
+    /// limbs.
+    fn target_to_u64(&mut self, x: Target) -> U64Target {
+        let low = self.add_virtual_u32_target();
+        let high = self.add_virtual_u32_target();
+        self.add_simple_generator(SplitToU32Generator::<F, D> {
+            x,
+            low,
+            high,
+            _phantom: PhantomData,
+        });
+        range_check_u32_circuit(self, vec![low, high]);
+
+        let result = U64Target { limbs: [low, high] };
+        let recombined = self.u64_to_target(result);
+        self.connect(recombined, x);
+
+        result
+    }
+
+    /// Returns `x` as a field element. As not every u64 fits in the field, this asserts that `x`
+    /// is less than the field order.
+    fn u64_to_target(&mut self, x: U64Target) -> Target {
+        let [low, high] = x.limbs;
+
+        // The field order is `2^64 - 2^32 + 1`, so `x` overflows exactly when its high limb is
+        // `2^32 - 1` and its low limb is nonzero.
+        let max_high = self.constant(F::from_canonical_u32(u32::MAX));
+        let high_is_max = self.is_equal(high.0, max_high);
+        let overflow = self.mul(high_is_max.target, low.0);
+        self.assert_zero(overflow);
+
+        self.mul_const_add(F::from_canonical_u64(1 << 32), high.0, low.0)
+    }
+
+    /// Returns `x + y + carry`, as a pair (result, carry). The output carry is 0 or 1 if the input
+    /// carry is.
+    fn add_u64(&mut self, x: U64Target, y: U64Target, carry: U32Target) -> (U64Target, U32Target) {
+        let (low, carry) = self.add_u32s_with_carry(&[x.limbs[0], y.limbs[0]], carry);
+        let (high, carry) = self.add_u32s_with_carry(&[x.limbs[1], y.limbs[1]], carry);
+
+        (U64Target { limbs: [low, high] }, carry)
+    }
+
+    /// Returns `x - y - borrow`, as a pair (result, borrow), where the output borrow is 1 iff
+    /// `y + borrow > x`.
+    fn sub_u64(&mut self, x: U64Target, y: U64Target, borrow: U32Target) -> (U64Target, U32Target) {
+        let (low, borrow) = self.sub_u32(x.limbs[0], y.limbs[0], borrow);
+        let (high, borrow) = self.sub_u32(x.limbs[1], y.limbs[1], borrow);
+
+        (U64Target { limbs: [low, high] }, borrow)
+    }
+
+    /// Returns the 128-bit product `x * y`, as a pair (low, high).
+    fn mul_u64(&mut self, x: U64Target, y: U64Target) -> (U64Target, U64Target) {
+        let [x0, x1] = x.limbs;
+        let [y0, y1] = y.limbs;
+
+        let (limb0, p00_high) = self.mul_u32(x0, y0);
+        let (p01_low, p01_high) = self.mul_u32(x0, y1);
+        let (p10_low, p10_high) = self.mul_u32(x1, y0);
+        let (p11_low, p11_high) = self.mul_u32(x1, y1);
+
+        let zero = self.zero_u32();
+        let (limb1, carry) = self.add_u32s_with_carry(&[p00_high, p01_low, p10_low], zero);
+        let (limb2, carry) = self.add_u32s_with_carry(&[p01_high, p10_high, p11_low], carry);
+        // The product is less than `2^128`, so there is no carry out of the top limb.
+        let (limb3, _) = self.add_u32(p11_high, carry);
+
+        (U64Target { limbs: [limb0, limb1] }, U64Target { limbs: [limb2, limb3] })
+    }
+
+    /// Returns true if `x <= y`.
+    fn le_u64(&mut self, x: U64Target, y: U64Target) -> BoolTarget {
+        list_le_u32_circuit(self, x.limbs.to_vec(), y.limbs.to_vec())
+    }
+
+    /// Returns true if `x < y`.
+    fn lt_u64(&mut self, x: U64Target, y: U64Target) -> BoolTarget {
+        let y_le_x = self.le_u64(y, x);
+        self.not(y_le_x)
+    }
+
+    /// Returns `(x / y, x % y)`. The circuit is unsatisfiable if `y` is zero.
+    fn div_rem_u64(&mut self, x: U64Target, y: U64Target) -> (U64Target, U64Target) {
+        let quotient = self.add_virtual_u64_target();
+        let remainder = self.add_virtual_u64_target();
+
+        self.add_simple_generator(U64DivRemGenerator::<F, D> {
+            x,
+            y,
+            quotient,
+            remainder,
+            _phantom: PhantomData,
+        });
+
+        // The remainder is range-checked by the comparison below.
+        range_check_u32_circuit(self, quotient.limbs.to_vec());
+
+        let (product_low, product_high) = self.mul_u64(quotient, y);
+        let zero = self.zero_u64();
+        self.connect_u64(product_high, zero);
+        let zero = self.zero_u32();
+        let (sum, carry) = self.add_u64(product_low, remainder, zero);
+        self.assert_zero_u32(carry);
+        self.connect_u64(sum, x);
+
+        let remainder_lt_y = self.lt_u64(remainder, y);
+        self.assert_one(remainder_lt_y.target);
+
+        (quotient, remainder)
+    }
+}
+
+#[derive(Debug, Clone, Default, GeneratorSerialize)]
+pub(crate) struct U64DivRemGenerator<F: RichField + Extendable<D>, const D: usize> {
+    x: U64Target,
+    y: U64Target,
+    quotient: U64Target,
+    remainder: U64Target,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for U64DivRemGenerator<F, D>
+{
+    fn id(&self) -> String {
+        "U64DivRemGenerator".to_string()
+    }
+
+    plonky2::derived_serialization!();
+
+    fn dependencies(&self) -> Vec<Target> {
+        let mut deps = self.x.targets();
+        deps.extend(self.y.targets());
+        deps
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let x = witness.get_u64_target(self.x);
+        let y = witness.get_u64_target(self.y);
+        // Division by zero leaves the circuit unsatisfiable, since no remainder is less than zero;
+        // filling in `(0, x)` lets that surface as a failed constraint.
+        let quotient = x.checked_div(y).unwrap_or(0);
+        let remainder = x.checked_rem(y).unwrap_or(x);
+
+        out_buffer.set_u64_target(self.quotient, quotient);
+        out_buffer.set_u64_target(self.remainder, remainder);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::{
+        field::types::{Field, Field64, PrimeField64},
+        iop::witness::PartialWitness,
+        plonk::{
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+    };
+    use rand::{rngs::OsRng, Rng};
+
+    use super::*;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_u64_arithmetic() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut rng = OsRng;
+        let x_value: u64 = rng.gen();
+        let y_value: u64 = rng.gen();
+
+        let x = builder.add_virtual_u64_target();
+        let y = builder.add_virtual_u64_target();
+        pw.set_u64_target(x, x_value);
+        pw.set_u64_target(y, y_value);
+
+        let zero = builder.zero_u32();
+        let (sum, carry) = builder.add_u64(x, y, zero);
+        let (expected_sum, expected_carry) = x_value.overflowing_add(y_value);
+        let expected_sum = builder.constant_u64(expected_sum);
+        let expected_carry = builder.constant_u32(expected_carry as u32);
+        builder.connect_u64(sum, expected_sum);
+        builder.connect_u32(carry, expected_carry);
+
+        let (diff, borrow) = builder.sub_u64(x, y, zero);
+        let (expected_diff, expected_borrow) = x_value.overflowing_sub(y_value);
+        let expected_diff = builder.constant_u64(expected_diff);
+        let expected_borrow = builder.constant_u32(expected_borrow as u32);
+        builder.connect_u64(diff, expected_diff);
+        builder.connect_u32(borrow, expected_borrow);
+
+        let (low, high) = builder.mul_u64(x, y);
+        let product = x_value as u128 * y_value as u128;
+        let expected_low = builder.constant_u64(product as u64);
+        let expected_high = builder.constant_u64((product >> 64) as u64);
+        builder.connect_u64(low, expected_low);
+        builder.connect_u64(high, expected_high);
+
+        let le = builder.le_u64(x, y);
+        let expected_le = builder.constant_bool(x_value <= y_value);
+        builder.connect(le.target, expected_le.target);
+        let lt = builder.lt_u64(x, x);
+        let expected_lt = builder._false();
+        builder.connect(lt.target, expected_lt.target);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+        Ok(data.verify(proof)?)
+    }
+
+    #[test]
+    fn test_div_rem_u64() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut rng = OsRng;
+        let small_y: u64 = rng.gen_range(1..=u32::MAX as u64);
+        let large_y: u64 = rng.gen_range(1 << 32..=u64::MAX);
+        let cases = [
+            (rng.gen(), small_y),
+            (rng.gen_range(large_y..=u64::MAX), large_y),
+            (u64::MAX, (1 << 32) + 1),
+            // The divisor exceeds the dividend, in both the low and the high limb.
+            (rng.gen_range(0..small_y), small_y),
+            (rng.gen_range(0..large_y), large_y),
+            (large_y, large_y),
+        ];
+        for (x_value, y_value) in cases {
+            let x = builder.add_virtual_u64_target();
+            let y = builder.add_virtual_u64_target();
+            pw.set_u64_target(x, x_value);
+            pw.set_u64_target(y, y_value);
+
+            let (quotient, remainder) = builder.div_rem_u64(x, y);
+            let expected_quotient = builder.constant_u64(x_value / y_value);
+            let expected_remainder = builder.constant_u64(x_value % y_value);
+            builder.connect_u64(quotient, expected_quotient);
+            builder.connect_u64(remainder, expected_remainder);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+        Ok(data.verify(proof)?)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_div_rem_u64_by_zero() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let x = builder.add_virtual_u64_target();
+        let y = builder.zero_u64();
+        pw.set_u64_target(x, 12345);
+        builder.div_rem_u64(x, y);
+
+        let data = builder.build::<C>();
+        data.prove(pw).unwrap();
+    }
+
+    #[test]
+    fn test_target_to_u64() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let values = [F::ZERO, F::NEG_ONE, F::from_canonical_u64(OsRng.gen_range(0..F::ORDER))];
+        for value in values {
+            let x = builder.add_virtual_target();
+            pw.set_target(x, value);
+
+            let x_u64 = builder.target_to_u64(x);
+            let expected = builder.constant_u64(value.to_canonical_u64());
+            builder.connect_u64(x_u64, expected);
+
+            let roundtrip = builder.u64_to_target(x_u64);
+            builder.connect(roundtrip, x);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+        Ok(data.verify(proof)?)
+    }
+}
@@ -0,0 +1,667 @@
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{array, marker::PhantomData};
+
+use plonky2::{
+    field::extension::Extendable,
+    gadgets::dynamic_array::BytesTarget,
+    hash::hash_types::RichField,
+    iop::{
+        generator::{GeneratedValues, SimpleGenerator},
+        target::{BoolTarget, Target},
+        witness::{PartitionWitness, Witness, WitnessWrite},
+    },
+    plonk::circuit_builder::CircuitBuilder,
+    util::serialization::GeneratorSerialize,
+};
+
+use crate::gadgets::arithmetic_u32::U32Target;
+
+/// The number of bytes absorbed per permutation by Keccak-256.
+const RATE: usize = 136;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The left rotations of the `ρ` step, for the lane at `(x, y)` at index `x + 5 * y`.
+const ROTATIONS: [usize; 25] =
+    [0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14];
+
+/// Keccak gadgets. The state of the permutation is kept as bytes, and the bitwise operations are
+/// done with lookups into tables of byte pairs, which are added to the circuit on first use.
+///
+/// Digests are returned as bytes. The `keccak_*` methods mirror `KeccakHash<N>`, which is used by
+/// `KeccakGoldilocksConfig`, so Merkle proofs of that config can be checked in a circuit.
+///
+/// Recursively verifying whole `KeccakGoldilocksConfig` proofs is not supported:
+/// `CircuitBuilder::verify_proof` requires an `AlgebraicHasher`, whose digests are `HashOut`s, and
+/// the challenger and FRI verifier would first need to be made generic over the in-circuit hasher.
+pub trait CircuitBuilderKeccak<F: RichField + Extendable<D>, const D: usize> {
+    /// Applies the Keccak-f[1600] permutation. The lane at `(x, y)` is at index `x + 5 * y`, and
+    /// is given by its low and high 32-bit halves, which are range-checked.
+    fn keccak_f(&mut self, state: &[[U32Target; 2]; 25]) -> [[U32Target; 2]; 25];
+
+    /// Ethereum's Keccak-256 hash of `message`, whose bytes are range-checked.
+    fn keccak256(&mut self, message: &[Target]) -> [Target; 32];
+
+    /// Ethereum's Keccak-256 hash of the bytes in use of `message`, whose length is only known from
+    /// the witness. Every block up to the capacity of `message` is permuted, so the cost only
+    /// depends on the capacity.
+    fn keccak256_variable_length(&mut self, message: &BytesTarget) -> [Target; 32];
+
+    /// Same as `KeccakHash::<N>::hash_no_pad`.
+    fn keccak_hash_no_pad<const N: usize>(&mut self, inputs: &[Target]) -> [Target; N];
+
+    /// Same as `KeccakHash::<N>::hash_or_noop`.
+    fn keccak_hash_or_noop<const N: usize>(&mut self, inputs: &[Target]) -> [Target; N];
+
+    /// Same as `KeccakHash::<N>::two_to_one`. The bytes of `left` and `right` are assumed to be
+    /// range-checked.
+    fn keccak_two_to_one<const N: usize>(
+        &mut self,
+        left: &[Target; N],
+        right: &[Target; N],
+    ) -> [Target; N];
+
+    /// Verifies that the given leaf data is present at the given index in a Merkle tree built with
+    /// `KeccakHash<N>`, with the given cap. The index is given by its little-endian bits.
+    fn verify_keccak_merkle_proof_to_cap<const N: usize>(
+        &mut self,
+        leaf_data: &[Target],
+        leaf_index_bits: &[BoolTarget],
+        merkle_cap: &[[Target; N]],
+        siblings: &[[Target; N]],
+    );
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderKeccak<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn keccak_f(&mut self, state: &[[U32Target; 2]; 25]) -> [[U32Target; 2]; 25] {
+        let mut bytes = [self.zero(); 200];
+        for (lane, lane_bytes) in state.iter().zip(bytes.chunks_mut(8)) {
+            for (half, half_bytes) in lane.iter().zip(lane_bytes.chunks_mut(4)) {
+                half_bytes.copy_from_slice(&split_to_bytes(self, half.0, 4));
+            }
+        }
+
+        let tables = KeccakTables::new(self);
+        let bytes = tables.permute(self, &bytes);
+        array::from_fn(|i| {
+            array::from_fn(|half| {
+                let start = 8 * i + 4 * half;
+                U32Target(le_sum_bytes(self, &bytes[start..start + 4]))
+            })
+        })
+    }
+
+    fn keccak256(&mut self, message: &[Target]) -> [Target; 32] {
+        range_check_bytes(self, message);
+        keccak256_range_checked(self, message)
+    }
+
+    fn keccak256_variable_length(&mut self, message: &BytesTarget) -> [Target; 32] {
+        let padded = self.bytes_pad101(message, RATE);
+
+        let tables = KeccakTables::new(self);
+        let mut state = [self.zero(); 200];
+        let mut result = [self.zero(); 32];
+        for (k, block) in padded.array.elements.chunks(RATE).enumerate() {
+            state = tables.absorb(self, &state, block, k == 0);
+
+            let len = self.constant(F::from_canonical_usize(RATE * (k + 1)));
+            let is_last = self.is_equal(padded.array.len, len);
+            let digest: [Target; 32] = state[..32].try_into().unwrap();
+            result = self.select_variable(is_last, &digest, &result);
+        }
+        result
+    }
+
+    fn keccak_hash_no_pad<const N: usize>(&mut self, inputs: &[Target]) -> [Target; N] {
+        let bytes = field_elements_to_bytes(self, inputs);
+        let digest = keccak256_range_checked(self, &bytes);
+        digest[..N].try_into().unwrap()
+    }
+
+    fn keccak_hash_or_noop<const N: usize>(&mut self, inputs: &[Target]) -> [Target; N] {
+        if inputs.len() * 8 <= N {
+            let mut bytes = field_elements_to_bytes(self, inputs);
+            bytes.resize(N, self.zero());
+            bytes.try_into().unwrap()
+        } else {
+            self.keccak_hash_no_pad(inputs)
+        }
+    }
+
+    fn keccak_two_to_one<const N: usize>(
+        &mut self,
+        left: &[Target; N],
+        right: &[Target; N],
+    ) -> [Target; N] {
+        let bytes = [left.as_slice(), right.as_slice()].concat();
+        let digest = keccak256_range_checked(self, &bytes);
+        digest[..N].try_into().unwrap()
+    }
+
+    fn verify_keccak_merkle_proof_to_cap<const N: usize>(
+        &mut self,
+        leaf_data: &[Target],
+        leaf_index_bits: &[BoolTarget],
+        merkle_cap: &[[Target; N]],
+        siblings: &[[Target; N]],
+    ) {
+        let mut state = self.keccak_hash_or_noop::<N>(leaf_data);
+        for (&bit, sibling) in leaf_index_bits.iter().zip(siblings) {
+            range_check_bytes(self, sibling);
+            let left = self.select_variable(bit, sibling, &state);
+            let right = self.select_variable(bit, &state, sibling);
+            state = self.keccak_two_to_one(&left, &right);
+        }
+
+        let cap_index = self.le_sum(leaf_index_bits[siblings.len()..].iter().copied());
+        for i in 0..N {
+            let result = self.random_access(cap_index, merkle_cap.iter().map(|h| h[i]).collect());
+            self.connect(result, state[i]);
+        }
+    }
+}
+
+/// The indices of the lookup tables used by the Keccak permutation.
+struct KeccakTables {
+    /// Maps `256 a + b` to `a ^ b`.
+    xor: usize,
+    /// Maps `256 a + b` to `!a & b`.
+    andn: usize,
+    /// Maps `256 s + b` to the part of `b` which stays in the same byte after a left rotation by
+    /// `s` bits.
+    shift_low: usize,
+    /// Maps `256 s + b` to the part of `b` which moves to the next byte after a left rotation by
+    /// `s` bits.
+    shift_high: usize,
+}
+
+impl KeccakTables {
+    fn new<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        let pairs: Vec<u16> = (0..=u16::MAX).collect();
+        let xor = builder.add_lookup_table_from_fn(|x| (x >> 8) ^ (x & 0xff), &pairs);
+        let andn = builder.add_lookup_table_from_fn(|x| !(x >> 8) & x & 0xff, &pairs);
+
+        let shifted: Vec<u16> = (0..8 << 8).collect();
+        let shift_low =
+            builder.add_lookup_table_from_fn(|x| ((x & 0xff) << (x >> 8)) & 0xff, &shifted);
+        let shift_high =
+            builder.add_lookup_table_from_fn(|x| (x & 0xff) >> (8 - (x >> 8)), &shifted);
+
+        Self { xor, andn, shift_low, shift_high }
+    }
+
+    fn pair_lookup<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        table: usize,
+        a: Target,
+        b: Target,
+    ) -> Target {
+        let index = builder.mul_const_add(F::from_canonical_u32(1 << 8), a, b);
+        builder.add_lookup_from_index(index, table)
+    }
+
+    fn xor_lanes<F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        a: &[Target; 8],
+        b: &[Target; 8],
+    ) -> [Target; 8] {
+        array::from_fn(|k| Self::pair_lookup(builder, self.xor, a[k], b[k]))
+    }
+
+    fn rotate_lane<F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        lane: &[Target; 8],
+        rotation: usize,
+    ) -> [Target; 8] {
+        // Rotate whole bytes first, then move the remaining bits across byte boundaries.
+        let (q, s) = (rotation / 8, rotation % 8);
+        let rotated: [Target; 8] = array::from_fn(|k| lane[(k + 8 - q) % 8]);
+        if s == 0 {
+            return rotated;
+        }
+
+        let shift = F::from_canonical_usize(s << 8);
+        let indices = rotated.map(|byte| builder.add_const(byte, shift));
+        let low = indices.map(|index| builder.add_lookup_from_index(index, self.shift_low));
+        let high = indices.map(|index| builder.add_lookup_from_index(index, self.shift_high));
+        array::from_fn(|k| builder.add(low[k], high[(k + 7) % 8]))
+    }
+
+    /// Applies Keccak-f[1600] to a state of 200 bytes, which are assumed to be range-checked.
+    fn permute<F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        state: &[Target; 200],
+    ) -> [Target; 200] {
+        let mut lanes: [[Target; 8]; 25] =
+            array::from_fn(|i| state[8 * i..8 * (i + 1)].try_into().unwrap());
+
+        for round_constant in ROUND_CONSTANTS {
+            // θ
+            let columns: Vec<[Target; 8]> = (0..5)
+                .map(|x| {
+                    (1..5).fold(lanes[x], |acc, y| self.xor_lanes(builder, &acc, &lanes[x + 5 * y]))
+                })
+                .collect();
+            for x in 0..5 {
+                let rotated = self.rotate_lane(builder, &columns[(x + 1) % 5], 1);
+                let d = self.xor_lanes(builder, &columns[(x + 4) % 5], &rotated);
+                for y in 0..5 {
+                    lanes[x + 5 * y] = self.xor_lanes(builder, &lanes[x + 5 * y], &d);
+                }
+            }
+
+            // ρ and π
+            let mut b = lanes;
+            for x in 0..5 {
+                for y in 0..5 {
+                    b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                        self.rotate_lane(builder, &lanes[x + 5 * y], ROTATIONS[x + 5 * y]);
+                }
+            }
+
+            // χ
+            for x in 0..5 {
+                for y in 0..5 {
+                    let next = &b[(x + 1) % 5 + 5 * y];
+                    let next_next = &b[(x + 2) % 5 + 5 * y];
+                    let andn: [Target; 8] = array::from_fn(|k| {
+                        Self::pair_lookup(builder, self.andn, next[k], next_next[k])
+                    });
+                    lanes[x + 5 * y] = self.xor_lanes(builder, &b[x + 5 * y], &andn);
+                }
+            }
+
+            // ι
+            for (k, byte) in round_constant.to_le_bytes().into_iter().enumerate() {
+                if byte != 0 {
+                    let byte = builder.constant(F::from_canonical_u8(byte));
+                    lanes[0][k] = Self::pair_lookup(builder, self.xor, lanes[0][k], byte);
+                }
+            }
+        }
+
+        array::from_fn(|i| lanes[i / 8][i % 8])
+    }
+
+    /// Xors a block of `RATE` bytes into the state and permutes it. If `first` is set, the state
+    /// is assumed to be zero.
+    fn absorb<F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        state: &[Target; 200],
+        block: &[Target],
+        first: bool,
+    ) -> [Target; 200] {
+        let mut state = *state;
+        for (s, &byte) in state.iter_mut().zip(block) {
+            *s = if first { byte } else { Self::pair_lookup(builder, self.xor, *s, byte) };
+        }
+        self.permute(builder, &state)
+    }
+}
+
+/// Hashes `message`, whose bytes are assumed to be range-checked, with Keccak-256.
+fn keccak256_range_checked<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    message: &[Target],
+) -> [Target; 32] {
+    let mut padding = vec![0u8; RATE - message.len() % RATE];
+    padding[0] = 0x01;
+    *padding.last_mut().unwrap() |= 0x80;
+    let mut padded = message.to_vec();
+    padded.extend(padding.into_iter().map(|byte| builder.constant(F::from_canonical_u8(byte))));
+
+    let tables = KeccakTables::new(builder);
+    let mut state = [builder.zero(); 200];
+    for (k, block) in padded.chunks(RATE).enumerate() {
+        state = tables.absorb(builder, &state, block, k == 0);
+    }
+    state[..32].try_into().unwrap()
+}
+
+/// Checks that each of `bytes` is less than 256, with lookups into a table of all bytes.
+fn range_check_bytes<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    bytes: &[Target],
+) {
+    // Only add the table if it is used, as unused tables are rejected.
+    if bytes.is_empty() {
+        return;
+    }
+    let all_bytes: Vec<u16> = (0..256).collect();
+    let table = builder.add_lookup_table_from_fn(|x| x, &all_bytes);
+    for &byte in bytes {
+        builder.add_lookup_from_index(byte, table);
+    }
+}
+
+/// Returns `Σ 256^i bytes[i]`.
+fn le_sum_bytes<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    bytes: &[Target],
+) -> Target {
+    let (&last, rest) = bytes.split_last().unwrap();
+    rest.iter()
+        .rev()
+        .fold(last, |acc, &byte| builder.mul_const_add(F::from_canonical_u32(1 << 8), acc, byte))
+}
+
+/// Splits `x`, which is assumed to be less than `256^num_bytes`, into little-endian bytes.
+fn split_to_bytes<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    x: Target,
+    num_bytes: usize,
+) -> Vec<Target> {
+    let bytes = builder.add_virtual_targets(num_bytes);
+    builder.add_simple_generator(LeBytesGenerator::<F, D> {
+        x,
+        bytes: bytes.clone(),
+        _phantom: PhantomData,
+    });
+    range_check_bytes(builder, &bytes);
+    let sum = le_sum_bytes(builder, &bytes);
+    builder.connect(sum, x);
+    bytes
+}
+
+/// Writes field elements as 8 little-endian bytes each, like `Write::write_field_vec`.
+fn field_elements_to_bytes<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    inputs: &[Target],
+) -> Vec<Target> {
+    let max_high = builder.constant(F::from_canonical_u32(u32::MAX));
+    let mut bytes = Vec::with_capacity(8 * inputs.len());
+    for &x in inputs {
+        let x_bytes = split_to_bytes(builder, x, 8);
+        // The bytes must encode the canonical representative of `x`. As the field order is
+        // `2^64 - 2^32 + 1`, this fails exactly when the high half is `2^32 - 1` and the low half
+        // is nonzero.
+        let low = le_sum_bytes(builder, &x_bytes[..4]);
+        let high = le_sum_bytes(builder, &x_bytes[4..]);
+        let high_is_max = builder.is_equal(high, max_high);
+        let overflow = builder.mul(high_is_max.target, low);
+        builder.assert_zero(overflow);
+        bytes.extend(x_bytes);
+    }
+    bytes
+}
+
+/// Fills in the little-endian bytes of the canonical representative of a field element.
+#[derive(Clone, Debug, Default, GeneratorSerialize)]
+pub(crate) struct LeBytesGenerator<F: RichField + Extendable<D>, const D: usize> {
+    x: Target,
+    bytes: Vec<Target>,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for LeBytesGenerator<F, D>
+{
+    fn id(&self) -> String {
+        "LeBytesGenerator".to_string()
+    }
+
+    plonky2::derived_serialization!();
+
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.x]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let x = witness.get_target(self.x).to_canonical_u64();
+        for (&byte, value) in self.bytes.iter().zip(x.to_le_bytes()) {
+            out_buffer.set_target(byte, F::from_canonical_u8(value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::{
+        field::types::{Field, Sample},
+        hash::{keccak::KeccakHash, merkle_tree::MerkleTree},
+        iop::witness::PartialWitness,
+        plonk::{
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, Hasher, PoseidonGoldilocksConfig},
+        },
+    };
+
+    use super::*;
+    use crate::{gadgets::arithmetic_u32::CircuitBuilderU32, witness::WitnessU32};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// Messages and their Keccak-256 hashes, as computed by Ethereum clients.
+    fn test_vectors() -> Vec<(Vec<u8>, &'static str)> {
+        vec![
+            (b"".to_vec(), "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"),
+            (b"abc".to_vec(), "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"),
+            (
+                b"The quick brown fox jumps over the lazy dog".to_vec(),
+                "4d741b6f1eb29cb2a9b9911c82f56fa8d73b04959d3d9d222895df6c0b28aa15",
+            ),
+            // Spans two blocks.
+            (
+                (0..200).map(|i| (i * 7 + 3) as u8).collect(),
+                "66d2cdf3ab4c5bd3c75add9b60b14ac5b7789534fa2da3f348853b847359a3a0",
+            ),
+        ]
+    }
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+    }
+
+    fn connect_bytes(builder: &mut CircuitBuilder<F, D>, targets: &[Target], expected: &[u8]) {
+        assert_eq!(targets.len(), expected.len());
+        for (&target, &byte) in targets.iter().zip(expected) {
+            let byte = builder.constant(F::from_canonical_u8(byte));
+            builder.connect(target, byte);
+        }
+    }
+
+    #[test]
+    fn test_keccak_f() -> Result<()> {
+        // Keccak-f[1600] applied to the zero state, from the Keccak team's intermediate values.
+        const EXPECTED: [u64; 25] = [
+            0xf1258f7940e1dde7,
+            0x84d5ccf933c0478a,
+            0xd598261ea65aa9ee,
+            0xbd1547306f80494d,
+            0x8b284e056253d057,
+            0xff97a42d7f8e6fd4,
+            0x90fee5a0a44647c4,
+            0x8c5bda0cd6192e76,
+            0xad30a6f71b19059c,
+            0x30935ab7d08ffc64,
+            0xeb5aa93f2317d635,
+            0xa9a6e6260d712103,
+            0x81a57c16dbcf555f,
+            0x43b831cd0347c826,
+            0x01f22f1a11a5569f,
+            0x05e5635a21d9ae61,
+            0x64befef28cc970f2,
+            0x613670957bc46611,
+            0xb87c5a554fd00ecb,
+            0x8c3ee88a1ccf32c8,
+            0x940c7922ae3a2614,
+            0x1841f924a2c509e4,
+            0x16f53526e70465c2,
+            0x75f644e97f30a13b,
+            0xeaf1ff7b5ceca249,
+        ];
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let state: [[U32Target; 2]; 25] = array::from_fn(|_| {
+            [builder.add_virtual_u32_target(), builder.add_virtual_u32_target()]
+        });
+        let output = builder.keccak_f(&state);
+        for (lane, expected) in output.into_iter().zip(EXPECTED) {
+            let low = builder.constant_u32(expected as u32);
+            let high = builder.constant_u32((expected >> 32) as u32);
+            builder.connect_u32(lane[0], low);
+            builder.connect_u32(lane[1], high);
+        }
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for lane in &state {
+            pw.set_u32_target(lane[0], 0);
+            pw.set_u32_target(lane[1], 0);
+        }
+        let proof = data.prove(pw)?;
+        Ok(data.verify(proof)?)
+    }
+
+    #[test]
+    fn test_keccak256() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut messages = Vec::new();
+        for (message, expected) in test_vectors() {
+            let targets = builder.add_virtual_targets(message.len());
+            let digest = builder.keccak256(&targets);
+            connect_bytes(&mut builder, &digest, &from_hex(expected));
+            messages.push((targets, message));
+        }
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (targets, message) in messages {
+            for (target, byte) in targets.into_iter().zip(message) {
+                pw.set_target(target, F::from_canonical_u8(byte));
+            }
+        }
+        let proof = data.prove(pw)?;
+        Ok(data.verify(proof)?)
+    }
+
+    #[test]
+    fn test_keccak256_variable_length() -> Result<()> {
+        const CAPACITY: usize = 200;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let message = builder.add_virtual_bytes_target(CAPACITY);
+        let digest = builder.keccak256_variable_length(&message);
+        let expected = builder.add_virtual_targets(32);
+        for (&byte, &expected) in digest.iter().zip(&expected) {
+            builder.connect(byte, expected);
+        }
+        let data = builder.build::<C>();
+
+        for (message_value, expected_value) in test_vectors() {
+            let mut pw = PartialWitness::new();
+            pw.set_bytes_target(&message, &message_value);
+            for (&target, byte) in expected.iter().zip(from_hex(expected_value)) {
+                pw.set_target(target, F::from_canonical_u8(byte));
+            }
+            let proof = data.prove(pw)?;
+            data.verify(proof)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_keccak_hash_compatibility() -> Result<()> {
+        const N: usize = 25;
+        type H = KeccakHash<N>;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        // `hash_no_pad` of enough elements to span two blocks, and of the largest element.
+        for inputs in [F::rand_vec(20), vec![F::NEG_ONE]] {
+            let targets = builder.add_virtual_targets(inputs.len());
+            pw.set_target_arr(&targets, &inputs);
+            let digest = builder.keccak_hash_no_pad::<N>(&targets);
+            connect_bytes(&mut builder, &digest, &<H as Hasher<F>>::hash_no_pad(&inputs).0);
+        }
+
+        // A Merkle proof, whose leaves are hashed, and one whose leaves are small enough not to be.
+        let cap_height = 1;
+        for leaf_len in [7, 3] {
+            let leaves: Vec<Vec<F>> = (0..8).map(|_| F::rand_vec(leaf_len)).collect();
+            let tree = MerkleTree::<F, H>::new_from_2d(leaves.clone(), cap_height);
+            let leaf_index = 5;
+            let proof = tree.prove(leaf_index);
+
+            let leaf_data = builder.add_virtual_targets(leaf_len);
+            pw.set_target_arr(&leaf_data, &leaves[leaf_index]);
+            let siblings: Vec<[Target; N]> = proof
+                .siblings
+                .iter()
+                .map(|_| array::from_fn(|_| builder.add_virtual_target()))
+                .collect();
+            for (targets, sibling) in siblings.iter().zip(&proof.siblings) {
+                for (&target, &byte) in targets.iter().zip(&sibling.0) {
+                    pw.set_target(target, F::from_canonical_u8(byte));
+                }
+            }
+            let merkle_cap: Vec<[Target; N]> = tree
+                .cap
+                .0
+                .iter()
+                .map(|hash| hash.0.map(|byte| builder.constant(F::from_canonical_u8(byte))))
+                .collect();
+            let index = builder.constant(F::from_canonical_usize(leaf_index));
+            let leaf_index_bits = builder.split_le(index, 3);
+            builder.verify_keccak_merkle_proof_to_cap(
+                &leaf_data,
+                &leaf_index_bits,
+                &merkle_cap,
+                &siblings,
+            );
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        Ok(data.verify(proof)?)
+    }
+}
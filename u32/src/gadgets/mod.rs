@@ -1,3 +1,6 @@
 pub mod arithmetic_u32;
+pub mod arithmetic_u64;
+pub mod keccak256;
 pub mod multiple_comparison;
 pub mod range_check;
+pub mod sha256;
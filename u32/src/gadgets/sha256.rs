@@ -0,0 +1,361 @@
+use alloc::vec::Vec;
+use core::array;
+
+use plonky2::{
+    field::extension::Extendable, gadgets::dynamic_array::BytesTarget, hash::hash_types::RichField,
+    iop::target::Target, plonk::circuit_builder::CircuitBuilder,
+};
+
+use crate::{
+    gadgets::arithmetic_u32::{CircuitBuilderU32, U32Target},
+    gates::{sha256_ch_maj::Sha256ChMajGate, sha256_sigma::Sha256SigmaGate},
+};
+
+const INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+// The rotations of `Σ0`, `Σ1`, `σ0` and `σ1`, and whether their last rotation is a shift.
+const BIG_SIGMA0: ([usize; 3], bool) = ([2, 13, 22], false);
+const BIG_SIGMA1: ([usize; 3], bool) = ([6, 11, 25], false);
+const SMALL_SIGMA0: ([usize; 3], bool) = ([7, 18, 3], true);
+const SMALL_SIGMA1: ([usize; 3], bool) = ([17, 19, 10], true);
+
+/// SHA-256 gadgets. Words are big-endian, as in the SHA-256 specification, and digests are
+/// returned as eight words.
+pub trait CircuitBuilderSha256<F: RichField + Extendable<D>, const D: usize> {
+    /// Applies the SHA-256 compression function to `state` and a 16-word message block. The words
+    /// of `state` and `block` are assumed to be range-checked.
+    fn sha256_compress(
+        &mut self,
+        state: &[U32Target; 8],
+        block: &[U32Target; 16],
+    ) -> [U32Target; 8];
+
+    /// Hashes a message made of 32-bit words, which are assumed to be range-checked.
+    fn sha256_u32(&mut self, message: &[U32Target]) -> [U32Target; 8];
+
+    /// Hashes a message made of bytes, which are range-checked.
+    fn sha256(&mut self, message: &[Target]) -> [U32Target; 8];
+
+    /// Hashes the bytes in use of `message`, whose length is only known from the witness. Every
+    /// block up to the capacity of `message` is compressed, so the cost only depends on the
+    /// capacity.
+    fn sha256_variable_length(&mut self, message: &BytesTarget) -> [U32Target; 8];
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderSha256<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn sha256_compress(
+        &mut self,
+        state: &[U32Target; 8],
+        block: &[U32Target; 16],
+    ) -> [U32Target; 8] {
+        let mut w = block.to_vec();
+        for t in 16..64 {
+            let s0 = sigma(self, SMALL_SIGMA0, w[t - 15]);
+            let s1 = sigma(self, SMALL_SIGMA1, w[t - 2]);
+            let (word, _) = self.add_many_u32(&[s1, w[t - 7], s0, w[t - 16]]);
+            w.push(word);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+        for t in 0..64 {
+            let big_s1 = sigma(self, BIG_SIGMA1, e);
+            let ch = ch_maj(self, false, e, f, g);
+            let k = self.constant_u32(ROUND_CONSTANTS[t]);
+            let big_s0 = sigma(self, BIG_SIGMA0, a);
+            let maj = ch_maj(self, true, a, b, c);
+
+            // `temp1 = h + Σ1(e) + Ch(e, f, g) + k + w`, `e' = d + temp1` and
+            // `a' = temp1 + Σ0(a) + Maj(a, b, c)`.
+            let (new_e, _) = self.add_many_u32(&[d, h, big_s1, ch, k, w[t]]);
+            let (new_a, _) = self.add_many_u32(&[h, big_s1, ch, k, w[t], big_s0, maj]);
+
+            h = g;
+            g = f;
+            f = e;
+            e = new_e;
+            d = c;
+            c = b;
+            b = a;
+            a = new_a;
+        }
+
+        let working = [a, b, c, d, e, f, g, h];
+        array::from_fn(|i| self.add_u32(state[i], working[i]).0)
+    }
+
+    fn sha256_u32(&mut self, message: &[U32Target]) -> [U32Target; 8] {
+        let bit_len = 32 * message.len() as u64;
+        let mut padded = message.to_vec();
+        padded.push(self.constant_u32(0x80000000));
+        while padded.len() % 16 != 14 {
+            padded.push(self.zero_u32());
+        }
+        padded.push(self.constant_u32((bit_len >> 32) as u32));
+        padded.push(self.constant_u32(bit_len as u32));
+
+        let mut state = INITIAL_STATE.map(|x| self.constant_u32(x));
+        for block in padded.chunks(16) {
+            state = self.sha256_compress(&state, block.try_into().unwrap());
+        }
+        state
+    }
+
+    fn sha256(&mut self, message: &[Target]) -> [U32Target; 8] {
+        for &byte in message {
+            self.range_check(byte, 8);
+        }
+
+        let bit_len = 8 * message.len() as u64;
+        let mut padded = message.to_vec();
+        padded.push(self.constant(F::from_canonical_u8(0x80)));
+        while padded.len() % 64 != 56 {
+            padded.push(self.zero());
+        }
+        padded.extend(bit_len.to_be_bytes().map(|byte| self.constant(F::from_canonical_u8(byte))));
+
+        let mut state = INITIAL_STATE.map(|x| self.constant_u32(x));
+        for block in padded.chunks(64) {
+            let block = bytes_to_words(self, block);
+            state = self.sha256_compress(&state, &block);
+        }
+        state
+    }
+
+    fn sha256_variable_length(&mut self, message: &BytesTarget) -> [U32Target; 8] {
+        let padded = self.bytes_pad_sha256(message);
+
+        let mut state = INITIAL_STATE.map(|x| self.constant_u32(x));
+        let mut result = state;
+        for (k, block) in padded.array.elements.chunks(64).enumerate() {
+            let block = bytes_to_words(self, block);
+            state = self.sha256_compress(&state, &block);
+
+            let len = self.constant(F::from_canonical_usize(64 * (k + 1)));
+            let is_last = self.is_equal(padded.array.len, len);
+            result = self.select_variable(is_last, &state, &result);
+        }
+        result
+    }
+}
+
+/// Returns `Σ0`, `Σ1`, `σ0` or `σ1` of `x`, depending on `function`.
+fn sigma<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    function: ([usize; 3], bool),
+    x: U32Target,
+) -> U32Target {
+    let (rotations, shift_last) = function;
+    let gate = Sha256SigmaGate::<F, D>::new_from_config(&builder.config, rotations, shift_last);
+    let (row, copy) = builder.find_slot(gate, &[], &[]);
+
+    builder.connect(Target::wire(row, gate.wire_ith_input(copy)), x.0);
+    U32Target(Target::wire(row, gate.wire_ith_output(copy)))
+}
+
+/// Returns `Maj(x, y, z)` if `majority` is set, and `Ch(x, y, z)` otherwise.
+fn ch_maj<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    majority: bool,
+    x: U32Target,
+    y: U32Target,
+    z: U32Target,
+) -> U32Target {
+    let gate = Sha256ChMajGate::<F, D>::new_from_config(&builder.config, majority);
+    let (row, copy) = builder.find_slot(gate, &[], &[]);
+
+    for (k, input) in [x, y, z].into_iter().enumerate() {
+        builder.connect(Target::wire(row, gate.wire_ith_input(copy, k)), input.0);
+    }
+    U32Target(Target::wire(row, gate.wire_ith_output(copy)))
+}
+
+/// Packs 64 bytes, which are assumed to be range-checked, into 16 big-endian words.
+fn bytes_to_words<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    bytes: &[Target],
+) -> [U32Target; 16] {
+    let words: Vec<_> = bytes
+        .chunks(4)
+        .map(|word| {
+            let base = F::from_canonical_u32(1 << 8);
+            let packed =
+                word[1..].iter().fold(word[0], |acc, &byte| builder.mul_const_add(base, acc, byte));
+            U32Target(packed)
+        })
+        .collect();
+    words.try_into().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::{
+        field::types::Field,
+        iop::witness::{PartialWitness, WitnessWrite},
+        plonk::{
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+    };
+
+    use super::*;
+    use crate::witness::WitnessU32;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// Test vectors from the NIST examples for SHA-256.
+    const TEST_VECTORS: [(&str, [u32; 8]); 4] = [
+        (
+            "",
+            [
+                0xe3b0c442, 0x98fc1c14, 0x9afbf4c8, 0x996fb924, 0x27ae41e4, 0x649b934c, 0xa495991b,
+                0x7852b855,
+            ],
+        ),
+        (
+            "abc",
+            [
+                0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+                0xf20015ad,
+            ],
+        ),
+        (
+            "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            [
+                0x248d6a61, 0xd20638b8, 0xe5c02693, 0x0c3e6039, 0xa33ce459, 0x64ff2167, 0xf6ecedd4,
+                0x19db06c1,
+            ],
+        ),
+        (
+            "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+            [
+                0xcf5b16a7, 0x78af8380, 0x036ce59e, 0x7b049237, 0x0b249b11, 0xe8f07a51, 0xafac4503,
+                0x7afee9d1,
+            ],
+        ),
+    ];
+
+    fn connect_digest(
+        builder: &mut CircuitBuilder<F, D>,
+        digest: [U32Target; 8],
+        expected: [u32; 8],
+    ) {
+        for (word, expected) in digest.into_iter().zip(expected) {
+            let expected = builder.constant_u32(expected);
+            builder.connect_u32(word, expected);
+        }
+    }
+
+    #[test]
+    fn test_sha256() -> Result<()> {
+        for (message, expected) in TEST_VECTORS {
+            let config = CircuitConfig::standard_recursion_config();
+            let mut builder = CircuitBuilder::<F, D>::new(config);
+
+            let bytes = builder.add_virtual_targets(message.len());
+            let digest = builder.sha256(&bytes);
+            connect_digest(&mut builder, digest, expected);
+            let data = builder.build::<C>();
+
+            let mut pw = PartialWitness::new();
+            for (&target, byte) in bytes.iter().zip(message.bytes()) {
+                pw.set_target(target, F::from_canonical_u8(byte));
+            }
+            let proof = data.prove(pw)?;
+            data.verify(proof)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sha256_u32() -> Result<()> {
+        // The 448-bit message is a whole number of words.
+        let (message, expected) = TEST_VECTORS[2];
+        let words: Vec<u32> = message
+            .as_bytes()
+            .chunks(4)
+            .map(|word| u32::from_be_bytes(word.try_into().unwrap()))
+            .collect();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let targets = builder.add_virtual_u32_targets(words.len());
+        let digest = builder.sha256_u32(&targets);
+        connect_digest(&mut builder, digest, expected);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (&target, &word) in targets.iter().zip(&words) {
+            pw.set_u32_target(target, word);
+        }
+        let proof = data.prove(pw)?;
+        Ok(data.verify(proof)?)
+    }
+
+    #[test]
+    fn test_sha256_variable_length() -> Result<()> {
+        // Needs up to three blocks once padded.
+        const CAPACITY: usize = 120;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let message = builder.add_virtual_bytes_target(CAPACITY);
+        let digest = builder.sha256_variable_length(&message);
+        let expected = builder.add_virtual_u32_targets(8);
+        for (word, expected) in digest.into_iter().zip(expected.clone()) {
+            builder.connect_u32(word, expected);
+        }
+        let data = builder.build::<C>();
+
+        for (message_value, expected_value) in TEST_VECTORS {
+            let mut pw = PartialWitness::new();
+            pw.set_bytes_target(&message, message_value.as_bytes());
+            for (&target, value) in expected.iter().zip(expected_value) {
+                pw.set_u32_target(target, value);
+            }
+            let proof = data.prove(pw)?;
+            data.verify(proof)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sha256_wrong_digest() {
+        let (message, mut expected) = TEST_VECTORS[1];
+        expected[0] ^= 1;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let bytes = builder.add_virtual_targets(message.len());
+        let digest = builder.sha256(&bytes);
+        connect_digest(&mut builder, digest, expected);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (&target, byte) in bytes.iter().zip(message.bytes()) {
+            pw.set_target(target, F::from_canonical_u8(byte));
+        }
+        data.verify(data.prove(pw).unwrap()).unwrap();
+    }
+}
@@ -4,7 +4,7 @@ use alloc::{
     vec::Vec,
 };
 use core::marker::PhantomData;
-use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use plonky2::util::serialization::{GateSerialize, GeneratorSerialize};
 
 use itertools::unfold;
 use plonky2::{
@@ -20,7 +20,7 @@ use plonky2::{
     },
     plonk::{
         circuit_builder::CircuitBuilder,
-        circuit_data::{CircuitConfig, CommonCircuitData},
+        circuit_data::CircuitConfig,
         vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase},
     },
     util::ceil_div_usize,
@@ -30,7 +30,7 @@ const LOG2_MAX_NUM_ADDENDS: usize = 4;
 const MAX_NUM_ADDENDS: usize = 16;
 
 /// A gate to perform addition on `num_addends` different 32-bit values, plus a small carry
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default, GateSerialize)]
 pub struct U32AddManyGate<F: RichField + Extendable<D>, const D: usize> {
     pub num_addends: usize,
     pub num_ops: usize,
@@ -94,21 +94,117 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for U32AddManyGate
     }
 
     fn export_circom_verification_code(&self) -> String {
-        todo!();
+        let mut template_str = "template U32AddMany$NUM_ADDENDSOps$NUM_OPS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  var index = 0;
+  signal computed_outputs[$NUM_OPS][$NUM_ADDENDS + 1][2];
+  signal combined_outputs[$NUM_OPS][2];
+  signal combined_result_limbs[$NUM_OPS][$NUM_RESULT_LIMBS + 1][2];
+  signal combined_carry_limbs[$NUM_OPS][$NUM_LIMBS - $NUM_RESULT_LIMBS + 1][2];
+  for (var i = 0; i < $NUM_OPS; i++) {
+    var start = ($NUM_ADDENDS + 3) * i;
+    computed_outputs[i][0] <== wires[start + $NUM_ADDENDS];
+    for (var j = 0; j < $NUM_ADDENDS; j++) {
+      computed_outputs[i][j + 1] <== GlExtAdd()(computed_outputs[i][j], wires[start + j]);
+    }
+    combined_outputs[i] <== GlExtScalarMulAdd()(wires[start + $NUM_ADDENDS + 2], 4294967296, wires[start + $NUM_ADDENDS + 1]);
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(combined_outputs[i], computed_outputs[i][$NUM_ADDENDS]));
+    index++;
+
+    combined_result_limbs[i][$NUM_RESULT_LIMBS] <== GlExt(0, 0)();
+    combined_carry_limbs[i][$NUM_LIMBS - $NUM_RESULT_LIMBS] <== GlExt(0, 0)();
+    for (var j = $NUM_LIMBS; j > 0; j--) {
+      var this_limb[2] = wires[$LIMBS_START + $NUM_LIMBS * i + j - 1];
+      out[index] <== ConstraintPush()(constraints[index], filter, RangeProduct($MAX_LIMB)(this_limb));
+      index++;
+      if (j - 1 < $NUM_RESULT_LIMBS) {
+        combined_result_limbs[i][j - 1] <== GlExtScalarMulAdd()(combined_result_limbs[i][j], $MAX_LIMB, this_limb);
+      } else {
+        combined_carry_limbs[i][j - 1 - $NUM_RESULT_LIMBS] <== GlExtScalarMulAdd()(combined_carry_limbs[i][j - $NUM_RESULT_LIMBS], $MAX_LIMB, this_limb);
+      }
+    }
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(combined_result_limbs[i][0], wires[start + $NUM_ADDENDS + 1]));
+    index++;
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(combined_carry_limbs[i][0], wires[start + $NUM_ADDENDS + 2]));
+    index++;
+  }
+
+  for (var i = index; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+
+        template_str = template_str.replace("$NUM_ADDENDS", &self.num_addends.to_string());
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str = template_str
+            .replace("$LIMBS_START", &((self.num_addends + 3) * self.num_ops).to_string());
+        template_str = template_str.replace("$NUM_LIMBS", &Self::num_limbs().to_string());
+        template_str =
+            template_str.replace("$NUM_RESULT_LIMBS", &Self::num_result_limbs().to_string());
+        template_str = template_str.replace("$MAX_LIMB", &(1 << Self::limb_bits()).to_string());
+
+        template_str
     }
     fn export_solidity_verification_code(&self) -> String {
-        todo!();
+        let mut template_str = "library U32AddMany$NUM_ADDENDSOps$NUM_OPSLib {
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
     }
-    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
-        dst.write_usize(self.num_addends)?;
-        dst.write_usize(self.num_ops)
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint32 index = 0;
+        for (uint32 i = 0; i < $NUM_OPS; i++) {
+            uint32 start = ($NUM_ADDENDS + 3) * i;
+            {
+                uint64[2] memory computed_output = ev.wires[start + $NUM_ADDENDS];
+                for (uint32 j = 0; j < $NUM_ADDENDS; j++) {
+                    computed_output = computed_output.add(ev.wires[start + j]);
+                }
+                uint64[2] memory combined_output = ev.wires[start + $NUM_ADDENDS + 2].scalar_mul(1 << 32).add(ev.wires[start + $NUM_ADDENDS + 1]);
+                GatesUtilsLib.push(constraints, ev.filter, index++, combined_output.sub(computed_output));
+            }
+
+            uint64[2] memory combined_result_limbs;
+            uint64[2] memory combined_carry_limbs;
+            for (uint32 j = $NUM_LIMBS; j > 0; j--) {
+                uint64[2] memory this_limb = ev.wires[$LIMBS_START + $NUM_LIMBS * i + j - 1];
+                GatesUtilsLib.push(constraints, ev.filter, index++, GatesUtilsLib.range_product(this_limb, $MAX_LIMB));
+                if (j - 1 < $NUM_RESULT_LIMBS) {
+                    combined_result_limbs = combined_result_limbs.scalar_mul($MAX_LIMB).add(this_limb);
+                } else {
+                    combined_carry_limbs = combined_carry_limbs.scalar_mul($MAX_LIMB).add(this_limb);
+                }
+            }
+            GatesUtilsLib.push(constraints, ev.filter, index++, combined_result_limbs.sub(ev.wires[start + $NUM_ADDENDS + 1]));
+            GatesUtilsLib.push(constraints, ev.filter, index++, combined_carry_limbs.sub(ev.wires[start + $NUM_ADDENDS + 2]));
+        }
     }
+}"
+        .to_string();
+
+        template_str = template_str.replace("$NUM_ADDENDS", &self.num_addends.to_string());
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str = template_str
+            .replace("$LIMBS_START", &((self.num_addends + 3) * self.num_ops).to_string());
+        template_str = template_str.replace("$NUM_LIMBS", &Self::num_limbs().to_string());
+        template_str =
+            template_str.replace("$NUM_RESULT_LIMBS", &Self::num_result_limbs().to_string());
+        template_str = template_str.replace("$MAX_LIMB", &(1 << Self::limb_bits()).to_string());
 
-    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let num_addends = src.read_usize()?;
-        let num_ops = src.read_usize()?;
-        Ok(Self { num_addends, num_ops, _phantom: PhantomData })
+        template_str
     }
+    plonky2::derived_serialization!();
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let mut constraints = Vec::with_capacity(self.num_constraints());
@@ -281,8 +377,8 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for U32AddManyGate
     }
 }
 
-#[derive(Clone, Debug)]
-struct U32AddManyGenerator<F: RichField + Extendable<D>, const D: usize> {
+#[derive(Clone, Debug, Default, GeneratorSerialize)]
+pub(crate) struct U32AddManyGenerator<F: RichField + Extendable<D>, const D: usize> {
     gate: U32AddManyGate<F, D>,
     row: usize,
     i: usize,
@@ -296,18 +392,7 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
         "U32AddManyGenerator".to_string()
     }
 
-    fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
-        self.gate.serialize(dst, common_data)?;
-        dst.write_usize(self.row)?;
-        dst.write_usize(self.i)
-    }
-
-    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let gate = U32AddManyGate::deserialize(src, common_data)?;
-        let row = src.read_usize()?;
-        let i = src.read_usize()?;
-        Ok(Self { gate, row, i, _phantom: PhantomData })
-    }
+    plonky2::derived_serialization!();
 
     fn dependencies(&self) -> Vec<Target> {
         let local_target = |column| Target::wire(self.row, column);
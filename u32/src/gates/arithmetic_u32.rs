@@ -5,7 +5,7 @@ use alloc::{
     vec::Vec,
 };
 use core::marker::PhantomData;
-use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use plonky2::util::serialization::{GateSerialize, GeneratorSerialize};
 
 use itertools::unfold;
 use plonky2::{
@@ -21,7 +21,7 @@ use plonky2::{
     },
     plonk::{
         circuit_builder::CircuitBuilder,
-        circuit_data::{CircuitConfig, CommonCircuitData},
+        circuit_data::CircuitConfig,
         vars::{
             EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
             EvaluationVarsBasePacked,
@@ -30,7 +30,7 @@ use plonky2::{
 };
 
 /// A gate to perform a basic mul-add on 32-bit values (we assume they are range-checked beforehand).
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default, GateSerialize)]
 pub struct U32ArithmeticGate<F: RichField + Extendable<D>, const D: usize> {
     pub num_ops: usize,
     _phantom: PhantomData<F>,
@@ -96,19 +96,120 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for U32ArithmeticG
     }
 
     fn export_circom_verification_code(&self) -> String {
-        todo!();
+        let mut template_str = "template U32ArithmeticOps$NUM_OPS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  var index = 0;
+  signal computed_output[$NUM_OPS][2];
+  signal diff[$NUM_OPS][2];
+  signal hi_not_max[$NUM_OPS][2];
+  signal combined_output[$NUM_OPS][2];
+  signal combined_low_limbs[$NUM_OPS][$NUM_LIMBS \\ 2 + 1][2];
+  signal combined_high_limbs[$NUM_OPS][$NUM_LIMBS - $NUM_LIMBS \\ 2 + 1][2];
+  for (var i = 0; i < $NUM_OPS; i++) {
+    var start = $ROUTED_WIRES_PER_OP * i;
+    computed_output[i] <== GlExtMulAdd()(wires[start], wires[start + 1], wires[start + 2]);
+    // Check canonicity of output_high * 2^32 + output_low.
+    diff[i] <== GlExtSub()(GlExt(4294967295, 0)(), wires[start + 4]);
+    hi_not_max[i] <== GlExtSub()(GlExtMul()(wires[start + 5], diff[i]), GlExt(1, 0)());
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtMul()(hi_not_max[i], wires[start + 3]));
+    index++;
+    combined_output[i] <== GlExtScalarMulAdd()(wires[start + 4], 4294967296, wires[start + 3]);
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(combined_output[i], computed_output[i]));
+    index++;
+
+    combined_low_limbs[i][$NUM_LIMBS \\ 2] <== GlExt(0, 0)();
+    combined_high_limbs[i][$NUM_LIMBS - $NUM_LIMBS \\ 2] <== GlExt(0, 0)();
+    for (var j = $NUM_LIMBS; j > 0; j--) {
+      var this_limb[2] = wires[$LIMBS_START + $NUM_LIMBS * i + j - 1];
+      out[index] <== ConstraintPush()(constraints[index], filter, RangeProduct($MAX_LIMB)(this_limb));
+      index++;
+      if (j - 1 < $NUM_LIMBS \\ 2) {
+        combined_low_limbs[i][j - 1] <== GlExtScalarMulAdd()(combined_low_limbs[i][j], $MAX_LIMB, this_limb);
+      } else {
+        combined_high_limbs[i][j - 1 - $NUM_LIMBS \\ 2] <== GlExtScalarMulAdd()(combined_high_limbs[i][j - $NUM_LIMBS \\ 2], $MAX_LIMB, this_limb);
+      }
+    }
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(combined_low_limbs[i][0], wires[start + 3]));
+    index++;
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(combined_high_limbs[i][0], wires[start + 4]));
+    index++;
+  }
+
+  for (var i = index; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str =
+            template_str.replace("$ROUTED_WIRES_PER_OP", &Self::routed_wires_per_op().to_string());
+        template_str = template_str
+            .replace("$LIMBS_START", &(Self::routed_wires_per_op() * self.num_ops).to_string());
+        template_str = template_str.replace("$NUM_LIMBS", &Self::num_limbs().to_string());
+        template_str = template_str.replace("$MAX_LIMB", &(1 << Self::limb_bits()).to_string());
+
+        template_str
     }
     fn export_solidity_verification_code(&self) -> String {
-        todo!();
-    }
-    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
-        dst.write_usize(self.num_ops)
+        let mut template_str = "library U32ArithmeticOps$NUM_OPSLib {
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint32 index = 0;
+        for (uint32 i = 0; i < $NUM_OPS; i++) {
+            uint32 start = $ROUTED_WIRES_PER_OP * i;
+            {
+                uint64[2] memory computed_output = ev.wires[start].mul(ev.wires[start + 1]).add(ev.wires[start + 2]);
+                // Check canonicity of output_high * 2^32 + output_low.
+                uint64[2] memory diff = GatesUtilsLib.field_ext_from(0xffffffff, 0).sub(ev.wires[start + 4]);
+                uint64[2] memory hi_not_max = ev.wires[start + 5].mul(diff).sub(GoldilocksExtLib.one());
+                GatesUtilsLib.push(constraints, ev.filter, index++, hi_not_max.mul(ev.wires[start + 3]));
+                uint64[2] memory combined_output = ev.wires[start + 4].scalar_mul(1 << 32).add(ev.wires[start + 3]);
+                GatesUtilsLib.push(constraints, ev.filter, index++, combined_output.sub(computed_output));
+            }
+
+            uint64[2] memory combined_low_limbs;
+            uint64[2] memory combined_high_limbs;
+            for (uint32 j = $NUM_LIMBS; j > 0; j--) {
+                uint64[2] memory this_limb = ev.wires[$LIMBS_START + $NUM_LIMBS * i + j - 1];
+                GatesUtilsLib.push(constraints, ev.filter, index++, GatesUtilsLib.range_product(this_limb, $MAX_LIMB));
+                if (j - 1 < $NUM_LIMBS / 2) {
+                    combined_low_limbs = combined_low_limbs.scalar_mul($MAX_LIMB).add(this_limb);
+                } else {
+                    combined_high_limbs = combined_high_limbs.scalar_mul($MAX_LIMB).add(this_limb);
+                }
+            }
+            GatesUtilsLib.push(constraints, ev.filter, index++, combined_low_limbs.sub(ev.wires[start + 3]));
+            GatesUtilsLib.push(constraints, ev.filter, index++, combined_high_limbs.sub(ev.wires[start + 4]));
+        }
     }
+}"
+        .to_string();
+
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str =
+            template_str.replace("$ROUTED_WIRES_PER_OP", &Self::routed_wires_per_op().to_string());
+        template_str = template_str
+            .replace("$LIMBS_START", &(Self::routed_wires_per_op() * self.num_ops).to_string());
+        template_str = template_str.replace("$NUM_LIMBS", &Self::num_limbs().to_string());
+        template_str = template_str.replace("$MAX_LIMB", &(1 << Self::limb_bits()).to_string());
 
-    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let num_ops = src.read_usize()?;
-        Ok(Self { num_ops, _phantom: PhantomData })
+        template_str
     }
+    plonky2::derived_serialization!();
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let mut constraints = Vec::with_capacity(self.num_constraints());
@@ -349,8 +450,8 @@ impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D>
     }
 }
 
-#[derive(Clone, Debug)]
-struct U32ArithmeticGenerator<F: RichField + Extendable<D>, const D: usize> {
+#[derive(Clone, Debug, Default, GeneratorSerialize)]
+pub(crate) struct U32ArithmeticGenerator<F: RichField + Extendable<D>, const D: usize> {
     gate: U32ArithmeticGate<F, D>,
     row: usize,
     i: usize,
@@ -419,18 +520,7 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
         }
     }
 
-    fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
-        self.gate.serialize(dst, common_data)?;
-        dst.write_usize(self.row)?;
-        dst.write_usize(self.i)
-    }
-
-    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let gate = U32ArithmeticGate::deserialize(src, common_data)?;
-        let row = src.read_usize()?;
-        let i = src.read_usize()?;
-        Ok(Self { gate, row, i, _phantom: PhantomData })
-    }
+    plonky2::derived_serialization!();
 }
 
 #[cfg(test)]
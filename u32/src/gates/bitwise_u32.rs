@@ -0,0 +1,493 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::marker::PhantomData;
+use plonky2::util::serialization::{GateSerialize, GeneratorSerialize};
+
+use plonky2::{
+    field::{extension::Extendable, types::Field},
+    gates::{gate::Gate, util::StridedConstraintConsumer},
+    hash::hash_types::RichField,
+    iop::{
+        ext_target::ExtensionTarget,
+        generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef},
+        target::Target,
+        wire::Wire,
+        witness::{PartitionWitness, Witness, WitnessWrite},
+    },
+    plonk::{
+        circuit_builder::CircuitBuilder,
+        circuit_data::CircuitConfig,
+        vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase},
+    },
+};
+
+/// A bitwise operation on two 32-bit values.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum BitwiseOp {
+    #[default]
+    And,
+    Or,
+    Xor,
+}
+
+impl BitwiseOp {
+    pub fn apply(self, x: u32, y: u32) -> u32 {
+        match self {
+            BitwiseOp::And => x & y,
+            BitwiseOp::Or => x | y,
+            BitwiseOp::Xor => x ^ y,
+        }
+    }
+
+    /// Applies the operation to two bits, given as field elements.
+    fn apply_to_bits<T: Field>(self, x: T, y: T) -> T {
+        match self {
+            BitwiseOp::And => x * y,
+            BitwiseOp::Or => x + y - x * y,
+            BitwiseOp::Xor => x + y - (x * y).double(),
+        }
+    }
+
+    fn apply_to_bit_targets<F: RichField + Extendable<D>, const D: usize>(
+        self,
+        builder: &mut CircuitBuilder<F, D>,
+        x: ExtensionTarget<D>,
+        y: ExtensionTarget<D>,
+    ) -> ExtensionTarget<D> {
+        let c = match self {
+            BitwiseOp::And => return builder.mul_extension(x, y),
+            BitwiseOp::Or => F::NEG_ONE,
+            BitwiseOp::Xor => -F::TWO,
+        };
+        // `x + y + c x y`
+        let sum = builder.add_extension(x, y);
+        builder.arithmetic_extension(c, F::ONE, x, y, sum)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            BitwiseOp::And => "And",
+            BitwiseOp::Or => "Or",
+            BitwiseOp::Xor => "Xor",
+        }
+    }
+}
+
+/// A gate to perform a bitwise operation on two 32-bit values. Both inputs are decomposed into
+/// bits, which also range-checks them.
+#[derive(Copy, Clone, Debug, Default, GateSerialize)]
+pub struct U32BitwiseGate<F: RichField + Extendable<D>, const D: usize> {
+    pub op: BitwiseOp,
+    pub num_ops: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> U32BitwiseGate<F, D> {
+    pub fn new_from_config(config: &CircuitConfig, op: BitwiseOp) -> Self {
+        Self { op, num_ops: Self::num_ops(config), _phantom: PhantomData }
+    }
+
+    pub(crate) fn num_ops(config: &CircuitConfig) -> usize {
+        let wires_per_op = 3 + 2 * 32;
+        (config.num_wires / wires_per_op).min(config.num_routed_wires / 3)
+    }
+
+    pub fn wire_ith_input_x(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        3 * i
+    }
+
+    pub fn wire_ith_input_y(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        3 * i + 1
+    }
+
+    pub fn wire_ith_output(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        3 * i + 2
+    }
+
+    /// The `j`-th bit of the `i`-th `x` input, starting from the least significant one.
+    pub fn wire_ith_input_x_jth_bit(&self, i: usize, j: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        debug_assert!(j < 32);
+        3 * self.num_ops + 64 * i + j
+    }
+
+    /// The `j`-th bit of the `i`-th `y` input, starting from the least significant one.
+    pub fn wire_ith_input_y_jth_bit(&self, i: usize, j: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        debug_assert!(j < 32);
+        3 * self.num_ops + 64 * i + 32 + j
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for U32BitwiseGate<F, D> {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        let mut template_str = "template U32Bitwise$OPOps$NUM_OPS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  signal combined_x[$NUM_OPS][33][2];
+  signal combined_y[$NUM_OPS][33][2];
+  signal combined_output[$NUM_OPS][33][2];
+  signal xy[$NUM_OPS][32][2];
+  var index = 0;
+  for (var i = 0; i < $NUM_OPS; i++) {
+    var start = $BITS_START + 64 * i;
+    combined_x[i][0] <== GlExt(0, 0)();
+    combined_y[i][0] <== GlExt(0, 0)();
+    combined_output[i][0] <== GlExt(0, 0)();
+    for (var j = 0; j < 32; j++) {
+      var x[2] = wires[start + j];
+      var y[2] = wires[start + 32 + j];
+      out[index] <== ConstraintPush()(constraints[index], filter, GlExtMul()(x, GlExtSub()(x, GlExt(1, 0)())));
+      index++;
+      out[index] <== ConstraintPush()(constraints[index], filter, GlExtMul()(y, GlExtSub()(y, GlExt(1, 0)())));
+      index++;
+      combined_x[i][j + 1] <== GlExtScalarMulAdd()(x, 1 << j, combined_x[i][j]);
+      combined_y[i][j + 1] <== GlExtScalarMulAdd()(y, 1 << j, combined_y[i][j]);
+      xy[i][j] <== GlExtMul()(x, y);
+      combined_output[i][j + 1] <== GlExtScalarMulAdd()($OUTPUT_BIT, 1 << j, combined_output[i][j]);
+    }
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(wires[3 * i], combined_x[i][32]));
+    index++;
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(wires[3 * i + 1], combined_y[i][32]));
+    index++;
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(wires[3 * i + 2], combined_output[i][32]));
+    index++;
+  }
+
+  for (var i = index; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+
+        let output_bit = match self.op {
+            BitwiseOp::And => "xy[i][j]",
+            BitwiseOp::Or => "GlExtSub()(GlExtAdd()(x, y), xy[i][j])",
+            BitwiseOp::Xor => "GlExtSub()(GlExtAdd()(x, y), GlExtScalarMul()(xy[i][j], 2))",
+        };
+        template_str = template_str.replace("$OUTPUT_BIT", output_bit);
+        template_str = template_str.replace("$OP", self.op.name());
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str = template_str.replace("$BITS_START", &(3 * self.num_ops).to_string());
+
+        template_str
+    }
+
+    fn export_solidity_verification_code(&self) -> String {
+        let mut template_str = "library U32Bitwise$OPOps$NUM_OPSLib {
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint32 index = 0;
+        for (uint32 i = 0; i < $NUM_OPS; i++) {
+            uint32 start = $BITS_START + 64 * i;
+            uint64[2] memory combined_x;
+            uint64[2] memory combined_y;
+            uint64[2] memory combined_output;
+            for (uint32 j = 0; j < 32; j++) {
+                uint64[2] memory x = ev.wires[start + j];
+                uint64[2] memory y = ev.wires[start + 32 + j];
+                GatesUtilsLib.push(constraints, ev.filter, index++, x.mul(x.sub(GoldilocksExtLib.one())));
+                GatesUtilsLib.push(constraints, ev.filter, index++, y.mul(y.sub(GoldilocksExtLib.one())));
+                combined_x = combined_x.add(x.scalar_mul(uint64(1) << j));
+                combined_y = combined_y.add(y.scalar_mul(uint64(1) << j));
+                combined_output = combined_output.add($OUTPUT_BIT.scalar_mul(uint64(1) << j));
+            }
+            GatesUtilsLib.push(constraints, ev.filter, index++, ev.wires[3 * i].sub(combined_x));
+            GatesUtilsLib.push(constraints, ev.filter, index++, ev.wires[3 * i + 1].sub(combined_y));
+            GatesUtilsLib.push(constraints, ev.filter, index++, ev.wires[3 * i + 2].sub(combined_output));
+        }
+    }
+}"
+        .to_string();
+
+        let output_bit = match self.op {
+            BitwiseOp::And => "x.mul(y)",
+            BitwiseOp::Or => "x.add(y).sub(x.mul(y))",
+            BitwiseOp::Xor => "x.add(y).sub(x.mul(y).scalar_mul(2))",
+        };
+        template_str = template_str.replace("$OUTPUT_BIT", output_bit);
+        template_str = template_str.replace("$OP", self.op.name());
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str = template_str.replace("$BITS_START", &(3 * self.num_ops).to_string());
+
+        template_str
+    }
+
+    plonky2::derived_serialization!();
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_ops {
+            let mut combined_x = F::Extension::ZERO;
+            let mut combined_y = F::Extension::ZERO;
+            let mut combined_output = F::Extension::ZERO;
+            for j in 0..32 {
+                let x = vars.local_wires[self.wire_ith_input_x_jth_bit(i, j)];
+                let y = vars.local_wires[self.wire_ith_input_y_jth_bit(i, j)];
+                constraints.push(x * (x - F::Extension::ONE));
+                constraints.push(y * (y - F::Extension::ONE));
+
+                let power = F::Extension::from_canonical_u64(1 << j);
+                combined_x += x * power;
+                combined_y += y * power;
+                combined_output += self.op.apply_to_bits(x, y) * power;
+            }
+
+            constraints.push(vars.local_wires[self.wire_ith_input_x(i)] - combined_x);
+            constraints.push(vars.local_wires[self.wire_ith_input_y(i)] - combined_y);
+            constraints.push(vars.local_wires[self.wire_ith_output(i)] - combined_output);
+        }
+
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        vars: EvaluationVarsBase<F>,
+        mut yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        for i in 0..self.num_ops {
+            let mut combined_x = F::ZERO;
+            let mut combined_y = F::ZERO;
+            let mut combined_output = F::ZERO;
+            for j in 0..32 {
+                let x = vars.local_wires[self.wire_ith_input_x_jth_bit(i, j)];
+                let y = vars.local_wires[self.wire_ith_input_y_jth_bit(i, j)];
+                yield_constr.one(x * (x - F::ONE));
+                yield_constr.one(y * (y - F::ONE));
+
+                let power = F::from_canonical_u64(1 << j);
+                combined_x += x * power;
+                combined_y += y * power;
+                combined_output += self.op.apply_to_bits(x, y) * power;
+            }
+
+            yield_constr.one(vars.local_wires[self.wire_ith_input_x(i)] - combined_x);
+            yield_constr.one(vars.local_wires[self.wire_ith_input_y(i)] - combined_y);
+            yield_constr.one(vars.local_wires[self.wire_ith_output(i)] - combined_output);
+        }
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_ops {
+            let mut combined_x = builder.zero_extension();
+            let mut combined_y = builder.zero_extension();
+            let mut combined_output = builder.zero_extension();
+            for j in 0..32 {
+                let x = vars.local_wires[self.wire_ith_input_x_jth_bit(i, j)];
+                let y = vars.local_wires[self.wire_ith_input_y_jth_bit(i, j)];
+                constraints.push(builder.mul_sub_extension(x, x, x));
+                constraints.push(builder.mul_sub_extension(y, y, y));
+
+                let power = F::from_canonical_u64(1 << j);
+                combined_x = builder.mul_const_add_extension(power, x, combined_x);
+                combined_y = builder.mul_const_add_extension(power, y, combined_y);
+                let output_bit = self.op.apply_to_bit_targets(builder, x, y);
+                combined_output =
+                    builder.mul_const_add_extension(power, output_bit, combined_output);
+            }
+
+            let input_x = vars.local_wires[self.wire_ith_input_x(i)];
+            let input_y = vars.local_wires[self.wire_ith_input_y(i)];
+            let output = vars.local_wires[self.wire_ith_output(i)];
+            constraints.push(builder.sub_extension(input_x, combined_x));
+            constraints.push(builder.sub_extension(input_y, combined_y));
+            constraints.push(builder.sub_extension(output, combined_output));
+        }
+
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        (0..self.num_ops)
+            .map(|i| {
+                WitnessGeneratorRef::new(
+                    U32BitwiseGenerator { gate: *self, row, i, _phantom: PhantomData }.adapter(),
+                )
+            })
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        (3 + 2 * 32) * self.num_ops
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn num_constraints(&self) -> usize {
+        (2 * 32 + 3) * self.num_ops
+    }
+}
+
+#[derive(Clone, Debug, Default, GeneratorSerialize)]
+pub(crate) struct U32BitwiseGenerator<F: RichField + Extendable<D>, const D: usize> {
+    gate: U32BitwiseGate<F, D>,
+    row: usize,
+    i: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for U32BitwiseGenerator<F, D>
+{
+    fn id(&self) -> String {
+        "U32BitwiseGenerator".to_string()
+    }
+
+    plonky2::derived_serialization!();
+
+    fn dependencies(&self) -> Vec<Target> {
+        let local_target = |column| Target::wire(self.row, column);
+
+        [
+            local_target(self.gate.wire_ith_input_x(self.i)),
+            local_target(self.gate.wire_ith_input_y(self.i)),
+        ]
+        .to_vec()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let local_wire = |column| Wire { row: self.row, column };
+
+        let x = witness.get_wire(local_wire(self.gate.wire_ith_input_x(self.i))).to_canonical_u64();
+        let y = witness.get_wire(local_wire(self.gate.wire_ith_input_y(self.i))).to_canonical_u64();
+        debug_assert!(x < 1 << 32 && y < 1 << 32, "Inputs are not u32s");
+        let (x, y) = (x as u32, y as u32);
+
+        for j in 0..32 {
+            let x_bit = F::from_bool((x >> j) & 1 == 1);
+            let y_bit = F::from_bool((y >> j) & 1 == 1);
+            out_buffer.set_wire(local_wire(self.gate.wire_ith_input_x_jth_bit(self.i, j)), x_bit);
+            out_buffer.set_wire(local_wire(self.gate.wire_ith_input_y_jth_bit(self.i, j)), y_bit);
+        }
+        let output = self.gate.op.apply(x, y);
+        out_buffer
+            .set_wire(local_wire(self.gate.wire_ith_output(self.i)), F::from_canonical_u32(output));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::{
+        field::{goldilocks_field::GoldilocksField, types::Sample},
+        gates::gate_testing::{test_eval_fns, test_low_degree},
+        hash::hash_types::HashOut,
+        plonk::config::{GenericConfig, PoseidonGoldilocksConfig},
+    };
+    use rand::{rngs::OsRng, Rng};
+
+    use super::*;
+
+    const OPS: [BitwiseOp; 3] = [BitwiseOp::And, BitwiseOp::Or, BitwiseOp::Xor];
+
+    #[test]
+    fn low_degree() {
+        for op in OPS {
+            test_low_degree::<GoldilocksField, _, 4>(U32BitwiseGate::<GoldilocksField, 4> {
+                op,
+                num_ops: 2,
+                _phantom: PhantomData,
+            })
+        }
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        for op in OPS {
+            test_eval_fns::<F, C, _, D>(U32BitwiseGate::<F, D> {
+                op,
+                num_ops: 2,
+                _phantom: PhantomData,
+            })?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_gate_constraint() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type FF = <C as GenericConfig<D>>::FE;
+        const NUM_OPS: usize = 2;
+
+        fn get_wires(inputs: &[(u32, u32)], outputs: &[u32]) -> Vec<FF> {
+            let mut v0 = Vec::new();
+            let mut v1 = Vec::new();
+            for (&(x, y), &output) in inputs.iter().zip(outputs) {
+                v0.extend([x, y, output].map(F::from_canonical_u32));
+                v1.extend((0..32).map(|j| F::from_bool((x >> j) & 1 == 1)));
+                v1.extend((0..32).map(|j| F::from_bool((y >> j) & 1 == 1)));
+            }
+            v0.iter().chain(v1.iter()).map(|&x| x.into()).collect()
+        }
+
+        let mut rng = OsRng;
+        for op in OPS {
+            let gate = U32BitwiseGate::<F, D> { op, num_ops: NUM_OPS, _phantom: PhantomData };
+            let inputs: Vec<(u32, u32)> = (0..NUM_OPS).map(|_| rng.gen()).collect();
+            let outputs: Vec<u32> = inputs.iter().map(|&(x, y)| op.apply(x, y)).collect();
+
+            let wires = get_wires(&inputs, &outputs);
+            let vars = EvaluationVars {
+                local_constants: &[],
+                local_wires: &wires,
+                public_inputs_hash: &HashOut::rand(),
+            };
+            assert!(
+                gate.eval_unfiltered(vars).iter().all(|x| x.is_zero()),
+                "Gate constraints are not satisfied."
+            );
+
+            let mut bad_outputs = outputs.clone();
+            bad_outputs[1] = !bad_outputs[1];
+            let wires = get_wires(&inputs, &bad_outputs);
+            let vars = EvaluationVars {
+                local_constants: &[],
+                local_wires: &wires,
+                public_inputs_hash: &HashOut::rand(),
+            };
+            assert!(
+                !gate.eval_unfiltered(vars).iter().all(|x| x.is_zero()),
+                "Gate constraints are satisfied but should not be."
+            );
+        }
+    }
+}
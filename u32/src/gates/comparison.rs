@@ -5,10 +5,7 @@ use alloc::{
     vec::Vec,
 };
 use core::marker::PhantomData;
-use plonky2::{
-    plonk::circuit_data::CommonCircuitData,
-    util::serialization::{Buffer, IoResult, Read, Write},
-};
+use plonky2::util::serialization::{GateSerialize, GeneratorSerialize};
 
 use plonky2::{
     field::{
@@ -37,7 +34,7 @@ use plonky2::{
 };
 
 /// A gate for checking that one value is less than or equal to another.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, GateSerialize)]
 pub struct ComparisonGate<F: Field64 + Extendable<D>, const D: usize> {
     pub(crate) num_bits: usize,
     pub(crate) num_chunks: usize,
@@ -107,22 +104,159 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ComparisonGate
     }
 
     fn export_circom_verification_code(&self) -> String {
-        todo!();
+        let mut template_str = "template Comparison$NUM_BITSChunks$NUM_CHUNKS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  // Get chunks and assert that they match.
+  signal first_chunks_combined[$NUM_CHUNKS + 1][2];
+  signal second_chunks_combined[$NUM_CHUNKS + 1][2];
+  first_chunks_combined[$NUM_CHUNKS] <== GlExt(0, 0)();
+  second_chunks_combined[$NUM_CHUNKS] <== GlExt(0, 0)();
+  for (var i = $NUM_CHUNKS; i > 0; i--) {
+    first_chunks_combined[i - 1] <== GlExtScalarMulAdd()(first_chunks_combined[i], $CHUNK_SIZE, wires[3 + i]);
+    second_chunks_combined[i - 1] <== GlExtScalarMulAdd()(second_chunks_combined[i], $CHUNK_SIZE, wires[3 + $NUM_CHUNKS + i]);
+  }
+  out[0] <== ConstraintPush()(constraints[0], filter, GlExtSub()(first_chunks_combined[0], wires[0]));
+  out[1] <== ConstraintPush()(constraints[1], filter, GlExtSub()(second_chunks_combined[0], wires[1]));
+
+  var index = 2;
+  signal differences[$NUM_CHUNKS][2];
+  signal not_equal[$NUM_CHUNKS][2];
+  signal most_significant_diff_so_far[$NUM_CHUNKS + 1][2];
+  most_significant_diff_so_far[0] <== GlExt(0, 0)();
+  for (var i = 0; i < $NUM_CHUNKS; i++) {
+    // Range-check the chunks to be less than `chunk_size`.
+    out[index] <== ConstraintPush()(constraints[index], filter, RangeProduct($CHUNK_SIZE)(wires[4 + i]));
+    index++;
+    out[index] <== ConstraintPush()(constraints[index], filter, RangeProduct($CHUNK_SIZE)(wires[4 + $NUM_CHUNKS + i]));
+    index++;
+
+    differences[i] <== GlExtSub()(wires[4 + $NUM_CHUNKS + i], wires[4 + i]);
+    var chunks_equal[2] = wires[4 + 3 * $NUM_CHUNKS + i];
+    not_equal[i] <== GlExtSub()(GlExt(1, 0)(), chunks_equal);
+
+    // Two constraints to assert that `chunks_equal` is valid.
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(GlExtMul()(differences[i], wires[4 + 2 * $NUM_CHUNKS + i]), not_equal[i]));
+    index++;
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtMul()(chunks_equal, differences[i]));
+    index++;
+
+    // Update `most_significant_diff_so_far`.
+    var intermediate_value[2] = wires[4 + 4 * $NUM_CHUNKS + i];
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(intermediate_value, GlExtMul()(chunks_equal, most_significant_diff_so_far[i])));
+    index++;
+    most_significant_diff_so_far[i + 1] <== GlExtMulAdd()(not_equal[i], differences[i], intermediate_value);
+  }
+
+  var most_significant_diff[2] = wires[3];
+  out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(most_significant_diff, most_significant_diff_so_far[$NUM_CHUNKS]));
+  index++;
+
+  // Range-check the bits.
+  signal bits_combined[$CHUNK_BITS + 2][2];
+  bits_combined[$CHUNK_BITS + 1] <== GlExt(0, 0)();
+  for (var i = $CHUNK_BITS + 1; i > 0; i--) {
+    var bit[2] = wires[4 + 5 * $NUM_CHUNKS + i - 1];
+    out[index + i - 1] <== ConstraintPush()(constraints[index + i - 1], filter, GlExtMul()(bit, GlExtSub()(GlExt(1, 0)(), bit)));
+    bits_combined[i - 1] <== GlExtScalarMulAdd()(bits_combined[i], 2, bit);
+  }
+  index += $CHUNK_BITS + 1;
+  out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(GlExtAdd()(GlExt($CHUNK_SIZE, 0)(), most_significant_diff), bits_combined[0]));
+  index++;
+
+  // Iff first <= second, the top (n + 1st) bit of (2^n + most_significant_diff) will be 1.
+  out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(wires[2], wires[4 + 5 * $NUM_CHUNKS + $CHUNK_BITS]));
+  index++;
+
+  for (var i = index; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+
+        template_str = template_str.replace("$NUM_BITS", &self.num_bits.to_string());
+        template_str = template_str.replace("$NUM_CHUNKS", &self.num_chunks.to_string());
+        template_str = template_str.replace("$CHUNK_BITS", &self.chunk_bits().to_string());
+        template_str = template_str.replace("$CHUNK_SIZE", &(1 << self.chunk_bits()).to_string());
+
+        template_str
     }
     fn export_solidity_verification_code(&self) -> String {
-        todo!();
+        let mut template_str = "library Comparison$NUM_BITSChunks$NUM_CHUNKSLib {
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
     }
-    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
-        dst.write_usize(self.num_bits)?;
-        dst.write_usize(self.num_chunks)?;
-        Ok(())
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        // Get chunks and assert that they match.
+        {
+            uint64[2] memory first_chunks_combined;
+            uint64[2] memory second_chunks_combined;
+            for (uint32 i = $NUM_CHUNKS; i > 0; i--) {
+                first_chunks_combined = first_chunks_combined.scalar_mul($CHUNK_SIZE).add(ev.wires[3 + i]);
+                second_chunks_combined = second_chunks_combined.scalar_mul($CHUNK_SIZE).add(ev.wires[3 + $NUM_CHUNKS + i]);
+            }
+            GatesUtilsLib.push(constraints, ev.filter, 0, first_chunks_combined.sub(ev.wires[0]));
+            GatesUtilsLib.push(constraints, ev.filter, 1, second_chunks_combined.sub(ev.wires[1]));
+        }
+
+        uint32 index = 2;
+        uint64[2] memory most_significant_diff_so_far;
+        for (uint32 i = 0; i < $NUM_CHUNKS; i++) {
+            // Range-check the chunks to be less than `chunk_size`.
+            GatesUtilsLib.push(constraints, ev.filter, index++, GatesUtilsLib.range_product(ev.wires[4 + i], $CHUNK_SIZE));
+            GatesUtilsLib.push(constraints, ev.filter, index++, GatesUtilsLib.range_product(ev.wires[4 + $NUM_CHUNKS + i], $CHUNK_SIZE));
+
+            uint64[2] memory difference = ev.wires[4 + $NUM_CHUNKS + i].sub(ev.wires[4 + i]);
+            uint64[2] memory chunks_equal = ev.wires[4 + 3 * $NUM_CHUNKS + i];
+            uint64[2] memory not_equal = GoldilocksExtLib.one().sub(chunks_equal);
+
+            // Two constraints to assert that `chunks_equal` is valid.
+            GatesUtilsLib.push(constraints, ev.filter, index++, difference.mul(ev.wires[4 + 2 * $NUM_CHUNKS + i]).sub(not_equal));
+            GatesUtilsLib.push(constraints, ev.filter, index++, chunks_equal.mul(difference));
+
+            // Update `most_significant_diff_so_far`.
+            uint64[2] memory intermediate_value = ev.wires[4 + 4 * $NUM_CHUNKS + i];
+            GatesUtilsLib.push(constraints, ev.filter, index++, intermediate_value.sub(chunks_equal.mul(most_significant_diff_so_far)));
+            most_significant_diff_so_far = intermediate_value.add(not_equal.mul(difference));
+        }
+
+        uint64[2] memory most_significant_diff = ev.wires[3];
+        GatesUtilsLib.push(constraints, ev.filter, index++, most_significant_diff.sub(most_significant_diff_so_far));
+
+        // Range-check the bits.
+        uint64[2] memory bits_combined;
+        for (uint32 i = $CHUNK_BITS + 1; i > 0; i--) {
+            uint64[2] memory bit = ev.wires[4 + 5 * $NUM_CHUNKS + i - 1];
+            GatesUtilsLib.push(constraints, ev.filter, index + i - 1, bit.mul(GoldilocksExtLib.one().sub(bit)));
+            bits_combined = bits_combined.scalar_mul(2).add(bit);
+        }
+        index += $CHUNK_BITS + 1;
+        GatesUtilsLib.push(constraints, ev.filter, index++, GatesUtilsLib.field_ext_from($CHUNK_SIZE, 0).add(most_significant_diff).sub(bits_combined));
+
+        // Iff first <= second, the top (n + 1st) bit of (2^n + most_significant_diff) will be 1.
+        GatesUtilsLib.push(constraints, ev.filter, index, ev.wires[2].sub(ev.wires[4 + 5 * $NUM_CHUNKS + $CHUNK_BITS]));
     }
+}"
+        .to_string();
+
+        template_str = template_str.replace("$NUM_BITS", &self.num_bits.to_string());
+        template_str = template_str.replace("$NUM_CHUNKS", &self.num_chunks.to_string());
+        template_str = template_str.replace("$CHUNK_BITS", &self.chunk_bits().to_string());
+        template_str = template_str.replace("$CHUNK_SIZE", &(1 << self.chunk_bits()).to_string());
 
-    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let num_bits = src.read_usize()?;
-        let num_chunks = src.read_usize()?;
-        Ok(Self { num_bits, num_chunks, _phantom: PhantomData })
+        template_str
     }
+    plonky2::derived_serialization!();
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let mut constraints = Vec::with_capacity(self.num_constraints());
@@ -406,8 +540,8 @@ impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D>
     }
 }
 
-#[derive(Debug, Clone)]
-struct ComparisonGenerator<F: RichField + Extendable<D>, const D: usize> {
+#[derive(Debug, Clone, Default, GeneratorSerialize)]
+pub(crate) struct ComparisonGenerator<F: RichField + Extendable<D>, const D: usize> {
     row: usize,
     gate: ComparisonGate<F, D>,
 }
@@ -509,16 +643,7 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
         }
     }
 
-    fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
-        dst.write_usize(self.row)?;
-        self.gate.serialize(dst, common_data)
-    }
-
-    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let row = src.read_usize()?;
-        let gate = ComparisonGate::deserialize(src, common_data)?;
-        Ok(Self { row, gate })
-    }
+    plonky2::derived_serialization!();
 }
 
 #[cfg(test)]
@@ -5,10 +5,7 @@ use alloc::{
     vec::Vec,
 };
 use core::marker::PhantomData;
-use plonky2::{
-    plonk::circuit_data::CommonCircuitData,
-    util::serialization::{Buffer, IoResult, Read, Write},
-};
+use plonky2::util::serialization::{GateSerialize, GeneratorSerialize};
 
 use plonky2::{
     field::{extension::Extendable, types::Field},
@@ -29,7 +26,7 @@ use plonky2::{
 };
 
 /// A gate which can decompose a number into base B little-endian limbs.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default, GateSerialize)]
 pub struct U32RangeCheckGate<F: RichField + Extendable<D>, const D: usize> {
     pub num_input_limbs: usize,
     _phantom: PhantomData<F>,
@@ -63,19 +60,78 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for U32RangeCheckG
     }
 
     fn export_circom_verification_code(&self) -> String {
-        todo!();
+        let mut template_str = "template U32RangeCheck$NUM_INPUT_LIMBS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  var index = 0;
+  signal computed_sums[$NUM_INPUT_LIMBS][$AUX_LIMBS_PER_INPUT_LIMB + 1][2];
+  for (var i = 0; i < $NUM_INPUT_LIMBS; i++) {
+    var start = $NUM_INPUT_LIMBS + $AUX_LIMBS_PER_INPUT_LIMB * i;
+    computed_sums[i][$AUX_LIMBS_PER_INPUT_LIMB] <== GlExt(0, 0)();
+    for (var j = $AUX_LIMBS_PER_INPUT_LIMB; j > 0; j--) {
+      computed_sums[i][j - 1] <== GlExtScalarMulAdd()(computed_sums[i][j], $BASE, wires[start + j - 1]);
+    }
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(computed_sums[i][0], wires[i]));
+    index++;
+    for (var j = 0; j < $AUX_LIMBS_PER_INPUT_LIMB; j++) {
+      out[index] <== ConstraintPush()(constraints[index], filter, RangeProduct($BASE)(wires[start + j]));
+      index++;
+    }
+  }
+
+  for (var i = index; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+
+        template_str = template_str.replace("$NUM_INPUT_LIMBS", &self.num_input_limbs.to_string());
+        template_str = template_str
+            .replace("$AUX_LIMBS_PER_INPUT_LIMB", &self.aux_limbs_per_input_limb().to_string());
+        template_str = template_str.replace("$BASE", &Self::BASE.to_string());
+
+        template_str
     }
     fn export_solidity_verification_code(&self) -> String {
-        todo!();
+        let mut template_str = "library U32RangeCheck$NUM_INPUT_LIMBSLib {
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
     }
-    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
-        dst.write_usize(self.num_input_limbs)
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint32 index = 0;
+        for (uint32 i = 0; i < $NUM_INPUT_LIMBS; i++) {
+            uint32 start = $NUM_INPUT_LIMBS + $AUX_LIMBS_PER_INPUT_LIMB * i;
+            uint64[2] memory computed_sum;
+            for (uint32 j = $AUX_LIMBS_PER_INPUT_LIMB; j > 0; j--) {
+                computed_sum = computed_sum.scalar_mul($BASE).add(ev.wires[start + j - 1]);
+            }
+            GatesUtilsLib.push(constraints, ev.filter, index++, computed_sum.sub(ev.wires[i]));
+            for (uint32 j = 0; j < $AUX_LIMBS_PER_INPUT_LIMB; j++) {
+                GatesUtilsLib.push(constraints, ev.filter, index++, GatesUtilsLib.range_product(ev.wires[start + j], $BASE));
+            }
+        }
     }
+}"
+        .to_string();
 
-    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let num_input_limbs = src.read_usize()?;
-        Ok(Self { num_input_limbs, _phantom: PhantomData })
+        template_str = template_str.replace("$NUM_INPUT_LIMBS", &self.num_input_limbs.to_string());
+        template_str = template_str
+            .replace("$AUX_LIMBS_PER_INPUT_LIMB", &self.aux_limbs_per_input_limb().to_string());
+        template_str = template_str.replace("$BASE", &Self::BASE.to_string());
+
+        template_str
     }
+    plonky2::derived_serialization!();
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let mut constraints = Vec::with_capacity(self.num_constraints());
@@ -181,7 +237,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for U32RangeCheckG
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, GeneratorSerialize)]
 pub struct U32RangeCheckGenerator<F: RichField + Extendable<D>, const D: usize> {
     gate: U32RangeCheckGate<F, D>,
     row: usize,
@@ -225,16 +281,7 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
         }
     }
 
-    fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
-        self.gate.serialize(dst, common_data)?;
-        dst.write_usize(self.row)
-    }
-
-    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let gate = U32RangeCheckGate::deserialize(src, common_data)?;
-        let row = src.read_usize()?;
-        Ok(Self { row, gate })
-    }
+    plonky2::derived_serialization!();
 }
 
 #[cfg(test)]
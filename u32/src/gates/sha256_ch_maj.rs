@@ -0,0 +1,484 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::marker::PhantomData;
+use plonky2::util::serialization::{GateSerialize, GeneratorSerialize};
+
+use plonky2::{
+    field::{extension::Extendable, types::Field},
+    gates::{gate::Gate, util::StridedConstraintConsumer},
+    hash::hash_types::RichField,
+    iop::{
+        ext_target::ExtensionTarget,
+        generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef},
+        target::Target,
+        wire::Wire,
+        witness::{PartitionWitness, Witness, WitnessWrite},
+    },
+    plonk::{
+        circuit_builder::CircuitBuilder,
+        circuit_data::CircuitConfig,
+        vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase},
+    },
+};
+
+/// A gate computing either the SHA-256 choice function `Ch(x, y, z) = (x & y) ^ (!x & z)` or the
+/// majority function `Maj(x, y, z) = (x & y) ^ (x & z) ^ (y & z)` on 32-bit words. All three
+/// inputs are decomposed into bits, which also range-checks them.
+#[derive(Copy, Clone, Debug, Default, GateSerialize)]
+pub struct Sha256ChMajGate<F: RichField + Extendable<D>, const D: usize> {
+    pub majority: bool,
+    pub num_ops: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Sha256ChMajGate<F, D> {
+    pub fn new_from_config(config: &CircuitConfig, majority: bool) -> Self {
+        Self { majority, num_ops: Self::num_ops(config), _phantom: PhantomData }
+    }
+
+    pub(crate) fn num_ops(config: &CircuitConfig) -> usize {
+        let wires_per_op = 4 + 3 * 32;
+        (config.num_wires / wires_per_op).min(config.num_routed_wires / 4)
+    }
+
+    /// The `k`-th input of the `i`-th operation, with `k` one of 0, 1 and 2 for `x`, `y` and `z`.
+    pub fn wire_ith_input(&self, i: usize, k: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        debug_assert!(k < 3);
+        4 * i + k
+    }
+
+    pub fn wire_ith_output(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        4 * i + 3
+    }
+
+    /// The `j`-th bit of the `k`-th input of the `i`-th operation, starting from the least
+    /// significant one.
+    pub fn wire_ith_input_jth_bit(&self, i: usize, k: usize, j: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        debug_assert!(k < 3);
+        debug_assert!(j < 32);
+        4 * self.num_ops + 96 * i + 32 * k + j
+    }
+
+    fn name(&self) -> &'static str {
+        if self.majority {
+            "Maj"
+        } else {
+            "Ch"
+        }
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for Sha256ChMajGate<F, D> {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        "template Sha256$NAMEOps$NUM_OPS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  signal combined_bits[$NUM_OPS][3][33][2];
+  signal combined_output[$NUM_OPS][33][2];
+  signal xy[$NUM_OPS][32][2];
+  signal output_bit[$NUM_OPS][32][2];
+  var index = 0;
+  for (var i = 0; i < $NUM_OPS; i++) {
+    var start = $BITS_START + 96 * i;
+    for (var k = 0; k < 3; k++) {
+      combined_bits[i][k][0] <== GlExt(0, 0)();
+      for (var j = 0; j < 32; j++) {
+        var bit[2] = wires[start + 32 * k + j];
+        out[index] <== ConstraintPush()(constraints[index], filter, GlExtMul()(bit, GlExtSub()(bit, GlExt(1, 0)())));
+        index++;
+        combined_bits[i][k][j + 1] <== GlExtScalarMulAdd()(bit, 1 << j, combined_bits[i][k][j]);
+      }
+      out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(wires[4 * i + k], combined_bits[i][k][32]));
+      index++;
+    }
+
+    combined_output[i][0] <== GlExt(0, 0)();
+    for (var j = 0; j < 32; j++) {
+      var x[2] = wires[start + j];
+      var y[2] = wires[start + 32 + j];
+      var z[2] = wires[start + 64 + j];
+      if ($MAJORITY == 1) {
+        // xy + z(x + y - 2xy)
+        xy[i][j] <== GlExtMul()(x, y);
+        output_bit[i][j] <== GlExtAdd()(xy[i][j], GlExtMul()(z, GlExtSub()(GlExtAdd()(x, y), GlExtScalarMul()(xy[i][j], 2))));
+      } else {
+        // z + x(y - z)
+        xy[i][j] <== GlExtMul()(x, GlExtSub()(y, z));
+        output_bit[i][j] <== GlExtAdd()(z, xy[i][j]);
+      }
+      combined_output[i][j + 1] <== GlExtScalarMulAdd()(output_bit[i][j], 1 << j, combined_output[i][j]);
+    }
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(wires[4 * i + 3], combined_output[i][32]));
+    index++;
+  }
+
+  for (var i = index; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .replace("$NAME", self.name())
+        .replace("$NUM_OPS", &self.num_ops.to_string())
+        .replace("$BITS_START", &(4 * self.num_ops).to_string())
+        .replace("$MAJORITY", &(self.majority as usize).to_string())
+    }
+
+    fn export_solidity_verification_code(&self) -> String {
+        "library Sha256$NAMEOps$NUM_OPSLib {
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint32 index = 0;
+        for (uint32 i = 0; i < $NUM_OPS; i++) {
+            uint32 start = $BITS_START + 96 * i;
+            for (uint32 k = 0; k < 3; k++) {
+                uint64[2] memory combined_bits;
+                for (uint32 j = 0; j < 32; j++) {
+                    uint64[2] memory bit = ev.wires[start + 32 * k + j];
+                    GatesUtilsLib.push(constraints, ev.filter, index++, bit.mul(bit.sub(GoldilocksExtLib.one())));
+                    combined_bits = combined_bits.add(bit.scalar_mul(uint64(1) << j));
+                }
+                GatesUtilsLib.push(constraints, ev.filter, index++, ev.wires[4 * i + k].sub(combined_bits));
+            }
+
+            uint64[2] memory combined_output;
+            for (uint32 j = 0; j < 32; j++) {
+                uint64[2] memory x = ev.wires[start + j];
+                uint64[2] memory y = ev.wires[start + 32 + j];
+                uint64[2] memory z = ev.wires[start + 64 + j];
+                uint64[2] memory output_bit;
+                if ($MAJORITY == 1) {
+                    uint64[2] memory xy = x.mul(y);
+                    output_bit = xy.add(z.mul(x.add(y).sub(xy.scalar_mul(2))));
+                } else {
+                    output_bit = z.add(x.mul(y.sub(z)));
+                }
+                combined_output = combined_output.add(output_bit.scalar_mul(uint64(1) << j));
+            }
+            GatesUtilsLib.push(constraints, ev.filter, index++, ev.wires[4 * i + 3].sub(combined_output));
+        }
+    }
+}"
+        .replace("$NAME", self.name())
+        .replace("$NUM_OPS", &self.num_ops.to_string())
+        .replace("$BITS_START", &(4 * self.num_ops).to_string())
+        .replace("$MAJORITY", &(self.majority as usize).to_string())
+    }
+
+    plonky2::derived_serialization!();
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_ops {
+            for k in 0..3 {
+                let mut combined_bits = F::Extension::ZERO;
+                for j in 0..32 {
+                    let bit = vars.local_wires[self.wire_ith_input_jth_bit(i, k, j)];
+                    constraints.push(bit * (bit - F::Extension::ONE));
+                    combined_bits += bit * F::Extension::from_canonical_u64(1 << j);
+                }
+                constraints.push(vars.local_wires[self.wire_ith_input(i, k)] - combined_bits);
+            }
+
+            let mut combined_output = F::Extension::ZERO;
+            for j in 0..32 {
+                let x = vars.local_wires[self.wire_ith_input_jth_bit(i, 0, j)];
+                let y = vars.local_wires[self.wire_ith_input_jth_bit(i, 1, j)];
+                let z = vars.local_wires[self.wire_ith_input_jth_bit(i, 2, j)];
+                let output_bit = if self.majority {
+                    let xy = x * y;
+                    xy + z * (x + y - xy.double())
+                } else {
+                    z + x * (y - z)
+                };
+                combined_output += output_bit * F::Extension::from_canonical_u64(1 << j);
+            }
+            constraints.push(vars.local_wires[self.wire_ith_output(i)] - combined_output);
+        }
+
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        vars: EvaluationVarsBase<F>,
+        mut yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        for i in 0..self.num_ops {
+            for k in 0..3 {
+                let mut combined_bits = F::ZERO;
+                for j in 0..32 {
+                    let bit = vars.local_wires[self.wire_ith_input_jth_bit(i, k, j)];
+                    yield_constr.one(bit * (bit - F::ONE));
+                    combined_bits += bit * F::from_canonical_u64(1 << j);
+                }
+                yield_constr.one(vars.local_wires[self.wire_ith_input(i, k)] - combined_bits);
+            }
+
+            let mut combined_output = F::ZERO;
+            for j in 0..32 {
+                let x = vars.local_wires[self.wire_ith_input_jth_bit(i, 0, j)];
+                let y = vars.local_wires[self.wire_ith_input_jth_bit(i, 1, j)];
+                let z = vars.local_wires[self.wire_ith_input_jth_bit(i, 2, j)];
+                let output_bit = if self.majority {
+                    let xy = x * y;
+                    xy + z * (x + y - xy.double())
+                } else {
+                    z + x * (y - z)
+                };
+                combined_output += output_bit * F::from_canonical_u64(1 << j);
+            }
+            yield_constr.one(vars.local_wires[self.wire_ith_output(i)] - combined_output);
+        }
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_ops {
+            for k in 0..3 {
+                let mut combined_bits = builder.zero_extension();
+                for j in 0..32 {
+                    let bit = vars.local_wires[self.wire_ith_input_jth_bit(i, k, j)];
+                    constraints.push(builder.mul_sub_extension(bit, bit, bit));
+                    combined_bits = builder.mul_const_add_extension(
+                        F::from_canonical_u64(1 << j),
+                        bit,
+                        combined_bits,
+                    );
+                }
+                let input = vars.local_wires[self.wire_ith_input(i, k)];
+                constraints.push(builder.sub_extension(input, combined_bits));
+            }
+
+            let mut combined_output = builder.zero_extension();
+            for j in 0..32 {
+                let x = vars.local_wires[self.wire_ith_input_jth_bit(i, 0, j)];
+                let y = vars.local_wires[self.wire_ith_input_jth_bit(i, 1, j)];
+                let z = vars.local_wires[self.wire_ith_input_jth_bit(i, 2, j)];
+                let output_bit = if self.majority {
+                    let xy = builder.mul_extension(x, y);
+                    let x_plus_y = builder.add_extension(x, y);
+                    // `x + y - 2xy`
+                    let t = builder.arithmetic_extension(-F::TWO, F::ONE, x, y, x_plus_y);
+                    builder.mul_add_extension(z, t, xy)
+                } else {
+                    let y_minus_z = builder.sub_extension(y, z);
+                    builder.mul_add_extension(x, y_minus_z, z)
+                };
+                combined_output = builder.mul_const_add_extension(
+                    F::from_canonical_u64(1 << j),
+                    output_bit,
+                    combined_output,
+                );
+            }
+            let output = vars.local_wires[self.wire_ith_output(i)];
+            constraints.push(builder.sub_extension(output, combined_output));
+        }
+
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        (0..self.num_ops)
+            .map(|i| {
+                WitnessGeneratorRef::new(
+                    Sha256ChMajGenerator { gate: *self, row, i, _phantom: PhantomData }.adapter(),
+                )
+            })
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        100 * self.num_ops
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        if self.majority {
+            3
+        } else {
+            2
+        }
+    }
+
+    fn num_constraints(&self) -> usize {
+        100 * self.num_ops
+    }
+}
+
+/// Computes the output of one operation of a `Sha256ChMajGate`.
+pub(crate) fn sha256_ch_maj_native(majority: bool, x: u32, y: u32, z: u32) -> u32 {
+    if majority {
+        (x & y) ^ (x & z) ^ (y & z)
+    } else {
+        (x & y) ^ (!x & z)
+    }
+}
+
+#[derive(Clone, Debug, Default, GeneratorSerialize)]
+pub(crate) struct Sha256ChMajGenerator<F: RichField + Extendable<D>, const D: usize> {
+    gate: Sha256ChMajGate<F, D>,
+    row: usize,
+    i: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for Sha256ChMajGenerator<F, D>
+{
+    fn id(&self) -> String {
+        "Sha256ChMajGenerator".to_string()
+    }
+
+    plonky2::derived_serialization!();
+
+    fn dependencies(&self) -> Vec<Target> {
+        (0..3).map(|k| Target::wire(self.row, self.gate.wire_ith_input(self.i, k))).collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let local_wire = |column| Wire { row: self.row, column };
+
+        let mut inputs = [0u32; 3];
+        for (k, input) in inputs.iter_mut().enumerate() {
+            let input_u64 = witness
+                .get_wire(local_wire(self.gate.wire_ith_input(self.i, k)))
+                .to_canonical_u64();
+            debug_assert!(input_u64 < 1 << 32, "Input is not a u32");
+            *input = input_u64 as u32;
+
+            for j in 0..32 {
+                let bit = F::from_bool((*input >> j) & 1 == 1);
+                out_buffer
+                    .set_wire(local_wire(self.gate.wire_ith_input_jth_bit(self.i, k, j)), bit);
+            }
+        }
+
+        let [x, y, z] = inputs;
+        let output = sha256_ch_maj_native(self.gate.majority, x, y, z);
+        out_buffer
+            .set_wire(local_wire(self.gate.wire_ith_output(self.i)), F::from_canonical_u32(output));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::{
+        field::{goldilocks_field::GoldilocksField, types::Sample},
+        gates::gate_testing::{test_eval_fns, test_low_degree},
+        hash::hash_types::HashOut,
+        plonk::config::{GenericConfig, PoseidonGoldilocksConfig},
+    };
+    use rand::{rngs::OsRng, Rng};
+
+    use super::*;
+
+    #[test]
+    fn low_degree() {
+        for majority in [false, true] {
+            test_low_degree::<GoldilocksField, _, 4>(Sha256ChMajGate::<GoldilocksField, 4> {
+                majority,
+                num_ops: 2,
+                _phantom: PhantomData,
+            })
+        }
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        for majority in [false, true] {
+            test_eval_fns::<F, C, _, D>(Sha256ChMajGate::<F, D> {
+                majority,
+                num_ops: 2,
+                _phantom: PhantomData,
+            })?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_gate_constraint() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type FF = <C as GenericConfig<D>>::FE;
+        const NUM_OPS: usize = 2;
+
+        fn get_wires(inputs: &[[u32; 3]], outputs: &[u32]) -> Vec<FF> {
+            let mut v0 = Vec::new();
+            let mut v1 = Vec::new();
+            for (input, &output) in inputs.iter().zip(outputs) {
+                v0.extend(input.iter().map(|&x| F::from_canonical_u32(x)));
+                v0.push(F::from_canonical_u32(output));
+                for x in input {
+                    v1.extend((0..32).map(|j| F::from_bool((x >> j) & 1 == 1)));
+                }
+            }
+            v0.iter().chain(v1.iter()).map(|&x| x.into()).collect()
+        }
+
+        let mut rng = OsRng;
+        for majority in [false, true] {
+            let gate =
+                Sha256ChMajGate::<F, D> { majority, num_ops: NUM_OPS, _phantom: PhantomData };
+            let inputs: Vec<[u32; 3]> = (0..NUM_OPS).map(|_| rng.gen()).collect();
+            let outputs: Vec<u32> =
+                inputs.iter().map(|&[x, y, z]| sha256_ch_maj_native(majority, x, y, z)).collect();
+
+            let wires = get_wires(&inputs, &outputs);
+            let vars = EvaluationVars {
+                local_constants: &[],
+                local_wires: &wires,
+                public_inputs_hash: &HashOut::rand(),
+            };
+            assert!(
+                gate.eval_unfiltered(vars).iter().all(|x| x.is_zero()),
+                "Gate constraints are not satisfied."
+            );
+
+            let mut bad_outputs = outputs.clone();
+            bad_outputs[0] ^= 1 << 20;
+            let wires = get_wires(&inputs, &bad_outputs);
+            let vars = EvaluationVars {
+                local_constants: &[],
+                local_wires: &wires,
+                public_inputs_hash: &HashOut::rand(),
+            };
+            assert!(
+                !gate.eval_unfiltered(vars).iter().all(|x| x.is_zero()),
+                "Gate constraints are satisfied but should not be."
+            );
+        }
+    }
+}
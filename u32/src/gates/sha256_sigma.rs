@@ -0,0 +1,468 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::marker::PhantomData;
+use plonky2::util::serialization::{GateSerialize, GeneratorSerialize};
+
+use plonky2::{
+    field::{extension::Extendable, types::Field},
+    gates::{gate::Gate, util::StridedConstraintConsumer},
+    hash::hash_types::RichField,
+    iop::{
+        ext_target::ExtensionTarget,
+        generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef},
+        target::Target,
+        wire::Wire,
+        witness::{PartitionWitness, Witness, WitnessWrite},
+    },
+    plonk::{
+        circuit_builder::CircuitBuilder,
+        circuit_data::CircuitConfig,
+        vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase},
+    },
+};
+
+/// A gate computing one of the SHA-256 functions `Σ0`, `Σ1`, `σ0` and `σ1`, which xor together
+/// three right rotations of a 32-bit word, the last of which is a right shift for `σ0` and `σ1`.
+/// The input is decomposed into bits, which also range-checks it.
+#[derive(Copy, Clone, Debug, Default, GateSerialize)]
+pub struct Sha256SigmaGate<F: RichField + Extendable<D>, const D: usize> {
+    pub rotations: [usize; 3],
+    pub shift_last: bool,
+    pub num_ops: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Sha256SigmaGate<F, D> {
+    pub fn new_from_config(
+        config: &CircuitConfig,
+        rotations: [usize; 3],
+        shift_last: bool,
+    ) -> Self {
+        debug_assert!(rotations.iter().all(|&r| r < 32));
+        Self { rotations, shift_last, num_ops: Self::num_ops(config), _phantom: PhantomData }
+    }
+
+    pub(crate) fn num_ops(config: &CircuitConfig) -> usize {
+        let wires_per_op = 2 + 32;
+        (config.num_wires / wires_per_op).min(config.num_routed_wires / 2)
+    }
+
+    pub fn wire_ith_input(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        2 * i
+    }
+
+    pub fn wire_ith_output(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        2 * i + 1
+    }
+
+    /// The `j`-th bit of the `i`-th input, starting from the least significant one.
+    pub fn wire_ith_input_jth_bit(&self, i: usize, j: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        debug_assert!(j < 32);
+        2 * self.num_ops + 32 * i + j
+    }
+
+    /// The bits of the input which are xored together into the `j`-th bit of the output.
+    fn sources(&self, j: usize) -> Vec<usize> {
+        self.rotations
+            .iter()
+            .enumerate()
+            .filter(|&(k, &r)| !(self.shift_last && k == 2 && j + r >= 32))
+            .map(|(_, &r)| (j + r) % 32)
+            .collect()
+    }
+
+    /// A short name for the function computed by this gate, used in the exported code.
+    fn name(&self) -> String {
+        let [r0, r1, r2] = self.rotations;
+        format!("{r0}x{r1}x{r2}{}", if self.shift_last { "Shr" } else { "Rot" })
+    }
+}
+
+/// Xors two bits, given as field elements.
+fn xor<T: Field>(a: T, b: T) -> T {
+    a + b - (a * b).double()
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for Sha256SigmaGate<F, D> {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        "template Sha256Sigma$NAMEOps$NUM_OPS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  var rotations[3] = [$ROT0, $ROT1, $ROT2];
+  signal combined_bits[$NUM_OPS][33][2];
+  signal combined_output[$NUM_OPS][33][2];
+  signal xor_01[$NUM_OPS][32][2];
+  signal xor_012[$NUM_OPS][32][2];
+  var index = 0;
+  for (var i = 0; i < $NUM_OPS; i++) {
+    var start = $BITS_START + 32 * i;
+    combined_bits[i][0] <== GlExt(0, 0)();
+    combined_output[i][0] <== GlExt(0, 0)();
+    for (var j = 0; j < 32; j++) {
+      out[index] <== ConstraintPush()(constraints[index], filter, GlExtMul()(wires[start + j], GlExtSub()(wires[start + j], GlExt(1, 0)())));
+      index++;
+      combined_bits[i][j + 1] <== GlExtScalarMulAdd()(wires[start + j], 1 << j, combined_bits[i][j]);
+
+      var b0 = start + (j + rotations[0]) % 32;
+      var b1 = start + (j + rotations[1]) % 32;
+      var b2 = start + (j + rotations[2]) % 32;
+      xor_01[i][j] <== GlExtSub()(GlExtAdd()(wires[b0], wires[b1]), GlExtScalarMul()(GlExtMul()(wires[b0], wires[b1]), 2));
+      if ($SHIFT_LAST == 1 && j + rotations[2] >= 32) {
+        xor_012[i][j] <== xor_01[i][j];
+      } else {
+        xor_012[i][j] <== GlExtSub()(GlExtAdd()(xor_01[i][j], wires[b2]), GlExtScalarMul()(GlExtMul()(xor_01[i][j], wires[b2]), 2));
+      }
+      combined_output[i][j + 1] <== GlExtScalarMulAdd()(xor_012[i][j], 1 << j, combined_output[i][j]);
+    }
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(wires[2 * i], combined_bits[i][32]));
+    index++;
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(wires[2 * i + 1], combined_output[i][32]));
+    index++;
+  }
+
+  for (var i = index; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .replace("$NAME", &self.name())
+        .replace("$NUM_OPS", &self.num_ops.to_string())
+        .replace("$BITS_START", &(2 * self.num_ops).to_string())
+        .replace("$ROT0", &self.rotations[0].to_string())
+        .replace("$ROT1", &self.rotations[1].to_string())
+        .replace("$ROT2", &self.rotations[2].to_string())
+        .replace("$SHIFT_LAST", &(self.shift_last as usize).to_string())
+    }
+
+    fn export_solidity_verification_code(&self) -> String {
+        "library Sha256Sigma$NAMEOps$NUM_OPSLib {
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+
+    function xor(uint64[2] memory a, uint64[2] memory b) internal pure returns (uint64[2] memory) {
+        return a.add(b).sub(a.mul(b).scalar_mul(2));
+    }
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint32[3] memory rotations = [uint32($ROT0), $ROT1, $ROT2];
+        uint32 index = 0;
+        for (uint32 i = 0; i < $NUM_OPS; i++) {
+            uint32 start = $BITS_START + 32 * i;
+            uint64[2] memory combined_bits;
+            uint64[2] memory combined_output;
+            for (uint32 j = 0; j < 32; j++) {
+                uint64[2] memory bit = ev.wires[start + j];
+                GatesUtilsLib.push(constraints, ev.filter, index++, bit.mul(bit.sub(GoldilocksExtLib.one())));
+                combined_bits = combined_bits.add(bit.scalar_mul(uint64(1) << j));
+
+                uint64[2] memory output_bit = xor(ev.wires[start + (j + rotations[0]) % 32], ev.wires[start + (j + rotations[1]) % 32]);
+                if ($SHIFT_LAST == 0 || j + rotations[2] < 32) {
+                    output_bit = xor(output_bit, ev.wires[start + (j + rotations[2]) % 32]);
+                }
+                combined_output = combined_output.add(output_bit.scalar_mul(uint64(1) << j));
+            }
+            GatesUtilsLib.push(constraints, ev.filter, index++, ev.wires[2 * i].sub(combined_bits));
+            GatesUtilsLib.push(constraints, ev.filter, index++, ev.wires[2 * i + 1].sub(combined_output));
+        }
+    }
+}"
+        .replace("$NAME", &self.name())
+        .replace("$NUM_OPS", &self.num_ops.to_string())
+        .replace("$BITS_START", &(2 * self.num_ops).to_string())
+        .replace("$ROT0", &self.rotations[0].to_string())
+        .replace("$ROT1", &self.rotations[1].to_string())
+        .replace("$ROT2", &self.rotations[2].to_string())
+        .replace("$SHIFT_LAST", &(self.shift_last as usize).to_string())
+    }
+
+    plonky2::derived_serialization!();
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_ops {
+            let bits: Vec<_> =
+                (0..32).map(|j| vars.local_wires[self.wire_ith_input_jth_bit(i, j)]).collect();
+
+            let mut combined_bits = F::Extension::ZERO;
+            let mut combined_output = F::Extension::ZERO;
+            for j in 0..32 {
+                let bit = bits[j];
+                constraints.push(bit * (bit - F::Extension::ONE));
+
+                let power = F::Extension::from_canonical_u64(1 << j);
+                combined_bits += bit * power;
+                let output_bit = self.sources(j).into_iter().map(|s| bits[s]).reduce(xor).unwrap();
+                combined_output += output_bit * power;
+            }
+
+            let input = vars.local_wires[self.wire_ith_input(i)];
+            let output = vars.local_wires[self.wire_ith_output(i)];
+            constraints.push(input - combined_bits);
+            constraints.push(output - combined_output);
+        }
+
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        vars: EvaluationVarsBase<F>,
+        mut yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        for i in 0..self.num_ops {
+            let bits: Vec<_> =
+                (0..32).map(|j| vars.local_wires[self.wire_ith_input_jth_bit(i, j)]).collect();
+
+            let mut combined_bits = F::ZERO;
+            let mut combined_output = F::ZERO;
+            for j in 0..32 {
+                let bit = bits[j];
+                yield_constr.one(bit * (bit - F::ONE));
+
+                let power = F::from_canonical_u64(1 << j);
+                combined_bits += bit * power;
+                let output_bit = self.sources(j).into_iter().map(|s| bits[s]).reduce(xor).unwrap();
+                combined_output += output_bit * power;
+            }
+
+            let input = vars.local_wires[self.wire_ith_input(i)];
+            let output = vars.local_wires[self.wire_ith_output(i)];
+            yield_constr.one(input - combined_bits);
+            yield_constr.one(output - combined_output);
+        }
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_ops {
+            let bits: Vec<_> =
+                (0..32).map(|j| vars.local_wires[self.wire_ith_input_jth_bit(i, j)]).collect();
+
+            let mut combined_bits = builder.zero_extension();
+            let mut combined_output = builder.zero_extension();
+            for j in 0..32 {
+                let bit = bits[j];
+                constraints.push(builder.mul_sub_extension(bit, bit, bit));
+
+                let power = F::from_canonical_u64(1 << j);
+                combined_bits = builder.mul_const_add_extension(power, bit, combined_bits);
+                let output_bit = self
+                    .sources(j)
+                    .into_iter()
+                    .map(|s| bits[s])
+                    .reduce(|acc, b| {
+                        // `acc + b - 2 acc b`
+                        let sum = builder.add_extension(acc, b);
+                        builder.arithmetic_extension(-F::TWO, F::ONE, acc, b, sum)
+                    })
+                    .unwrap();
+                combined_output =
+                    builder.mul_const_add_extension(power, output_bit, combined_output);
+            }
+
+            let input = vars.local_wires[self.wire_ith_input(i)];
+            let output = vars.local_wires[self.wire_ith_output(i)];
+            constraints.push(builder.sub_extension(input, combined_bits));
+            constraints.push(builder.sub_extension(output, combined_output));
+        }
+
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        (0..self.num_ops)
+            .map(|i| {
+                WitnessGeneratorRef::new(
+                    Sha256SigmaGenerator { gate: *self, row, i, _phantom: PhantomData }.adapter(),
+                )
+            })
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        34 * self.num_ops
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        3
+    }
+
+    fn num_constraints(&self) -> usize {
+        34 * self.num_ops
+    }
+}
+
+/// Computes the output of one operation of a `Sha256SigmaGate`.
+pub(crate) fn sha256_sigma_native(rotations: [usize; 3], shift_last: bool, x: u32) -> u32 {
+    let [r0, r1, r2] = rotations;
+    let last = if shift_last { x >> r2 } else { x.rotate_right(r2 as u32) };
+    x.rotate_right(r0 as u32) ^ x.rotate_right(r1 as u32) ^ last
+}
+
+#[derive(Clone, Debug, Default, GeneratorSerialize)]
+pub(crate) struct Sha256SigmaGenerator<F: RichField + Extendable<D>, const D: usize> {
+    gate: Sha256SigmaGate<F, D>,
+    row: usize,
+    i: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for Sha256SigmaGenerator<F, D>
+{
+    fn id(&self) -> String {
+        "Sha256SigmaGenerator".to_string()
+    }
+
+    plonky2::derived_serialization!();
+
+    fn dependencies(&self) -> Vec<Target> {
+        [Target::wire(self.row, self.gate.wire_ith_input(self.i))].to_vec()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let local_wire = |column| Wire { row: self.row, column };
+
+        let input = witness.get_wire(local_wire(self.gate.wire_ith_input(self.i)));
+        let input_u64 = input.to_canonical_u64();
+        debug_assert!(input_u64 < 1 << 32, "Input is not a u32");
+        let input_u32 = input_u64 as u32;
+
+        for j in 0..32 {
+            let bit = F::from_bool((input_u32 >> j) & 1 == 1);
+            out_buffer.set_wire(local_wire(self.gate.wire_ith_input_jth_bit(self.i, j)), bit);
+        }
+        let output = sha256_sigma_native(self.gate.rotations, self.gate.shift_last, input_u32);
+        out_buffer
+            .set_wire(local_wire(self.gate.wire_ith_output(self.i)), F::from_canonical_u32(output));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::{
+        field::{goldilocks_field::GoldilocksField, types::Sample},
+        gates::gate_testing::{test_eval_fns, test_low_degree},
+        hash::hash_types::HashOut,
+        plonk::config::{GenericConfig, PoseidonGoldilocksConfig},
+    };
+    use rand::{rngs::OsRng, Rng};
+
+    use super::*;
+
+    const SMALL_SIGMA0: ([usize; 3], bool) = ([7, 18, 3], true);
+    const BIG_SIGMA1: ([usize; 3], bool) = ([6, 11, 25], false);
+
+    #[test]
+    fn low_degree() {
+        for (rotations, shift_last) in [SMALL_SIGMA0, BIG_SIGMA1] {
+            test_low_degree::<GoldilocksField, _, 4>(Sha256SigmaGate::<GoldilocksField, 4> {
+                rotations,
+                shift_last,
+                num_ops: 3,
+                _phantom: PhantomData,
+            })
+        }
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        for (rotations, shift_last) in [SMALL_SIGMA0, BIG_SIGMA1] {
+            test_eval_fns::<F, C, _, D>(Sha256SigmaGate::<F, D> {
+                rotations,
+                shift_last,
+                num_ops: 3,
+                _phantom: PhantomData,
+            })?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_gate_constraint() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type FF = <C as GenericConfig<D>>::FE;
+        const NUM_OPS: usize = 3;
+
+        fn get_wires(inputs: &[u32], outputs: &[u32]) -> Vec<FF> {
+            let mut v0 = Vec::new();
+            let mut v1 = Vec::new();
+            for (&input, &output) in inputs.iter().zip(outputs) {
+                v0.push(F::from_canonical_u32(input));
+                v0.push(F::from_canonical_u32(output));
+                v1.extend((0..32).map(|j| F::from_bool((input >> j) & 1 == 1)));
+            }
+            v0.iter().chain(v1.iter()).map(|&x| x.into()).collect()
+        }
+
+        let mut rng = OsRng;
+        for (rotations, shift_last) in [SMALL_SIGMA0, BIG_SIGMA1] {
+            let gate = Sha256SigmaGate::<F, D> {
+                rotations,
+                shift_last,
+                num_ops: NUM_OPS,
+                _phantom: PhantomData,
+            };
+            let inputs: Vec<u32> = (0..NUM_OPS).map(|_| rng.gen()).collect();
+            let outputs: Vec<u32> =
+                inputs.iter().map(|&x| sha256_sigma_native(rotations, shift_last, x)).collect();
+
+            let wires = get_wires(&inputs, &outputs);
+            let vars = EvaluationVars {
+                local_constants: &[],
+                local_wires: &wires,
+                public_inputs_hash: &HashOut::rand(),
+            };
+            assert!(
+                gate.eval_unfiltered(vars).iter().all(|x| x.is_zero()),
+                "Gate constraints are not satisfied."
+            );
+
+            let mut bad_outputs = outputs.clone();
+            bad_outputs[1] ^= 1 << 7;
+            let wires = get_wires(&inputs, &bad_outputs);
+            let vars = EvaluationVars {
+                local_constants: &[],
+                local_wires: &wires,
+                public_inputs_hash: &HashOut::rand(),
+            };
+            assert!(
+                !gate.eval_unfiltered(vars).iter().all(|x| x.is_zero()),
+                "Gate constraints are satisfied but should not be."
+            );
+        }
+    }
+}
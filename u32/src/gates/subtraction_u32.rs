@@ -5,7 +5,7 @@ use alloc::{
     vec::Vec,
 };
 use core::marker::PhantomData;
-use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use plonky2::util::serialization::{GateSerialize, GeneratorSerialize};
 
 use plonky2::{
     field::{extension::Extendable, packed::PackedField, types::Field},
@@ -20,7 +20,7 @@ use plonky2::{
     },
     plonk::{
         circuit_builder::CircuitBuilder,
-        circuit_data::{CircuitConfig, CommonCircuitData},
+        circuit_data::CircuitConfig,
         vars::{
             EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
             EvaluationVarsBasePacked,
@@ -30,7 +30,7 @@ use plonky2::{
 
 /// A gate to perform a subtraction on 32-bit limbs: given `x`, `y`, and `borrow`, it returns
 /// the result `x - y - borrow` and, if this underflows, a new `borrow`. Inputs are not range-checked.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default, GateSerialize)]
 pub struct U32SubtractionGate<F: RichField + Extendable<D>, const D: usize> {
     pub num_ops: usize,
     _phantom: PhantomData<F>,
@@ -90,19 +90,97 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for U32Subtraction
     }
 
     fn export_circom_verification_code(&self) -> String {
-        todo!();
+        let mut template_str = "template U32SubtractionOps$NUM_OPS() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  var index = 0;
+  signal result_initial[$NUM_OPS][2];
+  signal combined_limbs[$NUM_OPS][$NUM_LIMBS + 1][2];
+  for (var i = 0; i < $NUM_OPS; i++) {
+    var output_result[2] = wires[5 * i + 3];
+    var output_borrow[2] = wires[5 * i + 4];
+    result_initial[i] <== GlExtSub()(GlExtSub()(wires[5 * i], wires[5 * i + 1]), wires[5 * i + 2]);
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(output_result, GlExtScalarMulAdd()(output_borrow, 4294967296, result_initial[i])));
+    index++;
+
+    // Range-check output_result to be at most 32 bits.
+    combined_limbs[i][$NUM_LIMBS] <== GlExt(0, 0)();
+    for (var j = $NUM_LIMBS; j > 0; j--) {
+      var this_limb[2] = wires[$LIMBS_START + $NUM_LIMBS * i + j - 1];
+      out[index] <== ConstraintPush()(constraints[index], filter, RangeProduct($MAX_LIMB)(this_limb));
+      index++;
+      combined_limbs[i][j - 1] <== GlExtScalarMulAdd()(combined_limbs[i][j], $MAX_LIMB, this_limb);
+    }
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtSub()(combined_limbs[i][0], output_result));
+    index++;
+
+    // Range-check output_borrow to be one bit.
+    out[index] <== ConstraintPush()(constraints[index], filter, GlExtMul()(output_borrow, GlExtSub()(GlExt(1, 0)(), output_borrow)));
+    index++;
+  }
+
+  for (var i = index; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .to_string();
+
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str = template_str.replace("$LIMBS_START", &(5 * self.num_ops).to_string());
+        template_str = template_str.replace("$NUM_LIMBS", &Self::num_limbs().to_string());
+        template_str = template_str.replace("$MAX_LIMB", &(1 << Self::limb_bits()).to_string());
+
+        template_str
     }
     fn export_solidity_verification_code(&self) -> String {
-        todo!();
+        let mut template_str = "library U32SubtractionOps$NUM_OPSLib {
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
     }
-    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
-        dst.write_usize(self.num_ops)
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint32 index = 0;
+        for (uint32 i = 0; i < $NUM_OPS; i++) {
+            uint64[2] memory output_result = ev.wires[5 * i + 3];
+            uint64[2] memory output_borrow = ev.wires[5 * i + 4];
+            {
+                uint64[2] memory result_initial = ev.wires[5 * i].sub(ev.wires[5 * i + 1]).sub(ev.wires[5 * i + 2]);
+                GatesUtilsLib.push(constraints, ev.filter, index++, output_result.sub(result_initial.add(output_borrow.scalar_mul(1 << 32))));
+            }
+
+            // Range-check output_result to be at most 32 bits.
+            uint64[2] memory combined_limbs;
+            for (uint32 j = $NUM_LIMBS; j > 0; j--) {
+                uint64[2] memory this_limb = ev.wires[$LIMBS_START + $NUM_LIMBS * i + j - 1];
+                GatesUtilsLib.push(constraints, ev.filter, index++, GatesUtilsLib.range_product(this_limb, $MAX_LIMB));
+                combined_limbs = combined_limbs.scalar_mul($MAX_LIMB).add(this_limb);
+            }
+            GatesUtilsLib.push(constraints, ev.filter, index++, combined_limbs.sub(output_result));
+
+            // Range-check output_borrow to be one bit.
+            GatesUtilsLib.push(constraints, ev.filter, index++, output_borrow.mul(GoldilocksExtLib.one().sub(output_borrow)));
+        }
     }
+}"
+        .to_string();
+
+        template_str = template_str.replace("$NUM_OPS", &self.num_ops.to_string());
+        template_str = template_str.replace("$LIMBS_START", &(5 * self.num_ops).to_string());
+        template_str = template_str.replace("$NUM_LIMBS", &Self::num_limbs().to_string());
+        template_str = template_str.replace("$MAX_LIMB", &(1 << Self::limb_bits()).to_string());
 
-    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let num_ops = src.read_usize()?;
-        Ok(Self { num_ops, _phantom: PhantomData })
+        template_str
     }
+    plonky2::derived_serialization!();
 
     fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
         let mut constraints = Vec::with_capacity(self.num_constraints());
@@ -272,8 +350,8 @@ impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D>
     }
 }
 
-#[derive(Clone, Debug)]
-struct U32SubtractionGenerator<F: RichField + Extendable<D>, const D: usize> {
+#[derive(Clone, Debug, Default, GeneratorSerialize)]
+pub(crate) struct U32SubtractionGenerator<F: RichField + Extendable<D>, const D: usize> {
     gate: U32SubtractionGate<F, D>,
     row: usize,
     i: usize,
@@ -337,18 +415,7 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
         }
     }
 
-    fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
-        self.gate.serialize(dst, common_data)?;
-        dst.write_usize(self.row)?;
-        dst.write_usize(self.i)
-    }
-
-    fn deserialize(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
-        let gate = U32SubtractionGate::deserialize(src, common_data)?;
-        let row = src.read_usize()?;
-        let i = src.read_usize()?;
-        Ok(Self { gate, row, i, _phantom: PhantomData })
-    }
+    plonky2::derived_serialization!();
 }
 
 #[cfg(test)]
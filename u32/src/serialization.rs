@@ -1,7 +1,34 @@
 use alloc::vec::Vec;
-use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use plonky2::{
+    field::extension::Extendable,
+    get_gate_tag_impl, get_generator_tag_impl,
+    hash::hash_types::RichField,
+    impl_gate_serializer, impl_generator_serializer,
+    plonk::circuit_data::CommonCircuitData,
+    read_gate_impl, read_generator_impl,
+    util::serialization::{
+        Buffer, CircuitSerialize, GateSerializer, IoError, IoResult, Read,
+        WitnessGeneratorSerializer, Write,
+    },
+};
 
-use crate::gadgets::arithmetic_u32::U32Target;
+use crate::{
+    gadgets::{
+        arithmetic_u32::{SplitToU32Generator, U32DivRemGenerator, U32Target},
+        arithmetic_u64::{U64DivRemGenerator, U64Target},
+        keccak256::LeBytesGenerator,
+    },
+    gates::{
+        add_many_u32::{U32AddManyGate, U32AddManyGenerator},
+        arithmetic_u32::{U32ArithmeticGate, U32ArithmeticGenerator},
+        bitwise_u32::{BitwiseOp, U32BitwiseGate, U32BitwiseGenerator},
+        comparison::{ComparisonGate, ComparisonGenerator},
+        range_check_u32::{U32RangeCheckGate, U32RangeCheckGenerator},
+        sha256_ch_maj::{Sha256ChMajGate, Sha256ChMajGenerator},
+        sha256_sigma::{Sha256SigmaGate, Sha256SigmaGenerator},
+        subtraction_u32::{U32SubtractionGate, U32SubtractionGenerator},
+    },
+};
 
 pub trait WriteU32 {
     fn write_target_u32(&mut self, x: U32Target) -> IoResult<()>;
@@ -24,3 +51,146 @@ impl ReadU32 for Buffer<'_> {
         Ok(U32Target(self.read_target()?))
     }
 }
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitSerialize<F, D> for U32Target {
+    fn write_to(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_u32(*self)
+    }
+
+    fn read_from(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        src.read_target_u32()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitSerialize<F, D> for U64Target {
+    fn write_to(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        self.limbs.write_to(dst, common_data)
+    }
+
+    fn read_from(src: &mut Buffer, common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        Ok(Self { limbs: <[U32Target; 2]>::read_from(src, common_data)? })
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitSerialize<F, D> for BitwiseOp {
+    fn write_to(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_u8(match self {
+            BitwiseOp::And => 0,
+            BitwiseOp::Or => 1,
+            BitwiseOp::Xor => 2,
+        })
+    }
+
+    fn read_from(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        match src.read_u8()? {
+            0 => Ok(BitwiseOp::And),
+            1 => Ok(BitwiseOp::Or),
+            2 => Ok(BitwiseOp::Xor),
+            _ => Err(IoError),
+        }
+    }
+}
+
+/// A gate serializer for the gates defined in this crate. Circuits also using `plonky2`'s gates
+/// can combine it with `DefaultGateSerializer` in a `GateSerializerRegistry`.
+#[derive(Debug, Default)]
+pub struct U32GateSerializer;
+
+impl<F: RichField + Extendable<D>, const D: usize> GateSerializer<F, D> for U32GateSerializer {
+    impl_gate_serializer! {
+        U32GateSerializer,
+        U32AddManyGate<F, D>,
+        U32ArithmeticGate<F, D>,
+        ComparisonGate<F, D>,
+        U32RangeCheckGate<F, D>,
+        U32SubtractionGate<F, D>,
+        Sha256ChMajGate<F, D>,
+        Sha256SigmaGate<F, D>,
+        U32BitwiseGate<F, D>
+    }
+}
+
+/// A generator serializer for the generators defined in this crate. Circuits also using
+/// `plonky2`'s generators can combine it with `DefaultGeneratorSerializer` in a
+/// `GeneratorSerializerRegistry`.
+#[derive(Debug, Default)]
+pub struct U32GeneratorSerializer;
+
+impl<F: RichField + Extendable<D>, const D: usize> WitnessGeneratorSerializer<F, D>
+    for U32GeneratorSerializer
+{
+    impl_generator_serializer! {
+        U32GeneratorSerializer,
+        SplitToU32Generator<F, D>,
+        U32AddManyGenerator<F, D>,
+        U32ArithmeticGenerator<F, D>,
+        ComparisonGenerator<F, D>,
+        U32RangeCheckGenerator<F, D>,
+        U32SubtractionGenerator<F, D>,
+        Sha256ChMajGenerator<F, D>,
+        Sha256SigmaGenerator<F, D>,
+        LeBytesGenerator<F, D>,
+        U32BitwiseGenerator<F, D>,
+        U64DivRemGenerator<F, D>,
+        U32DivRemGenerator<F, D>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::{
+        iop::witness::PartialWitness,
+        plonk::{
+            circuit_builder::CircuitBuilder,
+            circuit_data::{CircuitConfig, CircuitData},
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+        util::serialization::{
+            DefaultGateSerializer, DefaultGeneratorSerializer, GateSerializerRegistry,
+            GeneratorSerializerRegistry,
+        },
+    };
+
+    use super::*;
+    use crate::{gadgets::arithmetic_u32::CircuitBuilderU32, witness::WitnessU32};
+
+    #[test]
+    fn test_u32_serializers() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let x = builder.add_virtual_u32_target();
+        let y = builder.add_virtual_u32_target();
+        let (low, high) = builder.mul_u32(x, y);
+        let zero = builder.zero_u32();
+        let (diff, _) = builder.sub_u32(x, y, zero);
+        let (sum, _) = builder.add_many_u32(&[low, high, diff]);
+        builder.register_public_input(sum.0);
+        let data = builder.build::<C>();
+
+        let gate_serializer =
+            GateSerializerRegistry::new().with(DefaultGateSerializer).with(U32GateSerializer);
+        let generator_serializer = GeneratorSerializerRegistry::new()
+            .with(DefaultGeneratorSerializer::<C, D>::default())
+            .with(U32GeneratorSerializer);
+        assert!(data
+            .unsupported_by_serializers(&gate_serializer, &generator_serializer)
+            .is_empty());
+
+        let bytes =
+            data.to_bytes(&gate_serializer, &generator_serializer).map_err(anyhow::Error::msg)?;
+        let data_from_bytes =
+            CircuitData::<F, C, D>::from_bytes(&bytes, &gate_serializer, &generator_serializer)
+                .map_err(anyhow::Error::msg)?;
+        assert_eq!(data_from_bytes, data);
+
+        let mut pw = PartialWitness::new();
+        pw.set_u32_target(x, 0x1234_5678);
+        pw.set_u32_target(y, 0x0fed_cba9);
+        let proof = data_from_bytes.prove(pw)?;
+        Ok(data.verify(proof)?)
+    }
+}
@@ -6,7 +6,7 @@ use plonky2::{
     },
 };
 
-use crate::gadgets::arithmetic_u32::U32Target;
+use crate::gadgets::{arithmetic_u32::U32Target, arithmetic_u64::U64Target};
 
 pub trait WitnessU32<F: PrimeField64>: Witness<F> {
     fn set_u32_target(&mut self, target: U32Target, value: u32);
@@ -26,6 +26,24 @@ impl<T: Witness<F>, F: PrimeField64> WitnessU32<F> for T {
     }
 }
 
+pub trait WitnessU64<F: PrimeField64>: Witness<F> {
+    fn set_u64_target(&mut self, target: U64Target, value: u64);
+    fn get_u64_target(&self, target: U64Target) -> u64;
+}
+
+impl<T: Witness<F>, F: PrimeField64> WitnessU64<F> for T {
+    fn set_u64_target(&mut self, target: U64Target, value: u64) {
+        self.set_u32_target(target.limbs[0], value as u32);
+        self.set_u32_target(target.limbs[1], (value >> 32) as u32);
+    }
+
+    fn get_u64_target(&self, target: U64Target) -> u64 {
+        let (low, _) = self.get_u32_target(target.limbs[0]);
+        let (high, _) = self.get_u32_target(target.limbs[1]);
+        ((high as u64) << 32) | low as u64
+    }
+}
+
 pub trait GeneratedValuesU32<F: Field> {
     fn set_u32_target(&mut self, target: U32Target, value: u32);
 }
@@ -35,3 +53,14 @@ impl<F: Field> GeneratedValuesU32<F> for GeneratedValues<F> {
         self.set_target(target.0, F::from_canonical_u32(value))
     }
 }
+
+pub trait GeneratedValuesU64<F: Field> {
+    fn set_u64_target(&mut self, target: U64Target, value: u64);
+}
+
+impl<F: Field> GeneratedValuesU64<F> for GeneratedValues<F> {
+    fn set_u64_target(&mut self, target: U64Target, value: u64) {
+        self.set_u32_target(target.limbs[0], value as u32);
+        self.set_u32_target(target.limbs[1], (value >> 32) as u32);
+    }
+}
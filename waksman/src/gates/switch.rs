@@ -0,0 +1,438 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::marker::PhantomData;
+
+use plonky2::{
+    field::{extension::Extendable, types::Field},
+    gates::{gate::Gate, util::StridedConstraintConsumer},
+    hash::hash_types::RichField,
+    iop::{
+        ext_target::ExtensionTarget,
+        generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef},
+        target::Target,
+        wire::Wire,
+        witness::{PartitionWitness, Witness, WitnessWrite},
+    },
+    plonk::{
+        circuit_builder::CircuitBuilder,
+        circuit_data::CircuitConfig,
+        vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase},
+    },
+    util::serialization::{GateSerialize, GeneratorSerialize},
+};
+
+/// A gate for conditionally swapping two chunks of `chunk_size` values: the outputs are the
+/// inputs, in the same order if the switch is 0 and swapped if it is 1.
+///
+/// The switch isn't constrained to be boolean. If it is neither 0 nor 1, the constraints force
+/// all four chunks to be equal, so the outputs are still a permutation of the inputs.
+#[derive(Copy, Clone, Debug, Default, GateSerialize)]
+pub struct SwitchGate<F: RichField + Extendable<D>, const D: usize> {
+    pub(crate) chunk_size: usize,
+    pub(crate) num_copies: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SwitchGate<F, D> {
+    pub fn new_from_config(config: &CircuitConfig, chunk_size: usize) -> Self {
+        let num_copies = Self::max_num_copies(config.num_routed_wires, chunk_size);
+        assert!(num_copies > 0, "Chunks of {chunk_size} values don't fit in a switch gate");
+        Self { chunk_size, num_copies, _phantom: PhantomData }
+    }
+
+    /// The largest chunk size supported by a switch gate with the given number of routed wires.
+    pub fn max_chunk_size(num_routed_wires: usize) -> usize {
+        num_routed_wires.saturating_sub(1) / 4
+    }
+
+    pub fn max_num_copies(num_routed_wires: usize, chunk_size: usize) -> usize {
+        num_routed_wires / (4 * chunk_size + 1)
+    }
+
+    fn copy_start(&self, copy: usize) -> usize {
+        debug_assert!(copy < self.num_copies);
+        copy * (4 * self.chunk_size + 1)
+    }
+
+    pub fn wire_first_input(&self, copy: usize, element: usize) -> usize {
+        debug_assert!(element < self.chunk_size);
+        self.copy_start(copy) + element
+    }
+
+    pub fn wire_second_input(&self, copy: usize, element: usize) -> usize {
+        debug_assert!(element < self.chunk_size);
+        self.copy_start(copy) + self.chunk_size + element
+    }
+
+    pub fn wire_first_output(&self, copy: usize, element: usize) -> usize {
+        debug_assert!(element < self.chunk_size);
+        self.copy_start(copy) + 2 * self.chunk_size + element
+    }
+
+    pub fn wire_second_output(&self, copy: usize, element: usize) -> usize {
+        debug_assert!(element < self.chunk_size);
+        self.copy_start(copy) + 3 * self.chunk_size + element
+    }
+
+    pub fn wire_switch_bool(&self, copy: usize) -> usize {
+        self.copy_start(copy) + 4 * self.chunk_size
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for SwitchGate<F, D> {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        "template Switch$CHUNK_SIZECopies$NUM_COPIES() {
+  signal input constants[NUM_OPENINGS_CONSTANTS()][2];
+  signal input wires[NUM_OPENINGS_WIRES()][2];
+  signal input public_input_hash[4];
+  signal input constraints[NUM_GATE_CONSTRAINTS()][2];
+  signal output out[NUM_GATE_CONSTRAINTS()][2];
+
+  signal filter[2];
+  $SET_FILTER;
+
+  signal not_switch[$NUM_COPIES][2];
+  var index = 0;
+  for (var c = 0; c < $NUM_COPIES; c++) {
+    var start = c * (4 * $CHUNK_SIZE + 1);
+    var switch_bool = start + 4 * $CHUNK_SIZE;
+    not_switch[c] <== GlExtSub()(GlExt(1, 0)(), wires[switch_bool]);
+    for (var e = 0; e < $CHUNK_SIZE; e++) {
+      var first_input = start + e;
+      var second_input = start + $CHUNK_SIZE + e;
+      var first_output = start + 2 * $CHUNK_SIZE + e;
+      var second_output = start + 3 * $CHUNK_SIZE + e;
+      out[index] <== ConstraintPush()(constraints[index], filter, GlExtMul()(not_switch[c], GlExtSub()(wires[first_input], wires[first_output])));
+      index++;
+      out[index] <== ConstraintPush()(constraints[index], filter, GlExtMul()(not_switch[c], GlExtSub()(wires[second_input], wires[second_output])));
+      index++;
+      out[index] <== ConstraintPush()(constraints[index], filter, GlExtMul()(wires[switch_bool], GlExtSub()(wires[first_input], wires[second_output])));
+      index++;
+      out[index] <== ConstraintPush()(constraints[index], filter, GlExtMul()(wires[switch_bool], GlExtSub()(wires[second_input], wires[first_output])));
+      index++;
+    }
+  }
+
+  for (var i = index; i < NUM_GATE_CONSTRAINTS(); i++) {
+    out[i] <== constraints[i];
+  }
+}"
+        .replace("$CHUNK_SIZE", &self.chunk_size.to_string())
+        .replace("$NUM_COPIES", &self.num_copies.to_string())
+    }
+
+    fn export_solidity_verification_code(&self) -> String {
+        "library Switch$CHUNK_SIZECopies$NUM_COPIESLib {
+    using GoldilocksExtLib for uint64[2];
+
+    function set_filter(GatesUtilsLib.EvaluationVars memory ev) internal pure {
+        $SET_FILTER;
+    }
+
+    function eval(GatesUtilsLib.EvaluationVars memory ev, uint64[2][$NUM_GATE_CONSTRAINTS] memory constraints) internal pure {
+        uint32 index = 0;
+        for (uint32 c = 0; c < $NUM_COPIES; c++) {
+            uint32 start = c * (4 * $CHUNK_SIZE + 1);
+            uint64[2] memory switch_bool = ev.wires[start + 4 * $CHUNK_SIZE];
+            uint64[2] memory not_switch = GoldilocksExtLib.one().sub(switch_bool);
+            for (uint32 e = 0; e < $CHUNK_SIZE; e++) {
+                uint64[2] memory first_input = ev.wires[start + e];
+                uint64[2] memory second_input = ev.wires[start + $CHUNK_SIZE + e];
+                uint64[2] memory first_output = ev.wires[start + 2 * $CHUNK_SIZE + e];
+                uint64[2] memory second_output = ev.wires[start + 3 * $CHUNK_SIZE + e];
+                GatesUtilsLib.push(constraints, ev.filter, index++, not_switch.mul(first_input.sub(first_output)));
+                GatesUtilsLib.push(constraints, ev.filter, index++, not_switch.mul(second_input.sub(second_output)));
+                GatesUtilsLib.push(constraints, ev.filter, index++, switch_bool.mul(first_input.sub(second_output)));
+                GatesUtilsLib.push(constraints, ev.filter, index++, switch_bool.mul(second_input.sub(first_output)));
+            }
+        }
+    }
+}"
+        .replace("$CHUNK_SIZE", &self.chunk_size.to_string())
+        .replace("$NUM_COPIES", &self.num_copies.to_string())
+    }
+
+    plonky2::derived_serialization!();
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+
+        for c in 0..self.num_copies {
+            let switch_bool = vars.local_wires[self.wire_switch_bool(c)];
+            let not_switch = F::Extension::ONE - switch_bool;
+
+            for e in 0..self.chunk_size {
+                let first_input = vars.local_wires[self.wire_first_input(c, e)];
+                let second_input = vars.local_wires[self.wire_second_input(c, e)];
+                let first_output = vars.local_wires[self.wire_first_output(c, e)];
+                let second_output = vars.local_wires[self.wire_second_output(c, e)];
+
+                constraints.push(not_switch * (first_input - first_output));
+                constraints.push(not_switch * (second_input - second_output));
+                constraints.push(switch_bool * (first_input - second_output));
+                constraints.push(switch_bool * (second_input - first_output));
+            }
+        }
+
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        vars: EvaluationVarsBase<F>,
+        mut yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        for c in 0..self.num_copies {
+            let switch_bool = vars.local_wires[self.wire_switch_bool(c)];
+            let not_switch = F::ONE - switch_bool;
+
+            for e in 0..self.chunk_size {
+                let first_input = vars.local_wires[self.wire_first_input(c, e)];
+                let second_input = vars.local_wires[self.wire_second_input(c, e)];
+                let first_output = vars.local_wires[self.wire_first_output(c, e)];
+                let second_output = vars.local_wires[self.wire_second_output(c, e)];
+
+                yield_constr.one(not_switch * (first_input - first_output));
+                yield_constr.one(not_switch * (second_input - second_output));
+                yield_constr.one(switch_bool * (first_input - second_output));
+                yield_constr.one(switch_bool * (second_input - first_output));
+            }
+        }
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+
+        let one = builder.one_extension();
+        for c in 0..self.num_copies {
+            let switch_bool = vars.local_wires[self.wire_switch_bool(c)];
+            let not_switch = builder.sub_extension(one, switch_bool);
+
+            for e in 0..self.chunk_size {
+                let first_input = vars.local_wires[self.wire_first_input(c, e)];
+                let second_input = vars.local_wires[self.wire_second_input(c, e)];
+                let first_output = vars.local_wires[self.wire_first_output(c, e)];
+                let second_output = vars.local_wires[self.wire_second_output(c, e)];
+
+                let first_stays = builder.sub_extension(first_input, first_output);
+                constraints.push(builder.mul_extension(not_switch, first_stays));
+                let second_stays = builder.sub_extension(second_input, second_output);
+                constraints.push(builder.mul_extension(not_switch, second_stays));
+                let first_moves = builder.sub_extension(first_input, second_output);
+                constraints.push(builder.mul_extension(switch_bool, first_moves));
+                let second_moves = builder.sub_extension(second_input, first_output);
+                constraints.push(builder.mul_extension(switch_bool, second_moves));
+            }
+        }
+
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        (0..self.num_copies)
+            .map(|copy| {
+                WitnessGeneratorRef::new(SwitchGenerator { gate: *self, row, copy }.adapter())
+            })
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        self.wire_switch_bool(self.num_copies - 1) + 1
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn num_constraints(&self) -> usize {
+        4 * self.num_copies * self.chunk_size
+    }
+}
+
+/// Computes the outputs of a switch from its inputs and its switch value.
+#[derive(Clone, Debug, Default, GeneratorSerialize)]
+pub(crate) struct SwitchGenerator<F: RichField + Extendable<D>, const D: usize> {
+    gate: SwitchGate<F, D>,
+    row: usize,
+    copy: usize,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for SwitchGenerator<F, D> {
+    fn id(&self) -> String {
+        "SwitchGenerator".to_string()
+    }
+
+    plonky2::derived_serialization!();
+
+    fn dependencies(&self) -> Vec<Target> {
+        let local_target = |column| Target::wire(self.row, column);
+
+        (0..self.gate.chunk_size)
+            .flat_map(|e| {
+                [
+                    local_target(self.gate.wire_first_input(self.copy, e)),
+                    local_target(self.gate.wire_second_input(self.copy, e)),
+                ]
+            })
+            .chain([local_target(self.gate.wire_switch_bool(self.copy))])
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let local_wire = |column| Wire { row: self.row, column };
+
+        let get_local_wire = |column| witness.get_wire(local_wire(column));
+
+        let switch_bool = get_local_wire(self.gate.wire_switch_bool(self.copy));
+        let swap = switch_bool == F::ONE;
+        debug_assert!(swap || switch_bool == F::ZERO, "Switch value isn't boolean");
+
+        for e in 0..self.gate.chunk_size {
+            let first_input = get_local_wire(self.gate.wire_first_input(self.copy, e));
+            let second_input = get_local_wire(self.gate.wire_second_input(self.copy, e));
+            let (first_output, second_output) =
+                if swap { (second_input, first_input) } else { (first_input, second_input) };
+
+            out_buffer
+                .set_wire(local_wire(self.gate.wire_first_output(self.copy, e)), first_output);
+            out_buffer
+                .set_wire(local_wire(self.gate.wire_second_output(self.copy, e)), second_output);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::{
+        field::{goldilocks_field::GoldilocksField, types::Sample},
+        gates::gate_testing::{test_eval_fns, test_low_degree},
+        hash::hash_types::HashOut,
+        plonk::config::{GenericConfig, PoseidonGoldilocksConfig},
+    };
+
+    use super::*;
+
+    #[test]
+    fn wire_indices() {
+        type SG = SwitchGate<GoldilocksField, 4>;
+        let num_copies = 3;
+        let chunk_size = 3;
+
+        let gate = SG { chunk_size, num_copies, _phantom: PhantomData };
+
+        assert_eq!(gate.wire_first_input(0, 0), 0);
+        assert_eq!(gate.wire_first_input(0, 2), 2);
+        assert_eq!(gate.wire_second_input(0, 0), 3);
+        assert_eq!(gate.wire_second_input(0, 2), 5);
+        assert_eq!(gate.wire_first_output(0, 0), 6);
+        assert_eq!(gate.wire_second_output(0, 2), 11);
+        assert_eq!(gate.wire_switch_bool(0), 12);
+        assert_eq!(gate.wire_first_input(1, 0), 13);
+        assert_eq!(gate.wire_second_output(1, 2), 24);
+        assert_eq!(gate.wire_switch_bool(1), 25);
+        assert_eq!(gate.wire_first_input(2, 0), 26);
+        assert_eq!(gate.wire_switch_bool(2), 38);
+        assert_eq!(<SG as Gate<GoldilocksField, 4>>::num_wires(&gate), 39);
+    }
+
+    #[test]
+    fn low_degree() {
+        test_low_degree::<GoldilocksField, _, 4>(SwitchGate::<_, 4>::new_from_config(
+            &CircuitConfig::standard_recursion_config(),
+            3,
+        ));
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        test_eval_fns::<F, C, _, D>(SwitchGate::<_, D>::new_from_config(
+            &CircuitConfig::standard_recursion_config(),
+            3,
+        ))
+    }
+
+    #[test]
+    fn test_gate_constraint() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type FF = <C as GenericConfig<D>>::FE;
+        const CHUNK_SIZE: usize = 4;
+        const NUM_COPIES: usize = 3;
+
+        /// Returns the local wires for a switch gate given the inputs and the switch booleans.
+        fn get_wires(
+            first_inputs: &[Vec<F>],
+            second_inputs: &[Vec<F>],
+            switch_bools: &[bool],
+        ) -> Vec<FF> {
+            let mut v = Vec::new();
+            for c in 0..NUM_COPIES {
+                let (first_output, second_output) = if switch_bools[c] {
+                    (&second_inputs[c], &first_inputs[c])
+                } else {
+                    (&first_inputs[c], &second_inputs[c])
+                };
+                v.extend(&first_inputs[c]);
+                v.extend(&second_inputs[c]);
+                v.extend(first_output);
+                v.extend(second_output);
+                v.push(F::from_bool(switch_bools[c]));
+            }
+
+            v.iter().map(|&x| x.into()).collect()
+        }
+
+        let first_inputs: Vec<Vec<F>> = (0..NUM_COPIES).map(|_| F::rand_vec(CHUNK_SIZE)).collect();
+        let second_inputs: Vec<Vec<F>> = (0..NUM_COPIES).map(|_| F::rand_vec(CHUNK_SIZE)).collect();
+        let switch_bools = [true, false, true];
+
+        let gate = SwitchGate::<F, D> {
+            chunk_size: CHUNK_SIZE,
+            num_copies: NUM_COPIES,
+            _phantom: PhantomData,
+        };
+
+        let good_wires = get_wires(&first_inputs, &second_inputs, &switch_bools);
+        let vars = EvaluationVars {
+            local_constants: &[],
+            local_wires: &good_wires,
+            public_inputs_hash: &HashOut::rand(),
+        };
+        assert!(
+            gate.eval_unfiltered(vars).iter().all(|x| x.is_zero()),
+            "Gate constraints are not satisfied."
+        );
+
+        let mut bad_wires = good_wires;
+        bad_wires[gate.wire_switch_bool(0)] = FF::ZERO;
+        let vars = EvaluationVars {
+            local_constants: &[],
+            local_wires: &bad_wires,
+            public_inputs_hash: &HashOut::rand(),
+        };
+        assert!(
+            !gate.eval_unfiltered(vars).iter().all(|x| x.is_zero()),
+            "Gate constraints are satisfied by a wrong switch."
+        );
+    }
+}
@@ -0,0 +1,8 @@
+#![no_std]
+
+extern crate alloc;
+
+pub mod gates;
+pub mod permutation;
+pub mod serialization;
+pub mod sorting;
@@ -0,0 +1,431 @@
+//! Permutation checks using [AS-Waksman networks](https://www.cs.tau.ac.il/~stoledo/Bib/Pubs/waksman.pdf).
+//!
+//! A Waksman network on `n` wires is made of a column of switches on each side, and two
+//! sub-networks of sizes `floor(n / 2)` and `ceil(n / 2)` in between. It can route its inputs to
+//! any permutation of them, using `O(n log n)` switches. To check that `b` is a permutation of
+//! `a`, we feed `a` on one side and `b` on the other, and let a generator pick the switch
+//! settings.
+
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::marker::PhantomData;
+
+use itertools::zip_eq;
+use plonky2::{
+    field::extension::Extendable,
+    hash::hash_types::RichField,
+    iop::{
+        generator::{GeneratedValues, SimpleGenerator},
+        target::Target,
+        variable::CircuitVariable,
+        witness::{PartitionWitness, Witness, WitnessWrite},
+    },
+    plonk::circuit_builder::CircuitBuilder,
+    util::serialization::GeneratorSerialize,
+};
+
+use crate::gates::switch::SwitchGate;
+
+pub trait CircuitBuilderPermutation<F: RichField + Extendable<D>, const D: usize> {
+    /// Asserts that `b` is a permutation of `a`.
+    fn assert_permutation<V: CircuitVariable>(&mut self, a: &[V], b: &[V]);
+
+    /// Asserts that `b` is a permutation of `a`, where each item is a chunk of targets. All
+    /// chunks must have the same length.
+    fn assert_permutation_of_chunks(&mut self, a: Vec<Vec<Target>>, b: Vec<Vec<Target>>);
+
+    /// Adds a switch, with an unset switch value, on two chunks of targets. Returns the switch
+    /// value and the two output chunks.
+    fn create_switch(
+        &mut self,
+        a1: Vec<Target>,
+        a2: Vec<Target>,
+    ) -> (Target, Vec<Target>, Vec<Target>);
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderPermutation<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn assert_permutation<V: CircuitVariable>(&mut self, a: &[V], b: &[V]) {
+        self.assert_permutation_of_chunks(
+            a.iter().map(V::targets).collect(),
+            b.iter().map(V::targets).collect(),
+        );
+    }
+
+    fn assert_permutation_of_chunks(&mut self, a: Vec<Vec<Target>>, b: Vec<Vec<Target>>) {
+        assert_eq!(a.len(), b.len(), "Permutation must have same number of inputs and outputs");
+        let chunk_size = a.first().map_or(0, Vec::len);
+        assert!(
+            a.iter().chain(&b).all(|chunk| chunk.len() == chunk_size),
+            "Chunks must all have the same size"
+        );
+
+        match a.len() {
+            0 => (),
+            1 => {
+                for (&x, &y) in zip_eq(&a[0], &b[0]) {
+                    self.connect(x, y);
+                }
+            }
+            _ => assert_permutation_recursive(self, a, b),
+        }
+    }
+
+    fn create_switch(
+        &mut self,
+        a1: Vec<Target>,
+        a2: Vec<Target>,
+    ) -> (Target, Vec<Target>, Vec<Target>) {
+        assert_eq!(a1.len(), a2.len(), "Chunks must have the same size");
+
+        // Chunks which are too large for a single gate are split across several gates, which
+        // share the same switch value.
+        let max_chunk_size = SwitchGate::<F, D>::max_chunk_size(self.config.num_routed_wires);
+        let mut switch = None;
+        let mut c = Vec::with_capacity(a1.len());
+        let mut d = Vec::with_capacity(a2.len());
+        for (a1_part, a2_part) in a1.chunks(max_chunk_size).zip(a2.chunks(max_chunk_size)) {
+            let gate = SwitchGate::<F, D>::new_from_config(&self.config, a1_part.len());
+            let (row, copy) = self.find_slot(gate, &[], &[]);
+
+            for (e, (&x, &y)) in a1_part.iter().zip(a2_part).enumerate() {
+                self.connect(x, Target::wire(row, gate.wire_first_input(copy, e)));
+                self.connect(y, Target::wire(row, gate.wire_second_input(copy, e)));
+                c.push(Target::wire(row, gate.wire_first_output(copy, e)));
+                d.push(Target::wire(row, gate.wire_second_output(copy, e)));
+            }
+
+            let part_switch = Target::wire(row, gate.wire_switch_bool(copy));
+            match switch {
+                Some(switch) => self.connect(switch, part_switch),
+                None => switch = Some(part_switch),
+            }
+        }
+
+        // Empty chunks need no gate; the switch value is then irrelevant.
+        let switch = switch.unwrap_or_else(|| self.add_virtual_target());
+        (switch, c, d)
+    }
+}
+
+/// Builds a network for `n >= 2` items: see Figure 8 of the AS-Waksman paper. The `n / 2` pairs
+/// of items in `a` each go through a switch, whose outputs go to the first and second
+/// sub-networks respectively, and likewise for the pairs in `b`. If `n` is odd, the last items of
+/// `a` and `b` go directly to the second sub-network. If `n` is even, the last pair of `b` needs
+/// no switch, as the sub-networks can route its items to either position.
+fn assert_permutation_recursive<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    a: Vec<Vec<Target>>,
+    b: Vec<Vec<Target>>,
+) {
+    let n = a.len();
+    let even = n % 2 == 0;
+
+    let mut child_1_a = Vec::new();
+    let mut child_2_a = Vec::new();
+    let mut child_1_b = Vec::new();
+    let mut child_2_b = Vec::new();
+
+    let a_num_switches = n / 2;
+    let b_num_switches = if even { a_num_switches - 1 } else { a_num_switches };
+
+    let mut a_switches = Vec::with_capacity(a_num_switches);
+    for i in 0..a_num_switches {
+        let (switch, out_1, out_2) = builder.create_switch(a[2 * i].clone(), a[2 * i + 1].clone());
+        a_switches.push(switch);
+        child_1_a.push(out_1);
+        child_2_a.push(out_2);
+    }
+
+    let mut b_switches = Vec::with_capacity(b_num_switches);
+    for i in 0..b_num_switches {
+        let (switch, out_1, out_2) = builder.create_switch(b[2 * i].clone(), b[2 * i + 1].clone());
+        b_switches.push(switch);
+        child_1_b.push(out_1);
+        child_2_b.push(out_2);
+    }
+
+    if even {
+        child_1_b.push(b[n - 2].clone());
+        child_2_b.push(b[n - 1].clone());
+    } else {
+        child_2_a.push(a[n - 1].clone());
+        child_2_b.push(b[n - 1].clone());
+    }
+
+    builder.assert_permutation_of_chunks(child_1_a, child_1_b);
+    builder.assert_permutation_of_chunks(child_2_a, child_2_b);
+
+    builder.add_simple_generator(PermutationGenerator::<F, D> {
+        a,
+        b,
+        a_switches,
+        b_switches,
+        _phantom: PhantomData,
+    });
+}
+
+/// Sets the switches of the outer layers of a Waksman network so that `b` is routed to `a`.
+#[derive(Clone, Debug, Default, GeneratorSerialize)]
+pub(crate) struct PermutationGenerator<F: RichField + Extendable<D>, const D: usize> {
+    a: Vec<Vec<Target>>,
+    b: Vec<Vec<Target>>,
+    a_switches: Vec<Target>,
+    b_switches: Vec<Target>,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for PermutationGenerator<F, D>
+{
+    fn id(&self) -> String {
+        "PermutationGenerator".to_string()
+    }
+
+    plonky2::derived_serialization!();
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.a.iter().chain(&self.b).flatten().copied().collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let get_chunks = |chunks: &[Vec<Target>]| -> Vec<Vec<F>> {
+            chunks.iter().map(|chunk| witness.get_targets(chunk)).collect()
+        };
+        let a_values = get_chunks(&self.a);
+        let b_values = get_chunks(&self.b);
+
+        let (a_swaps, b_swaps) = route(&a_values, &b_values);
+        for (&switch, swap) in zip_eq(
+            self.a_switches.iter().chain(&self.b_switches),
+            a_swaps.into_iter().chain(b_swaps),
+        ) {
+            out_buffer.set_target(switch, F::from_bool(swap));
+        }
+    }
+}
+
+/// Computes the settings of the outer switches of a network with the layout built by
+/// `assert_permutation_recursive`, such that each item of `a` is sent to the same sub-network as
+/// an equal item of `b`. Returns whether to swap each switch on the `a` side and on the `b` side.
+///
+/// Panics if `b` isn't a permutation of `a`.
+fn route<F: RichField>(a_values: &[Vec<F>], b_values: &[Vec<F>]) -> (Vec<bool>, Vec<bool>) {
+    let n = a_values.len();
+    let even = n % 2 == 0;
+    let num_switches = [n / 2, if even { n / 2 - 1 } else { n / 2 }];
+
+    // Match each item of `a` with an equal item of `b`.
+    let key = |value: &[F]| value.iter().map(F::to_canonical_u64).collect::<Vec<_>>();
+    let mut b_indices: BTreeMap<Vec<u64>, Vec<usize>> = BTreeMap::new();
+    for (j, value) in b_values.iter().enumerate().rev() {
+        b_indices.entry(key(value)).or_default().push(j);
+    }
+    let mut partner = [vec![0; n], vec![0; n]];
+    for (i, value) in a_values.iter().enumerate() {
+        let j = b_indices
+            .get_mut(&key(value))
+            .and_then(Vec::pop)
+            .expect("The second list is not a permutation of the first");
+        partner[0][i] = j;
+        partner[1][j] = i;
+    }
+
+    // The sub-network each item is sent to, on each side. Items which don't go through a switch
+    // have a fixed sub-network.
+    let mut subnet = [vec![None; n], vec![None; n]];
+    let mut pending = Vec::new();
+    if even {
+        assign(&mut subnet, &mut pending, 1, n - 2, false);
+        assign(&mut subnet, &mut pending, 1, n - 1, true);
+    } else {
+        assign(&mut subnet, &mut pending, 0, n - 1, true);
+        assign(&mut subnet, &mut pending, 1, n - 1, true);
+    }
+
+    // An item and its partner on the other side must go to the same sub-network, while the two
+    // items of a switch go to different sub-networks. Each constraint links two items, and every
+    // item is in at most two constraints, so they form paths and cycles, along which we propagate
+    // the assignments. Where nothing is assigned yet, either choice works.
+    let mut next_switch = 0;
+    loop {
+        while let Some((side, i)) = pending.pop() {
+            let value = subnet[side][i].unwrap();
+            assign(&mut subnet, &mut pending, 1 - side, partner[side][i], value);
+            if i / 2 < num_switches[side] {
+                assign(&mut subnet, &mut pending, side, i ^ 1, !value);
+            }
+        }
+
+        // Every item of `b` is the partner of one of `a`, so it's enough to scan `a`.
+        while next_switch < num_switches[0] && subnet[0][2 * next_switch].is_some() {
+            next_switch += 1;
+        }
+        if next_switch == num_switches[0] {
+            break;
+        }
+        assign(&mut subnet, &mut pending, 0, 2 * next_switch, false);
+    }
+
+    // A switch is swapped when its first item goes to the second sub-network.
+    let swaps = |side: usize| -> Vec<bool> {
+        (0..num_switches[side]).map(|k| subnet[side][2 * k].unwrap()).collect()
+    };
+    (swaps(0), swaps(1))
+}
+
+/// Sends item `i` on the given side to the given sub-network, and queues it so that the items
+/// linked to it are assigned too.
+fn assign(
+    subnet: &mut [Vec<Option<bool>>; 2],
+    pending: &mut Vec<(usize, usize)>,
+    side: usize,
+    i: usize,
+    value: bool,
+) {
+    match subnet[side][i] {
+        Some(old) => assert_eq!(old, value, "Routing conflict (should never happen)"),
+        None => {
+            subnet[side][i] = Some(value);
+            pending.push((side, i));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::{
+        field::types::{Field, PrimeField64, Sample},
+        iop::witness::PartialWitness,
+        plonk::{
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+    };
+    use rand::{rngs::OsRng, seq::SliceRandom, Rng};
+
+    use super::*;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn test_permutation_good(size: usize, chunk_size: usize) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut rng = OsRng;
+
+        // Use a duplicate item, which the routing must handle.
+        let mut a: Vec<Vec<F>> = (0..size).map(|_| F::rand_vec(chunk_size)).collect();
+        if size > 2 {
+            a[1] = a[0].clone();
+        }
+        let mut b = a.clone();
+        b.shuffle(&mut rng);
+
+        let a_targets = a.iter().map(|chunk| builder.constants(chunk)).collect();
+        let b_targets = b.iter().map(|chunk| builder.constants(chunk)).collect();
+        builder.assert_permutation_of_chunks(a_targets, b_targets);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(PartialWitness::new())?;
+        Ok(data.verify(proof)?)
+    }
+
+    #[test]
+    fn test_permutations_good() -> Result<()> {
+        for size in 0..=9 {
+            test_permutation_good(size, 2)?;
+        }
+        test_permutation_good(16, 1)?;
+        test_permutation_good(13, 3)?;
+        // Chunks which need several gates per switch.
+        test_permutation_good(5, 25)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_permutation_bad() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let a = builder.add_virtual_variable::<[(Target, Target); 4]>();
+        let b = builder.add_virtual_variable::<[(Target, Target); 4]>();
+        builder.assert_permutation(&a, &b);
+        let data = builder.build::<C>();
+
+        let a_values = [(1, 2), (3, 4), (1, 2), (5, 6)]
+            .map(|(x, y)| (F::from_canonical_u32(x), F::from_canonical_u32(y)));
+        let b_values = [a_values[3], a_values[0], a_values[1], a_values[3]];
+        let mut pw = PartialWitness::new();
+        a.set_witness(&mut pw, &a_values);
+        b.set_witness(&mut pw, &b_values);
+        data.verify(data.prove(pw).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_permutation_variables() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let a = builder.add_virtual_variable::<[(Target, Target); 4]>();
+        let b = builder.add_virtual_variable::<[(Target, Target); 4]>();
+        builder.assert_permutation(&a, &b);
+        let data = builder.build::<C>();
+
+        let a_values = [(1, 2), (3, 4), (1, 2), (5, 6)]
+            .map(|(x, y)| (F::from_canonical_u32(x), F::from_canonical_u32(y)));
+        let b_values = [a_values[3], a_values[0], a_values[1], a_values[2]];
+        let mut pw = PartialWitness::new();
+        a.set_witness(&mut pw, &a_values);
+        b.set_witness(&mut pw, &b_values);
+        let proof = data.prove(pw)?;
+        Ok(data.verify(proof)?)
+    }
+
+    #[test]
+    fn test_route() {
+        let mut rng = OsRng;
+        for n in 2..40 {
+            let a: Vec<Vec<F>> =
+                (0..n).map(|_| vec![F::from_canonical_u32(rng.gen_range(0..4))]).collect();
+            let mut b = a.clone();
+            b.shuffle(&mut rng);
+            let (a_swaps, b_swaps) = route(&a, &b);
+
+            // Apply the switches and check that both sides send the same items to each
+            // sub-network.
+            let split = |values: &[Vec<F>], swaps: &[bool]| {
+                let (mut child_1, mut child_2) = (Vec::new(), Vec::new());
+                for (k, &swap) in swaps.iter().enumerate() {
+                    let (x, y) = (values[2 * k].clone(), values[2 * k + 1].clone());
+                    let (x, y) = if swap { (y, x) } else { (x, y) };
+                    child_1.push(x);
+                    child_2.push(y);
+                }
+                (child_1, child_2)
+            };
+            let (mut a_1, mut a_2) = split(&a, &a_swaps);
+            let (mut b_1, mut b_2) = split(&b, &b_swaps);
+            if n % 2 == 0 {
+                b_1.push(b[n - 2].clone());
+                b_2.push(b[n - 1].clone());
+            } else {
+                a_2.push(a[n - 1].clone());
+                b_2.push(b[n - 1].clone());
+            }
+            for list in [&mut a_1, &mut a_2, &mut b_1, &mut b_2] {
+                list.sort_by_key(|x| x[0].to_canonical_u64());
+            }
+            assert_eq!(a_1, b_1);
+            assert_eq!(a_2, b_2);
+        }
+    }
+}
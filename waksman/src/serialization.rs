@@ -0,0 +1,108 @@
+use plonky2::{
+    field::extension::Extendable,
+    get_gate_tag_impl, get_generator_tag_impl,
+    hash::hash_types::RichField,
+    impl_gate_serializer, impl_generator_serializer, read_gate_impl, read_generator_impl,
+    util::serialization::{GateSerializer, WitnessGeneratorSerializer},
+};
+
+use crate::{
+    gates::switch::{SwitchGate, SwitchGenerator},
+    permutation::PermutationGenerator,
+    sorting::SortGenerator,
+};
+
+/// A gate serializer for the gates defined in this crate. Circuits also using `plonky2`'s gates
+/// can combine it with `DefaultGateSerializer` in a `GateSerializerRegistry`.
+#[derive(Debug, Default)]
+pub struct WaksmanGateSerializer;
+
+impl<F: RichField + Extendable<D>, const D: usize> GateSerializer<F, D> for WaksmanGateSerializer {
+    impl_gate_serializer! {
+        WaksmanGateSerializer,
+        SwitchGate<F, D>
+    }
+}
+
+/// A generator serializer for the generators defined in this crate. Sorting also uses the
+/// comparison gadget of `plonky2_u32`, so such circuits need its `U32GeneratorSerializer` too.
+#[derive(Debug, Default)]
+pub struct WaksmanGeneratorSerializer;
+
+impl<F: RichField + Extendable<D>, const D: usize> WitnessGeneratorSerializer<F, D>
+    for WaksmanGeneratorSerializer
+{
+    impl_generator_serializer! {
+        WaksmanGeneratorSerializer,
+        SwitchGenerator<F, D>,
+        PermutationGenerator<F, D>,
+        SortGenerator<F, D>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use anyhow::Result;
+    use plonky2::{
+        field::types::Field,
+        iop::{
+            target::Target,
+            witness::{PartialWitness, WitnessWrite},
+        },
+        plonk::{
+            circuit_builder::CircuitBuilder,
+            circuit_data::{CircuitConfig, CircuitData},
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+        util::serialization::{
+            DefaultGateSerializer, DefaultGeneratorSerializer, GateSerializerRegistry,
+            GeneratorSerializerRegistry,
+        },
+    };
+    use plonky2_u32::serialization::{U32GateSerializer, U32GeneratorSerializer};
+
+    use super::*;
+    use crate::sorting::CircuitBuilderSort;
+
+    #[test]
+    fn test_waksman_serializers() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let items: Vec<Target> = builder.add_virtual_targets(5);
+        let sorted = builder.sort_by_key(&items, |&item| alloc::vec![item], 32);
+        builder.register_public_inputs(&sorted);
+        let data = builder.build::<C>();
+
+        let gate_serializer = GateSerializerRegistry::new()
+            .with(DefaultGateSerializer)
+            .with(U32GateSerializer)
+            .with(WaksmanGateSerializer);
+        let generator_serializer = GeneratorSerializerRegistry::new()
+            .with(DefaultGeneratorSerializer::<C, D>::default())
+            .with(U32GeneratorSerializer)
+            .with(WaksmanGeneratorSerializer);
+        assert!(data
+            .unsupported_by_serializers(&gate_serializer, &generator_serializer)
+            .is_empty());
+
+        let bytes =
+            data.to_bytes(&gate_serializer, &generator_serializer).map_err(anyhow::Error::msg)?;
+        let data_from_bytes =
+            CircuitData::<F, C, D>::from_bytes(&bytes, &gate_serializer, &generator_serializer)
+                .map_err(anyhow::Error::msg)?;
+        assert_eq!(data_from_bytes, data);
+
+        let mut pw = PartialWitness::new();
+        for (&item, value) in items.iter().zip([5, 3, 9, 3, 1]) {
+            pw.set_target(item, F::from_canonical_u32(value));
+        }
+        let proof = data_from_bytes.prove(pw)?;
+        assert_eq!(proof.public_inputs, [1, 3, 3, 5, 9].map(F::from_canonical_u32));
+        Ok(data.verify(proof)?)
+    }
+}
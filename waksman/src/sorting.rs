@@ -0,0 +1,196 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::marker::PhantomData;
+
+use itertools::zip_eq;
+use plonky2::{
+    field::extension::Extendable,
+    hash::hash_types::RichField,
+    iop::{
+        generator::{GeneratedValues, SimpleGenerator},
+        target::Target,
+        variable::CircuitVariable,
+        witness::{PartitionWitness, Witness, WitnessWrite},
+    },
+    plonk::circuit_builder::CircuitBuilder,
+    util::serialization::GeneratorSerialize,
+};
+use plonky2_u32::gadgets::multiple_comparison::list_le_circuit;
+
+use crate::permutation::CircuitBuilderPermutation;
+
+pub trait CircuitBuilderSort<F: RichField + Extendable<D>, const D: usize> {
+    /// Returns `items` sorted by key in non-decreasing order.
+    ///
+    /// `key` returns the targets of an item making up its key. Keys are compared
+    /// lexicographically, with the first target being the most significant, and each target is
+    /// range-checked to `num_bits` bits, where `num_bits` is less than 64. Items with equal keys
+    /// may come in any order.
+    fn sort_by_key<V, K>(&mut self, items: &[V], key: K, num_bits: usize) -> Vec<V>
+    where
+        V: CircuitVariable,
+        K: Fn(&V) -> Vec<Target>;
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderSort<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn sort_by_key<V, K>(&mut self, items: &[V], key: K, num_bits: usize) -> Vec<V>
+    where
+        V: CircuitVariable,
+        K: Fn(&V) -> Vec<Target>,
+    {
+        let sorted: Vec<V> = items
+            .iter()
+            .map(|_| V::from_targets(&self.add_virtual_targets(V::num_targets())))
+            .collect();
+
+        self.add_simple_generator(SortGenerator::<F, D> {
+            items: items.iter().map(V::targets).collect(),
+            keys: items.iter().map(&key).collect(),
+            sorted: sorted.iter().map(V::targets).collect(),
+            _phantom: PhantomData,
+        });
+
+        // `sorted` holds the same items, so its targets satisfy the same constraints as those of
+        // `items`, even though it was built from unconstrained targets.
+        self.assert_permutation(items, &sorted);
+
+        for pair in sorted.windows(2) {
+            // `list_le_circuit` takes the least significant limb first.
+            let mut lhs = key(&pair[0]);
+            let mut rhs = key(&pair[1]);
+            assert_eq!(lhs.len(), rhs.len(), "Keys must all have the same length");
+            lhs.reverse();
+            rhs.reverse();
+            let le = list_le_circuit(self, lhs, rhs, num_bits);
+            self.assert_one(le.target);
+        }
+
+        sorted
+    }
+}
+
+/// Fills in the sorted items, using a stable sort.
+#[derive(Clone, Debug, Default, GeneratorSerialize)]
+pub(crate) struct SortGenerator<F: RichField + Extendable<D>, const D: usize> {
+    items: Vec<Vec<Target>>,
+    keys: Vec<Vec<Target>>,
+    sorted: Vec<Vec<Target>>,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for SortGenerator<F, D> {
+    fn id(&self) -> String {
+        "SortGenerator".to_string()
+    }
+
+    plonky2::derived_serialization!();
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.items.iter().chain(&self.keys).flatten().copied().collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let key_values: Vec<Vec<u64>> = self
+            .keys
+            .iter()
+            .map(|key| witness.get_targets(key).iter().map(F::to_canonical_u64).collect())
+            .collect();
+        let mut order: Vec<usize> = (0..self.items.len()).collect();
+        order.sort_by_key(|&i| &key_values[i]);
+
+        for (sorted, &i) in zip_eq(&self.sorted, &order) {
+            out_buffer.set_target_arr(sorted, &witness.get_targets(&self.items[i]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use anyhow::Result;
+    use plonky2::{
+        field::types::Field,
+        iop::witness::PartialWitness,
+        plonk::{
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+    };
+    use rand::{rngs::OsRng, Rng};
+
+    use super::*;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_sort_by_key() -> Result<()> {
+        const SIZE: usize = 12;
+        const NUM_BITS: usize = 16;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // Items are `(major key, minor key, payload)`.
+        let items: Vec<(Target, Target, Target)> =
+            (0..SIZE).map(|_| builder.add_virtual_variable()).collect();
+        let sorted = builder.sort_by_key(&items, |item| vec![item.0, item.1], NUM_BITS);
+        for item in &sorted {
+            item.register_public_input(&mut builder);
+        }
+        let data = builder.build::<C>();
+
+        // Major keys have many ties, while (major, minor) pairs are distinct.
+        let mut rng = OsRng;
+        let mut values: Vec<(u64, u64, u64)> = (0..SIZE as u64)
+            .map(|i| (rng.gen_range(0..3), i * 1000 + rng.gen_range(0..1000), rng.gen()))
+            .collect();
+        let mut pw = PartialWitness::new();
+        for (item, &(major, minor, payload)) in items.iter().zip(&values) {
+            let value = (
+                F::from_canonical_u64(major),
+                F::from_canonical_u64(minor),
+                F::from_noncanonical_u64(payload),
+            );
+            item.set_witness(&mut pw, &value);
+        }
+        let proof = data.prove(pw)?;
+
+        values.sort();
+        let expected: Vec<F> = values
+            .iter()
+            .flat_map(|&(major, minor, payload)| {
+                [
+                    F::from_canonical_u64(major),
+                    F::from_canonical_u64(minor),
+                    F::from_noncanonical_u64(payload),
+                ]
+            })
+            .collect();
+        assert_eq!(proof.public_inputs, expected);
+        Ok(data.verify(proof)?)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sort_key_too_large() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let items: Vec<Target> = builder.add_virtual_targets(3);
+        builder.sort_by_key(&items, |&item| vec![item], 8);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (&item, value) in items.iter().zip([3, 256, 1]) {
+            pw.set_target(item, F::from_canonical_u64(value));
+        }
+        data.verify(data.prove(pw).unwrap()).unwrap();
+    }
+}